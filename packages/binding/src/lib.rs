@@ -1,5 +1,6 @@
 #![deny(clippy::all)]
 
+use angular_compiler_cli::linker::napi::LinkResult;
 use angular_compiler_cli::ngtsc::core::NgCompilerOptions;
 use angular_compiler_cli::ngtsc::file_system::src::node_js_file_system::NodeJSFileSystem;
 use angular_compiler_cli::ngtsc::file_system::src::types::{
@@ -27,48 +28,38 @@ fn compute_hash(content: &str) -> String {
     format!("{:016x}", xxh3_64(content.as_bytes()))
 }
 
-/// Extract template content if templateUrl is present in the TS file.
-/// Returns the combined content (ts + template) for hashing.
+/// Combine the TS source with the contents of the component resources it references
+/// (`templateUrl`, `styleUrl` and every entry of `styleUrls`) for hashing. Inline
+/// `template` and `styles` are part of the TS source already.
 fn get_combined_content_for_hash(filename: &str, ts_content: &str) -> String {
     use regex::Regex;
 
-    // Look for templateUrl: './something.html' or templateUrl: "./something.html"
-    let re = Regex::new(r#"templateUrl\s*:\s*['"]([^'"]+)['"]"#).ok();
-
-    if let Some(regex) = re {
-        if let Some(captures) = regex.captures(ts_content) {
-            if let Some(template_path) = captures.get(1) {
-                // Resolve template path relative to TS file
-                let ts_dir = Path::new(filename).parent().unwrap_or(Path::new("."));
-                let template_file = ts_dir.join(template_path.as_str());
-
-                // Read template content
-                if let Ok(template_content) = fs::read_to_string(&template_file) {
-                    // Combine TS + template content for hash
-                    return format!("{}\n---TEMPLATE---\n{}", ts_content, template_content);
-                }
+    let ts_dir = Path::new(filename).parent().unwrap_or(Path::new("."));
+    let read_resource = |url: &str| fs::read_to_string(ts_dir.join(url)).ok();
+    let mut combined = ts_content.to_string();
+
+    let template_re = Regex::new(r#"templateUrl\s*:\s*['"`]([^'"`]+)['"`]"#).unwrap();
+    if let Some(template_content) = template_re
+        .captures(ts_content)
+        .and_then(|captures| read_resource(&captures[1]))
+    {
+        combined.push_str("\n---TEMPLATE---\n");
+        combined.push_str(&template_content);
+    }
+
+    // Both `styleUrl: './a.css'` and `styleUrls: ['./a.css', './b.css']`.
+    let style_re = Regex::new(r#"styleUrls?\s*:\s*(\[[^\]]*\]|['"`][^'"`]+['"`])"#).unwrap();
+    let url_re = Regex::new(r#"['"`]([^'"`]+)['"`]"#).unwrap();
+    for styles in style_re.captures_iter(ts_content) {
+        for url in url_re.captures_iter(&styles[1]) {
+            if let Some(style_content) = read_resource(&url[1]) {
+                combined.push_str("\n---STYLE---\n");
+                combined.push_str(&style_content);
             }
         }
     }
 
-    // Also check for styleUrl/styleUrls
-    let style_re = Regex::new(r#"styleUrl[s]?\s*:\s*['"]([^'"]+)['"]"#).ok();
-    if let Some(regex) = style_re {
-        let mut combined = ts_content.to_string();
-        for captures in regex.captures_iter(ts_content) {
-            if let Some(style_path) = captures.get(1) {
-                let ts_dir = Path::new(filename).parent().unwrap_or(Path::new("."));
-                let style_file = ts_dir.join(style_path.as_str());
-                if let Ok(style_content) = fs::read_to_string(&style_file) {
-                    combined.push_str("\n---STYLE---\n");
-                    combined.push_str(&style_content);
-                }
-            }
-        }
-        return combined;
-    }
-
-    ts_content.to_string()
+    combined
 }
 
 /// Get cache directory, creating it if necessary
@@ -100,6 +91,8 @@ fn get_cache_dir(subdir: &str) -> PathBuf {
 #[derive(Serialize, Deserialize)]
 struct CachedCompileResult {
     code: String,
+    #[serde(default)]
+    map: Option<String>,
     diagnostics: Vec<CachedDiagnostic>,
 }
 
@@ -255,6 +248,8 @@ pub struct Diagnostic {
 #[napi(object)]
 pub struct CompileResult {
     pub code: String,
+    /// Source map (JSON) for `code`, mapping back to the original `.ts` file.
+    pub map: Option<String>,
     pub diagnostics: Vec<Diagnostic>,
 }
 
@@ -317,13 +312,13 @@ impl Compiler {
     }
 
     /// Read cached linker result from disk
-    fn read_linker_cache(&self, _hash: &str) -> Option<String> {
+    fn read_linker_cache(&self, _hash: &str) -> Option<LinkResult> {
         // CACHE DISABLED TEMPORARILY
         None
     }
 
     /// Write linker result to disk cache
-    fn write_linker_cache(&self, _hash: &str, _result: &str) {
+    fn write_linker_cache(&self, _hash: &str, _result: &LinkResult) {
         // CACHE DISABLED TEMPORARILY
     }
 
@@ -348,6 +343,8 @@ impl Compiler {
         let mut options = NgCompilerOptions::default();
         options.project = abs_filename_str.clone();
         options.out_dir = Some(fs.dirname(&abs_filename_str));
+        options.source_map = true;
+        options.inline_sources = true;
 
         // 5. Create Program
         let root_names = vec![abs_filename_str.clone()];
@@ -358,6 +355,7 @@ impl Compiler {
         if let Err(e) = program.load_ng_structure(Path::new("/")) {
             return CompileResult {
                 code: format!("/* Error loading: {} */", e),
+                map: None,
                 diagnostics: vec![],
             };
         }
@@ -378,6 +376,7 @@ impl Compiler {
             Err(e) => {
                 return CompileResult {
                     code: format!("/* Error emitting: {} */", e),
+                    map: None,
                     diagnostics: vec![],
                 };
             }
@@ -387,14 +386,28 @@ impl Compiler {
         let output_path_str = abs_filename_str.replace(".ts", ".js");
         let output_path = AbsoluteFsPath::from(Path::new(&output_path_str));
 
-        let code = match fs.read_file(&output_path) {
+        let mut code = match fs.read_file(&output_path) {
             Ok(js_content) => js_content,
             Err(_) => {
                 format!("/* Output not found in memory for {} */", output_path_str)
             }
         };
 
-        let result = CompileResult { code, diagnostics };
+        // The map is handed back directly, so drop the reference to the file that
+        // only exists in memory.
+        let map_path = AbsoluteFsPath::from(Path::new(&format!("{}.map", output_path_str)));
+        let map = fs.read_file(&map_path).ok();
+        if map.is_some() {
+            if let Some(pos) = code.rfind("\n//# sourceMappingURL=") {
+                code.truncate(pos + 1);
+            }
+        }
+
+        let result = CompileResult {
+            code,
+            map,
+            diagnostics,
+        };
 
         // 9. Write to cache
         self.write_compiler_cache(&hash, &result);
@@ -403,7 +416,7 @@ impl Compiler {
     }
 
     #[napi]
-    pub fn link_file(&self, filename: String, source_code: String) -> String {
+    pub fn link_file(&self, filename: String, source_code: String) -> LinkResult {
         // 1. Compute hash of source code
         let hash = compute_hash(&source_code);
 
@@ -416,12 +429,15 @@ impl Compiler {
         use angular_compiler_cli::linker::napi::link_file;
 
         let result = match link_file(source_code, filename) {
-            Ok(code) => code,
-            Err(e) => format!("/* Linker Error: {} */", e),
+            Ok(linked) => linked,
+            Err(e) => LinkResult {
+                code: format!("/* Linker Error: {} */", e),
+                map: None,
+            },
         };
 
         // 4. Write to cache (only if successful)
-        if !result.starts_with("/* Linker Error") {
+        if !result.code.starts_with("/* Linker Error") {
             self.write_linker_cache(&hash, &result);
         }
 
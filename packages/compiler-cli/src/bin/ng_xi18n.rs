@@ -3,6 +3,7 @@
  *
  * Extract i18n messages from Angular templates
  */
+use angular_compiler_cli::extract_i18n::{extract_i18n, parse_i18n_format, ExtractI18nOptions};
 use clap::{Arg, Command};
 use std::process;
 
@@ -22,23 +23,58 @@ fn main() {
                 .short('o')
                 .long("output-path")
                 .value_name("PATH")
-                .help("Output path for extracted messages"),
+                .help("Directory the extracted messages are written to"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Format of the extracted messages: xlf, xlf2, xmb, json or arb"),
+        )
+        .arg(
+            Arg::new("locale")
+                .long("locale")
+                .value_name("LOCALE")
+                .help("Source locale of the extracted messages"),
+        )
+        .arg(
+            Arg::new("out-file")
+                .long("out-file")
+                .value_name("FILE")
+                .help("Name of the file the messages are written to"),
         )
         .get_matches();
 
-    // TODO: Implement actual i18n extraction logic
-    println!("Angular i18n Extractor (Rust) - ng-xi18n");
-    println!("Version: {}", env!("CARGO_PKG_VERSION"));
-
-    if let Some(project) = matches.get_one::<String>("project") {
-        println!("Project: {}", project);
+    let mut options = ExtractI18nOptions {
+        project: matches.get_one::<String>("project").cloned(),
+        out_dir: matches.get_one::<String>("output-path").cloned(),
+        out_file: matches.get_one::<String>("out-file").cloned(),
+        locale: matches.get_one::<String>("locale").cloned(),
+        ..Default::default()
+    };
+    if let Some(format) = matches.get_one::<String>("format") {
+        match parse_i18n_format(format) {
+            Ok(format) => options.format = Some(format),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
     }
-
-    if let Some(output) = matches.get_one::<String>("output-path") {
-        println!("Output path: {}", output);
+    if options.project.is_none() && std::path::Path::new("tsconfig.json").exists() {
+        options.project = Some("tsconfig.json".to_string());
     }
 
-    // Placeholder - will implement actual extraction
-    eprintln!("i18n extraction not yet implemented");
-    process::exit(1);
+    let result = extract_i18n(options);
+    if !result.success {
+        for diag in result.diagnostics {
+            eprintln!("Error: {}", diag);
+        }
+        process::exit(1);
+    }
+    println!(
+        "Extracted {} messages to {}",
+        result.message_count,
+        result.out_file.unwrap_or_default()
+    );
 }
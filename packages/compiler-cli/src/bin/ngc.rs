@@ -3,7 +3,7 @@
  *
  * Main entry point for Angular compilation
  */
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 use std::process;
 
 fn main() {
@@ -17,10 +17,15 @@ fn main() {
                 .value_name("PATH")
                 .help("Path to tsconfig.json"),
         )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .help("Rebuild when source, template or style files change"),
+        )
         .get_matches();
 
-    // TODO: Handle watch mode via perform_watch
-
     let temp_project;
     let project = if let Some(p) = matches.get_one::<String>("project") {
         Some(p.as_str())
@@ -34,6 +39,28 @@ fn main() {
         }
     };
 
+    if matches.get_flag("watch") {
+        use angular_compiler_cli::main_entry::watch_mode;
+        use angular_compiler_cli::perform_compile::read_configuration;
+        use angular_compiler_cli::transformers::api::CompilerOptions;
+
+        let Some(project) = project else {
+            eprintln!("Error: --watch requires a tsconfig.json (use -p <path>)");
+            process::exit(1);
+        };
+        let config = read_configuration(project, None);
+        if !config.errors.is_empty() {
+            for diag in config.errors {
+                eprintln!("Error: {}", diag.message);
+            }
+            process::exit(1);
+        }
+        watch_mode(project, &CompilerOptions::default(), &|message: &str| {
+            eprintln!("{}", message)
+        });
+        return;
+    }
+
     use angular_compiler_cli::perform_compile::perform_compilation_simple;
 
     let result = perform_compilation_simple(project, None, None);
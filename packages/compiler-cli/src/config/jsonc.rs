@@ -0,0 +1,171 @@
+//! JSON with comments
+//!
+//! Parser for the JSONC dialect accepted by TypeScript for tsconfig files: `//` line
+//! comments, `/* */` block comments and trailing commas in objects and arrays.
+
+use serde_json::Value;
+
+/// Parse JSONC text into a JSON value.
+///
+/// Comments are blanked out rather than removed so that line and column numbers in
+/// error messages still point into the original text.
+pub fn parse_jsonc(input: &str) -> Result<Value, String> {
+    let normalized = normalize_jsonc(input)?;
+    serde_json::from_str(&normalized).map_err(|e| e.to_string())
+}
+
+/// Rewrite JSONC into plain JSON of the same length, line for line.
+fn normalize_jsonc(input: &str) -> Result<String, String> {
+    let bytes = input.as_bytes();
+    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+    // Position in `out` of the last comma seen outside of a string, cleared by any
+    // significant character that follows it.
+    let mut pending_comma: Option<usize> = None;
+    let mut i = 0;
+
+    // Skip a leading byte order mark.
+    if input.starts_with('\u{feff}') {
+        i = 3;
+    }
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'"' => {
+                pending_comma = None;
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if i >= bytes.len() {
+                    let (line, column) = line_and_column(input, start);
+                    return Err(format!(
+                        "Unterminated string literal at line {} column {}",
+                        line, column
+                    ));
+                }
+                i += 1;
+                out.extend_from_slice(&bytes[start..i]);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    out.push(b' ');
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let start = i;
+                out.extend_from_slice(b"  ");
+                i += 2;
+                loop {
+                    if i >= bytes.len() {
+                        let (line, column) = line_and_column(input, start);
+                        return Err(format!(
+                            "Unterminated block comment at line {} column {}",
+                            line, column
+                        ));
+                    }
+                    if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+                        out.extend_from_slice(b"  ");
+                        i += 2;
+                        break;
+                    }
+                    out.push(if bytes[i] == b'\n' { b'\n' } else { b' ' });
+                    i += 1;
+                }
+            }
+            b',' => {
+                pending_comma = Some(out.len());
+                out.push(b',');
+                i += 1;
+            }
+            b'}' | b']' => {
+                if let Some(pos) = pending_comma.take() {
+                    out[pos] = b' ';
+                }
+                out.push(b);
+                i += 1;
+            }
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                out.push(b);
+                i += 1;
+            }
+            _ => {
+                pending_comma = None;
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    // Only ASCII bytes were replaced, so the output is still valid UTF-8.
+    String::from_utf8(out).map_err(|e| e.to_string())
+}
+
+fn line_and_column(input: &str, offset: usize) -> (usize, usize) {
+    let prefix = &input[..offset];
+    let line = prefix.matches('\n').count() + 1;
+    let column = offset - prefix.rfind('\n').map_or(0, |p| p + 1) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_line_and_block_comments() {
+        let value = parse_jsonc(
+            r#"{
+                // line comment
+                "a": 1, /* inline */ "b": "x",
+                /*
+                 * block comment
+                 */
+                "c": true
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(value["a"], 1);
+        assert_eq!(value["b"], "x");
+        assert_eq!(value["c"], true);
+    }
+
+    #[test]
+    fn keeps_comment_markers_inside_strings() {
+        let value =
+            parse_jsonc(r#"{ "url": "http://example.com/*", "glob": "src/**/*.ts" }"#).unwrap();
+        assert_eq!(value["url"], "http://example.com/*");
+        assert_eq!(value["glob"], "src/**/*.ts");
+    }
+
+    #[test]
+    fn allows_trailing_commas() {
+        let value = parse_jsonc(
+            r#"{
+                "include": ["src/**/*.ts", ],
+                "compilerOptions": { "strict": true, // trailing
+                },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(value["include"].as_array().unwrap().len(), 1);
+        assert_eq!(value["compilerOptions"]["strict"], true);
+    }
+
+    #[test]
+    fn reports_original_line_numbers() {
+        let err = parse_jsonc("{\n  /* a\n  b */\n  \"a\": }").unwrap_err();
+        assert!(err.contains("line 4"), "{}", err);
+    }
+
+    #[test]
+    fn reports_unterminated_block_comment() {
+        let err = parse_jsonc("{ /* never closed").unwrap_err();
+        assert!(err.contains("Unterminated block comment"), "{}", err);
+    }
+}
@@ -1 +1,3 @@
 pub mod angular;
+pub mod jsonc;
+pub mod tsconfig;
@@ -0,0 +1,1086 @@
+//! tsconfig.json options
+//!
+//! Reads a tsconfig and the chain of configs it `extends`, and maps the
+//! `compilerOptions` and `angularCompilerOptions` blocks onto [`CompilerOptions`].
+//! Unknown keys and values of the wrong type are reported as diagnostics instead of
+//! being silently ignored.
+
+use super::jsonc::parse_jsonc;
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use crate::ngtsc::typecheck::extended::{DiagnosticCategoryLabel, ExtendedDiagnosticsOptions};
+use crate::transformers::api::{
+    CompilationMode, CompilerOptions, Diagnostic, DiagnosticCategory, NewLineKind,
+};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// TypeScript diagnostic code for an unknown compiler option.
+pub const UNKNOWN_COMPILER_OPTION: i32 = 5023;
+/// TypeScript diagnostic code for a compiler option with a value of the wrong type.
+pub const COMPILER_OPTION_WRONG_TYPE: i32 = 5024;
+/// TypeScript diagnostic code for a config file that cannot be found.
+pub const CONFIG_FILE_NOT_FOUND: i32 = 6053;
+/// TypeScript diagnostic code for a cycle in a chain of `extends`.
+pub const CONFIG_EXTENDS_CIRCULARITY: i32 = 18000;
+
+/// TypeScript compiler options that are valid in a tsconfig but have no effect on this
+/// compiler. They are accepted without a diagnostic.
+const IGNORED_TS_OPTIONS: &[&str] = &[
+    "allowArbitraryExtensions",
+    "allowImportingTsExtensions",
+    "allowJs",
+    "allowSyntheticDefaultImports",
+    "allowUmdGlobalAccess",
+    "allowUnreachableCode",
+    "allowUnusedLabels",
+    "alwaysStrict",
+    "assumeChangesOnlyAffectDirectDependencies",
+    "charset",
+    "checkJs",
+    "composite",
+    "customConditions",
+    "declarationDir",
+    "declarationMap",
+    "diagnostics",
+    "disableReferencedProjectLoad",
+    "disableSizeLimit",
+    "disableSolutionSearching",
+    "disableSourceOfProjectReferenceRedirect",
+    "downlevelIteration",
+    "emitBOM",
+    "emitDeclarationOnly",
+    "emitDecoratorMetadata",
+    "esModuleInterop",
+    "exactOptionalPropertyTypes",
+    "experimentalDecorators",
+    "explainFiles",
+    "extendedDiagnostics",
+    "forceConsistentCasingInFileNames",
+    "importHelpers",
+    "importsNotUsedAsValues",
+    "incremental",
+    "isolatedDeclarations",
+    "isolatedModules",
+    "jsx",
+    "jsxFactory",
+    "jsxFragmentFactory",
+    "jsxImportSource",
+    "keyofStringsOnly",
+    "lib",
+    "listEmittedFiles",
+    "listFiles",
+    "mapRoot",
+    "maxNodeModuleJsDepth",
+    "module",
+    "moduleDetection",
+    "moduleResolution",
+    "moduleSuffixes",
+    "noEmit",
+    "noEmitHelpers",
+    "noEmitOnError",
+    "noErrorTruncation",
+    "noFallthroughCasesInSwitch",
+    "noImplicitAny",
+    "noImplicitOverride",
+    "noImplicitReturns",
+    "noImplicitThis",
+    "noImplicitUseStrict",
+    "noLib",
+    "noPropertyAccessFromIndexSignature",
+    "noResolve",
+    "noStrictGenericChecks",
+    "noUncheckedIndexedAccess",
+    "noUncheckedSideEffectImports",
+    "noUnusedLocals",
+    "noUnusedParameters",
+    "outFile",
+    "paths",
+    "plugins",
+    "preserveConstEnums",
+    "preserveSymlinks",
+    "preserveValueImports",
+    "pretty",
+    "reactNamespace",
+    "removeComments",
+    "resolveJsonModule",
+    "resolvePackageJsonExports",
+    "resolvePackageJsonImports",
+    "rootDirs",
+    "skipDefaultLibCheck",
+    "skipLibCheck",
+    "sourceRoot",
+    "strictBindCallApply",
+    "strictBuiltinIteratorReturn",
+    "strictFunctionTypes",
+    "strictPropertyInitialization",
+    "stripInternal",
+    "suppressExcessPropertyErrors",
+    "suppressImplicitAnyIndexErrors",
+    "target",
+    "traceResolution",
+    "tsBuildInfoFile",
+    "typeRoots",
+    "types",
+    "useDefineForClassFields",
+    "useUnknownInCatchVariables",
+    "verbatimModuleSyntax",
+];
+
+/// Angular compiler options that are valid in `angularCompilerOptions` but have no
+/// effect on this compiler. They are accepted without a diagnostic.
+const IGNORED_ANGULAR_OPTIONS: &[&str] = &[
+    "allowEmptyCodegenFiles",
+    "annotateForClosureCompiler",
+    "compileNonExportedClasses",
+    "disableTypeScriptVersionCheck",
+    "enableBlockSyntax",
+    "enableLetSyntax",
+    "enableResourceInlining",
+    "flatModuleId",
+    "flatModulePrivateSymbolPrefix",
+    "forbidOrphanComponents",
+    "generateCodeForLibraries",
+    "generateDeepReexports",
+    "generateExtraImportsInLocalMode",
+    "i18nPreserveWhitespaceForLegacyExtraction",
+    "onlyPublishPublicTypingsForNgModules",
+    "preserveWhitespaces",
+    "skipMetadataEmit",
+    "strictAttributeTypes",
+    "strictContextGenerics",
+    "strictDomEventTypes",
+    "strictDomLocalRefTypes",
+    "strictInputTypes",
+    "strictLiteralTypes",
+    "strictMetadataEmit",
+    "strictNullInputTypes",
+    "strictOutputEventTypes",
+    "strictSafeNavigationTypes",
+    "strictStandalone",
+    "supportJitMode",
+    "supportTestBed",
+    "trace",
+    "tracePerformance",
+    "typeCheckHostBindings",
+];
+
+/// Apply the `compilerOptions` block of a tsconfig to `options`.
+///
+/// Path-valued options are resolved relative to `base_dir`, the directory of the
+/// tsconfig that declared them.
+pub fn apply_compiler_options(
+    options: &mut CompilerOptions,
+    raw: &Value,
+    base_dir: &Path,
+    config_file: &str,
+    errors: &mut Vec<Diagnostic>,
+) {
+    let Some(entries) = expect_object("compilerOptions", raw, config_file, errors) else {
+        return;
+    };
+
+    let mut reader = OptionReader {
+        config_file,
+        errors,
+    };
+    for (key, value) in entries {
+        match key.as_str() {
+            "outDir" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.out_dir = Some(resolve_path(base_dir, &v));
+                }
+            }
+            "rootDir" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.root_dir = Some(resolve_path(base_dir, &v));
+                }
+            }
+            "baseUrl" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.base_url = Some(resolve_path(base_dir, &v));
+                }
+            }
+            "sourceMap" => reader.bool_into(key, value, &mut options.source_map),
+            "inlineSourceMap" => reader.bool_into(key, value, &mut options.inline_source_map),
+            "inlineSources" => reader.bool_into(key, value, &mut options.inline_sources),
+            "declaration" => reader.bool_into(key, value, &mut options.declaration),
+            "strict" => reader.bool_into(key, value, &mut options.strict),
+            "strictNullChecks" => reader.bool_into(key, value, &mut options.strict_null_checks),
+            "locale" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.locale = Some(v);
+                }
+            }
+            "newLine" => {
+                if let Some(v) = reader.string(key, value) {
+                    match v.to_ascii_lowercase().as_str() {
+                        "lf" => options.new_line = Some(NewLineKind::LineFeed),
+                        "crlf" => options.new_line = Some(NewLineKind::CarriageReturnLineFeed),
+                        _ => reader.wrong_type(key, "'crlf' | 'lf'"),
+                    }
+                }
+            }
+            other if IGNORED_TS_OPTIONS.contains(&other) => {}
+            other => reader.unknown(other),
+        }
+    }
+}
+
+/// Apply the `angularCompilerOptions` block of a tsconfig to `options`.
+pub fn apply_angular_compiler_options(
+    options: &mut CompilerOptions,
+    raw: &Value,
+    base_dir: &Path,
+    config_file: &str,
+    errors: &mut Vec<Diagnostic>,
+) {
+    let Some(entries) = expect_object("angularCompilerOptions", raw, config_file, errors) else {
+        return;
+    };
+
+    let mut reader = OptionReader {
+        config_file,
+        errors,
+    };
+    for (key, value) in entries {
+        match key.as_str() {
+            "strictTemplates" => reader.bool_into(key, value, &mut options.strict_templates),
+            "extendedDiagnostics" => {
+                if let Some(v) = reader.extended_diagnostics(key, value) {
+                    options.extended_diagnostics = Some(v);
+                }
+            }
+            "fullTemplateTypeCheck" => {
+                reader.bool_into(key, value, &mut options.full_template_type_check)
+            }
+            "strictInjectionParameters" => {
+                reader.bool_into(key, value, &mut options.strict_injection_parameters)
+            }
+            "strictInputAccessModifiers" => {
+                reader.bool_into(key, value, &mut options.strict_input_access_modifiers)
+            }
+            "skipTemplateCodegen" => {
+                reader.bool_into(key, value, &mut options.skip_template_codegen)
+            }
+            "enableIvy" => reader.bool_into(key, value, &mut options.enable_ivy),
+            "flatModuleOutFile" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.flat_module_out_file = Some(v);
+                }
+            }
+            "compilationMode" => {
+                if let Some(v) = reader.string(key, value) {
+                    match v.as_str() {
+                        "full" => options.compilation_mode = Some(CompilationMode::Full),
+                        "partial" => options.compilation_mode = Some(CompilationMode::Partial),
+                        "experimental-local" => {
+                            options.compilation_mode = Some(CompilationMode::Local)
+                        }
+                        _ => reader.wrong_type(key, "'full' | 'partial' | 'experimental-local'"),
+                    }
+                }
+            }
+            "i18nInFile" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.i18n_in_file = Some(resolve_path(base_dir, &v));
+                }
+            }
+            "i18nInFiles" => {
+                if let Some(files) = reader.string_list(key, value) {
+                    options.i18n_in_files = Some(
+                        files
+                            .iter()
+                            .map(|file| resolve_path(base_dir, file))
+                            .collect(),
+                    );
+                }
+            }
+            "i18nInFormat" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.i18n_in_format = Some(v);
+                }
+            }
+            "i18nInLocale" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.i18n_in_locale = Some(v);
+                }
+            }
+            "i18nInMissingTranslations" => {
+                if let Some(v) = reader.string(key, value) {
+                    match v.as_str() {
+                        "error" | "warning" | "ignore" => {
+                            options.i18n_in_missing_translations = Some(v)
+                        }
+                        _ => reader.wrong_type(key, "'error' | 'warning' | 'ignore'"),
+                    }
+                }
+            }
+            "i18nOutFile" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.i18n_out_file = Some(resolve_path(base_dir, &v));
+                }
+            }
+            "i18nOutFormat" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.i18n_out_format = Some(v);
+                }
+            }
+            "i18nOutLocale" => {
+                if let Some(v) = reader.string(key, value) {
+                    options.i18n_out_locale = Some(v);
+                }
+            }
+            "i18nUseExternalIds" => {
+                reader.bool_into(key, value, &mut options.i18n_use_external_ids)
+            }
+            "enableI18nLegacyMessageIdFormat" => reader.bool_into(
+                key,
+                value,
+                &mut options.enable_i18n_legacy_message_id_format,
+            ),
+            "i18nNormalizeLineEndingsInICUs" => {
+                reader.bool_into(key, value, &mut options.i18n_normalize_line_endings_in_icus)
+            }
+            "_enableTemplateSourceLocations" => {
+                reader.bool_into(key, value, &mut options.enable_template_source_locations)
+            }
+            other if IGNORED_ANGULAR_OPTIONS.contains(&other) => {}
+            // Private options (e.g. `_enableTemplateTypeChecker`) are not validated.
+            other if other.starts_with('_') => {}
+            other => reader.unknown(other),
+        }
+    }
+}
+
+/// Layer `overrides` on top of `target`.
+///
+/// Options that are set in `overrides` win; unset options keep the value from `target`.
+pub fn overlay_options(target: &mut CompilerOptions, overrides: &CompilerOptions) {
+    macro_rules! overlay_some {
+        ($($field:ident),*) => {
+            $(if overrides.$field.is_some() {
+                target.$field = overrides.$field.clone();
+            })*
+        };
+    }
+
+    overlay_some!(
+        project,
+        base_url,
+        base_path,
+        root_dir,
+        out_dir,
+        flat_module_out_file,
+        i18n_in_file,
+        i18n_in_files,
+        i18n_in_format,
+        i18n_in_locale,
+        i18n_in_missing_translations,
+        i18n_out_file,
+        i18n_out_format,
+        i18n_out_locale,
+        locale,
+        new_line,
+        extended_diagnostics,
+        source_map,
+        inline_source_map,
+        inline_sources,
+        declaration,
+        strict,
+        strict_null_checks,
+        full_template_type_check,
+        strict_templates,
+        strict_injection_parameters,
+        strict_input_access_modifiers,
+        skip_template_codegen,
+        enable_ivy,
+        i18n_use_external_ids,
+        enable_i18n_legacy_message_id_format,
+        i18n_normalize_line_endings_in_icus,
+        enable_template_source_locations,
+        compilation_mode
+    );
+}
+
+/// A `files`, `include` or `exclude` list, with the directory of the tsconfig that
+/// declared it. Entries are relative to that directory.
+#[derive(Debug, Clone)]
+pub struct ConfigFileSpecs {
+    pub base_dir: PathBuf,
+    pub specs: Vec<String>,
+}
+
+/// A tsconfig merged with every config it extends.
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedTsConfig {
+    /// Options merged from the whole chain, most derived config last.
+    pub options: CompilerOptions,
+    /// `files` of the most derived config that declares it.
+    pub files: Option<ConfigFileSpecs>,
+    /// `include` of the most derived config that declares it.
+    pub include: Option<ConfigFileSpecs>,
+    /// `exclude` of the most derived config that declares it.
+    pub exclude: Option<ConfigFileSpecs>,
+}
+
+/// Read the tsconfig at `config_path`, following `extends`.
+///
+/// As in TypeScript, base configs are applied first: `compilerOptions` and
+/// `angularCompilerOptions` are merged key by key, while `files`, `include` and
+/// `exclude` are replaced as a whole. Returns `None` when the config itself cannot be
+/// read or parsed; problems in base configs are reported and skipped.
+pub fn load_tsconfig(config_path: &Path, errors: &mut Vec<Diagnostic>) -> Option<ResolvedTsConfig> {
+    let mut resolved = ResolvedTsConfig::default();
+    let mut stack = Vec::new();
+    if load_into(config_path, &mut resolved, &mut stack, errors) {
+        Some(resolved)
+    } else {
+        None
+    }
+}
+
+fn load_into(
+    config_path: &Path,
+    resolved: &mut ResolvedTsConfig,
+    stack: &mut Vec<PathBuf>,
+    errors: &mut Vec<Diagnostic>,
+) -> bool {
+    let config_file = config_path.to_string_lossy().to_string();
+    let canonical = std::fs::canonicalize(config_path).unwrap_or(config_path.to_path_buf());
+    if stack.contains(&canonical) {
+        let chain: Vec<String> = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        errors.push(config_diagnostic(
+            CONFIG_EXTENDS_CIRCULARITY,
+            format!(
+                "Circularity detected while resolving configuration: {}",
+                chain.join(" -> ")
+            ),
+            &config_file,
+        ));
+        return false;
+    }
+
+    let content = match std::fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            errors.push(config_diagnostic(
+                -1,
+                format!("Failed to read {}: {}", config_file, e),
+                &config_file,
+            ));
+            return false;
+        }
+    };
+    let config = match parse_jsonc(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            errors.push(config_diagnostic(
+                -1,
+                format!("Failed to parse {}: {}", config_file, e),
+                &config_file,
+            ));
+            return false;
+        }
+    };
+
+    let base_dir = config_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+    stack.push(canonical);
+    let extends: Vec<&Value> = match config.get("extends") {
+        None => Vec::new(),
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(single) => vec![single],
+    };
+    for spec in extends {
+        let Some(spec) = spec.as_str() else {
+            errors.push(config_diagnostic(
+                COMPILER_OPTION_WRONG_TYPE,
+                "Compiler option 'extends' requires a value of type string or Array.".to_string(),
+                &config_file,
+            ));
+            continue;
+        };
+        match resolve_extends(spec, &base_dir) {
+            Some(base_config) => {
+                load_into(&base_config, resolved, stack, errors);
+            }
+            None => errors.push(config_diagnostic(
+                CONFIG_FILE_NOT_FOUND,
+                format!("File '{}' not found.", spec),
+                &config_file,
+            )),
+        }
+    }
+    stack.pop();
+
+    if let Some(raw) = config.get("compilerOptions") {
+        apply_compiler_options(&mut resolved.options, raw, &base_dir, &config_file, errors);
+    }
+    if let Some(raw) = config.get("angularCompilerOptions") {
+        apply_angular_compiler_options(&mut resolved.options, raw, &base_dir, &config_file, errors);
+    }
+    for (key, target) in [
+        ("files", &mut resolved.files),
+        ("include", &mut resolved.include),
+        ("exclude", &mut resolved.exclude),
+    ] {
+        match config.get(key) {
+            None => {}
+            Some(Value::Array(items)) => {
+                *target = Some(ConfigFileSpecs {
+                    base_dir: base_dir.clone(),
+                    specs: items
+                        .iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect(),
+                });
+            }
+            Some(_) => errors.push(config_diagnostic(
+                COMPILER_OPTION_WRONG_TYPE,
+                format!("Compiler option '{}' requires a value of type Array.", key),
+                &config_file,
+            )),
+        }
+    }
+    true
+}
+
+/// Resolve an `extends` specifier relative to the directory of the extending config.
+///
+/// Relative and absolute specifiers name a file, with `.json` optional. Anything else is
+/// looked up in `node_modules` directories from `base_dir` upwards, either as a file
+/// within a package or as a package whose `tsconfig` field (or `tsconfig.json`) is used.
+fn resolve_extends(spec: &str, base_dir: &Path) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| -> Option<PathBuf> {
+        if path.is_file() {
+            return Some(path);
+        }
+        let mut json = path.into_os_string();
+        json.push(".json");
+        let json = PathBuf::from(json);
+        json.is_file().then_some(json)
+    };
+
+    if spec.starts_with("./") || spec.starts_with("../") || Path::new(spec).is_absolute() {
+        return with_json(normalize_path(&base_dir.join(spec)));
+    }
+
+    for dir in base_dir.ancestors() {
+        let candidate = dir.join("node_modules").join(spec);
+        if let Some(file) = with_json(candidate.clone()) {
+            return Some(file);
+        }
+        if candidate.is_dir() {
+            let tsconfig_field = std::fs::read_to_string(candidate.join("package.json"))
+                .ok()
+                .and_then(|pkg| serde_json::from_str::<Value>(&pkg).ok())
+                .and_then(|pkg| pkg.get("tsconfig")?.as_str().map(String::from));
+            let file = match tsconfig_field {
+                Some(field) => candidate.join(field),
+                None => candidate.join("tsconfig.json"),
+            };
+            if file.is_file() {
+                return Some(file);
+            }
+        }
+    }
+    None
+}
+
+fn resolve_path(base_dir: &Path, value: &str) -> String {
+    normalize_path(&base_dir.join(value))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Lexically remove `.` and `..` components from a path.
+fn normalize_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn expect_object<'v>(
+    block: &str,
+    raw: &'v Value,
+    config_file: &str,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<&'v serde_json::Map<String, Value>> {
+    match raw {
+        Value::Object(map) => Some(map),
+        Value::Null => None,
+        _ => {
+            errors.push(config_diagnostic(
+                COMPILER_OPTION_WRONG_TYPE,
+                format!("'{}' must be an object.", block),
+                config_file,
+            ));
+            None
+        }
+    }
+}
+
+fn config_diagnostic(code: i32, message: String, config_file: &str) -> Diagnostic {
+    Diagnostic {
+        category: DiagnosticCategory::Error,
+        code,
+        message,
+        file: Some(config_file.to_string()),
+        start: None,
+        length: None,
+    }
+}
+
+/// Reads typed option values, reporting a diagnostic for each mismatch.
+struct OptionReader<'a> {
+    config_file: &'a str,
+    errors: &'a mut Vec<Diagnostic>,
+}
+
+impl OptionReader<'_> {
+    fn string(&mut self, key: &str, value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            _ => {
+                self.wrong_type(key, "string");
+                None
+            }
+        }
+    }
+
+    fn string_list(&mut self, key: &str, value: &Value) -> Option<Vec<String>> {
+        match value {
+            Value::Array(items) if items.iter().all(Value::is_string) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect(),
+            ),
+            _ => {
+                self.wrong_type(key, "string[]");
+                None
+            }
+        }
+    }
+
+    fn bool_into(&mut self, key: &str, value: &Value, target: &mut Option<bool>) {
+        match value {
+            Value::Bool(b) => *target = Some(*b),
+            _ => self.wrong_type(key, "boolean"),
+        }
+    }
+
+    fn extended_diagnostics(
+        &mut self,
+        key: &str,
+        value: &Value,
+    ) -> Option<ExtendedDiagnosticsOptions> {
+        let Value::Object(entries) = value else {
+            self.wrong_type(key, "object");
+            return None;
+        };
+        let mut options = ExtendedDiagnosticsOptions::default();
+        for (name, value) in entries {
+            let option = format!("{}.{}", key, name);
+            match name.as_str() {
+                "defaultCategory" => {
+                    if let Some(label) = self.string(&option, value) {
+                        options.default_category = self.category_label(&option, &label);
+                    }
+                }
+                "checks" => {
+                    let Value::Object(checks) = value else {
+                        self.wrong_type(&option, "object");
+                        continue;
+                    };
+                    for (check, label) in checks {
+                        if !ExtendedTemplateDiagnosticName::ALL
+                            .iter()
+                            .any(|name| name.to_string() == *check)
+                        {
+                            let allowed: Vec<String> = ExtendedTemplateDiagnosticName::ALL
+                                .iter()
+                                .map(|name| name.to_string())
+                                .collect();
+                            self.errors.push(config_diagnostic(
+                                ErrorCode::ConfigExtendedDiagnosticsUnknownCheck as i32,
+                                format!(
+                                    "Angular compiler option \"{}\" has an unknown check: \"{}\".\n\nAllowed check names are:\n{}",
+                                    option,
+                                    check,
+                                    allowed.join("\n")
+                                ),
+                                self.config_file,
+                            ));
+                            continue;
+                        }
+                        let check_option = format!("{}['{}']", option, check);
+                        let Some(label) = self.string(&check_option, label) else {
+                            continue;
+                        };
+                        let Some(label) = self.category_label(&check_option, &label) else {
+                            continue;
+                        };
+                        options.checks.insert(check.clone(), label);
+                    }
+                }
+                _ => self.unknown(&option),
+            }
+        }
+        Some(options)
+    }
+
+    fn category_label(&mut self, key: &str, label: &str) -> Option<DiagnosticCategoryLabel> {
+        let parsed = DiagnosticCategoryLabel::parse(label);
+        if parsed.is_none() {
+            self.errors.push(config_diagnostic(
+                ErrorCode::ConfigExtendedDiagnosticsUnknownCategoryLabel as i32,
+                format!(
+                    "Angular compiler option \"{}\" has an unknown diagnostic category: \"{}\".\n\nAllowed diagnostic categories are:\nwarning\nerror\nsuppress",
+                    key, label
+                ),
+                self.config_file,
+            ));
+        }
+        parsed
+    }
+
+    fn wrong_type(&mut self, key: &str, expected: &str) {
+        self.errors.push(config_diagnostic(
+            COMPILER_OPTION_WRONG_TYPE,
+            format!(
+                "Compiler option '{}' requires a value of type {}.",
+                key, expected
+            ),
+            self.config_file,
+        ));
+    }
+
+    fn unknown(&mut self, key: &str) {
+        self.errors.push(config_diagnostic(
+            UNKNOWN_COMPILER_OPTION,
+            format!("Unknown compiler option '{}'.", key),
+            self.config_file,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_compiler_options_relative_to_config_dir() {
+        let mut options = CompilerOptions::default();
+        let mut errors = Vec::new();
+        apply_compiler_options(
+            &mut options,
+            &json!({ "outDir": "dist", "sourceMap": true, "target": "ES2022" }),
+            Path::new("/project"),
+            "/project/tsconfig.json",
+            &mut errors,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(options.out_dir.as_deref(), Some("/project/dist"));
+        assert_eq!(options.source_map, Some(true));
+    }
+
+    #[test]
+    fn maps_angular_compiler_options() {
+        let mut options = CompilerOptions::default();
+        let mut errors = Vec::new();
+        apply_angular_compiler_options(
+            &mut options,
+            &json!({
+                "strictTemplates": true,
+                "compilationMode": "partial",
+                "i18nInFile": "locale/messages.fr.xlf",
+                "i18nInFiles": ["locale/messages.fr.xlf", "locale/messages.de.xlf"],
+                "_enableTemplateSourceLocations": true,
+            }),
+            Path::new("/project"),
+            "/project/tsconfig.json",
+            &mut errors,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(options.strict_templates, Some(true));
+        assert_eq!(options.compilation_mode, Some(CompilationMode::Partial));
+        assert_eq!(
+            options.i18n_in_file.as_deref(),
+            Some("/project/locale/messages.fr.xlf")
+        );
+        assert_eq!(
+            options.i18n_in_files,
+            Some(vec![
+                "/project/locale/messages.fr.xlf".to_string(),
+                "/project/locale/messages.de.xlf".to_string(),
+            ])
+        );
+        assert_eq!(options.enable_template_source_locations, Some(true));
+    }
+
+    #[test]
+    fn maps_extended_diagnostics() {
+        let mut options = CompilerOptions::default();
+        let mut errors = Vec::new();
+        apply_angular_compiler_options(
+            &mut options,
+            &json!({
+                "extendedDiagnostics": {
+                    "defaultCategory": "error",
+                    "checks": {
+                        "invalidBananaInBox": "suppress",
+                        "notACheck": "warning",
+                        "missingNgForOfLet": "loud",
+                        "nullishCoalescingNotNullable": "error",
+                        "unusedStandaloneImports": "suppress",
+                    },
+                },
+            }),
+            Path::new("/project"),
+            "/project/tsconfig.json",
+            &mut errors,
+        );
+
+        let extended = options.extended_diagnostics.unwrap();
+        assert_eq!(
+            extended.default_category,
+            Some(DiagnosticCategoryLabel::Error)
+        );
+        assert_eq!(
+            extended.checks.get("invalidBananaInBox"),
+            Some(&DiagnosticCategoryLabel::Suppress)
+        );
+        assert_eq!(
+            extended.checks.get("unusedStandaloneImports"),
+            Some(&DiagnosticCategoryLabel::Suppress)
+        );
+        // Checks which are not implemented are accepted, and have no effect.
+        assert_eq!(
+            extended.checks.get("nullishCoalescingNotNullable"),
+            Some(&DiagnosticCategoryLabel::Error)
+        );
+        assert_eq!(extended.checks.len(), 3);
+        let codes: Vec<i32> = errors.iter().map(|e| e.code).collect();
+        assert_eq!(codes, vec![4005, 4004]);
+    }
+
+    #[test]
+    fn reports_unknown_and_mistyped_options() {
+        let mut options = CompilerOptions::default();
+        let mut errors = Vec::new();
+        apply_compiler_options(
+            &mut options,
+            &json!({ "sourcemap": true, "declaration": "yes" }),
+            Path::new("/project"),
+            "/project/tsconfig.json",
+            &mut errors,
+        );
+
+        let codes: Vec<i32> = errors.iter().map(|e| e.code).collect();
+        assert!(codes.contains(&UNKNOWN_COMPILER_OPTION));
+        assert!(codes.contains(&COMPILER_OPTION_WRONG_TYPE));
+        assert_eq!(options.declaration, None);
+    }
+
+    #[test]
+    fn overlay_prefers_set_overrides() {
+        let mut options = CompilerOptions {
+            out_dir: Some("/project/dist".to_string()),
+            root_dir: Some("/project/src".to_string()),
+            ..CompilerOptions::default()
+        };
+        let overrides = CompilerOptions {
+            out_dir: Some("/tmp/out".to_string()),
+            declaration: Some(true),
+            ..CompilerOptions::default()
+        };
+        overlay_options(&mut options, &overrides);
+
+        assert_eq!(options.out_dir.as_deref(), Some("/tmp/out"));
+        assert_eq!(options.root_dir.as_deref(), Some("/project/src"));
+        assert_eq!(options.declaration, Some(true));
+    }
+
+    #[test]
+    fn overlay_can_disable_options() {
+        let mut options = CompilerOptions {
+            source_map: Some(true),
+            enable_template_source_locations: Some(true),
+            strict_templates: Some(true),
+            ..CompilerOptions::default()
+        };
+        let overrides = CompilerOptions {
+            source_map: Some(false),
+            enable_template_source_locations: Some(false),
+            ..CompilerOptions::default()
+        };
+        overlay_options(&mut options, &overrides);
+
+        assert_eq!(options.source_map, Some(false));
+        assert_eq!(options.enable_template_source_locations, Some(false));
+        assert_eq!(options.strict_templates, Some(true));
+    }
+
+    #[test]
+    fn overlay_applies_an_explicit_full_compilation_mode() {
+        let mut options = CompilerOptions {
+            compilation_mode: Some(CompilationMode::Partial),
+            ..CompilerOptions::default()
+        };
+        overlay_options(&mut options, &CompilerOptions::default());
+        assert_eq!(options.compilation_mode, Some(CompilationMode::Partial));
+
+        let overrides = CompilerOptions {
+            compilation_mode: Some(CompilationMode::Full),
+            ..CompilerOptions::default()
+        };
+        overlay_options(&mut options, &overrides);
+        assert_eq!(options.compilation_mode, Some(CompilationMode::Full));
+    }
+
+    #[test]
+    fn specific_strict_flags_override_strict_in_any_order() {
+        for raw in [
+            json!({ "strict": true, "strictNullChecks": false }),
+            json!({ "strictNullChecks": false, "strict": true }),
+        ] {
+            let mut options = CompilerOptions::default();
+            let mut errors = Vec::new();
+            apply_compiler_options(
+                &mut options,
+                &raw,
+                Path::new("/project"),
+                "/project/tsconfig.json",
+                &mut errors,
+            );
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert!(!options.uses_strict_null_checks());
+        }
+
+        let options = CompilerOptions {
+            strict: Some(true),
+            ..CompilerOptions::default()
+        };
+        assert!(options.uses_strict_null_checks());
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ngc_tsconfig_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn follows_relative_extends_chain() {
+        let dir = temp_dir("relative");
+        write(
+            &dir,
+            "tsconfig.json",
+            r#"{
+                // root config
+                "compilerOptions": { "outDir": "./dist", "sourceMap": true, },
+                "angularCompilerOptions": { "strictTemplates": true },
+                "include": ["src/**/*.ts"],
+            }"#,
+        );
+        let app = write(
+            &dir,
+            "apps/web/tsconfig.app.json",
+            r#"{
+                "extends": "../../tsconfig",
+                /* sourceMap is overridden, outDir is inherited */
+                "compilerOptions": { "sourceMap": false },
+                "files": ["main.ts"]
+            }"#,
+        );
+
+        let mut errors = Vec::new();
+        let resolved = load_tsconfig(&app, &mut errors).unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            resolved.options.out_dir,
+            Some(dir.join("dist").to_string_lossy().to_string())
+        );
+        assert_eq!(resolved.options.source_map, Some(false));
+        assert_eq!(resolved.options.strict_templates, Some(true));
+        assert_eq!(resolved.files.unwrap().base_dir, dir.join("apps/web"));
+        assert_eq!(resolved.include.unwrap().base_dir, dir);
+    }
+
+    #[test]
+    fn resolves_package_and_array_extends() {
+        let dir = temp_dir("package");
+        write(
+            &dir,
+            "node_modules/@tsconfig/strictest/package.json",
+            r#"{ "name": "@tsconfig/strictest", "tsconfig": "base.json" }"#,
+        );
+        write(
+            &dir,
+            "node_modules/@tsconfig/strictest/base.json",
+            r#"{ "compilerOptions": { "strict": true, "declaration": true } }"#,
+        );
+        write(
+            &dir,
+            "tsconfig.base.json",
+            r#"{ "compilerOptions": { "declaration": false } }"#,
+        );
+        let app = write(
+            &dir,
+            "tsconfig.json",
+            r#"{ "extends": ["@tsconfig/strictest", "./tsconfig.base.json"] }"#,
+        );
+
+        let mut errors = Vec::new();
+        let resolved = load_tsconfig(&app, &mut errors).unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert!(resolved.options.uses_strict_null_checks());
+        assert_eq!(resolved.options.declaration, Some(false));
+    }
+
+    #[test]
+    fn reports_missing_and_circular_extends() {
+        let dir = temp_dir("errors");
+        write(&dir, "a.json", r#"{ "extends": "./b.json" }"#);
+        write(
+            &dir,
+            "b.json",
+            r#"{ "extends": ["./a.json", "./missing.json"] }"#,
+        );
+
+        let mut errors = Vec::new();
+        assert!(load_tsconfig(&dir.join("a.json"), &mut errors).is_some());
+        let codes: Vec<i32> = errors.iter().map(|e| e.code).collect();
+        assert!(codes.contains(&CONFIG_EXTENDS_CIRCULARITY), "{:?}", errors);
+        assert!(codes.contains(&CONFIG_FILE_NOT_FOUND), "{:?}", errors);
+    }
+}
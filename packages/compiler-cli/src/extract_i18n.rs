@@ -1,21 +1,34 @@
 //! Extract i18n
 //!
 //! Corresponds to packages/compiler-cli/src/extract_i18n.ts
-//! Extracts i18n messages from Angular templates.
+//! Extracts i18n messages from Angular templates and `$localize` tagged templates.
 
-use crate::ngtsc::xi18n::MessageExtractor;
-use crate::transformers::api::I18nFormat;
+use crate::ngtsc::file_system::NodeJSFileSystem;
+use crate::ngtsc::program::NgtscProgram;
+use crate::perform_compile::{
+    compiler_diagnostic, format_diagnostics, read_configuration, to_ng_compiler_options,
+};
+use crate::transformers::api::{CompilerOptions, DiagnosticCategory, I18nFormat};
+use crate::transformers::i18n::{i18n_extract, i18n_get_serializer};
+use angular_compiler::i18n::message_bundle::MessageBundle;
+use angular_compiler::ml_parser::html_parser::HtmlParser;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 /// Options for i18n extraction.
 #[derive(Debug, Clone, Default)]
 pub struct ExtractI18nOptions {
+    /// Path to the tsconfig.json of the project to extract from.
+    pub project: Option<String>,
     /// Output format.
     pub format: Option<I18nFormat>,
     /// Output file path.
     pub out_file: Option<String>,
+    /// Directory the output file is written to, the project's directory by default.
+    pub out_dir: Option<String>,
     /// Locale for the output.
     pub locale: Option<String>,
-    /// Source files to extract from.
+    /// Source files to extract from, when no project is given.
     pub source_files: Vec<String>,
 }
 
@@ -26,39 +39,139 @@ pub struct ExtractI18nResult {
     pub success: bool,
     /// Output content.
     pub output: Option<String>,
+    /// Path the output was written to.
+    pub out_file: Option<String>,
     /// Diagnostics.
     pub diagnostics: Vec<String>,
     /// Number of messages extracted.
     pub message_count: usize,
 }
 
-/// Extract i18n messages from source files.
-pub fn extract_i18n(options: ExtractI18nOptions) -> ExtractI18nResult {
-    let extractor = MessageExtractor::new();
-    let mut message_count = 0;
+impl ExtractI18nResult {
+    fn failed(diagnostics: Vec<String>) -> Self {
+        ExtractI18nResult {
+            success: false,
+            output: None,
+            out_file: None,
+            diagnostics,
+            message_count: 0,
+        }
+    }
+}
 
-    // In a real implementation, we would:
-    // 1. Parse each source file
-    // 2. Find i18n-marked content
-    // 3. Extract messages
+/// Parse the name of an i18n format as given on the command line.
+pub fn parse_i18n_format(name: &str) -> Result<I18nFormat, String> {
+    match name.to_lowercase().as_str() {
+        "xlf" | "xlif" | "xliff" => Ok(I18nFormat::Xlf),
+        "xlf2" | "xliff2" => Ok(I18nFormat::Xlf2),
+        "xmb" => Ok(I18nFormat::Xmb),
+        "json" => Ok(I18nFormat::Json),
+        "arb" => Ok(I18nFormat::Arb),
+        _ => Err(format!("Unsupported format \"{}\"", name)),
+    }
+}
 
-    for _file in &options.source_files {
-        // Would parse and extract here
-        message_count += 0; // Placeholder
+fn format_name(format: I18nFormat) -> &'static str {
+    match format {
+        I18nFormat::Xlf => "xlf",
+        I18nFormat::Xlf2 => "xlf2",
+        I18nFormat::Xmb => "xmb",
+        I18nFormat::Json => "json",
+        I18nFormat::Arb => "arb",
     }
+}
 
-    // Generate output based on format
-    let output = match options.format.unwrap_or(I18nFormat::Xlf) {
-        I18nFormat::Xlf | I18nFormat::Xlf2 => Some(extractor.to_xliff()),
-        I18nFormat::Xmb => Some(extractor.to_xmb()),
-        I18nFormat::Json => Some("{}".to_string()),
+/// Extract the i18n messages of the component templates and `$localize` tagged templates
+/// of a project and write them to the output file.
+///
+/// Nothing is written when the project or its templates have errors.
+pub fn extract_i18n(options: ExtractI18nOptions) -> ExtractI18nResult {
+    let (root_names, mut compiler_options) = match &options.project {
+        Some(project) => {
+            let parsed = read_configuration(project, None);
+            if !parsed.errors.is_empty() {
+                return ExtractI18nResult::failed(
+                    parsed.errors.into_iter().map(|e| e.message).collect(),
+                );
+            }
+            (parsed.root_names, parsed.options)
+        }
+        None => (options.source_files.clone(), CompilerOptions::default()),
     };
 
-    ExtractI18nResult {
-        success: true,
-        output,
-        diagnostics: Vec::new(),
-        message_count,
+    // Command-line options take precedence over the tsconfig.
+    if let Some(format) = options.format {
+        compiler_options.i18n_out_format = Some(format_name(format).to_string());
+    }
+    if options.out_file.is_some() {
+        compiler_options.i18n_out_file = options.out_file.clone();
+    }
+    if options.locale.is_some() {
+        compiler_options.i18n_out_locale = options.locale.clone();
+    }
+    if options.out_dir.is_some() {
+        compiler_options.out_dir = options.out_dir.clone();
+    }
+
+    let fs = NodeJSFileSystem::new();
+    let mut ng_options = to_ng_compiler_options(&compiler_options);
+    // Template type-checking isn't needed to extract the messages.
+    ng_options.strict_templates = false;
+    let mut program = NgtscProgram::new(root_names, ng_options, &fs);
+    if let Err(e) = program.load_ng_structure(Path::new(".")) {
+        return ExtractI18nResult::failed(vec![e]);
+    }
+
+    let mut bundle = MessageBundle::new(
+        HtmlParser::new(),
+        vec![],
+        HashMap::new(),
+        compiler_options.i18n_out_locale.clone(),
+        true,
+    );
+    let mut diagnostics: Vec<_> = program
+        .get_diagnostics()
+        .into_iter()
+        .map(compiler_diagnostic)
+        .collect();
+    match program.xi18n(&mut bundle) {
+        Ok(xi18n_diagnostics) => {
+            diagnostics.extend(xi18n_diagnostics.into_iter().map(compiler_diagnostic))
+        }
+        Err(e) => return ExtractI18nResult::failed(vec![e]),
+    }
+    if diagnostics
+        .iter()
+        .any(|d| d.category == DiagnosticCategory::Error)
+    {
+        let host = crate::main_entry::FormatDiagnosticsHost::new(None);
+        return ExtractI18nResult::failed(vec![format_diagnostics(&diagnostics, &host)]);
+    }
+
+    let format = compiler_options.i18n_out_format.as_deref();
+    match i18n_extract(
+        format,
+        compiler_options.i18n_out_file.as_deref(),
+        &compiler_options,
+        &bundle,
+    ) {
+        Ok((path, content)) => {
+            let serializer = i18n_get_serializer(format.unwrap_or("xlf"));
+            let message_count = bundle
+                .get_messages()
+                .iter()
+                .map(|message| serializer.digest(message))
+                .collect::<HashSet<_>>()
+                .len();
+            ExtractI18nResult {
+                success: true,
+                output: Some(content),
+                out_file: Some(path.to_string_lossy().to_string()),
+                diagnostics: Vec::new(),
+                message_count,
+            }
+        }
+        Err(e) => ExtractI18nResult::failed(vec![e]),
     }
 }
 
@@ -70,24 +183,36 @@ pub fn main_xi18n(args: &[String]) -> i32 {
     let mut i = 0;
     while i < args.len() {
         match args[i].as_str() {
-            "--format" | "-f" => {
+            "--project" | "-p" => {
                 if i + 1 < args.len() {
-                    options.format = match args[i + 1].as_str() {
-                        "xlf" | "xliff" => Some(I18nFormat::Xlf),
-                        "xlf2" | "xliff2" => Some(I18nFormat::Xlf2),
-                        "xmb" => Some(I18nFormat::Xmb),
-                        "json" => Some(I18nFormat::Json),
-                        _ => None,
-                    };
+                    options.project = Some(args[i + 1].clone());
                     i += 1;
                 }
             }
-            "--out-file" | "-o" => {
+            "--format" | "--i18nFormat" | "-f" => {
+                if i + 1 < args.len() {
+                    match parse_i18n_format(&args[i + 1]) {
+                        Ok(format) => options.format = Some(format),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            return 1;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+            "--out-file" | "--outFile" | "-o" => {
                 if i + 1 < args.len() {
                     options.out_file = Some(args[i + 1].clone());
                     i += 1;
                 }
             }
+            "--output-path" | "--outDir" => {
+                if i + 1 < args.len() {
+                    options.out_dir = Some(args[i + 1].clone());
+                    i += 1;
+                }
+            }
             "--locale" | "-l" => {
                 if i + 1 < args.len() {
                     options.locale = Some(args[i + 1].clone());
@@ -102,12 +227,21 @@ pub fn main_xi18n(args: &[String]) -> i32 {
         i += 1;
     }
 
+    if options.project.is_none()
+        && options.source_files.is_empty()
+        && Path::new("tsconfig.json").exists()
+    {
+        options.project = Some("tsconfig.json".to_string());
+    }
+
     let result = extract_i18n(options);
 
     if result.success {
-        if let Some(output) = result.output {
-            println!("{}", output);
-        }
+        println!(
+            "Extracted {} messages to {}",
+            result.message_count,
+            result.out_file.unwrap_or_default()
+        );
         0
     } else {
         for diag in result.diagnostics {
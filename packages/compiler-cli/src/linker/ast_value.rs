@@ -67,6 +67,30 @@ impl<'a, TExpression: AstNode> AstValue<'a, TExpression> {
         self.host.is_null(&self.node)
     }
 
+    /// The name of the symbol this value refers to, if it is an identifier.
+    pub fn get_symbol_name(&self) -> Option<String> {
+        self.host.get_symbol_name(&self.node)
+    }
+
+    pub fn is_function(&self) -> bool {
+        self.host.is_function_expression(&self.node)
+    }
+
+    /// The value returned by this function expression.
+    pub fn get_function_return_value(&self) -> Result<AstValue<'a, TExpression>, String> {
+        let node = self.host.parse_return_value(&self.node)?;
+        Ok(AstValue::new(node, self.host))
+    }
+
+    /// The parameters of this function expression.
+    pub fn get_function_parameters(&self) -> Result<Vec<AstValue<'a, TExpression>>, String> {
+        let params = self.host.parse_parameters(&self.node)?;
+        Ok(params
+            .into_iter()
+            .map(|n| AstValue::new(n, self.host))
+            .collect())
+    }
+
     pub fn print(&self) -> String {
         self.host.print_node(&self.node)
     }
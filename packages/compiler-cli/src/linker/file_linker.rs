@@ -24,7 +24,10 @@ impl<'a, A: AstFactory> LinkerEnvironment<'a, A> {
 }
 
 use crate::linker::ast::AstNode;
-use crate::linker::partial_linkers::partial_linker_selector::PartialLinkerSelector;
+use crate::linker::ast_value::AstObject;
+use crate::linker::partial_linkers::partial_linker_selector::{
+    PartialLinkerSelector, PLACEHOLDER_VERSION,
+};
 
 pub struct FileLinker<'a, A: AstFactory>
 where
@@ -73,8 +76,10 @@ where
             .get_object()
             .map_err(|_| "Metadata is not an object".to_string())?;
 
-        // We need a proper version handling. For now, empty string or stub.
-        let linker = self.linker_selector.get_linker(name, "0.0.0", "0.0.0");
+        let (min_version, version) = declaration_versions(&obj);
+        let linker = self
+            .linker_selector
+            .get_linker(name, &min_version, &version)?;
 
         let mut constant_pool = ConstantPool::new(false); // Mock Constant Pool (needs implementation or passing in)
 
@@ -82,11 +87,11 @@ where
             &mut constant_pool,
             &obj,
             source_url,
-            "0.0.0",
+            &version,
             None,
             None,
             None,
-        );
+        )?;
 
         // Translate definition (output AST) to native AST using environment.translator?
         // Since we don't have translator instance yet, we stub.
@@ -95,3 +100,19 @@ where
         Err("Translator not available yet".to_string())
     }
 }
+
+/// The `minVersion` and `version` of a partial declaration.
+///
+/// A missing `version` is treated as the development placeholder, and a missing
+/// `minVersion` as the declaration's own `version`.
+pub fn declaration_versions<TExpression: AstNode>(
+    meta_obj: &AstObject<TExpression>,
+) -> (String, String) {
+    let version = meta_obj
+        .get_string("version")
+        .unwrap_or_else(|_| PLACEHOLDER_VERSION.to_string());
+    let min_version = meta_obj
+        .get_string("minVersion")
+        .unwrap_or_else(|_| version.clone());
+    (min_version, version)
+}
@@ -2,10 +2,17 @@
 #![deny(clippy::all)]
 use std::any::Any;
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::linker::ast_value::AstValue;
+use crate::linker::file_linker::declaration_versions;
 use crate::linker::oxc_ast_host::{OxcAstHost, OxcNode};
-use crate::linker::partial_linkers::partial_linker_selector::PartialLinkerSelector;
+use crate::linker::partial_linkers::partial_linker_selector::{
+    PartialLinkerSelector, PLACEHOLDER_VERSION,
+};
+use crate::ngtsc::sourcemaps::{
+    remap_through_edits, strip_source_mapping_url, SourceFileLoader, SourceMap, TextEdit,
+};
 use angular_compiler::constant_pool::ConstantPool;
 use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
 use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
@@ -18,8 +25,16 @@ use oxc_ast::ast::{self, Expression};
 use oxc_parser::Parser;
 use oxc_span::SourceType;
 
+/// The linked code of a file, and a source map for it.
+#[napi(object)]
+pub struct LinkResult {
+    pub code: String,
+    /// Source map (JSON) for `code`, composed with the source map of the input.
+    pub map: Option<String>,
+}
+
 #[napi]
-pub fn link_file(source_code: String, filename: String) -> Result<String> {
+pub fn link_file(source_code: String, filename: String) -> Result<LinkResult> {
     eprintln!("[Linker NAPI Debug] link_file: {}", filename);
     let allocator = Allocator::default();
     let clean_filename = filename.split('?').next().unwrap_or(&filename);
@@ -801,14 +816,22 @@ pub fn link_file(source_code: String, filename: String) -> Result<String> {
                                                         match value.get_object() {
                                                             Ok(obj) => {
                                                                 let linker =
-                                                                    self.selector.get_linker(
-                                                                        d_name, "0.0.0", "0.0.0",
-                                                                    );
+                                                                    match self.selector.get_linker(
+                                                                        d_name,
+                                                                        PLACEHOLDER_VERSION,
+                                                                        PLACEHOLDER_VERSION,
+                                                                    ) {
+                                                                        Ok(linker) => linker,
+                                                                        Err(e) => {
+                                                                            self.errors.push(e);
+                                                                            continue;
+                                                                        }
+                                                                    };
                                                                 let mut constant_pool =
                                                                     ConstantPool::new(false);
 
                                                                 // Link partial declaration (reads templateUrl!)
-                                                                let result_expr = linker
+                                                                let result_expr = match linker
                                                                     .link_partial_declaration(
                                                                         &mut constant_pool,
                                                                         &obj,
@@ -817,7 +840,13 @@ pub fn link_file(source_code: String, filename: String) -> Result<String> {
                                                                         Some(target_name),
                                                                         Some(&self.alias_imports),
                                                                         None,
-                                                                    );
+                                                                    ) {
+                                                                    Ok(expr) => expr,
+                                                                    Err(e) => {
+                                                                        self.errors.push(e);
+                                                                        continue;
+                                                                    }
+                                                                };
 
                                                                 let js_code = if constant_pool
                                                                     .statements
@@ -927,15 +956,26 @@ pub fn link_file(source_code: String, filename: String) -> Result<String> {
                             let value = AstValue::new(oxc_node, &self.host);
                             match value.get_object() {
                                 Ok(obj) => {
-                                    let linker = self.selector.get_linker(n, "0.0.0", "0.0.0");
+                                    let (min_version, version) = declaration_versions(&obj);
+                                    let linker =
+                                        match self.selector.get_linker(n, &min_version, &version) {
+                                            Ok(linker) => linker,
+                                            Err(e) => {
+                                                self.errors.push(format!(
+                                                    "Failed to link {} in {}: {}",
+                                                    n, self.source_url, e
+                                                ));
+                                                return;
+                                            }
+                                        };
                                     let mut constant_pool = ConstantPool::new(false);
 
                                     // Link!
-                                    let result_expr = linker.link_partial_declaration(
+                                    let result_expr = match linker.link_partial_declaration(
                                         &mut constant_pool,
                                         &obj,
                                         self.source_url,
-                                        "0.0.0", // TODO: version
+                                        &version,
                                         None,
                                         Some(&self.alias_imports),
                                         // Try to find class metadata
@@ -963,7 +1003,16 @@ pub fn link_file(source_code: String, filename: String) -> Result<String> {
                                             }
                                         }
                                         .as_ref(),
-                                    );
+                                    ) {
+                                        Ok(expr) => expr,
+                                        Err(e) => {
+                                            self.errors.push(format!(
+                                                "Failed to link {} in {}: {}",
+                                                n, self.source_url, e
+                                            ));
+                                            return;
+                                        }
+                                    };
 
                                     // Emit JS
                                     let js_code = if constant_pool.statements.is_empty() {
@@ -1059,15 +1108,58 @@ pub fn link_file(source_code: String, filename: String) -> Result<String> {
     )
     .unwrap();
 
-    // Apply replacements
-    // Sort replacements by start position descending to avoid index shifting issues
-    visitor.replacements.sort_by(|a, b| b.0.cmp(&a.0));
+    // Apply replacements, in source order so that the edits can be tracked for the
+    // source map.
+    visitor.replacements.sort_by_key(|r| (r.0, r.1));
 
-    let mut result_code = source_code.clone();
+    let mut result_code = String::with_capacity(source_code.len());
+    let mut edits = Vec::with_capacity(visitor.replacements.len());
     let had_replacements = !visitor.replacements.is_empty();
+    let mut copied = 0;
 
-    for (start, end, new_text) in visitor.replacements {
-        result_code.replace_range((start as usize)..(end as usize), &new_text);
+    for (index, (start, end, new_text)) in visitor.replacements.into_iter().enumerate() {
+        let (start, end) = (start as usize, end as usize);
+        if start < copied {
+            // Nested within a range that was already replaced.
+            continue;
+        }
+        result_code.push_str(&source_code[copied..start]);
+        result_code.push_str(&new_text);
+        copied = end;
+        edits.push(TextEdit {
+            start,
+            end,
+            replacement_len: new_text.len(),
+            replacement: index,
+        });
+    }
+    result_code.push_str(&source_code[copied..]);
+
+    // Compose with the map of the input, if it has one, so that positions in the linked
+    // code trace back to the library's original sources.
+    let input_map = SourceFileLoader::new("").load_source_map(clean_filename, &source_code);
+    let has_input_map = input_map.is_some();
+    let map = match remap_through_edits(
+        &input_map.unwrap_or_else(|| SourceMap::identity(clean_filename, &source_code)),
+        &source_code,
+        &result_code,
+        &edits,
+    ) {
+        Ok(mut map) => {
+            map.file = Path::new(clean_filename)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Some(map.to_json())
+        }
+        Err(e) => {
+            writeln!(log_file, "Failed to compose source map: {}", e).ok();
+            None
+        }
+    };
+    // The returned map replaces the one the input referenced.
+    if has_input_map && map.is_some() {
+        result_code = strip_source_mapping_url(&result_code).to_string();
     }
 
     if result_code.contains("MatFormField") && !filename.contains(".html") {
@@ -1095,5 +1187,8 @@ pub fn link_file(source_code: String, filename: String) -> Result<String> {
         }
     }
 
-    Ok(result_code)
+    Ok(LinkResult {
+        code: result_code,
+        map,
+    })
 }
@@ -3,7 +3,10 @@
 //! Implements the `AstHost` trait for the OXC AST.
 
 use crate::linker::ast::{AstHost, AstNode, Range};
-use oxc_ast::ast::{Argument, ArrayExpressionElement, Expression, PropertyKey, Statement};
+use oxc_ast::ast::{
+    Argument, ArrayExpressionElement, BindingPatternKind, Expression, FormalParameter,
+    FormalParameters, PropertyKey, Statement,
+};
 use oxc_span::Span;
 
 /// Wrapper around various OXC AST node types to satisfy AstNode traits and unify disjoint enum types.
@@ -15,6 +18,7 @@ pub enum OxcNode<'a> {
     ArrayElement(&'a ArrayExpressionElement<'a>),
     Argument(&'a Argument<'a>),
     Statement(&'a Statement<'a>),
+    Parameter(&'a FormalParameter<'a>),
 }
 
 impl<'a> AstNode for OxcNode<'a> {}
@@ -34,6 +38,7 @@ impl<'a> OxcAstHost<'a> {
             OxcNode::ArrayElement(e) => e.span(),
             OxcNode::Argument(e) => e.span(),
             OxcNode::Statement(s) => s.span(),
+            OxcNode::Parameter(p) => p.span,
         }
     }
 }
@@ -53,6 +58,10 @@ impl<'a> AstHost<OxcNode<'a>> for OxcAstHost<'a> {
                 Some(ident.name.to_string())
             }
             OxcNode::Argument(Argument::Identifier(ident)) => Some(ident.name.to_string()),
+            OxcNode::Parameter(param) => match &param.pattern.kind {
+                BindingPatternKind::BindingIdentifier(ident) => Some(ident.name.to_string()),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -251,8 +260,9 @@ impl<'a> AstHost<OxcNode<'a>> for OxcAstHost<'a> {
             _ => None,
         } {
             if is_expr {
+                // Object literals are returned as `() => ({ ... })`.
                 if let Some(Statement::ExpressionStatement(stmt)) = body.statements.first() {
-                    return Ok(OxcNode::Expression(&stmt.expression));
+                    return Ok(OxcNode::Expression(stmt.expression.without_parentheses()));
                 }
                 return Err("Expression body not found in arrow function".to_string());
             }
@@ -281,7 +291,7 @@ impl<'a> AstHost<OxcNode<'a>> for OxcAstHost<'a> {
             for stmt in &body.statements {
                 if let Statement::ReturnStatement(ret) = stmt {
                     if let Some(arg) = &ret.argument {
-                        return Ok(OxcNode::Expression(arg));
+                        return Ok(OxcNode::Expression(arg.without_parentheses()));
                     }
                 }
             }
@@ -291,8 +301,17 @@ impl<'a> AstHost<OxcNode<'a>> for OxcAstHost<'a> {
         }
     }
 
-    fn parse_parameters(&self, _fn_node: &OxcNode<'a>) -> Result<Vec<OxcNode<'a>>, String> {
-        Err("Parameter parsing not implemented".to_string())
+    fn parse_parameters(&self, fn_node: &OxcNode<'a>) -> Result<Vec<OxcNode<'a>>, String> {
+        let params: &'a FormalParameters<'a> = match fn_node {
+            OxcNode::Expression(Expression::FunctionExpression(f)) => &f.params,
+            OxcNode::ArrayElement(ArrayExpressionElement::FunctionExpression(f)) => &f.params,
+            OxcNode::Argument(Argument::FunctionExpression(f)) => &f.params,
+            OxcNode::Expression(Expression::ArrowFunctionExpression(a)) => &a.params,
+            OxcNode::ArrayElement(ArrayExpressionElement::ArrowFunctionExpression(a)) => &a.params,
+            OxcNode::Argument(Argument::ArrowFunctionExpression(a)) => &a.params,
+            _ => return Err("Not a function expression".to_string()),
+        };
+        Ok(params.items.iter().map(OxcNode::Parameter).collect())
     }
 
     fn is_call_expression(&self, node: &OxcNode<'a>) -> bool {
@@ -6,6 +6,9 @@ use angular_compiler::output::output_ast as o;
 /// Trait implemented by all partial linkers (component, directive, etc.).
 pub trait PartialLinker<TExpression: AstNode> {
     /// Links a partial declaration metadata object to a full definition expression.
+    ///
+    /// Fails when the metadata object is not a valid declaration, in which case the
+    /// declaration must be left unlinked.
     fn link_partial_declaration(
         &self,
         constant_pool: &mut ConstantPool,
@@ -15,5 +18,5 @@ pub trait PartialLinker<TExpression: AstNode> {
         target_name: Option<&str>,
         imports: Option<&std::collections::HashMap<String, String>>,
         class_metadata: Option<&AstObject<TExpression>>,
-    ) -> o::Expression;
+    ) -> Result<o::Expression, String>;
 }
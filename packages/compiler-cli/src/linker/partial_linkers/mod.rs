@@ -1,3 +1,5 @@
+pub mod partial_class_metadata_async_linker_1;
+pub mod partial_class_metadata_linker_1;
 pub mod partial_component_linker_2;
 pub mod partial_directive_linker_2;
 pub mod partial_factory_linker_2;
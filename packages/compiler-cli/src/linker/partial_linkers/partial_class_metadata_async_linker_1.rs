@@ -0,0 +1,173 @@
+use crate::linker::ast::AstNode;
+use crate::linker::ast_value::AstObject;
+use crate::linker::partial_linker::PartialLinker;
+use crate::linker::partial_linkers::partial_class_metadata_linker_1::{opaque, optional_opaque};
+use angular_compiler::constant_pool::ConstantPool;
+use angular_compiler::output::output_ast as o;
+use angular_compiler::render3::r3_class_metadata_compiler::{
+    compile_opaque_async_class_metadata, R3ClassMetadata,
+};
+
+/// Links `ɵɵngDeclareClassMetadataAsync` into a `ɵsetClassMetadataAsync` call.
+///
+/// Used for components with `@defer` blocks: the metadata is only set once the
+/// deferred dependencies have been loaded.
+pub struct PartialClassMetadataAsyncLinker1;
+
+impl PartialClassMetadataAsyncLinker1 {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn link<TExpression: AstNode>(
+        &self,
+        meta_obj: &AstObject<TExpression>,
+    ) -> Result<o::Expression, String> {
+        let resolve_metadata = meta_obj.get_value("resolveMetadata")?;
+        if !resolve_metadata.is_function() {
+            return Err("Unsupported `resolveMetadata` value. Expected a function.".to_string());
+        }
+
+        let dependency_names = resolve_metadata
+            .get_function_parameters()?
+            .iter()
+            .map(|param| {
+                param
+                    .get_symbol_name()
+                    .ok_or_else(|| "Unsupported `resolveMetadata` parameter.".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The decorators and constructor/property metadata live in the object
+        // returned by `resolveMetadata`, since they may reference the deferred symbols.
+        let metadata_obj = resolve_metadata.get_function_return_value()?.get_object()?;
+        let meta = R3ClassMetadata {
+            type_: opaque(&meta_obj.get_value("type")?),
+            decorators: opaque(&metadata_obj.get_value("decorators")?),
+            ctor_parameters: optional_opaque(&metadata_obj, "ctorParameters"),
+            prop_decorators: optional_opaque(&metadata_obj, "propDecorators"),
+        };
+        let resolver = opaque(&meta_obj.get_value("resolveDeferredDeps")?);
+
+        Ok(compile_opaque_async_class_metadata(
+            &meta,
+            resolver,
+            &dependency_names,
+        ))
+    }
+}
+
+impl<TExpression: AstNode> PartialLinker<TExpression> for PartialClassMetadataAsyncLinker1 {
+    fn link_partial_declaration(
+        &self,
+        _constant_pool: &mut ConstantPool,
+        meta_obj: &AstObject<TExpression>,
+        _source_url: &str,
+        _version: &str,
+        _target_name: Option<&str>,
+        _imports: Option<&std::collections::HashMap<String, String>>,
+        _class_metadata: Option<&AstObject<TExpression>>,
+    ) -> Result<o::Expression, String> {
+        self.link(meta_obj)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linker::ast_value::AstValue;
+    use crate::linker::oxc_ast_host::{OxcAstHost, OxcNode};
+    use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
+    use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+    use angular_compiler::output::output_ast::ExpressionTrait;
+    use oxc_allocator::Allocator;
+    use oxc_ast::ast::Statement;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    #[test]
+    fn links_async_class_metadata_to_set_class_metadata_async() {
+        let source = "({ minVersion: '18.0.0', version: '21.0.0', ngImport: i0, type: Foo, \
+                      resolveDeferredDeps: () => [import('./a').then(m => m.A)], \
+                      resolveMetadata: A => ({ decorators: [{ type: Component, args: [{ imports: [A] }] }] }) })";
+        let allocator = Allocator::default();
+        let program = Parser::new(&allocator, source, SourceType::mjs())
+            .parse()
+            .program;
+        let Some(Statement::ExpressionStatement(stmt)) = program.body.first() else {
+            panic!("expected an expression statement");
+        };
+        let host = OxcAstHost::new(source);
+        let meta_obj = AstValue::new(
+            OxcNode::Expression(stmt.expression.without_parentheses()),
+            &host,
+        )
+        .get_object()
+        .unwrap();
+
+        let linker = PartialClassMetadataAsyncLinker1::new();
+        let linked = linker
+            .link_partial_declaration(
+                &mut ConstantPool::new(false),
+                &meta_obj,
+                "foo.mjs",
+                "21.0.0",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let mut ctx = EmitterVisitorContext::create_root();
+        linked.visit_expression(&mut AbstractJsEmitterVisitor::new(), &mut ctx);
+        let code = ctx.to_source();
+        assert!(
+            code.contains(
+                "i0.ɵsetClassMetadataAsync(Foo, () => [import('./a').then(m => m.A)], (A) => {"
+            ),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains(
+                "i0.ɵsetClassMetadata(Foo, [{ type: Component, args: [{ imports: [A] }] }], null, null)"
+            ),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn reports_unsupported_resolve_metadata() {
+        let source = "({ type: Foo, resolveDeferredDeps: () => [], resolveMetadata: null })";
+        let allocator = Allocator::default();
+        let program = Parser::new(&allocator, source, SourceType::mjs())
+            .parse()
+            .program;
+        let Some(Statement::ExpressionStatement(stmt)) = program.body.first() else {
+            panic!("expected an expression statement");
+        };
+        let host = OxcAstHost::new(source);
+        let meta_obj = AstValue::new(
+            OxcNode::Expression(stmt.expression.without_parentheses()),
+            &host,
+        )
+        .get_object()
+        .unwrap();
+
+        let linker = PartialClassMetadataAsyncLinker1::new();
+        let linked = linker.link_partial_declaration(
+            &mut ConstantPool::new(false),
+            &meta_obj,
+            "foo.mjs",
+            "21.0.0",
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            linked.err().as_deref(),
+            Some("Unsupported `resolveMetadata` value. Expected a function.")
+        );
+    }
+}
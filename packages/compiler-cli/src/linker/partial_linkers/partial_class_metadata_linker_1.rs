@@ -0,0 +1,125 @@
+use crate::linker::ast::AstNode;
+use crate::linker::ast_value::{AstObject, AstValue};
+use crate::linker::partial_linker::PartialLinker;
+use angular_compiler::constant_pool::ConstantPool;
+use angular_compiler::output::output_ast as o;
+use angular_compiler::render3::r3_class_metadata_compiler::{
+    compile_class_metadata, R3ClassMetadata,
+};
+
+/// Links `ɵɵngDeclareClassMetadata` into a `ɵsetClassMetadata` call.
+///
+/// The call is guarded by `ngDevMode`, so production builds drop it.
+pub struct PartialClassMetadataLinker1;
+
+impl PartialClassMetadataLinker1 {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn to_r3_class_metadata<TExpression: AstNode>(
+        &self,
+        meta_obj: &AstObject<TExpression>,
+    ) -> Result<R3ClassMetadata, String> {
+        Ok(R3ClassMetadata {
+            type_: opaque(&meta_obj.get_value("type")?),
+            decorators: opaque(&meta_obj.get_value("decorators")?),
+            ctor_parameters: optional_opaque(meta_obj, "ctorParameters"),
+            prop_decorators: optional_opaque(meta_obj, "propDecorators"),
+        })
+    }
+}
+
+impl<TExpression: AstNode> PartialLinker<TExpression> for PartialClassMetadataLinker1 {
+    fn link_partial_declaration(
+        &self,
+        _constant_pool: &mut ConstantPool,
+        meta_obj: &AstObject<TExpression>,
+        _source_url: &str,
+        _version: &str,
+        _target_name: Option<&str>,
+        _imports: Option<&std::collections::HashMap<String, String>>,
+        _class_metadata: Option<&AstObject<TExpression>>,
+    ) -> Result<o::Expression, String> {
+        let meta = self.to_r3_class_metadata(meta_obj)?;
+        Ok(compile_class_metadata(&meta))
+    }
+}
+
+/// The source of `value`, passed through to the output unchanged.
+pub(crate) fn opaque<TExpression: AstNode>(value: &AstValue<TExpression>) -> o::Expression {
+    o::Expression::RawCode(o::RawCodeExpr {
+        code: value.print(),
+        source_span: None,
+    })
+}
+
+/// The source of the `key` property of `obj`, if present.
+pub(crate) fn optional_opaque<TExpression: AstNode>(
+    obj: &AstObject<TExpression>,
+    key: &str,
+) -> Option<o::Expression> {
+    obj.get_value(key).ok().map(|value| opaque(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linker::oxc_ast_host::{OxcAstHost, OxcNode};
+    use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
+    use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+    use angular_compiler::output::output_ast::ExpressionTrait;
+    use oxc_allocator::Allocator;
+    use oxc_ast::ast::Statement;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    #[test]
+    fn links_class_metadata_to_set_class_metadata() {
+        let source = "({ minVersion: '12.0.0', version: '21.0.0', ngImport: i0, type: Foo, \
+                      decorators: [{ type: Injectable }], ctorParameters: () => [{ type: Bar }] })";
+        let allocator = Allocator::default();
+        let program = Parser::new(&allocator, source, SourceType::mjs())
+            .parse()
+            .program;
+        let Some(Statement::ExpressionStatement(stmt)) = program.body.first() else {
+            panic!("expected an expression statement");
+        };
+        let host = OxcAstHost::new(source);
+        let meta_obj = AstValue::new(
+            OxcNode::Expression(stmt.expression.without_parentheses()),
+            &host,
+        )
+        .get_object()
+        .unwrap();
+
+        let linker = PartialClassMetadataLinker1::new();
+        let linked = linker
+            .link_partial_declaration(
+                &mut ConstantPool::new(false),
+                &meta_obj,
+                "foo.mjs",
+                "21.0.0",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let mut ctx = EmitterVisitorContext::create_root();
+        linked.visit_expression(&mut AbstractJsEmitterVisitor::new(), &mut ctx);
+        let code = ctx.to_source();
+        assert!(
+            code.contains("(typeof ngDevMode === \"undefined\" || ngDevMode) &&"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains(
+                "i0.ɵsetClassMetadata(Foo, [{ type: Injectable }], () => [{ type: Bar }], null)"
+            ),
+            "{}",
+            code
+        );
+    }
+}
@@ -1167,6 +1167,7 @@ impl PartialComponentLinker2 {
             i18n_use_external_ids: false,
             change_detection,
             relative_template_path: None,
+            enable_template_source_locations: false,
             has_directive_dependencies: false,
             raw_imports: None,
         })
@@ -1183,136 +1184,124 @@ impl<TExpression: AstNode> PartialLinker<TExpression> for PartialComponentLinker
         _target_name: Option<&str>,
         imports: Option<&std::collections::HashMap<String, String>>,
         _class_metadata: Option<&AstObject<TExpression>>,
-    ) -> o::Expression {
+    ) -> Result<o::Expression, String> {
         // TODO: Use source_url to resolve template if needed
         // println!("[LINKER] link_partial_declaration called, source_url: {}", source_url);
-        match self.to_r3_component_metadata(meta_obj, source_url, _target_name, imports) {
-            Ok(meta) => {
-                let _parser = angular_compiler::expression_parser::parser::Parser::new();
-                struct DummySchemaRegistry;
-                impl angular_compiler::schema::element_schema_registry::ElementSchemaRegistry
-                    for DummySchemaRegistry
-                {
-                    fn has_property(
-                        &self,
-                        _tag_name: &str,
-                        _prop_name: &str,
-                        _schemas: &[angular_compiler::core::SchemaMetadata],
-                    ) -> bool {
-                        true
-                    }
-                    fn has_element(
-                        &self,
-                        _tag_name: &str,
-                        _schemas: &[angular_compiler::core::SchemaMetadata],
-                    ) -> bool {
-                        true
-                    }
-                    fn security_context(
-                        &self,
-                        _tag_name: &str,
-                        _prop_name: &str,
-                        _is_attribute: bool,
-                    ) -> angular_compiler::core::SecurityContext {
-                        angular_compiler::core::SecurityContext::NONE
-                    }
-                    fn all_known_element_names(&self) -> Vec<String> {
-                        vec![]
-                    }
-                    fn get_mapped_prop_name(&self, prop_name: &str) -> String {
-                        prop_name.to_string()
-                    }
-                    fn get_default_component_element_name(&self) -> String {
-                        "ng-component".to_string()
-                    }
-                    fn validate_property(
-                        &self,
-                        _name: &str,
-                    ) -> angular_compiler::schema::element_schema_registry::ValidationResult
-                    {
-                        angular_compiler::schema::element_schema_registry::ValidationResult {
-                            error: false,
-                            msg: None,
-                        }
-                    }
-                    fn validate_attribute(
-                        &self,
-                        _name: &str,
-                    ) -> angular_compiler::schema::element_schema_registry::ValidationResult
-                    {
-                        angular_compiler::schema::element_schema_registry::ValidationResult {
-                            error: false,
-                            msg: None,
-                        }
-                    }
-                    fn normalize_animation_style_property(&self, prop_name: &str) -> String {
-                        prop_name.to_string()
-                    }
-                    fn normalize_animation_style_value(
-                        &self,
-                        _camel_case_prop: &str,
-                        _user_provided_prop: &str,
-                        val: &str,
-                    ) -> angular_compiler::schema::element_schema_registry::NormalizationResult
-                    {
-                        angular_compiler::schema::element_schema_registry::NormalizationResult {
-                            error: "".to_string(),
-                            value: val.to_string(),
-                        }
-                    }
+        let meta = self.to_r3_component_metadata(meta_obj, source_url, _target_name, imports)?;
+        let _parser = angular_compiler::expression_parser::parser::Parser::new();
+        struct DummySchemaRegistry;
+        impl angular_compiler::schema::element_schema_registry::ElementSchemaRegistry
+            for DummySchemaRegistry
+        {
+            fn has_property(
+                &self,
+                _tag_name: &str,
+                _prop_name: &str,
+                _schemas: &[angular_compiler::core::SchemaMetadata],
+            ) -> bool {
+                true
+            }
+            fn has_element(
+                &self,
+                _tag_name: &str,
+                _schemas: &[angular_compiler::core::SchemaMetadata],
+            ) -> bool {
+                true
+            }
+            fn security_context(
+                &self,
+                _tag_name: &str,
+                _prop_name: &str,
+                _is_attribute: bool,
+            ) -> angular_compiler::core::SecurityContext {
+                angular_compiler::core::SecurityContext::NONE
+            }
+            fn all_known_element_names(&self) -> Vec<String> {
+                vec![]
+            }
+            fn get_mapped_prop_name(&self, prop_name: &str) -> String {
+                prop_name.to_string()
+            }
+            fn get_default_component_element_name(&self) -> String {
+                "ng-component".to_string()
+            }
+            fn validate_property(
+                &self,
+                _name: &str,
+            ) -> angular_compiler::schema::element_schema_registry::ValidationResult {
+                angular_compiler::schema::element_schema_registry::ValidationResult {
+                    error: false,
+                    msg: None,
                 }
-                let _schema_registry = DummySchemaRegistry;
-
-                let mut binding_parser =
-                    angular_compiler::template_parser::binding_parser::BindingParser::new(
-                        &_parser,
-                        &_schema_registry,
-                        vec![],
-                    );
-
-                eprintln!("[Linker CHECKPOINT] About to call compile_component_from_metadata. Queries count: {}", meta.directive.queries.len());
-                let res =
-                    compile_component_from_metadata(&meta, constant_pool, &mut binding_parser);
-
-                // Merge res.statements into constant_pool. Because compile_component_from_metadata
-                // adds statements (template functions) directly to res.statements while also
-                // populating constant_pool.statements (e.g., _forTrack vars), we need to combine
-                // them into a single scope to avoid duplicate declarations.
-                // Prepend constant_pool.statements before res.statements and emit once.
-                if res.statements.is_empty() && constant_pool.statements.is_empty() {
-                    res.expression
-                } else {
-                    // Combine: constant_pool statements first, then res.statements
-                    let mut all_stmts = std::mem::take(&mut constant_pool.statements);
-                    all_stmts.extend(res.statements);
-                    all_stmts.push(o::Statement::Return(o::ReturnStatement {
-                        value: Box::new(res.expression),
-                        source_span: None,
-                    }));
-
-                    o::Expression::InvokeFn(o::InvokeFunctionExpr {
-                        fn_: Box::new(o::Expression::ArrowFn(o::ArrowFunctionExpr {
-                            params: vec![],
-                            body: o::ArrowFunctionBody::Statements(all_stmts),
-                            type_: None,
-                            source_span: None,
-                        })),
-                        args: vec![],
-                        type_: None,
-                        source_span: None,
-                        pure: false,
-                    })
+            }
+            fn validate_attribute(
+                &self,
+                _name: &str,
+            ) -> angular_compiler::schema::element_schema_registry::ValidationResult {
+                angular_compiler::schema::element_schema_registry::ValidationResult {
+                    error: false,
+                    msg: None,
+                }
+            }
+            fn normalize_animation_style_property(&self, prop_name: &str) -> String {
+                prop_name.to_string()
+            }
+            fn normalize_animation_style_value(
+                &self,
+                _camel_case_prop: &str,
+                _user_provided_prop: &str,
+                val: &str,
+            ) -> angular_compiler::schema::element_schema_registry::NormalizationResult
+            {
+                angular_compiler::schema::element_schema_registry::NormalizationResult {
+                    error: "".to_string(),
+                    value: val.to_string(),
                 }
             }
-            Err(e) => {
-                // Return error expression or panic?
-                // For now, simple error literal
-                o::Expression::Literal(o::LiteralExpr {
-                    value: o::LiteralValue::String(format!("Error: {}", e)),
+        }
+        let _schema_registry = DummySchemaRegistry;
+
+        let mut binding_parser =
+            angular_compiler::template_parser::binding_parser::BindingParser::new(
+                &_parser,
+                &_schema_registry,
+                vec![],
+            );
+
+        eprintln!(
+            "[Linker CHECKPOINT] About to call compile_component_from_metadata. Queries count: {}",
+            meta.directive.queries.len()
+        );
+        let res = compile_component_from_metadata(&meta, constant_pool, &mut binding_parser);
+
+        // Merge res.statements into constant_pool. Because compile_component_from_metadata
+        // adds statements (template functions) directly to res.statements while also
+        // populating constant_pool.statements (e.g., _forTrack vars), we need to combine
+        // them into a single scope to avoid duplicate declarations.
+        // Prepend constant_pool.statements before res.statements and emit once.
+        if res.statements.is_empty() && constant_pool.statements.is_empty() {
+            Ok(res.expression)
+        } else {
+            // Combine: constant_pool statements first, then res.statements
+            let mut all_stmts = std::mem::take(&mut constant_pool.statements);
+            all_stmts.extend(res.statements);
+            all_stmts.push(o::Statement::Return(o::ReturnStatement {
+                value: Box::new(res.expression),
+                source_span: None,
+            }));
+
+            Ok(o::Expression::InvokeFn(o::InvokeFunctionExpr {
+                fn_: Box::new(o::Expression::ArrowFn(o::ArrowFunctionExpr {
+                    params: vec![],
+                    body: o::ArrowFunctionBody::Statements(all_stmts),
                     type_: None,
                     source_span: None,
-                })
-            }
+                })),
+                args: vec![],
+                type_: None,
+                source_span: None,
+                pure: false,
+            }))
         }
     }
 }
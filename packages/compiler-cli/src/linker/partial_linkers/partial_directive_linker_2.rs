@@ -604,100 +604,89 @@ impl<TExpression: AstNode> PartialLinker<TExpression> for PartialDirectiveLinker
         target_name: Option<&str>,
         imports: Option<&std::collections::HashMap<String, String>>,
         class_metadata: Option<&AstObject<TExpression>>,
-    ) -> o::Expression {
-        match self.to_r3_directive_metadata(meta_obj, class_metadata) {
-            Ok(meta) => {
-                let parser = angular_compiler::expression_parser::parser::Parser::new();
-                struct DummySchemaRegistry;
-                impl angular_compiler::schema::element_schema_registry::ElementSchemaRegistry
-                    for DummySchemaRegistry
-                {
-                    fn has_property(
-                        &self,
-                        _tag_name: &str,
-                        _prop_name: &str,
-                        _schemas: &[angular_compiler::core::SchemaMetadata],
-                    ) -> bool {
-                        true
-                    }
-                    fn has_element(
-                        &self,
-                        _tag_name: &str,
-                        _schemas: &[angular_compiler::core::SchemaMetadata],
-                    ) -> bool {
-                        true
-                    }
-                    fn security_context(
-                        &self,
-                        _tag_name: &str,
-                        _prop_name: &str,
-                        _is_attribute: bool,
-                    ) -> angular_compiler::core::SecurityContext {
-                        angular_compiler::core::SecurityContext::NONE
-                    }
-                    fn all_known_element_names(&self) -> Vec<String> {
-                        vec![]
-                    }
-                    fn get_mapped_prop_name(&self, prop_name: &str) -> String {
-                        prop_name.to_string()
-                    }
-                    fn get_default_component_element_name(&self) -> String {
-                        "ng-component".to_string()
-                    }
-                    fn validate_property(
-                        &self,
-                        _name: &str,
-                    ) -> angular_compiler::schema::element_schema_registry::ValidationResult
-                    {
-                        angular_compiler::schema::element_schema_registry::ValidationResult {
-                            error: false,
-                            msg: None,
-                        }
-                    }
-                    fn validate_attribute(
-                        &self,
-                        _name: &str,
-                    ) -> angular_compiler::schema::element_schema_registry::ValidationResult
-                    {
-                        angular_compiler::schema::element_schema_registry::ValidationResult {
-                            error: false,
-                            msg: None,
-                        }
-                    }
-                    fn normalize_animation_style_property(&self, prop_name: &str) -> String {
-                        prop_name.to_string()
-                    }
-                    fn normalize_animation_style_value(
-                        &self,
-                        _camel_case_prop: &str,
-                        _user_provided_prop: &str,
-                        val: &str,
-                    ) -> angular_compiler::schema::element_schema_registry::NormalizationResult
-                    {
-                        angular_compiler::schema::element_schema_registry::NormalizationResult {
-                            error: "".to_string(),
-                            value: val.to_string(),
-                        }
-                    }
+    ) -> Result<o::Expression, String> {
+        let meta = self.to_r3_directive_metadata(meta_obj, class_metadata)?;
+        let parser = angular_compiler::expression_parser::parser::Parser::new();
+        struct DummySchemaRegistry;
+        impl angular_compiler::schema::element_schema_registry::ElementSchemaRegistry
+            for DummySchemaRegistry
+        {
+            fn has_property(
+                &self,
+                _tag_name: &str,
+                _prop_name: &str,
+                _schemas: &[angular_compiler::core::SchemaMetadata],
+            ) -> bool {
+                true
+            }
+            fn has_element(
+                &self,
+                _tag_name: &str,
+                _schemas: &[angular_compiler::core::SchemaMetadata],
+            ) -> bool {
+                true
+            }
+            fn security_context(
+                &self,
+                _tag_name: &str,
+                _prop_name: &str,
+                _is_attribute: bool,
+            ) -> angular_compiler::core::SecurityContext {
+                angular_compiler::core::SecurityContext::NONE
+            }
+            fn all_known_element_names(&self) -> Vec<String> {
+                vec![]
+            }
+            fn get_mapped_prop_name(&self, prop_name: &str) -> String {
+                prop_name.to_string()
+            }
+            fn get_default_component_element_name(&self) -> String {
+                "ng-component".to_string()
+            }
+            fn validate_property(
+                &self,
+                _name: &str,
+            ) -> angular_compiler::schema::element_schema_registry::ValidationResult {
+                angular_compiler::schema::element_schema_registry::ValidationResult {
+                    error: false,
+                    msg: None,
+                }
+            }
+            fn validate_attribute(
+                &self,
+                _name: &str,
+            ) -> angular_compiler::schema::element_schema_registry::ValidationResult {
+                angular_compiler::schema::element_schema_registry::ValidationResult {
+                    error: false,
+                    msg: None,
+                }
+            }
+            fn normalize_animation_style_property(&self, prop_name: &str) -> String {
+                prop_name.to_string()
+            }
+            fn normalize_animation_style_value(
+                &self,
+                _camel_case_prop: &str,
+                _user_provided_prop: &str,
+                val: &str,
+            ) -> angular_compiler::schema::element_schema_registry::NormalizationResult
+            {
+                angular_compiler::schema::element_schema_registry::NormalizationResult {
+                    error: "".to_string(),
+                    value: val.to_string(),
                 }
-                let schema_registry = DummySchemaRegistry;
-
-                let mut binding_parser =
-                    angular_compiler::template_parser::binding_parser::BindingParser::new(
-                        &parser,
-                        &schema_registry,
-                        vec![],
-                    );
-
-                let res =
-                    compile_directive_from_metadata(&meta, constant_pool, &mut binding_parser);
-                res.expression
             }
-            Err(e) => o::Expression::Literal(o::LiteralExpr {
-                value: o::LiteralValue::String(format!("Error: {}", e)),
-                type_: None,
-                source_span: None,
-            }),
         }
+        let schema_registry = DummySchemaRegistry;
+
+        let mut binding_parser =
+            angular_compiler::template_parser::binding_parser::BindingParser::new(
+                &parser,
+                &schema_registry,
+                vec![],
+            );
+
+        let res = compile_directive_from_metadata(&meta, constant_pool, &mut binding_parser);
+        Ok(res.expression)
     }
 }
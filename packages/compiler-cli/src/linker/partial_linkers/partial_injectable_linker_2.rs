@@ -28,18 +28,9 @@ impl<TExpression: AstNode> PartialLinker<TExpression> for PartialInjectableLinke
         _target_name: Option<&str>,
         _imports: Option<&std::collections::HashMap<String, String>>,
         _class_metadata: Option<&AstObject<TExpression>>,
-    ) -> o::Expression {
+    ) -> Result<o::Expression, String> {
         // Extract type
-        let type_expr = match meta_obj.get_value("type") {
-            Ok(v) => v.node,
-            Err(e) => {
-                return o::Expression::Literal(o::LiteralExpr {
-                    value: o::LiteralValue::String(format!("Error: {}", e)),
-                    type_: None,
-                    source_span: None,
-                })
-            }
-        };
+        let type_expr = meta_obj.get_value("type")?.node;
 
         let type_str = meta_obj.host.print_node(&type_expr);
         let wrapped_type = o::Expression::ReadVar(o::ReadVarExpr {
@@ -253,6 +244,6 @@ impl<TExpression: AstNode> PartialLinker<TExpression> for PartialInjectableLinke
             pure: true,
         });
 
-        define_injectable_call
+        Ok(define_injectable_call)
     }
 }
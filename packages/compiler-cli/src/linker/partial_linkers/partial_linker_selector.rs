@@ -1,15 +1,30 @@
 use crate::linker::ast::AstNode;
 use crate::linker::partial_linker::PartialLinker;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
+/// The version stamped into partial declarations by development builds of Angular.
+pub const PLACEHOLDER_VERSION: &str = "0.0.0-PLACEHOLDER";
+
+/// A partial linker together with the declaration versions it can handle.
+struct LinkerRange<'a, TExpression: AstNode> {
+    range: VersionRange,
+    linker: Box<dyn PartialLinker<TExpression> + 'a>,
+}
+
 pub struct PartialLinkerSelector<'a, TExpression: AstNode> {
-    linkers: HashMap<String, Box<dyn PartialLinker<TExpression> + 'a>>,
+    /// For each declaration function, the linkers ordered from oldest to newest.
+    linkers: HashMap<String, Vec<LinkerRange<'a, TExpression>>>,
 }
 
 impl<'a, TExpression: AstNode + 'a> PartialLinkerSelector<'a, TExpression> {
     pub fn new() -> Self {
-        let mut linkers: HashMap<String, Box<dyn PartialLinker<TExpression> + 'a>> = HashMap::new();
+        let mut selector = Self {
+            linkers: HashMap::new(),
+        };
         // Register linkers here
+        use crate::linker::partial_linkers::partial_class_metadata_async_linker_1::PartialClassMetadataAsyncLinker1;
+        use crate::linker::partial_linkers::partial_class_metadata_linker_1::PartialClassMetadataLinker1;
         use crate::linker::partial_linkers::partial_component_linker_2::PartialComponentLinker2;
         use crate::linker::partial_linkers::partial_directive_linker_2::PartialDirectiveLinker2;
         use crate::linker::partial_linkers::partial_factory_linker_2::PartialFactoryLinker2;
@@ -18,85 +33,242 @@ impl<'a, TExpression: AstNode + 'a> PartialLinkerSelector<'a, TExpression> {
         use crate::linker::partial_linkers::partial_ng_module_linker_2::PartialNgModuleLinker2;
         use crate::linker::partial_linkers::partial_pipe_linker_2::PartialPipeLinker2;
 
-        // Note: The specific linkers must satisfy PartialLinker<TExpression>
-        // We assume they are implemented generically.
-        linkers.insert(
-            "ɵɵngDeclareComponent".to_string(),
+        // Every linker handles declarations up to the version of this compiler.
+        selector.register(
+            "ɵɵngDeclareComponent",
             Box::new(PartialComponentLinker2::new()),
         );
-        linkers.insert(
-            "ɵɵngDeclareDirective".to_string(),
+        selector.register(
+            "ɵɵngDeclareDirective",
             Box::new(PartialDirectiveLinker2::new()),
         );
-        linkers.insert(
-            "ɵɵngDeclarePipe".to_string(),
-            Box::new(PartialPipeLinker2::new()),
-        );
-        linkers.insert(
-            "ɵɵngDeclareNgModule".to_string(),
+        selector.register("ɵɵngDeclarePipe", Box::new(PartialPipeLinker2::new()));
+        selector.register(
+            "ɵɵngDeclareNgModule",
             Box::new(PartialNgModuleLinker2::new()),
         );
-        linkers.insert(
-            "ɵɵngDeclareFactory".to_string(),
-            Box::new(PartialFactoryLinker2::new()),
-        );
-        linkers.insert(
-            "ɵɵngDeclareInjectable".to_string(),
+        selector.register("ɵɵngDeclareFactory", Box::new(PartialFactoryLinker2::new()));
+        selector.register(
+            "ɵɵngDeclareInjectable",
             Box::new(PartialInjectableLinker2::new()),
         );
-        linkers.insert(
-            "ɵɵngDeclareInjector".to_string(),
+        selector.register(
+            "ɵɵngDeclareInjector",
             Box::new(PartialInjectorLinker2::new()),
         );
-
-        // Aliases for JIT/Decorator mode
-        linkers.insert(
-            "Component".to_string(),
-            Box::new(PartialComponentLinker2::new()),
-        );
-        linkers.insert(
-            "Directive".to_string(),
-            Box::new(PartialDirectiveLinker2::new()),
+        selector.register(
+            "ɵɵngDeclareClassMetadata",
+            Box::new(PartialClassMetadataLinker1::new()),
         );
-        linkers.insert("Pipe".to_string(), Box::new(PartialPipeLinker2::new()));
-        linkers.insert(
-            "NgModule".to_string(),
-            Box::new(PartialNgModuleLinker2::new()),
-        );
-        linkers.insert(
-            "Injectable".to_string(),
-            Box::new(PartialInjectableLinker2::new()),
+        selector.register(
+            "ɵɵngDeclareClassMetadataAsync",
+            Box::new(PartialClassMetadataAsyncLinker1::new()),
         );
 
-        Self { linkers }
+        // Aliases for JIT/Decorator mode
+        selector.register("Component", Box::new(PartialComponentLinker2::new()));
+        selector.register("Directive", Box::new(PartialDirectiveLinker2::new()));
+        selector.register("Pipe", Box::new(PartialPipeLinker2::new()));
+        selector.register("NgModule", Box::new(PartialNgModuleLinker2::new()));
+        selector.register("Injectable", Box::new(PartialInjectableLinker2::new()));
+
+        selector
+    }
+
+    fn register(&mut self, name: &str, linker: Box<dyn PartialLinker<TExpression> + 'a>) {
+        let range = VersionRange {
+            min: None,
+            max: Version::parse(crate::version::ANGULAR_VERSION).ok(),
+        };
+        self.linkers
+            .entry(name.to_string())
+            .or_default()
+            .push(LinkerRange { range, linker });
+    }
+
+    fn linker_ranges(&self, name: &str) -> Option<&Vec<LinkerRange<'a, TExpression>>> {
+        self.linkers.get(name).or_else(|| {
+            name.rsplit('.')
+                .next()
+                .and_then(|suffix| self.linkers.get(suffix))
+        })
     }
 
     pub fn supports_declaration(&self, name: &str) -> bool {
-        if self.linkers.contains_key(name) {
-            return true;
-        }
-        if let Some(suffix) = name.split('.').last() {
-            return self.linkers.contains_key(suffix);
-        }
-        false
+        self.linker_ranges(name).is_some()
     }
 
+    /// Select the linker for a declaration of `name` that was published with Angular
+    /// `version` and needs at least Angular `min_version` to be linked.
+    ///
+    /// Returns an error if no registered linker is recent enough.
     pub fn get_linker(
         &self,
         name: &str,
-        _min_version: &str,
-        _version: &str,
-    ) -> &dyn PartialLinker<TExpression> {
-        if let Some(linker) = self.linkers.get(name) {
-            return linker.as_ref();
+        min_version: &str,
+        version: &str,
+    ) -> Result<&dyn PartialLinker<TExpression>, String> {
+        let ranges = self
+            .linker_ranges(name)
+            .ok_or_else(|| format!("Unknown partial declaration function {}.", name))?;
+        let latest = ranges
+            .last()
+            .ok_or_else(|| format!("No linker registered for {}.", name))?;
+
+        // Declarations built from the Angular sources themselves always use the
+        // newest linker.
+        if version == PLACEHOLDER_VERSION {
+            return Ok(latest.linker.as_ref());
         }
 
-        if let Some(suffix) = name.split('.').last() {
-            if let Some(linker) = self.linkers.get(suffix) {
-                return linker.as_ref();
+        let declaration_range = VersionRange::at_least(min_version)?;
+        for LinkerRange { range, linker } in ranges {
+            if declaration_range.intersects(range) {
+                return Ok(linker.as_ref());
             }
         }
 
-        panic!("Linker for {} not found", name)
+        Err(format!(
+            "This application depends upon a library published using Angular version {}, \
+             which requires Angular version {} or newer to work correctly.\n\
+             Consider upgrading your application to use a more recent version of Angular.",
+            version, min_version
+        ))
+    }
+}
+
+/// A `major.minor.patch` version, ignoring any prerelease or build suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl Version {
+    fn parse(version: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid version \"{}\" in partial declaration.", version);
+        let core = version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default();
+        let parts = core
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        match parts[..] {
+            [major, minor, patch] => Ok(Self {
+                major,
+                minor,
+                patch,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// An inclusive range of versions; a missing bound is unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VersionRange {
+    min: Option<Version>,
+    max: Option<Version>,
+}
+
+impl VersionRange {
+    /// `>=version`. The placeholder version accepts any version.
+    fn at_least(version: &str) -> Result<Self, String> {
+        let min = if version == PLACEHOLDER_VERSION {
+            None
+        } else {
+            Some(Version::parse(version)?)
+        };
+        Ok(Self { min, max: None })
+    }
+
+    fn intersects(&self, other: &VersionRange) -> bool {
+        let below = |min: Option<Version>, max: Option<Version>| match (min, max) {
+            (Some(min), Some(max)) => min.cmp(&max) == Ordering::Greater,
+            _ => false,
+        };
+        !below(self.min, other.max) && !below(other.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linker::oxc_ast_host::OxcNode;
+
+    fn selector() -> PartialLinkerSelector<'static, OxcNode<'static>> {
+        PartialLinkerSelector::new()
+    }
+
+    #[test]
+    fn parses_versions_with_prerelease_suffixes() {
+        assert_eq!(
+            Version::parse("17.1.0-next.2").unwrap(),
+            Version {
+                major: 17,
+                minor: 1,
+                patch: 0
+            }
+        );
+        assert!(Version::parse("17.1").is_err());
+        assert!(Version::parse("latest").is_err());
+    }
+
+    #[test]
+    fn selects_linker_for_supported_versions() {
+        let selector = selector();
+        assert!(selector
+            .get_linker("ɵɵngDeclareComponent", "14.0.0", "17.3.1")
+            .is_ok());
+        assert!(selector
+            .get_linker(
+                "i0.ɵɵngDeclarePipe",
+                crate::version::ANGULAR_VERSION,
+                "21.0.0-rc.0"
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn placeholder_version_uses_newest_linker() {
+        let selector = selector();
+        assert!(selector
+            .get_linker("ɵɵngDeclareDirective", "999.0.0", PLACEHOLDER_VERSION)
+            .is_ok());
+        assert!(selector
+            .get_linker("ɵɵngDeclareDirective", PLACEHOLDER_VERSION, "15.0.0")
+            .is_ok());
+    }
+
+    #[test]
+    fn reports_declarations_requiring_a_newer_linker() {
+        let selector = selector();
+        let err = selector
+            .get_linker("ɵɵngDeclareComponent", "999.0.0", "999.1.0")
+            .err()
+            .unwrap();
+        assert!(
+            err.contains("published using Angular version 999.1.0"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("requires Angular version 999.0.0 or newer"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn reports_invalid_versions() {
+        let selector = selector();
+        assert!(selector
+            .get_linker("ɵɵngDeclareFactory", "not-a-version", "1.0.0")
+            .is_err());
     }
 }
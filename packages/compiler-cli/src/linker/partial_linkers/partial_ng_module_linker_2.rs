@@ -119,41 +119,33 @@ impl<TExpression: AstNode> PartialLinker<TExpression> for PartialNgModuleLinker2
         _target_name: Option<&str>,
         _imports: Option<&std::collections::HashMap<String, String>>,
         _class_metadata: Option<&AstObject<TExpression>>,
-    ) -> o::Expression {
-        match self.to_r3_ng_module_metadata(meta_obj) {
-            Ok(meta) => {
-                let res = compile_ng_module(&meta);
-                if !res.statements.is_empty() {
-                    // Wrap in IIFE if there are statements
-                    // (function() { statements; return expression; })()
-                    let mut stmts = res.statements;
-                    stmts.push(o::Statement::Return(o::ReturnStatement {
-                        value: Box::new(res.expression),
-                        source_span: None,
-                    }));
-
-                    o::Expression::InvokeFn(o::InvokeFunctionExpr {
-                        fn_: Box::new(o::Expression::Fn(o::FunctionExpr {
-                            params: vec![],
-                            statements: stmts,
-                            type_: None,
-                            source_span: None,
-                            name: None,
-                        })),
-                        args: vec![],
-                        type_: None,
-                        source_span: None,
-                        pure: false,
-                    })
-                } else {
-                    res.expression
-                }
-            }
-            Err(e) => o::Expression::Literal(o::LiteralExpr {
-                value: o::LiteralValue::String(format!("Error: {}", e)),
+    ) -> Result<o::Expression, String> {
+        let meta = self.to_r3_ng_module_metadata(meta_obj)?;
+        let res = compile_ng_module(&meta);
+        if !res.statements.is_empty() {
+            // Wrap in IIFE if there are statements
+            // (function() { statements; return expression; })()
+            let mut stmts = res.statements;
+            stmts.push(o::Statement::Return(o::ReturnStatement {
+                value: Box::new(res.expression),
+                source_span: None,
+            }));
+
+            Ok(o::Expression::InvokeFn(o::InvokeFunctionExpr {
+                fn_: Box::new(o::Expression::Fn(o::FunctionExpr {
+                    params: vec![],
+                    statements: stmts,
+                    type_: None,
+                    source_span: None,
+                    name: None,
+                })),
+                args: vec![],
                 type_: None,
                 source_span: None,
-            }),
+                pure: false,
+            }))
+        } else {
+            Ok(res.expression)
         }
     }
 }
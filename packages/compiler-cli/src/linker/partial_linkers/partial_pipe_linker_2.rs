@@ -68,17 +68,8 @@ impl<TExpression: AstNode> PartialLinker<TExpression> for PartialPipeLinker2 {
         _target_name: Option<&str>,
         _imports: Option<&std::collections::HashMap<String, String>>,
         _class_metadata: Option<&AstObject<TExpression>>,
-    ) -> o::Expression {
-        match self.to_r3_pipe_metadata(meta_obj) {
-            Ok(meta) => {
-                let res = compile_pipe_from_metadata(&meta);
-                res.expression
-            }
-            Err(e) => o::Expression::Literal(o::LiteralExpr {
-                value: o::LiteralValue::String(format!("Error: {}", e)),
-                type_: None,
-                source_span: None,
-            }),
-        }
+    ) -> Result<o::Expression, String> {
+        let meta = self.to_r3_pipe_metadata(meta_obj)?;
+        Ok(compile_pipe_from_metadata(&meta).expression)
     }
 }
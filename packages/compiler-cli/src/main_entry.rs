@@ -6,6 +6,7 @@
 
 use std::collections::HashSet;
 
+use crate::config::tsconfig::overlay_options;
 use crate::perform_compile::{
     exit_code_from_result, format_diagnostics, perform_compilation, read_configuration,
     CompilationResult, EmitFlags, ParsedConfiguration, Program,
@@ -135,7 +136,7 @@ where
     }
 
     if watch {
-        let result = watch_mode(&project, &options, &console_error);
+        let result = watch_mode(&project, &command_line_options(args), &console_error);
         return report_errors_and_exit(
             &result.first_compile_result,
             Some(&options),
@@ -155,6 +156,7 @@ where
         old_program,
         custom_transformers,
         modified_resource_files,
+        None,
     );
 
     if let Some(pr) = program_reuse {
@@ -198,6 +200,7 @@ pub fn main_diagnostics_for_test(
         old_program,
         None,
         modified_resource_files,
+        None,
     );
 
     if let Some(pr) = program_reuse {
@@ -212,27 +215,12 @@ pub fn main_diagnostics_for_test(
 
 /// Read ngc command line arguments and configuration.
 pub fn read_ngc_command_line_and_configuration(args: &[String]) -> NgcParsedConfiguration {
-    let mut options = CompilerOptions::default();
-
     // Parse command line arguments
     let parsed_args = parse_ngc_args(args);
 
-    if let Some(i18n_file) = &parsed_args.i18n_file {
-        options.i18n_in_file = Some(i18n_file.clone());
-    }
-    if let Some(i18n_format) = &parsed_args.i18n_format {
-        options.i18n_in_format = Some(i18n_format.clone());
-    }
-    if let Some(locale) = &parsed_args.locale {
-        options.i18n_in_locale = Some(locale.clone());
-    }
-    if let Some(missing_translation) = &parsed_args.missing_translation {
-        options.i18n_in_missing_translations = Some(missing_translation.clone());
-    }
-
     let config = read_command_line_and_configuration(
         args,
-        Some(options),
+        Some(ngc_options(&parsed_args)),
         &[
             "i18nFile",
             "i18nFormat",
@@ -252,6 +240,25 @@ pub fn read_ngc_command_line_and_configuration(args: &[String]) -> NgcParsedConf
     }
 }
 
+/// The compiler options given on the command line, which take precedence over the
+/// tsconfig.
+pub fn command_line_options(args: &[String]) -> CompilerOptions {
+    let mut options = parse_command_line(args).options;
+    overlay_options(&mut options, &ngc_options(&parse_ngc_args(args)));
+    options
+}
+
+/// The compiler options set by the ngc-specific arguments.
+fn ngc_options(parsed_args: &NgcArgs) -> CompilerOptions {
+    CompilerOptions {
+        i18n_in_file: parsed_args.i18n_file.clone(),
+        i18n_in_format: parsed_args.i18n_format.clone(),
+        i18n_in_locale: parsed_args.locale.clone(),
+        i18n_in_missing_translations: parsed_args.missing_translation.clone(),
+        ..CompilerOptions::default()
+    }
+}
+
 /// Parsed ngc command line arguments.
 #[derive(Debug, Default)]
 struct NgcArgs {
@@ -338,7 +345,7 @@ pub fn read_command_line_and_configuration(
 
     // Merge with existing options
     if let Some(existing) = existing_options {
-        merge_options(&mut options, &existing);
+        overlay_options(&mut options, &existing);
     }
 
     // Handle locale
@@ -368,36 +375,80 @@ fn parse_command_line(args: &[String]) -> CommandLineConfig {
     let mut result = CommandLineConfig::default();
     let mut i = 0;
     while i < args.len() {
-        let arg = &args[i];
-        if arg == "-p" || arg == "--project" {
-            if i + 1 < args.len() {
-                result.project = Some(args[i + 1].clone());
-                i += 1;
+        // A value is either the next argument or attached as `--name=value`.
+        let (name, inline_value) = match args[i].split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (args[i].as_str(), None),
+        };
+        let attached = inline_value.is_some();
+        let value = inline_value.or_else(|| args.get(i + 1).cloned());
+        let mut consumed = false;
+        match name {
+            "-p" | "--project" => {
+                if value.is_some() {
+                    result.project = value;
+                    consumed = true;
+                }
+            }
+            "--outDir" => {
+                if value.is_some() {
+                    result.options.out_dir = value;
+                    consumed = true;
+                }
+            }
+            "--rootDir" => {
+                if value.is_some() {
+                    result.options.root_dir = value;
+                    consumed = true;
+                }
+            }
+            "--baseUrl" => {
+                if value.is_some() {
+                    result.options.base_url = value;
+                    consumed = true;
+                }
+            }
+            "--sourceMap" => {
+                result.options.source_map = Some(boolean_value(value.as_deref(), &mut consumed))
+            }
+            "--inlineSourceMap" => {
+                result.options.inline_source_map =
+                    Some(boolean_value(value.as_deref(), &mut consumed))
             }
+            "--inlineSources" => {
+                result.options.inline_sources = Some(boolean_value(value.as_deref(), &mut consumed))
+            }
+            "--declaration" | "-d" => {
+                result.options.declaration = Some(boolean_value(value.as_deref(), &mut consumed))
+            }
+            _ => {}
+        }
+        if consumed && !attached {
+            i += 1;
         }
-        // Add more options as needed
         i += 1;
     }
     result
 }
 
-/// Merge two compiler options, preferring the second.
-fn merge_options(target: &mut CompilerOptions, source: &CompilerOptions) {
-    if source.i18n_in_file.is_some() {
-        target.i18n_in_file = source.i18n_in_file.clone();
-    }
-    if source.i18n_in_format.is_some() {
-        target.i18n_in_format = source.i18n_in_format.clone();
-    }
-    if source.i18n_in_locale.is_some() {
-        target.i18n_in_locale = source.i18n_in_locale.clone();
-    }
-    if source.i18n_in_missing_translations.is_some() {
-        target.i18n_in_missing_translations = source.i18n_in_missing_translations.clone();
+/// The value of a boolean flag. As with `tsc`, a flag followed by `true` or `false`
+/// takes that value, and a bare flag is `true`.
+fn boolean_value(value: Option<&str>, consumed: &mut bool) -> bool {
+    match value {
+        Some("true") => {
+            *consumed = true;
+            true
+        }
+        Some("false") => {
+            *consumed = true;
+            false
+        }
+        _ => true,
     }
 }
 
-/// Run in watch mode.
+/// Run in watch mode. `options` are the options given on the command line, which are
+/// layered over the tsconfig every time it is read.
 pub fn watch_mode<F>(project: &str, options: &CompilerOptions, console_error: &F) -> WatchResult
 where
     F: Fn(&str),
@@ -491,6 +542,45 @@ mod tests {
         assert_eq!(result.locale, Some("en-US".to_string()));
     }
 
+    #[test]
+    fn test_parse_command_line_options() {
+        let args = vec![
+            "-p".to_string(),
+            "tsconfig.app.json".to_string(),
+            "--outDir".to_string(),
+            "out".to_string(),
+            "--sourceMap".to_string(),
+        ];
+        let result = parse_command_line(&args);
+        assert_eq!(result.project, Some("tsconfig.app.json".to_string()));
+        assert_eq!(result.options.out_dir, Some("out".to_string()));
+        assert_eq!(result.options.source_map, Some(true));
+        assert_eq!(result.options.declaration, None);
+    }
+
+    #[test]
+    fn test_parse_command_line_boolean_values() {
+        let args: Vec<String> = [
+            "--sourceMap",
+            "false",
+            "--declaration=false",
+            "--inlineSources",
+            "true",
+            "--inlineSourceMap",
+            "-p",
+            "tsconfig.lib.json",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let result = parse_command_line(&args);
+        assert_eq!(result.options.source_map, Some(false));
+        assert_eq!(result.options.declaration, Some(false));
+        assert_eq!(result.options.inline_sources, Some(true));
+        assert_eq!(result.options.inline_source_map, Some(true));
+        assert_eq!(result.project, Some("tsconfig.lib.json".to_string()));
+    }
+
     #[test]
     fn test_format_diagnostics_host() {
         let host = FormatDiagnosticsHost::new(None);
@@ -0,0 +1,234 @@
+// Declaration Types
+//
+// Types of the static Ivy fields (`ɵfac`, `ɵdir`, `ɵcmp`, `ɵmod`, ...) as they are
+// written to `.d.ts` files. Corresponds to `createDirectiveType`, `createFactoryType`
+// and friends in the TypeScript compiler, emitted directly as TypeScript text.
+
+use crate::ngtsc::metadata::src::api::ConstructorParam;
+use crate::ngtsc::metadata::{DirectiveMeta, NgModuleMeta};
+
+/// `i0.ɵɵFactoryDeclaration<T, CtorDeps>`.
+///
+/// `CtorDeps` records the parameter decorators (`@Attribute`, `@Optional`, ...) of the
+/// constructor, or is `never` if there are none.
+pub fn factory_declaration_type(class_name: &str, ctor_params: &[ConstructorParam]) -> String {
+    let deps: Vec<String> = ctor_params.iter().map(ctor_dep_type).collect();
+    let deps_type = if deps.iter().all(|d| d == "null") {
+        "never".to_string()
+    } else {
+        format!("[{}]", deps.join(", "))
+    };
+    format!("i0.ɵɵFactoryDeclaration<{}, {}>", class_name, deps_type)
+}
+
+fn ctor_dep_type(param: &ConstructorParam) -> String {
+    let mut entries = Vec::new();
+    if let Some(attribute) = &param.attribute {
+        entries.push(format!("attribute: {};", string_literal_type(attribute)));
+    }
+    for (flag, name) in [
+        (param.optional, "optional"),
+        (param.host, "host"),
+        (param.self_, "self"),
+        (param.skip_self, "skipSelf"),
+    ] {
+        if flag {
+            entries.push(format!("{}: true;", name));
+        }
+    }
+    if entries.is_empty() {
+        "null".to_string()
+    } else {
+        format!("{{ {} }}", entries.join(" "))
+    }
+}
+
+/// `i0.ɵɵDirectiveDeclaration<...>` or `i0.ɵɵComponentDeclaration<...>`, depending on
+/// whether `dir` is a component.
+pub fn directive_declaration_type(dir: &DirectiveMeta) -> String {
+    let declaration = if dir.t2.is_component {
+        "ɵɵComponentDeclaration"
+    } else {
+        "ɵɵDirectiveDeclaration"
+    };
+
+    let selector = dir
+        .t2
+        .selector
+        .as_deref()
+        .map(|s| string_literal_type(&s.replace('\n', "")))
+        .unwrap_or_else(|| "never".to_string());
+    let export_as = dir
+        .t2
+        .export_as
+        .as_deref()
+        .map(string_tuple_type)
+        .unwrap_or_else(|| "never".to_string());
+
+    let inputs: Vec<String> = dir
+        .t2
+        .inputs
+        .iter()
+        .map(|(name, input)| {
+            let signal = if input.is_signal {
+                " \"isSignal\": true;"
+            } else {
+                ""
+            };
+            format!(
+                "{}: {{ \"alias\": {}; \"required\": {};{} }};",
+                string_literal_type(name),
+                string_literal_type(&input.binding_property_name),
+                input.required,
+                signal
+            )
+        })
+        .collect();
+    let outputs: Vec<String> = dir
+        .t2
+        .outputs
+        .iter()
+        .map(|(name, output)| {
+            format!(
+                "{}: {};",
+                string_literal_type(name),
+                string_literal_type(&output.binding_property_name)
+            )
+        })
+        .collect();
+
+    let query_fields: Vec<String> = dir
+        .queries
+        .iter()
+        .map(|q| q.property_name.clone())
+        .collect();
+    let ng_content_selectors = match (&dir.t2.ng_content_selectors, dir.t2.is_component) {
+        (Some(selectors), true) => string_tuple_type(selectors),
+        _ => "never".to_string(),
+    };
+
+    let mut params = vec![
+        dir.t2.name.clone(),
+        selector,
+        export_as,
+        object_type(&inputs),
+        object_type(&outputs),
+        string_tuple_type(&query_fields),
+        ng_content_selectors,
+        dir.is_standalone.to_string(),
+        // Host directive references are not tracked in the metadata yet.
+        "never".to_string(),
+    ];
+    if dir.is_signal {
+        params.push("true".to_string());
+    }
+
+    format!("i0.{}<{}>", declaration, params.join(", "))
+}
+
+/// `i0.ɵɵNgModuleDeclaration<T, Declarations, Imports, Exports>`.
+pub fn ng_module_declaration_type(ngm: &NgModuleMeta) -> String {
+    format!(
+        "i0.ɵɵNgModuleDeclaration<{}, {}, {}, {}>",
+        ngm.name,
+        type_query_tuple(&ngm.declarations),
+        type_query_tuple(&ngm.imports),
+        type_query_tuple(&ngm.exports)
+    )
+}
+
+/// `[typeof A, typeof B]` for a list of references, or `never` if it is empty.
+fn type_query_tuple(references: &[String]) -> String {
+    let types: Vec<String> = references
+        .iter()
+        .filter_map(|r| reference_type_name(r))
+        .map(|name| format!("typeof {}", name))
+        .collect();
+    if types.is_empty() {
+        "never".to_string()
+    } else {
+        format!("[{}]", types.join(", "))
+    }
+}
+
+/// The class a module reference resolves to. `ModuleWithProviders` calls such as
+/// `RouterModule.forRoot(routes)` resolve to their receiver.
+fn reference_type_name(reference: &str) -> Option<&str> {
+    let reference = reference.trim();
+    let name = match reference.find('(') {
+        Some(paren) => reference[..paren].rsplit_once('.')?.0,
+        None => reference,
+    };
+    let is_path = !name.is_empty()
+        && name.split('.').all(|part| {
+            !part.is_empty()
+                && !part.starts_with(|c: char| c.is_ascii_digit())
+                && part
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        });
+    is_path.then_some(name)
+}
+
+fn string_literal_type(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
+fn string_tuple_type(values: &[String]) -> String {
+    if values.is_empty() {
+        return "never".to_string();
+    }
+    let items: Vec<String> = values.iter().map(|v| string_literal_type(v)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn object_type(entries: &[String]) -> String {
+    if entries.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{ {} }}", entries.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factory_type_without_decorated_params() {
+        assert_eq!(
+            factory_declaration_type("Foo", &[]),
+            "i0.ɵɵFactoryDeclaration<Foo, never>"
+        );
+    }
+
+    #[test]
+    fn factory_type_with_decorated_params() {
+        let params = vec![
+            ConstructorParam::default(),
+            ConstructorParam {
+                optional: true,
+                attribute: Some("title".to_string()),
+                ..ConstructorParam::default()
+            },
+        ];
+        assert_eq!(
+            factory_declaration_type("Foo", &params),
+            "i0.ɵɵFactoryDeclaration<Foo, [null, { attribute: \"title\"; optional: true; }]>"
+        );
+    }
+
+    #[test]
+    fn module_references_resolve_to_classes() {
+        let refs = vec![
+            "CommonModule".to_string(),
+            "RouterModule.forRoot(routes)".to_string(),
+            "[...SHARED]".to_string(),
+        ];
+        assert_eq!(
+            type_query_tuple(&refs),
+            "[typeof CommonModule, typeof RouterModule]"
+        );
+        assert_eq!(type_query_tuple(&[]), "never");
+    }
+}
@@ -2,6 +2,21 @@
 //
 // Functions for analyzing constructor dependencies and injection tokens.
 
+use crate::ngtsc::diagnostics::{Diagnostic, DiagnosticCategory, ErrorCode};
+use crate::ngtsc::metadata::src::api::ConstructorParam;
+use crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager;
+use angular_compiler::output::output_ast::{
+    Expression, ExternalExpr, ExternalReference, LiteralExpr, LiteralValue, ReadPropExpr,
+    ReadVarExpr,
+};
+use angular_compiler::render3::r3_factory::{
+    DepsOrInvalid, R3DependencyMetadata as FactoryDependency,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+const ANGULAR_CORE: &str = "@angular/core";
+
 /// Represents a constructor dependency metadata.
 #[derive(Debug, Clone)]
 pub struct R3DependencyMetadata {
@@ -267,3 +282,179 @@ pub fn get_valid_constructor_dependencies(
 ) -> Option<Vec<R3DependencyMetadata>> {
     unwrap_constructor_dependencies(get_constructor_dependencies(constructor_params, is_core))
 }
+
+/// Resolve the constructor parameters of `class_name` to the dependencies of its factory.
+///
+/// Parameters injected by type reference imported types through the namespace import
+/// registered in `import_manager`. A parameter without an injection token makes the
+/// factory invalid and produces an NG2003 diagnostic for it.
+pub fn resolve_constructor_deps(
+    class_name: &str,
+    constructor_params: &[ConstructorParam],
+    file_imports: Option<&HashMap<String, String>>,
+    source_file: Option<&Path>,
+    is_core: bool,
+    import_manager: &mut EmitterImportManager,
+) -> (DepsOrInvalid, Vec<Diagnostic>) {
+    let ctor_params: Vec<CtorParameter> = constructor_params.iter().map(ctor_parameter).collect();
+
+    match get_constructor_dependencies(&ctor_params, is_core) {
+        Some(ConstructorDeps::Invalid(errors)) => {
+            let diagnostics = errors
+                .iter()
+                .map(|error| {
+                    let (start, length) = constructor_params[error.index].span.unwrap_or((0, 0));
+                    Diagnostic {
+                        category: DiagnosticCategory::Error,
+                        code: ErrorCode::ParamMissingToken as i32,
+                        file: source_file.map(|path| path.to_string_lossy().into_owned()),
+                        start,
+                        length,
+                        message_text: format!(
+                            "No suitable injection token for parameter '{}' of class '{}'.\n  \
+                             Consider adding a type to the parameter or using the @Inject \
+                             decorator to specify an injection token.",
+                            error.name.as_deref().unwrap_or("?"),
+                            class_name
+                        )
+                        .into(),
+                        related_information: None,
+                    }
+                })
+                .collect();
+            (DepsOrInvalid::Invalid, diagnostics)
+        }
+        Some(ConstructorDeps::Valid(deps)) => {
+            let empty_imports = HashMap::new();
+            let file_imports = file_imports.unwrap_or(&empty_imports);
+            let deps = deps
+                .iter()
+                .zip(constructor_params)
+                .map(|(dep, param)| {
+                    let (token, attribute_name_type) = match dep.resolved {
+                        R3ResolvedDependencyType::Attribute => {
+                            let attribute = string_literal(&dep.token);
+                            (attribute.clone(), Some(attribute))
+                        }
+                        _ if param.inject_token.is_some() => {
+                            (inject_token_expression(&dep.token), None)
+                        }
+                        _ => (
+                            type_token_expression(&dep.token, param, file_imports, import_manager),
+                            None,
+                        ),
+                    };
+                    FactoryDependency {
+                        token: Some(token),
+                        attribute_name_type,
+                        host: dep.host,
+                        optional: dep.optional,
+                        self_: dep.self_,
+                        skip_self: dep.skip_self,
+                    }
+                })
+                .collect();
+            (DepsOrInvalid::Valid(deps), vec![])
+        }
+        None => (DepsOrInvalid::Valid(vec![]), vec![]),
+    }
+}
+
+/// Convert the extracted constructor parameter to the form analyzed by
+/// [`get_constructor_dependencies`]. The DI decorators are only recognized by the
+/// metadata extraction when they come from `@angular/core`.
+fn ctor_parameter(param: &ConstructorParam) -> CtorParameter {
+    let decorator = |name: &str, args: Vec<String>| ParameterDecorator {
+        name: name.to_string(),
+        args,
+        from_module: Some(ANGULAR_CORE.to_string()),
+    };
+
+    let mut decorators = Vec::new();
+    if let Some(token) = &param.inject_token {
+        decorators.push(decorator(INJECT_DECORATOR, vec![token.clone()]));
+    }
+    if let Some(attribute) = &param.attribute {
+        decorators.push(decorator(ATTRIBUTE_DECORATOR, vec![attribute.clone()]));
+    }
+    for (flag, name) in [
+        (param.optional, OPTIONAL_DECORATOR),
+        (param.self_, SELF_DECORATOR),
+        (param.skip_self, SKIP_SELF_DECORATOR),
+        (param.host, HOST_DECORATOR),
+    ] {
+        if flag {
+            decorators.push(decorator(name, vec![]));
+        }
+    }
+
+    CtorParameter {
+        name: param.name.clone(),
+        type_token: param.type_name.clone(),
+        decorators,
+    }
+}
+
+/// The token of a parameter injected by type. Imported types are referenced through a
+/// namespace import of their module, since the original import only serves as a type
+/// and may be elided.
+fn type_token_expression(
+    type_name: &str,
+    param: &ConstructorParam,
+    file_imports: &HashMap<String, String>,
+    import_manager: &mut EmitterImportManager,
+) -> Expression {
+    let module = file_imports
+        .get(type_name)
+        .cloned()
+        .or_else(|| param.from_module.clone());
+    match module {
+        Some(module) => {
+            let _ = import_manager.get_or_generate_alias(&module);
+            Expression::External(ExternalExpr {
+                value: ExternalReference {
+                    module_name: Some(module),
+                    name: Some(type_name.to_string()),
+                    runtime: None,
+                },
+                type_: None,
+                source_span: None,
+            })
+        }
+        None => read_var(type_name),
+    }
+}
+
+/// The token passed to `@Inject()`. The decorator argument is a value reference, so it
+/// is emitted as written: a quoted string, an identifier or a property access.
+fn inject_token_expression(token: &str) -> Expression {
+    if let Ok(value) = serde_json::from_str::<String>(token) {
+        return string_literal(&value);
+    }
+    let mut segments = token.split('.');
+    let root = read_var(segments.next().unwrap_or(token));
+    segments.fold(root, |receiver, name| {
+        Expression::ReadProp(ReadPropExpr {
+            receiver: Box::new(receiver),
+            name: name.to_string(),
+            type_: None,
+            source_span: None,
+        })
+    })
+}
+
+fn read_var(name: &str) -> Expression {
+    Expression::ReadVar(ReadVarExpr {
+        name: name.to_string(),
+        type_: None,
+        source_span: None,
+    })
+}
+
+fn string_literal(value: &str) -> Expression {
+    Expression::Literal(LiteralExpr {
+        value: LiteralValue::String(value.to_string()),
+        type_: None,
+        source_span: None,
+    })
+}
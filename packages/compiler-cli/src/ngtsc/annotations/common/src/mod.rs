@@ -2,6 +2,7 @@
 
 pub mod api;
 pub mod debug_info;
+pub mod declaration_types;
 pub mod di;
 pub mod diagnostics;
 pub mod evaluation;
@@ -17,8 +18,11 @@ pub mod util;
 // Re-exports
 pub use api::{NoopResourceLoader, ResourceLoader, ResourceLoaderContext, ResourceType};
 pub use debug_info::{extract_class_debug_info, R3ClassDebugInfo};
+pub use declaration_types::{
+    directive_declaration_type, factory_declaration_type, ng_module_declaration_type,
+};
 pub use di::{
-    get_constructor_dependencies, get_valid_constructor_dependencies,
+    get_constructor_dependencies, get_valid_constructor_dependencies, resolve_constructor_deps,
     unwrap_constructor_dependencies, ConstructorDepError, ConstructorDeps, CtorParameter,
     ParameterDecorator, R3DependencyMetadata, R3ResolvedDependencyType, UnavailableValueKind,
 };
@@ -1,29 +1,58 @@
+use crate::ngtsc::annotations::common::src::{
+    directive_declaration_type, factory_declaration_type,
+};
+use crate::ngtsc::diagnostics::ErrorCode;
+use crate::ngtsc::metadata::src::api::TemplateGuardType;
 use crate::ngtsc::metadata::{
-    extract_directive_metadata, DecoratorMetadata, DirectiveMetadata, ModuleMetadataReader,
+    extract_directive_metadata, ComponentMetadata, DecoratorMetadata, DirectiveMeta,
+    DirectiveMetadata, ModuleMetadataReader,
+};
+use crate::ngtsc::reflection::{
+    ClassDeclaration, ClassMemberKind, ReflectionHost, TypeScriptReflectionHost,
 };
-use crate::ngtsc::reflection::{ClassDeclaration, ReflectionHost, TypeScriptReflectionHost};
+use crate::ngtsc::sourcemaps::{decode_mappings, encode_mappings, MappingSegment, SourceMap};
 use crate::ngtsc::transform::src::api::{
-    AnalysisOutput, CompileResult, ConstantPool, DecoratorHandler, DetectResult, HandlerPrecedence,
+    AnalysisOutput, CompilationMode, CompileResult, ConstantPool, DecoratorHandler, DetectResult,
+    HandlerPrecedence,
 };
+use crate::ngtsc::typecheck::extended::{visit_template, ComponentMembers, TemplateNode};
+use crate::ngtsc::typecheck::{
+    TemplateGuard, TemplateGuardKind, TypeCheckBlockMetadata, TypeCheckImport, TypeCheckInput,
+    TypeCheckInputs, TypeCheckOutput, TypeCheckOutputs, TypeCheckableDirectiveMeta,
+};
+use crate::ngtsc::xi18n::Xi18nContext;
 use angular_compiler::core::ViewEncapsulation;
+use angular_compiler::i18n::{merge_translations, TranslationBundle};
+use angular_compiler::ml_parser::html_parser::HtmlParser;
 use angular_compiler::ml_parser::html_whitespaces::{
     visit_all_with_siblings_nodes, WhitespaceVisitor,
 };
+use angular_compiler::ml_parser::lexer::{LexerRange, TokenizeOptions};
 use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
 use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
 use angular_compiler::output::output_ast::{Expression, ExpressionTrait, ReadVarExpr};
-use angular_compiler::parse_util::{ParseLocation, ParseSourceFile, ParseSourceSpan};
+use angular_compiler::parse_util::{
+    ParseError, ParseErrorLevel, ParseLocation, ParseSourceFile, ParseSourceSpan,
+};
+use angular_compiler::render3::r3_ast::R3Node;
 use angular_compiler::render3::r3_template_transform::{
     html_ast_to_render3_ast, Render3ParseOptions,
 };
 use angular_compiler::render3::view::api::{
     DeclarationListEmitMode, R3ComponentDeferMetadata, R3ComponentMetadata, R3ComponentTemplate,
-    R3DirectiveMetadata, R3HostMetadata, R3LifecycleMetadata, R3TemplateDependencyMetadata,
+    R3DeferPerBlockDependency, R3DeferPerComponentDependency, R3DeferResolverFunctionMetadata,
+    R3DirectiveDependencyMetadata, R3DirectiveMetadata, R3HostMetadata, R3LifecycleMetadata,
+    R3TemplateDependencyMetadata,
 };
+use angular_compiler::render3::view::compiler::compile_defer_resolver_function;
+use angular_compiler::render3::view::t2_binder::{
+    find_matching_directives_and_pipes, find_used_directives_and_pipes,
+};
+use angular_compiler::template_parser::binding_parser::BindingParser;
 use indexmap::IndexMap;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 // use angular_compiler::render3::view::template::{parse_template, ParseTemplateOptions};
-// use std::collections::HashMap;
 use angular_compiler::template::pipeline::src::compilation::TemplateCompilationMode;
 use angular_compiler::template::pipeline::src::emit::emit_component;
 use angular_compiler::template::pipeline::src::ingest::{ingest_host_binding, HostBindingInput};
@@ -32,16 +61,30 @@ use std::any::Any;
 // use std::time::Instant;
 // use angular_compiler::constant_pool::ConstantPool as CompilerConstantPool; // Distinct from ngtsc ConstantPool if needed
 use angular_compiler::output::output_ast::{ExternalExpr, ExternalReference};
+use angular_compiler::render3::partial::{
+    compile_declare_component_from_metadata, compile_declare_factory_function,
+    DeclareComponentTemplateInfo, ParsedTemplate,
+};
 use angular_compiler::render3::r3_factory::{
     compile_factory_function, DepsOrInvalid, FactoryTarget, R3ConstructorFactoryMetadata,
     R3DependencyMetadata, R3FactoryMetadata,
 };
 
-pub struct ComponentDecoratorHandler;
+pub struct ComponentDecoratorHandler {
+    enable_template_source_locations: bool,
+}
 
 impl ComponentDecoratorHandler {
     pub fn new() -> Self {
-        Self
+        Self {
+            enable_template_source_locations: false,
+        }
+    }
+
+    /// Attach the source location of each DOM node created by compiled templates.
+    pub fn with_template_source_locations(mut self, enabled: bool) -> Self {
+        self.enable_template_source_locations = enabled;
+        self
     }
 }
 
@@ -131,13 +174,291 @@ impl DecoratorHandler<DirectiveMetadata<'static>, DirectiveMetadata<'static>, ()
     ) -> Vec<CompileResult> {
         self.compile_ivy(analysis, None)
     }
+
+    fn compile_partial(
+        &self,
+        _node: &ClassDeclaration,
+        analysis: &DirectiveMetadata<'static>,
+        _resolution: Option<&()>,
+    ) -> Vec<CompileResult> {
+        self.compile_with_mode(analysis, None, CompilationMode::Partial)
+    }
 }
 
 impl ComponentDecoratorHandler {
+    /// Collect what the template type-checker needs to know about a component: its
+    /// template with the directives and pipes in scope, and the import declarations of
+    /// its source file, which the type-check block uses to reference them.
+    ///
+    /// The directives and pipes analyzed in the current compilation are passed as
+    /// `analyzed`; their inputs and outputs are known in more detail than those read from
+    /// `.d.ts` files, and they resolve local imports which have no metadata to read.
+    /// `source` is the content of the component's source file.
+    pub fn type_check_metadata(
+        &self,
+        analysis: &DirectiveMetadata<'static>,
+        analyzed: &[DirectiveMetadata<'static>],
+        source: &str,
+    ) -> Option<(Vec<String>, TypeCheckBlockMetadata)> {
+        let dir = match analysis {
+            DecoratorMetadata::Directive(d) if d.t2.is_component => d,
+            _ => return None,
+        };
+        let comp_meta = dir.component.as_ref()?;
+        let source_file = dir.source_file.as_ref()?;
+
+        let template_str = comp_meta.template.clone().unwrap_or_default();
+        let template_url = comp_meta
+            .template_url
+            .clone()
+            .unwrap_or_else(|| "inline-template.html".to_string());
+        let expression_parser = angular_compiler::expression_parser::parser::Parser::new();
+        let schema_registry =
+            angular_compiler::schema::dom_element_schema_registry::DomElementSchemaRegistry::new();
+        let mut binding_parser = BindingParser::new(&expression_parser, &schema_registry, vec![]);
+        let (nodes, _, _, _, _) =
+            parse_component_template(comp_meta, &template_str, &template_url, &mut binding_parser);
+        let (template_file, template_offset) =
+            template_location(comp_meta, source_file, &template_url);
+
+        let metadata_reader = ModuleMetadataReader::new(&find_project_root(dir));
+        let local_imports_map = imports_map(source);
+        let (declarations, unresolved) =
+            resolve_template_dependencies(dir, &local_imports_map, &metadata_reader);
+
+        let mut namespaces = IndexMap::new();
+        let mut directives = Vec::new();
+        let mut pipes = HashMap::new();
+        for meta in declarations.values() {
+            match meta {
+                R3TemplateDependencyMetadata::Directive(d) if !d.selector.is_empty() => {
+                    let Some(reference) = type_check_reference(&d.type_, &mut namespaces) else {
+                        continue;
+                    };
+                    let name = reference
+                        .rsplit('.')
+                        .next()
+                        .unwrap_or(&reference)
+                        .to_string();
+                    directives.push(match analyzed_directive(analyzed, &name) {
+                        Some(analyzed) => type_checkable_directive(analyzed, reference),
+                        None => type_checkable_dependency(d, name, reference),
+                    });
+                }
+                R3TemplateDependencyMetadata::Pipe(p) => {
+                    if let Some(reference) = type_check_reference(&p.type_, &mut namespaces) {
+                        pipes.insert(p.name.clone(), reference);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The scope of components declared by an NgModule is not resolved.
+        let mut is_poisoned = !dir.is_standalone;
+        for name in &unresolved {
+            if let Some(analyzed) = analyzed_directive(analyzed, name) {
+                directives.push(type_checkable_directive(analyzed, name.clone()));
+            } else if let Some(pipe) = analyzed.iter().find_map(|meta| match meta {
+                DecoratorMetadata::Pipe(pipe) if pipe.name == *name => Some(pipe),
+                _ => None,
+            }) {
+                pipes.insert(pipe.pipe_name.clone(), name.clone());
+            } else {
+                is_poisoned = true;
+            }
+        }
+
+        // Imports which are not a directive or pipe in scope, e.g. NgModules, are not
+        // recorded: whether they are used cannot be told from the bound template.
+        let class_name = |reference: &str| {
+            reference
+                .rsplit('.')
+                .next()
+                .unwrap_or(reference)
+                .to_string()
+        };
+        let standalone_imports = dir
+            .imports
+            .iter()
+            .flatten()
+            .filter_map(|import_ref| {
+                let span = import_ref.span?;
+                let name = import_ref.debug_name().to_string();
+                let pipe_name = pipes
+                    .iter()
+                    .find(|(_, reference)| class_name(reference) == name)
+                    .map(|(pipe_name, _)| pipe_name.clone());
+                if pipe_name.is_none() && !directives.iter().any(|d| d.name == name) {
+                    return None;
+                }
+                Some(TypeCheckImport {
+                    name,
+                    pipe_name,
+                    start: span.start as usize,
+                    end: span.end as usize,
+                })
+            })
+            .collect();
+
+        let mut imports = source_import_declarations(source);
+        for (module, alias) in namespaces {
+            imports.push(format!("import * as {} from '{}';", alias, module));
+        }
+
+        Some((
+            imports,
+            TypeCheckBlockMetadata {
+                component_name: dir.t2.name.clone(),
+                nodes,
+                directives,
+                pipes,
+                template_file,
+                template_offset,
+                schemas: dir.schemas.clone().unwrap_or_default(),
+                is_poisoned,
+                component_file: source_file.to_string_lossy().to_string(),
+                imports: standalone_imports,
+            },
+        ))
+    }
+
+    /// The methods and signals of a component class, read from `source`, the content of
+    /// its source file, for the extended template checks.
+    pub fn component_members(
+        &self,
+        analysis: &DirectiveMetadata<'static>,
+        source: &str,
+    ) -> Option<ComponentMembers> {
+        let dir = match analysis {
+            DecoratorMetadata::Directive(d) if d.t2.is_component => d,
+            _ => return None,
+        };
+        let allocator = oxc_allocator::Allocator::default();
+        let source_type = oxc_span::SourceType::ts().with_module(true);
+        let ret = oxc_parser::Parser::new(&allocator, source, source_type).parse();
+        let class = ret.program.body.iter().find_map(|stmt| {
+            let class = match stmt {
+                oxc_ast::ast::Statement::ClassDeclaration(class) => class,
+                oxc_ast::ast::Statement::ExportNamedDeclaration(decl) => {
+                    match decl.declaration.as_ref()? {
+                        oxc_ast::ast::Declaration::ClassDeclaration(class) => class,
+                        _ => return None,
+                    }
+                }
+                oxc_ast::ast::Statement::ExportDefaultDeclaration(decl) => {
+                    match &decl.declaration {
+                        oxc_ast::ast::ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                            class
+                        }
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            };
+            (class.id.as_ref()?.name == dir.t2.name.as_str()).then_some(&**class)
+        })?;
+
+        let mut members = ComponentMembers::default();
+        for member in TypeScriptReflectionHost::new().get_members_of_class(class) {
+            if member.is_static {
+                continue;
+            }
+            match (&member.kind, member.value) {
+                (ClassMemberKind::Method, _) => {
+                    members.methods.insert(member.name);
+                }
+                (ClassMemberKind::Property, Some(value)) if is_function_expression(value) => {
+                    members.methods.insert(member.name);
+                }
+                (ClassMemberKind::Property, Some(value)) if is_signal_initializer(value) => {
+                    members.signals.insert(member.name);
+                }
+                _ => {}
+            }
+        }
+        Some(members)
+    }
+
+    /// Capture the i18n messages of a component's template in `ctx`, attributed to the
+    /// template file, or to the component's file for inline templates. Returns the errors
+    /// of the extraction, e.g. translatable sections nested in one another.
+    ///
+    /// Templates which failed to parse have been reported by the analysis and are skipped.
+    pub fn xi18n(
+        &self,
+        analysis: &DirectiveMetadata<'static>,
+        ctx: &mut dyn Xi18nContext,
+    ) -> Vec<ts::Diagnostic> {
+        let dir = match analysis {
+            DecoratorMetadata::Directive(d) if d.t2.is_component => d,
+            _ => return Vec::new(),
+        };
+        let Some((template, url)) = template_source(dir) else {
+            return Vec::new();
+        };
+
+        let errors = ctx.update_from_template(template, &url);
+        template_errors(dir, &url, &errors)
+    }
+
+    /// Merge `translations` into a component's template, replacing the HTML AST it is
+    /// compiled from. Returns the errors of the merge, along with the warnings about
+    /// missing translations, whose source text is kept.
+    ///
+    /// Templates which failed to parse have been reported by the analysis and are skipped.
+    pub fn translate(
+        &self,
+        analysis: &mut DirectiveMetadata<'static>,
+        translations: &mut TranslationBundle,
+    ) -> Vec<ts::Diagnostic> {
+        let dir = match analysis {
+            DecoratorMetadata::Directive(d) if d.t2.is_component => d,
+            _ => return Vec::new(),
+        };
+        let Some((template, url)) =
+            template_source(dir).map(|(template, url)| (template.to_string(), url))
+        else {
+            return Vec::new();
+        };
+
+        // Messages are extracted with expansion forms, so they must be merged with them too.
+        let options = TokenizeOptions {
+            tokenize_expansion_forms: true,
+            ..dir
+                .component
+                .as_ref()
+                .map(|comp_meta| template_tokenize_options(comp_meta, &template))
+                .unwrap_or_default()
+        };
+        let parse_result = HtmlParser::new().parse(&template, &url, Some(options));
+        if !parse_result.errors.is_empty() {
+            return template_errors(dir, &url, &parse_result.errors);
+        }
+        let merged =
+            merge_translations(&parse_result.root_nodes, translations, &[], &HashMap::new());
+        if let Some(comp_meta) = dir.component.as_mut() {
+            comp_meta.template_ast = Some(merged.root_nodes);
+        }
+        template_errors(dir, &url, &merged.errors)
+    }
+
     pub fn compile_ivy(
         &self,
         analysis: &DirectiveMetadata<'static>,
         external_import_manager: Option<&mut crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager>,
+    ) -> Vec<CompileResult> {
+        self.compile_with_mode(analysis, external_import_manager, CompilationMode::Full)
+    }
+
+    /// Compile the `ɵfac` and `ɵcmp` fields. In partial mode these are emitted as
+    /// `ɵɵngDeclareFactory` / `ɵɵngDeclareComponent` calls which carry the template
+    /// source for the linker to compile.
+    pub fn compile_with_mode(
+        &self,
+        analysis: &DirectiveMetadata<'static>,
+        external_import_manager: Option<&mut crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager>,
+        mode: CompilationMode,
     ) -> Vec<CompileResult> {
         // Extract DirectiveMeta from DecoratorMetadata enum (must be a component)
         let dir = match analysis {
@@ -180,359 +501,93 @@ impl ComponentDecoratorHandler {
                 vec![],
             );
 
-        // Find project root by traversing up from source file to find node_modules
-        // ... (this part is unchanged, but included for context in large replacement if needed, but I will use smaller chunks)
-        // Actually, let's just replace the signature and the creation of import_manager.
-
-        // I will use a smaller chunk replacement.
-
-        // Find project root by traversing up from source file to find node_modules
-        let source_file_path = dir
-            .source_file
-            .as_deref()
-            .unwrap_or(std::path::Path::new("."));
-        let source_dir = PathBuf::from(source_file_path)
-            .parent()
-            .unwrap_or(std::path::Path::new("."))
-            .to_path_buf();
-        let project_root = {
-            let mut search_dir = source_dir;
-            loop {
-                if search_dir.join("node_modules").exists() {
-                    break search_dir;
-                }
-                if !search_dir.pop() {
-                    // Fallback to cwd if no node_modules found
-                    break std::env::current_dir()
-                        .unwrap_or_else(|_| std::path::PathBuf::from("."));
-                }
-            }
-        };
+        let project_root = find_project_root(dir);
         eprintln!(
             "DEBUG: [handler] Project root for ModuleMetadataReader: {}",
             project_root.display()
         );
         let metadata_reader = ModuleMetadataReader::new(&project_root);
 
-        let (nodes, ng_content_selectors, preserve_whitespaces, styles) = if let Some(ast) =
-            comp_meta.template_ast.as_ref()
-        {
-            let options = Render3ParseOptions {
-                collect_comment_nodes: false,
-                ..Default::default()
-            };
-
-            // Apply whitespace visitor
-            let mut visitor = WhitespaceVisitor::new(true, None, false);
-            let processed_nodes = visit_all_with_siblings_nodes(&mut visitor, ast);
-
-            let result = html_ast_to_render3_ast(&processed_nodes, &mut binding_parser, &options);
-            // Combine inline styles from template with any style URLs
-            let mut combined_styles = result.styles;
-            combined_styles.extend(result.style_urls);
-            (
-                result.nodes,
-                result.ng_content_selectors,
-                true, // TODO: Get from options
-                combined_styles,
-            )
+        let (nodes, ng_content_selectors, preserve_whitespaces, styles, parse_errors) =
+            parse_component_template(comp_meta, &template_str, &template_url, &mut binding_parser);
+        let parse_diagnostics: Vec<ts::Diagnostic> = if parse_errors.is_empty() {
+            vec![]
         } else {
-            let parsed_template = angular_compiler::render3::view::template::parse_template(
-                &template_str,
-                &template_url,
-                angular_compiler::render3::view::template::ParseTemplateOptions {
-                    preserve_whitespaces: Some(false),
-                    ..Default::default()
-                },
-            );
-            // Combine inline styles from template with any style URLs
-            let mut combined_styles = parsed_template.styles;
-            combined_styles.extend(parsed_template.style_urls);
-            (
-                parsed_template.nodes,
-                parsed_template.ng_content_selectors,
-                parsed_template.preserve_whitespaces.unwrap_or(false),
-                combined_styles,
-            )
+            let source_file = dir
+                .source_file
+                .as_deref()
+                .unwrap_or(Path::new(&template_url));
+            let (template_file, template_offset) =
+                template_location(comp_meta, source_file, &template_url);
+            parse_errors
+                .iter()
+                .map(|error| template_parse_diagnostic(error, &template_file, template_offset))
+                .collect()
         };
 
-        // TODO: Handle parsing errors?
-        // if let Some(errors) = parsed_template.errors { ... }
-
         // Build imports map from source file to resolve module paths
-        let local_imports_map: std::collections::HashMap<String, String> = dir.source_file
-            .as_ref()
-            .and_then(|path| std::fs::read_to_string(path).ok())
-            .map(|content| {
-                let allocator = oxc_allocator::Allocator::default();
-                let source_type = oxc_span::SourceType::ts().with_module(true);
-                let ret = oxc_parser::Parser::new(&allocator, &content, source_type).parse();
-                let mut map = std::collections::HashMap::new();
-                for stmt in &ret.program.body {
-                    if let Some(oxc_ast::ast::ModuleDeclaration::ImportDeclaration(import_decl)) = stmt.as_module_declaration() {
-                        let source = import_decl.source.value.as_str();
-                        if let Some(specifiers) = &import_decl.specifiers {
-                            for spec in specifiers {
-                                let local_name = match spec {
-                                    oxc_ast::ast::ImportDeclarationSpecifier::ImportSpecifier(s) => s.local.name.as_str(),
-                                    oxc_ast::ast::ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => s.local.name.as_str(),
-                                    oxc_ast::ast::ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => s.local.name.as_str(),
-                                };
-                                map.insert(local_name.to_string(), source.to_string());
-                            }
-                        }
-                    }
-                }
-                map
-            })
-            .unwrap_or_default();
+        let local_imports_map = source_imports_map(dir);
 
         // Detect dependencies (directives, pipes, modules) from imports
-        let mut declarations_map = indexmap::IndexMap::new();
-
-        if let Some(imports) = &dir.imports {
-            for import_ref in imports {
-                let import_name = import_ref.debug_name().to_string();
-                // First try best_guess_owning_module, fallback to local_imports_map
-                let module_path = import_ref
-                    .best_guess_owning_module
-                    .as_ref()
-                    .map(|m| m.specifier.clone())
-                    .or_else(|| local_imports_map.get(&import_name).cloned());
-
-                let source_span = dir.source_file.as_ref().and_then(|path| {
-                    import_ref.span.map(|span| {
-                        let file = std::sync::Arc::new(ParseSourceFile::new(
-                            "".to_string(),
-                            path.to_string_lossy().to_string(),
-                        ));
-                        ParseSourceSpan {
-                            start: ParseLocation::new(
-                                std::sync::Arc::clone(&file),
-                                span.start as usize,
-                                0,
-                                0,
-                            ),
-                            end: ParseLocation::new(file, span.end as usize, 0, 0),
-                            details: None,
-                        }
-                    })
-                });
-
-                let local_import_expr = Expression::ReadVar(ReadVarExpr {
-                    name: import_name.clone(),
-                    type_: None,
-                    source_span: None,
-                });
-
-                // Strategy:
-                // 1. If module_path exists (external module), try dynamic loading first
-                // 2. If dynamic fails, fall back to hardcoded for compatibility
-                // 3. If no module_path (local), use ReadVar expression directly
-
-                if let Some(path) = &module_path {
-                    eprintln!("DEBUG: [handler] checking module path: {}", path);
-                    // External module - try dynamic loading first
-                    if let Some(dynamic_deps) = metadata_reader.read_metadata(path) {
-                        eprintln!(
-                            "DEBUG: [handler] read_metadata success for {}. Found {} deps",
-                            path,
-                            dynamic_deps.len()
-                        );
-                        let mut matched_specific_symbol = false;
-                        let mut matched_symbol_is_module = false;
-
-                        for meta in &dynamic_deps {
-                            // Check if this metadata corresponds to the imported symbol
-                            let meta_name = match &meta {
-                                R3TemplateDependencyMetadata::Directive(d) => {
-                                    eprintln!(
-                                        "DEBUG: [handler] Found Directive in metadata: selector={}",
-                                        d.selector
-                                    );
-                                    Some(&d.type_)
-                                }
-                                R3TemplateDependencyMetadata::Pipe(p) => Some(&p.type_),
-                                R3TemplateDependencyMetadata::NgModule(m) => {
-                                    eprintln!(
-                                        "DEBUG: [handler] Found NgModule in metadata: {:?}",
-                                        m.type_
-                                    );
-                                    Some(&m.type_)
-                                }
-                            }
-                            .and_then(|expr| match expr {
-                                Expression::External(ext) => ext.value.name.as_ref(),
-                                Expression::ReadVar(rv) => Some(&rv.name),
-                                _ => None,
-                            });
-
-                            if let Some(name) = meta_name {
-                                if name == &import_name
-                                    || (name == "NgForOf" && import_name == "NgFor")
-                                    || (name == "NgIf" && import_name == "NgIf")
-                                {
-                                    matched_specific_symbol = true;
-
-                                    let key = match &meta {
-                                        R3TemplateDependencyMetadata::Directive(d) => {
-                                            format!("dir:{}", d.selector)
-                                        }
-                                        R3TemplateDependencyMetadata::Pipe(p) => {
-                                            format!("pipe:{}", p.name)
-                                        }
-                                        R3TemplateDependencyMetadata::NgModule(_) => {
-                                            format!("module:{}", import_name)
-                                        }
-                                    };
-
-                                    let mut meta_clone = meta.clone();
-
-                                    // Use the local import expression for the type to avoid synthetic aliases (i1, i2...)
-                                    // when the symbol is explicitly imported by the user.
-                                    let local_type = local_import_expr.clone();
-                                    match &mut meta_clone {
-                                        R3TemplateDependencyMetadata::Directive(d) => {
-                                            d.source_span = source_span.clone();
-                                            d.type_ = local_type;
-                                        }
-                                        R3TemplateDependencyMetadata::Pipe(p) => {
-                                            p.source_span = source_span.clone();
-                                            p.type_ = local_type;
-                                        }
-                                        R3TemplateDependencyMetadata::NgModule(m) => {
-                                            m.type_ = local_type;
-                                            matched_symbol_is_module = true;
-                                        }
-                                    }
-                                    declarations_map.insert(key, meta_clone);
-                                }
-                            }
-                        }
-
-                        // If we didn't match a specific directive/pipe, assume it's an NgModule (like CommonModule)
-                        // In this case, we add the module itself AND all its exports (which are in dynamic_deps)
-                        // This allows the template to see all exported directives/pipes
-                        if !matched_specific_symbol || matched_symbol_is_module {
-                            // 1. Add the module itself
-                            let module_meta = R3TemplateDependencyMetadata::NgModule(
-                                angular_compiler::render3::view::api::R3NgModuleDependencyMetadata {
-                                    kind: angular_compiler::render3::view::api::R3TemplateDependencyKind::NgModule,
-                                    type_: local_import_expr.clone(),
-                                }
-                            );
-                            declarations_map.insert(format!("module:{}", import_name), module_meta);
-
-                            // 2. Add all exports from the module
-                            for mut meta in dynamic_deps {
-                                let key = match &meta {
-                                    R3TemplateDependencyMetadata::Directive(d) => {
-                                        format!("dir:{}", d.selector)
-                                    }
-                                    R3TemplateDependencyMetadata::Pipe(p) => {
-                                        format!("pipe:{}", p.name)
-                                    }
-                                    R3TemplateDependencyMetadata::NgModule(_) => {
-                                        format!("module:unknown")
-                                    } // Should not happen for exports usually
-                                };
-
-                                match &mut meta {
-                                    R3TemplateDependencyMetadata::Directive(d) => {
-                                        d.source_span = source_span.clone()
-                                    }
-                                    R3TemplateDependencyMetadata::Pipe(p) => {
-                                        p.source_span = source_span.clone()
-                                    }
-                                    R3TemplateDependencyMetadata::NgModule(_) => {}
-                                }
-                                declarations_map.insert(key, meta);
-                            }
-                        }
-                    } else {
-                        // Dynamic loading failed - just add module itself as dependency
-                        // The directives/pipes should already be compiled and linked
-
-                        // Dynamic loading failed - just add module itself as dependency
-                        let module_meta = R3TemplateDependencyMetadata::NgModule(
-                            angular_compiler::render3::view::api::R3NgModuleDependencyMetadata {
-                                kind: angular_compiler::render3::view::api::R3TemplateDependencyKind::NgModule,
-                                type_: local_import_expr.clone(),
-                            }
-                        );
-                        declarations_map.insert(format!("module:{}", import_name), module_meta);
-                    }
-                } else {
-                    // Local component/directive - no external module
-
-                    let mut found = false;
-                    // Dynamic resolution for local components
-                    if let Some(source_file) = &dir.source_file {
-                        if let Some(local_details) =
-                            metadata_reader.extract_ts_metadata(source_file)
-                        {
-                            for meta in local_details {
-                                let matches = match &meta {
-                                     R3TemplateDependencyMetadata::Directive(d) => {
-                                         if let angular_compiler::output::output_ast::Expression::ReadVar(rv) = &d.type_ {
-                                             rv.name == import_name
-                                         } else {
-                                             false
-                                         }
-                                     },
-                                     R3TemplateDependencyMetadata::Pipe(p) => {
-                                         if let angular_compiler::output::output_ast::Expression::ReadVar(rv) = &p.type_ {
-                                              rv.name == import_name
-                                         } else {
-                                              false
-                                         }
-                                     },
-                                     R3TemplateDependencyMetadata::NgModule(n) => {
-                                          if let angular_compiler::output::output_ast::Expression::ReadVar(rv) = &n.type_ {
-                                              rv.name == import_name
-                                          } else {
-                                              false
-                                          }
-                                     }
-                                };
-
-                                if matches {
-                                    let key = match &meta {
-                                        R3TemplateDependencyMetadata::Directive(d) => {
-                                            format!("dir:{}", d.selector)
-                                        }
-                                        R3TemplateDependencyMetadata::Pipe(p) => {
-                                            format!("pipe:{}", p.name)
-                                        }
-                                        R3TemplateDependencyMetadata::NgModule(_) => {
-                                            format!("module:{}", import_name)
-                                        }
-                                    };
-                                    declarations_map.insert(key, meta.clone());
-                                    found = true;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-
-                    if !found {
-                        // Unknown local - create placeholder with ReadVar
-                        let meta = R3TemplateDependencyMetadata::Directive(angular_compiler::render3::view::api::R3DirectiveDependencyMetadata {
-                            selector: "".to_string(),
-                            type_: local_import_expr,
-                            inputs: vec![],
-                            outputs: vec![],
-                            export_as: vec![].into(),
-                            kind: angular_compiler::render3::view::api::R3TemplateDependencyKind::Directive,
-                            is_component: false,
-                            source_span: source_span.clone(),
-                        });
-                        declarations_map.insert(format!("unknown:{}", import_name), meta);
-                    }
-                }
+        let (mut declarations_map, _) =
+            resolve_template_dependencies(dir, &local_imports_map, &metadata_reader);
+
+        // Dependencies only used within `@defer` blocks are loaded lazily through a
+        // dependency function, so they are dropped from the eager declarations.
+        let deferrable_dependencies = match (mode, &dir.source_file) {
+            (CompilationMode::Partial, _) | (_, None) => vec![],
+            (_, Some(source_file)) => {
+                let template = match (&comp_meta.template, &comp_meta.template_file) {
+                    (Some(template), _) => template.as_str(),
+                    (None, Some((_, content))) => content.as_str(),
+                    (None, None) => "",
+                };
+                find_deferrable_dependencies(
+                    source_file,
+                    template,
+                    &declarations_map,
+                    &metadata_reader,
+                )
             }
-        }
+        };
+        declarations_map.retain(|_, meta| {
+            let type_ = match meta {
+                R3TemplateDependencyMetadata::Directive(d) => &d.type_,
+                R3TemplateDependencyMetadata::Pipe(p) => &p.type_,
+                R3TemplateDependencyMetadata::NgModule(m) => &m.type_,
+            };
+            !matches!(type_, Expression::ReadVar(rv) if deferrable_dependencies
+                .iter()
+                .any(|dependency| dependency.local_name == rv.name))
+        });
+
+        // Each `@defer` block loads the dependencies used within it, except in local
+        // compilation, where a single function loads all deferred dependencies.
+        let defer = match mode {
+            CompilationMode::Partial => R3ComponentDeferMetadata::PerBlock {
+                blocks: std::collections::HashMap::new(),
+            },
+            CompilationMode::Full => R3ComponentDeferMetadata::PerBlock {
+                blocks: defer_block_dependency_fns(
+                    &nodes,
+                    &declarations_map,
+                    &deferrable_dependencies,
+                ),
+            },
+            _ if deferrable_dependencies.is_empty() => R3ComponentDeferMetadata::PerComponent {
+                dependencies_fn: None,
+            },
+            _ => R3ComponentDeferMetadata::PerComponent {
+                dependencies_fn: Some(compile_defer_resolver_function(
+                    &R3DeferResolverFunctionMetadata::PerComponent {
+                        dependencies: deferrable_dependencies
+                            .iter()
+                            .map(|deferrable| deferrable.dependency.clone())
+                            .collect(),
+                    },
+                )),
+            },
+        };
 
         let mut r3_metadata = R3ComponentMetadata {
             directive: R3DirectiveMetadata {
@@ -661,21 +716,53 @@ impl ComponentDecoratorHandler {
             i18n_use_external_ids: false,
             raw_imports: None,
             external_styles: None,
-            defer: R3ComponentDeferMetadata::PerComponent {
-                dependencies_fn: None,
-            },
-            relative_template_path: None,
+            defer,
+            relative_template_path: template_source(dir).map(|(_, url)| {
+                let path = Path::new(&url);
+                path.strip_prefix(&project_root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            enable_template_source_locations: self.enable_template_source_locations,
             has_directive_dependencies: false,
         };
 
         let mut real_constant_pool = angular_compiler::constant_pool::ConstantPool::new(false);
 
         // 4. Emit component definition using centralized compiler
-        let compiled = angular_compiler::render3::view::compiler::compile_component_from_metadata(
-            &r3_metadata,
-            &mut real_constant_pool,
-            &mut binding_parser,
-        );
+        let compiled = match mode {
+            CompilationMode::Partial => {
+                // The linker compiles the template itself, so the declaration carries its source.
+                let template_info = match (&comp_meta.template, &comp_meta.template_file) {
+                    (None, Some((path, content))) => DeclareComponentTemplateInfo {
+                        content: content.clone(),
+                        source_url: path.clone(),
+                        is_inline: false,
+                        inline_template_literal_expression: None,
+                    },
+                    _ => DeclareComponentTemplateInfo {
+                        content: template_str.clone(),
+                        source_url: template_url.clone(),
+                        is_inline: true,
+                        inline_template_literal_expression: None,
+                    },
+                };
+                compile_declare_component_from_metadata(
+                    &r3_metadata,
+                    &ParsedTemplate {
+                        nodes: nodes.clone(),
+                        preserve_whitespaces,
+                    },
+                    &template_info,
+                )
+            }
+            _ => angular_compiler::render3::view::compiler::compile_component_from_metadata(
+                &r3_metadata,
+                &mut real_constant_pool,
+                &mut binding_parser,
+            ),
+        };
 
         // 4b. Generate Factory (ɵfac)
         // Map constructor params to R3DependencyMetadata
@@ -741,7 +828,10 @@ impl ComponentDecoratorHandler {
             target: FactoryTarget::Component,
         });
 
-        let factory_compiled = compile_factory_function(&factory_metadata);
+        let factory_compiled = match mode {
+            CompilationMode::Partial => compile_declare_factory_function(&factory_metadata),
+            _ => compile_factory_function(&factory_metadata),
+        };
 
         // Detect required imports based on metadata
         let mut local_manager = crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager::new();
@@ -823,14 +913,20 @@ impl ComponentDecoratorHandler {
         compiled.expression.visit_expression(&mut emitter, context);
 
         let initializer = ctx.to_source();
+        let initializer_map = template_source_map(&ctx);
 
         // Emit statements (hoisted statements)
         let mut emitted_statements = vec![];
+        let mut statement_maps = vec![];
         for stmt in &compiled.statements {
             let mut stmt_ctx = EmitterVisitorContext::create_root();
             let stmt_context: &mut dyn Any = &mut stmt_ctx;
             stmt.visit_statement(&mut emitter, stmt_context);
             emitted_statements.push(stmt_ctx.to_source());
+            statement_maps.push(template_source_map(&stmt_ctx));
+        }
+        if statement_maps.iter().all(Option::is_none) {
+            statement_maps.clear();
         }
 
         // Filter additional_imports based on actual usage during emission
@@ -846,33 +942,1052 @@ impl ComponentDecoratorHandler {
             get_num(&a.0).cmp(&get_num(&b.0))
         });
 
-        // 4. Convert diagnostics (not easily available from compiled result yet, need to improve return type of compile_component_from_metadata if we want them back)
-        // For now, returning empty diagnostics as the centralized compiler doesn't return them directly in the struct yet
-        let ts_diagnostics: Vec<ts::Diagnostic> = vec![];
-
         vec![
             CompileResult {
                 name: "ɵfac".to_string(),
                 initializer: Some(factory_initializer),
                 statements: vec![],
-                type_desc: "Factory".to_string(),
+                type_desc: factory_declaration_type(&dir.t2.name, &dir.constructor_params),
                 deferrable_imports: None,
                 diagnostics: vec![],
                 additional_imports: vec![], // Imports handled by Emitter turned into import_manager
+                initializer_map: None,
+                statement_maps: Vec::new(),
             },
             CompileResult {
                 name: "ɵcmp".to_string(),
                 initializer: Some(initializer),
                 statements: emitted_statements,
-                type_desc: "ComponentDef".to_string(),
-                deferrable_imports: None,
-                diagnostics: ts_diagnostics,
+                type_desc: directive_declaration_type(dir),
+                deferrable_imports: (!deferrable_dependencies.is_empty()).then(|| {
+                    deferrable_dependencies
+                        .iter()
+                        .map(|deferrable| deferrable.local_name.clone())
+                        .collect()
+                }),
+                diagnostics: parse_diagnostics,
                 additional_imports,
+                initializer_map,
+                statement_maps,
             },
         ]
     }
 }
 
+/// Find the project root of a component by traversing up from its source file to the
+/// closest directory containing `node_modules`, falling back to the working directory.
+fn find_project_root(dir: &DirectiveMeta<'_>) -> PathBuf {
+    let source_file_path = dir
+        .source_file
+        .as_deref()
+        .unwrap_or(std::path::Path::new("."));
+    let mut search_dir = PathBuf::from(source_file_path)
+        .parent()
+        .unwrap_or(std::path::Path::new("."))
+        .to_path_buf();
+    loop {
+        if search_dir.join("node_modules").exists() {
+            return search_dir;
+        }
+        if !search_dir.pop() {
+            return std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        }
+    }
+}
+
+/// Parse a component's template into R3 nodes, along with its `ng-content` selectors,
+/// whether whitespace is preserved, the styles declared within it and the errors found
+/// while binding it.
+fn parse_component_template(
+    comp_meta: &ComponentMetadata,
+    template_str: &str,
+    template_url: &str,
+    binding_parser: &mut BindingParser<'_>,
+) -> (Vec<R3Node>, Vec<String>, bool, Vec<String>, Vec<ParseError>) {
+    if let Some(ast) = comp_meta.template_ast.as_ref() {
+        let options = Render3ParseOptions {
+            collect_comment_nodes: false,
+            ..Default::default()
+        };
+
+        // Apply whitespace visitor
+        let mut visitor = WhitespaceVisitor::new(true, None, false);
+        let processed_nodes = visit_all_with_siblings_nodes(&mut visitor, ast);
+
+        let result = html_ast_to_render3_ast(&processed_nodes, binding_parser, &options);
+        // Combine inline styles from template with any style URLs
+        let mut combined_styles = result.styles;
+        combined_styles.extend(result.style_urls);
+        (
+            result.nodes,
+            result.ng_content_selectors,
+            true, // TODO: Get from options
+            combined_styles,
+            result.errors,
+        )
+    } else {
+        let parsed_template = angular_compiler::render3::view::template::parse_template(
+            template_str,
+            template_url,
+            angular_compiler::render3::view::template::ParseTemplateOptions {
+                preserve_whitespaces: Some(false),
+                ..Default::default()
+            },
+        );
+        // Combine inline styles from template with any style URLs
+        let mut combined_styles = parsed_template.styles;
+        combined_styles.extend(parsed_template.style_urls);
+        // Templates without an HTML AST failed to parse during analysis, which reported
+        // their errors already.
+        (
+            parsed_template.nodes,
+            parsed_template.ng_content_selectors,
+            parsed_template.preserve_whitespaces.unwrap_or(false),
+            combined_styles,
+            vec![],
+        )
+    }
+}
+
+/// The source of a component's template and its URL, which is the component's file for
+/// inline templates. Templates which failed to parse have none.
+fn template_source<'m>(dir: &'m DirectiveMeta<'_>) -> Option<(&'m str, String)> {
+    let comp_meta = dir.component.as_ref()?;
+    comp_meta.template_ast.as_ref()?;
+    match (&comp_meta.template, &comp_meta.template_file) {
+        (Some(template), _) => Some((
+            template,
+            dir.source_file.as_ref().map_or_else(
+                || "inline-template.html".to_string(),
+                |path| path.to_string_lossy().to_string(),
+            ),
+        )),
+        (None, Some((path, content))) => Some((content, path.clone())),
+        (None, None) => None,
+    }
+}
+
+/// Options to tokenize a component's template with. Inline templates keep the lines and
+/// columns they have in the component's file, so that source maps point into it, while
+/// their offsets stay relative to the template.
+pub fn template_tokenize_options(comp_meta: &ComponentMetadata, template: &str) -> TokenizeOptions {
+    let range = comp_meta
+        .template
+        .as_ref()
+        .and(comp_meta.template_start)
+        .map(|(line, column)| LexerRange {
+            start_pos: 0,
+            start_line: line,
+            start_col: column,
+            end_pos: template.len(),
+        });
+    TokenizeOptions {
+        range,
+        ..TokenizeOptions::default()
+    }
+}
+
+/// The errors found in a component's template at `url` as `TemplateParseError`
+/// diagnostics, see `template_location()`.
+fn template_errors(
+    dir: &DirectiveMeta<'_>,
+    url: &str,
+    errors: &[ParseError],
+) -> Vec<ts::Diagnostic> {
+    let Some(comp_meta) = dir.component.as_ref() else {
+        return Vec::new();
+    };
+    if errors.is_empty() {
+        return Vec::new();
+    }
+    let (template_file, template_offset) = template_location(comp_meta, Path::new(url), url);
+    errors
+        .iter()
+        .map(|error| template_parse_diagnostic(error, &template_file, template_offset))
+        .collect()
+}
+
+/// The file diagnostics of a component's template are reported against, and the offset
+/// of the template within it. Inline templates are reported against the component's file.
+fn template_location(
+    comp_meta: &ComponentMetadata,
+    source_file: &Path,
+    template_url: &str,
+) -> (String, usize) {
+    match (&comp_meta.template, &comp_meta.template_file) {
+        (Some(_), _) => (
+            source_file.to_string_lossy().to_string(),
+            comp_meta.template_offset.unwrap_or(0),
+        ),
+        (None, Some((path, _))) => (path.clone(), 0),
+        (None, None) => (template_url.to_string(), 0),
+    }
+}
+
+/// A template parse error as a `TemplateParseError` (NG5002) diagnostic, at its span in
+/// `template_file`, whose template starts at `template_offset`.
+pub fn template_parse_diagnostic(
+    error: &ParseError,
+    template_file: &str,
+    template_offset: usize,
+) -> ts::Diagnostic {
+    let start = error.span.start.offset;
+    // Errors at a single position, e.g. an unexpected end of file, still cover a character.
+    let end = error.span.end.offset.max(start + 1);
+    ts::Diagnostic {
+        category: match error.level {
+            ParseErrorLevel::Warning => ts::DiagnosticCategory::Warning,
+            ParseErrorLevel::Error => ts::DiagnosticCategory::Error,
+        },
+        code: ErrorCode::TemplateParseError as i32,
+        file: Some(template_file.to_string()),
+        start: template_offset + start,
+        length: end - start,
+        message_text: error.msg.clone().into(),
+        related_information: None,
+    }
+}
+
+/// Map the local names imported by a component's source file to their module specifiers.
+fn source_imports_map(dir: &DirectiveMeta<'_>) -> HashMap<String, String> {
+    dir.source_file
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|content| imports_map(&content))
+        .unwrap_or_default()
+}
+
+/// Map the local names imported by `content` to their module specifiers.
+fn imports_map(content: &str) -> HashMap<String, String> {
+    let allocator = oxc_allocator::Allocator::default();
+    let source_type = oxc_span::SourceType::ts().with_module(true);
+    let ret = oxc_parser::Parser::new(&allocator, content, source_type).parse();
+    let mut map = std::collections::HashMap::new();
+    for stmt in &ret.program.body {
+        if let Some(oxc_ast::ast::ModuleDeclaration::ImportDeclaration(import_decl)) =
+            stmt.as_module_declaration()
+        {
+            let source = import_decl.source.value.as_str();
+            if let Some(specifiers) = &import_decl.specifiers {
+                for spec in specifiers {
+                    let local_name = match spec {
+                        oxc_ast::ast::ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                            s.local.name.as_str()
+                        }
+                        oxc_ast::ast::ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => {
+                            s.local.name.as_str()
+                        }
+                        oxc_ast::ast::ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                            s.local.name.as_str()
+                        }
+                    };
+                    map.insert(local_name.to_string(), source.to_string());
+                }
+            }
+        }
+    }
+    map
+}
+
+/// Resolve the directives, pipes and NgModules a component imports into the
+/// dependencies of its template, keyed by selector, pipe name or module name. Also
+/// returns the names of the imports whose metadata could not be found.
+fn resolve_template_dependencies(
+    dir: &DirectiveMeta<'_>,
+    local_imports_map: &HashMap<String, String>,
+    metadata_reader: &ModuleMetadataReader,
+) -> (IndexMap<String, R3TemplateDependencyMetadata>, Vec<String>) {
+    let mut declarations_map = indexmap::IndexMap::new();
+    let mut unresolved = Vec::new();
+
+    if let Some(imports) = &dir.imports {
+        for import_ref in imports {
+            let import_name = import_ref.debug_name().to_string();
+            // First try best_guess_owning_module, fallback to local_imports_map
+            let module_path = import_ref
+                .best_guess_owning_module
+                .as_ref()
+                .map(|m| m.specifier.clone())
+                .or_else(|| local_imports_map.get(&import_name).cloned());
+
+            let source_span = dir.source_file.as_ref().and_then(|path| {
+                import_ref.span.map(|span| {
+                    let file = std::sync::Arc::new(ParseSourceFile::new(
+                        "".to_string(),
+                        path.to_string_lossy().to_string(),
+                    ));
+                    ParseSourceSpan {
+                        start: ParseLocation::new(
+                            std::sync::Arc::clone(&file),
+                            span.start as usize,
+                            0,
+                            0,
+                        ),
+                        end: ParseLocation::new(file, span.end as usize, 0, 0),
+                        details: None,
+                    }
+                })
+            });
+
+            let local_import_expr = Expression::ReadVar(ReadVarExpr {
+                name: import_name.clone(),
+                type_: None,
+                source_span: None,
+            });
+
+            // Strategy:
+            // 1. If module_path exists (external module), try dynamic loading first
+            // 2. If dynamic fails, fall back to hardcoded for compatibility
+            // 3. If no module_path (local), use ReadVar expression directly
+
+            if let Some(path) = &module_path {
+                eprintln!("DEBUG: [handler] checking module path: {}", path);
+                // External module - try dynamic loading first
+                if let Some(dynamic_deps) = metadata_reader.read_metadata(path) {
+                    eprintln!(
+                        "DEBUG: [handler] read_metadata success for {}. Found {} deps",
+                        path,
+                        dynamic_deps.len()
+                    );
+                    let mut matched_specific_symbol = false;
+                    let mut matched_symbol_is_module = false;
+
+                    for meta in &dynamic_deps {
+                        // Check if this metadata corresponds to the imported symbol
+                        let meta_name = match &meta {
+                            R3TemplateDependencyMetadata::Directive(d) => {
+                                eprintln!(
+                                    "DEBUG: [handler] Found Directive in metadata: selector={}",
+                                    d.selector
+                                );
+                                Some(&d.type_)
+                            }
+                            R3TemplateDependencyMetadata::Pipe(p) => Some(&p.type_),
+                            R3TemplateDependencyMetadata::NgModule(m) => {
+                                eprintln!(
+                                    "DEBUG: [handler] Found NgModule in metadata: {:?}",
+                                    m.type_
+                                );
+                                Some(&m.type_)
+                            }
+                        }
+                        .and_then(|expr| match expr {
+                            Expression::External(ext) => ext.value.name.as_ref(),
+                            Expression::ReadVar(rv) => Some(&rv.name),
+                            _ => None,
+                        });
+
+                        if let Some(name) = meta_name {
+                            if name == &import_name
+                                || (name == "NgForOf" && import_name == "NgFor")
+                                || (name == "NgIf" && import_name == "NgIf")
+                            {
+                                matched_specific_symbol = true;
+
+                                let key = match &meta {
+                                    R3TemplateDependencyMetadata::Directive(d) => {
+                                        format!("dir:{}", d.selector)
+                                    }
+                                    R3TemplateDependencyMetadata::Pipe(p) => {
+                                        format!("pipe:{}", p.name)
+                                    }
+                                    R3TemplateDependencyMetadata::NgModule(_) => {
+                                        format!("module:{}", import_name)
+                                    }
+                                };
+
+                                let mut meta_clone = meta.clone();
+
+                                // Use the local import expression for the type to avoid synthetic aliases (i1, i2...)
+                                // when the symbol is explicitly imported by the user.
+                                let local_type = local_import_expr.clone();
+                                match &mut meta_clone {
+                                    R3TemplateDependencyMetadata::Directive(d) => {
+                                        d.source_span = source_span.clone();
+                                        d.type_ = local_type;
+                                    }
+                                    R3TemplateDependencyMetadata::Pipe(p) => {
+                                        p.source_span = source_span.clone();
+                                        p.type_ = local_type;
+                                    }
+                                    R3TemplateDependencyMetadata::NgModule(m) => {
+                                        m.type_ = local_type;
+                                        matched_symbol_is_module = true;
+                                    }
+                                }
+                                declarations_map.insert(key, meta_clone);
+                            }
+                        }
+                    }
+
+                    // If we didn't match a specific directive/pipe, assume it's an NgModule (like CommonModule)
+                    // In this case, we add the module itself AND all its exports (which are in dynamic_deps)
+                    // This allows the template to see all exported directives/pipes
+                    if !matched_specific_symbol || matched_symbol_is_module {
+                        // 1. Add the module itself
+                        let module_meta = R3TemplateDependencyMetadata::NgModule(
+                            angular_compiler::render3::view::api::R3NgModuleDependencyMetadata {
+                                kind: angular_compiler::render3::view::api::R3TemplateDependencyKind::NgModule,
+                                type_: local_import_expr.clone(),
+                            }
+                        );
+                        declarations_map.insert(format!("module:{}", import_name), module_meta);
+
+                        // 2. Add all exports from the module
+                        for mut meta in dynamic_deps {
+                            let key = match &meta {
+                                R3TemplateDependencyMetadata::Directive(d) => {
+                                    format!("dir:{}", d.selector)
+                                }
+                                R3TemplateDependencyMetadata::Pipe(p) => {
+                                    format!("pipe:{}", p.name)
+                                }
+                                R3TemplateDependencyMetadata::NgModule(_) => {
+                                    format!("module:unknown")
+                                } // Should not happen for exports usually
+                            };
+
+                            match &mut meta {
+                                R3TemplateDependencyMetadata::Directive(d) => {
+                                    d.source_span = source_span.clone()
+                                }
+                                R3TemplateDependencyMetadata::Pipe(p) => {
+                                    p.source_span = source_span.clone()
+                                }
+                                R3TemplateDependencyMetadata::NgModule(_) => {}
+                            }
+                            declarations_map.insert(key, meta);
+                        }
+                    }
+                } else {
+                    // Dynamic loading failed - just add module itself as dependency
+                    // The directives/pipes should already be compiled and linked
+
+                    // Dynamic loading failed - just add module itself as dependency
+                    unresolved.push(import_name.clone());
+                    let module_meta = R3TemplateDependencyMetadata::NgModule(
+                        angular_compiler::render3::view::api::R3NgModuleDependencyMetadata {
+                            kind: angular_compiler::render3::view::api::R3TemplateDependencyKind::NgModule,
+                            type_: local_import_expr.clone(),
+                        }
+                    );
+                    declarations_map.insert(format!("module:{}", import_name), module_meta);
+                }
+            } else {
+                // Local component/directive - no external module
+
+                let mut found = false;
+                // Dynamic resolution for local components
+                if let Some(source_file) = &dir.source_file {
+                    if let Some(local_details) = metadata_reader.extract_ts_metadata(source_file) {
+                        for meta in local_details {
+                            let matches = match &meta {
+                                 R3TemplateDependencyMetadata::Directive(d) => {
+                                     if let angular_compiler::output::output_ast::Expression::ReadVar(rv) = &d.type_ {
+                                         rv.name == import_name
+                                     } else {
+                                         false
+                                     }
+                                 },
+                                 R3TemplateDependencyMetadata::Pipe(p) => {
+                                     if let angular_compiler::output::output_ast::Expression::ReadVar(rv) = &p.type_ {
+                                          rv.name == import_name
+                                     } else {
+                                          false
+                                     }
+                                 },
+                                 R3TemplateDependencyMetadata::NgModule(n) => {
+                                      if let angular_compiler::output::output_ast::Expression::ReadVar(rv) = &n.type_ {
+                                          rv.name == import_name
+                                      } else {
+                                          false
+                                      }
+                                 }
+                            };
+
+                            if matches {
+                                let key = match &meta {
+                                    R3TemplateDependencyMetadata::Directive(d) => {
+                                        format!("dir:{}", d.selector)
+                                    }
+                                    R3TemplateDependencyMetadata::Pipe(p) => {
+                                        format!("pipe:{}", p.name)
+                                    }
+                                    R3TemplateDependencyMetadata::NgModule(_) => {
+                                        format!("module:{}", import_name)
+                                    }
+                                };
+                                declarations_map.insert(key, meta.clone());
+                                found = true;
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                if !found {
+                    // Unknown local - create placeholder with ReadVar
+                    unresolved.push(import_name.clone());
+                    let meta = R3TemplateDependencyMetadata::Directive(angular_compiler::render3::view::api::R3DirectiveDependencyMetadata {
+                        selector: "".to_string(),
+                        type_: local_import_expr,
+                        inputs: vec![],
+                        outputs: vec![],
+                        export_as: vec![].into(),
+                        kind: angular_compiler::render3::view::api::R3TemplateDependencyKind::Directive,
+                        is_component: false,
+                        source_span: source_span.clone(),
+                    });
+                    declarations_map.insert(format!("unknown:{}", import_name), meta);
+                }
+            }
+        }
+    }
+
+    (declarations_map, unresolved)
+}
+
+/// Inputs of well-known directives from other packages whose bound expression narrows
+/// the type of their template's context, as declared by `ngTemplateGuard_*` members.
+const KNOWN_BINDING_TEMPLATE_GUARDS: &[(&str, &str)] = &[("NgIf", "ngIf")];
+
+/// Functions of `@angular/core` whose result is a signal, e.g. `count = signal(0)`.
+const SIGNAL_FUNCTIONS: &[&str] = &[
+    "signal",
+    "computed",
+    "linkedSignal",
+    "input",
+    "model",
+    "toSignal",
+    "viewChild",
+    "viewChildren",
+    "contentChild",
+    "contentChildren",
+];
+
+/// Whether a property initializer is an arrow function or function expression.
+fn is_function_expression(value: &oxc_ast::ast::Expression<'_>) -> bool {
+    matches!(
+        value.without_parentheses(),
+        oxc_ast::ast::Expression::ArrowFunctionExpression(_)
+            | oxc_ast::ast::Expression::FunctionExpression(_)
+    )
+}
+
+/// Whether a property initializer creates a signal, e.g. `signal(0)` or
+/// `input.required<string>()`.
+fn is_signal_initializer(value: &oxc_ast::ast::Expression<'_>) -> bool {
+    let oxc_ast::ast::Expression::CallExpression(call) = value.without_parentheses() else {
+        return false;
+    };
+    let name = match &call.callee {
+        oxc_ast::ast::Expression::Identifier(id) => id.name.as_str(),
+        oxc_ast::ast::Expression::StaticMemberExpression(member)
+            if member.property.name == "required" =>
+        {
+            match &member.object {
+                oxc_ast::ast::Expression::Identifier(id) => id.name.as_str(),
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+    SIGNAL_FUNCTIONS.contains(&name)
+}
+
+/// The import declarations of a source file, as written.
+fn source_import_declarations(source: &str) -> Vec<String> {
+    let allocator = oxc_allocator::Allocator::default();
+    let source_type = oxc_span::SourceType::ts().with_module(true);
+    let ret = oxc_parser::Parser::new(&allocator, source, source_type).parse();
+    ret.program
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt.as_module_declaration() {
+            Some(oxc_ast::ast::ModuleDeclaration::ImportDeclaration(import_decl)) => Some(
+                source[import_decl.span.start as usize..import_decl.span.end as usize].to_string(),
+            ),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The expression referencing a template dependency from a type-check file. Dependencies
+/// read from other modules are referenced through a namespace import of the module,
+/// whose alias is recorded in `namespaces`.
+fn type_check_reference(
+    type_: &Expression,
+    namespaces: &mut IndexMap<String, String>,
+) -> Option<String> {
+    match type_ {
+        Expression::ReadVar(rv) => Some(rv.name.clone()),
+        Expression::External(ext) => {
+            let name = ext.value.name.as_ref()?;
+            match &ext.value.module_name {
+                Some(module_name) => {
+                    let next = namespaces.len() + 1;
+                    let alias = namespaces
+                        .entry(module_name.clone())
+                        .or_insert_with(|| format!("_tcbi{}", next));
+                    Some(format!("{}.{}", alias, name))
+                }
+                None => Some(name.clone()),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Type-check metadata of a directive analyzed in the current compilation.
+/// The directive named `name` among those analyzed in the current compilation.
+fn analyzed_directive<'a>(
+    analyzed: &'a [DirectiveMetadata<'static>],
+    name: &str,
+) -> Option<&'a DirectiveMeta<'static>> {
+    analyzed.iter().find_map(|meta| match meta {
+        DecoratorMetadata::Directive(dir) if dir.t2.name == name => Some(dir),
+        _ => None,
+    })
+}
+
+fn type_checkable_directive(
+    dir: &DirectiveMeta<'_>,
+    reference: String,
+) -> TypeCheckableDirectiveMeta {
+    let inputs = dir
+        .t2
+        .inputs
+        .iter()
+        .map(|(_, input)| TypeCheckInput {
+            binding_property_name: input.binding_property_name.clone(),
+            class_property_name: Some(input.class_property_name.clone()),
+            is_signal: input.is_signal,
+            required: input.required,
+        })
+        .collect();
+    let outputs = dir
+        .t2
+        .outputs
+        .iter()
+        .map(|(_, output)| TypeCheckOutput {
+            binding_property_name: output.binding_property_name.clone(),
+            class_property_name: Some(output.class_property_name.clone()),
+        })
+        .collect();
+
+    // Inputs with a transform accept a different type than their class property.
+    let mut coerced_input_fields = dir.type_check.coerced_input_fields.clone();
+    coerced_input_fields.extend(
+        dir.t2
+            .inputs
+            .iter()
+            .filter(|(_, input)| input.transform.is_some())
+            .map(|(_, input)| input.class_property_name.clone()),
+    );
+
+    TypeCheckableDirectiveMeta {
+        name: dir.t2.name.clone(),
+        reference,
+        selector: dir.t2.selector.clone(),
+        is_component: dir.t2.is_component,
+        is_structural: dir.t2.is_structural,
+        inputs: TypeCheckInputs(inputs),
+        outputs: TypeCheckOutputs(outputs),
+        export_as: dir.t2.export_as.clone(),
+        ng_template_guards: dir
+            .type_check
+            .ng_template_guards
+            .iter()
+            .map(|guard| TemplateGuard {
+                input_name: guard.input_name.clone(),
+                kind: match guard.guard_type {
+                    TemplateGuardType::Binding => TemplateGuardKind::Binding,
+                    TemplateGuardType::Invocation => TemplateGuardKind::Invocation,
+                },
+            })
+            .collect(),
+        has_ng_template_context_guard: dir.type_check.has_ng_template_context_guard,
+        coerced_input_fields,
+    }
+}
+
+/// Type-check metadata of a directive read from another module. Only the binding names
+/// of its inputs and outputs are known, so bindings to them are evaluated but not checked
+/// against the directive's type.
+fn type_checkable_dependency(
+    dep: &R3DirectiveDependencyMetadata,
+    name: String,
+    reference: String,
+) -> TypeCheckableDirectiveMeta {
+    let ng_template_guards = KNOWN_BINDING_TEMPLATE_GUARDS
+        .iter()
+        .filter(|(directive, _)| *directive == name)
+        .map(|(_, input)| TemplateGuard {
+            input_name: input.to_string(),
+            kind: TemplateGuardKind::Binding,
+        })
+        .collect();
+    TypeCheckableDirectiveMeta {
+        selector: Some(dep.selector.clone()),
+        is_component: dep.is_component,
+        inputs: TypeCheckInputs(
+            dep.inputs
+                .iter()
+                .map(|input| TypeCheckInput {
+                    binding_property_name: input.clone(),
+                    class_property_name: None,
+                    is_signal: false,
+                    required: false,
+                })
+                .collect(),
+        ),
+        outputs: TypeCheckOutputs(
+            dep.outputs
+                .iter()
+                .map(|output| TypeCheckOutput {
+                    binding_property_name: output.clone(),
+                    class_property_name: None,
+                })
+                .collect(),
+        ),
+        export_as: dep.export_as.clone(),
+        ng_template_guards,
+        name,
+        reference,
+        ..Default::default()
+    }
+}
+
+/// A value import specifier of the component's source file.
+struct ImportedSymbol {
+    local_name: String,
+    imported_name: String,
+    import_path: String,
+    is_default_import: bool,
+    /// Whether the symbol is referenced only once, i.e. by the `imports` array.
+    single_reference: bool,
+}
+
+/// Find the component dependencies that are only used within `@defer` blocks and whose
+/// imports can be removed from the source file, so that they end up in a lazy chunk.
+///
+/// A dependency is deferrable if it is imported into the file, referenced nowhere but in
+/// the `imports` array, and every other value imported by the same import declaration is
+/// deferrable as well.
+fn find_deferrable_dependencies(
+    source_file: &std::path::Path,
+    template: &str,
+    declarations: &IndexMap<String, R3TemplateDependencyMetadata>,
+    metadata_reader: &ModuleMetadataReader,
+) -> Vec<DeferrableDependency> {
+    if !template.contains("@defer") {
+        return vec![];
+    }
+    let Ok(content) = std::fs::read_to_string(source_file) else {
+        return vec![];
+    };
+    let allocator = oxc_allocator::Allocator::default();
+    let source_type = oxc_span::SourceType::ts().with_module(true);
+    let ret = oxc_parser::Parser::new(&allocator, &content, source_type).parse();
+    if !ret.errors.is_empty() {
+        return vec![];
+    }
+    let semantic = oxc_semantic::SemanticBuilder::new().build(&ret.program);
+    let scoping = semantic.semantic.scoping();
+
+    // Value imports of the file, grouped by import declaration.
+    let mut import_groups: Vec<Vec<ImportedSymbol>> = vec![];
+    for stmt in &ret.program.body {
+        let oxc_ast::ast::Statement::ImportDeclaration(import_decl) = stmt else {
+            continue;
+        };
+        if import_decl.import_kind.is_type() {
+            continue;
+        }
+        let Some(specifiers) = &import_decl.specifiers else {
+            continue;
+        };
+        let import_path = import_decl.source.value.to_string();
+        let mut group = vec![];
+        for spec in specifiers {
+            let (local, imported_name, is_default_import) = match spec {
+                oxc_ast::ast::ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                    if s.import_kind.is_type() {
+                        continue;
+                    }
+                    let imported_name = match &s.imported {
+                        oxc_ast::ast::ModuleExportName::IdentifierName(id) => id.name.to_string(),
+                        oxc_ast::ast::ModuleExportName::IdentifierReference(id) => {
+                            id.name.to_string()
+                        }
+                        oxc_ast::ast::ModuleExportName::StringLiteral(lit) => lit.value.to_string(),
+                    };
+                    (&s.local, imported_name, false)
+                }
+                oxc_ast::ast::ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => {
+                    (&s.local, s.local.name.to_string(), true)
+                }
+                // Namespace imports can't be split into individual dynamic imports.
+                oxc_ast::ast::ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                    (&s.local, String::new(), false)
+                }
+            };
+            let single_reference = !imported_name.is_empty()
+                && local.symbol_id.get().is_some_and(|symbol_id| {
+                    scoping.get_resolved_reference_ids(symbol_id).len() == 1
+                });
+            group.push(ImportedSymbol {
+                local_name: local.name.to_string(),
+                imported_name,
+                import_path: import_path.clone(),
+                is_default_import,
+                single_reference,
+            });
+        }
+        import_groups.push(group);
+    }
+
+    // Selectors and pipe names of the dependencies, keyed by local name.
+    let source_dir = source_file.parent().unwrap_or(std::path::Path::new("."));
+    let mut selectors: IndexMap<String, String> = IndexMap::new();
+    let mut pipe_names: IndexMap<String, String> = IndexMap::new();
+    for symbol in import_groups.iter().flatten() {
+        if !symbol.single_reference {
+            continue;
+        }
+        let matches_symbol = |type_: &Expression, name: &str| matches!(type_, Expression::ReadVar(rv) if rv.name == name);
+        let mut found = false;
+        for meta in declarations.values() {
+            match meta {
+                R3TemplateDependencyMetadata::Directive(d)
+                    if !d.selector.is_empty() && matches_symbol(&d.type_, &symbol.local_name) =>
+                {
+                    selectors.insert(symbol.local_name.clone(), d.selector.clone());
+                    found = true;
+                }
+                R3TemplateDependencyMetadata::Pipe(p)
+                    if matches_symbol(&p.type_, &symbol.local_name) =>
+                {
+                    pipe_names.insert(symbol.local_name.clone(), p.name.clone());
+                    found = true;
+                }
+                _ => {}
+            }
+        }
+        // Relative imports aren't resolved by the metadata reader, so read the file itself.
+        if found || !symbol.import_path.starts_with('.') {
+            continue;
+        }
+        let base = source_dir.join(&symbol.import_path);
+        let candidates = [
+            PathBuf::from(format!("{}.ts", base.display())),
+            base.join("index.ts"),
+        ];
+        let Some(metas) = candidates
+            .iter()
+            .find(|path| path.is_file())
+            .and_then(|path| metadata_reader.extract_ts_metadata(path))
+        else {
+            continue;
+        };
+        for meta in metas {
+            match meta {
+                R3TemplateDependencyMetadata::Directive(d)
+                    if !d.selector.is_empty()
+                        && matches_symbol(&d.type_, &symbol.imported_name) =>
+                {
+                    selectors.insert(symbol.local_name.clone(), d.selector);
+                }
+                R3TemplateDependencyMetadata::Pipe(p)
+                    if matches_symbol(&p.type_, &symbol.imported_name) =>
+                {
+                    pipe_names.insert(symbol.local_name.clone(), p.name);
+                }
+                _ => {}
+            }
+        }
+    }
+    if selectors.is_empty() && pipe_names.is_empty() {
+        return vec![];
+    }
+
+    let matching = find_matching_directives_and_pipes(
+        template,
+        &selectors.values().cloned().collect::<Vec<_>>(),
+    );
+    let is_deferrable = |symbol: &ImportedSymbol| {
+        symbol.single_reference
+            && (selectors.get(&symbol.local_name).is_some_and(|selector| {
+                matching.directives.defer_candidates.contains(selector)
+                    && !matching.directives.regular.contains(selector)
+            }) || pipe_names.get(&symbol.local_name).is_some_and(|name| {
+                matching.pipes.defer_candidates.contains(name)
+                    && !matching.pipes.regular.contains(name)
+            }))
+    };
+
+    import_groups
+        .iter()
+        .filter(|group| !group.is_empty() && group.iter().all(|symbol| is_deferrable(symbol)))
+        .flatten()
+        .map(|symbol| DeferrableDependency {
+            local_name: symbol.local_name.clone(),
+            selector: selectors.get(&symbol.local_name).cloned(),
+            pipe_name: pipe_names.get(&symbol.local_name).cloned(),
+            dependency: R3DeferPerComponentDependency {
+                symbol_name: symbol.imported_name.clone(),
+                import_path: symbol.import_path.clone(),
+                is_default_import: symbol.is_default_import,
+            },
+        })
+        .collect()
+}
+
+/// A component dependency which is only used within `@defer` blocks.
+struct DeferrableDependency {
+    /// Name the dependency is imported under.
+    local_name: String,
+    /// Selector of the directive, if the dependency is one.
+    selector: Option<String>,
+    /// Name of the pipe, if the dependency is one.
+    pipe_name: Option<String>,
+    dependency: R3DeferPerComponentDependency,
+}
+
+/// The dependency function of each `@defer` block of a template, keyed by the start
+/// offset of the block. A function returns the directives and pipes used within its
+/// block, loading the deferrable ones with a dynamic `import()`; blocks without
+/// dependencies have no function.
+fn defer_block_dependency_fns(
+    nodes: &[R3Node],
+    declarations: &IndexMap<String, R3TemplateDependencyMetadata>,
+    deferrable: &[DeferrableDependency],
+) -> HashMap<usize, Option<Expression>> {
+    let mut blocks = Vec::new();
+    visit_template(nodes, &mut |node| {
+        if let TemplateNode::DeferredBlock(block) = node {
+            blocks.push(block);
+        }
+    });
+    let selectors: Vec<String> = declarations
+        .values()
+        .filter_map(|meta| match meta {
+            R3TemplateDependencyMetadata::Directive(d) if !d.selector.is_empty() => {
+                Some(d.selector.clone())
+            }
+            _ => None,
+        })
+        .chain(deferrable.iter().filter_map(|d| d.selector.clone()))
+        .collect();
+
+    blocks
+        .into_iter()
+        .map(|block| {
+            let used = find_used_directives_and_pipes(&block.children, &selectors);
+            let mut dependencies = Vec::new();
+            for meta in declarations.values() {
+                let (type_, is_used) = match meta {
+                    R3TemplateDependencyMetadata::Directive(d) => {
+                        (&d.type_, used.directive_selectors.contains(&d.selector))
+                    }
+                    R3TemplateDependencyMetadata::Pipe(p) => {
+                        (&p.type_, used.pipes.contains(&p.name))
+                    }
+                    R3TemplateDependencyMetadata::NgModule(_) => continue,
+                };
+                if !is_used {
+                    continue;
+                }
+                let symbol_name = match type_ {
+                    Expression::ReadVar(rv) => rv.name.clone(),
+                    Expression::External(ext) => ext.value.name.clone().unwrap_or_default(),
+                    _ => String::new(),
+                };
+                dependencies.push(R3DeferPerBlockDependency {
+                    type_reference: type_.clone(),
+                    symbol_name,
+                    is_deferrable: false,
+                    import_path: None,
+                    is_default_import: false,
+                });
+            }
+            for d in deferrable {
+                let is_used = d
+                    .selector
+                    .as_ref()
+                    .is_some_and(|selector| used.directive_selectors.contains(selector))
+                    || d.pipe_name
+                        .as_ref()
+                        .is_some_and(|name| used.pipes.contains(name));
+                if !is_used {
+                    continue;
+                }
+                dependencies.push(R3DeferPerBlockDependency {
+                    type_reference: Expression::ReadVar(ReadVarExpr {
+                        name: d.local_name.clone(),
+                        type_: None,
+                        source_span: None,
+                    }),
+                    symbol_name: d.dependency.symbol_name.clone(),
+                    is_deferrable: true,
+                    import_path: Some(d.dependency.import_path.clone()),
+                    is_default_import: d.dependency.is_default_import,
+                });
+            }
+            let dependencies_fn = (!dependencies.is_empty()).then(|| {
+                compile_defer_resolver_function(&R3DeferResolverFunctionMetadata::PerBlock {
+                    dependencies,
+                })
+            });
+            (block.block.source_span.start.offset, dependencies_fn)
+        })
+        .collect()
+}
+
+/// Placeholder source name for generated code without a template span.
+const GENERATED_SOURCE: &str = "<generated>";
+
+/// Build a source map for the code emitted into `ctx`, keeping only the segments that
+/// map back to a template file. Returns `None` if nothing maps to a template.
+fn template_source_map(ctx: &EmitterVisitorContext) -> Option<SourceMap> {
+    let generated = ctx.to_source_map_generator(GENERATED_SOURCE, 0).to_json()?;
+
+    // Drop the placeholder source and renumber the remaining ones.
+    let kept: Vec<usize> = (0..generated.sources.len())
+        .filter(|&i| generated.sources[i] != GENERATED_SOURCE)
+        .collect();
+    let renumber = |index: u32| kept.iter().position(|&k| k == index as usize);
+
+    let lines: Vec<Vec<MappingSegment>> = decode_mappings(&generated.mappings)
+        .ok()?
+        .into_iter()
+        .map(|segments| {
+            segments
+                .into_iter()
+                .filter_map(|mut segment| {
+                    let original = segment.original.as_mut()?;
+                    original.source_index =
+                        Some(renumber(original.source_index.unwrap_or(0))? as u32);
+                    Some(segment)
+                })
+                .collect()
+        })
+        .collect();
+    if lines.iter().all(Vec::is_empty) {
+        return None;
+    }
+
+    let mut map = SourceMap::new(String::new());
+    map.sources = kept.iter().map(|&i| generated.sources[i].clone()).collect();
+    map.sources_content = Some(
+        kept.iter()
+            .map(|&i| generated.sources_content[i].clone())
+            .collect(),
+    );
+    map.mappings = encode_mappings(&lines);
+    Some(map)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
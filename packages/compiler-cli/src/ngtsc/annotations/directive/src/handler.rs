@@ -3,10 +3,14 @@
 // Handles @Directive decorator processing.
 
 use super::symbol::DirectiveSymbol;
+use crate::ngtsc::annotations::common::src::{
+    directive_declaration_type, factory_declaration_type,
+};
 use crate::ngtsc::metadata::{extract_directive_metadata, DecoratorMetadata, DirectiveMetadata};
 use crate::ngtsc::reflection::{ClassDeclaration, ReflectionHost, TypeScriptReflectionHost};
 use crate::ngtsc::transform::src::api::{
-    AnalysisOutput, CompileResult, DecoratorHandler, DetectResult, HandlerPrecedence,
+    AnalysisOutput, CompilationMode, CompileResult, DecoratorHandler, DetectResult,
+    HandlerPrecedence,
 };
 use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
 use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
@@ -14,6 +18,9 @@ use angular_compiler::output::output_ast::{
     Expression, ExpressionTrait, ExternalExpr, ExternalReference, LiteralExpr, LiteralValue,
     ReadVarExpr,
 };
+use angular_compiler::render3::partial::{
+    compile_declare_directive_from_metadata, compile_declare_factory_function,
+};
 use angular_compiler::render3::r3_factory::{
     compile_factory_function, DepsOrInvalid, FactoryTarget, R3ConstructorFactoryMetadata,
     R3DependencyMetadata, R3FactoryMetadata,
@@ -142,6 +149,15 @@ impl DecoratorHandler<DirectiveHandlerData, DirectiveHandlerData, DirectiveSymbo
     ) -> Vec<CompileResult> {
         self.compile_ivy(analysis, None)
     }
+
+    fn compile_partial(
+        &self,
+        _node: &ClassDeclaration,
+        analysis: &DirectiveHandlerData,
+        _resolution: Option<&()>,
+    ) -> Vec<CompileResult> {
+        self.compile_with_mode(analysis, None, CompilationMode::Partial)
+    }
 }
 use crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager;
 
@@ -151,7 +167,17 @@ impl DirectiveDecoratorHandler {
         analysis: &DirectiveMetadata,
         external_import_manager: Option<&mut EmitterImportManager>,
     ) -> Vec<CompileResult> {
-        // ... (existing code)
+        self.compile_with_mode(analysis, external_import_manager, CompilationMode::Full)
+    }
+
+    /// Compile the `ɵfac` and `ɵdir` fields. In partial mode these are emitted as
+    /// `ɵɵngDeclareFactory` / `ɵɵngDeclareDirective` calls for the linker to process.
+    pub fn compile_with_mode(
+        &self,
+        analysis: &DirectiveMetadata,
+        external_import_manager: Option<&mut EmitterImportManager>,
+        mode: CompilationMode,
+    ) -> Vec<CompileResult> {
         // Extract DirectiveMeta from DecoratorMetadata enum
         let dir = match analysis {
             DecoratorMetadata::Directive(d) => d,
@@ -322,9 +348,10 @@ impl DirectiveDecoratorHandler {
         );
 
         // 2. Compile Directive Definition (ɵdir)
-        // 2. Compile Directive Definition (ɵdir)
-        let compiled_dir =
-            compile_directive_from_metadata(&r3_meta, &mut constant_pool, &mut binding_parser);
+        let compiled_dir = match mode {
+            CompilationMode::Partial => compile_declare_directive_from_metadata(&r3_meta),
+            _ => compile_directive_from_metadata(&r3_meta, &mut constant_pool, &mut binding_parser),
+        };
 
         // 3. Compile Factory (ɵfac)
         let deps: Option<angular_compiler::render3::r3_factory::DepsOrInvalid> =
@@ -410,7 +437,10 @@ impl DirectiveDecoratorHandler {
             target: FactoryTarget::Directive,
         });
 
-        let compiled_fac = compile_factory_function(&factory_meta);
+        let compiled_fac = match mode {
+            CompilationMode::Partial => compile_declare_factory_function(&factory_meta),
+            _ => compile_factory_function(&factory_meta),
+        };
 
         // 4. Emit
         // Use ImportManager to track and map imports dynamically
@@ -459,19 +489,23 @@ impl DirectiveDecoratorHandler {
                 name: "ɵfac".to_string(),
                 initializer: Some(fac_initializer),
                 statements: vec![],
-                type_desc: "FactoryDef".to_string(),
+                type_desc: factory_declaration_type(&dir.t2.name, &dir.constructor_params),
                 deferrable_imports: None,
                 diagnostics: vec![],
                 additional_imports: additional_imports.clone(),
+                initializer_map: None,
+                statement_maps: Vec::new(),
             },
             CompileResult {
                 name: "ɵdir".to_string(),
                 initializer: Some(dir_initializer),
                 statements: vec![],
-                type_desc: "DirectiveDef".to_string(),
+                type_desc: directive_declaration_type(dir),
                 deferrable_imports: None,
                 diagnostics: vec![],
                 additional_imports,
+                initializer_map: None,
+                statement_maps: Vec::new(),
             },
         ]
     }
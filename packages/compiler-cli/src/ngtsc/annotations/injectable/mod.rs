@@ -0,0 +1,5 @@
+//! Injectable decorator handler module
+
+pub mod src;
+
+pub use src::handler::InjectableDecoratorHandler;
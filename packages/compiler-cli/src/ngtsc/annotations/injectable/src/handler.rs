@@ -0,0 +1,363 @@
+//! Injectable decorator handler
+//!
+//! Handles @Injectable decorator and generates the ɵfac and ɵprov definitions.
+
+use crate::ngtsc::annotations::common::src::{factory_declaration_type, resolve_constructor_deps};
+use crate::ngtsc::diagnostics::Diagnostic;
+use crate::ngtsc::metadata::InjectableMeta;
+use crate::ngtsc::transform::src::api::{CompilationMode, CompileResult};
+use crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager;
+use angular_compiler::compiler::{compile_injectable, R3InjectableMetadata};
+use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
+use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+use angular_compiler::output::output_ast::{
+    Expression, ExpressionTrait, LiteralExpr, LiteralValue, ReadVarExpr,
+};
+use angular_compiler::render3::partial::{
+    compile_declare_factory_function, compile_declare_injectable_from_metadata,
+};
+use angular_compiler::render3::r3_factory::{
+    compile_factory_function, DepsOrInvalid, FactoryTarget, R3ConstructorFactoryMetadata,
+    R3FactoryMetadata,
+};
+use angular_compiler::render3::util::{ForwardRefHandling, MaybeForwardRefExpression, R3Reference};
+
+const ANGULAR_CORE: &str = "@angular/core";
+
+/// Handler for @Injectable decorator
+pub struct InjectableDecoratorHandler {
+    is_core: bool,
+}
+
+impl InjectableDecoratorHandler {
+    pub fn new(is_core: bool) -> Self {
+        Self { is_core }
+    }
+
+    /// Compile the `ɵfac` and `ɵprov` fields. In partial mode these are emitted as
+    /// `ɵɵngDeclareFactory` / `ɵɵngDeclareInjectable` calls for the linker to process.
+    ///
+    /// Constructor parameters are injected with `ɵɵinject`. A parameter without an
+    /// injection token produces an invalid factory, and an NG2003 diagnostic unless the
+    /// class is provided through `useClass`, `useFactory`, `useExisting` or `useValue`.
+    pub fn compile_with_mode(
+        &self,
+        inj: &InjectableMeta,
+        external_import_manager: Option<&mut EmitterImportManager>,
+        mode: CompilationMode,
+    ) -> Vec<CompileResult> {
+        let mut local_manager = EmitterImportManager::new();
+        let import_manager = external_import_manager.unwrap_or(&mut local_manager);
+        let _ = import_manager.get_or_generate_alias(ANGULAR_CORE);
+
+        let type_ref = class_reference(&inj.name);
+        let (deps, diagnostics) = self.constructor_deps(inj, import_manager);
+
+        // 1. Compile Factory (ɵfac)
+        let factory_meta = R3FactoryMetadata::Constructor(R3ConstructorFactoryMetadata {
+            name: inj.name.clone(),
+            type_: type_ref.clone(),
+            type_argument_count: 0,
+            deps: Some(deps),
+            target: FactoryTarget::Injectable,
+        });
+        let fac_expr = match mode {
+            CompilationMode::Partial => compile_declare_factory_function(&factory_meta).expression,
+            _ => compile_factory_function(&factory_meta).expression,
+        };
+
+        let imports_map = import_manager.get_imports_map();
+        let mut emitter = AbstractJsEmitterVisitor::with_imports(imports_map.clone());
+        let fac_initializer = emit(&fac_expr, &mut emitter);
+
+        // 2. Compile Injectable (ɵprov)
+        let meta = R3InjectableMetadata {
+            name: inj.name.clone(),
+            type_: type_ref,
+            type_argument_count: 0,
+            provided_in: inj
+                .provided_in
+                .clone()
+                .unwrap_or(MaybeForwardRefExpression {
+                    expression: Expression::Literal(LiteralExpr {
+                        value: LiteralValue::Null,
+                        type_: None,
+                        source_span: None,
+                    }),
+                    forward_ref: ForwardRefHandling::None,
+                }),
+            use_class: inj.use_class.clone(),
+            use_factory: inj.use_factory.clone(),
+            use_existing: inj.use_existing.clone(),
+            use_value: inj.use_value.clone(),
+            deps: inj.deps.clone(),
+        };
+        let prov_expr = match mode {
+            CompilationMode::Partial => compile_declare_injectable_from_metadata(&meta).expression,
+            _ => compile_injectable(&meta, false).expression,
+        };
+        let prov_initializer = emit(&prov_expr, &mut emitter);
+
+        // Only import the modules the emitted code actually references.
+        let used_imports = emitter.used_imports;
+        let mut additional_imports: Vec<(String, String)> = imports_map
+            .into_iter()
+            .filter(|(module, _)| used_imports.contains(module) || module == ANGULAR_CORE)
+            .map(|(module, alias)| (alias, module))
+            .collect();
+        additional_imports.sort();
+
+        vec![
+            CompileResult {
+                name: "ɵfac".to_string(),
+                initializer: Some(fac_initializer),
+                statements: vec![],
+                type_desc: factory_declaration_type(&inj.name, &inj.constructor_params),
+                deferrable_imports: None,
+                diagnostics,
+                additional_imports: additional_imports.clone(),
+                initializer_map: None,
+                statement_maps: Vec::new(),
+            },
+            CompileResult {
+                name: "ɵprov".to_string(),
+                initializer: Some(prov_initializer),
+                statements: vec![],
+                type_desc: format!("i0.ɵɵInjectableDeclaration<{}>", inj.name),
+                deferrable_imports: None,
+                diagnostics: vec![],
+                additional_imports,
+                initializer_map: None,
+                statement_maps: Vec::new(),
+            },
+        ]
+    }
+
+    /// Resolve the constructor parameters of `inj` to factory dependencies. A missing
+    /// injection token is only reported when the provider instantiates the class.
+    fn constructor_deps(
+        &self,
+        inj: &InjectableMeta,
+        import_manager: &mut EmitterImportManager,
+    ) -> (DepsOrInvalid, Vec<Diagnostic>) {
+        let (deps, diagnostics) = resolve_constructor_deps(
+            &inj.name,
+            &inj.constructor_params,
+            inj.file_imports.as_ref(),
+            inj.source_file.as_deref(),
+            self.is_core,
+            import_manager,
+        );
+        if requires_valid_ctor(inj) {
+            (deps, diagnostics)
+        } else {
+            (deps, vec![])
+        }
+    }
+}
+
+/// Whether the class itself is instantiated by the provider, in which case its constructor
+/// must be injectable.
+fn requires_valid_ctor(inj: &InjectableMeta) -> bool {
+    inj.use_value.is_none()
+        && inj.use_existing.is_none()
+        && inj.use_class.is_none()
+        && inj.use_factory.is_none()
+}
+
+fn class_reference(class_name: &str) -> R3Reference {
+    let expr = read_var(class_name);
+    R3Reference {
+        value: expr.clone(),
+        type_expr: expr,
+    }
+}
+
+fn read_var(name: &str) -> Expression {
+    Expression::ReadVar(ReadVarExpr {
+        name: name.to_string(),
+        type_: None,
+        source_span: None,
+    })
+}
+
+fn emit(expr: &Expression, emitter: &mut AbstractJsEmitterVisitor) -> String {
+    let mut ctx = EmitterVisitorContext::create_root();
+    expr.visit_expression(emitter, &mut ctx);
+    ctx.to_source()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ngtsc::metadata::src::api::ConstructorParam;
+    use angular_compiler::render3::r3_factory::R3DependencyMetadata;
+    use std::collections::HashMap;
+
+    fn string_literal(value: &str) -> Expression {
+        Expression::Literal(LiteralExpr {
+            value: LiteralValue::String(value.to_string()),
+            type_: None,
+            source_span: None,
+        })
+    }
+
+    fn injectable(constructor_params: Vec<ConstructorParam>) -> InjectableMeta {
+        InjectableMeta {
+            name: "Service".to_string(),
+            provided_in: Some(MaybeForwardRefExpression {
+                expression: string_literal("root"),
+                forward_ref: ForwardRefHandling::None,
+            }),
+            use_class: None,
+            use_factory: None,
+            use_existing: None,
+            use_value: None,
+            deps: None,
+            source_file: Some("service.ts".into()),
+            constructor_params,
+            file_imports: Some(HashMap::from([
+                ("Api".to_string(), "./api".to_string()),
+                ("Injector".to_string(), ANGULAR_CORE.to_string()),
+            ])),
+        }
+    }
+
+    fn param(name: &str, type_name: Option<&str>) -> ConstructorParam {
+        ConstructorParam {
+            name: Some(name.to_string()),
+            type_name: type_name.map(str::to_string),
+            span: Some((20, 8)),
+            ..ConstructorParam::default()
+        }
+    }
+
+    #[test]
+    fn injects_constructor_dependencies() {
+        let handler = InjectableDecoratorHandler::new(false);
+        let results = handler.compile_with_mode(
+            &injectable(vec![
+                param("api", Some("Api")),
+                ConstructorParam {
+                    optional: true,
+                    skip_self: true,
+                    ..param("injector", Some("Injector"))
+                },
+                ConstructorParam {
+                    inject_token: Some("API_URL".to_string()),
+                    ..param("url", Some("string"))
+                },
+                ConstructorParam {
+                    inject_token: Some("\"locale\"".to_string()),
+                    ..param("locale", None)
+                },
+            ]),
+            None,
+            CompilationMode::Full,
+        );
+
+        let fac = results[0].initializer.as_deref().unwrap();
+        assert!(
+            fac.contains(
+                "i0.ɵɵinject(i1.Api), i0.ɵɵinject(i0.Injector, 12), \
+                 i0.ɵɵinject(API_URL), i0.ɵɵinject(\"locale\")"
+            ),
+            "{}",
+            fac
+        );
+        assert!(results[0].diagnostics.is_empty());
+        assert_eq!(
+            results[0].additional_imports,
+            vec![
+                ("i0".to_string(), ANGULAR_CORE.to_string()),
+                ("i1".to_string(), "./api".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_parameters_without_a_token() {
+        let handler = InjectableDecoratorHandler::new(false);
+        let results = handler.compile_with_mode(
+            &injectable(vec![param("value", None)]),
+            None,
+            CompilationMode::Full,
+        );
+
+        let fac = results[0].initializer.as_deref().unwrap();
+        assert!(fac.contains("i0.ɵɵinvalidFactory()"), "{}", fac);
+        let diagnostic = &results[0].diagnostics[0];
+        assert_eq!(diagnostic.code, 2003);
+        assert_eq!((diagnostic.start, diagnostic.length), (20, 8));
+        assert!(diagnostic
+            .message_text
+            .to_string()
+            .starts_with("No suitable injection token for parameter 'value' of class 'Service'."));
+    }
+
+    #[test]
+    fn defines_provider_for_root() {
+        let handler = InjectableDecoratorHandler::new(false);
+        let results = handler.compile_with_mode(&injectable(vec![]), None, CompilationMode::Full);
+
+        let prov = results[1].initializer.as_deref().unwrap();
+        assert!(
+            prov.contains(
+                "i0.ɵɵdefineInjectable({token: Service, factory: Service.ɵfac, \
+                 providedIn: \"root\"})"
+            ),
+            "{}",
+            prov
+        );
+    }
+
+    #[test]
+    fn defines_use_factory_provider() {
+        let handler = InjectableDecoratorHandler::new(false);
+        let results = handler.compile_with_mode(
+            &InjectableMeta {
+                use_factory: Some(read_var("createService")),
+                deps: Some(vec![R3DependencyMetadata {
+                    token: Some(read_var("Api")),
+                    attribute_name_type: None,
+                    host: false,
+                    optional: true,
+                    self_: false,
+                    skip_self: false,
+                }]),
+                ..injectable(vec![param("value", None)])
+            },
+            None,
+            CompilationMode::Full,
+        );
+
+        let prov = results[1].initializer.as_deref().unwrap();
+        assert!(
+            prov.contains("createService(i0.ɵɵinject(Api, 8))"),
+            "{}",
+            prov
+        );
+        // The class constructor is not used by the provider, so it may be invalid.
+        assert!(results[0].diagnostics.is_empty());
+    }
+
+    #[test]
+    fn defines_use_existing_provider_in_partial_mode() {
+        let handler = InjectableDecoratorHandler::new(false);
+        let results = handler.compile_with_mode(
+            &InjectableMeta {
+                use_existing: Some(MaybeForwardRefExpression {
+                    expression: read_var("Other"),
+                    forward_ref: ForwardRefHandling::None,
+                }),
+                ..injectable(vec![])
+            },
+            None,
+            CompilationMode::Partial,
+        );
+
+        let prov = results[1].initializer.as_deref().unwrap();
+        assert!(prov.contains("i0.ɵɵngDeclareInjectable({"), "{}", prov);
+        assert!(prov.contains("useExisting: Other"), "{}", prov);
+    }
+}
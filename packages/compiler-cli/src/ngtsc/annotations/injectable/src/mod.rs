@@ -0,0 +1,3 @@
+//! Injectable handler source module
+
+pub mod handler;
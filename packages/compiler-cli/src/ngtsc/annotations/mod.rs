@@ -1,5 +1,6 @@
 pub mod common;
 pub mod component;
 pub mod directive;
+pub mod injectable;
 pub mod ng_module;
 pub mod pipe;
@@ -252,6 +252,8 @@ impl DecoratorHandler<NgModuleAnalysis, NgModuleAnalysis, NgModuleSymbol, NgModu
                 deferrable_imports: None,
                 diagnostics: Vec::new(),
                 additional_imports: Vec::new(),
+                initializer_map: None,
+                statement_maps: Vec::new(),
             },
             CompileResult {
                 name: "ɵinj".to_string(),
@@ -261,6 +263,8 @@ impl DecoratorHandler<NgModuleAnalysis, NgModuleAnalysis, NgModuleSymbol, NgModu
                 deferrable_imports: None,
                 diagnostics: Vec::new(),
                 additional_imports: Vec::new(),
+                initializer_map: None,
+                statement_maps: Vec::new(),
             },
             CompileResult {
                 name: "ɵfac".to_string(),
@@ -270,6 +274,8 @@ impl DecoratorHandler<NgModuleAnalysis, NgModuleAnalysis, NgModuleSymbol, NgModu
                 deferrable_imports: None,
                 diagnostics: Vec::new(),
                 additional_imports: Vec::new(),
+                initializer_map: None,
+                statement_maps: Vec::new(),
             },
         ]
     }
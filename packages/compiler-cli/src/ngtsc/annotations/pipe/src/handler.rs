@@ -95,6 +95,8 @@ impl PipeDecoratorHandler {
             deferrable_imports: None,
             diagnostics: Vec::new(),
             additional_imports: Vec::new(),
+            initializer_map: None,
+            statement_maps: Vec::new(),
         }
     }
 }
@@ -9,6 +9,7 @@ use oxc_allocator::{Allocator, CloneIn};
 use oxc_ast::ast::*;
 use oxc_ast::AstBuilder;
 use oxc_span::SPAN;
+use std::collections::HashSet;
 
 /// Represents a static property that failed to be parsed and needs to be injected via string manipulation
 #[derive(Debug, Clone)]
@@ -17,10 +18,29 @@ pub struct FailedProperty {
     pub prop_name: String,
     pub placeholder: String,
     pub expr: String,
+    /// Whether the placeholder stands for whole statements rather than an expression.
+    pub statement: bool,
+}
+
+impl FailedProperty {
+    /// The text codegen prints for the placeholder, to be replaced with `expr`.
+    /// Codegen with `single_quote: true` prints the string literal as 'placeholder'.
+    pub fn placeholder_text(&self) -> String {
+        if self.statement {
+            format!("'{}';", self.placeholder)
+        } else {
+            format!("'{}'", self.placeholder)
+        }
+    }
 }
 
 /// Transform a parsed program to add Ivy compilation results
 /// Returns Vec of properties that couldn't be parsed and need to be injected into class bodies
+///
+/// With `raw` set, the hoisted statements and all static properties are injected as
+/// placeholders so their text reaches the output verbatim, e.g. to keep source maps
+/// of the generated code intact.
+#[allow(clippy::too_many_arguments)]
 pub fn transform_component_ast<'a>(
     allocator: &'a Allocator,
     program: &mut Program<'a>,
@@ -29,6 +49,7 @@ pub fn transform_component_ast<'a>(
     fac_expr_str: &'a str,
     definitions: &[(&str, &str)],
     additional_imports: &[(String, String)],
+    raw: bool,
 ) -> Vec<FailedProperty> {
     // 1. Remove Angular decorators from the class
     remove_angular_decorators(program, component_name);
@@ -42,6 +63,17 @@ pub fn transform_component_ast<'a>(
 
     ensure_imports(allocator, program, &imports);
 
+    if raw {
+        return add_raw_placeholders(
+            allocator,
+            program,
+            component_name,
+            hoisted_statements,
+            fac_expr_str,
+            definitions,
+        );
+    }
+
     // 3. Add hoisted statements to program body (after all imports)
     add_hoisted_statements(allocator, program, hoisted_statements);
 
@@ -87,6 +119,37 @@ pub fn remove_angular_decorators<'a>(program: &mut Program<'a>, class_name: &str
     }
 }
 
+/// Remove the import specifiers of symbols that are loaded lazily by `@defer` blocks,
+/// dropping import declarations that become empty.
+pub fn remove_deferred_imports(program: &mut Program<'_>, local_names: &HashSet<String>) {
+    let mut emptied = Vec::new();
+    for stmt in program.body.iter_mut() {
+        let Statement::ImportDeclaration(import) = stmt else {
+            continue;
+        };
+        let Some(specifiers) = &mut import.specifiers else {
+            continue;
+        };
+        if specifiers.is_empty() {
+            continue;
+        }
+        specifiers.retain(|spec| {
+            let local = match spec {
+                ImportDeclarationSpecifier::ImportSpecifier(s) => &s.local,
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => &s.local,
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => &s.local,
+            };
+            !local_names.contains(local.name.as_str())
+        });
+        if specifiers.is_empty() {
+            emptied.push(import.span);
+        }
+    }
+    program.body.retain(
+        |stmt| !matches!(stmt, Statement::ImportDeclaration(import) if emptied.contains(&import.span)),
+    );
+}
+
 /// Remove Angular decorators from a class
 fn remove_decorators_from_class(class: &mut Class) {
     // Angular decorators to remove from class declaration
@@ -202,6 +265,71 @@ fn add_hoisted_statements<'a>(
     }
 }
 
+/// Inject the hoisted statements and static properties as placeholders only.
+fn add_raw_placeholders<'a>(
+    allocator: &'a Allocator,
+    program: &mut Program<'a>,
+    class_name: &str,
+    hoisted_statements: &str,
+    fac_expr_str: &str,
+    definitions: &[(&str, &str)],
+) -> Vec<FailedProperty> {
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    let mut failed_properties = Vec::new();
+
+    let hoisted = hoisted_statements.trim_end();
+    if !hoisted.is_empty() {
+        let placeholder = format!("__NG_RAW_{}_hoisted__", class_name);
+        // A leading string statement would be parsed as a directive, so parse it after
+        // another statement and keep only the placeholder.
+        let stmt_str = allocator.alloc_str(&format!("void 0;\n'{}';", placeholder));
+        let parse_result = Parser::new(allocator, stmt_str, SourceType::mjs()).parse();
+        if let Some(stmt) = parse_result.program.body.into_iter().nth(1) {
+            let last_import_index = program
+                .body
+                .iter()
+                .rposition(|stmt| matches!(stmt, Statement::ImportDeclaration(_)))
+                .map(|idx| idx + 1)
+                .unwrap_or(0);
+            program.body.insert(last_import_index, stmt);
+            failed_properties.push(FailedProperty {
+                class_name: class_name.to_string(),
+                prop_name: "hoisted".to_string(),
+                placeholder,
+                expr: hoisted.to_string(),
+                statement: true,
+            });
+        }
+    }
+
+    if !fac_expr_str.is_empty() {
+        add_placeholder_to_class(
+            allocator,
+            program,
+            class_name,
+            "ɵfac",
+            fac_expr_str,
+            &mut failed_properties,
+        );
+    }
+    for (def_name, def_expr_str) in definitions {
+        if !def_expr_str.is_empty() {
+            add_placeholder_to_class(
+                allocator,
+                program,
+                class_name,
+                def_name,
+                def_expr_str,
+                &mut failed_properties,
+            );
+        }
+    }
+
+    failed_properties
+}
+
 /// Adds static properties to the class. Returns Vec<FailedProperty> with properties
 /// that couldn't be parsed and need to be injected into class bodies via string manipulation.
 fn add_static_properties_to_class<'a>(
@@ -373,6 +501,7 @@ fn add_placeholder_to_class<'a>(
                     prop_name: prop_name.to_string(),
                     placeholder,
                     expr: expr.to_string(),
+                    statement: false,
                 });
             }
             return;
@@ -1,28 +1,63 @@
-use crate::ngtsc::annotations::component::src::handler::ComponentDecoratorHandler;
+use crate::ngtsc::annotations::common::src::{
+    factory_declaration_type, ng_module_declaration_type, resolve_constructor_deps,
+};
+use crate::ngtsc::annotations::component::src::handler::{
+    template_parse_diagnostic, template_tokenize_options, ComponentDecoratorHandler,
+};
 use crate::ngtsc::annotations::directive::src::handler::DirectiveDecoratorHandler;
+use crate::ngtsc::annotations::injectable::InjectableDecoratorHandler;
 use crate::ngtsc::core::NgCompilerOptions;
+use crate::ngtsc::diagnostics::{DiagnosticCategory, ErrorCode};
 use crate::ngtsc::file_system::{AbsoluteFsPath, FileSystem};
 use crate::ngtsc::metadata::{
-    DecoratorMetadata, DirectiveMetadata, MetadataReader, OxcMetadataReader,
+    ComponentMetadata, DecoratorMetadata, DirectiveMetadata, MetadataReader, OxcMetadataReader,
+};
+use crate::ngtsc::resource::{AdapterResourceLoader, ResourceError, ResourceLoader};
+use crate::ngtsc::sourcemaps::{
+    original_offset, overlay_replacement_maps, remap_through_edits, replace_all_tracked,
+    replace_ranges_tracked, SourceMap, TextEdit,
+};
+use crate::ngtsc::transform::src::api::{CompilationMode, CompileResult};
+use crate::ngtsc::transform::src::declaration::{
+    emit_declaration_file, IvyDeclarationDtsTransform, IvyDeclarationField,
 };
 use crate::ngtsc::translator::src::import_manager::import_manager::EmitterImportManager;
+use crate::ngtsc::typecheck::extended::{ExtendedTemplateCheckerImpl, ALL_DIAGNOSTIC_FACTORIES};
+use crate::ngtsc::typecheck::{
+    find_tsc, TemplateTypeCheckerImpl, TypeCheckError, TypeCheckingConfig, TypeCheckingContext,
+};
+use crate::ngtsc::xi18n::{
+    extract_localize_messages, parse_missing_translation_strategy, translate_localize_messages,
+    Translations, Xi18nContext,
+};
+use angular_compiler::i18n::TranslationBundle;
 use angular_compiler::ml_parser::tags::TagDefinition;
 use angular_compiler::ml_parser::{
     html_tags::get_html_tag_definition, parser::Parser as HtmlParser,
 };
 use angular_compiler::output::abstract_emitter::{AbstractEmitterVisitor, EmitterVisitorContext};
-use angular_compiler::output::output_ast::ExpressionTrait;
+use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+use angular_compiler::output::output_ast::{Expression, ExpressionTrait};
+use angular_compiler::parse_util::ParseErrorLevel;
+use angular_compiler::render3::partial::{
+    compile_declare_factory_function, compile_declare_injector_from_metadata,
+    compile_declare_ng_module_from_metadata, compile_declare_pipe_from_metadata,
+};
+use angular_compiler::render3::r3_factory::{
+    compile_factory_function, FactoryTarget, R3ConstructorFactoryMetadata, R3FactoryMetadata,
+};
 use angular_compiler::render3::r3_injector_compiler::{compile_injector, R3InjectorMetadata};
 use angular_compiler::render3::r3_module_compiler::{
-    compile_ng_module, R3NgModuleMetadata, R3NgModuleMetadataCommon, R3NgModuleMetadataKind,
-    R3NgModuleMetadataLocal, R3SelectorScopeMode,
+    compile_ng_module, R3NgModuleMetadata, R3NgModuleMetadataCommon, R3NgModuleMetadataGlobal,
+    R3NgModuleMetadataKind, R3NgModuleMetadataLocal, R3SelectorScopeMode,
 };
+use angular_compiler::render3::r3_pipe_compiler::R3PipeMetadata;
 use angular_compiler::render3::util::R3Reference;
 use oxc_allocator::Allocator;
 use oxc_parser::Parser;
 use oxc_span::SourceType;
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 fn expression_to_string(expr: &angular_compiler::output::output_ast::Expression) -> String {
     let mut ctx = EmitterVisitorContext::create_root();
@@ -31,6 +66,124 @@ fn expression_to_string(expr: &angular_compiler::output::output_ast::Expression)
     ctx.to_source()
 }
 
+/// Version the compiler writes into partial declarations before it is stamped.
+const PARTIAL_VERSION_PLACEHOLDER: &str = "0.0.0-PLACEHOLDER";
+
+/// Emit a partial declaration. `@angular/core` is referenced through `i0`, which also
+/// serves as the declaration's `ngImport`.
+fn declaration_to_string(expr: &Expression) -> String {
+    let mut ctx = EmitterVisitorContext::create_root();
+    let mut visitor = AbstractJsEmitterVisitor::new();
+    expr.visit_expression(&mut visitor, &mut ctx);
+    ctx.to_source()
+}
+
+/// Replace the placeholder `version` of partial declarations with the Angular version
+/// the generated code targets.
+fn stamp_partial_version(results: &mut [CompileResult]) {
+    for result in results {
+        if let Some(initializer) = &mut result.initializer {
+            *initializer =
+                initializer.replace(PARTIAL_VERSION_PLACEHOLDER, crate::version::ANGULAR_VERSION);
+        }
+    }
+}
+
+/// A reference to a class declared in the file being compiled.
+fn class_reference(class_name: &str) -> R3Reference {
+    let expr = Expression::ReadVar(angular_compiler::output::output_ast::ReadVarExpr {
+        name: class_name.to_string(),
+        type_: None,
+        source_span: None,
+    });
+    R3Reference {
+        value: expr.clone(),
+        type_expr: expr,
+    }
+}
+
+/// The `ɵfac` of a pipe or NgModule, which injects the class's constructor parameters.
+/// In partial mode it is emitted as an `ɵɵngDeclareFactory` call.
+fn compile_factory(
+    meta: &DecoratorMetadata,
+    mode: CompilationMode,
+    is_core: bool,
+    import_manager: &mut EmitterImportManager,
+) -> CompileResult {
+    let (class_name, constructor_params, file_imports, target) = match meta {
+        DecoratorMetadata::Pipe(pipe) => (
+            &pipe.name,
+            &pipe.constructor_params,
+            &pipe.file_imports,
+            FactoryTarget::Pipe,
+        ),
+        DecoratorMetadata::NgModule(ngm) => (
+            &ngm.name,
+            &ngm.constructor_params,
+            &ngm.file_imports,
+            FactoryTarget::NgModule,
+        ),
+        _ => unreachable!("directives and injectables compile their own factories"),
+    };
+    let (deps, diagnostics) = resolve_constructor_deps(
+        class_name,
+        constructor_params,
+        file_imports.as_ref(),
+        meta.source_file().map(PathBuf::as_path),
+        is_core,
+        import_manager,
+    );
+    let factory_meta = R3FactoryMetadata::Constructor(R3ConstructorFactoryMetadata {
+        name: class_name.clone(),
+        type_: class_reference(class_name),
+        type_argument_count: 0,
+        deps: Some(deps),
+        target,
+    });
+    let expr = match mode {
+        CompilationMode::Partial => compile_declare_factory_function(&factory_meta).expression,
+        _ => compile_factory_function(&factory_meta).expression,
+    };
+
+    let imports_map = import_manager.get_imports_map();
+    let mut ctx = EmitterVisitorContext::create_root();
+    let mut emitter = AbstractJsEmitterVisitor::with_imports(imports_map.clone());
+    expr.visit_expression(&mut emitter, &mut ctx);
+
+    let mut result = CompileResult::new(
+        "ɵfac",
+        factory_declaration_type(class_name, constructor_params),
+    );
+    result.initializer = Some(ctx.to_source());
+    result.diagnostics = diagnostics;
+    result.additional_imports = imports_map
+        .into_iter()
+        .filter(|(module, _)| emitter.used_imports.contains(module))
+        .map(|(module, alias)| (alias, module))
+        .collect();
+    result.additional_imports.sort();
+    result
+}
+
+/// A diagnostic reported at the resource URL literal `url` in a component's decorator.
+fn decorator_diagnostic(
+    file: &Path,
+    component: Option<&ComponentMetadata>,
+    url: &str,
+    code: ErrorCode,
+    message: String,
+) -> crate::ngtsc::core::Diagnostic {
+    let span = component.and_then(|comp| comp.resource_url_spans.get(url).copied());
+    crate::ngtsc::core::Diagnostic {
+        category: DiagnosticCategory::Error,
+        file: Some(file.to_path_buf()),
+        message,
+        code: code as usize,
+        start: span.map(|(start, _)| start),
+        length: span.map(|(_, length)| length),
+    }
+}
+
 fn get_html_tag_definition_wrapper(name: &str) -> &'static dyn TagDefinition {
     get_html_tag_definition(name)
 }
@@ -50,27 +203,139 @@ pub struct NgCompiler<'a, T: FileSystem> {
     pub options: NgCompilerOptions,
     pub fs: &'a T,
     pub is_core: bool,
+    /// Loads the `templateUrl` and `styleUrls` of components. Defaults to reading them
+    /// from `fs`, relative to the component's file.
+    pub resource_loader: Box<dyn ResourceLoader + Send + Sync + 'a>,
 }
 
-#[derive(Default)]
+#[derive(Debug, Clone, Default)]
 pub struct CompilationResult {
     pub files: Vec<PathBuf>,
     pub directives: Vec<DirectiveMetadata<'static>>,
     pub diagnostics: Vec<crate::ngtsc::core::Diagnostic>,
 }
 
+impl CompilationResult {
+    fn extend(&mut self, other: CompilationResult) {
+        self.files.extend(other.files);
+        self.directives.extend(other.directives);
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    /// The part of the result that concerns `files`.
+    pub fn for_files(&self, files: &HashSet<PathBuf>) -> CompilationResult {
+        CompilationResult {
+            files: self
+                .files
+                .iter()
+                .filter(|file| files.contains(*file))
+                .cloned()
+                .collect(),
+            directives: self
+                .directives
+                .iter()
+                .filter(|directive| directive.source_file().is_some_and(|f| files.contains(f)))
+                .cloned()
+                .collect(),
+            diagnostics: self
+                .diagnostics
+                .iter()
+                .filter(|d| d.file.as_ref().is_none_or(|f| files.contains(f)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// The analysis of a single file, with the inputs it was computed from.
+#[derive(Debug, Clone)]
+struct FileAnalysis {
+    content: String,
+    resources: Vec<PathBuf>,
+    result: CompilationResult,
+}
+
+/// The analysis of each file of a compilation.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisCache {
+    files: HashMap<PathBuf, FileAnalysis>,
+}
+
+impl AnalysisCache {
+    /// The analysis of `file`, unless its content or one of its resources changed. Files
+    /// with diagnostics are always analyzed again.
+    fn reusable(
+        &self,
+        file: &Path,
+        content: &str,
+        modified_resource_files: &HashSet<String>,
+    ) -> Option<&FileAnalysis> {
+        let prior = self.files.get(file)?;
+        let resource_changed = prior.resources.iter().any(|resource| {
+            modified_resource_files
+                .iter()
+                .any(|modified| Path::new(modified) == resource)
+        });
+        (prior.content == content && prior.result.diagnostics.is_empty() && !resource_changed)
+            .then_some(prior)
+    }
+
+    /// Whether `file` was analyzed.
+    pub fn contains(&self, file: &Path) -> bool {
+        self.files.contains_key(file)
+    }
+
+    /// Drop the analysis of `files`, so that they are analyzed again.
+    pub fn invalidate(&mut self, files: &HashSet<PathBuf>) {
+        self.files.retain(|file, _| !files.contains(file));
+    }
+}
+
 impl<'a, T: FileSystem> NgCompiler<'a, T> {
     pub fn new(ticket: CompilationTicket<'a, T>) -> Self {
         NgCompiler {
             options: ticket.options,
             fs: ticket.fs,
             is_core: false,
+            resource_loader: Box::new(AdapterResourceLoader::new(ticket.fs)),
         }
     }
 
+    /// Use `loader` to resolve and load component resources instead of the file system.
+    pub fn with_resource_loader(mut self, loader: impl ResourceLoader + Send + Sync + 'a) -> Self {
+        self.resource_loader = Box::new(loader);
+        self
+    }
+
+    /// Resolve `url` relative to `containing_file` and load it, returning its contents
+    /// and resolved URL.
+    fn load_resource(
+        &self,
+        url: &str,
+        containing_file: &str,
+    ) -> Result<(String, String), ResourceError> {
+        let resolved = self.resource_loader.resolve(url, containing_file)?;
+        let content = self.resource_loader.load(&resolved)?;
+        Ok((content, resolved))
+    }
+
     pub fn analyze_async(&mut self, root_names: &[String]) -> Result<CompilationResult, String> {
+        self.analyze_incremental(root_names, &AnalysisCache::default(), &HashSet::new())
+            .map(|(result, _)| result)
+    }
+
+    /// Analyze `root_names`, reusing the analysis of `prior` for files whose content and
+    /// component resources did not change. Also returns the analysis of every file, for
+    /// the next compilation of the program.
+    pub fn analyze_incremental(
+        &mut self,
+        root_names: &[String],
+        prior: &AnalysisCache,
+        modified_resource_files: &HashSet<String>,
+    ) -> Result<(CompilationResult, AnalysisCache), String> {
         // eprintln!("DEBUG: NgCompiler::analyze_async called with {} root files", root_names.len());
         let mut result = CompilationResult::default();
+        let mut analysis = AnalysisCache::default();
         let metadata_reader = OxcMetadataReader;
 
         for file in root_names {
@@ -89,6 +354,14 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                 }
             };
 
+            if let Some(prior) = prior.reusable(&path, &content, modified_resource_files) {
+                result.extend(prior.result.clone());
+                analysis.files.insert(path, prior.clone());
+                continue;
+            }
+            let mut file_result = CompilationResult::default();
+            let mut resources = Vec::new();
+
             let allocator = Allocator::default();
             let source_type = SourceType::from_path(&path).unwrap_or_default();
 
@@ -112,21 +385,34 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                             continue;
                         }
 
+                        // Spans of templates point at the file they are written in, the
+                        // component's own for inline ones, so that emitted source maps can
+                        // reference it.
+                        if let Some(comp) = &mut dir.component {
+                            comp.template_start = comp
+                                .template_offset
+                                .map(|offset| line_and_column(&content, offset));
+                        }
                         let template_str = if let Some(comp) = &dir.component {
                             if let Some(template) = &comp.template {
-                                Some(template.clone())
+                                Some((template.clone(), path.to_string_lossy().to_string()))
                             } else if let Some(template_url) = &comp.template_url {
-                                let component_dir = self.fs.dirname(abs_path.as_str());
-                                let template_path =
-                                    self.fs.resolve(&[&component_dir, template_url]);
-                                // eprintln!("DEBUG: Resolving template URL '{}' relative to '{}' -> '{}'", template_url, component_dir, template_path);
-                                match self.fs.read_file(&template_path) {
-                                    Ok(content) => {
-                                        // eprintln!("DEBUG: Successfully read template file: {}", template_path);
-                                        Some(content)
+                                match self.load_resource(template_url, abs_path.as_str()) {
+                                    Ok(loaded) => {
+                                        resources.push(PathBuf::from(&loaded.1));
+                                        Some(loaded)
                                     }
-                                    Err(e) => {
-                                        // eprintln!("DEBUG: Failed to read template file: {} (Error: {})", template_path, e);
+                                    Err(error) => {
+                                        file_result.diagnostics.push(decorator_diagnostic(
+                                            &path,
+                                            Some(comp),
+                                            template_url,
+                                            ErrorCode::ComponentResourceNotFound,
+                                            format!(
+                                                "Could not load template file '{}': {}",
+                                                template_url, error.message
+                                            ),
+                                        ));
                                         None
                                     }
                                 }
@@ -137,32 +423,101 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                             None
                         };
 
-                        if let Some(template) = template_str {
+                        if let Some((template, template_url)) = template_str {
+                            if let Some(comp) = &mut dir.component {
+                                if comp.template.is_none() {
+                                    comp.template_file =
+                                        Some((template_url.clone(), template.clone()));
+                                }
+                            }
+                            let options = dir
+                                .component
+                                .as_ref()
+                                .map(|comp| template_tokenize_options(comp, &template));
                             let parser = HtmlParser::new(get_html_tag_definition_wrapper);
-                            let parse_result = parser.parse(&template, "template.html", None);
+                            let parse_result = parser.parse(&template, &template_url, options);
 
                             if !parse_result.errors.is_empty() {
-                            } else {
-                                if let Some(comp) = &mut dir.component {
-                                    comp.template_ast = Some(parse_result.root_nodes);
-                                }
+                                // Inline templates are reported against the component's file.
+                                let (template_file, template_offset) = if dir
+                                    .component
+                                    .as_ref()
+                                    .is_some_and(|comp| comp.template.is_some())
+                                {
+                                    (
+                                        path.to_string_lossy().to_string(),
+                                        dir.component
+                                            .as_ref()
+                                            .and_then(|comp| comp.template_offset)
+                                            .unwrap_or(0),
+                                    )
+                                } else {
+                                    (template_url.clone(), 0)
+                                };
+                                file_result
+                                    .diagnostics
+                                    .extend(parse_result.errors.iter().map(|error| {
+                                        let d = template_parse_diagnostic(
+                                            error,
+                                            &template_file,
+                                            template_offset,
+                                        );
+                                        crate::ngtsc::core::Diagnostic {
+                                            category: d.category,
+                                            file: d.file.map(PathBuf::from),
+                                            message: d.message_text.to_string(),
+                                            code: d.code as usize,
+                                            start: Some(d.start),
+                                            length: Some(d.length),
+                                        }
+                                    }));
+                            } else if let Some(comp) = &mut dir.component {
+                                comp.template_ast = Some(parse_result.root_nodes);
                             }
                         }
 
-                        let style_urls = dir.component.as_ref().and_then(|c| c.style_urls.clone());
-                        if let Some(style_urls) = style_urls {
-                            let component_dir = self.fs.dirname(abs_path.as_str());
+                        let (style_url, style_urls) = dir
+                            .component
+                            .as_ref()
+                            .map(|c| (c.style_url.clone(), c.style_urls.clone()))
+                            .unwrap_or_default();
+                        if let (Some(url), Some(_)) = (&style_url, &style_urls) {
+                            file_result.diagnostics.push(decorator_diagnostic(
+                                &path,
+                                dir.component.as_ref(),
+                                url,
+                                ErrorCode::ComponentInvalidStyleUrls,
+                                "@Component cannot define both `styleUrl` and `styleUrls`. Use \
+                                 `styleUrl` if the component has one stylesheet, or `styleUrls` \
+                                 if it has multiple"
+                                    .to_string(),
+                            ));
+                        }
+                        if style_url.is_some() || style_urls.is_some() {
                             let mut resolved_styles = if let Some(comp) = &mut dir.component {
                                 comp.styles.take().unwrap_or_default()
                             } else {
                                 Vec::new()
                             };
 
-                            for url in style_urls {
-                                let style_path = self.fs.resolve(&[&component_dir, &url]);
-                                match self.fs.read_file(&style_path) {
-                                    Ok(content) => resolved_styles.push(content),
-                                    Err(_) => {}
+                            for url in style_urls.into_iter().flatten().chain(style_url) {
+                                match self.load_resource(&url, abs_path.as_str()) {
+                                    Ok((style, resolved)) => {
+                                        resources.push(PathBuf::from(resolved));
+                                        resolved_styles.push(style);
+                                    }
+                                    Err(error) => {
+                                        file_result.diagnostics.push(decorator_diagnostic(
+                                            &path,
+                                            dir.component.as_ref(),
+                                            &url,
+                                            ErrorCode::ComponentResourceNotFound,
+                                            format!(
+                                                "Could not load stylesheet file '{}': {}",
+                                                url, error.message
+                                            ),
+                                        ))
+                                    }
                                 }
                             }
                             if let Some(comp) = &mut dir.component {
@@ -172,27 +527,291 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                     }
                 }
 
-                result.directives.extend(directives);
-                result.files.push(path);
+                file_result.directives.extend(directives);
+                file_result.files.push(path.clone());
+                result.extend(file_result.clone());
+                analysis.files.insert(
+                    path,
+                    FileAnalysis {
+                        content,
+                        resources,
+                        result: file_result,
+                    },
+                );
+            }
+        }
+        Ok((result, analysis))
+    }
+
+    /// Type-check the templates of the analyzed components with the project's `tsc`.
+    /// Diagnostics are reported against the templates, or where `tsc` reports them outside
+    /// of the type-check blocks; type-checking is skipped with a warning when the project
+    /// has no `tsc` installed.
+    ///
+    /// Only the diagnostics reported by `tsc` are returned: the schema-level ones are
+    /// reported by [`NgCompiler::emit`].
+    pub fn get_template_diagnostics(
+        &self,
+        compilation_result: &CompilationResult,
+    ) -> Vec<crate::ngtsc::core::Diagnostic> {
+        let project = Path::new(&self.options.project);
+        let Some(tsc) = find_tsc(project) else {
+            eprintln!("Warning: skipping template type-checking: Could not find `tsc` in node_modules/.bin");
+            return Vec::new();
+        };
+        let context = self.type_checking_context(compilation_result);
+        let mut checker = TemplateTypeCheckerImpl::new(context.config().clone());
+        match checker.type_check(&context, &tsc, project) {
+            Ok(result) => result
+                .diagnostics
+                .into_iter()
+                .filter(|error| error.code.starts_with("TS"))
+                .map(|error| template_diagnostic(error, DiagnosticCategory::Error))
+                .collect(),
+            Err(e) => vec![crate::ngtsc::core::Diagnostic {
+                category: DiagnosticCategory::Error,
+                file: None,
+                message: format!("Template type-checking failed: {}", e),
+                code: 0,
+                start: None,
+                length: None,
+            }],
+        }
+    }
+
+    /// Check the templates of the analyzed components against the DOM schema and their
+    /// scopes, without TypeScript: unknown elements (NG8001), properties (NG8002) and
+    /// pipes (NG8004), and missing required inputs (NG8008).
+    pub fn get_template_schema_diagnostics(
+        &self,
+        compilation_result: &CompilationResult,
+    ) -> Vec<crate::ngtsc::core::Diagnostic> {
+        self.type_checking_context(compilation_result)
+            .finalize()
+            .into_iter()
+            .flat_map(|shim| shim.diagnostics)
+            .map(|error| template_diagnostic(error, DiagnosticCategory::Error))
+            .collect()
+    }
+
+    /// Run the extended template checks over the templates of the analyzed components,
+    /// with the categories configured in `extendedDiagnostics`.
+    pub fn get_extended_template_diagnostics(
+        &self,
+        compilation_result: &CompilationResult,
+    ) -> Vec<crate::ngtsc::core::Diagnostic> {
+        let options = self
+            .options
+            .extended_diagnostics
+            .clone()
+            .unwrap_or_default();
+        let checker = ExtendedTemplateCheckerImpl::new(ALL_DIAGNOSTIC_FACTORIES, &options);
+        let component_handler = ComponentDecoratorHandler::new();
+        let mut diagnostics = Vec::new();
+        for directive in &compilation_result.directives {
+            let Some(source) = self.directive_source(directive) else {
+                continue;
+            };
+            let Some((_, meta)) = component_handler.type_check_metadata(
+                directive,
+                &compilation_result.directives,
+                &source,
+            ) else {
+                continue;
+            };
+            let members = component_handler
+                .component_members(directive, &source)
+                .unwrap_or_default();
+            diagnostics.extend(
+                checker
+                    .get_diagnostics_for_component(&meta, &members)
+                    .into_iter()
+                    .map(|d| template_diagnostic(d.diagnostic, d.category)),
+            );
+        }
+        diagnostics
+    }
+
+    /// Collect the i18n messages of the analyzed components' templates and of the
+    /// `$localize` tagged templates of the analyzed files in `ctx`, returning the errors
+    /// found while extracting them.
+    pub fn xi18n(
+        &self,
+        compilation_result: &CompilationResult,
+        ctx: &mut dyn Xi18nContext,
+    ) -> Vec<crate::ngtsc::core::Diagnostic> {
+        let component_handler = ComponentDecoratorHandler::new();
+        let mut diagnostics: Vec<_> = compilation_result
+            .directives
+            .iter()
+            .flat_map(|directive| component_handler.xi18n(directive, &mut *ctx))
+            .map(|d| crate::ngtsc::core::Diagnostic {
+                category: d.category,
+                file: d.file.map(PathBuf::from),
+                message: d.message_text.to_string(),
+                code: d.code as usize,
+                start: Some(d.start),
+                length: Some(d.length),
+            })
+            .collect();
+
+        for file in &compilation_result.files {
+            let Ok(content) = self.fs.read_file(&AbsoluteFsPath::from(file)) else {
+                continue;
+            };
+            let (messages, errors) = extract_localize_messages(&file.to_string_lossy(), &content);
+            ctx.add_messages(messages);
+            diagnostics.extend(errors.iter().map(|error| crate::ngtsc::core::Diagnostic {
+                category: DiagnosticCategory::Error,
+                file: Some(file.clone()),
+                message: error.msg.clone(),
+                code: 0,
+                start: Some(error.span.start.offset),
+                length: Some(error.span.end.offset - error.span.start.offset),
+            }));
+        }
+        diagnostics
+    }
+
+    fn type_checking_context(&self, compilation_result: &CompilationResult) -> TypeCheckingContext {
+        let component_handler = ComponentDecoratorHandler::new();
+        let mut context = TypeCheckingContext::new(TypeCheckingConfig::strict());
+        for directive in &compilation_result.directives {
+            let (Some(source_file), Some(source)) =
+                (directive.source_file(), self.directive_source(directive))
+            else {
+                continue;
+            };
+            if let Some((imports, meta)) = component_handler.type_check_metadata(
+                directive,
+                &compilation_result.directives,
+                &source,
+            ) {
+                context.add_template(source_file.to_string_lossy().to_string(), imports, meta);
             }
         }
-        Ok(result)
+        context
     }
 
+    /// The content of the source file declaring `directive`.
+    fn directive_source(&self, directive: &DecoratorMetadata) -> Option<String> {
+        let source_file = directive.source_file()?;
+        self.fs.read_file(&AbsoluteFsPath::from(source_file)).ok()
+    }
+
+    /// Emit the analyzed files, translated with the translation file of the `i18nInFile`
+    /// option, if any. With `i18nInFiles`, the files are emitted once per translation file
+    /// instead, see `emit_for_locales`.
     pub fn emit(
         &self,
         compilation_result: &CompilationResult,
     ) -> Result<Vec<crate::ngtsc::core::Diagnostic>, String> {
+        if !self.options.i18n_in_files.is_empty() {
+            let translations = self.load_locale_translations()?;
+            return self.emit_for_locales(compilation_result, &translations);
+        }
+        let translations = self.load_translations()?;
+        let mut diagnostics = self.emit_files(
+            compilation_result,
+            self.options.out_dir.as_deref(),
+            translations.as_ref(),
+        );
+        diagnostics.extend(self.get_template_schema_diagnostics(compilation_result));
+        Ok(diagnostics)
+    }
+
+    /// Emit the analyzed files once for each of `translations`, into the `<outDir>/<locale>`
+    /// directory of their locale.
+    pub fn emit_for_locales(
+        &self,
+        compilation_result: &CompilationResult,
+        translations: &[Translations],
+    ) -> Result<Vec<crate::ngtsc::core::Diagnostic>, String> {
+        let out_dir =
+            self.options.out_dir.as_deref().ok_or_else(|| {
+                "An outDir is required to emit a directory per locale".to_string()
+            })?;
+        let mut diagnostics = Vec::new();
+        for translations in translations {
+            let locale = translations.locale().ok_or_else(|| {
+                "Translations without a locale can not be emitted to a locale directory".to_string()
+            })?;
+            let locale_dir = Path::new(out_dir)
+                .join(locale)
+                .to_string_lossy()
+                .into_owned();
+            diagnostics.extend(self.emit_files(
+                compilation_result,
+                Some(locale_dir.as_str()),
+                Some(translations),
+            ));
+        }
+        diagnostics.extend(self.get_template_schema_diagnostics(compilation_result));
+        Ok(diagnostics)
+    }
+
+    /// Load the translation file of the `i18nInFile` option, if any, in the format of
+    /// `i18nInFormat`.
+    pub fn load_translations(&self) -> Result<Option<Translations>, String> {
+        let Some(path) = &self.options.i18n_in_file else {
+            return Ok(None);
+        };
+        let translations = self.load_translation_file(path)?;
+        Ok(Some(match &self.options.i18n_in_locale {
+            Some(locale) => translations.with_locale(locale.clone()),
+            None => translations,
+        }))
+    }
+
+    /// Load the translation files of the `i18nInFiles` option, each with the locale it
+    /// declares.
+    pub fn load_locale_translations(&self) -> Result<Vec<Translations>, String> {
+        self.options
+            .i18n_in_files
+            .iter()
+            .map(|path| self.load_translation_file(path))
+            .collect()
+    }
+
+    fn load_translation_file(&self, path: &str) -> Result<Translations, String> {
+        let missing_translation = parse_missing_translation_strategy(
+            self.options
+                .i18n_in_missing_translations
+                .as_deref()
+                .unwrap_or("warning"),
+        )?;
+        let content = self
+            .fs
+            .read_file(&AbsoluteFsPath::from(path))
+            .map_err(|e| format!("Cannot read translation file {}: {}", path, e))?;
+        let format = self.options.i18n_in_format.as_deref().unwrap_or("xlf");
+        Translations::load(&content, path, format, missing_translation)
+            .map_err(|e| format!("Cannot load translation file {}: {}", path, e))
+    }
+
+    /// Emit the analyzed files into `out_dir`, or next to their sources without one. With
+    /// `translations`, the i18n messages of templates and `$localize` messages are replaced
+    /// by their translation.
+    fn emit_files(
+        &self,
+        compilation_result: &CompilationResult,
+        out_dir: Option<&str>,
+        translations: Option<&Translations>,
+    ) -> Vec<crate::ngtsc::core::Diagnostic> {
         use oxc_ast::ast::*;
         let mut result_diagnostics: Vec<crate::ngtsc::core::Diagnostic> = Vec::new();
         let fs = self.fs;
 
         let component_handler =
-            crate::ngtsc::annotations::component::src::handler::ComponentDecoratorHandler::new();
+            crate::ngtsc::annotations::component::src::handler::ComponentDecoratorHandler::new()
+                .with_template_source_locations(self.options.enable_template_source_locations);
         let directive_handler =
             crate::ngtsc::annotations::directive::src::handler::DirectiveDecoratorHandler::new(
                 false,
             );
+        let injectable_handler = InjectableDecoratorHandler::new(self.is_core);
+        let mode = self.options.compilation_mode;
+        let partial = mode == CompilationMode::Partial;
 
         use rayon::prelude::*;
         use std::collections::{HashMap, HashSet};
@@ -219,6 +838,8 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
         struct FileResult {
             path: PathBuf,
             diagnostics: Vec<crate::ngtsc::core::Diagnostic>,
+            /// Errors and warnings of the translation of the file.
+            translation_diagnostics: Vec<crate::ngtsc::core::Diagnostic>,
         }
 
         struct UnsafeSyncWrapper<T>(T);
@@ -235,7 +856,7 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                 let directives: Vec<&DecoratorMetadata> = directives_wrapper.into_iter().map(|w| w.0).collect();
 
                 // Setup output path
-                let mut out_path = if let Some(out_dir) = &self.options.out_dir {
+                let mut out_path = if let Some(out_dir) = out_dir {
                     let absolute_project_root = if let Some(root_dir) = &self.options.root_dir {
                         let p = PathBuf::from(root_dir);
                         std::fs::canonicalize(&p).unwrap_or(p)
@@ -272,6 +893,9 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
 
                 let source_path = AbsoluteFsPath::from(src_file.as_path());
                 let mut diagnostics = Vec::new();
+                let mut translation_diagnostics = Vec::new();
+                // Bundles are not thread-safe, each file translates through its own.
+                let mut translation_bundle = translations.map(Translations::bundle);
 
                 // Read parse and transform
                 let output_content = match fs.read_file(&source_path) {
@@ -304,6 +928,12 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                             // Step 3: AST-based Ivy transformation for ALL directives in this file
                             // Track failed properties that couldn't be parsed (accumulated across all directives)
                             let mut failed_properties: Vec<super::ast_transformer::FailedProperty> = Vec::new();
+                            // With source maps on, generated code is injected verbatim so that
+                            // its template maps can be overlaid. Keyed by (class, property).
+                            let raw_emit = self.source_map_path(&src_file).is_some();
+                            let mut template_maps: HashMap<(String, String), SourceMap> = HashMap::new();
+                            // Static fields to declare in the .d.ts, when declarations are emitted.
+                            let mut dts_transform = IvyDeclarationDtsTransform::new();
                             let mut last_def_name = "ɵcmp".to_string(); // Default, will be updated for each directive
 
                             // Create a shared ImportManager for this file to coordinate aliases (i0, i1, etc.)
@@ -312,22 +942,56 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                             let _ = import_manager.get_or_generate_alias("@angular/core");
 
                             for directive in directives {
-                                let (compiled_results, directive_name) = match directive {
+                                // Templates are compiled from their translated HTML AST.
+                                let translated = match translation_bundle.as_mut() {
+                                    Some(bundle) if directive.is_component() => {
+                                        let mut translated = directive.clone();
+                                        translation_diagnostics.extend(component_handler.translate(&mut translated, bundle).iter().map(|d| crate::ngtsc::core::Diagnostic {
+                                            category: d.category,
+                                            file: d.file.clone().map(PathBuf::from),
+                                            message: d.message_text.to_string(),
+                                            code: d.code as usize,
+                                            start: Some(d.start),
+                                            length: Some(d.length),
+                                        }));
+                                        Some(translated)
+                                    }
+                                    _ => None,
+                                };
+                                let directive = translated.as_ref().unwrap_or(directive);
+                                let (mut compiled_results, directive_name) = match directive {
                                     DecoratorMetadata::Directive(dir) => {
                                         let results = if dir.t2.is_component {
-                                            component_handler.compile_ivy(&directive, Some(&mut import_manager))
+                                            component_handler.compile_with_mode(&directive, Some(&mut import_manager), mode)
                                         } else {
-                                            directive_handler.compile_ivy(&directive, Some(&mut import_manager))
+                                            directive_handler.compile_with_mode(&directive, Some(&mut import_manager), mode)
                                         };
                                         (results, dir.t2.name.clone())
                                     }
+                                    DecoratorMetadata::Pipe(pipe) if partial => {
+                                        let meta = R3PipeMetadata {
+                                            name: pipe.name.clone(),
+                                            type_: class_reference(&pipe.name),
+                                            type_argument_count: 0,
+                                            pipe_name: Some(pipe.pipe_name.clone()),
+                                            deps: None,
+                                            pure: pipe.is_pure,
+                                            is_standalone: pipe.is_standalone,
+                                        };
+                                        let mut result = CompileResult::new(
+                                            "ɵpipe",
+                                            format!("i0.ɵɵPipeDeclaration<{}, '{}', {}>", pipe.name, pipe.pipe_name, pipe.is_standalone),
+                                        );
+                                        result.initializer = Some(declaration_to_string(&compile_declare_pipe_from_metadata(&meta).expression));
+                                        (vec![compile_factory(directive, mode, self.is_core, &mut import_manager), result], pipe.name.clone())
+                                    }
                                     DecoratorMetadata::Pipe(pipe) => {
                                         let initializer = format!(
                                             "/*@__PURE__*/ i0.ɵɵdefinePipe({{ name: '{}', type: {}, pure: {}{} }})",
                                             pipe.pipe_name, pipe.name, pipe.is_pure,
                                             if pipe.is_standalone { ", standalone: true" } else { "" }
                                         );
-                                        let results = vec![crate::ngtsc::transform::src::api::CompileResult {
+                                        let results = vec![compile_factory(directive, mode, self.is_core, &mut import_manager), crate::ngtsc::transform::src::api::CompileResult {
                                             name: "ɵpipe".to_string(),
                                             initializer: Some(initializer),
                                             statements: vec![],
@@ -335,34 +999,13 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                             deferrable_imports: None,
                                             diagnostics: Vec::new(),
                                             additional_imports: Vec::new(),
+                                            initializer_map: None,
+                                            statement_maps: Vec::new(),
                                         }];
                                         (results, pipe.name.clone())
                                     }
                                     DecoratorMetadata::Injectable(inj) => {
-                                        let provided_in = inj.provided_in.as_deref().unwrap_or("null");
-                                        let provided_in_value = if provided_in == "null" { "null".to_string() } else { format!("'{}'", provided_in) };
-                                        let fac_initializer = format!("function {}_Factory(__ngFactoryType__) {{ return new (__ngFactoryType__ || {})(); }}", inj.name, inj.name);
-                                        let prov_initializer = format!("/*@__PURE__*/ i0.ɵɵdefineInjectable({{ token: {}, factory: {}.ɵfac, providedIn: {} }})", inj.name, inj.name, provided_in_value);
-                                        let results = vec![
-                                            crate::ngtsc::transform::src::api::CompileResult {
-                                                name: "ɵfac".to_string(),
-                                                initializer: Some(fac_initializer),
-                                                statements: vec![],
-                                                type_desc: format!("i0.ɵɵFactoryDeclaration<{}, never>", inj.name),
-                                                deferrable_imports: None,
-                                                diagnostics: Vec::new(),
-                                                additional_imports: Vec::new(),
-                                            },
-                                            crate::ngtsc::transform::src::api::CompileResult {
-                                                name: "ɵprov".to_string(),
-                                                initializer: Some(prov_initializer),
-                                                statements: vec![],
-                                                type_desc: format!("i0.ɵɵInjectableDeclaration<{}>", inj.name),
-                                                deferrable_imports: None,
-                                                diagnostics: Vec::new(),
-                                                additional_imports: Vec::new(),
-                                            }
-                                        ];
+                                        let results = injectable_handler.compile_with_mode(inj, Some(&mut import_manager), mode);
                                         (results, inj.name.clone())
                                     }
                                     DecoratorMetadata::NgModule(ngm) => {
@@ -386,7 +1029,8 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
 
                                         // Create NgModule Local Compilation Metadata
                                         let common = R3NgModuleMetadataCommon {
-                                            kind: R3NgModuleMetadataKind::Local,
+                                            // Partial declarations list their references, like global compilation.
+                                            kind: if partial { R3NgModuleMetadataKind::Global } else { R3NgModuleMetadataKind::Local },
                                             type_: type_ref.clone(),
                                             selector_scope_mode: R3SelectorScopeMode::Inline, // Or SideEffect if desired? Inline is simpler for local.
                                             schemas: if ngm.schemas.is_empty() {
@@ -421,16 +1065,35 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                             }
                                         };
 
-                                        let ng_module_meta = R3NgModuleMetadataLocal {
-                                            common,
-                                            bootstrap_expression: None, // We don't support bootstrap component yet
-                                            declarations_expression: ngm.declarations_expression.clone().or_else(|| to_array_expr(&ngm.declarations)),
-                                            imports_expression: ngm.imports_expression.clone().or_else(|| to_array_expr(&ngm.imports)),
-                                            exports_expression: ngm.exports_expression.clone().or_else(|| to_array_expr(&ngm.exports)),
+                                        let res_mod = if partial {
+                                            let to_refs = |expr: &Option<Expression>, names: &[String]| -> Vec<R3Reference> {
+                                                let entries = match expr {
+                                                    Some(Expression::LiteralArray(arr)) => arr.entries.clone(),
+                                                    _ => names.iter().map(|n| str_to_expr(n)).collect(),
+                                                };
+                                                entries.into_iter().map(|e| R3Reference { value: e.clone(), type_expr: e }).collect()
+                                            };
+                                            compile_declare_ng_module_from_metadata(&R3NgModuleMetadata::Global(R3NgModuleMetadataGlobal {
+                                                common,
+                                                bootstrap: vec![],
+                                                declarations: to_refs(&ngm.declarations_expression, &ngm.declarations),
+                                                public_declaration_types: None,
+                                                imports: to_refs(&ngm.imports_expression, &ngm.imports),
+                                                include_import_types: true,
+                                                exports: to_refs(&ngm.exports_expression, &ngm.exports),
+                                                contains_forward_decls: false,
+                                            }))
+                                        } else {
+                                            let ng_module_meta = R3NgModuleMetadataLocal {
+                                                common,
+                                                bootstrap_expression: None, // We don't support bootstrap component yet
+                                                declarations_expression: ngm.declarations_expression.clone().or_else(|| to_array_expr(&ngm.declarations)),
+                                                imports_expression: ngm.imports_expression.clone().or_else(|| to_array_expr(&ngm.imports)),
+                                                exports_expression: ngm.exports_expression.clone().or_else(|| to_array_expr(&ngm.exports)),
+                                            };
+                                            compile_ng_module(&R3NgModuleMetadata::Local(ng_module_meta))
                                         };
 
-                                        let res_mod = compile_ng_module(&R3NgModuleMetadata::Local(ng_module_meta));
-
                                         // Injector Compilation
                                         // Extract imports Vec<Expression> from imports_expression if possible
                                         let injector_imports: Vec<Expression> = if let Some(Expression::LiteralArray(arr)) = &ngm.imports_expression {
@@ -457,27 +1120,36 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                             imports: injector_imports,
                                         };
 
-                                        let res_inj = compile_injector(&injector_meta);
+                                        let res_inj = if partial {
+                                            compile_declare_injector_from_metadata(&injector_meta)
+                                        } else {
+                                            compile_injector(&injector_meta)
+                                        };
+                                        let emit_definition = |expr: &Expression| if partial {
+                                            declaration_to_string(expr)
+                                        } else {
+                                            expression_to_string(expr).replace("@angular/core.", "i0.")
+                                        };
 
                                         // Combine results
-                                        let mut results = vec![];
+                                        let mut results = vec![compile_factory(directive, mode, self.is_core, &mut import_manager)];
 
                                         // Result for ɵmod
-                                        let mod_init = expression_to_string(&res_mod.expression)
-                                            .replace("@angular/core.", "i0.");
+                                        let mod_init = emit_definition(&res_mod.expression);
                                         results.push(crate::ngtsc::transform::src::api::CompileResult {
                                             name: "ɵmod".to_string(),
                                             initializer: Some(mod_init),
                                             statements: vec![],
-                                            type_desc: format!("i0.ɵɵNgModuleDeclaration<{}, never, never, never>", ngm.name),
+                                            type_desc: ng_module_declaration_type(ngm),
                                             deferrable_imports: None,
                                             diagnostics: Vec::new(),
                                             additional_imports: Vec::new(),
+                                            initializer_map: None,
+                                            statement_maps: Vec::new(),
                                         });
 
                                         // Result for ɵinj
-                                        let inj_init = expression_to_string(&res_inj.expression)
-                                            .replace("@angular/core.", "i0.");
+                                        let inj_init = emit_definition(&res_inj.expression);
                                         results.push(crate::ngtsc::transform::src::api::CompileResult {
                                             name: "ɵinj".to_string(),
                                             initializer: Some(inj_init),
@@ -486,6 +1158,8 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                             deferrable_imports: None,
                                             diagnostics: Vec::new(),
                                             additional_imports: Vec::new(),
+                                            initializer_map: None,
+                                            statement_maps: Vec::new(),
                                         });
 
                                         eprintln!("[RUST_DEBUG] Matched NgModule: {}, Results: {}", ngm.name, results.len());
@@ -494,9 +1168,14 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                     }
                                 };
 
+                                if partial {
+                                    stamp_partial_version(&mut compiled_results);
+                                }
+
                                 // Collect diagnostics
                                 for r in &compiled_results {
                                     diagnostics.extend(r.diagnostics.iter().map(|d| crate::ngtsc::core::Diagnostic {
+                                        category: d.category,
                                         file: d.file.clone().map(PathBuf::from),
                                         message: d.message_text.to_string(),
                                         code: d.code as usize,
@@ -509,17 +1188,41 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                     continue;
                                 }
 
+                                if self.options.declaration {
+                                    let mut fields: Vec<IvyDeclarationField> = compiled_results
+                                        .iter()
+                                        .map(|r| IvyDeclarationField::new(r.name.clone(), r.type_desc.clone()))
+                                        .collect();
+                                    // A default factory is generated below when none was compiled.
+                                    if !compiled_results.iter().any(|r| r.name == "ɵfac") {
+                                        fields.insert(0, IvyDeclarationField::new(
+                                            "ɵfac",
+                                            factory_declaration_type(&directive_name, &[]),
+                                        ));
+                                    }
+                                    dts_transform.add_fields(&directive_name, fields);
+                                }
+
                                 // Apply to AST
                                 // Primary result
                                 let mut hoisted_statements = String::new();
 
+                                let mut hoisted_map = SourceMap::new(String::new());
+
                                 // Merge results if multiple (e.g. fac and cmp)
                                 for res in &compiled_results {
-                                    for stmt in &res.statements {
+                                    for (i, stmt) in res.statements.iter().enumerate() {
+                                        if let Some(Some(map)) = res.statement_maps.get(i) {
+                                            let line = hoisted_statements.matches('\n').count();
+                                            let _ = hoisted_map.append_at(map, line, 0);
+                                        }
                                         hoisted_statements.push_str(stmt);
                                         hoisted_statements.push('\n');
                                     }
                                 }
+                                if !hoisted_map.sources.is_empty() {
+                                    template_maps.insert((directive_name.clone(), "hoisted".to_string()), hoisted_map);
+                                }
 
                                 // Prepare expressions for transform_component_ast
                                 let fac_expr_str_default = format!("function {}_Factory(t) {{ return new (t || {})(); }}", directive_name, directive_name);
@@ -536,7 +1239,13 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                     }
                                     let init = res.initializer.as_deref().unwrap_or("null");
                                     // Wrap in PURE annotation
-                                    let expr = format!("/*@__PURE__*/ {}", init);
+                                    const PURE_PREFIX: &str = "/*@__PURE__*/ ";
+                                    let expr = format!("{}{}", PURE_PREFIX, init);
+                                    if let Some(map) = &res.initializer_map {
+                                        let mut shifted = SourceMap::new(String::new());
+                                        let _ = shifted.append_at(map, 0, PURE_PREFIX.len() as u32);
+                                        template_maps.insert((directive_name.clone(), res.name.clone()), shifted);
+                                    }
                                     definitions_vec.push((res.name.clone(), expr));
                                     last_def_name = res.name.clone();
                                 }
@@ -568,33 +1277,75 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                                         fac_expr_arena, // fac
                                         &definitions_arena,
                                         &additional_imports,
+                                        raw_emit,
                                     );
 
                                     failed_properties.extend(failed);
                                 }
+
+                                // Symbols only used in `@defer` blocks are loaded through dynamic
+                                // imports, so their static imports must go.
+                                for res in &compiled_results {
+                                    if let Some(deferrable_imports) = &res.deferrable_imports {
+                                        super::ast_transformer::remove_deferred_imports(
+                                            &mut parse_result.program,
+                                            deferrable_imports,
+                                        );
+                                    }
+                                }
                             }
 
                             // Step 4: Codegen final JavaScript
                             let codegen = oxc_codegen::Codegen::new().with_options(oxc_codegen::CodegenOptions {
                                 single_quote: true,
+                                source_map_path: self.codegen_map_path(
+                                    &src_file,
+                                    translation_bundle.is_some(),
+                                ),
                                 ..oxc_codegen::CodegenOptions::default()
                             });
-                            let mut code = codegen.build(&parse_result.program).code;
+                            let codegen_result = codegen.build(&parse_result.program);
+                            let map_json = codegen_result.map.map(|map| map.to_json_string());
+                            let (generated, map_json) = self.translate_localize(
+                                codegen_result.code,
+                                map_json,
+                                &src_file,
+                                &source_content,
+                                translation_bundle.as_mut(),
+                                &mut translation_diagnostics,
+                            );
 
-                            // Replace placeholders with real expressions
+                            // Replace placeholders with real expressions, along with the
+                            // template maps of those expressions.
+                            let mut replacements: Vec<(String, String)> = Vec::new();
+                            let mut replacement_maps: Vec<Option<&SourceMap>> = Vec::new();
                             for prop in &failed_properties {
-                                // Codegen for string literals with single_quote: true will be 'placeholder'
-                                let placeholder_pattern = format!("'{}'", prop.placeholder);
-                                if code.contains(&placeholder_pattern) {
-                                    code = code.replace(&placeholder_pattern, &prop.expr);
-                                }
+                                replacements.push((prop.placeholder_text(), prop.expr.clone()));
+                                replacement_maps.push(
+                                    template_maps.get(&(prop.class_name.clone(), prop.prop_name.clone())),
+                                );
                             }
 
                             // Fix property names (ɵUNIQUE_FAC -> ɵfac, ɵUNIQUE_DIR -> last_def_name)
-                            code = code.replace("ɵUNIQUE_FAC", "ɵfac");
-                            code = code.replace("ɵUNIQUE_DIR", &last_def_name);
-
-                            Some(code)
+                            replacements.push(("ɵUNIQUE_FAC".to_string(), "ɵfac".to_string()));
+                            replacements.push(("ɵUNIQUE_DIR".to_string(), last_def_name.clone()));
+
+                            // The edits are tracked so the codegen source map can follow them.
+                            let (code, edits) =
+                                replace_all_tracked(&generated, &replacements);
+
+                            let js_output = self.attach_source_map(
+                                code,
+                                map_json,
+                                &generated,
+                                &edits,
+                                &replacement_maps,
+                                &src_file,
+                                &source_content,
+                                &out_path,
+                            );
+                            self.write_declaration_file(&src_file, &source_content, &dts_transform, &out_path);
+                            Some(js_output)
                         } else {
                             // Parse error
                             None
@@ -613,7 +1364,8 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
 
                 FileResult {
                     path: src_file,
-                    diagnostics
+                    diagnostics,
+                    translation_diagnostics,
                 }
             })
             .collect();
@@ -621,6 +1373,8 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
         // Collect results
         for res in file_results {
             component_files.insert(res.path);
+            result_diagnostics.extend(res.diagnostics);
+            result_diagnostics.extend(res.translation_diagnostics);
         }
 
         // Handle directives without source (fallback, sequential)
@@ -635,7 +1389,8 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
         }
         // Second pass: transpile non-component TypeScript files
         // Use parallel iterator
-        compilation_result.files.par_iter().for_each(|file| {
+        let translation_diagnostics: Vec<Vec<crate::ngtsc::core::Diagnostic>> = compilation_result.files.par_iter().map(|file| {
+            let mut translation_diagnostics = Vec::new();
             // Skip files in node_modules, spec files, and declaration files
             let src_path = file.to_string_lossy();
 
@@ -643,15 +1398,15 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                 || src_path.ends_with(".spec.ts")
                 || src_path.ends_with(".d.ts")
             {
-                return; // Use return for for_each
+                return translation_diagnostics;
             }
 
             // Skip files that have components (already emitted)
             if component_files.contains(file) {
-                return;
+                return translation_diagnostics;
             }
 
-            if let Some(out_dir) = &self.options.out_dir {
+            if let Some(out_dir) = out_dir {
                 // Calculate output path preserving directory structure
                 let absolute_project_root = if let Some(root_dir) = &self.options.root_dir {
                     let p = PathBuf::from(root_dir);
@@ -692,7 +1447,7 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
                         let mut parse_result = parser.parse();
 
                         if !parse_result.errors.is_empty() {
-                            return;
+                            return translation_diagnostics;
                         }
 
                         // Run semantic analysis to get scoping information
@@ -716,31 +1471,225 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
 
                         // Use OXC codegen to emit JavaScript without types
 
+                        let mut translation_bundle = translations.map(Translations::bundle);
                         let codegen =
                             oxc_codegen::Codegen::new().with_options(oxc_codegen::CodegenOptions {
                                 single_quote: true,
+                                source_map_path: self
+                                    .codegen_map_path(file, translation_bundle.is_some()),
                                 ..oxc_codegen::CodegenOptions::default()
                             });
-                        let mut js_output = codegen.build(&parse_result.program).code;
+                        let codegen_result = codegen.build(&parse_result.program);
+                        let map_json = codegen_result.map.map(|map| map.to_json_string());
+                        let (mut js_output, map_json) = self.translate_localize(
+                            codegen_result.code,
+                            map_json,
+                            file,
+                            &source_content,
+                            translation_bundle.as_mut(),
+                            &mut translation_diagnostics,
+                        );
 
                         // Add signature line for main.ts
                         if file_path.ends_with("main.ts") {
                             js_output.push_str("\nconsole.log('%cAngular Rust compiler powered by Truonglv4', 'color: #00ff00; font-weight: bold;');\n");
                         }
 
+                        let generated = js_output.clone();
+                        let js_output = self.attach_source_map(
+                            js_output,
+                            map_json,
+                            &generated,
+                            &[],
+                            &[],
+                            file,
+                            &source_content,
+                            &out_path,
+                        );
+
                         let out_path_abs = AbsoluteFsPath::from(out_path.as_path());
 
                         match fs.write_file(&out_path_abs, js_output.as_bytes(), None) {
                             Ok(_) => (),
                             Err(_) => (),
                         }
+
+                        self.write_declaration_file(
+                            file,
+                            &source_content,
+                            &IvyDeclarationDtsTransform::new(),
+                            &out_path,
+                        );
                     }
                     Err(_) => {}
                 }
             }
+            translation_diagnostics
+        }).collect();
+        result_diagnostics.extend(translation_diagnostics.into_iter().flatten());
+
+        result_diagnostics
+    }
+
+    /// Replace the `$localize` messages of the generated `code` by their translation, and
+    /// remap its source map `map_json` through the replacements. Without a translation
+    /// bundle, `code` and `map_json` are returned unchanged.
+    ///
+    /// Translation errors are reported without an error code, as they are not about a
+    /// template, at the message in `source_content` that `map_json` maps them to. The map is requested whenever
+    /// translating (see `codegen_map_path()`), and only kept if source maps are enabled.
+    fn translate_localize(
+        &self,
+        code: String,
+        map_json: Option<String>,
+        src_file: &Path,
+        source_content: &str,
+        translations: Option<&mut TranslationBundle>,
+        diagnostics: &mut Vec<crate::ngtsc::core::Diagnostic>,
+    ) -> (String, Option<String>) {
+        let Some(translations) = translations else {
+            return (code, map_json);
+        };
+        let (replacements, errors) =
+            translate_localize_messages(&src_file.to_string_lossy(), &code, translations);
+        let map = map_json
+            .as_deref()
+            .and_then(|json| SourceMap::from_json(json).ok());
+        diagnostics.extend(errors.iter().map(|e| {
+            let original = |offset| {
+                map.as_ref()
+                    .and_then(|map| original_offset(map, &code, source_content, offset))
+            };
+            let start = original(e.span.start.offset);
+            let end = original(e.span.end.offset).filter(|&end| Some(end) > start);
+            crate::ngtsc::core::Diagnostic {
+                category: match e.level {
+                    ParseErrorLevel::Error => DiagnosticCategory::Error,
+                    ParseErrorLevel::Warning => DiagnosticCategory::Warning,
+                },
+                file: Some(src_file.to_path_buf()),
+                message: e.msg.clone(),
+                code: 0,
+                start,
+                length: start.zip(end).map(|(start, end)| end - start),
+            }
+        }));
+        let map_json = map_json.filter(|_| self.source_map_path(src_file).is_some());
+        if replacements.is_empty() {
+            return (code, map_json);
+        }
+
+        let (translated, edits) = replace_ranges_tracked(&code, &replacements);
+        let map_json = map_json.and_then(|json| {
+            SourceMap::from_json(&json)
+                .and_then(|map| remap_through_edits(&map, &code, &translated, &edits))
+                .map(|map| map.to_json())
+                .ok()
         });
+        (translated, map_json)
+    }
+
+    /// Write the `.d.ts` file for `src_file` next to its JavaScript output, if
+    /// declarations are enabled.
+    fn write_declaration_file(
+        &self,
+        src_file: &Path,
+        source_content: &str,
+        transform: &IvyDeclarationDtsTransform,
+        out_path: &Path,
+    ) {
+        if !self.options.declaration {
+            return;
+        }
+        if let Ok(dts) = emit_declaration_file(source_content, src_file, transform) {
+            let dts_path = AbsoluteFsPath::from(out_path.with_extension("d.ts").as_path());
+            let _ = self.fs.write_file(&dts_path, dts.as_bytes(), None);
+        }
+    }
+
+    /// Path handed to codegen to request a source map, if maps are enabled.
+    fn source_map_path(&self, src_file: &Path) -> Option<PathBuf> {
+        if self.options.source_map || self.options.inline_source_map {
+            Some(src_file.to_path_buf())
+        } else {
+            None
+        }
+    }
 
-        Ok(result_diagnostics)
+    /// Path handed to codegen to request a source map, which is also needed to locate
+    /// the translation errors of `$localize` messages when `translating`.
+    fn codegen_map_path(&self, src_file: &Path, translating: bool) -> Option<PathBuf> {
+        self.source_map_path(src_file)
+            .or_else(|| translating.then(|| src_file.to_path_buf()))
+    }
+
+    /// Attach the codegen source map to the emitted code, either inline or as a
+    /// sibling `.js.map` file.
+    ///
+    /// `generated` is the codegen output the map was built for, and `edits` the
+    /// replacements that turned it into `code`. Replacements with an entry in
+    /// `replacement_maps` (e.g. compiled templates) are mapped by that map instead.
+    #[allow(clippy::too_many_arguments)]
+    fn attach_source_map(
+        &self,
+        mut code: String,
+        map_json: Option<String>,
+        generated: &str,
+        edits: &[TextEdit],
+        replacement_maps: &[Option<&SourceMap>],
+        src_file: &Path,
+        source_content: &str,
+        out_path: &Path,
+    ) -> String {
+        let Some(map_json) = map_json else {
+            return code;
+        };
+        let mut map = match SourceMap::from_json(&map_json)
+            .and_then(|map| remap_through_edits(&map, generated, &code, edits))
+        {
+            Ok(map) => map,
+            Err(_) => return code,
+        };
+
+        // Codegen maps the single source file; templates are added as further sources.
+        map.sources = vec![absolute_path(src_file).to_string_lossy().to_string()];
+        map.sources_content = Some(vec![Some(source_content.to_string())]);
+        map = match overlay_replacement_maps(&map, &code, edits, replacement_maps) {
+            Ok(map) => map,
+            Err(_) => return code,
+        };
+
+        let out_file_name = out_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let out_dir = absolute_path(out_path.parent().unwrap_or(Path::new(".")));
+        map.file = out_file_name.clone();
+        map.source_root = None;
+        map.sources = map
+            .sources
+            .iter()
+            .map(|source| relative_url(&out_dir, &absolute_path(Path::new(source))))
+            .collect();
+        if !self.options.inline_sources {
+            map.sources_content = None;
+        }
+
+        if !code.ends_with('\n') {
+            code.push('\n');
+        }
+        if self.options.inline_source_map {
+            code.push_str(&map.to_inline_comment());
+        } else {
+            let map_path = out_dir.join(format!("{}.map", out_file_name));
+            let _ = self.fs.write_file(
+                &AbsoluteFsPath::from(map_path.as_path()),
+                map.to_json().as_bytes(),
+                None,
+            );
+            code.push_str(&format!("//# sourceMappingURL={}.map", out_file_name));
+        }
+        code
     }
 
     fn process_directive_fallback(
@@ -751,25 +1700,31 @@ impl<'a, T: FileSystem> NgCompiler<'a, T> {
         result_diagnostics: &mut Vec<crate::ngtsc::core::Diagnostic>,
         compilation_files: &[PathBuf],
     ) {
-        let component_handler = ComponentDecoratorHandler::new();
+        let component_handler = ComponentDecoratorHandler::new()
+            .with_template_source_locations(self.options.enable_template_source_locations);
         let directive_handler = DirectiveDecoratorHandler::new(self.is_core);
 
-        let (compiled_results, directive_name, source_file) = match directive {
+        let mode = self.options.compilation_mode;
+        let (mut compiled_results, directive_name, source_file) = match directive {
             DecoratorMetadata::Directive(dir) => {
                 let results = if dir.t2.is_component {
-                    component_handler.compile_ivy(directive, None)
+                    component_handler.compile_with_mode(directive, None, mode)
                 } else {
-                    directive_handler.compile_ivy(directive, None)
+                    directive_handler.compile_with_mode(directive, None, mode)
                 };
                 (results, dir.t2.name.clone(), dir.source_file.clone())
             }
             _ => return,
         };
+        if mode == CompilationMode::Partial {
+            stamp_partial_version(&mut compiled_results);
+        }
 
         // Collect diagnostics
         for r in &compiled_results {
             result_diagnostics.extend(r.diagnostics.iter().map(|d| {
                 crate::ngtsc::core::Diagnostic {
+                    category: d.category,
                     file: d.file.clone().map(PathBuf::from),
                     message: d.message_text.to_string(),
                     code: d.code as usize,
@@ -935,3 +1890,74 @@ fn extract_and_remove_imports(code: &str) -> (Vec<String>, String) {
 
     (imports, remaining_lines.join("\n"))
 }
+
+/// A template diagnostic as a compiler diagnostic, with the numeric part of its code.
+fn template_diagnostic(
+    error: TypeCheckError,
+    category: DiagnosticCategory,
+) -> crate::ngtsc::core::Diagnostic {
+    crate::ngtsc::core::Diagnostic {
+        category,
+        file: error.file.map(PathBuf::from),
+        message: error.message,
+        code: error
+            .code
+            .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .parse()
+            .unwrap_or(0),
+        start: error.start,
+        length: error.length,
+    }
+}
+
+/// Zero-based line and column of the byte `offset` in `text`, counted in characters as
+/// the template lexer does.
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count(),
+        before[line_start..].chars().count(),
+    )
+}
+
+/// Make a path absolute against the current directory without touching the disk.
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+/// Relative URL from `from_dir` to `to`, as used in source map `sources`.
+fn relative_url(from_dir: &Path, to: &Path) -> String {
+    use std::path::Component;
+
+    fn normalize(path: &Path) -> Vec<Component<'_>> {
+        let mut parts: Vec<Component> = Vec::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir if matches!(parts.last(), Some(Component::Normal(_))) => {
+                    parts.pop();
+                }
+                other => parts.push(other),
+            }
+        }
+        parts
+    }
+    let from = normalize(from_dir);
+    let to = normalize(to);
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut segments: Vec<String> = vec!["..".to_string(); from.len() - common];
+    segments.extend(
+        to[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    segments.join("/")
+}
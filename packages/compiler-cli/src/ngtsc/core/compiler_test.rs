@@ -1,12 +1,22 @@
 #[cfg(test)]
 mod tests {
     use crate::ngtsc::core::{
-        CompilationTicket, CompilationTicketKind, NgCompiler, NgCompilerOptions,
+        AnalysisCache, CompilationTicket, CompilationTicketKind, NgCompiler, NgCompilerOptions,
     };
+    use crate::ngtsc::diagnostics::DiagnosticCategory;
     use crate::ngtsc::file_system::testing::MockFileSystem;
-    use crate::ngtsc::file_system::FileSystem;
+    use crate::ngtsc::file_system::{AbsoluteFsPath, FileSystem, ReadonlyFileSystem};
     use crate::ngtsc::metadata::DecoratorMetadata;
+    use crate::ngtsc::resource::InMemoryResourceLoader;
+    use crate::ngtsc::sourcemaps::SourceMap;
+    use crate::ngtsc::transform::src::api::CompilationMode;
+    use crate::ngtsc::xi18n::Translations;
+    use angular_compiler::core::MissingTranslationStrategy;
+    use angular_compiler::i18n::message_bundle::MessageBundle;
+    use angular_compiler::i18n::serializers::{Json, Xliff};
     use angular_compiler::ml_parser::ast::Node;
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use std::collections::{HashMap, HashSet};
     use std::sync::Arc;
 
     #[test]
@@ -35,6 +45,7 @@ mod tests {
             flat_module_out_file: None,
             out_dir: None,
             root_dir: None,
+            ..NgCompilerOptions::default()
         };
 
         let ticket = CompilationTicket {
@@ -108,6 +119,7 @@ mod tests {
             flat_module_out_file: None,
             out_dir: None,
             root_dir: None,
+            ..NgCompilerOptions::default()
         };
 
         let ticket = CompilationTicket {
@@ -143,6 +155,416 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_analyze_incremental_reuses_analysis_of_unchanged_files() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![
+            (
+                "/app.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({ selector: 'app-root', templateUrl: './app.component.html' })
+                export class AppComponent {}
+            "#,
+            ),
+            ("/app.component.html", "<p>Before</p>"),
+        ]);
+        let template = |result: &crate::ngtsc::core::CompilationResult| match &result.directives[0]
+        {
+            DecoratorMetadata::Directive(dir) => dir
+                .component
+                .as_ref()
+                .and_then(|c| c.template_file.as_ref())
+                .map(|(_, template)| template.clone()),
+            _ => None,
+        };
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions::default(),
+            fs: &fs,
+        };
+        let mut compiler = NgCompiler::new(ticket);
+        let roots = vec!["/app.component.ts".to_string()];
+        let (_, analysis) = compiler
+            .analyze_incremental(&roots, &AnalysisCache::default(), &HashSet::new())
+            .unwrap();
+        assert!(analysis.contains(std::path::Path::new("/app.component.ts")));
+
+        fs.write_file(
+            &AbsoluteFsPath::new("/app.component.html".to_string()),
+            b"<p>After</p>",
+            None,
+        )
+        .unwrap();
+
+        // The template is not reported as modified, so the prior analysis is reused.
+        let (result, analysis) = compiler
+            .analyze_incremental(&roots, &analysis, &HashSet::new())
+            .unwrap();
+        assert_eq!(template(&result).as_deref(), Some("<p>Before</p>"));
+
+        let modified = HashSet::from(["/app.component.html".to_string()]);
+        let (result, _) = compiler
+            .analyze_incremental(&roots, &analysis, &modified)
+            .unwrap();
+        assert_eq!(template(&result).as_deref(), Some("<p>After</p>"));
+    }
+
+    #[test]
+    fn test_analyze_async_reports_template_parse_errors() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![
+            (
+                "/app.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    templateUrl: './app.component.html',
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#,
+            ),
+            ("/app.component.html", "<div>\n  <span>Hi</div>"),
+        ]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.code, 5002);
+        assert_eq!(diagnostic.message, "Unclosed element \"span\"");
+        assert_eq!(
+            diagnostic.file.as_deref(),
+            Some(std::path::Path::new("/app.component.html"))
+        );
+        assert_eq!((diagnostic.start, diagnostic.length), (Some(8), Some(6)));
+    }
+
+    #[test]
+    fn test_analyze_async_locates_inline_template_parse_errors() {
+        let source = r#"
+                import { Component } from '@angular/core';
+
+                // <span>Hi</div>
+                @Component({
+                    selector: 'app-root',
+                    template: '<span>Hi</div>',
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#;
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![("/app.component.ts", source)]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.code, 5002);
+        assert_eq!(
+            diagnostic.file.as_deref(),
+            Some(std::path::Path::new("/app.component.ts"))
+        );
+        // The error is reported at `</div>`, 8 characters into the inline template.
+        let template_start = source.find("'<span>Hi</div>'").unwrap() + 1;
+        assert_eq!(diagnostic.start, Some(template_start + 8));
+    }
+
+    #[test]
+    fn test_extended_diagnostics_read_sources_from_the_compiler_file_system() {
+        let source = r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<input ([ngModel])="name">',
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#;
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![("/app.component.ts", source)]);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &fs,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        // The component only exists in the mock file system.
+        let diagnostics = compiler.get_extended_template_diagnostics(&result);
+        let codes: Vec<usize> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(codes, vec![8101]);
+        let banana = source.find("([ngModel])").unwrap();
+        assert_eq!(diagnostics[0].start, Some(banana));
+    }
+
+    #[test]
+    fn test_extended_diagnostics_report_unused_standalone_imports() {
+        let source = r#"
+                import { Component, Directive, Pipe } from '@angular/core';
+
+                @Directive({ selector: '[appHighlight]', standalone: true })
+                export class Highlight {}
+
+                @Directive({ selector: '[appTooltip]', standalone: true })
+                export class Tooltip {}
+
+                @Pipe({ name: 'shout', standalone: true })
+                export class ShoutPipe {
+                    transform(value: string) { return value; }
+                }
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<p appHighlight>Hi</p>',
+                    imports: [Highlight, Tooltip, ShoutPipe],
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#;
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![("/app.component.ts", source)]);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &fs,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        let diagnostics = compiler.get_extended_template_diagnostics(&result);
+        let reported: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.code, d.file.clone(), d.start, d.message.as_str()))
+            .collect();
+        let file = Some(std::path::PathBuf::from("/app.component.ts"));
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    8113,
+                    file.clone(),
+                    source.find("Tooltip,"),
+                    "Tooltip is not used within the template of AppComponent"
+                ),
+                (
+                    8113,
+                    file,
+                    source.find("ShoutPipe]"),
+                    "ShoutPipe is not used within the template of AppComponent"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_async_reports_missing_component_resources() {
+        let fs = MockFileSystem::new_posix();
+        let source = r#"
+                import { Component } from '@angular/core';
+
+                // Previously templateUrl: './missing.html'.
+                @Component({
+                    selector: 'app-root',
+                    templateUrl: './missing.html',
+                    styleUrls: ['./app.component.css', "./renamed.css"],
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#;
+        fs.init_with_files(vec![
+            ("/app.component.ts", source),
+            ("/app.component.css", "div { color: red; }"),
+        ]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        let reported: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.code, d.message.as_str(), d.start, d.length))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    2008,
+                    "Could not load template file './missing.html': Resource not found: ./missing.html",
+                    source.rfind("'./missing.html'"),
+                    Some(16),
+                ),
+                (
+                    2008,
+                    "Could not load stylesheet file './renamed.css': Resource not found: ./renamed.css",
+                    source.find("\"./renamed.css\""),
+                    Some(15),
+                ),
+            ]
+        );
+
+        let DecoratorMetadata::Directive(dir) = &result.directives[0] else {
+            panic!("Expected component metadata");
+        };
+        let styles = dir.component.as_ref().unwrap().styles.as_ref().unwrap();
+        assert_eq!(styles, &vec!["div { color: red; }".to_string()]);
+    }
+
+    #[test]
+    fn test_analyze_async_loads_resources_through_custom_loader() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![(
+            "/app.component.ts",
+            r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    templateUrl: './app.component.html',
+                    styleUrls: ['./app.component.css'],
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#,
+        )]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut loader = InMemoryResourceLoader::new();
+        loader.add("./app.component.html", "<p>From loader</p>");
+        loader.add("./app.component.css", "p { margin: 0; }");
+        let mut compiler = NgCompiler::new(ticket).with_resource_loader(loader);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        assert!(result.diagnostics.is_empty());
+        let DecoratorMetadata::Directive(dir) = &result.directives[0] else {
+            panic!("Expected component metadata");
+        };
+        let component = dir.component.as_ref().unwrap();
+        assert_eq!(
+            component.template_file,
+            Some((
+                "./app.component.html".to_string(),
+                "<p>From loader</p>".to_string()
+            ))
+        );
+        assert_eq!(component.styles, Some(vec!["p { margin: 0; }".to_string()]));
+    }
+
+    #[test]
+    fn test_emit_reports_template_binding_errors() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![(
+            "/app.component.ts",
+            r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<p>{{ a b }}</p>',
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#,
+        )]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                out_dir: Some("/dist".to_string()),
+                root_dir: Some("/".to_string()),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+        assert!(result.diagnostics.is_empty());
+
+        let diagnostics = compiler.emit(&result).expect("Emit failed");
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.code == 5002 && d.message.contains("Unexpected token")),
+            "{:?}",
+            diagnostics
+        );
+    }
+
     #[test]
     fn test_analyze_async_parses_style_urls() {
         let fs = MockFileSystem::new_posix();
@@ -173,6 +595,7 @@ mod tests {
             flat_module_out_file: None,
             out_dir: None,
             root_dir: None,
+            ..NgCompilerOptions::default()
         };
 
         let ticket = CompilationTicket {
@@ -200,4 +623,934 @@ mod tests {
             panic!("Expected Directive metadata");
         }
     }
+
+    #[test]
+    fn test_analyze_async_reads_style_url_and_string_styles() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![
+            (
+                "/app.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '',
+                    styleUrl: './app.component.css',
+                    styles: `p { margin: 0; }`,
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#,
+            ),
+            ("/app.component.css", "h1 { color: red; }"),
+        ]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        assert!(result.diagnostics.is_empty());
+        let DecoratorMetadata::Directive(dir) = &result.directives[0] else {
+            panic!("Expected component metadata");
+        };
+        assert_eq!(
+            dir.component.as_ref().unwrap().styles,
+            Some(vec![
+                "p { margin: 0; }".to_string(),
+                "h1 { color: red; }".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_analyze_async_reports_style_url_and_style_urls() {
+        let fs = MockFileSystem::new_posix();
+        let source = r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '',
+                    styleUrls: ['./a.css'],
+                    styleUrl: './b.css',
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#;
+        fs.init_with_files(vec![
+            ("/app.component.ts", source),
+            ("/a.css", "a {}"),
+            ("/b.css", "b {}"),
+        ]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.code, 2021);
+        assert!(diagnostic
+            .message
+            .starts_with("@Component cannot define both `styleUrl` and `styleUrls`."));
+        assert_eq!(
+            (diagnostic.start, diagnostic.length),
+            (source.find("'./b.css'"), Some(9))
+        );
+    }
+
+    #[test]
+    fn test_xi18n_extracts_inline_and_external_templates() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![
+            (
+                "/app.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<h1 i18n="site header|An introduction header@@introductionHeader">Hello</h1>',
+                    standalone: true
+                })
+                export class AppComponent {}
+            "#,
+            ),
+            (
+                "/list.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-list',
+                    templateUrl: './list.component.html',
+                    standalone: true
+                })
+                export class ListComponent {}
+            "#,
+            ),
+            (
+                "/list.component.html",
+                "<ul>\n  <li i18n>Item</li>\n  <li i18n>Item</li>\n</ul>\n<img [src]=\"src\" i18n-title title=\"Logo\">",
+            ),
+        ]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&[
+                "/app.component.ts".to_string(),
+                "/list.component.ts".to_string(),
+            ])
+            .expect("Analysis failed");
+
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        let diagnostics = compiler.xi18n(&result, &mut bundle);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        let messages = bundle.get_messages();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].custom_id, "introductionHeader");
+        assert_eq!(messages[0].meaning, "site header");
+        assert_eq!(messages[0].description, "An introduction header");
+        assert_eq!(messages[0].sources[0].file_path, "/app.component.ts");
+        assert_eq!(messages[1].sources[0].file_path, "/list.component.html");
+        assert_eq!(messages[1].sources[0].start_line, 2);
+        assert_eq!(messages[3].message_string, "Logo");
+
+        // The two identical list items are merged into one message with both sources.
+        let xliff = bundle.write(&Xliff::new(), None);
+        assert_eq!(xliff.matches("<trans-unit ").count(), 3);
+        assert!(xliff.contains(r#"<trans-unit datatype="html" id="introductionHeader">"#));
+        assert!(xliff.contains(r#"<context context-type="linenumber">3</context>"#));
+    }
+
+    #[test]
+    fn test_xi18n_extracts_localize_messages() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![
+            (
+                "/app.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<h1 i18n>Hello</h1>',
+                    standalone: true
+                })
+                export class AppComponent {
+                    title = $localize`:Page title:Welcome`;
+                }
+            "#,
+            ),
+            (
+                "/greeting.service.ts",
+                r#"
+                export class GreetingService {
+                    greet(name: string) {
+                        return $localize`:@@greeting:Hello ${name}:name:`;
+                    }
+                }
+            "#,
+            ),
+        ]);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&[
+                "/app.component.ts".to_string(),
+                "/greeting.service.ts".to_string(),
+            ])
+            .expect("Analysis failed");
+
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        let diagnostics = compiler.xi18n(&result, &mut bundle);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        let messages = bundle.get_messages();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1].description, "Page title");
+        assert_eq!(messages[1].sources[0].file_path, "/app.component.ts");
+        assert_eq!(messages[2].id, "greeting");
+        assert_eq!(messages[2].message_string, "Hello {$name}");
+
+        let xliff = bundle.write(&Xliff::new(), None);
+        assert!(xliff.contains(r#"<trans-unit datatype="html" id="greeting">"#));
+        assert!(xliff.contains(r#"<source>Hello <x equiv-text="{{name}}" id="name"/></source>"#));
+
+        let json = bundle.write(&Json::new(), None);
+        assert!(json.contains(r#""greeting": "Hello {$name}""#));
+    }
+
+    #[test]
+    fn test_emit_writes_declaration_file() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![(
+            "/app.component.ts",
+            r#"
+                import { Component, Input } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<h1>{{ title }}</h1>',
+                    standalone: true
+                })
+                export class AppComponent {
+                    @Input() title: string = 'app';
+
+                    greet(name: string): string {
+                        return 'Hello ' + name;
+                    }
+                }
+            "#,
+        )]);
+        let fs_arc = Arc::new(fs);
+
+        let options = NgCompilerOptions {
+            project: ".".to_string(),
+            out_dir: Some("/dist".to_string()),
+            root_dir: Some("/".to_string()),
+            declaration: true,
+            ..NgCompilerOptions::default()
+        };
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options,
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+        compiler.emit(&result).expect("Emit failed");
+
+        let dts = fs_arc
+            .read_file(&AbsoluteFsPath::from("/dist/app.component.d.ts"))
+            .expect("Declaration file not found");
+
+        assert!(
+            dts.contains("import * as i0 from \"@angular/core\";"),
+            "{}",
+            dts
+        );
+        assert!(dts.contains("greet(name: string): string;"), "{}", dts);
+        assert!(!dts.contains("Hello"), "{}", dts);
+        assert!(
+            dts.contains("static ɵfac: i0.ɵɵFactoryDeclaration<AppComponent, never>;"),
+            "{}",
+            dts
+        );
+        assert!(
+            dts.contains("static ɵcmp: i0.ɵɵComponentDeclaration<AppComponent, \"app-root\""),
+            "{}",
+            dts
+        );
+    }
+
+    #[test]
+    fn test_emit_maps_inline_templates_into_component_file() {
+        let source = r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<h1>{{ title }}</h1>',
+                    standalone: true
+                })
+                export class AppComponent {
+                    title = 'app';
+                }
+            "#;
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![("/app.component.ts", source)]);
+        let fs_arc = Arc::new(fs);
+
+        let options = NgCompilerOptions {
+            project: ".".to_string(),
+            out_dir: Some("/dist".to_string()),
+            root_dir: Some("/".to_string()),
+            source_map: true,
+            ..NgCompilerOptions::default()
+        };
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options,
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+        compiler.emit(&result).expect("Emit failed");
+
+        let map_json = fs_arc
+            .read_file(&AbsoluteFsPath::from("/dist/app.component.js.map"))
+            .expect("Source map not found");
+        let map = SourceMap::from_json(&map_json).unwrap();
+        assert_eq!(map.sources, vec!["../app.component.ts".to_string()]);
+
+        // The `<h1>` element is mapped to its position in the component's file.
+        let h1_offset = source.find("<h1>").unwrap();
+        let line = source[..h1_offset].matches('\n').count() as u32;
+        let column = (h1_offset - source[..h1_offset].rfind('\n').unwrap() - 1) as u32;
+        let mapped = map
+            .decode_mappings()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .filter_map(|segment| segment.original)
+            .any(|original| (original.line, original.column) == (line, column));
+        assert!(mapped, "{}", map_json);
+    }
+
+    #[test]
+    fn test_emit_partial_declarations() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![(
+            "/app.component.ts",
+            r#"
+                import { Component, Pipe } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<h1>{{ title }}</h1>',
+                    standalone: true
+                })
+                export class AppComponent {
+                    title = 'app';
+                }
+
+                @Pipe({ name: 'shout', standalone: true })
+                export class ShoutPipe {
+                    transform(value: string): string {
+                        return value.toUpperCase();
+                    }
+                }
+            "#,
+        )]);
+        let fs_arc = Arc::new(fs);
+
+        let options = NgCompilerOptions {
+            project: ".".to_string(),
+            out_dir: Some("/dist".to_string()),
+            root_dir: Some("/".to_string()),
+            compilation_mode: CompilationMode::Partial,
+            ..NgCompilerOptions::default()
+        };
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options,
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&["/app.component.ts".to_string()])
+            .expect("Analysis failed");
+        compiler.emit(&result).expect("Emit failed");
+
+        let js = fs_arc
+            .read_file(&AbsoluteFsPath::from("/dist/app.component.js"))
+            .expect("Output file not found");
+
+        assert!(js.contains("i0.ɵɵngDeclareComponent({"), "{}", js);
+        assert!(js.contains("i0.ɵɵngDeclareFactory({"), "{}", js);
+        assert!(js.contains("i0.ɵɵngDeclarePipe({"), "{}", js);
+        assert!(js.contains("ngImport: i0,"), "{}", js);
+        assert!(js.contains("template: '<h1>{{ title }}</h1>'"), "{}", js);
+        assert!(
+            js.contains(&format!("version: '{}'", crate::version::ANGULAR_VERSION)),
+            "{}",
+            js
+        );
+        assert!(!js.contains("0.0.0-PLACEHOLDER"), "{}", js);
+        assert!(!js.contains("ɵɵdefineComponent"), "{}", js);
+    }
+
+    #[test]
+    fn test_emit_pipe_and_ng_module_factories_inject_constructor_dependencies() {
+        for (mode, factories) in [
+            (
+                CompilationMode::Partial,
+                [
+                    "target: i0.ɵɵFactoryTarget.Pipe",
+                    "target: i0.ɵɵFactoryTarget.NgModule",
+                ],
+            ),
+            (
+                CompilationMode::Full,
+                [
+                    "(__ngFactoryType__ || ShoutPipe)(i0.ɵɵdirectiveInject(i1.Formatter))",
+                    "(__ngFactoryType__ || ShoutModule)(i0.ɵɵinject(i1.Formatter))",
+                ],
+            ),
+        ] {
+            let fs = MockFileSystem::new_posix();
+            fs.init_with_files(vec![
+                ("/formatter.ts", "export class Formatter {}"),
+                (
+                    "/shout.pipe.ts",
+                    r#"
+                        import { NgModule, Pipe } from '@angular/core';
+                        import { Formatter } from './formatter';
+
+                        @Pipe({ name: 'shout', standalone: true })
+                        export class ShoutPipe {
+                            constructor(private formatter: Formatter) {}
+                        }
+
+                        @NgModule({})
+                        export class ShoutModule {
+                            constructor(formatter: Formatter) {}
+                        }
+                    "#,
+                ),
+            ]);
+            let fs_arc = Arc::new(fs);
+
+            let options = NgCompilerOptions {
+                project: ".".to_string(),
+                out_dir: Some("/dist".to_string()),
+                root_dir: Some("/".to_string()),
+                compilation_mode: mode,
+                ..NgCompilerOptions::default()
+            };
+            let ticket = CompilationTicket {
+                kind: CompilationTicketKind::Fresh,
+                options,
+                fs: &*fs_arc,
+            };
+
+            let mut compiler = NgCompiler::new(ticket);
+            let result = compiler
+                .analyze_async(&["/shout.pipe.ts".to_string()])
+                .expect("Analysis failed");
+            let diagnostics = compiler.emit(&result).expect("Emit failed");
+            assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+            let js = fs_arc
+                .read_file(&AbsoluteFsPath::from("/dist/shout.pipe.js"))
+                .expect("Output file not found");
+            for factory in factories {
+                assert!(js.contains(factory), "{}", js);
+            }
+            if mode == CompilationMode::Partial {
+                assert_eq!(
+                    js.matches("deps: [{ token: i1.Formatter }]").count(),
+                    2,
+                    "{}",
+                    js
+                );
+            }
+            assert!(js.contains("import * as i1 from './formatter'"), "{}", js);
+        }
+    }
+
+    #[test]
+    fn test_template_source_locations_are_configured_per_compilation() {
+        let compilers: Vec<_> = [true, false]
+            .into_iter()
+            .map(|enabled| {
+                let fs = MockFileSystem::new_posix();
+                fs.init_with_files(vec![
+                    (
+                        "/app.component.ts",
+                        r#"
+                            import { Component } from '@angular/core';
+
+                            @Component({ selector: 'app-root', templateUrl: './app.component.html' })
+                            export class AppComponent {}
+                        "#,
+                    ),
+                    ("/app.component.html", "<div><span>Hi</span></div>"),
+                ]);
+                (enabled, Arc::new(fs))
+            })
+            .collect();
+        // Both compilers exist before either emits, so neither may see the other's setting.
+        let mut runs: Vec<_> = compilers
+            .iter()
+            .map(|(enabled, fs)| {
+                let ticket = CompilationTicket {
+                    kind: CompilationTicketKind::Fresh,
+                    options: NgCompilerOptions {
+                        project: ".".to_string(),
+                        out_dir: Some("/dist".to_string()),
+                        root_dir: Some("/".to_string()),
+                        enable_template_source_locations: *enabled,
+                        ..NgCompilerOptions::default()
+                    },
+                    fs: &**fs,
+                };
+                (*enabled, fs, NgCompiler::new(ticket))
+            })
+            .collect();
+
+        for (enabled, fs, compiler) in &mut runs {
+            let result = compiler
+                .analyze_async(&["/app.component.ts".to_string()])
+                .expect("Analysis failed");
+            compiler.emit(&result).expect("Emit failed");
+            let js = fs
+                .read_file(&AbsoluteFsPath::from("/dist/app.component.js"))
+                .expect("Output file not found");
+            assert_eq!(js.contains("ɵɵattachSourceLocations("), *enabled, "{}", js);
+        }
+    }
+
+    fn i18n_files() -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "/app.component.ts",
+                r#"
+                import { Component } from '@angular/core';
+
+                @Component({
+                    selector: 'app-root',
+                    template: '<h1 i18n="@@title">Hello</h1>',
+                    standalone: true
+                })
+                export class AppComponent {
+                    title = $localize`:@@welcome:Welcome`;
+                }
+            "#,
+            ),
+            (
+                "/greeting.service.ts",
+                r#"
+                export class GreetingService {
+                    greet(name: string) {
+                        return $localize`:@@greeting:Hello ${name}:name:!`;
+                    }
+                }
+            "#,
+            ),
+        ]
+    }
+
+    const FR_TRANSLATIONS: &str = r#"{
+        "locale": "fr",
+        "translations": {
+            "title": "Bonjour",
+            "welcome": "Bienvenue",
+            "greeting": "Bonjour {$name} !"
+        }
+    }"#;
+
+    fn analyze_and_emit(
+        files: Vec<(&str, &str)>,
+        options: NgCompilerOptions,
+    ) -> (Arc<MockFileSystem>, Vec<crate::ngtsc::core::Diagnostic>) {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(files);
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options,
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&[
+                "/app.component.ts".to_string(),
+                "/greeting.service.ts".to_string(),
+            ])
+            .expect("Analysis failed");
+        let diagnostics = compiler.emit(&result).expect("Emit failed");
+        drop(compiler);
+        (fs_arc, diagnostics)
+    }
+
+    #[test]
+    fn test_emit_inlines_translations() {
+        let mut files = i18n_files();
+        files.push(("/messages.fr.json", FR_TRANSLATIONS));
+        let (fs, diagnostics) = analyze_and_emit(
+            files,
+            NgCompilerOptions {
+                project: ".".to_string(),
+                out_dir: Some("/dist".to_string()),
+                root_dir: Some("/".to_string()),
+                i18n_in_file: Some("/messages.fr.json".to_string()),
+                i18n_in_format: Some("json".to_string()),
+                ..NgCompilerOptions::default()
+            },
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        let js = fs
+            .read_file(&AbsoluteFsPath::from("/dist/app.component.js"))
+            .expect("Output file not found");
+        assert!(js.contains("Bonjour"), "{}", js);
+        assert!(js.contains("'Bienvenue'"), "{}", js);
+        assert!(!js.contains("Hello"), "{}", js);
+        assert!(!js.contains("$localize"), "{}", js);
+
+        let js = fs
+            .read_file(&AbsoluteFsPath::from("/dist/greeting.service.js"))
+            .expect("Output file not found");
+        assert!(js.contains("return 'Bonjour ' + name + ' !';"), "{}", js);
+    }
+
+    #[test]
+    fn test_emit_reports_missing_translations() {
+        let empty = r#"{ "locale": "fr", "translations": {} }"#;
+        for (strategy, category) in [
+            ("error", Some(DiagnosticCategory::Error)),
+            ("warning", Some(DiagnosticCategory::Warning)),
+            ("ignore", None),
+        ] {
+            let mut files = i18n_files();
+            files.push(("/messages.fr.json", empty));
+            let (fs, diagnostics) = analyze_and_emit(
+                files,
+                NgCompilerOptions {
+                    project: ".".to_string(),
+                    out_dir: Some("/dist".to_string()),
+                    root_dir: Some("/".to_string()),
+                    i18n_in_file: Some("/messages.fr.json".to_string()),
+                    i18n_in_format: Some("json".to_string()),
+                    i18n_in_missing_translations: Some(strategy.to_string()),
+                    ..NgCompilerOptions::default()
+                },
+            );
+
+            let Some(category) = category else {
+                assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+                continue;
+            };
+            assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+            assert!(diagnostics.iter().all(|d| d.category == category));
+            let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+            assert!(
+                messages
+                    .iter()
+                    .any(|m| m.contains("Missing translation for message \"title\"")),
+                "{:?}",
+                messages
+            );
+            let greeting = diagnostics
+                .iter()
+                .find(|d| {
+                    d.message
+                        .starts_with("No translation found for \"greeting\"")
+                })
+                .unwrap_or_else(|| panic!("{:?}", messages));
+            assert_eq!(greeting.code, 0);
+            assert_eq!(
+                greeting.file.as_deref(),
+                Some(std::path::Path::new("/greeting.service.ts"))
+            );
+            // Located at the message in the source file, not in the generated code.
+            let service = i18n_files()[1].1;
+            let message = "$localize`:@@greeting:Hello ${name}:name:!`";
+            assert_eq!(
+                (greeting.start, greeting.length),
+                (service.find(message), Some(message.len())),
+            );
+
+            // Messages without translation keep their source text.
+            let js = fs
+                .read_file(&AbsoluteFsPath::from("/dist/greeting.service.js"))
+                .expect("Output file not found");
+            assert!(js.contains("return 'Hello ' + name + '!';"), "{}", js);
+            assert!(!js.contains("sourceMappingURL"), "{}", js);
+        }
+    }
+
+    #[test]
+    fn test_emit_rejects_unknown_missing_translation_strategy() {
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(vec![("/messages.fr.json", FR_TRANSLATIONS)]);
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                i18n_in_file: Some("/messages.fr.json".to_string()),
+                i18n_in_format: Some("json".to_string()),
+                i18n_in_missing_translations: Some("fail".to_string()),
+                ..NgCompilerOptions::default()
+            },
+            fs: &fs,
+        };
+
+        let compiler = NgCompiler::new(ticket);
+        assert!(compiler.load_translations().is_err());
+    }
+
+    #[test]
+    fn test_emit_for_locales() {
+        let de = r#"{
+            "locale": "de",
+            "translations": {
+                "title": "Hallo",
+                "welcome": "Willkommen",
+                "greeting": "Hallo {$name}!"
+            }
+        }"#;
+        let fs = MockFileSystem::new_posix();
+        fs.init_with_files(i18n_files());
+        let fs_arc = Arc::new(fs);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                out_dir: Some("/dist".to_string()),
+                root_dir: Some("/".to_string()),
+                ..NgCompilerOptions::default()
+            },
+            fs: &*fs_arc,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&[
+                "/app.component.ts".to_string(),
+                "/greeting.service.ts".to_string(),
+            ])
+            .expect("Analysis failed");
+        let translations = [
+            Translations::load(
+                FR_TRANSLATIONS,
+                "fr.json",
+                "json",
+                MissingTranslationStrategy::Error,
+            )
+            .unwrap(),
+            Translations::load(de, "de.json", "json", MissingTranslationStrategy::Error).unwrap(),
+        ];
+        let diagnostics = compiler
+            .emit_for_locales(&result, &translations)
+            .expect("Emit failed");
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        for (locale, greeting) in [
+            ("fr", "'Bonjour ' + name + ' !'"),
+            ("de", "'Hallo ' + name + '!'"),
+        ] {
+            let js = fs_arc
+                .read_file(&AbsoluteFsPath::from(
+                    format!("/dist/{}/greeting.service.js", locale).as_str(),
+                ))
+                .expect("Output file not found");
+            assert!(js.contains(greeting), "{}", js);
+            assert!(fs_arc.exists(&AbsoluteFsPath::from(
+                format!("/dist/{}/app.component.js", locale).as_str()
+            )));
+        }
+    }
+
+    #[test]
+    fn test_emit_with_i18n_in_files_emits_each_locale() {
+        let de = r#"{ "locale": "de", "translations": { "greeting": "Hallo {$name}!" } }"#;
+        let fs = MockFileSystem::new_posix();
+        let mut files = i18n_files();
+        files.push(("/messages.fr.json", FR_TRANSLATIONS));
+        files.push(("/messages.de.json", de));
+        fs.init_with_files(files);
+
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: ".".to_string(),
+                out_dir: Some("/dist".to_string()),
+                root_dir: Some("/".to_string()),
+                i18n_in_files: vec![
+                    "/messages.fr.json".to_string(),
+                    "/messages.de.json".to_string(),
+                ],
+                i18n_in_format: Some("json".to_string()),
+                i18n_in_missing_translations: Some("ignore".to_string()),
+                ..NgCompilerOptions::default()
+            },
+            fs: &fs,
+        };
+
+        let mut compiler = NgCompiler::new(ticket);
+        let result = compiler
+            .analyze_async(&[
+                "/app.component.ts".to_string(),
+                "/greeting.service.ts".to_string(),
+            ])
+            .expect("Analysis failed");
+        let diagnostics = compiler.emit(&result).expect("Emit failed");
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        for (locale, greeting) in [
+            ("fr", "'Bonjour ' + name + ' !'"),
+            ("de", "'Hallo ' + name + '!'"),
+        ] {
+            let js = fs
+                .read_file(&AbsoluteFsPath::from(
+                    format!("/dist/{}/greeting.service.js", locale).as_str(),
+                ))
+                .expect("Output file not found");
+            assert!(js.contains(greeting), "{}", js);
+        }
+        assert!(!fs.exists(&AbsoluteFsPath::from("/dist/greeting.service.js")));
+    }
+
+    #[test]
+    fn test_emit_defer_blocks_with_per_block_dependency_functions() {
+        use crate::ngtsc::file_system::NodeJSFileSystem;
+
+        let dir = std::env::temp_dir().join(format!("ngc_defer_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = std::fs::canonicalize(&dir).unwrap();
+        std::fs::write(
+            dir.join("lazy.component.ts"),
+            r#"
+                import { Component } from '@angular/core';
+
+                @Component({ selector: 'lazy-cmp', template: 'Lazy', standalone: true })
+                export class LazyComponent {}
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("app.component.ts"),
+            r#"
+                import { Component } from '@angular/core';
+                import { LazyComponent } from './lazy.component';
+
+                @Component({
+                    selector: 'app-root',
+                    standalone: true,
+                    imports: [LazyComponent],
+                    template: '@defer { <lazy-cmp></lazy-cmp> } @defer (on idle) { <p>Hi</p> }'
+                })
+                export class AppComponent {}
+            "#,
+        )
+        .unwrap();
+
+        let fs = NodeJSFileSystem::new();
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Fresh,
+            options: NgCompilerOptions {
+                project: dir.join("tsconfig.json").to_string_lossy().to_string(),
+                out_dir: Some(dir.join("dist").to_string_lossy().to_string()),
+                root_dir: Some(dir.to_string_lossy().to_string()),
+                ..NgCompilerOptions::default()
+            },
+            fs: &fs,
+        };
+        let mut compiler = NgCompiler::new(ticket);
+        let files: Vec<String> = ["app.component.ts", "lazy.component.ts"]
+            .iter()
+            .map(|file| dir.join(file).to_string_lossy().to_string())
+            .collect();
+        let result = compiler.analyze_async(&files).expect("Analysis failed");
+        compiler.emit(&result).expect("Emit failed");
+
+        let js = std::fs::read_to_string(dir.join("dist/app.component.js")).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        // Only the first block has dependencies, which it loads lazily.
+        assert!(
+            js.contains(
+                "AppComponent_Defer_1_DepsFn = () => [import('./lazy.component').then((m) => m.LazyComponent)];"
+            ),
+            "{}",
+            js
+        );
+        assert!(
+            js.contains("i0.ɵɵdefer(1, 0, AppComponent_Defer_1_DepsFn)"),
+            "{}",
+            js
+        );
+        assert!(js.contains("i0.ɵɵdefer(4, 3)"), "{}", js);
+        assert!(!js.contains("AppComponent_DeferFn"), "{}", js);
+        assert!(!js.contains("import { LazyComponent }"), "{}", js);
+    }
 }
@@ -4,29 +4,63 @@
 
 use std::path::PathBuf;
 
+use crate::ngtsc::diagnostics::DiagnosticCategory;
+use crate::ngtsc::transform::src::api::CompilationMode;
+use crate::ngtsc::typecheck::extended::ExtendedDiagnosticsOptions;
+
 pub mod ast_transformer;
 /// Compiler options (subset of tsconfig)
 pub mod compiler;
 #[cfg(test)]
 mod compiler_test;
 
-pub use compiler::{CompilationResult, CompilationTicket, CompilationTicketKind, NgCompiler};
+pub use compiler::{
+    AnalysisCache, CompilationResult, CompilationTicket, CompilationTicketKind, NgCompiler,
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct NgCompilerOptions {
     pub project: String,
     // Add other options as needed
     pub strict_templates: bool,
+    /// Categories of the extended template diagnostics, when they are configured.
+    pub extended_diagnostics: Option<ExtendedDiagnosticsOptions>,
     pub strict_injection_parameters: bool,
     pub skip_template_codegen: bool,
     pub flat_module_out_file: Option<String>,
     pub out_dir: Option<String>,
     pub root_dir: Option<String>,
+    /// Emit a `.js.map` file next to each output file.
+    pub source_map: bool,
+    /// Embed the source map in the output file as a data URL instead.
+    pub inline_source_map: bool,
+    /// Include the original sources in the emitted source maps.
+    pub inline_sources: bool,
+    /// Emit a `.d.ts` file next to each output file.
+    pub declaration: bool,
+    /// Whether to emit full Ivy definitions or partial declarations for the linker.
+    pub compilation_mode: CompilationMode,
+    /// Translation file whose messages replace the i18n messages of the templates and the
+    /// `$localize` messages in the emitted files.
+    pub i18n_in_file: Option<String>,
+    /// Translation files to emit the program with once each, into the `<outDir>/<locale>`
+    /// directory of their locale. Takes precedence over `i18n_in_file`.
+    pub i18n_in_files: Vec<String>,
+    /// Format of `i18n_in_file`: `xlf` (the default), `xlf2`, `xtb`, `json` or `arb`.
+    pub i18n_in_format: Option<String>,
+    /// Locale of the translations, instead of the one declared by `i18n_in_file`.
+    pub i18n_in_locale: Option<String>,
+    /// How missing translations are reported: `error`, `warning` (the default) or `ignore`.
+    pub i18n_in_missing_translations: Option<String>,
+    /// Emit `ɵɵattachSourceLocations` calls exposing where each element of a template is
+    /// defined.
+    pub enable_template_source_locations: bool,
 }
 
 /// Compilation diagnostics
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
+    pub category: DiagnosticCategory,
     pub file: Option<PathBuf>,
     pub message: String,
     pub code: usize,
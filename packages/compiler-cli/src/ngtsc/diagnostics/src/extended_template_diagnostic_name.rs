@@ -25,6 +25,30 @@ pub enum ExtendedTemplateDiagnosticName {
     DeferTriggerMisconfiguration,
 }
 
+impl ExtendedTemplateDiagnosticName {
+    /// Every extended template diagnostic, in declaration order.
+    pub const ALL: &'static [Self] = &[
+        Self::InvalidBananaInBox,
+        Self::NullishCoalescingNotNullable,
+        Self::OptionalChainNotNullable,
+        Self::MissingControlFlowDirective,
+        Self::MissingStructuralDirective,
+        Self::TextAttributeNotBinding,
+        Self::UninvokedFunctionInEventBinding,
+        Self::MissingNgForOfLet,
+        Self::SuffixNotSupported,
+        Self::SkipHydrationNotStatic,
+        Self::InterpolatedSignalNotInvoked,
+        Self::ControlFlowPreventingContentProjection,
+        Self::UnusedLetDeclaration,
+        Self::UninvokedTrackFunction,
+        Self::UnusedStandaloneImports,
+        Self::UnparenthesizedNullishCoalescing,
+        Self::UninvokedFunctionInTextInterpolation,
+        Self::DeferTriggerMisconfiguration,
+    ];
+}
+
 impl std::fmt::Display for ExtendedTemplateDiagnosticName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -27,6 +27,22 @@ impl FileDependencyGraph {
         self.reverse.clear();
     }
 
+    /// Remove the recorded dependencies of a file, e.g. before re-scanning it.
+    ///
+    /// Edges pointing at the file from its dependents are kept.
+    pub fn clear_dependencies_of(&mut self, file: &str) {
+        if let Some(deps) = self.forward.remove(file) {
+            for dep in deps {
+                if let Some(dependents) = self.reverse.get_mut(&dep) {
+                    dependents.remove(file);
+                    if dependents.is_empty() {
+                        self.reverse.remove(&dep);
+                    }
+                }
+            }
+        }
+    }
+
     /// Get all files in the graph.
     pub fn all_files(&self) -> HashSet<String> {
         let mut files = HashSet::new();
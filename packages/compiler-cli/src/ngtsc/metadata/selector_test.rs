@@ -124,6 +124,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_extract_component_singular_assets() {
+        let source = r#"
+        import { Component } from '@angular/core';
+
+        @Component({
+            selector: 'app-assets',
+            template: '',
+            styleUrl: './assets.component.css',
+            styles: `div { color: red; }`
+        })
+        export class AssetsComponent {}
+    "#;
+
+        let allocator = Allocator::default();
+        let source_type = SourceType::default()
+            .with_typescript(true)
+            .with_module(true);
+        let parser = Parser::new(&allocator, source, source_type);
+        let ret = parser.parse();
+
+        assert!(ret.errors.is_empty(), "Parser errors: {:?}", ret.errors);
+
+        let reader = OxcMetadataReader;
+        let directives = reader.get_directive_metadata(&ret.program, &PathBuf::from("test.ts"));
+
+        let DecoratorMetadata::Directive(meta) = &directives[0] else {
+            panic!("Expected Directive metadata");
+        };
+        let component = meta.component.as_ref().unwrap();
+        assert_eq!(
+            component.style_url.as_deref(),
+            Some("./assets.component.css")
+        );
+        assert_eq!(component.style_urls, None);
+        assert_eq!(
+            component.styles,
+            Some(vec!["div { color: red; }".to_string()])
+        );
+    }
+
     #[test]
     fn test_extract_directive_selector() {
         let source = r#"
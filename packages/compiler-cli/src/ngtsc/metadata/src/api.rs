@@ -143,10 +143,23 @@ pub struct T2DirectiveMetadata {
 #[derive(Debug, Clone, Default)]
 pub struct ComponentMetadata {
     pub template: Option<String>,
+    /// Offset of the first character of the inline template in the component's source
+    /// file, just past the opening quote of the `template` initializer.
+    pub template_offset: Option<usize>,
+    /// Line and column of `template_offset`, set once the component's source file is read.
+    pub template_start: Option<(usize, usize)>,
     pub template_url: Option<String>,
+    /// Resolved path and contents of the file referenced by `template_url`, once loaded.
+    pub template_file: Option<(String, String)>,
     pub template_ast: Option<Vec<HtmlNode>>,
     pub styles: Option<Vec<String>>,
     pub style_urls: Option<Vec<String>>,
+    /// The singular `styleUrl`, kept apart from `style_urls` so that setting both can be
+    /// reported.
+    pub style_url: Option<String>,
+    /// Start and length of the `templateUrl`, `styleUrl` and `styleUrls` string literals
+    /// in the component's source file, keyed by URL.
+    pub resource_url_spans: HashMap<String, (usize, usize)>,
     pub change_detection: Option<angular_compiler::core::ChangeDetectionStrategy>,
 }
 
@@ -225,7 +238,7 @@ pub struct DirectiveMeta<'a> {
 }
 
 /// Constructor parameter metadata.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConstructorParam {
     /// Parameter name.
     pub name: Option<String>,
@@ -235,10 +248,13 @@ pub struct ConstructorParam {
     pub from_module: Option<String>,
     /// Dependency flags.
     pub attribute: Option<String>, // @Attribute('name')
-    pub optional: bool,  // @Optional()
-    pub host: bool,      // @Host()
-    pub self_: bool,     // @Self()
-    pub skip_self: bool, // @SkipSelf()
+    pub inject_token: Option<String>, // @Inject(TOKEN)
+    pub optional: bool,               // @Optional()
+    pub host: bool,                   // @Host()
+    pub self_: bool,                  // @Self()
+    pub skip_self: bool,              // @SkipSelf()
+    /// Start and length of the parameter in the source file.
+    pub span: Option<(usize, usize)>,
 }
 
 /// Metadata for queries (ViewChild, ViewChildren, ContentChild, ContentChildren).
@@ -389,6 +405,10 @@ pub struct PipeMeta {
     pub decorator: Option<String>,
     pub is_explicitly_deferred: bool,
     pub source_file: Option<PathBuf>,
+    /// Constructor parameters for dependency injection.
+    pub constructor_params: Vec<ConstructorParam>,
+    /// File-level imports mapping (local name -> module path).
+    pub file_imports: Option<HashMap<String, String>>,
 }
 
 impl Default for PipeMeta {
@@ -403,6 +423,8 @@ impl Default for PipeMeta {
             decorator: None,
             is_explicitly_deferred: false,
             source_file: None,
+            constructor_params: Vec::new(),
+            file_imports: None,
         }
     }
 }
@@ -411,8 +433,20 @@ impl Default for PipeMeta {
 #[derive(Debug, Clone)]
 pub struct InjectableMeta {
     pub name: String,
-    pub provided_in: Option<String>,
+    /// The `providedIn` scope: a string such as `'root'`, or a type (possibly in `forwardRef()`).
+    pub provided_in: Option<angular_compiler::render3::util::MaybeForwardRefExpression>,
+    /// At most one of `use_class`, `use_factory`, `use_existing` and `use_value` is set.
+    pub use_class: Option<angular_compiler::render3::util::MaybeForwardRefExpression>,
+    pub use_factory: Option<angular_compiler::output::output_ast::Expression>,
+    pub use_existing: Option<angular_compiler::render3::util::MaybeForwardRefExpression>,
+    pub use_value: Option<angular_compiler::render3::util::MaybeForwardRefExpression>,
+    /// The `deps` of a `useClass` or `useFactory` provider.
+    pub deps: Option<Vec<angular_compiler::render3::r3_factory::R3DependencyMetadata>>,
     pub source_file: Option<PathBuf>,
+    /// Constructor parameters for dependency injection.
+    pub constructor_params: Vec<ConstructorParam>,
+    /// File-level imports mapping (local name -> module path).
+    pub file_imports: Option<HashMap<String, String>>,
 }
 
 /// Metadata for @NgModule decorator.
@@ -437,6 +471,10 @@ pub struct NgModuleMeta {
     pub imports_expression: Option<angular_compiler::output::output_ast::Expression>,
     pub exports_expression: Option<angular_compiler::output::output_ast::Expression>,
     pub providers_expression: Option<angular_compiler::output::output_ast::Expression>,
+    /// Constructor parameters for dependency injection.
+    pub constructor_params: Vec<ConstructorParam>,
+    /// File-level imports mapping (local name -> module path).
+    pub file_imports: Option<HashMap<String, String>>,
 }
 
 impl Default for NgModuleMeta {
@@ -459,6 +497,8 @@ impl Default for NgModuleMeta {
             imports_expression: None,
             exports_expression: None,
             providers_expression: None,
+            constructor_params: Vec::new(),
+            file_imports: None,
         }
     }
 }
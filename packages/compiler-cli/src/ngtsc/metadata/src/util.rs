@@ -6,6 +6,7 @@
 use crate::ngtsc::imports::OwningModule;
 use oxc_ast::ast::Program;
 use oxc_ast::ast::{Declaration, Expression, ModuleDeclaration, ObjectPropertyKind, PropertyKey};
+use oxc_span::GetSpan;
 use std::collections::HashMap;
 
 use super::api::{
@@ -54,97 +55,7 @@ pub fn extract_directive_metadata<'a>(
         ..Default::default()
     };
 
-    // Extract constructor parameters
-    for element in &class_decl.body.body {
-        if let oxc_ast::ast::ClassElement::MethodDefinition(method) = element {
-            if method.kind == oxc_ast::ast::MethodDefinitionKind::Constructor {
-                for param in &method.value.params.items {
-                    let param_name = match &param.pattern.kind {
-                        oxc_ast::ast::BindingPatternKind::BindingIdentifier(id) => {
-                            Some(id.name.to_string())
-                        }
-                        _ => None,
-                    };
-
-                    // Extract type name from type annotation
-                    let type_name =
-                        param.pattern.type_annotation.as_ref().and_then(|ann| {
-                            match &ann.type_annotation {
-                                oxc_ast::ast::TSType::TSTypeReference(ref_type) => {
-                                    match &ref_type.type_name {
-                                        oxc_ast::ast::TSTypeName::IdentifierReference(ident) => {
-                                            Some(ident.name.to_string())
-                                        }
-                                        _ => None,
-                                    }
-                                }
-                                _ => None,
-                            }
-                        });
-
-                    // Try to determine module from imports (simplified - would need full import analysis)
-                    let from_module = type_name.as_ref().and_then(|tn| match tn.as_str() {
-                        "ElementRef" | "Renderer2" | "Injector" | "ChangeDetectorRef" => {
-                            Some("@angular/core".to_string())
-                        }
-                        "NgControl" | "FormControl" | "FormGroup" => {
-                            Some("@angular/forms".to_string())
-                        }
-                        _ => None,
-                    });
-
-                    let mut attribute = None;
-                    let mut optional = false;
-                    let mut host = false;
-                    let mut self_ = false;
-                    let mut skip_self = false;
-
-                    for dec in &param.decorators {
-                        if let Expression::CallExpression(call) = &dec.expression {
-                            if let Expression::Identifier(ident) = &call.callee {
-                                match ident.name.as_str() {
-                                    "Optional" => optional = true,
-                                    "Host" => host = true,
-                                    "Self" => self_ = true,
-                                    "SkipSelf" => skip_self = true,
-                                    "Attribute" => {
-                                        if let Some(arg) = call.arguments.first() {
-                                            if let Some(Expression::StringLiteral(s)) =
-                                                arg.as_expression()
-                                            {
-                                                attribute = Some(s.value.to_string());
-                                            }
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        } else if let Expression::Identifier(ident) = &dec.expression {
-                            match ident.name.as_str() {
-                                "Optional" => optional = true,
-                                "Host" => host = true,
-                                "Self" => self_ = true,
-                                "SkipSelf" => skip_self = true,
-                                _ => {}
-                            }
-                        }
-                    }
-
-                    meta.constructor_params.push(super::api::ConstructorParam {
-                        name: param_name,
-                        type_name,
-                        from_module,
-                        attribute,
-                        optional,
-                        host,
-                        self_,
-                        skip_self,
-                    });
-                }
-                break; // Only one constructor
-            }
-        }
-    }
+    meta.constructor_params = extract_constructor_params(class_decl);
 
     // Scan class body for @Input and signals (input(), input.required())
     for element in &class_decl.body.body {
@@ -759,6 +670,8 @@ pub fn extract_directive_metadata<'a>(
                             "templateUrl" => {
                                 if let Some(val) = extract_string_value(&prop.value) {
                                     if let Some(comp) = meta.component.as_mut() {
+                                        comp.resource_url_spans
+                                            .insert(val.clone(), literal_span(&prop.value));
                                         comp.template_url = Some(val);
                                     }
                                 }
@@ -767,32 +680,43 @@ pub fn extract_directive_metadata<'a>(
                                 if let Some(val) = extract_string_value(&prop.value) {
                                     if let Some(comp) = meta.component.as_mut() {
                                         comp.template = Some(val);
+                                        comp.template_offset =
+                                            Some(prop.value.span().start as usize + 1);
                                     }
                                 }
                             }
                             "styleUrls" => {
                                 if let Expression::ArrayExpression(arr) = &prop.value {
-                                    let collected: Vec<String> = arr
+                                    let collected: Vec<(String, (usize, usize))> = arr
                                         .elements
                                         .iter()
                                         .filter_map(|e| {
                                             if let Some(expr) = e.as_expression() {
                                                 if let Expression::StringLiteral(s) = expr {
-                                                    return Some(s.value.to_string());
+                                                    return Some((
+                                                        s.value.to_string(),
+                                                        literal_span(expr),
+                                                    ));
                                                 }
                                             }
                                             None
                                         })
                                         .collect();
                                     if let Some(comp) = meta.component.as_mut() {
-                                        comp.style_urls = Some(collected);
+                                        comp.resource_url_spans
+                                            .extend(collected.iter().cloned());
+                                        comp.style_urls = Some(
+                                            collected.into_iter().map(|(url, _)| url).collect(),
+                                        );
                                     }
                                 }
                             }
                             "styleUrl" => {
                                 if let Some(val) = extract_string_value(&prop.value) {
                                     if let Some(comp) = meta.component.as_mut() {
-                                        comp.style_urls = Some(vec![val]);
+                                        comp.resource_url_spans
+                                            .insert(val.clone(), literal_span(&prop.value));
+                                        comp.style_url = Some(val);
                                     }
                                 }
                             }
@@ -813,6 +737,10 @@ pub fn extract_directive_metadata<'a>(
                                     if let Some(comp) = meta.component.as_mut() {
                                         comp.styles = Some(collected);
                                     }
+                                } else if let Some(val) = extract_string_value(&prop.value) {
+                                    if let Some(comp) = meta.component.as_mut() {
+                                        comp.styles = Some(vec![val]);
+                                    }
                                 }
                             }
                             "imports" => {
@@ -866,6 +794,9 @@ pub fn extract_directive_metadata<'a>(
                                     meta.is_standalone = b.value;
                                 }
                             }
+                            "schemas" => {
+                                meta.schemas = extract_string_array(&prop.value);
+                            }
                             "changeDetection" => {
                                 if let Some(comp) = meta.component.as_mut() {
                                     if let Expression::StaticMemberExpression(member) = &prop.value
@@ -1067,6 +998,7 @@ pub fn extract_pipe_metadata<'a>(
     class_decl: &'a ClassDeclaration<'a>,
     decorator: &Decorator<'a>,
     source_file: &std::path::Path,
+    imports_map: &HashMap<String, String>,
 ) -> Option<DecoratorMetadata<'a>> {
     let name = class_decl
         .id
@@ -1079,6 +1011,8 @@ pub fn extract_pipe_metadata<'a>(
         name: name.clone(),
         pipe_name: name,
         source_file: Some(source_file.to_path_buf()),
+        constructor_params: extract_constructor_params(class_decl),
+        file_imports: Some(imports_map.clone()),
         ..Default::default()
     };
 
@@ -1120,11 +1054,143 @@ pub fn extract_pipe_metadata<'a>(
     Some(DecoratorMetadata::Pipe(meta))
 }
 
+/// Extract the parameters of the class constructor, with the DI decorators applied to them.
+///
+/// Returns an empty list if the class has no constructor.
+fn extract_constructor_params(class_decl: &ClassDeclaration) -> Vec<super::api::ConstructorParam> {
+    let mut params = Vec::new();
+    for element in &class_decl.body.body {
+        if let oxc_ast::ast::ClassElement::MethodDefinition(method) = element {
+            if method.kind == oxc_ast::ast::MethodDefinitionKind::Constructor {
+                for param in &method.value.params.items {
+                    let param_name = match &param.pattern.kind {
+                        oxc_ast::ast::BindingPatternKind::BindingIdentifier(id) => {
+                            Some(id.name.to_string())
+                        }
+                        _ => None,
+                    };
+
+                    // Extract type name from type annotation
+                    let type_name =
+                        param.pattern.type_annotation.as_ref().and_then(|ann| {
+                            match &ann.type_annotation {
+                                oxc_ast::ast::TSType::TSTypeReference(ref_type) => {
+                                    match &ref_type.type_name {
+                                        oxc_ast::ast::TSTypeName::IdentifierReference(ident) => {
+                                            Some(ident.name.to_string())
+                                        }
+                                        _ => None,
+                                    }
+                                }
+                                _ => None,
+                            }
+                        });
+
+                    // Try to determine module from imports (simplified - would need full import analysis)
+                    let from_module = type_name.as_ref().and_then(|tn| match tn.as_str() {
+                        "ElementRef" | "Renderer2" | "Injector" | "ChangeDetectorRef" => {
+                            Some("@angular/core".to_string())
+                        }
+                        "NgControl" | "FormControl" | "FormGroup" => {
+                            Some("@angular/forms".to_string())
+                        }
+                        _ => None,
+                    });
+
+                    let mut attribute = None;
+                    let mut inject_token = None;
+                    let mut optional = false;
+                    let mut host = false;
+                    let mut self_ = false;
+                    let mut skip_self = false;
+
+                    for dec in &param.decorators {
+                        if let Expression::CallExpression(call) = &dec.expression {
+                            if let Expression::Identifier(ident) = &call.callee {
+                                match ident.name.as_str() {
+                                    "Optional" => optional = true,
+                                    "Host" => host = true,
+                                    "Self" => self_ = true,
+                                    "SkipSelf" => skip_self = true,
+                                    "Attribute" => {
+                                        if let Some(arg) = call.arguments.first() {
+                                            if let Some(Expression::StringLiteral(s)) =
+                                                arg.as_expression()
+                                            {
+                                                attribute = Some(s.value.to_string());
+                                            }
+                                        }
+                                    }
+                                    "Inject" => {
+                                        inject_token = call
+                                            .arguments
+                                            .first()
+                                            .and_then(|arg| arg.as_expression())
+                                            .and_then(injection_token_text);
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        } else if let Expression::Identifier(ident) = &dec.expression {
+                            match ident.name.as_str() {
+                                "Optional" => optional = true,
+                                "Host" => host = true,
+                                "Self" => self_ = true,
+                                "SkipSelf" => skip_self = true,
+                                _ => {}
+                            }
+                        }
+                    }
+
+                    params.push(super::api::ConstructorParam {
+                        name: param_name,
+                        type_name,
+                        from_module,
+                        attribute,
+                        inject_token,
+                        optional,
+                        host,
+                        self_,
+                        skip_self,
+                        span: Some((
+                            param.span.start as usize,
+                            (param.span.end - param.span.start) as usize,
+                        )),
+                    });
+                }
+                break; // Only one constructor
+            }
+        }
+    }
+    params
+}
+
+/// The source text of an `@Inject()` token: an identifier, a property access on one
+/// (`tokens.API_URL`) or a string literal (kept quoted).
+fn injection_token_text(expr: &Expression) -> Option<String> {
+    match expr {
+        Expression::Identifier(ident) => Some(ident.name.to_string()),
+        Expression::StaticMemberExpression(member) => Some(format!(
+            "{}.{}",
+            injection_token_text(&member.object)?,
+            member.property.name
+        )),
+        Expression::StringLiteral(s) => serde_json::to_string(s.value.as_str()).ok(),
+        Expression::ParenthesizedExpression(paren) => injection_token_text(&paren.expression),
+        _ => None,
+    }
+}
+
 /// Extract injectable metadata from a class declaration and its @Injectable decorator.
+///
+/// Provider expressions that can't be represented as references (e.g. a `useFactory` arrow
+/// function) are kept as their source text, transpiled to JavaScript.
 pub fn extract_injectable_metadata<'a>(
     class_decl: &'a ClassDeclaration<'a>,
     decorator: &Decorator<'a>,
     source_file: &std::path::Path,
+    source_text: &str,
+    imports_map: &HashMap<String, String>,
 ) -> Option<DecoratorMetadata<'a>> {
     let name = class_decl
         .id
@@ -1132,37 +1198,224 @@ pub fn extract_injectable_metadata<'a>(
         .map(|id| id.name.to_string())
         .unwrap_or_default();
 
-    let mut provided_in: Option<String> = None;
-
-    // Extract @Injectable({ providedIn: '...' })
-    if let Some(args) = &decorator.args {
-        if let Some(first_arg) = args.first() {
-            if let Expression::ObjectExpression(obj) = first_arg {
-                for prop in &obj.properties {
-                    if let ObjectPropertyKind::ObjectProperty(obj_prop) = prop {
-                        let key = match &obj_prop.key {
-                            PropertyKey::StaticIdentifier(id) => Some(id.name.as_str()),
-                            _ => None,
-                        };
-
-                        if key == Some("providedIn") {
-                            if let Expression::StringLiteral(s) = &obj_prop.value {
-                                provided_in = Some(s.value.to_string());
-                            }
+    let mut provided_in = None;
+    let mut use_class = None;
+    let mut use_factory = None;
+    let mut use_existing = None;
+    let mut use_value = None;
+    let mut deps = None;
+
+    // Extract @Injectable({ providedIn: ..., useClass: ..., deps: [...] })
+    if let Some(Expression::ObjectExpression(obj)) =
+        decorator.args.as_ref().and_then(|args| args.first())
+    {
+        for prop in &obj.properties {
+            if let ObjectPropertyKind::ObjectProperty(obj_prop) = prop {
+                let key = match &obj_prop.key {
+                    PropertyKey::StaticIdentifier(id) => id.name.as_str(),
+                    _ => continue,
+                };
+                let value = &obj_prop.value;
+                match key {
+                    "providedIn" => {
+                        provided_in = Some(provider_expression(value, source_text, imports_map))
+                    }
+                    "useClass" => {
+                        use_class = Some(provider_expression(value, source_text, imports_map))
+                    }
+                    "useFactory" => {
+                        use_factory = Some(provider_value(value, source_text, imports_map))
+                    }
+                    "useExisting" => {
+                        use_existing = Some(provider_expression(value, source_text, imports_map))
+                    }
+                    "useValue" => {
+                        use_value = Some(provider_expression(value, source_text, imports_map))
+                    }
+                    "deps" => {
+                        if let Expression::ArrayExpression(arr) = value {
+                            deps = Some(
+                                arr.elements
+                                    .iter()
+                                    .filter_map(|elem| elem.as_expression())
+                                    .map(|dep| provider_dependency(dep, imports_map))
+                                    .collect(),
+                            );
                         }
                     }
+                    _ => {}
                 }
             }
         }
     }
 
+    // Only one provider form is used, in the same order of precedence as upstream Angular.
+    // `deps` only apply to `useClass` and `useFactory`.
+    if use_value.is_some() {
+        (use_class, use_factory, use_existing, deps) = (None, None, None, None);
+    } else if use_existing.is_some() {
+        (use_class, use_factory, deps) = (None, None, None);
+    } else if use_class.is_some() {
+        use_factory = None;
+    } else if use_factory.is_none() {
+        deps = None;
+    }
+
     Some(DecoratorMetadata::Injectable(InjectableMeta {
         name,
         provided_in,
+        use_class,
+        use_factory,
+        use_existing,
+        use_value,
+        deps,
         source_file: Some(source_file.to_path_buf()),
+        constructor_params: extract_constructor_params(class_decl),
+        file_imports: Some(imports_map.clone()),
     }))
 }
 
+/// A provider expression, unwrapping `forwardRef(() => X)`.
+fn provider_expression(
+    expr: &Expression,
+    source_text: &str,
+    imports_map: &HashMap<String, String>,
+) -> angular_compiler::render3::util::MaybeForwardRefExpression {
+    use angular_compiler::render3::util::{ForwardRefHandling, MaybeForwardRefExpression};
+
+    if let Expression::CallExpression(call) = expr {
+        if matches!(&call.callee, Expression::Identifier(id) if id.name == "forwardRef") {
+            let body = call
+                .arguments
+                .first()
+                .and_then(|arg| arg.as_expression())
+                .and_then(|arg| match arg {
+                    Expression::ArrowFunctionExpression(arrow) => arrow.get_expression(),
+                    _ => None,
+                });
+            if let Some(body) = body {
+                return MaybeForwardRefExpression {
+                    expression: provider_value(body, source_text, imports_map),
+                    forward_ref: ForwardRefHandling::Unwrapped,
+                };
+            }
+        }
+    }
+
+    MaybeForwardRefExpression {
+        expression: provider_value(expr, source_text, imports_map),
+        forward_ref: ForwardRefHandling::None,
+    }
+}
+
+/// A provider value. References and literals are converted; anything else is emitted as
+/// its source text.
+fn provider_value(
+    expr: &Expression,
+    source_text: &str,
+    imports_map: &HashMap<String, String>,
+) -> angular_compiler::output::output_ast::Expression {
+    use angular_compiler::output::output_ast::RawCodeExpr;
+
+    match expr.without_parentheses() {
+        Expression::Identifier(_)
+        | Expression::StaticMemberExpression(_)
+        | Expression::StringLiteral(_)
+        | Expression::NumericLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::NullLiteral(_) => convert_oxc_expression(expr, imports_map),
+        _ => {
+            let span = expr.span();
+            let code = &source_text[span.start as usize..span.end as usize];
+            angular_compiler::output::output_ast::Expression::RawCode(RawCodeExpr {
+                code: transpile_expression(code),
+                source_span: None,
+            })
+        }
+    }
+}
+
+/// Strip the TypeScript syntax from an expression. The source is returned unchanged if it
+/// can't be parsed.
+fn transpile_expression(code: &str) -> String {
+    let allocator = oxc_allocator::Allocator::default();
+    let source = format!("({});", code);
+    let mut ret = oxc_parser::Parser::new(&allocator, &source, oxc_span::SourceType::ts()).parse();
+    if !ret.errors.is_empty() {
+        return code.to_string();
+    }
+
+    let semantic = oxc_semantic::SemanticBuilder::new().build(&ret.program);
+    let _ = oxc_transformer::Transformer::new(
+        &allocator,
+        std::path::Path::new("expression.ts"),
+        &oxc_transformer::TransformOptions::default(),
+    )
+    .build_with_scoping(semantic.semantic.into_scoping(), &mut ret.program);
+
+    let js = oxc_codegen::Codegen::new().build(&ret.program).code;
+    js.trim_end().trim_end_matches(';').to_string()
+}
+
+/// An entry of a provider's `deps` array: either a token, or an array of a token and the
+/// DI flags that apply to it, e.g. `[new Optional(), new Inject(TOKEN)]`.
+fn provider_dependency(
+    dep: &Expression,
+    imports_map: &HashMap<String, String>,
+) -> angular_compiler::render3::r3_factory::R3DependencyMetadata {
+    use angular_compiler::render3::r3_factory::R3DependencyMetadata;
+
+    let mut meta = R3DependencyMetadata {
+        token: None,
+        attribute_name_type: None,
+        host: false,
+        optional: false,
+        self_: false,
+        skip_self: false,
+    };
+
+    let Expression::ArrayExpression(arr) = dep else {
+        meta.token = Some(convert_oxc_expression(dep, imports_map));
+        return meta;
+    };
+
+    for elem in arr.elements.iter().filter_map(|elem| elem.as_expression()) {
+        // A DI flag is written either as the decorator itself or as an instance of it.
+        let (callee, arg) = match elem {
+            Expression::NewExpression(new) => (
+                &new.callee,
+                new.arguments.first().and_then(|arg| arg.as_expression()),
+            ),
+            _ => (elem, None),
+        };
+        let flag = match callee {
+            Expression::Identifier(id)
+                if imports_map.get(id.name.as_str()).map(String::as_str)
+                    == Some("@angular/core") =>
+            {
+                Some(id.name.as_str())
+            }
+            _ => None,
+        };
+        match (flag, arg) {
+            (Some("Optional"), _) => meta.optional = true,
+            (Some("Self"), _) => meta.self_ = true,
+            (Some("SkipSelf"), _) => meta.skip_self = true,
+            (Some("Host"), _) => meta.host = true,
+            (Some("Inject"), Some(token)) => {
+                meta.token = Some(convert_oxc_expression(token, imports_map))
+            }
+            (Some("Attribute"), Some(name)) => {
+                let name = convert_oxc_expression(name, imports_map);
+                meta.token = Some(name.clone());
+                meta.attribute_name_type = Some(name);
+            }
+            _ => meta.token = Some(convert_oxc_expression(elem, imports_map)),
+        }
+    }
+    meta
+}
+
 /// Extract NgModule metadata from a class declaration and its @NgModule decorator.
 pub fn extract_ng_module_metadata<'a>(
     class_decl: &'a ClassDeclaration<'a>,
@@ -1179,6 +1432,8 @@ pub fn extract_ng_module_metadata<'a>(
     let mut meta = crate::ngtsc::metadata::src::api::NgModuleMeta {
         name: name.clone(),
         source_file: Some(source_file.to_path_buf()),
+        constructor_params: extract_constructor_params(class_decl),
+        file_imports: Some(imports_map.clone()),
         ..Default::default()
     };
 
@@ -1355,14 +1610,19 @@ pub fn get_all_metadata<'a>(
                             directives.push(metadata);
                         }
                     } else if decorator.name == "Pipe" {
-                        if let Some(metadata) = extract_pipe_metadata(class_decl, &decorator, path)
+                        if let Some(metadata) =
+                            extract_pipe_metadata(class_decl, &decorator, path, &imports_map)
                         {
                             directives.push(metadata);
                         }
                     } else if decorator.name == "Injectable" {
-                        if let Some(metadata) =
-                            extract_injectable_metadata(class_decl, &decorator, path)
-                        {
+                        if let Some(metadata) = extract_injectable_metadata(
+                            class_decl,
+                            &decorator,
+                            path,
+                            program.source_text,
+                            &imports_map,
+                        ) {
                             directives.push(metadata);
                         }
                     } else if decorator.name == "NgModule" {
@@ -1398,6 +1658,12 @@ fn extract_string_value(expr: &oxc_ast::ast::Expression) -> Option<String> {
     }
 }
 
+/// Start and length of `expr` in its source file.
+fn literal_span(expr: &oxc_ast::ast::Expression) -> (usize, usize) {
+    let span = expr.span();
+    (span.start as usize, span.size() as usize)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1737,6 +2003,123 @@ mod tests {
             panic!("Expected Directive metadata");
         }
     }
+
+    #[test]
+    fn test_extract_injectable_providers() {
+        let source = r#"
+            import {Injectable, Optional, forwardRef} from '@angular/core';
+            import {Api, PlatformModule} from './api';
+
+            @Injectable({
+                providedIn: forwardRef(() => PlatformModule),
+                useFactory: (api: Api): Service => new Service(api),
+                deps: [[new Optional(), Api]],
+                useValue: undefined
+            })
+            export class Service {}
+        "#;
+
+        let allocator = Allocator::default();
+        let program = TestProgram::new(&allocator, source);
+        let class_decl = program.find_class("Service").expect("Class not found");
+
+        let host = TypeScriptReflectionHost::new();
+        let decl = program
+            .find_declaration("Service")
+            .expect("Declaration not found");
+        let decorators = host.get_decorators_of_declaration(decl);
+        let decorator = decorators
+            .iter()
+            .find(|d| d.name == "Injectable")
+            .expect("Injectable decorator not found");
+
+        let path = std::path::Path::new("test.ts");
+        let imports = HashMap::from([
+            ("Optional".to_string(), "@angular/core".to_string()),
+            ("Api".to_string(), "./api".to_string()),
+        ]);
+
+        let metadata = extract_injectable_metadata(class_decl, decorator, path, source, &imports)
+            .expect("Metadata extraction failed");
+
+        let DecoratorMetadata::Injectable(inj) = metadata else {
+            panic!("Expected Injectable metadata");
+        };
+        use angular_compiler::output::output_ast::Expression as O;
+        use angular_compiler::render3::util::ForwardRefHandling;
+
+        let provided_in = inj.provided_in.expect("providedIn not found");
+        assert!(matches!(
+            provided_in.forward_ref,
+            ForwardRefHandling::Unwrapped
+        ));
+        assert!(matches!(&provided_in.expression, O::ReadVar(v) if v.name == "PlatformModule"));
+
+        // `useValue` takes precedence over `useFactory`, which is dropped along with its deps.
+        assert!(inj.use_value.is_some());
+        assert!(inj.use_factory.is_none());
+        assert!(inj.deps.is_none());
+    }
+
+    #[test]
+    fn test_extract_injectable_use_factory() {
+        let source = r#"
+            import {Injectable, Optional} from '@angular/core';
+            import {Api} from './api';
+
+            @Injectable({
+                providedIn: 'platform',
+                useFactory: (api: Api): Service => new Service(api),
+                deps: [[new Optional(), Api]]
+            })
+            export class Service {}
+        "#;
+
+        let allocator = Allocator::default();
+        let program = TestProgram::new(&allocator, source);
+        let class_decl = program.find_class("Service").expect("Class not found");
+
+        let host = TypeScriptReflectionHost::new();
+        let decl = program
+            .find_declaration("Service")
+            .expect("Declaration not found");
+        let decorators = host.get_decorators_of_declaration(decl);
+        let decorator = decorators
+            .iter()
+            .find(|d| d.name == "Injectable")
+            .expect("Injectable decorator not found");
+
+        let path = std::path::Path::new("test.ts");
+        let imports = HashMap::from([
+            ("Optional".to_string(), "@angular/core".to_string()),
+            ("Api".to_string(), "./api".to_string()),
+        ]);
+
+        let metadata = extract_injectable_metadata(class_decl, decorator, path, source, &imports)
+            .expect("Metadata extraction failed");
+
+        let DecoratorMetadata::Injectable(inj) = metadata else {
+            panic!("Expected Injectable metadata");
+        };
+        use angular_compiler::output::output_ast::Expression as O;
+
+        // The factory is kept as source, without its type annotations.
+        match inj.use_factory.expect("useFactory not found") {
+            O::RawCode(raw) => {
+                assert!(
+                    raw.code.contains("(api) => new Service(api)"),
+                    "{}",
+                    raw.code
+                );
+            }
+            other => panic!("Expected raw code, got {:?}", other),
+        }
+
+        let deps = inj.deps.expect("deps not found");
+        assert_eq!(deps.len(), 1);
+        assert!(deps[0].optional);
+        assert!(matches!(&deps[0].token, Some(O::ReadVar(v)) if v.name == "Api"));
+    }
 }
 
 pub fn convert_oxc_expression(
@@ -1,5 +1,7 @@
 use crate::ngtsc::core::NgCompilerOptions;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 // use crate::compiler::CompilationResult; // Removed to resolve conflict with ngtsc::core::CompilationResult
 // Let's use the one from ngtsc::core if exported, or fully qualify.
 // Actually, let's remove this import and use the one NgCompiler uses.
@@ -9,14 +11,23 @@ use std::path::Path;
 // I should change `program.rs` to use `crate::ngtsc::core::compiler::CompilationResult` (if that's what core one is).
 // Or check where NgCompiler comes from.
 // Import:
-use crate::ngtsc::core::{CompilationResult, CompilationTicket, CompilationTicketKind, NgCompiler};
+use crate::ngtsc::core::{
+    AnalysisCache, CompilationResult, CompilationTicket, CompilationTicketKind, NgCompiler,
+};
 use crate::ngtsc::file_system::FileSystem;
+use crate::ngtsc::xi18n::{Translations, Xi18nContext};
 
 pub struct NgtscProgram<'a, T: FileSystem> {
     root_names: Vec<String>,
     options: NgCompilerOptions,
     compiler: NgCompiler<'a, T>,
     result: Option<CompilationResult>,
+    /// Analysis of an earlier compilation of the program, and the resources changed since.
+    prior_analysis: AnalysisCache,
+    modified_resource_files: HashSet<String>,
+    /// The files to emit; all of them when unset.
+    affected_files: Option<HashSet<PathBuf>>,
+    analysis: AnalysisCache,
 }
 
 impl<'a, T: FileSystem> NgtscProgram<'a, T> {
@@ -33,16 +44,69 @@ impl<'a, T: FileSystem> NgtscProgram<'a, T> {
             options,
             compiler,
             result: None,
+            prior_analysis: AnalysisCache::default(),
+            modified_resource_files: HashSet::new(),
+            affected_files: None,
+            analysis: AnalysisCache::default(),
         }
     }
 
+    /// A program that reuses the analysis of an earlier compilation for the files that did
+    /// not change, and whose resources are not among `modified_resource_files`.
+    ///
+    /// With `affected_files`, only those files are analyzed again and emitted.
+    pub fn incremental(
+        root_names: Vec<String>,
+        options: NgCompilerOptions,
+        fs: &'a T,
+        mut prior_analysis: AnalysisCache,
+        modified_resource_files: HashSet<String>,
+        affected_files: Option<HashSet<PathBuf>>,
+    ) -> Self {
+        if let Some(files) = &affected_files {
+            prior_analysis.invalidate(files);
+        }
+        let ticket = CompilationTicket {
+            kind: CompilationTicketKind::Incremental,
+            options: options.clone(),
+            fs,
+        };
+        NgtscProgram {
+            root_names,
+            options,
+            compiler: NgCompiler::new(ticket),
+            result: None,
+            prior_analysis,
+            modified_resource_files,
+            affected_files,
+            analysis: AnalysisCache::default(),
+        }
+    }
+
+    /// Take the analysis of the program, for the next compilation of it.
+    pub fn take_analysis(&mut self) -> AnalysisCache {
+        std::mem::take(&mut self.analysis)
+    }
+
     pub fn load_ng_structure(&mut self, _path: &Path) -> Result<(), String> {
         // eprintln!("DEBUG: NgtscProgram::load_ng_structure called with {} root files", self.root_names.len());
         for name in &self.root_names {
             // eprintln!("DEBUG: Root file: {}", name);
         }
         // We trigger analysis with the root files we know about
-        let res = self.compiler.analyze_async(&self.root_names)?;
+        let prior_analysis = std::mem::take(&mut self.prior_analysis);
+        let (mut res, analysis) = self.compiler.analyze_incremental(
+            &self.root_names,
+            &prior_analysis,
+            &self.modified_resource_files,
+        )?;
+        self.analysis = analysis;
+        if self.options.strict_templates {
+            let template_diagnostics = self.compiler.get_template_diagnostics(&res);
+            res.diagnostics.extend(template_diagnostics);
+            let extended_diagnostics = self.compiler.get_extended_template_diagnostics(&res);
+            res.diagnostics.extend(extended_diagnostics);
+        }
         self.result = Some(res);
         Ok(())
     }
@@ -59,7 +123,41 @@ impl<'a, T: FileSystem> NgtscProgram<'a, T> {
         // Ensure analysis happens if not already done (simplified)
         // In reality, load_ng_structure is called before emit.
         if let Some(result) = &self.result {
-            self.compiler.emit(result)
+            self.compiler.emit(&self.emitted(result))
+        } else {
+            Err("Compilation result not available. Did you call load_ng_structure?".to_string())
+        }
+    }
+
+    /// Emit the program once per locale of `translations`, each into its own directory
+    /// under the output directory.
+    pub fn emit_for_locales(
+        &self,
+        translations: &[Translations],
+    ) -> Result<Vec<crate::ngtsc::core::Diagnostic>, String> {
+        if let Some(result) = &self.result {
+            self.compiler
+                .emit_for_locales(&self.emitted(result), translations)
+        } else {
+            Err("Compilation result not available. Did you call load_ng_structure?".to_string())
+        }
+    }
+
+    /// The part of `result` to emit.
+    fn emitted<'r>(&self, result: &'r CompilationResult) -> Cow<'r, CompilationResult> {
+        match &self.affected_files {
+            Some(files) => Cow::Owned(result.for_files(files)),
+            None => Cow::Borrowed(result),
+        }
+    }
+
+    /// Collect the i18n messages of the program's templates in `ctx`.
+    pub fn xi18n(
+        &self,
+        ctx: &mut dyn Xi18nContext,
+    ) -> Result<Vec<crate::ngtsc::core::Diagnostic>, String> {
+        if let Some(result) = &self.result {
+            Ok(self.compiler.xi18n(result, ctx))
         } else {
             Err("Compilation result not available. Did you call load_ng_structure?".to_string())
         }
@@ -2,6 +2,7 @@
 //
 // Loads external resources (templates, styles).
 
+use crate::ngtsc::file_system::{AbsoluteFsPath, ReadonlyFileSystem};
 use std::collections::HashMap;
 
 /// Resource load error.
@@ -32,6 +33,12 @@ pub trait ResourceLoader {
     fn can_preload(&self, url: &str) -> bool;
     fn preload(&self, url: &str) -> Result<(), ResourceError>;
     fn load(&self, url: &str) -> Result<String, ResourceError>;
+
+    /// Resolve `url`, as written in the file `from_file`, to the URL passed to `load`.
+    /// Loaders keyed by the URL itself keep it unchanged.
+    fn resolve(&self, url: &str, _from_file: &str) -> Result<String, ResourceError> {
+        Ok(url.to_string())
+    }
 }
 
 /// In-memory resource loader.
@@ -99,3 +106,47 @@ impl ResourceLoader for FileResourceLoader {
         std::fs::read_to_string(&path).map_err(|e| ResourceError::load_failed(url, &e.to_string()))
     }
 }
+
+/// Resource loader backed by the compiler's file system. URLs are resolved relative to
+/// the directory of the file that references them.
+pub struct AdapterResourceLoader<'a, T: ReadonlyFileSystem> {
+    fs: &'a T,
+}
+
+impl<'a, T: ReadonlyFileSystem> AdapterResourceLoader<'a, T> {
+    pub fn new(fs: &'a T) -> Self {
+        Self { fs }
+    }
+}
+
+impl<T: ReadonlyFileSystem> ResourceLoader for AdapterResourceLoader<'_, T> {
+    fn can_preload(&self, url: &str) -> bool {
+        self.fs.exists(&AbsoluteFsPath::new(url.to_string()))
+    }
+
+    fn preload(&self, url: &str) -> Result<(), ResourceError> {
+        if self.can_preload(url) {
+            Ok(())
+        } else {
+            Err(ResourceError::not_found(url))
+        }
+    }
+
+    fn load(&self, url: &str) -> Result<String, ResourceError> {
+        self.fs
+            .read_file(&AbsoluteFsPath::new(url.to_string()))
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ResourceError::not_found(url),
+                _ => ResourceError::load_failed(url, &e.to_string()),
+            })
+    }
+
+    fn resolve(&self, url: &str, from_file: &str) -> Result<String, ResourceError> {
+        let resolved = self.fs.resolve(&[&self.fs.dirname(from_file), url]);
+        if self.fs.exists(&resolved) {
+            Ok(resolved.to_string())
+        } else {
+            Err(ResourceError::not_found(url))
+        }
+    }
+}
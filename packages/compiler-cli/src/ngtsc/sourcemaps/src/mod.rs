@@ -5,7 +5,10 @@ pub mod raw_source_map;
 pub mod segment_marker;
 pub mod source_file;
 pub mod source_file_loader;
+pub mod text_edit;
 
 pub use raw_source_map::*;
 pub use segment_marker::*;
 pub use source_file::*;
+pub use source_file_loader::*;
+pub use text_edit::*;
@@ -2,18 +2,45 @@
 //
 // Raw source map data structure.
 
+use serde::{Deserialize, Serialize};
+
+use super::segment_marker::SegmentMarker;
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
 /// Raw source map (version 3).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceMap {
     pub version: u32,
+    #[serde(default)]
     pub file: String,
+    #[serde(
+        rename = "sourceRoot",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub source_root: Option<String>,
     pub sources: Vec<String>,
+    #[serde(
+        rename = "sourcesContent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub sources_content: Option<Vec<Option<String>>>,
+    #[serde(default)]
     pub names: Vec<String>,
     pub mappings: String,
 }
 
+/// A decoded mapping segment on a generated line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappingSegment {
+    /// Column in the generated line (0-indexed, UTF-16 code units).
+    pub generated_column: u32,
+    /// Original position, if the segment maps back to a source.
+    pub original: Option<SegmentMarker>,
+}
+
 impl SourceMap {
     pub fn new(file: impl Into<String>) -> Self {
         Self {
@@ -26,12 +53,153 @@ impl SourceMap {
             mappings: String::new(),
         }
     }
+
+    /// A map of `content` onto itself, with a segment at the start of each line and of
+    /// every token that follows whitespace.
+    pub fn identity(source: &str, content: &str) -> Self {
+        let mut map = Self::new(source);
+        map.add_source(source, Some(content.to_string()));
+
+        let lines: Vec<Vec<MappingSegment>> = content
+            .split('\n')
+            .enumerate()
+            .map(|(line, text)| {
+                let mut segments = Vec::new();
+                let mut column = 0u32;
+                let mut after_space = true;
+                for ch in text.chars() {
+                    if ch.is_whitespace() {
+                        after_space = true;
+                    } else if after_space {
+                        after_space = false;
+                        segments.push(MappingSegment {
+                            generated_column: column,
+                            original: Some(SegmentMarker::new(line as u32, column).with_source(0)),
+                        });
+                    }
+                    column += ch.len_utf16() as u32;
+                }
+                segments
+            })
+            .collect();
+        map.mappings = encode_mappings(&lines);
+        map
+    }
+
+    /// Parse a source map from its JSON representation.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid source map: {}", e))
+    }
+
+    /// Serialize the source map to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Render the source map as an inline `//# sourceMappingURL=data:...` comment.
+    pub fn to_inline_comment(&self) -> String {
+        format!(
+            "//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
+            angular_compiler::output::source_map::to_base64_string(&self.to_json())
+        )
+    }
+
+    /// Decode the `mappings` field into per-line segments.
+    pub fn decode_mappings(&self) -> Result<Vec<Vec<MappingSegment>>, String> {
+        decode_mappings(&self.mappings)
+    }
+
+    /// Add a source, reusing an existing entry with the same name.
+    pub fn add_source(&mut self, source: &str, content: Option<String>) -> u32 {
+        if let Some(idx) = self.sources.iter().position(|s| s == source) {
+            return idx as u32;
+        }
+        if content.is_some() && self.sources_content.is_none() {
+            self.sources_content = Some(Vec::new());
+        }
+        if let Some(contents) = &mut self.sources_content {
+            contents.resize(self.sources.len(), None);
+            contents.push(content);
+        }
+        self.sources.push(source.to_string());
+        (self.sources.len() - 1) as u32
+    }
+
+    /// Merge the mappings of `other` into this map, placing the start of the code
+    /// `other` describes at the generated `line` and `column`.
+    pub fn append_at(&mut self, other: &SourceMap, line: usize, column: u32) -> Result<(), String> {
+        let mut lines = self.decode_mappings()?;
+
+        let source_ids: Vec<u32> = other
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(i, source)| {
+                let content = other
+                    .sources_content
+                    .as_ref()
+                    .and_then(|c| c.get(i).cloned().flatten());
+                self.add_source(source, content)
+            })
+            .collect();
+        let name_ids: Vec<u32> = other
+            .names
+            .iter()
+            .map(|name| match self.names.iter().position(|n| n == name) {
+                Some(idx) => idx as u32,
+                None => {
+                    self.names.push(name.clone());
+                    (self.names.len() - 1) as u32
+                }
+            })
+            .collect();
+
+        for (i, segments) in other.decode_mappings()?.into_iter().enumerate() {
+            let target = line + i;
+            if segments.is_empty() {
+                continue;
+            }
+            if lines.len() <= target {
+                lines.resize(target + 1, Vec::new());
+            }
+            for mut segment in segments {
+                if i == 0 {
+                    segment.generated_column += column;
+                }
+                if let Some(original) = &mut segment.original {
+                    original.source_index = original
+                        .source_index
+                        .and_then(|idx| source_ids.get(idx as usize).copied());
+                    original.name_index = original
+                        .name_index
+                        .and_then(|idx| name_ids.get(idx as usize).copied());
+                }
+                lines[target].push(segment);
+            }
+            lines[target].sort_by_key(|s| s.generated_column);
+        }
+
+        self.mappings = encode_mappings(&lines);
+        Ok(())
+    }
+
+    /// Remove the segments whose generated position lies within `start..end`, given as
+    /// (line, column) pairs.
+    pub fn clear_range(&mut self, start: (usize, u32), end: (usize, u32)) -> Result<(), String> {
+        let mut lines = self.decode_mappings()?;
+        for (line, segments) in lines.iter_mut().enumerate() {
+            segments.retain(|s| {
+                let pos = (line, s.generated_column);
+                pos < start || pos >= end
+            });
+        }
+        self.mappings = encode_mappings(&lines);
+        Ok(())
+    }
 }
 
 /// VLQ encoding for source maps.
 pub fn encode_vlq(mut value: i32) -> String {
-    const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-
     let mut result = String::new();
     let negative = value < 0;
 
@@ -62,3 +230,124 @@ pub fn encode_vlq(mut value: i32) -> String {
 
     result
 }
+
+/// Decode the VLQ values of a single mapping segment (the text between commas).
+pub fn decode_vlq(segment: &str) -> Result<Vec<i32>, String> {
+    let mut values = Vec::new();
+    let mut value: i64 = 0;
+    let mut shift = 0;
+
+    for c in segment.bytes() {
+        let digit = BASE64_CHARS
+            .iter()
+            .position(|&b| b == c)
+            .ok_or_else(|| format!("Invalid base64 character '{}' in mappings", c as char))?
+            as i64;
+
+        value += (digit & 0x1F) << shift;
+        if digit & 0x20 != 0 {
+            shift += 5;
+            if shift > 30 {
+                return Err("VLQ value out of range in mappings".to_string());
+            }
+            continue;
+        }
+
+        let negative = value & 1 == 1;
+        let magnitude = (value >> 1) as i32;
+        values.push(if negative { -magnitude } else { magnitude });
+        value = 0;
+        shift = 0;
+    }
+
+    if shift != 0 {
+        return Err("Unterminated VLQ value in mappings".to_string());
+    }
+    Ok(values)
+}
+
+/// Decode a `mappings` string into absolute, per-line segments.
+pub fn decode_mappings(mappings: &str) -> Result<Vec<Vec<MappingSegment>>, String> {
+    let mut lines = Vec::new();
+    let (mut source, mut src_line, mut src_col, mut name) = (0i64, 0i64, 0i64, 0i64);
+
+    for line in mappings.split(';') {
+        let mut segments = Vec::new();
+        let mut gen_col = 0i64;
+
+        for segment in line.split(',').filter(|s| !s.is_empty()) {
+            let values = decode_vlq(segment)?;
+            gen_col += values[0] as i64;
+
+            let original = match values.len() {
+                1 => None,
+                4 | 5 => {
+                    source += values[1] as i64;
+                    src_line += values[2] as i64;
+                    src_col += values[3] as i64;
+                    let mut marker = SegmentMarker::new(src_line as u32, src_col as u32)
+                        .with_source(source as u32);
+                    if values.len() == 5 {
+                        name += values[4] as i64;
+                        marker = marker.with_name(name as u32);
+                    }
+                    Some(marker)
+                }
+                n => return Err(format!("Invalid mapping segment with {} fields", n)),
+            };
+
+            if gen_col < 0 || source < 0 || src_line < 0 || src_col < 0 || name < 0 {
+                return Err("Negative position in mappings".to_string());
+            }
+
+            segments.push(MappingSegment {
+                generated_column: gen_col as u32,
+                original,
+            });
+        }
+
+        lines.push(segments);
+    }
+
+    Ok(lines)
+}
+
+/// Encode absolute, per-line segments into a `mappings` string.
+pub fn encode_mappings(lines: &[Vec<MappingSegment>]) -> String {
+    let mut out = String::new();
+    let (mut source, mut src_line, mut src_col, mut name) = (0i64, 0i64, 0i64, 0i64);
+
+    for (i, segments) in lines.iter().enumerate() {
+        if i > 0 {
+            out.push(';');
+        }
+        let mut gen_col = 0i64;
+
+        for (j, segment) in segments.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            out.push_str(&encode_vlq(
+                (segment.generated_column as i64 - gen_col) as i32,
+            ));
+            gen_col = segment.generated_column as i64;
+
+            if let Some(original) = &segment.original {
+                let src = original.source_index.unwrap_or(0) as i64;
+                out.push_str(&encode_vlq((src - source) as i32));
+                out.push_str(&encode_vlq((original.line as i64 - src_line) as i32));
+                out.push_str(&encode_vlq((original.column as i64 - src_col) as i32));
+                source = src;
+                src_line = original.line as i64;
+                src_col = original.column as i64;
+
+                if let Some(name_index) = original.name_index {
+                    out.push_str(&encode_vlq((name_index as i64 - name) as i32));
+                    name = name_index as i64;
+                }
+            }
+        }
+    }
+
+    out
+}
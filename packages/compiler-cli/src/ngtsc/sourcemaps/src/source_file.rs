@@ -2,6 +2,7 @@
 //
 // Source file representation for source maps.
 
+use super::raw_source_map::{encode_mappings, MappingSegment};
 use super::segment_marker::SegmentMarker;
 
 /// Source map builder.
@@ -10,7 +11,7 @@ pub struct SourceMapBuilder {
     sources: Vec<String>,
     sources_content: Vec<Option<String>>,
     names: Vec<String>,
-    mappings: Vec<Vec<SegmentMarker>>,
+    mappings: Vec<Vec<MappingSegment>>,
 }
 
 impl SourceMapBuilder {
@@ -60,7 +61,14 @@ impl SourceMapBuilder {
             marker = marker.with_name(idx as u32);
         }
 
-        self.mappings[gen_line as usize].push(marker);
+        let line = &mut self.mappings[gen_line as usize];
+        let segment = MappingSegment {
+            generated_column: gen_col,
+            original: Some(marker),
+        };
+        // Keep segments ordered by generated column, as the encoding requires.
+        let idx = line.partition_point(|s| s.generated_column <= gen_col);
+        line.insert(idx, segment);
     }
 
     pub fn build(self) -> super::raw_source_map::SourceMap {
@@ -77,7 +85,6 @@ impl SourceMapBuilder {
     }
 
     fn encode_mappings(&self) -> String {
-        // Simplified encoding
-        "AAAA".to_string()
+        encode_mappings(&self.mappings)
     }
 }
@@ -2,9 +2,17 @@
 //
 // Loads source files and their source maps.
 
+use std::path::{Component, Path, PathBuf};
+
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+
 use super::content_origin::ContentOrigin;
 use super::raw_source_map::SourceMap;
 
+const SOURCE_MAPPING_URL_PREFIXES: [&str; 2] = ["//# sourceMappingURL=", "//@ sourceMappingURL="];
+
 /// Source file loader.
 pub struct SourceFileLoader {
     root_dir: String,
@@ -19,43 +27,163 @@ impl SourceFileLoader {
 
     /// Load a source file.
     pub fn load_source_file(&self, path: &str) -> Result<LoadedFile, String> {
-        let full_path = format!("{}/{}", self.root_dir, path);
+        let full_path = self.resolve(path);
 
         let content = std::fs::read_to_string(&full_path)
             .map_err(|e| format!("Failed to read {}: {}", path, e))?;
 
-        let source_map = self.find_source_map(&content, path);
+        let source_map = self.load_source_map(path, &content);
 
         Ok(LoadedFile {
             path: path.to_string(),
             content,
             source_map,
-            origin: ContentOrigin::File(full_path),
+            origin: ContentOrigin::File(full_path.to_string_lossy().into_owned()),
         })
     }
 
-    /// Find source map for content.
-    fn find_source_map(&self, content: &str, _path: &str) -> Option<SourceMap> {
-        // Check for inline source map
-        if content.contains("//# sourceMappingURL=data:") {
-            // Would parse inline source map
-            return None;
+    /// Load the source map referenced by the `sourceMappingURL` comment of `content`,
+    /// the contents of the file at `path`.
+    ///
+    /// Both inline (`data:` URL) and external maps are supported. External maps are
+    /// resolved relative to `path`, and their sources are rebased from the map's location
+    /// onto `path`, so that the map can be used for the file at `path`.
+    pub fn load_source_map(&self, path: &str, content: &str) -> Option<SourceMap> {
+        let url = source_mapping_url(content)?;
+
+        if let Some(data) = url.strip_prefix("data:") {
+            let (header, payload) = data.split_once(',')?;
+            let json = if header.ends_with(";base64") {
+                String::from_utf8(decode_base64(payload)?).ok()?
+            } else {
+                payload.to_string()
+            };
+            return SourceMap::from_json(&json).ok();
+        }
+
+        let file_dir = self
+            .resolve(path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let map_path = file_dir.join(url);
+        let json = std::fs::read_to_string(&map_path).ok()?;
+        let mut map = SourceMap::from_json(&json).ok()?;
+        rebase_sources(
+            &mut map,
+            map_path.parent().unwrap_or(Path::new("")),
+            &file_dir,
+        );
+        Some(map)
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        if Path::new(path).is_absolute() || self.root_dir.is_empty() {
+            PathBuf::from(path)
+        } else {
+            Path::new(&self.root_dir).join(path)
+        }
+    }
+}
+
+/// Make the sources of `map`, which are relative to `map_dir` and its `sourceRoot`,
+/// relative to `file_dir` instead. Sources that are URLs or absolute paths are kept.
+fn rebase_sources(map: &mut SourceMap, map_dir: &Path, file_dir: &Path) {
+    let source_root = match map.source_root.take() {
+        Some(root) if is_url(&root) => {
+            map.source_root = Some(root);
+            return;
+        }
+        root => root.unwrap_or_default(),
+    };
+    let base = map_dir.join(source_root);
+    for source in &mut map.sources {
+        if is_url(source) || Path::new(source.as_str()).is_absolute() {
+            continue;
         }
+        *source = relative_path(file_dir, &base.join(source.as_str()));
+    }
+}
+
+fn is_url(path: &str) -> bool {
+    path.contains("://")
+}
+
+/// The path of `to` relative to the directory `from`, with `/` separators. Both paths are
+/// normalized lexically; `to` is returned as is if only one of them is absolute.
+fn relative_path(from: &Path, to: &Path) -> String {
+    let (from, to) = (normalize(from), normalize(to));
+    if from.is_absolute() != to.is_absolute() {
+        return to.to_string_lossy().replace('\\', "/");
+    }
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut segments = vec!["..".to_string(); from.len() - common];
+    segments.extend(
+        to[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+    );
+    segments.join("/")
+}
 
-        // Check for external source map
-        if let Some(url_line) = content
-            .lines()
-            .find(|l| l.starts_with("//# sourceMappingURL="))
-        {
-            let _url = url_line.trim_start_matches("//# sourceMappingURL=");
-            // Would load external source map
-            return None;
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            other => normalized.push(other),
         }
+    }
+    normalized
+}
+
+/// The URL of the last `sourceMappingURL` comment in `content`, if any.
+pub fn source_mapping_url(content: &str) -> Option<&str> {
+    content.lines().rev().find_map(|line| {
+        let line = line.trim();
+        SOURCE_MAPPING_URL_PREFIXES
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+    })
+}
 
-        None
+/// Remove a trailing `sourceMappingURL` comment from `content`.
+pub fn strip_source_mapping_url(content: &str) -> &str {
+    let trimmed = content.trim_end();
+    let line_start = trimmed.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let last_line = &trimmed[line_start..];
+    if SOURCE_MAPPING_URL_PREFIXES
+        .iter()
+        .any(|prefix| last_line.starts_with(prefix))
+    {
+        &content[..line_start]
+    } else {
+        content
     }
 }
 
+/// Decode the base64 payload of a `data:` URL. Padding is optional.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+    let input: String = input.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    ENGINE.decode(input).ok()
+}
+
 /// Loaded source file.
 #[derive(Debug, Clone)]
 pub struct LoadedFile {
@@ -0,0 +1,234 @@
+// Text Edit
+//
+// Tracks textual replacements applied to generated code after a source map was
+// produced for it, so that the map can be moved onto the edited text.
+
+use super::raw_source_map::{encode_mappings, MappingSegment, SourceMap};
+use std::ops::Range;
+
+/// A replacement of the byte range `start..end` of the original text with
+/// `replacement_len` bytes of new text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement_len: usize,
+    /// Index of the replacement (as passed to `replace_all_tracked`) that was applied.
+    pub replacement: usize,
+}
+
+/// Replace every occurrence of each pattern in a single left-to-right pass,
+/// returning the new text together with the edits that were applied.
+///
+/// When several patterns match at the same position the first one wins.
+pub fn replace_all_tracked(
+    code: &str,
+    replacements: &[(String, String)],
+) -> (String, Vec<TextEdit>) {
+    let mut out = String::with_capacity(code.len());
+    let mut edits = Vec::new();
+    let mut i = 0;
+
+    'outer: while i < code.len() {
+        for (index, (from, to)) in replacements.iter().enumerate() {
+            if !from.is_empty() && code[i..].starts_with(from.as_str()) {
+                edits.push(TextEdit {
+                    start: i,
+                    end: i + from.len(),
+                    replacement_len: to.len(),
+                    replacement: index,
+                });
+                out.push_str(to);
+                i += from.len();
+                continue 'outer;
+            }
+        }
+        let ch = code[i..].chars().next().unwrap();
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+
+    (out, edits)
+}
+
+/// Replace byte ranges of `code`, returning the new text together with the edits
+/// that were applied. The ranges must not overlap.
+pub fn replace_ranges_tracked(
+    code: &str,
+    replacements: &[(Range<usize>, String)],
+) -> (String, Vec<TextEdit>) {
+    let mut order: Vec<usize> = (0..replacements.len()).collect();
+    order.sort_by_key(|&index| replacements[index].0.start);
+
+    let mut out = String::with_capacity(code.len());
+    let mut edits = Vec::new();
+    let mut i = 0;
+    for index in order {
+        let (range, to) = &replacements[index];
+        out.push_str(&code[i..range.start]);
+        out.push_str(to);
+        edits.push(TextEdit {
+            start: range.start,
+            end: range.end,
+            replacement_len: to.len(),
+            replacement: index,
+        });
+        i = range.end;
+    }
+    out.push_str(&code[i..]);
+
+    (out, edits)
+}
+
+/// Move the generated positions of `map` from `original` onto `updated`, where
+/// `updated` is `original` with `edits` applied.
+///
+/// Segments that pointed into a replaced range are moved to the start of the
+/// replacement.
+pub fn remap_through_edits(
+    map: &SourceMap,
+    original: &str,
+    updated: &str,
+    edits: &[TextEdit],
+) -> Result<SourceMap, String> {
+    if edits.is_empty() {
+        return Ok(map.clone());
+    }
+
+    let before = LineIndex::new(original);
+    let after = LineIndex::new(updated);
+    let mut lines: Vec<Vec<MappingSegment>> = Vec::new();
+
+    for (line, segments) in map.decode_mappings()?.into_iter().enumerate() {
+        for segment in segments {
+            let offset = match before.offset_of(line, segment.generated_column) {
+                Some(offset) => offset,
+                None => continue,
+            };
+            let (new_line, new_column) = after.position_of(shift_offset(offset, edits));
+
+            while lines.len() <= new_line {
+                lines.push(Vec::new());
+            }
+            let target = &mut lines[new_line];
+            // Segments collapsed onto the same column only keep the first mapping.
+            if target.last().map(|s| s.generated_column) == Some(new_column) {
+                continue;
+            }
+            target.push(MappingSegment {
+                generated_column: new_column,
+                original: segment.original,
+            });
+        }
+    }
+
+    let mut remapped = map.clone();
+    remapped.mappings = encode_mappings(&lines);
+    Ok(remapped)
+}
+
+/// Map the byte `offset` in `generated` back to a byte offset in `original`, the
+/// source `map` describes `generated` in terms of.
+///
+/// Offsets between two segments resolve through the closest segment before them on
+/// the same line.
+pub fn original_offset(
+    map: &SourceMap,
+    generated: &str,
+    original: &str,
+    offset: usize,
+) -> Option<usize> {
+    let (line, column) = LineIndex::new(generated).position_of(offset);
+    let lines = map.decode_mappings().ok()?;
+    let segment = lines
+        .get(line)?
+        .iter()
+        .filter(|segment| segment.generated_column <= column && segment.original.is_some())
+        .last()?;
+    let marker = segment.original.as_ref()?;
+    let delta = column - segment.generated_column;
+    LineIndex::new(original).offset_of(marker.line as usize, marker.column + delta)
+}
+
+/// Overlay the source maps of replacement texts onto `map`, a map of `updated`.
+///
+/// `replacement_maps` is parallel to the replacements passed to `replace_all_tracked`.
+/// Segments of `map` that fall inside a replacement with its own map are dropped in
+/// favour of that map.
+pub fn overlay_replacement_maps(
+    map: &SourceMap,
+    updated: &str,
+    edits: &[TextEdit],
+    replacement_maps: &[Option<&SourceMap>],
+) -> Result<SourceMap, String> {
+    let index = LineIndex::new(updated);
+    let mut result = map.clone();
+    let mut delta: isize = 0;
+
+    for edit in edits {
+        let start = (edit.start as isize + delta) as usize;
+        delta += edit.replacement_len as isize - (edit.end - edit.start) as isize;
+
+        if let Some(Some(inner)) = replacement_maps.get(edit.replacement) {
+            let from = index.position_of(start);
+            let to = index.position_of(start + edit.replacement_len);
+            result.clear_range(from, to)?;
+            result.append_at(inner, from.0, from.1)?;
+        }
+    }
+
+    Ok(result)
+}
+
+fn shift_offset(offset: usize, edits: &[TextEdit]) -> usize {
+    let mut delta: isize = 0;
+    for edit in edits {
+        if offset < edit.start {
+            break;
+        }
+        if offset < edit.end {
+            return (edit.start as isize + delta) as usize;
+        }
+        delta += edit.replacement_len as isize - (edit.end - edit.start) as isize;
+    }
+    (offset as isize + delta) as usize
+}
+
+/// Converts between byte offsets and source map positions, whose columns are
+/// measured in UTF-16 code units.
+struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self { text, line_starts }
+    }
+
+    fn offset_of(&self, line: usize, column: u32) -> Option<usize> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        let mut units = 0u32;
+        for (i, ch) in self.text[start..end].char_indices() {
+            if units >= column {
+                return Some(start + i);
+            }
+            units += ch.len_utf16() as u32;
+        }
+        Some(end)
+    }
+
+    fn position_of(&self, offset: usize) -> (usize, u32) {
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let start = self.line_starts[line];
+        let column = self.text[start..offset].encode_utf16().count() as u32;
+        (line, column)
+    }
+}
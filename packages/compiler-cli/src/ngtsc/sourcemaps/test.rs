@@ -19,6 +19,18 @@ mod tests {
         fn should_encode_positive_numbers() {
             assert_eq!(encode_vlq(1), "C");
         }
+
+        #[test]
+        fn should_round_trip_through_decode() {
+            for value in [0, 1, -1, 15, 16, -16, 1000, -123456] {
+                assert_eq!(decode_vlq(&encode_vlq(value)).unwrap(), vec![value]);
+            }
+        }
+
+        #[test]
+        fn should_reject_invalid_characters() {
+            assert!(decode_vlq("A!").is_err());
+        }
     }
 
     mod source_map_tests {
@@ -39,6 +51,247 @@ mod tests {
             assert_eq!(map.version, 3);
             assert_eq!(map.sources.len(), 1);
         }
+
+        #[test]
+        fn should_parse_and_serialize_json() {
+            let map = SourceMap::from_json(
+                r#"{"version":3,"sources":["a.ts"],"sourcesContent":["x"],"names":[],"mappings":"AAAA;AACA"}"#,
+            )
+            .unwrap();
+            assert_eq!(map.sources, vec!["a.ts".to_string()]);
+            assert_eq!(map.file, "");
+
+            let json = map.to_json();
+            assert!(json.contains(r#""sourcesContent":["x"]"#));
+            assert!(!json.contains("sourceRoot"));
+        }
+
+        #[test]
+        fn should_round_trip_mappings() {
+            let mappings = "AAAA,IAAIA;;ACCE,SAAS";
+            let decoded = decode_mappings(mappings).unwrap();
+            assert_eq!(decoded.len(), 3);
+            assert_eq!(decoded[2][0].original.unwrap().source_index, Some(1));
+            assert_eq!(encode_mappings(&decoded), mappings);
+        }
+    }
+
+    mod text_edit_tests {
+        use super::*;
+
+        fn map_for(mappings: &str) -> SourceMap {
+            let mut map = SourceMap::new("out.js");
+            map.sources.push("in.ts".to_string());
+            map.mappings = mappings.to_string();
+            map
+        }
+
+        #[test]
+        fn should_track_replacements() {
+            let replacements = vec![
+                ("'ph'".to_string(), "i0.expr()".to_string()),
+                ("ɵUNIQUE_FAC".to_string(), "ɵfac".to_string()),
+            ];
+            let (code, edits) = replace_all_tracked("a = 'ph'; b.ɵUNIQUE_FAC;", &replacements);
+            assert_eq!(code, "a = i0.expr(); b.ɵfac;");
+            assert_eq!(edits.len(), 2);
+            assert_eq!(edits[0].start, 4);
+        }
+
+        #[test]
+        fn should_track_range_replacements() {
+            let code = "f($localize`Hi`, $localize`Bye`);";
+            let replacements = vec![
+                (17..31, "'Au revoir'".to_string()),
+                (2..15, "'Salut'".to_string()),
+            ];
+            let (updated, edits) = replace_ranges_tracked(code, &replacements);
+            assert_eq!(updated, "f('Salut', 'Au revoir');");
+            assert_eq!((edits[0].start, edits[0].replacement), (2, 1));
+            assert_eq!((edits[1].start, edits[1].replacement_len), (17, 11));
+        }
+
+        #[test]
+        fn should_map_generated_offsets_back_to_the_original() {
+            // `y = 1;` was generated from the second line of `// c\nx = 1;`.
+            let map = map_for("AACA,IAAI");
+            assert_eq!(original_offset(&map, "y = 1;", "// c\nx = 1;", 5), Some(10));
+            assert_eq!(original_offset(&map, "y = 1;", "// c\nx = 1;", 2), Some(7));
+        }
+
+        #[test]
+        fn should_shift_segments_after_an_edit() {
+            // `x = 'ph'; y;` with segments at the columns of `x`, `'ph'` and `y`.
+            let original = "x = 'ph'; y;";
+            let map = map_for("AAAA,IAAI,MAAM");
+            let replacements = vec![("'ph'".to_string(), "longer()".to_string())];
+            let (updated, edits) = replace_all_tracked(original, &replacements);
+
+            let remapped = remap_through_edits(&map, original, &updated, &edits).unwrap();
+            let columns: Vec<u32> = remapped.decode_mappings().unwrap()[0]
+                .iter()
+                .map(|s| s.generated_column)
+                .collect();
+            assert_eq!(columns, vec![0, 4, 14]);
+        }
+
+        #[test]
+        fn should_measure_columns_in_utf16() {
+            // 'ɵ' is two bytes in UTF-8 but a single UTF-16 code unit.
+            let original = "ɵUNIQUE_DIR = 1; z;";
+            let map = map_for("AAAA,iBAAiB");
+            let replacements = vec![("ɵUNIQUE_DIR".to_string(), "ɵcmp".to_string())];
+            let (updated, edits) = replace_all_tracked(original, &replacements);
+
+            let remapped = remap_through_edits(&map, original, &updated, &edits).unwrap();
+            let segments = &remapped.decode_mappings().unwrap()[0];
+            assert_eq!(segments[1].generated_column, 10);
+            assert_eq!(segments[1].original.unwrap().column, 17);
+        }
+
+        #[test]
+        fn should_append_map_at_offset() {
+            let mut map = map_for("AAAA");
+            let mut inner = SourceMap::new("");
+            inner.sources.push("template.html".to_string());
+            inner.mappings = "AAAA,EAAE;AACA".to_string();
+
+            map.append_at(&inner, 0, 10).unwrap();
+            assert_eq!(map.sources, vec!["in.ts", "template.html"]);
+            let lines = map.decode_mappings().unwrap();
+            let columns: Vec<u32> = lines[0].iter().map(|s| s.generated_column).collect();
+            assert_eq!(columns, vec![0, 10, 12]);
+            assert_eq!(lines[0][1].original.unwrap().source_index, Some(1));
+            assert_eq!(lines[1][0].generated_column, 0);
+        }
+
+        #[test]
+        fn should_overlay_replacement_maps() {
+            let original = "x = 'ph'; y;";
+            let map = map_for("AAAA,IAAI,MAAM");
+            let replacements = vec![("'ph'".to_string(), "tpl()".to_string())];
+            let (updated, edits) = replace_all_tracked(original, &replacements);
+            let remapped = remap_through_edits(&map, original, &updated, &edits).unwrap();
+
+            let mut inner = SourceMap::new("");
+            inner.sources.push("template.html".to_string());
+            inner.mappings = "AAEA".to_string();
+            let overlaid =
+                overlay_replacement_maps(&remapped, &updated, &edits, &[Some(&inner)]).unwrap();
+
+            let segments = &overlaid.decode_mappings().unwrap()[0];
+            assert_eq!(segments.len(), 3);
+            let tpl = segments[1].original.unwrap();
+            assert_eq!(segments[1].generated_column, 4);
+            assert_eq!((tpl.source_index, tpl.line), (Some(1), 2));
+            assert_eq!(segments[2].generated_column, 11);
+        }
+    }
+
+    mod source_file_loader_tests {
+        use super::*;
+
+        #[test]
+        fn should_load_inline_source_maps() {
+            let json = r#"{"version":3,"sources":["a.ts"],"names":[],"mappings":"AAAA"}"#;
+            let content = format!(
+                "x;\n{}\n",
+                SourceMap::from_json(json).unwrap().to_inline_comment()
+            );
+            let map = SourceFileLoader::new("")
+                .load_source_map("a.js", &content)
+                .unwrap();
+            assert_eq!(map.sources, vec!["a.ts".to_string()]);
+            assert_eq!(map.mappings, "AAAA");
+        }
+
+        #[test]
+        fn should_decode_base64_data_urls_with_or_without_padding() {
+            let payload = "eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImEudHMiXSwibWFwcGluZ3MiOiJBQUFBIn0";
+            let loader = SourceFileLoader::new("");
+            for url in [format!("{}=", payload), payload.to_string()] {
+                let content = format!(
+                    "x;\n//# sourceMappingURL=data:application/json;base64,{}",
+                    url
+                );
+                let map = loader.load_source_map("a.js", &content).unwrap();
+                assert_eq!(map.sources, vec!["a.ts".to_string()]);
+            }
+        }
+
+        #[test]
+        fn should_reject_invalid_base64_data_urls() {
+            let payload = "eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImEudHMiXSwibWFwcGluZ3MiOiJBQUFBIn0";
+            let loader = SourceFileLoader::new("");
+            for payload in [
+                format!("{}={}", &payload[..4], &payload[4..]),
+                format!("{}!", payload),
+            ] {
+                let content = format!(
+                    "x;\n//# sourceMappingURL=data:application/json;base64,{}",
+                    payload
+                );
+                assert!(loader.load_source_map("a.js", &content).is_none());
+            }
+        }
+
+        #[test]
+        fn should_load_external_source_maps() {
+            let dir = std::env::temp_dir().join("ngtsc_source_file_loader_test");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("b.js.map"),
+                r#"{"version":3,"sources":["b.ts"],"mappings":"AAAA"}"#,
+            )
+            .unwrap();
+
+            let loader = SourceFileLoader::new(dir.to_string_lossy());
+            let map = loader
+                .load_source_map("b.js", "y;\n//# sourceMappingURL=b.js.map")
+                .unwrap();
+            assert_eq!(map.sources, vec!["b.ts".to_string()]);
+            assert!(loader.load_source_map("c.js", "z;").is_none());
+        }
+
+        #[test]
+        fn should_rebase_sources_of_external_maps_onto_the_file() {
+            let dir = std::env::temp_dir().join("ngtsc_source_file_loader_rebase_test");
+            std::fs::create_dir_all(dir.join("dist/maps")).unwrap();
+            std::fs::write(
+                dir.join("dist/maps/b.js.map"),
+                r#"{"version":3,"sourceRoot":"../","sources":["../src/b.ts","webpack://lib/c.ts"],"mappings":"AAAA"}"#,
+            )
+            .unwrap();
+
+            let map = SourceFileLoader::new(dir.to_string_lossy())
+                .load_source_map("dist/b.js", "y;\n//# sourceMappingURL=maps/b.js.map")
+                .unwrap();
+            assert_eq!(map.sources, vec!["../src/b.ts", "webpack://lib/c.ts"]);
+            assert_eq!(map.source_root, None);
+        }
+
+        #[test]
+        fn should_strip_trailing_source_mapping_url() {
+            assert_eq!(
+                strip_source_mapping_url("x;\n//# sourceMappingURL=x.js.map\n"),
+                "x;\n"
+            );
+            assert_eq!(strip_source_mapping_url("x;\n"), "x;\n");
+        }
+
+        #[test]
+        fn should_map_content_onto_itself() {
+            let map = SourceMap::identity("a.js", "let a = 1;\n  b();");
+            let lines = map.decode_mappings().unwrap();
+            let columns: Vec<u32> = lines[0].iter().map(|s| s.generated_column).collect();
+            assert_eq!(columns, vec![0, 4, 6, 8]);
+            let b = lines[1][0];
+            assert_eq!(b.generated_column, 2);
+            assert_eq!(
+                (b.original.unwrap().line, b.original.unwrap().column),
+                (1, 2)
+            );
+        }
     }
 
     mod source_map_builder_tests {
@@ -66,5 +319,17 @@ mod tests {
 
             assert_eq!(idx, 0);
         }
+
+        #[test]
+        fn should_encode_mappings() {
+            let mut builder = SourceMapBuilder::new("output.js".to_string());
+            let src = builder.add_source("input.ts", None);
+            builder.add_mapping(0, 4, 0, 4, src, None);
+            builder.add_mapping(0, 0, 0, 0, src, None);
+            builder.add_mapping(2, 2, 1, 0, src, None);
+
+            let map = builder.build();
+            assert_eq!(map.mappings, "AAAA,IAAI;;EACJ");
+        }
     }
 }
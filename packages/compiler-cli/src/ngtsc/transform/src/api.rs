@@ -4,6 +4,8 @@
 // and the Rust-based compilation pipeline.
 
 use crate::ngtsc::reflection::ClassDeclaration;
+use crate::ngtsc::sourcemaps::SourceMap;
+pub use crate::ngtsc::xi18n::Xi18nContext;
 use std::collections::HashSet;
 use ts::Diagnostic;
 
@@ -34,9 +36,6 @@ pub type SemanticSymbol = ();
 /// Placeholder for indexing context
 pub type IndexingContext = ();
 
-/// Placeholder for i18n context
-pub type Xi18nContext = ();
-
 /// Placeholder for type checking context
 pub type TypeCheckContext = ();
 
@@ -67,9 +66,10 @@ pub struct Reexport {
 // ============================================================================
 
 /// Specifies the compilation mode that is used for the compilation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum CompilationMode {
     /// Generates fully AOT compiled code using Ivy instructions.
+    #[default]
     Full,
     /// Generates code using a stable, but intermediate format suitable to be published to NPM.
     Partial,
@@ -182,6 +182,14 @@ pub struct CompileResult {
 
     /// Additional imports to be injected. Vector of (alias, module_path).
     pub additional_imports: Vec<(String, String)>,
+
+    /// Source map of `initializer` back to the component template, if it was compiled
+    /// from an external template file.
+    pub initializer_map: Option<SourceMap>,
+
+    /// Source maps of `statements` back to the component template, parallel to
+    /// `statements`. Empty when no statement maps to a template.
+    pub statement_maps: Vec<Option<SourceMap>>,
 }
 
 impl CompileResult {
@@ -194,6 +202,8 @@ impl CompileResult {
             deferrable_imports: None,
             diagnostics: Vec::new(),
             additional_imports: Vec::new(),
+            initializer_map: None,
+            statement_maps: Vec::new(),
         }
     }
 }
@@ -277,7 +287,7 @@ pub trait DecoratorHandler<D, A, S, R> {
     }
 
     /// Extract i18n messages into the Xi18nContext.
-    fn xi18n(&self, _bundle: &mut Xi18nContext, _node: &ClassDeclaration, _analysis: &A) {
+    fn xi18n(&self, _bundle: &mut dyn Xi18nContext, _node: &ClassDeclaration, _analysis: &A) {
         // Default: no i18n extraction
     }
 
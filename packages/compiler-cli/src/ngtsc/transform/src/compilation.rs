@@ -350,7 +350,7 @@ impl<D: Clone, A: Clone, S: Clone, R: Clone> TraitCompiler<D, A, S, R> {
     }
 
     /// Extract i18n messages from all analyzed classes.
-    pub fn xi18n(&self, _bundle: &mut Xi18nContext) {
+    pub fn xi18n(&self, _bundle: &mut dyn Xi18nContext) {
         // TODO: Implement i18n extraction
     }
 
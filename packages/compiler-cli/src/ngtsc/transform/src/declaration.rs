@@ -6,7 +6,14 @@
 use crate::ngtsc::transform::src::api::{
     DtsTransform, ImportManager, ReferenceEmitter, ReflectionHost,
 };
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Class, Declaration, ExportDefaultDeclarationKind, Program, Statement};
+use oxc_codegen::Codegen;
+use oxc_isolated_declarations::{IsolatedDeclarations, IsolatedDeclarationsOptions};
+use oxc_parser::Parser;
+use oxc_span::SourceType;
 use std::collections::HashMap;
+use std::path::Path;
 
 // ============================================================================
 // DTS Transform Registry
@@ -135,6 +142,110 @@ impl IvyDeclarationDtsTransform {
     pub fn has_fields(&self) -> bool {
         !self.declaration_fields.is_empty()
     }
+
+    /// Declare the registered fields as static members of their classes in `program`,
+    /// importing `@angular/core` as `i0` for the field types.
+    ///
+    /// This runs on the TypeScript source before declarations are generated from it, so
+    /// that the imports the field types refer to are kept in the declaration file.
+    pub fn transform_program<'a>(&self, allocator: &'a Allocator, program: &mut Program<'a>) {
+        let mut transformed = false;
+        for stmt in program.body.iter_mut() {
+            let class = match stmt {
+                Statement::ClassDeclaration(class) => class,
+                Statement::ExportNamedDeclaration(export_decl) => {
+                    match &mut export_decl.declaration {
+                        Some(Declaration::ClassDeclaration(class)) => class,
+                        _ => continue,
+                    }
+                }
+                Statement::ExportDefaultDeclaration(export_decl) => {
+                    match &mut export_decl.declaration {
+                        ExportDefaultDeclarationKind::ClassDeclaration(class) => class,
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            let Some(fields) = class
+                .id
+                .as_ref()
+                .and_then(|id| self.declaration_fields.get(id.name.as_str()))
+            else {
+                continue;
+            };
+            transformed |= add_field_declarations(allocator, class, fields);
+        }
+
+        if transformed {
+            let import = allocator.alloc_str("import * as i0 from \"@angular/core\";");
+            let parsed = Parser::new(allocator, import, SourceType::ts()).parse();
+            if let Some(stmt) = parsed.program.body.into_iter().next() {
+                program.body.insert(0, stmt);
+            }
+        }
+    }
+}
+
+/// Parse `static name: type;` declarations for `fields` and append them to `class`.
+fn add_field_declarations<'a>(
+    allocator: &'a Allocator,
+    class: &mut Class<'a>,
+    fields: &[IvyDeclarationField],
+) -> bool {
+    let members: String = fields
+        .iter()
+        .map(|field| format!("static {}: {};\n", field.name, field.type_str))
+        .collect();
+    let source = allocator.alloc_str(&format!("class __ngDeclarations {{\n{}}}", members));
+    let parsed = Parser::new(allocator, source, SourceType::ts()).parse();
+    if !parsed.errors.is_empty() {
+        return false;
+    }
+
+    let mut added = false;
+    for stmt in parsed.program.body {
+        if let Statement::ClassDeclaration(parsed_class) = stmt {
+            let parsed_class = parsed_class.unbox();
+            for element in parsed_class.body.unbox().body {
+                class.body.body.push(element);
+                added = true;
+            }
+        }
+    }
+    added
+}
+
+/// Generate the contents of the `.d.ts` file for a TypeScript source file, with the
+/// fields registered on `transform` declared on their classes.
+///
+/// Declarations are generated without type inference, so types that TypeScript would
+/// infer (e.g. unannotated return types) may be missing from the output.
+pub fn emit_declaration_file(
+    source_text: &str,
+    file_path: &Path,
+    transform: &IvyDeclarationDtsTransform,
+) -> Result<String, String> {
+    let allocator = Allocator::default();
+    let parse_result = Parser::new(&allocator, source_text, SourceType::ts()).parse();
+    if !parse_result.errors.is_empty() {
+        return Err(format!(
+            "Cannot generate declarations for {}: the file has syntax errors",
+            file_path.display()
+        ));
+    }
+
+    let mut program = parse_result.program;
+    transform.transform_program(&allocator, &mut program);
+
+    let declarations = IsolatedDeclarations::new(
+        &allocator,
+        IsolatedDeclarationsOptions {
+            strip_internal: false,
+        },
+    )
+    .build(&program);
+    Ok(Codegen::new().build(&declarations.program).code)
 }
 
 impl Default for IvyDeclarationDtsTransform {
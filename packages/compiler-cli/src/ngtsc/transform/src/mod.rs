@@ -17,6 +17,8 @@ pub use api::{
     HandlerPrecedence, ResolveResult,
 };
 pub use compilation::{ClassRecord, TraitCompiler};
-pub use declaration::{DtsTransformRegistry, IvyDeclarationDtsTransform, IvyDeclarationField};
+pub use declaration::{
+    emit_declaration_file, DtsTransformRegistry, IvyDeclarationDtsTransform, IvyDeclarationField,
+};
 pub use trait_::{Trait, TraitFactory, TraitState};
 pub use transform::{IvyCompilationVisitor, IvyTransformConfig, IvyTransformationVisitor};
@@ -52,6 +52,7 @@ mod tests {
             flat_module_out_file: None,
             out_dir: Some("/dist".to_string()),
             root_dir: Some("/".to_string()),
+            ..NgCompilerOptions::default()
         };
 
         let ticket = CompilationTicket {
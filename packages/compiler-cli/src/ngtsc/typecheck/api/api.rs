@@ -2,7 +2,11 @@
 //
 // Public API types for template type-checking.
 
-use std::collections::HashMap;
+use angular_compiler::render3::r3_ast::R3Node;
+use angular_compiler::render3::view::t2_api::{
+    DirectiveMeta, InputOutputPropertySet, LegacyAnimationTriggerNames,
+};
+use std::collections::{HashMap, HashSet};
 
 /// Configuration for type-checking.
 #[derive(Debug, Clone)]
@@ -25,6 +29,8 @@ pub struct TypeCheckingConfig {
     pub check_type_of_dom_references: bool,
     /// Whether to check pipe types.
     pub check_type_of_pipes: bool,
+    /// Whether to check bindings to DOM properties against the element's type.
+    pub check_type_of_dom_bindings: bool,
     /// Whether to suggest fixes for template errors.
     pub suggest_fixes_for_template_errors: bool,
     /// Use any type for controls.
@@ -43,12 +49,24 @@ impl Default for TypeCheckingConfig {
             check_type_of_two_way_bindings: true,
             check_type_of_dom_references: true,
             check_type_of_pipes: true,
+            check_type_of_dom_bindings: false,
             suggest_fixes_for_template_errors: false,
             control_flow_preventing_content_projection: ControlFlowPrevention::Warning,
         }
     }
 }
 
+impl TypeCheckingConfig {
+    /// The configuration used when `strictTemplates` is enabled, which checks the bodies
+    /// of all embedded views in addition to the component's own view.
+    pub fn strict() -> Self {
+        Self {
+            apply_full_template_type_check_mode: true,
+            ..Self::default()
+        }
+    }
+}
+
 /// Options for control flow content projection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ControlFlowPrevention {
@@ -60,13 +78,194 @@ pub enum ControlFlowPrevention {
     Error,
 }
 
-/// Type-check block metadata.
+/// Everything needed to generate the type-check block of a single component template.
 #[derive(Debug, Clone)]
 pub struct TypeCheckBlockMetadata {
-    /// Template node ID to TCB location mapping.
-    pub template_mappings: HashMap<String, TcbLocation>,
-    /// Inline TCB bound.
-    pub is_inline: bool,
+    /// Name of the component class, as exported from its source file.
+    pub component_name: String,
+    /// The parsed template.
+    pub nodes: Vec<R3Node>,
+    /// Directives in scope of the template.
+    pub directives: Vec<TypeCheckableDirectiveMeta>,
+    /// Pipes in scope of the template, keyed by pipe name. Values are TypeScript
+    /// expressions referencing the pipe class from the type-check file.
+    pub pipes: HashMap<String, String>,
+    /// File that template diagnostics are reported against.
+    pub template_file: String,
+    /// Offset of the template within `template_file`; non-zero for inline templates.
+    pub template_offset: usize,
+    /// Names of the schemas declared by the component, e.g. `CUSTOM_ELEMENTS_SCHEMA`.
+    pub schemas: Vec<String>,
+    /// Whether some directives or pipes in scope of the template could not be resolved.
+    /// Unknown elements, properties and pipes are not reported for such templates, as
+    /// they may come from the unresolved dependencies.
+    pub is_poisoned: bool,
+    /// File the component class is declared in.
+    pub component_file: String,
+    /// The directives and pipes listed in the `imports` of a standalone component.
+    pub imports: Vec<TypeCheckImport>,
+}
+
+/// A directive or pipe listed in the `imports` of a standalone component.
+#[derive(Debug, Clone)]
+pub struct TypeCheckImport {
+    /// Name of the imported class.
+    pub name: String,
+    /// Name of the pipe, when the import is a pipe rather than a directive.
+    pub pipe_name: Option<String>,
+    /// Offset of the start of the import within `component_file`.
+    pub start: usize,
+    /// Offset of the end of the import within `component_file`.
+    pub end: usize,
+}
+
+/// An input of a directive, as seen by the type-checker.
+#[derive(Debug, Clone)]
+pub struct TypeCheckInput {
+    /// Name used to bind to the input in templates.
+    pub binding_property_name: String,
+    /// Name of the class member backing the input, when known. Inputs without one are
+    /// evaluated but not checked against the directive's type.
+    pub class_property_name: Option<String>,
+    /// Whether the input is declared with `input()`.
+    pub is_signal: bool,
+    /// Whether the input is required.
+    pub required: bool,
+}
+
+/// An output of a directive, as seen by the type-checker.
+#[derive(Debug, Clone)]
+pub struct TypeCheckOutput {
+    /// Name used to listen to the output in templates.
+    pub binding_property_name: String,
+    /// Name of the class member backing the output, when known.
+    pub class_property_name: Option<String>,
+}
+
+/// The inputs of a [`TypeCheckableDirectiveMeta`].
+#[derive(Debug, Clone, Default)]
+pub struct TypeCheckInputs(pub Vec<TypeCheckInput>);
+
+impl TypeCheckInputs {
+    /// Inputs claiming the given binding name.
+    pub fn get_by_binding_property_name(&self, name: &str) -> Option<&TypeCheckInput> {
+        self.0
+            .iter()
+            .find(|input| input.binding_property_name == name)
+    }
+}
+
+impl InputOutputPropertySet for TypeCheckInputs {
+    fn has_binding_property_name(&self, property_name: &str) -> bool {
+        self.get_by_binding_property_name(property_name).is_some()
+    }
+}
+
+/// The outputs of a [`TypeCheckableDirectiveMeta`].
+#[derive(Debug, Clone, Default)]
+pub struct TypeCheckOutputs(pub Vec<TypeCheckOutput>);
+
+impl TypeCheckOutputs {
+    /// Outputs claiming the given binding name.
+    pub fn get_by_binding_property_name(&self, name: &str) -> Option<&TypeCheckOutput> {
+        self.0
+            .iter()
+            .find(|output| output.binding_property_name == name)
+    }
+}
+
+impl InputOutputPropertySet for TypeCheckOutputs {
+    fn has_binding_property_name(&self, property_name: &str) -> bool {
+        self.get_by_binding_property_name(property_name).is_some()
+    }
+}
+
+/// How a static `ngTemplateGuard_*` member narrows the context of a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateGuardKind {
+    /// `ngTemplateGuard_input: 'binding'`: the bound expression itself is the guard.
+    Binding,
+    /// A guard function invoked with the directive and the bound expression.
+    Invocation,
+}
+
+/// A template guard declared by a structural directive.
+#[derive(Debug, Clone)]
+pub struct TemplateGuard {
+    /// The input whose bound expression is guarded.
+    pub input_name: String,
+    /// Kind of the guard.
+    pub kind: TemplateGuardKind,
+}
+
+/// Metadata about a directive which is needed to type-check its usages in templates.
+#[derive(Debug, Clone, Default)]
+pub struct TypeCheckableDirectiveMeta {
+    /// Name of the directive class.
+    pub name: String,
+    /// TypeScript expression referencing the directive class from the type-check file.
+    pub reference: String,
+    /// The directive's selector.
+    pub selector: Option<String>,
+    /// Whether the directive is a component.
+    pub is_component: bool,
+    /// Whether the directive is a structural directive.
+    pub is_structural: bool,
+    /// Inputs of the directive.
+    pub inputs: TypeCheckInputs,
+    /// Outputs of the directive.
+    pub outputs: TypeCheckOutputs,
+    /// Names under which the directive is exported.
+    pub export_as: Option<Vec<String>>,
+    /// Static `ngTemplateGuard_*` members of the directive.
+    pub ng_template_guards: Vec<TemplateGuard>,
+    /// Whether the directive has a static `ngTemplateContextGuard`.
+    pub has_ng_template_context_guard: bool,
+    /// Class properties of inputs whose accepted type differs from the property type,
+    /// e.g. because of an input transform. They are evaluated but not assigned.
+    pub coerced_input_fields: HashSet<String>,
+}
+
+impl DirectiveMeta for TypeCheckableDirectiveMeta {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn selector(&self) -> Option<&str> {
+        self.selector.as_deref()
+    }
+
+    fn is_component(&self) -> bool {
+        self.is_component
+    }
+
+    fn inputs(&self) -> &dyn InputOutputPropertySet {
+        &self.inputs
+    }
+
+    fn outputs(&self) -> &dyn InputOutputPropertySet {
+        &self.outputs
+    }
+
+    fn export_as(&self) -> Option<&[String]> {
+        self.export_as.as_deref()
+    }
+
+    fn is_structural(&self) -> bool {
+        self.is_structural
+    }
+
+    fn ng_content_selectors(&self) -> Option<&[String]> {
+        None
+    }
+
+    fn preserve_whitespaces(&self) -> bool {
+        false
+    }
+
+    fn animation_trigger_names(&self) -> Option<&LegacyAnimationTriggerNames> {
+        None
+    }
 }
 
 /// Location in a type-check block.
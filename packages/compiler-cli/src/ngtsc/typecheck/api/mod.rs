@@ -6,8 +6,10 @@ pub mod symbols;
 
 // Re-exports
 pub use api::{
-    ControlFlowPrevention, PendingTypeCheckBlock, TcbLocation, TypeCheckBlockMetadata,
-    TypeCheckContext, TypeCheckError, TypeCheckOp, TypeCheckingConfig,
+    ControlFlowPrevention, PendingTypeCheckBlock, TcbLocation, TemplateGuard, TemplateGuardKind,
+    TypeCheckBlockMetadata, TypeCheckContext, TypeCheckError, TypeCheckImport, TypeCheckInput,
+    TypeCheckInputs, TypeCheckOp, TypeCheckOutput, TypeCheckOutputs, TypeCheckableDirectiveMeta,
+    TypeCheckingConfig,
 };
 pub use checker::{TemplateTypeChecker, TypeCheckResult};
 pub use symbols::{
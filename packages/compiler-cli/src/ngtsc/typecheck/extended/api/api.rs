@@ -0,0 +1,422 @@
+// Extended Template Diagnostics API
+//
+// An extended template check inspects the R3 AST of a component template for patterns
+// which compile but are most likely mistakes. Checks do not have TypeScript type
+// information: they rely on the component's scope, the template bound against the
+// directives in that scope, and the members declared by the component class.
+
+use super::super::super::api::{
+    TypeCheckBlockMetadata, TypeCheckError, TypeCheckableDirectiveMeta,
+};
+use crate::ngtsc::diagnostics::{DiagnosticCategory, ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::expression_parser::ast::{AbsoluteSourceSpan, AST};
+use angular_compiler::parse_util::ParseSourceSpan;
+use angular_compiler::render3::r3_ast::{
+    BoundAttribute, BoundEvent, BoundText, Component, Content, DeferredBlock, Element,
+    ForLoopBlock, IcuPlaceholder, IfBlock, LetDeclaration, R3Node, Reference, SwitchBlock,
+    Template, TemplateAttr, TextAttribute, Variable,
+};
+use angular_compiler::render3::view::t2_api::BoundTarget;
+use std::collections::{HashMap, HashSet};
+
+/// How the diagnostics of an extended check are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCategoryLabel {
+    /// Report the diagnostics as warnings.
+    Warning,
+    /// Report the diagnostics as errors.
+    Error,
+    /// Do not run the check.
+    Suppress,
+}
+
+impl DiagnosticCategoryLabel {
+    /// Parse a label as written in `extendedDiagnostics`.
+    pub fn parse(label: &str) -> Option<Self> {
+        match label {
+            "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            "suppress" => Some(Self::Suppress),
+            _ => None,
+        }
+    }
+
+    /// The category of the diagnostics, or `None` when they are suppressed.
+    pub fn category(self) -> Option<DiagnosticCategory> {
+        match self {
+            Self::Warning => Some(DiagnosticCategory::Warning),
+            Self::Error => Some(DiagnosticCategory::Error),
+            Self::Suppress => None,
+        }
+    }
+}
+
+/// The `angularCompilerOptions.extendedDiagnostics` configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtendedDiagnosticsOptions {
+    /// Label of the checks not listed in `checks`. Defaults to `warning`.
+    pub default_category: Option<DiagnosticCategoryLabel>,
+    /// Label of individual checks, keyed by check name.
+    pub checks: HashMap<String, DiagnosticCategoryLabel>,
+}
+
+impl ExtendedDiagnosticsOptions {
+    /// The label the check `name` is configured with.
+    pub fn label_of(&self, name: ExtendedTemplateDiagnosticName) -> DiagnosticCategoryLabel {
+        self.checks
+            .get(&name.to_string())
+            .copied()
+            .or(self.default_category)
+            .unwrap_or(DiagnosticCategoryLabel::Warning)
+    }
+}
+
+/// Members of a component class, as far as they can be told apart without types.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentMembers {
+    /// Methods, and properties initialized with a function.
+    pub methods: HashSet<String>,
+    /// Properties initialized with a signal, e.g. `signal()`, `computed()` or `input()`.
+    pub signals: HashSet<String>,
+}
+
+/// A diagnostic reported by an extended check, with its configured category.
+#[derive(Debug, Clone)]
+pub struct ExtendedTemplateDiagnostic {
+    /// Category the check is configured with.
+    pub category: DiagnosticCategory,
+    /// The diagnostic.
+    pub diagnostic: TypeCheckError,
+}
+
+/// The template being checked, and what is known about its component.
+pub struct TemplateContext<'a> {
+    /// The component's template and scope.
+    pub meta: &'a TypeCheckBlockMetadata,
+    /// Members of the component class.
+    pub members: &'a ComponentMembers,
+    /// The template bound against the directives in scope.
+    pub bound: &'a dyn BoundTarget<TypeCheckableDirectiveMeta>,
+}
+
+impl TemplateContext<'_> {
+    /// A diagnostic at a span of the template.
+    pub fn make_template_diagnostic(
+        &self,
+        code: ErrorCode,
+        span: &ParseSourceSpan,
+        message: impl Into<String>,
+    ) -> TypeCheckError {
+        self.diagnostic_at(code, span.start.offset, span.end.offset, message.into())
+    }
+
+    /// A diagnostic at the span of a template expression.
+    pub fn make_expression_diagnostic(
+        &self,
+        code: ErrorCode,
+        span: AbsoluteSourceSpan,
+        message: impl Into<String>,
+    ) -> TypeCheckError {
+        self.diagnostic_at(code, span.start, span.end, message.into())
+    }
+
+    fn diagnostic_at(
+        &self,
+        code: ErrorCode,
+        start: usize,
+        end: usize,
+        message: String,
+    ) -> TypeCheckError {
+        TypeCheckError {
+            message,
+            code: format!("NG{}", code as i32),
+            file: Some(self.meta.template_file.clone()),
+            start: Some(self.meta.template_offset + start),
+            length: Some(end.saturating_sub(start)),
+        }
+    }
+
+    /// The name of the component member an expression reads, unless it reads a template
+    /// variable, reference or `@let` declaration, or a property of another object.
+    pub fn component_member<'e>(&self, expr: &'e AST) -> Option<&'e str> {
+        let (receiver, name) = match expr {
+            AST::PropertyRead(read) => (&read.receiver, &read.name),
+            AST::SafePropertyRead(read) => (&read.receiver, &read.name),
+            _ => return None,
+        };
+        match **receiver {
+            AST::ThisReceiver(_) => Some(name),
+            AST::ImplicitReceiver(_) if self.bound.get_expression_target(expr).is_none() => {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A node of a template, as visited by `visit_template`.
+#[derive(Debug, Clone, Copy)]
+pub enum TemplateNode<'a> {
+    Element(&'a Element),
+    Component(&'a Component),
+    Template(&'a Template),
+    Content(&'a Content),
+    TextAttribute(&'a TextAttribute),
+    BoundAttribute(&'a BoundAttribute),
+    BoundEvent(&'a BoundEvent),
+    BoundText(&'a BoundText),
+    Reference(&'a Reference),
+    Variable(&'a Variable),
+    IfBlock(&'a IfBlock),
+    SwitchBlock(&'a SwitchBlock),
+    ForLoopBlock(&'a ForLoopBlock),
+    DeferredBlock(&'a DeferredBlock),
+    LetDeclaration(&'a LetDeclaration),
+    Expression(&'a AST),
+}
+
+/// A check for a template pattern which is likely a mistake.
+pub trait TemplateCheck {
+    /// The code of the diagnostics the check reports.
+    fn code(&self) -> ErrorCode;
+
+    /// The name the check is configured with.
+    fn name(&self) -> ExtendedTemplateDiagnosticName;
+
+    /// Run the check over a template. By default `visit_node` is called for every node
+    /// and expression of the template.
+    fn run(&self, ctx: &TemplateContext<'_>, template: &[R3Node]) -> Vec<TypeCheckError> {
+        let mut diagnostics = Vec::new();
+        visit_template(template, &mut |node| {
+            diagnostics.extend(self.visit_node(ctx, node))
+        });
+        diagnostics
+    }
+
+    /// Check a single node or expression of the template.
+    fn visit_node(
+        &self,
+        _ctx: &TemplateContext<'_>,
+        _node: TemplateNode<'_>,
+    ) -> Vec<TypeCheckError> {
+        Vec::new()
+    }
+}
+
+/// Creates an extended check.
+pub type TemplateCheckFactory = fn() -> Box<dyn TemplateCheck>;
+
+/// Call `visit` for every node of a template and every expression within it, parents
+/// before their children.
+pub fn visit_template<'a>(nodes: &'a [R3Node], visit: &mut dyn FnMut(TemplateNode<'a>)) {
+    for node in nodes {
+        match node {
+            R3Node::Element(element) => {
+                visit(TemplateNode::Element(element));
+                visit_bindings(
+                    &element.attributes,
+                    &element.inputs,
+                    &element.outputs,
+                    visit,
+                );
+                for directive in &element.directives {
+                    visit_bindings(
+                        &directive.attributes,
+                        &directive.inputs,
+                        &directive.outputs,
+                        visit,
+                    );
+                }
+                element
+                    .references
+                    .iter()
+                    .for_each(|r| visit(TemplateNode::Reference(r)));
+                visit_template(&element.children, visit);
+            }
+            R3Node::Component(component) => {
+                visit(TemplateNode::Component(component));
+                visit_bindings(
+                    &component.attributes,
+                    &component.inputs,
+                    &component.outputs,
+                    visit,
+                );
+                component
+                    .references
+                    .iter()
+                    .for_each(|r| visit(TemplateNode::Reference(r)));
+                visit_template(&component.children, visit);
+            }
+            R3Node::Template(template) => {
+                visit(TemplateNode::Template(template));
+                visit_bindings(
+                    &template.attributes,
+                    &template.inputs,
+                    &template.outputs,
+                    visit,
+                );
+                for attr in &template.template_attrs {
+                    match attr {
+                        TemplateAttr::Bound(input) => {
+                            visit_bindings(&[], std::slice::from_ref(input), &[], visit)
+                        }
+                        TemplateAttr::Text(attribute) => {
+                            visit(TemplateNode::TextAttribute(attribute))
+                        }
+                    }
+                }
+                template
+                    .variables
+                    .iter()
+                    .for_each(|v| visit(TemplateNode::Variable(v)));
+                template
+                    .references
+                    .iter()
+                    .for_each(|r| visit(TemplateNode::Reference(r)));
+                visit_template(&template.children, visit);
+            }
+            R3Node::Content(content) => {
+                visit(TemplateNode::Content(content));
+                visit_bindings(&content.attributes, &[], &[], visit);
+                visit_template(&content.children, visit);
+            }
+            R3Node::BoundText(text) => visit_bound_text(text, visit),
+            R3Node::Icu(icu) => {
+                for text in icu.vars.values() {
+                    visit_bound_text(text, visit);
+                }
+                for placeholder in icu.placeholders.values() {
+                    if let IcuPlaceholder::BoundText(text) = placeholder {
+                        visit_bound_text(text, visit);
+                    }
+                }
+            }
+            R3Node::IfBlock(block) => {
+                visit(TemplateNode::IfBlock(block));
+                for branch in &block.branches {
+                    if let Some(expression) = &branch.expression {
+                        visit_expression(expression, visit);
+                    }
+                    if let Some(alias) = &branch.expression_alias {
+                        visit(TemplateNode::Variable(alias));
+                    }
+                    visit_template(&branch.children, visit);
+                }
+            }
+            R3Node::SwitchBlock(block) => {
+                visit(TemplateNode::SwitchBlock(block));
+                visit_expression(&block.expression, visit);
+                for case in &block.cases {
+                    if let Some(expression) = &case.expression {
+                        visit_expression(expression, visit);
+                    }
+                    visit_template(&case.children, visit);
+                }
+            }
+            R3Node::ForLoopBlock(block) => {
+                visit(TemplateNode::ForLoopBlock(block));
+                visit(TemplateNode::Variable(&block.item));
+                visit_expression(&block.expression.ast, visit);
+                visit_expression(&block.track_by.ast, visit);
+                block
+                    .context_variables
+                    .iter()
+                    .for_each(|v| visit(TemplateNode::Variable(v)));
+                visit_template(&block.children, visit);
+                if let Some(empty) = &block.empty {
+                    visit_template(&empty.children, visit);
+                }
+            }
+            R3Node::DeferredBlock(block) => {
+                visit(TemplateNode::DeferredBlock(block));
+                visit_template(&block.children, visit);
+                if let Some(placeholder) = &block.placeholder {
+                    visit_template(&placeholder.children, visit);
+                }
+                if let Some(loading) = &block.loading {
+                    visit_template(&loading.children, visit);
+                }
+                if let Some(error) = &block.error {
+                    visit_template(&error.children, visit);
+                }
+            }
+            R3Node::LetDeclaration(decl) => {
+                visit(TemplateNode::LetDeclaration(decl));
+                visit_expression(&decl.value, visit);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn visit_bindings<'a>(
+    attributes: &'a [TextAttribute],
+    inputs: &'a [BoundAttribute],
+    outputs: &'a [BoundEvent],
+    visit: &mut dyn FnMut(TemplateNode<'a>),
+) {
+    for attribute in attributes {
+        visit(TemplateNode::TextAttribute(attribute));
+    }
+    for input in inputs {
+        visit(TemplateNode::BoundAttribute(input));
+        visit_expression(&input.value, visit);
+    }
+    for output in outputs {
+        visit(TemplateNode::BoundEvent(output));
+        visit_expression(&output.handler, visit);
+    }
+}
+
+fn visit_bound_text<'a>(text: &'a BoundText, visit: &mut dyn FnMut(TemplateNode<'a>)) {
+    visit(TemplateNode::BoundText(text));
+    visit_expression(&text.value, visit);
+}
+
+/// Call `visit` for an expression and each of its sub-expressions, parents first.
+pub fn visit_expression<'a>(ast: &'a AST, visit: &mut dyn FnMut(TemplateNode<'a>)) {
+    visit(TemplateNode::Expression(ast));
+    let children: Vec<&'a AST> = match ast {
+        AST::Chain(chain) => chain.expressions.iter().map(|e| &**e).collect(),
+        AST::Conditional(c) => vec![&c.condition, &c.true_exp, &c.false_exp],
+        AST::PropertyRead(read) => vec![&read.receiver],
+        AST::SafePropertyRead(read) => vec![&read.receiver],
+        AST::KeyedRead(read) => vec![&read.receiver, &read.key],
+        AST::SafeKeyedRead(read) => vec![&read.receiver, &read.key],
+        AST::BindingPipe(pipe) => std::iter::once(&*pipe.exp)
+            .chain(pipe.args.iter().map(|e| &**e))
+            .collect(),
+        AST::LiteralArray(array) => array.expressions.iter().map(|e| &**e).collect(),
+        AST::LiteralMap(map) => map.values.iter().map(|e| &**e).collect(),
+        AST::Interpolation(interpolation) => {
+            interpolation.expressions.iter().map(|e| &**e).collect()
+        }
+        AST::Binary(binary) => vec![&binary.left, &binary.right],
+        AST::PrefixNot(not) => vec![&not.expression],
+        AST::Unary(unary) => vec![&unary.expr],
+        AST::TypeofExpression(typeof_) => vec![&typeof_.expression],
+        AST::VoidExpression(void) => vec![&void.expression],
+        AST::NonNullAssert(assert) => vec![&assert.expression],
+        AST::Call(call) => std::iter::once(&*call.receiver)
+            .chain(call.args.iter().map(|e| &**e))
+            .collect(),
+        AST::SafeCall(call) => std::iter::once(&*call.receiver)
+            .chain(call.args.iter().map(|e| &**e))
+            .collect(),
+        AST::PropertyWrite(write) => vec![&write.receiver, &write.value],
+        AST::KeyedWrite(write) => vec![&write.receiver, &write.key, &write.value],
+        AST::TemplateLiteral(literal) => literal.expressions.iter().map(|e| &**e).collect(),
+        AST::TaggedTemplateLiteral(tagged) => std::iter::once(&*tagged.tag)
+            .chain(tagged.template.expressions.iter().map(|e| &**e))
+            .collect(),
+        AST::ParenthesizedExpression(parens) => vec![&parens.expression],
+        AST::EmptyExpr(_)
+        | AST::ImplicitReceiver(_)
+        | AST::ThisReceiver(_)
+        | AST::LiteralPrimitive(_)
+        | AST::RegularExpressionLiteral(_) => Vec::new(),
+    };
+    for child in children {
+        visit_expression(child, visit);
+    }
+}
@@ -0,0 +1,10 @@
+// Extended Template Diagnostics API Module
+
+pub mod api;
+
+// Re-exports
+pub use api::{
+    visit_expression, visit_template, ComponentMembers, DiagnosticCategoryLabel,
+    ExtendedDiagnosticsOptions, ExtendedTemplateDiagnostic, TemplateCheck, TemplateCheckFactory,
+    TemplateContext, TemplateNode,
+};
@@ -0,0 +1,60 @@
+// Interpolated Signal Not Invoked Check
+//
+// Reports signals of the component which are interpolated, or bound to a class, style
+// or attribute, without being called, e.g. `{{ count }}` instead of `{{ count() }}`.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::expression_parser::ast::{BindingType, AST};
+
+pub struct InterpolatedSignalNotInvokedCheck;
+
+impl InterpolatedSignalNotInvokedCheck {
+    fn check_expression(&self, ctx: &TemplateContext<'_>, expr: &AST) -> Option<TypeCheckError> {
+        let name = ctx.component_member(expr)?;
+        if !ctx.members.signals.contains(name) {
+            return None;
+        }
+        Some(ctx.make_expression_diagnostic(
+            self.code(),
+            expr.source_span(),
+            format!("{} is a function and should be invoked: {}()", name, name),
+        ))
+    }
+}
+
+impl TemplateCheck for InterpolatedSignalNotInvokedCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::InterpolatedSignalNotInvoked
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::InterpolatedSignalNotInvoked
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        match node {
+            TemplateNode::Expression(AST::Interpolation(interpolation)) => interpolation
+                .expressions
+                .iter()
+                .filter_map(|expr| self.check_expression(ctx, expr))
+                .collect(),
+            TemplateNode::BoundAttribute(input)
+                if matches!(
+                    input.type_,
+                    BindingType::Class | BindingType::Style | BindingType::Attribute
+                ) =>
+            {
+                self.check_expression(ctx, &input.value)
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(InterpolatedSignalNotInvokedCheck)
+}
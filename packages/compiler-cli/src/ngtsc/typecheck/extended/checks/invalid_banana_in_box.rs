@@ -0,0 +1,48 @@
+// Invalid Banana-in-Box Check
+//
+// Reports two-way bindings written with the parentheses outside the brackets. The
+// binding `([foo])="bar"` listens to an event named `[foo]` instead of binding `foo`.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+
+pub struct InvalidBananaInBoxCheck;
+
+impl TemplateCheck for InvalidBananaInBoxCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::InvalidBananaInBox
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::InvalidBananaInBox
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::BoundEvent(event) = node else {
+            return Vec::new();
+        };
+        let name = &*event.name;
+        if name.len() < 2 || !name.starts_with('[') || !name.ends_with(']') {
+            return Vec::new();
+        }
+        let bound_syntax = event.source_span.to_string();
+        let expected_bound_syntax = bound_syntax.replacen(
+            &format!("({})", name),
+            &format!("[({})]", &name[1..name.len() - 1]),
+            1,
+        );
+        vec![ctx.make_template_diagnostic(
+            self.code(),
+            &event.source_span,
+            format!(
+                "In the two-way binding syntax the parentheses should be inside the brackets, ex. '{}'.\n        Find more at https://angular.dev/guide/templates/two-way-binding",
+                expected_bound_syntax
+            ),
+        )]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(InvalidBananaInBoxCheck)
+}
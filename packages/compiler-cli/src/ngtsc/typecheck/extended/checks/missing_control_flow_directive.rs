@@ -0,0 +1,100 @@
+// Missing Control Flow Directive Check
+//
+// Reports structural control flow directives, e.g. `*ngIf`, used in the template of a
+// standalone component which imports neither the directive nor the `CommonModule`.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{visit_template, TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::parse_util::ParseSourceSpan;
+use angular_compiler::render3::r3_ast::{R3Node, Template, TemplateAttr};
+use angular_compiler::render3::view::t2_api::DirectiveOwner;
+
+/// The control flow directives of `@angular/common`, by the name of their structural
+/// attribute, with the directive and the built-in block replacing them.
+pub const KNOWN_CONTROL_FLOW_DIRECTIVES: &[(&str, &str, &str)] = &[
+    ("ngIf", "NgIf", "@if"),
+    ("ngFor", "NgFor", "@for"),
+    ("ngSwitchCase", "NgSwitchCase", "@switch with @case"),
+    (
+        "ngSwitchDefault",
+        "NgSwitchDefault",
+        "@switch with @default",
+    ),
+];
+
+pub struct MissingControlFlowDirectiveCheck;
+
+impl TemplateCheck for MissingControlFlowDirectiveCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::MissingControlFlowDirective
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::MissingControlFlowDirective
+    }
+
+    fn run(&self, ctx: &TemplateContext<'_>, template: &[R3Node]) -> Vec<TypeCheckError> {
+        // The directives in scope of the template are only known for standalone components.
+        if ctx.meta.is_poisoned {
+            return Vec::new();
+        }
+        let mut diagnostics = Vec::new();
+        visit_template(template, &mut |node| {
+            diagnostics.extend(self.visit_node(ctx, node))
+        });
+        diagnostics
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::Template(template) = node else {
+            return Vec::new();
+        };
+        let Some((name, span)) = structural_attribute(template) else {
+            return Vec::new();
+        };
+        let Some((_, directive, built_in)) = KNOWN_CONTROL_FLOW_DIRECTIVES
+            .iter()
+            .find(|(attribute, _, _)| *attribute == name)
+        else {
+            return Vec::new();
+        };
+        if has_directives(ctx, template) {
+            return Vec::new();
+        }
+        vec![ctx.make_template_diagnostic(
+            self.code(),
+            span,
+            format!(
+                "The `*{}` directive was used in the template, but neither the `{}` directive nor the `CommonModule` was imported. Use Angular's built-in control flow {} or make sure that either the `{}` directive or the `CommonModule` is included in the `@Component.imports` array of this component.",
+                name, directive, built_in, directive
+            ),
+        )]
+    }
+}
+
+/// The name and key span of the attribute a template was created from with the `*`
+/// syntax, e.g. `ngIf` for `<div *ngIf="shown">`.
+pub fn structural_attribute(template: &Template) -> Option<(&str, &ParseSourceSpan)> {
+    match template.template_attrs.first()? {
+        TemplateAttr::Bound(input) => Some((&input.name, &input.key_span)),
+        TemplateAttr::Text(attribute) => Some((
+            &attribute.name,
+            attribute
+                .key_span
+                .as_ref()
+                .unwrap_or(&attribute.source_span),
+        )),
+    }
+}
+
+/// Whether any directive in scope matches a template.
+pub fn has_directives(ctx: &TemplateContext<'_>, template: &Template) -> bool {
+    ctx.bound
+        .get_directives_of_node(&DirectiveOwner::Template(template.clone()))
+        .is_some_and(|directives| !directives.is_empty())
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(MissingControlFlowDirectiveCheck)
+}
@@ -0,0 +1,49 @@
+// Missing ngForOf Let Check
+//
+// Reports `*ngFor` directives written without a `let` declaration, e.g.
+// `*ngFor="items"`, which iterate nothing.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::render3::r3_ast::TemplateAttr;
+
+pub struct MissingNgForOfLetCheck;
+
+impl TemplateCheck for MissingNgForOfLetCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::MissingNgforofLet
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::MissingNgForOfLet
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::Template(template) = node else {
+            return Vec::new();
+        };
+        if !template.variables.is_empty() {
+            return Vec::new();
+        }
+        let ng_for = template.template_attrs.iter().find_map(|attr| match attr {
+            TemplateAttr::Bound(input) if &*input.name == "ngFor" => Some(&input.source_span),
+            TemplateAttr::Text(attribute) if &*attribute.name == "ngFor" => {
+                Some(&attribute.source_span)
+            }
+            _ => None,
+        });
+        let Some(span) = ng_for else {
+            return Vec::new();
+        };
+        vec![ctx.make_template_diagnostic(
+            self.code(),
+            span,
+            "Your ngFor is missing a value. Did you forget to add the `let` keyword?",
+        )]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(MissingNgForOfLetCheck)
+}
@@ -0,0 +1,65 @@
+// Missing Structural Directive Check
+//
+// Reports custom structural directives, e.g. `*appHighlight`, used in the template of a
+// standalone component which does not import a directive matching them. Control flow
+// directives are reported by the missing control flow directive check instead.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{visit_template, TemplateCheck, TemplateContext, TemplateNode};
+use super::missing_control_flow_directive::{
+    has_directives, structural_attribute, KNOWN_CONTROL_FLOW_DIRECTIVES,
+};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::render3::r3_ast::R3Node;
+
+pub struct MissingStructuralDirectiveCheck;
+
+impl TemplateCheck for MissingStructuralDirectiveCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::MissingStructuralDirective
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::MissingStructuralDirective
+    }
+
+    fn run(&self, ctx: &TemplateContext<'_>, template: &[R3Node]) -> Vec<TypeCheckError> {
+        // The directives in scope of the template are only known for standalone components.
+        if ctx.meta.is_poisoned {
+            return Vec::new();
+        }
+        let mut diagnostics = Vec::new();
+        visit_template(template, &mut |node| {
+            diagnostics.extend(self.visit_node(ctx, node))
+        });
+        diagnostics
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::Template(template) = node else {
+            return Vec::new();
+        };
+        let Some((name, span)) = structural_attribute(template) else {
+            return Vec::new();
+        };
+        if KNOWN_CONTROL_FLOW_DIRECTIVES
+            .iter()
+            .any(|(attribute, _, _)| *attribute == name)
+            || has_directives(ctx, template)
+        {
+            return Vec::new();
+        }
+        vec![ctx.make_template_diagnostic(
+            self.code(),
+            span,
+            format!(
+                "A structural directive `{}` was used in the template without a corresponding import in the component. Make sure that the directive is included in the `@Component.imports` array of this component.",
+                name
+            ),
+        )]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(MissingStructuralDirectiveCheck)
+}
@@ -0,0 +1,16 @@
+// Extended Template Checks Module
+
+pub mod interpolated_signal_not_invoked;
+pub mod invalid_banana_in_box;
+pub mod missing_control_flow_directive;
+pub mod missing_ngforof_let;
+pub mod missing_structural_directive;
+pub mod skip_hydration_not_static;
+pub mod suffix_not_supported;
+pub mod text_attribute_not_binding;
+pub mod uninvoked_function_in_event_binding;
+pub mod uninvoked_function_in_text_interpolation;
+pub mod uninvoked_track_function;
+pub mod unparenthesized_nullish_coalescing;
+pub mod unused_let_declaration;
+pub mod unused_standalone_imports;
@@ -0,0 +1,49 @@
+// Skip Hydration Not Static Check
+//
+// Reports `ngSkipHydration` used as a binding or with a value other than `"true"`.
+// Hydration is skipped based on the static attribute only.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+
+const NG_SKIP_HYDRATION_ATTR_NAME: &str = "ngSkipHydration";
+
+pub struct SkipHydrationNotStaticCheck;
+
+impl TemplateCheck for SkipHydrationNotStaticCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::SkipHydrationNotStatic
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::SkipHydrationNotStatic
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        match node {
+            TemplateNode::BoundAttribute(input) if &*input.name == NG_SKIP_HYDRATION_ATTR_NAME => {
+                vec![ctx.make_template_diagnostic(
+                    self.code(),
+                    &input.source_span,
+                    "ngSkipHydration should not be used as a binding.",
+                )]
+            }
+            TemplateNode::TextAttribute(attribute)
+                if &*attribute.name == NG_SKIP_HYDRATION_ATTR_NAME
+                    && !matches!(&*attribute.value, "" | "true") =>
+            {
+                vec![ctx.make_template_diagnostic(
+                    self.code(),
+                    &attribute.source_span,
+                    "ngSkipHydration only accepts \"true\" or \"\" as value or no value at all. For example 'ngSkipHydration=\"true\"' or 'ngSkipHydration'",
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(SkipHydrationNotStaticCheck)
+}
@@ -0,0 +1,44 @@
+// Suffix Not Supported Check
+//
+// Reports unit suffixes on attribute bindings, e.g. `[attr.width.px]`. Units are only
+// applied to style bindings; on attribute bindings they become part of the name.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+
+const STYLE_SUFFIXES: &[&str] = &[".px", ".%", ".em"];
+
+pub struct SuffixNotSupportedCheck;
+
+impl TemplateCheck for SuffixNotSupportedCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::SuffixNotSupported
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::SuffixNotSupported
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::BoundAttribute(input) = node else {
+            return Vec::new();
+        };
+        if !input.key_span.to_string().starts_with("attr.")
+            || !STYLE_SUFFIXES
+                .iter()
+                .any(|suffix| input.name.ends_with(suffix))
+        {
+            return Vec::new();
+        }
+        vec![ctx.make_template_diagnostic(
+            self.code(),
+            &input.key_span,
+            "The '.px', '.%', '.em' suffixes are only supported on style bindings.",
+        )]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(SuffixNotSupportedCheck)
+}
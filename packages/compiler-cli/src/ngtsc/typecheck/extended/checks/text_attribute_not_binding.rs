@@ -0,0 +1,59 @@
+// Text Attribute Not Binding Check
+//
+// Reports static attributes which look like attribute, class or style bindings but are
+// missing the square brackets, e.g. `attr.id="foo"` or `class.active="true"`.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+
+pub struct TextAttributeNotBindingCheck;
+
+impl TemplateCheck for TextAttributeNotBindingCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::TextAttributeNotBinding
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::TextAttributeNotBinding
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::TextAttribute(attribute) = node else {
+            return Vec::new();
+        };
+        let name = &*attribute.name;
+        let value = &*attribute.value;
+        let message = if let Some(static_name) = name.strip_prefix("attr.") {
+            let mut message =
+                "Static attributes should be written without the 'attr.' prefix.".to_string();
+            if !value.is_empty() {
+                message.push_str(&format!(" For example, {}=\"{}\".", static_name, value));
+            }
+            message
+        } else if name.starts_with("style.") || name.starts_with("class.") {
+            let mut message =
+                "Attribute, style, and class bindings should be enclosed with square braces."
+                    .to_string();
+            if !value.is_empty() {
+                let expected_value = if value == "true" || value == "false" {
+                    value
+                } else {
+                    "'value'"
+                };
+                message.push_str(&format!(
+                    " For example, '[{}]=\"{}\"'.",
+                    name, expected_value
+                ));
+            }
+            message
+        } else {
+            return Vec::new();
+        };
+        vec![ctx.make_template_diagnostic(self.code(), &attribute.source_span, message)]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(TextAttributeNotBindingCheck)
+}
@@ -0,0 +1,73 @@
+// Uninvoked Function In Event Binding Check
+//
+// Reports event bindings whose handler reads a method of the component without calling
+// it, e.g. `(click)="save"` instead of `(click)="save()"`.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::expression_parser::ast::{ParsedEventType, AST};
+
+pub struct UninvokedFunctionInEventBindingCheck;
+
+impl TemplateCheck for UninvokedFunctionInEventBindingCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::UninvokedFunctionInEventBinding
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::UninvokedFunctionInEventBinding
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::BoundEvent(event) = node else {
+            return Vec::new();
+        };
+        if !matches!(
+            event.type_,
+            ParsedEventType::Regular | ParsedEventType::Animation
+        ) {
+            return Vec::new();
+        }
+        let mut handlers = Vec::new();
+        collect_handlers(&event.handler, &mut handlers);
+        let content = &event.source_span.start.file.content;
+        handlers
+            .into_iter()
+            .filter(|expr| {
+                ctx.component_member(expr).is_some_and(|name| {
+                    ctx.members.methods.contains(name) || ctx.members.signals.contains(name)
+                })
+            })
+            .map(|expr| {
+                let span = expr.source_span();
+                let text = content.get(span.start..span.end).unwrap_or_default();
+                ctx.make_template_diagnostic(
+                    self.code(),
+                    &event.source_span,
+                    format!("Function in event binding should be invoked: {}()", text),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The expressions whose value an event handler evaluates to: each expression of a chain
+/// and both branches of a conditional.
+fn collect_handlers<'a>(expr: &'a AST, handlers: &mut Vec<&'a AST>) {
+    match expr {
+        AST::Chain(chain) => chain
+            .expressions
+            .iter()
+            .for_each(|expr| collect_handlers(expr, handlers)),
+        AST::Conditional(conditional) => {
+            collect_handlers(&conditional.true_exp, handlers);
+            collect_handlers(&conditional.false_exp, handlers);
+        }
+        _ => handlers.push(expr),
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(UninvokedFunctionInEventBindingCheck)
+}
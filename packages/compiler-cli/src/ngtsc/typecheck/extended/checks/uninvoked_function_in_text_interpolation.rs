@@ -0,0 +1,50 @@
+// Uninvoked Function In Text Interpolation Check
+//
+// Reports methods of the component interpolated without being called, e.g.
+// `{{ fullName }}` instead of `{{ fullName() }}`. Signals are reported by the
+// interpolated signal check instead.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::expression_parser::ast::AST;
+
+pub struct UninvokedFunctionInTextInterpolationCheck;
+
+impl TemplateCheck for UninvokedFunctionInTextInterpolationCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::UninvokedFunctionInTextInterpolation
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::UninvokedFunctionInTextInterpolation
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::Expression(AST::Interpolation(interpolation)) = node else {
+            return Vec::new();
+        };
+        interpolation
+            .expressions
+            .iter()
+            .filter_map(|expr| {
+                let name = ctx.component_member(expr)?;
+                if !ctx.members.methods.contains(name) {
+                    return None;
+                }
+                Some(ctx.make_expression_diagnostic(
+                    self.code(),
+                    expr.source_span(),
+                    format!(
+                        "Function in text interpolation should be invoked: {}()",
+                        name
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(UninvokedFunctionInTextInterpolationCheck)
+}
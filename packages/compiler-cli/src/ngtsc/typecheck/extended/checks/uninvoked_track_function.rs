@@ -0,0 +1,46 @@
+// Uninvoked Track Function Check
+//
+// Reports `@for` blocks tracking items by a method of the component without calling it,
+// e.g. `track trackById` instead of `track trackById($index, item)`.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+
+pub struct UninvokedTrackFunctionCheck;
+
+impl TemplateCheck for UninvokedTrackFunctionCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::UninvokedTrackFunction
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::UninvokedTrackFunction
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::ForLoopBlock(block) = node else {
+            return Vec::new();
+        };
+        let is_function = ctx
+            .component_member(&block.track_by.ast)
+            .is_some_and(|name| {
+                ctx.members.methods.contains(name) || ctx.members.signals.contains(name)
+            });
+        if !is_function {
+            return Vec::new();
+        }
+        vec![ctx.make_template_diagnostic(
+            self.code(),
+            &block.block.source_span,
+            format!(
+                "The track function in the @for block should be invoked: {}(/* arguments */)",
+                block.track_by.source.as_deref().unwrap_or_default().trim()
+            ),
+        )]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(UninvokedTrackFunctionCheck)
+}
@@ -0,0 +1,44 @@
+// Unparenthesized Nullish Coalescing Check
+//
+// Reports `??` mixed with `&&` or `||` without parentheses, e.g. `a ?? b || c`. The
+// expression is a syntax error in TypeScript, which requires the precedence be explicit.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::expression_parser::ast::AST;
+
+pub struct UnparenthesizedNullishCoalescingCheck;
+
+impl TemplateCheck for UnparenthesizedNullishCoalescingCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::UnparenthesizedNullishCoalescing
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::UnparenthesizedNullishCoalescing
+    }
+
+    fn visit_node(&self, ctx: &TemplateContext<'_>, node: TemplateNode<'_>) -> Vec<TypeCheckError> {
+        let TemplateNode::Expression(AST::Binary(binary)) = node else {
+            return Vec::new();
+        };
+        if binary.operation != "&&" && binary.operation != "||" {
+            return Vec::new();
+        }
+        let is_nullish_coalescing =
+            |expr: &AST| matches!(expr, AST::Binary(operand) if operand.operation == "??");
+        if !is_nullish_coalescing(&binary.left) && !is_nullish_coalescing(&binary.right) {
+            return Vec::new();
+        }
+        vec![ctx.make_expression_diagnostic(
+            self.code(),
+            binary.source_span,
+            "Parentheses are required to disambiguate precedence when mixing '??' with '&&' and '||'.",
+        )]
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(UnparenthesizedNullishCoalescingCheck)
+}
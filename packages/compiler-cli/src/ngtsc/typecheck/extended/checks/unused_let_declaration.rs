@@ -0,0 +1,57 @@
+// Unused Let Declaration Check
+//
+// Reports `@let` declarations whose value is never read in the template.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{visit_template, TemplateCheck, TemplateContext, TemplateNode};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::render3::r3_ast::R3Node;
+use angular_compiler::render3::view::t2_api::TemplateEntity;
+use std::collections::HashSet;
+
+pub struct UnusedLetDeclarationCheck;
+
+impl TemplateCheck for UnusedLetDeclarationCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::UnusedLetDeclaration
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::UnusedLetDeclaration
+    }
+
+    fn run(&self, ctx: &TemplateContext<'_>, template: &[R3Node]) -> Vec<TypeCheckError> {
+        let mut declarations = Vec::new();
+        // Declarations are told apart by the offset they start at.
+        let mut used = HashSet::new();
+        visit_template(template, &mut |node| match node {
+            TemplateNode::LetDeclaration(declaration) => declarations.push(declaration),
+            TemplateNode::Expression(expr) => {
+                if let Some(TemplateEntity::LetDeclaration(declaration)) =
+                    ctx.bound.get_expression_target(expr)
+                {
+                    used.insert(declaration.source_span.start.offset);
+                }
+            }
+            _ => {}
+        });
+        declarations
+            .into_iter()
+            .filter(|declaration| !used.contains(&declaration.source_span.start.offset))
+            .map(|declaration| {
+                ctx.make_template_diagnostic(
+                    self.code(),
+                    &declaration.source_span,
+                    format!(
+                        "@let {} is declared but its value is never read.",
+                        declaration.name
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(UnusedLetDeclarationCheck)
+}
@@ -0,0 +1,54 @@
+// Unused Standalone Imports Check
+//
+// Reports directives and pipes listed in the `imports` of a standalone component which are
+// not used within its template.
+
+use super::super::super::api::TypeCheckError;
+use super::super::api::{TemplateCheck, TemplateContext};
+use crate::ngtsc::diagnostics::{ErrorCode, ExtendedTemplateDiagnosticName};
+use angular_compiler::render3::r3_ast::R3Node;
+use std::collections::HashSet;
+
+pub struct UnusedStandaloneImportsCheck;
+
+impl TemplateCheck for UnusedStandaloneImportsCheck {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::UnusedStandaloneImports
+    }
+
+    fn name(&self) -> ExtendedTemplateDiagnosticName {
+        ExtendedTemplateDiagnosticName::UnusedStandaloneImports
+    }
+
+    fn run(&self, ctx: &TemplateContext<'_>, _template: &[R3Node]) -> Vec<TypeCheckError> {
+        let used_directives: HashSet<String> = ctx
+            .bound
+            .get_used_directives()
+            .into_iter()
+            .map(|directive| directive.name)
+            .collect();
+        let used_pipes: HashSet<String> = ctx.bound.get_used_pipes().into_iter().collect();
+        ctx.meta
+            .imports
+            .iter()
+            .filter(|import| match &import.pipe_name {
+                Some(pipe_name) => !used_pipes.contains(pipe_name),
+                None => !used_directives.contains(&import.name),
+            })
+            .map(|import| TypeCheckError {
+                message: format!(
+                    "{} is not used within the template of {}",
+                    import.name, ctx.meta.component_name
+                ),
+                code: format!("NG{}", self.code() as i32),
+                file: Some(ctx.meta.component_file.clone()),
+                start: Some(import.start),
+                length: Some(import.end.saturating_sub(import.start)),
+            })
+            .collect()
+    }
+}
+
+pub fn factory() -> Box<dyn TemplateCheck> {
+    Box::new(UnusedStandaloneImportsCheck)
+}
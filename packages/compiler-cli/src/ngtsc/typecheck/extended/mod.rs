@@ -0,0 +1,31 @@
+// Extended Template Diagnostics Module
+
+pub mod api;
+pub mod checks;
+pub mod src;
+
+pub use api::*;
+pub use src::*;
+
+use checks::*;
+
+/// Every extended check, in the order their diagnostics are reported.
+///
+/// Checks which need TypeScript type information, such as `nullishCoalescingNotNullable`,
+/// are not implemented. They are accepted in `extendedDiagnostics`, and have no effect.
+pub const ALL_DIAGNOSTIC_FACTORIES: &[TemplateCheckFactory] = &[
+    invalid_banana_in_box::factory,
+    missing_control_flow_directive::factory,
+    text_attribute_not_binding::factory,
+    missing_ngforof_let::factory,
+    suffix_not_supported::factory,
+    interpolated_signal_not_invoked::factory,
+    uninvoked_function_in_event_binding::factory,
+    unused_let_declaration::factory,
+    skip_hydration_not_static::factory,
+    unparenthesized_nullish_coalescing::factory,
+    uninvoked_track_function::factory,
+    missing_structural_directive::factory,
+    uninvoked_function_in_text_interpolation::factory,
+    unused_standalone_imports::factory,
+];
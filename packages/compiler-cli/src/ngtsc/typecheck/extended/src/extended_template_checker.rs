@@ -0,0 +1,412 @@
+// Extended Template Checker
+//
+// Runs the extended checks over component templates, reporting the diagnostics of each
+// check with the category it is configured with. Suppressed checks are not run at all.
+
+use super::super::super::api::TypeCheckBlockMetadata;
+use super::super::super::src::bind_template;
+use super::super::api::{
+    ComponentMembers, ExtendedDiagnosticsOptions, ExtendedTemplateDiagnostic, TemplateCheck,
+    TemplateCheckFactory, TemplateContext,
+};
+use crate::ngtsc::diagnostics::DiagnosticCategory;
+
+/// Runs the enabled extended checks over component templates.
+pub struct ExtendedTemplateCheckerImpl {
+    /// The enabled checks, with the category of their diagnostics.
+    checks: Vec<(Box<dyn TemplateCheck>, DiagnosticCategory)>,
+}
+
+impl ExtendedTemplateCheckerImpl {
+    pub fn new(factories: &[TemplateCheckFactory], options: &ExtendedDiagnosticsOptions) -> Self {
+        let checks = factories
+            .iter()
+            .filter_map(|factory| {
+                let check = factory();
+                let category = options.label_of(check.name()).category()?;
+                Some((check, category))
+            })
+            .collect();
+        Self { checks }
+    }
+
+    /// Run the enabled checks over the template of a component.
+    pub fn get_diagnostics_for_component(
+        &self,
+        meta: &TypeCheckBlockMetadata,
+        members: &ComponentMembers,
+    ) -> Vec<ExtendedTemplateDiagnostic> {
+        if self.checks.is_empty() {
+            return Vec::new();
+        }
+        let bound = bind_template(meta);
+        let ctx = TemplateContext {
+            meta,
+            members,
+            bound: bound.as_ref(),
+        };
+        self.checks
+            .iter()
+            .flat_map(|(check, category)| {
+                check.run(&ctx, &meta.nodes).into_iter().map(|diagnostic| {
+                    ExtendedTemplateDiagnostic {
+                        category: *category,
+                        diagnostic,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::super::api::{TypeCheckImport, TypeCheckableDirectiveMeta};
+    use super::super::super::api::DiagnosticCategoryLabel;
+    use super::super::super::ALL_DIAGNOSTIC_FACTORIES;
+    use super::*;
+    use angular_compiler::render3::view::template::{parse_template, ParseTemplateOptions};
+
+    fn metadata(
+        template: &str,
+        directives: Vec<TypeCheckableDirectiveMeta>,
+    ) -> TypeCheckBlockMetadata {
+        let parsed = parse_template(template, "app.html", ParseTemplateOptions::default());
+        TypeCheckBlockMetadata {
+            component_name: "AppComponent".to_string(),
+            nodes: parsed.nodes,
+            directives,
+            pipes: Default::default(),
+            template_file: "app.html".to_string(),
+            template_offset: 0,
+            schemas: Vec::new(),
+            is_poisoned: false,
+            component_file: "app.ts".to_string(),
+            imports: Vec::new(),
+        }
+    }
+
+    fn members(methods: &[&str], signals: &[&str]) -> ComponentMembers {
+        ComponentMembers {
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+            signals: signals.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn check_with(
+        template: &str,
+        members: &ComponentMembers,
+        options: &ExtendedDiagnosticsOptions,
+    ) -> Vec<ExtendedTemplateDiagnostic> {
+        ExtendedTemplateCheckerImpl::new(ALL_DIAGNOSTIC_FACTORIES, options)
+            .get_diagnostics_for_component(&metadata(template, Vec::new()), members)
+    }
+
+    /// The code and template text of each diagnostic reported for a template.
+    fn reported(template: &str, members: &ComponentMembers) -> Vec<(String, String)> {
+        check_with(template, members, &ExtendedDiagnosticsOptions::default())
+            .into_iter()
+            .map(|d| {
+                let start = d.diagnostic.start.unwrap();
+                let end = start + d.diagnostic.length.unwrap();
+                (d.diagnostic.code, template[start..end].to_string())
+            })
+            .collect()
+    }
+
+    fn messages(template: &str, members: &ComponentMembers) -> Vec<String> {
+        check_with(template, members, &ExtendedDiagnosticsOptions::default())
+            .into_iter()
+            .map(|d| d.diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_invalid_banana_in_box() {
+        let template = r#"<input ([ngModel])="name">"#;
+        assert_eq!(
+            reported(template, &members(&[], &[])),
+            vec![("NG8101".to_string(), r#"([ngModel])="name""#.to_string())]
+        );
+        assert!(messages(template, &members(&[], &[]))[0].contains(r#"ex. '[(ngModel)]="name"'"#));
+        assert!(reported(r#"<input [(ngModel)]="name">"#, &members(&[], &[])).is_empty());
+    }
+
+    #[test]
+    fn test_missing_control_flow_directive() {
+        let template = r#"<div *ngIf="shown"></div>"#;
+        assert_eq!(
+            reported(template, &members(&[], &[])),
+            vec![("NG8103".to_string(), "ngIf".to_string())]
+        );
+        assert!(messages(template, &members(&[], &[]))[0].contains("control flow @if"));
+
+        let ng_if = TypeCheckableDirectiveMeta {
+            name: "NgIf".to_string(),
+            reference: "i1.NgIf".to_string(),
+            selector: Some("[ngIf]".to_string()),
+            is_structural: true,
+            ..Default::default()
+        };
+        let checker = ExtendedTemplateCheckerImpl::new(
+            ALL_DIAGNOSTIC_FACTORIES,
+            &ExtendedDiagnosticsOptions::default(),
+        );
+        let meta = metadata(template, vec![ng_if]);
+        assert!(checker
+            .get_diagnostics_for_component(&meta, &members(&[], &[]))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_missing_structural_directive() {
+        assert_eq!(
+            reported(r#"<div *appHighlight="color"></div>"#, &members(&[], &[])),
+            vec![("NG8116".to_string(), "appHighlight".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_directive_checks_skip_poisoned_scopes() {
+        let mut meta = metadata(
+            r#"<div *ngIf="shown"></div><p *appHighlight></p>"#,
+            Vec::new(),
+        );
+        meta.is_poisoned = true;
+        let checker = ExtendedTemplateCheckerImpl::new(
+            ALL_DIAGNOSTIC_FACTORIES,
+            &ExtendedDiagnosticsOptions::default(),
+        );
+        assert!(checker
+            .get_diagnostics_for_component(&meta, &members(&[], &[]))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_text_attribute_not_binding() {
+        let template = r#"<div attr.id="main" class.active="true" style.color="red"></div>"#;
+        assert_eq!(
+            messages(template, &members(&[], &[])),
+            vec![
+                r#"Static attributes should be written without the 'attr.' prefix. For example, id="main"."#,
+                r#"Attribute, style, and class bindings should be enclosed with square braces. For example, '[class.active]="true"'."#,
+                r#"Attribute, style, and class bindings should be enclosed with square braces. For example, '[style.color]="'value'"'."#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_ngforof_let() {
+        let diagnostics = reported(r#"<li *ngFor="items"></li>"#, &members(&[], &[]));
+        assert!(diagnostics.iter().any(|(code, _)| code == "NG8105"));
+        assert!(reported(
+            r#"<li *ngFor="let item of items"></li>"#,
+            &members(&[], &[])
+        )
+        .iter()
+        .all(|(code, _)| code != "NG8105"));
+    }
+
+    #[test]
+    fn test_suffix_not_supported() {
+        assert_eq!(
+            reported(
+                r#"<img [attr.width.px]="width" [style.height.px]="height">"#,
+                &members(&[], &[])
+            ),
+            vec![("NG8106".to_string(), "attr.width.px".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_skip_hydration_not_static() {
+        let template = r#"<a ngSkipHydration="yes"></a><b [ngSkipHydration]="skip"></b><c ngSkipHydration></c><d ngSkipHydration="true"></d>"#;
+        assert_eq!(
+            reported(template, &members(&[], &[])),
+            vec![
+                ("NG8108".to_string(), r#"ngSkipHydration="yes""#.to_string()),
+                (
+                    "NG8108".to_string(),
+                    r#"[ngSkipHydration]="skip""#.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interpolated_signal_not_invoked() {
+        let template = r#"{{ count }} {{ count() }} <div [class.active]="active"></div>"#;
+        assert_eq!(
+            reported(template, &members(&[], &["count", "active"])),
+            vec![
+                ("NG8109".to_string(), "count".to_string()),
+                ("NG8109".to_string(), "active".to_string()),
+            ]
+        );
+        assert_eq!(
+            messages("{{ count }}", &members(&[], &["count"])),
+            vec!["count is a function and should be invoked: count()"]
+        );
+    }
+
+    #[test]
+    fn test_uninvoked_function_in_event_binding() {
+        let template = r#"<button (click)="save"></button><button (click)="save()"></button>"#;
+        assert_eq!(
+            reported(template, &members(&["save"], &[])),
+            vec![("NG8111".to_string(), r#"(click)="save""#.to_string())]
+        );
+        assert_eq!(
+            messages(
+                r#"<button (click)="ok ? save : reset()"></button>"#,
+                &members(&["save"], &[])
+            ),
+            vec!["Function in event binding should be invoked: save()"]
+        );
+    }
+
+    #[test]
+    fn test_unused_let_declaration() {
+        let template = "@let used = 1; @let unused = 2; {{ used }}";
+        assert_eq!(
+            messages(template, &members(&[], &[])),
+            vec!["@let unused is declared but its value is never read."]
+        );
+    }
+
+    #[test]
+    fn test_unused_standalone_imports() {
+        let highlight = TypeCheckableDirectiveMeta {
+            name: "Highlight".to_string(),
+            reference: "Highlight".to_string(),
+            selector: Some("[appHighlight]".to_string()),
+            ..Default::default()
+        };
+        let tooltip = TypeCheckableDirectiveMeta {
+            name: "Tooltip".to_string(),
+            reference: "Tooltip".to_string(),
+            selector: Some("[appTooltip]".to_string()),
+            ..Default::default()
+        };
+        let mut meta = metadata(
+            "<p appHighlight>{{ today | date }}</p>",
+            vec![highlight, tooltip],
+        );
+        meta.pipes
+            .insert("date".to_string(), "DatePipe".to_string());
+        meta.pipes
+            .insert("upper".to_string(), "UpperPipe".to_string());
+        let import = |name: &str, pipe_name: Option<&str>, start: usize| TypeCheckImport {
+            name: name.to_string(),
+            pipe_name: pipe_name.map(str::to_string),
+            start,
+            end: start + name.len(),
+        };
+        meta.imports = vec![
+            import("Highlight", None, 100),
+            import("Tooltip", None, 111),
+            import("DatePipe", Some("date"), 120),
+            import("UpperPipe", Some("upper"), 130),
+        ];
+
+        let diagnostics = ExtendedTemplateCheckerImpl::new(
+            ALL_DIAGNOSTIC_FACTORIES,
+            &ExtendedDiagnosticsOptions::default(),
+        )
+        .get_diagnostics_for_component(&meta, &members(&[], &[]));
+        let reported: Vec<_> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.diagnostic.code.as_str(),
+                    d.diagnostic.file.as_deref(),
+                    d.diagnostic.start,
+                    d.diagnostic.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "NG8113",
+                    Some("app.ts"),
+                    Some(111),
+                    "Tooltip is not used within the template of AppComponent"
+                ),
+                (
+                    "NG8113",
+                    Some("app.ts"),
+                    Some(130),
+                    "UpperPipe is not used within the template of AppComponent"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unparenthesized_nullish_coalescing() {
+        assert_eq!(
+            reported("{{ a ?? b || c }} {{ (a ?? b) || c }}", &members(&[], &[])),
+            vec![("NG8114".to_string(), "a ?? b || c".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_uninvoked_track_function() {
+        let template = "@for (item of items; track trackById) {{{ item }}}";
+        assert_eq!(
+            messages(template, &members(&["trackById"], &[])),
+            vec!["The track function in the @for block should be invoked: trackById(/* arguments */)"]
+        );
+        assert!(messages(
+            "@for (item of items; track trackById($index, item)) {{{ item }}}",
+            &members(&["trackById"], &[])
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_uninvoked_function_in_text_interpolation() {
+        assert_eq!(
+            reported(
+                "{{ fullName }} {{ fullName() }} {{ title }}",
+                &members(&["fullName"], &[])
+            ),
+            vec![("NG8117".to_string(), "fullName".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_template_variables_are_not_component_members() {
+        let template = "@for (save of items; track $index) {{{ save }}}";
+        assert!(messages(template, &members(&["save"], &[])).is_empty());
+    }
+
+    #[test]
+    fn test_configured_categories() {
+        let template = r#"<input ([ngModel])="name" attr.id="main">"#;
+        let mut options = ExtendedDiagnosticsOptions {
+            default_category: Some(DiagnosticCategoryLabel::Error),
+            ..Default::default()
+        };
+        options.checks.insert(
+            "textAttributeNotBinding".to_string(),
+            DiagnosticCategoryLabel::Suppress,
+        );
+        let diagnostics = check_with(template, &members(&[], &[]), &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].diagnostic.code, "NG8101");
+        assert_eq!(diagnostics[0].category, DiagnosticCategory::Error);
+
+        let diagnostics = check_with(
+            template,
+            &members(&[], &[]),
+            &ExtendedDiagnosticsOptions::default(),
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.category == DiagnosticCategory::Warning));
+    }
+}
@@ -0,0 +1,6 @@
+// Extended Template Diagnostics Source Module
+
+pub mod extended_template_checker;
+
+// Re-exports
+pub use extended_template_checker::ExtendedTemplateCheckerImpl;
@@ -1,6 +1,7 @@
 // TypeCheck Module
 
 pub mod api;
+pub mod extended;
 pub mod src;
 
 pub use api::*;
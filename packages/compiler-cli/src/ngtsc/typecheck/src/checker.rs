@@ -1,12 +1,24 @@
 // TypeCheck Checker Implementation
 //
-// Main template type-checker implementation.
+// Main template type-checker implementation. Type-check blocks are written to shim
+// files in a temporary directory of the project and checked by the project's own `tsc`.
 
-use super::super::api::{
-    TemplateTypeChecker, TypeCheckContext, TypeCheckError, TypeCheckResult, TypeCheckingConfig,
+use super::super::api::{TemplateTypeChecker, TypeCheckError, TypeCheckResult, TypeCheckingConfig};
+use super::context::TypeCheckingContext;
+use super::diagnostics::{
+    offset_in_shim, parse_tsc_global_errors, parse_tsc_output, translate_file_diagnostic,
+    translate_tsc_diagnostic, TscDiagnostic,
 };
-use super::type_check_block::TypeCheckBlockGenerator;
+use super::expression::quote_string;
+use super::shim::{type_check_shim_path, TypeCheckShim};
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory the shims and the tsconfig checking them are written to while `tsc` runs.
+/// It is created in the project directory, so that packages resolve from the project's
+/// `node_modules`, and mirrors the layout of the source files.
+const TYPE_CHECK_DIR: &str = ".ngtypecheck";
 
 /// Implementation of the template type-checker.
 pub struct TemplateTypeCheckerImpl {
@@ -16,8 +28,6 @@ pub struct TemplateTypeCheckerImpl {
     checked_components: HashSet<String>,
     /// Cached diagnostics per component.
     cached_diagnostics: HashMap<String, Vec<TypeCheckError>>,
-    /// Global context.
-    context: TypeCheckContext,
 }
 
 impl TemplateTypeCheckerImpl {
@@ -26,49 +36,219 @@ impl TemplateTypeCheckerImpl {
             config,
             checked_components: HashSet::new(),
             cached_diagnostics: HashMap::new(),
-            context: TypeCheckContext::new(),
         }
     }
 
-    /// Type-check a component.
-    pub fn type_check_component(&mut self, component: &str, template: &str) -> TypeCheckResult {
-        if self.checked_components.contains(component) {
-            // Return cached result
-            let diagnostics = self
-                .cached_diagnostics
-                .get(component)
-                .cloned()
-                .unwrap_or_default();
-            return TypeCheckResult {
-                success: diagnostics.is_empty(),
-                diagnostics,
+    /// Get the configuration.
+    pub fn config(&self) -> &TypeCheckingConfig {
+        &self.config
+    }
+
+    /// Type-check the templates of a context with `tsc`, in the project configured by
+    /// `tsconfig`. The shims only exist on disk while `tsc` runs. Diagnostics `tsc`
+    /// reports outside of the shims are returned as they are.
+    pub fn type_check(
+        &mut self,
+        context: &TypeCheckingContext,
+        tsc: &Path,
+        tsconfig: &Path,
+    ) -> Result<TypeCheckResult, String> {
+        let shims = context.finalize();
+        let project_dir = tsconfig
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let output = self.run_tsc(tsc, &project_dir, tsconfig, &shims)?;
+
+        let mut diagnostics_by_component: HashMap<String, Vec<TypeCheckError>> = HashMap::new();
+        for shim in &shims {
+            for block in &shim.blocks {
+                diagnostics_by_component
+                    .entry(block.component_name.clone())
+                    .or_default();
+            }
+        }
+        for (shim, diagnostic) in &output.shim_diagnostics {
+            let Some(error) = translate_tsc_diagnostic(diagnostic, shim) else {
+                continue;
             };
+            let offset = offset_in_shim(shim, diagnostic);
+            if let Some(block) = offset.and_then(|offset| shim.block_at(offset)) {
+                diagnostics_by_component
+                    .entry(block.component_name.clone())
+                    .or_default()
+                    .push(error);
+            }
+        }
+        for shim in &shims {
+            for diagnostic in &shim.diagnostics {
+                let owner = shim
+                    .blocks
+                    .iter()
+                    .find(|block| Some(&block.template_file) == diagnostic.file.as_ref())
+                    .or(shim.blocks.first());
+                if let Some(block) = owner {
+                    diagnostics_by_component
+                        .entry(block.component_name.clone())
+                        .or_default()
+                        .push(diagnostic.clone());
+                }
+            }
         }
 
-        // Generate type-check block
-        let mut generator = TypeCheckBlockGenerator::new(self.config.clone());
-        let result = generator.generate(component, template);
+        let mut diagnostics = output.other_diagnostics;
+        for (component, component_diagnostics) in diagnostics_by_component {
+            diagnostics.extend(component_diagnostics.iter().cloned());
+            self.checked_components.insert(component.clone());
+            self.cached_diagnostics
+                .insert(component, component_diagnostics);
+        }
+        Ok(TypeCheckResult {
+            success: diagnostics.is_empty(),
+            diagnostics,
+        })
+    }
 
-        let diagnostics = match result {
-            Ok(_tcb) => {
-                // In a real implementation, we would feed the TCB to TypeScript
-                // and collect diagnostics. For now, return empty.
-                Vec::new()
+    /// Write the shims and a tsconfig including them, run `tsc` and collect the
+    /// diagnostics it reported. Fails when `tsc` exits with an error without reporting
+    /// any diagnostic.
+    fn run_tsc<'s>(
+        &self,
+        tsc: &Path,
+        project_dir: &Path,
+        tsconfig: &Path,
+        shims: &'s [TypeCheckShim],
+    ) -> Result<TscOutput<'s>, String> {
+        let dir = TempDir(project_dir.join(TYPE_CHECK_DIR));
+        std::fs::create_dir_all(&dir.0)
+            .map_err(|e| format!("Failed to create {}: {}", dir.0.display(), e))?;
+        let dir_path = std::fs::canonicalize(&dir.0).unwrap_or_else(|_| dir.0.clone());
+
+        // Shims are placed at the same path relative to the directory as their source file
+        // relative to `root`; `rootDirs` then resolves their relative imports next to the
+        // source file.
+        let sources: Vec<PathBuf> = shims
+            .iter()
+            .map(|shim| {
+                std::fs::canonicalize(&shim.source_file)
+                    .unwrap_or_else(|_| PathBuf::from(&shim.source_file))
+            })
+            .collect();
+        let root = common_ancestor(&sources);
+        let mut shim_paths = Vec::with_capacity(shims.len());
+        for (shim, source) in shims.iter().zip(&sources) {
+            let relative = source.strip_prefix(&root).unwrap_or(source);
+            let path = dir_path.join(type_check_shim_path(&relative.to_string_lossy()));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
             }
-            Err(e) => vec![e],
-        };
+            std::fs::write(&path, &shim.code)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            shim_paths.push(path);
+        }
 
-        self.checked_components.insert(component.to_string());
-        self.cached_diagnostics
-            .insert(component.to_string(), diagnostics.clone());
+        let extends = std::fs::canonicalize(tsconfig).unwrap_or_else(|_| tsconfig.to_path_buf());
+        let files = shim_paths
+            .iter()
+            .map(|path| quote_string(&path.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let config = format!(
+            "{{\n  \"extends\": {},\n  \"compilerOptions\": {{\n    \"noEmit\": true,\n    \"incremental\": false,\n    \"composite\": false,\n    \"noUnusedLocals\": false,\n    \"noUnusedParameters\": false,\n    \"rootDirs\": [{}, {}]\n  }},\n  \"files\": [{}],\n  \"include\": []\n}}\n",
+            quote_string(&extends.to_string_lossy()),
+            quote_string(&root.to_string_lossy()),
+            quote_string(&dir_path.to_string_lossy()),
+            files
+        );
+        let config_path = dir_path.join("tsconfig.json");
+        std::fs::write(&config_path, config)
+            .map_err(|e| format!("Failed to write {}: {}", config_path.display(), e))?;
 
-        TypeCheckResult {
-            success: diagnostics.is_empty(),
-            diagnostics,
+        let output = Command::new(tsc)
+            .arg("-p")
+            .arg(&config_path)
+            .arg("--pretty")
+            .arg("false")
+            .current_dir(project_dir)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", tsc.display(), e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let diagnostics = parse_tsc_output(&stdout);
+        let mut result = TscOutput {
+            shim_diagnostics: Vec::new(),
+            other_diagnostics: parse_tsc_global_errors(&stdout),
+        };
+        if !output.status.success() && diagnostics.is_empty() && result.other_diagnostics.is_empty()
+        {
+            return Err(format!(
+                "{} failed with {}: {}{}",
+                tsc.display(),
+                output.status,
+                stdout,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        for diagnostic in diagnostics {
+            let reported = project_dir.join(&diagnostic.file);
+            let reported = std::fs::canonicalize(&reported).unwrap_or(reported);
+            match shim_paths.iter().position(|path| *path == reported) {
+                Some(index) => result.shim_diagnostics.push((&shims[index], diagnostic)),
+                None => {
+                    let content = std::fs::read_to_string(&reported).unwrap_or_default();
+                    result.other_diagnostics.push(translate_file_diagnostic(
+                        &diagnostic,
+                        &reported.to_string_lossy(),
+                        &content,
+                    ));
+                }
+            }
         }
+        Ok(result)
     }
 }
 
+/// The diagnostics `tsc` reported within the shims, and the other ones, such as errors in
+/// the source files or in the configuration.
+struct TscOutput<'s> {
+    shim_diagnostics: Vec<(&'s TypeCheckShim, TscDiagnostic)>,
+    other_diagnostics: Vec<TypeCheckError>,
+}
+
+/// A directory which is removed, along with its contents, when dropped.
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The deepest directory containing all of `paths`.
+fn common_ancestor(paths: &[PathBuf]) -> PathBuf {
+    let mut root = paths
+        .first()
+        .and_then(|path| path.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    for path in paths {
+        while !path.starts_with(&root) && root.pop() {}
+    }
+    root
+}
+
+/// Find the `tsc` binary of the project, looking in `node_modules/.bin` of `dir` and its
+/// ancestors.
+pub fn find_tsc(dir: &Path) -> Option<PathBuf> {
+    let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let name = if cfg!(windows) { "tsc.cmd" } else { "tsc" };
+    dir.ancestors()
+        .map(|ancestor| ancestor.join("node_modules").join(".bin").join(name))
+        .find(|candidate| candidate.exists())
+}
+
 impl TemplateTypeChecker for TemplateTypeCheckerImpl {
     fn get_diagnostics_for_component(&self, component: &str) -> Vec<TypeCheckError> {
         self.cached_diagnostics
@@ -98,3 +278,121 @@ impl TemplateTypeChecker for TemplateTypeCheckerImpl {
         self.cached_diagnostics.clear();
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::super::super::api::TypeCheckBlockMetadata;
+    use super::*;
+    use angular_compiler::render3::view::template::{parse_template, ParseTemplateOptions};
+    use std::os::unix::fs::PermissionsExt;
+
+    fn project(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ngc_typecheck_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("tsconfig.json"), "{}").unwrap();
+        std::fs::write(
+            dir.join("src/app.ts"),
+            "export class App {}\nlet x: number = '';\n",
+        )
+        .unwrap();
+        std::fs::canonicalize(dir).unwrap()
+    }
+
+    fn context(project: &Path) -> TypeCheckingContext {
+        let template = "<p>{{ nmae }}</p>";
+        let parsed = parse_template(template, "app.html", ParseTemplateOptions::default());
+        let mut context = TypeCheckingContext::new(TypeCheckingConfig::strict());
+        context.add_template(
+            project.join("src/app.ts").to_string_lossy(),
+            Vec::new(),
+            TypeCheckBlockMetadata {
+                component_name: "App".to_string(),
+                nodes: parsed.nodes,
+                directives: vec![],
+                pipes: Default::default(),
+                template_file: "src/app.html".to_string(),
+                template_offset: 0,
+                schemas: vec![],
+                is_poisoned: false,
+                component_file: "src/app.ts".to_string(),
+                imports: vec![],
+            },
+        );
+        context
+    }
+
+    /// A `tsc` which runs `script` after saving the tsconfig it is given.
+    fn fake_tsc(project: &Path, script: &str) -> PathBuf {
+        let tsc = project.join("tsc.sh");
+        std::fs::write(
+            &tsc,
+            format!("#!/bin/sh\ncp \"$2\" seen-tsconfig.json\n{}\n", script),
+        )
+        .unwrap();
+        std::fs::set_permissions(&tsc, std::fs::Permissions::from_mode(0o755)).unwrap();
+        tsc
+    }
+
+    #[test]
+    fn test_reports_shim_source_and_config_diagnostics() {
+        let project = project("diagnostics");
+        let context = context(&project);
+        let code = &context.finalize()[0].code;
+        let offset = code.find("nmae").unwrap();
+        let line = code[..offset].matches('\n').count() + 1;
+        let column = offset - code[..offset].rfind('\n').map_or(0, |i| i + 1) + 1;
+        let tsc = fake_tsc(
+            &project,
+            &format!(
+                "test -f .ngtypecheck/app.ngtypecheck.ts || exit 3\n\
+                 echo \".ngtypecheck/app.ngtypecheck.ts({},{}): error TS2339: Property 'nmae' does not exist on type 'App'.\"\n\
+                 echo \"src/app.ts(2,5): error TS2322: Type 'string' is not assignable to type 'number'.\"\n\
+                 echo \"error TS5023: Unknown compiler option 'foo'.\"\n\
+                 exit 2",
+                line, column
+            ),
+        );
+
+        let mut checker = TemplateTypeCheckerImpl::new(TypeCheckingConfig::strict());
+        let result = checker
+            .type_check(&context, &tsc, &project.join("tsconfig.json"))
+            .unwrap();
+        let reported: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.file.clone(), d.start))
+            .collect();
+        let app = project.join("src/app.ts").to_string_lossy().to_string();
+        assert_eq!(
+            reported,
+            vec![
+                ("TS5023", None, None),
+                ("TS2322", Some(app), Some(24)),
+                ("TS2339", Some("src/app.html".to_string()), Some(6)),
+            ]
+        );
+        assert_eq!(checker.get_diagnostics_for_component("App").len(), 1);
+
+        let config = std::fs::read_to_string(project.join("seen-tsconfig.json")).unwrap();
+        assert!(config.contains("\"rootDirs\""), "{}", config);
+        assert!(!project.join(TYPE_CHECK_DIR).exists());
+        assert!(!project.join("src/app.ngtypecheck.ts").exists());
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn test_fails_when_tsc_fails_without_diagnostics() {
+        let project = project("failure");
+        let tsc = fake_tsc(&project, "echo 'out of memory' >&2\nexit 134");
+
+        let mut checker = TemplateTypeCheckerImpl::new(TypeCheckingConfig::strict());
+        let error = checker
+            .type_check(&context(&project), &tsc, &project.join("tsconfig.json"))
+            .unwrap_err();
+        assert!(error.contains("out of memory"), "{}", error);
+        assert!(!project.join(TYPE_CHECK_DIR).exists());
+        let _ = std::fs::remove_dir_all(&project);
+    }
+}
@@ -2,31 +2,34 @@
 //
 // Manages type-check block generation context.
 
-use super::super::api::{TypeCheckError, TypeCheckingConfig};
+use super::super::api::{TypeCheckBlockMetadata, TypeCheckingConfig};
+use super::expression::TcbSourceMapping;
+use super::shim::{shim_module_specifier, type_check_shim_path, ShimBlock, TypeCheckShim};
+use super::type_check_block::TypeCheckBlockGenerator;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
-/// Context for generating type-check blocks for a file.
+/// Templates of a source file waiting to be written to its shim.
+struct PendingShim {
+    /// Import declarations of the source file, copied into the shim.
+    imports: Vec<String>,
+    /// Templates of the components declared in the source file.
+    templates: Vec<TypeCheckBlockMetadata>,
+}
+
+/// Collects component templates and generates the type-check shims for their files.
 pub struct TypeCheckingContext {
     /// Configuration.
     config: TypeCheckingConfig,
-    /// File being type-checked.
-    file: String,
-    /// Generated type-check block code.
-    generated_code: Vec<String>,
-    /// Mapping from component to TCB.
-    component_to_tcb: HashMap<String, String>,
-    /// Errors.
-    errors: Vec<TypeCheckError>,
+    /// Pending shims by source file, in the order files were added.
+    files: IndexMap<String, PendingShim>,
 }
 
 impl TypeCheckingContext {
-    pub fn new(config: TypeCheckingConfig, file: impl Into<String>) -> Self {
+    pub fn new(config: TypeCheckingConfig) -> Self {
         Self {
             config,
-            file: file.into(),
-            generated_code: Vec::new(),
-            component_to_tcb: HashMap::new(),
-            errors: Vec::new(),
+            files: IndexMap::new(),
         }
     }
 
@@ -35,32 +38,87 @@ impl TypeCheckingContext {
         &self.config
     }
 
-    /// Add a type-check block for a component.
-    pub fn add_type_check_block(&mut self, component: impl Into<String>, tcb: impl Into<String>) {
-        let comp = component.into();
-        let code = tcb.into();
-        self.component_to_tcb.insert(comp, code.clone());
-        self.generated_code.push(code);
+    /// Add the template of a component declared in `source_file`. `imports` are the import
+    /// declarations of the source file, which directive and pipe references rely on.
+    pub fn add_template(
+        &mut self,
+        source_file: impl Into<String>,
+        imports: Vec<String>,
+        template: TypeCheckBlockMetadata,
+    ) {
+        let pending = self
+            .files
+            .entry(source_file.into())
+            .or_insert_with(|| PendingShim {
+                imports: Vec::new(),
+                templates: Vec::new(),
+            });
+        for import in imports {
+            if !pending.imports.contains(&import) {
+                pending.imports.push(import);
+            }
+        }
+        pending.templates.push(template);
     }
 
-    /// Add an error.
-    pub fn add_error(&mut self, error: TypeCheckError) {
-        self.errors.push(error);
-    }
+    /// Generate the shims for all templates added so far.
+    pub fn finalize(&self) -> Vec<TypeCheckShim> {
+        let generator = TypeCheckBlockGenerator::new(self.config.clone());
+        let mut next_tcb = 1;
+        let mut shims = Vec::with_capacity(self.files.len());
 
-    /// Get all generated code.
-    pub fn generated_code(&self) -> &[String] {
-        &self.generated_code
-    }
+        for (source_file, pending) in &self.files {
+            let mut code = String::new();
+            code.push_str("import * as i0 from '@angular/core';\n");
+            code.push_str(&format!(
+                "import * as i1 from '{}';\n",
+                shim_module_specifier(source_file)
+            ));
+            for import in &pending.imports {
+                code.push_str(import);
+                code.push('\n');
+            }
+            code.push('\n');
 
-    /// Get all errors.
-    pub fn errors(&self) -> &[TypeCheckError] {
-        &self.errors
-    }
+            let mut blocks = Vec::new();
+            let mut diagnostics = Vec::new();
+            for template in &pending.templates {
+                let name = format!("_tcb{}", next_tcb);
+                next_tcb += 1;
+                let tcb =
+                    generator.generate(&name, &format!("i1.{}", template.component_name), template);
+                let start = code.len();
+                code.push_str(&tcb.code);
+                blocks.push(ShimBlock {
+                    component_name: template.component_name.clone(),
+                    start,
+                    end: code.len(),
+                    mappings: tcb
+                        .mappings
+                        .iter()
+                        .map(|m| TcbSourceMapping {
+                            tcb_start: m.tcb_start + start,
+                            tcb_end: m.tcb_end + start,
+                            ..*m
+                        })
+                        .collect(),
+                    template_file: template.template_file.clone(),
+                    template_offset: template.template_offset,
+                });
+                diagnostics.extend(tcb.diagnostics);
+                code.push('\n');
+            }
+            code.push_str("export const IS_A_MODULE = true;\n");
 
-    /// Get TCB for a component.
-    pub fn get_tcb(&self, component: &str) -> Option<&String> {
-        self.component_to_tcb.get(component)
+            shims.push(TypeCheckShim {
+                path: type_check_shim_path(source_file),
+                source_file: source_file.clone(),
+                code,
+                blocks,
+                diagnostics,
+            });
+        }
+        shims
     }
 }
 
@@ -3,6 +3,7 @@
 // Template type-check diagnostics handling.
 
 use super::super::api::TypeCheckError;
+use super::shim::TypeCheckShim;
 
 /// Diagnostic code for template errors.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,10 +16,10 @@ pub enum TemplateDiagnosticCode {
     InvalidTwoWayBinding = 8005,
     /// Invalid event binding.
     InvalidEventBinding = 8006,
+    /// Required input not provided.
+    MissingRequiredInput = 8008,
     /// Type error in binding.
     TypeMismatch = 8100,
-    /// Required input not provided.
-    MissingRequiredInput = 8101,
     /// Unknown element.
     UnknownElement = 8001,
 }
@@ -35,11 +36,23 @@ pub fn create_unknown_property_diagnostic(
     element: &str,
     property: &str,
 ) -> TypeCheckError {
+    let mut message = format!(
+        "Can't bind to '{}' since it isn't a known property of '{}'.",
+        property, element
+    );
+    if element.starts_with("ng-") {
+        message.push_str(&format!(
+            "\n1. If '{}' is an Angular directive, then add 'CommonModule' to the '@Component.imports' of this component.\n2. To allow any property add 'NO_ERRORS_SCHEMA' to the '@Component.schemas' of this component.",
+            property
+        ));
+    } else if element.contains('-') {
+        message.push_str(&format!(
+            "\n1. If '{}' is an Angular component and it has '{}' input, then verify that it is included in the '@Component.imports' of this component.\n2. If '{}' is a Web Component then add 'CUSTOM_ELEMENTS_SCHEMA' to the '@Component.schemas' of this component to suppress this message.\n3. To allow any property add 'NO_ERRORS_SCHEMA' to the '@Component.schemas' of this component.",
+            element, property, element
+        ));
+    }
     TypeCheckError {
-        message: format!(
-            "Can't bind to '{}' since it isn't a known property of '{}'",
-            property, element
-        ),
+        message,
         code: TemplateDiagnosticCode::UnknownProperty.code(),
         file: Some(file.to_string()),
         start: None,
@@ -49,8 +62,20 @@ pub fn create_unknown_property_diagnostic(
 
 /// Create a diagnostic for unknown element.
 pub fn create_unknown_element_diagnostic(file: &str, element: &str) -> TypeCheckError {
+    let hint = if element.contains('-') {
+        format!(
+            "2. If '{}' is a Web Component then add 'CUSTOM_ELEMENTS_SCHEMA' to the '@Component.schemas' of this component to suppress this message.",
+            element
+        )
+    } else {
+        "2. To allow any element add 'NO_ERRORS_SCHEMA' to the '@Component.schemas' of this component."
+            .to_string()
+    };
     TypeCheckError {
-        message: format!("'{}' is not a known element", element),
+        message: format!(
+            "'{}' is not a known element:\n1. If '{}' is an Angular component, then verify that it is included in the '@Component.imports' of this component.\n{}",
+            element, element, hint
+        ),
         code: TemplateDiagnosticCode::UnknownElement.code(),
         file: Some(file.to_string()),
         start: None,
@@ -61,7 +86,7 @@ pub fn create_unknown_element_diagnostic(file: &str, element: &str) -> TypeCheck
 /// Create a diagnostic for missing pipe.
 pub fn create_missing_pipe_diagnostic(file: &str, pipe_name: &str) -> TypeCheckError {
     TypeCheckError {
-        message: format!("The pipe '{}' could not be found", pipe_name),
+        message: format!("No pipe found with name '{}'.", pipe_name),
         code: TemplateDiagnosticCode::MissingPipe.code(),
         file: Some(file.to_string()),
         start: None,
@@ -88,7 +113,7 @@ pub fn create_missing_required_input_diagnostic(
 ) -> TypeCheckError {
     TypeCheckError {
         message: format!(
-            "Required input '{}' from directive '{}' must be specified",
+            "Required input '{}' from directive {} must be specified.",
             input, directive
         ),
         code: TemplateDiagnosticCode::MissingRequiredInput.code(),
@@ -97,3 +122,235 @@ pub fn create_missing_required_input_diagnostic(
         length: None,
     }
 }
+
+/// A diagnostic printed by `tsc --pretty false`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TscDiagnostic {
+    /// File the diagnostic is reported in, as printed by `tsc`.
+    pub file: String,
+    /// One-based line.
+    pub line: usize,
+    /// One-based column, in UTF-16 code units.
+    pub column: usize,
+    /// TypeScript error code.
+    pub code: u32,
+    /// Message, including the lines of chained messages.
+    pub message: String,
+}
+
+/// Parse the output of `tsc --pretty false`, which prints one
+/// `file(line,col): error TSnnnn: message` line per diagnostic, followed by indented
+/// lines for chained messages.
+pub fn parse_tsc_output(output: &str) -> Vec<TscDiagnostic> {
+    let mut diagnostics: Vec<TscDiagnostic> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with(' ') {
+            if let Some(last) = diagnostics.last_mut() {
+                last.message.push('\n');
+                last.message.push_str(line.trim_start());
+            }
+            continue;
+        }
+        if let Some(diagnostic) = parse_tsc_line(line) {
+            diagnostics.push(diagnostic);
+        }
+    }
+    diagnostics
+}
+
+/// Parse the `error TSnnnn: message` lines of the output of `tsc --pretty false`, which
+/// are not reported in a file, e.g. configuration errors.
+pub fn parse_tsc_global_errors(output: &str) -> Vec<TypeCheckError> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("error TS")?.split_once(": "))
+        .map(|(code, message)| TypeCheckError {
+            message: message.to_string(),
+            code: format!("TS{}", code),
+            file: None,
+            start: None,
+            length: None,
+        })
+        .collect()
+}
+
+fn parse_tsc_line(line: &str) -> Option<TscDiagnostic> {
+    let (location, rest) = line.split_once("): error TS")?;
+    let (file, position) = location.rsplit_once('(')?;
+    let (line_number, column) = position.split_once(',')?;
+    let (code, message) = rest.split_once(": ")?;
+    Some(TscDiagnostic {
+        file: file.to_string(),
+        line: line_number.parse().ok()?,
+        column: column.parse().ok()?,
+        code: code.parse().ok()?,
+        message: message.to_string(),
+    })
+}
+
+/// TypeScript errors which are artifacts of how type-check blocks are generated rather
+/// than mistakes in templates.
+const IGNORED_TSC_CODES: &[u32] = &[
+    // Declared but never used.
+    6133, 6192, 6196, 6198, 6199, 6205, // Left side of comma operator is unused.
+    2695, // Protected members are accessible from templates.
+    2445,
+];
+
+/// Translate a diagnostic `tsc` reported in a type-check shim to the template span it
+/// originates from. Diagnostics outside of mapped template expressions are dropped.
+pub fn translate_tsc_diagnostic(
+    diagnostic: &TscDiagnostic,
+    shim: &TypeCheckShim,
+) -> Option<TypeCheckError> {
+    if IGNORED_TSC_CODES.contains(&diagnostic.code) {
+        return None;
+    }
+    let offset = offset_in_shim(shim, diagnostic)?;
+    let block = shim.block_at(offset)?;
+    let (start, end) = block.template_position(offset)?;
+    Some(TypeCheckError {
+        message: diagnostic.message.clone(),
+        code: format!("TS{}", diagnostic.code),
+        file: Some(block.template_file.clone()),
+        start: Some(block.template_offset + start),
+        length: Some(end - start),
+    })
+}
+
+/// A diagnostic `tsc` reported in `file`, outside of the shims, whose content is
+/// `content`.
+pub fn translate_file_diagnostic(
+    diagnostic: &TscDiagnostic,
+    file: &str,
+    content: &str,
+) -> TypeCheckError {
+    TypeCheckError {
+        message: diagnostic.message.clone(),
+        code: format!("TS{}", diagnostic.code),
+        file: Some(file.to_string()),
+        start: offset_of_position(content, diagnostic.line, diagnostic.column),
+        length: None,
+    }
+}
+
+/// Byte offset in the shim at which a diagnostic is reported.
+pub fn offset_in_shim(shim: &TypeCheckShim, diagnostic: &TscDiagnostic) -> Option<usize> {
+    offset_of_position(&shim.code, diagnostic.line, diagnostic.column)
+}
+
+/// Byte offset of a one-based line and UTF-16 column in `code`.
+fn offset_of_position(code: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        code.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+    let mut units = 0;
+    for (index, c) in code[line_start..].char_indices() {
+        if units + 1 >= column || c == '\n' {
+            return Some(line_start + index);
+        }
+        units += c.len_utf16();
+    }
+    Some(code.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::api::{TypeCheckBlockMetadata, TypeCheckingConfig};
+    use super::super::context::TypeCheckingContext;
+    use super::*;
+    use angular_compiler::render3::view::template::{parse_template, ParseTemplateOptions};
+
+    fn shim(template: &str, template_offset: usize) -> TypeCheckShim {
+        let parsed = parse_template(template, "app.html", ParseTemplateOptions::default());
+        let mut context = TypeCheckingContext::new(TypeCheckingConfig::strict());
+        context.add_template(
+            "src/app.ts",
+            vec!["import { Foo } from './foo';".to_string()],
+            TypeCheckBlockMetadata {
+                component_name: "App".to_string(),
+                nodes: parsed.nodes,
+                directives: vec![],
+                pipes: Default::default(),
+                template_file: "src/app.html".to_string(),
+                template_offset,
+                schemas: vec![],
+                is_poisoned: false,
+                component_file: "src/app.ts".to_string(),
+                imports: vec![],
+            },
+        );
+        context.finalize().remove(0)
+    }
+
+    /// One-based line and column of the first occurrence of `needle` in `code`.
+    fn position_of(code: &str, needle: &str) -> (usize, usize) {
+        let offset = code.find(needle).unwrap();
+        let line_start = code[..offset].rfind('\n').map_or(0, |i| i + 1);
+        (
+            code[..offset].matches('\n').count() + 1,
+            code[line_start..offset].encode_utf16().count() + 1,
+        )
+    }
+
+    #[test]
+    fn test_parses_tsc_output() {
+        let output = "src/app.ngtypecheck.ts(7,14): error TS2322: Type 'number' is not assignable to type 'string'.\n  Details follow.\nsrc/other.ts(1,1): error TS1005: ';' expected.\n";
+        let diagnostics = parse_tsc_output(output);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file, "src/app.ngtypecheck.ts");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (7, 14));
+        assert_eq!(diagnostics[0].code, 2322);
+        assert_eq!(
+            diagnostics[0].message,
+            "Type 'number' is not assignable to type 'string'.\nDetails follow."
+        );
+        assert_eq!(diagnostics[1].code, 1005);
+    }
+
+    #[test]
+    fn test_translates_shim_diagnostics_to_the_template() {
+        let template = "<p>{{ user.nmae }}</p>";
+        let shim = shim(template, 0);
+        assert_eq!(shim.path, "src/app.ngtypecheck.ts");
+        assert!(shim.code.contains("import * as i1 from './app';"));
+        assert!(shim.code.contains("import { Foo } from './foo';"));
+        assert!(shim.code.contains("function _tcb1(this: i1.App) {"));
+        assert!(shim.code.ends_with("export const IS_A_MODULE = true;\n"));
+
+        let (line, column) = position_of(&shim.code, "nmae");
+        let diagnostic = TscDiagnostic {
+            file: shim.path.clone(),
+            line,
+            column,
+            code: 2339,
+            message: "Property 'nmae' does not exist on type 'User'.".to_string(),
+        };
+        let error = translate_tsc_diagnostic(&diagnostic, &shim).unwrap();
+        assert_eq!(error.code, "TS2339");
+        assert_eq!(error.file.as_deref(), Some("src/app.html"));
+        let start = error.start.unwrap();
+        assert_eq!(&template[start..start + error.length.unwrap()], "nmae");
+    }
+
+    #[test]
+    fn test_offsets_inline_templates_and_drops_unmapped_diagnostics() {
+        let shim = shim("{{ a }}", 100);
+        let (line, column) = position_of(&shim.code, "this.a");
+        let mut diagnostic = TscDiagnostic {
+            file: shim.path.clone(),
+            line,
+            column,
+            code: 2339,
+            message: String::new(),
+        };
+        let error = translate_tsc_diagnostic(&diagnostic, &shim).unwrap();
+        assert_eq!(error.start, Some(103));
+
+        diagnostic.line = 1;
+        diagnostic.column = 1;
+        assert!(translate_tsc_diagnostic(&diagnostic, &shim).is_none());
+    }
+}
@@ -0,0 +1,74 @@
+// TypeCheck DOM Schema Checking
+//
+// Checks the elements of a template, and the properties bound on them which no directive
+// claims, against the DOM schema. TypeScript cannot report these, as type-check blocks
+// create elements through `document.createElement` whatever their name.
+
+use super::type_check_block::OutOfBandDiagnosticRecorder;
+use angular_compiler::core::{custom_elements_schema, no_errors_schema, SchemaMetadata};
+use angular_compiler::schema::{DomElementSchemaRegistry, ElementSchemaRegistry};
+
+/// The schemas of a component, from the names listed in its `schemas`.
+pub fn schemas_from_names(names: &[String]) -> Vec<SchemaMetadata> {
+    names
+        .iter()
+        .filter_map(|name| match name.as_str() {
+            "CUSTOM_ELEMENTS_SCHEMA" => Some(custom_elements_schema()),
+            "NO_ERRORS_SCHEMA" => Some(no_errors_schema()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks elements and properties against the `DomElementSchemaRegistry`.
+pub struct RegistryDomSchemaChecker {
+    registry: DomElementSchemaRegistry,
+}
+
+impl RegistryDomSchemaChecker {
+    pub fn new() -> Self {
+        Self {
+            registry: DomElementSchemaRegistry::new(),
+        }
+    }
+
+    /// Check that an element is known to the schema, recording NG8001 otherwise.
+    pub fn check_element(
+        &self,
+        oob: &mut OutOfBandDiagnosticRecorder,
+        file: &str,
+        element: &str,
+        schemas: &[SchemaMetadata],
+        start: usize,
+        length: usize,
+    ) {
+        if !self.registry.has_element(element, schemas) {
+            oob.unknown_element(file, element, start, length);
+        }
+    }
+
+    /// Check that a property bound on an element is known to the schema, recording NG8002
+    /// otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_property(
+        &self,
+        oob: &mut OutOfBandDiagnosticRecorder,
+        file: &str,
+        element: &str,
+        property: &str,
+        schemas: &[SchemaMetadata],
+        start: usize,
+        length: usize,
+    ) {
+        let property = self.registry.get_mapped_prop_name(property);
+        if !self.registry.has_property(element, &property, schemas) {
+            oob.unknown_property(file, element, &property, start, length);
+        }
+    }
+}
+
+impl Default for RegistryDomSchemaChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,386 @@
+// TypeCheck Expression Translation
+//
+// Translates template expressions into TypeScript for type-check blocks, keeping
+// track of which generated code corresponds to which part of the template.
+
+use angular_compiler::expression_parser::ast::{
+    AbsoluteSourceSpan, BindingPipe, LiteralPrimitive, TemplateLiteral, AST,
+};
+
+/// A range of generated code which corresponds to a range of the template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcbSourceMapping {
+    /// Start offset in the generated code.
+    pub tcb_start: usize,
+    /// End offset in the generated code.
+    pub tcb_end: usize,
+    /// Start offset in the template.
+    pub template_start: usize,
+    /// End offset in the template.
+    pub template_end: usize,
+}
+
+/// Generated code together with its mappings back to the template.
+#[derive(Debug, Default)]
+pub struct TcbWriter {
+    /// The generated code.
+    pub code: String,
+    /// Mappings recorded while generating.
+    pub mappings: Vec<TcbSourceMapping>,
+    /// Whether mappings are recorded. Guards replicated into event listeners are emitted
+    /// without mappings so that each template expression is reported once.
+    pub record_mappings: bool,
+}
+
+impl TcbWriter {
+    pub fn new() -> Self {
+        Self {
+            code: String::new(),
+            mappings: Vec::new(),
+            record_mappings: true,
+        }
+    }
+
+    /// Current offset in the generated code.
+    pub fn offset(&self) -> usize {
+        self.code.len()
+    }
+
+    /// Append code.
+    pub fn write(&mut self, code: &str) {
+        self.code.push_str(code);
+    }
+
+    /// Map the code generated since `tcb_start` to a template span.
+    pub fn map(&mut self, tcb_start: usize, template_start: usize, template_end: usize) {
+        if self.record_mappings && template_end >= template_start {
+            self.mappings.push(TcbSourceMapping {
+                tcb_start,
+                tcb_end: self.code.len(),
+                template_start,
+                template_end,
+            });
+        }
+    }
+}
+
+/// The environment in which template expressions are translated.
+pub trait TcbExpressionScope {
+    /// TypeScript expression for a template variable, reference or `@let` declaration
+    /// visible under `name`, or `None` if the name refers to the component.
+    fn resolve(&self, name: &str) -> Option<String>;
+
+    /// TypeScript expression for the instance of the pipe used by `pipe`, or `None` if
+    /// the pipe is not in scope of the template.
+    fn pipe_instance(&mut self, pipe: &BindingPipe) -> Option<String>;
+
+    /// Whether pipe calls are checked against the signature of `transform`.
+    fn check_pipes(&self) -> bool;
+}
+
+/// Translate a template expression to TypeScript, writing it to `out`.
+pub fn translate_expression(ast: &AST, scope: &mut dyn TcbExpressionScope, out: &mut TcbWriter) {
+    let start = out.offset();
+    match ast {
+        AST::EmptyExpr(_) => out.write("undefined"),
+        AST::ImplicitReceiver(_) | AST::ThisReceiver(_) => out.write("this"),
+        AST::Chain(chain) => {
+            out.write("(");
+            for (i, expr) in chain.expressions.iter().enumerate() {
+                if i > 0 {
+                    out.write(", ");
+                }
+                translate_expression(expr, scope, out);
+            }
+            out.write(")");
+        }
+        AST::Conditional(cond) => {
+            translate_operand(&cond.condition, scope, out);
+            out.write(" ? ");
+            translate_operand(&cond.true_exp, scope, out);
+            out.write(" : ");
+            translate_operand(&cond.false_exp, scope, out);
+        }
+        AST::PropertyRead(read) => match (&*read.receiver, scope.resolve(&read.name)) {
+            (AST::ImplicitReceiver(_), Some(local)) => out.write(&local),
+            (AST::ImplicitReceiver(_) | AST::ThisReceiver(_), _) => {
+                out.write("this.");
+                write_name(&read.name, read.name_span, out);
+            }
+            (receiver, _) => {
+                translate_receiver(receiver, scope, out);
+                out.write(".");
+                write_name(&read.name, read.name_span, out);
+            }
+        },
+        AST::SafePropertyRead(read) => {
+            translate_receiver(&read.receiver, scope, out);
+            out.write("?.");
+            write_name(&read.name, read.name_span, out);
+        }
+        AST::KeyedRead(read) => {
+            translate_receiver(&read.receiver, scope, out);
+            out.write("[");
+            translate_expression(&read.key, scope, out);
+            out.write("]");
+        }
+        AST::SafeKeyedRead(read) => {
+            translate_receiver(&read.receiver, scope, out);
+            out.write("?.[");
+            translate_expression(&read.key, scope, out);
+            out.write("]");
+        }
+        AST::PropertyWrite(write) => {
+            match (&*write.receiver, scope.resolve(&write.name)) {
+                (AST::ImplicitReceiver(_), Some(local)) => out.write(&local),
+                (AST::ImplicitReceiver(_) | AST::ThisReceiver(_), _) => {
+                    out.write("this.");
+                    out.write(&write.name);
+                }
+                (receiver, _) => {
+                    translate_receiver(receiver, scope, out);
+                    out.write(".");
+                    out.write(&write.name);
+                }
+            }
+            out.write(" = ");
+            translate_operand(&write.value, scope, out);
+        }
+        AST::KeyedWrite(write) => {
+            translate_receiver(&write.receiver, scope, out);
+            out.write("[");
+            translate_expression(&write.key, scope, out);
+            out.write("] = ");
+            translate_operand(&write.value, scope, out);
+        }
+        AST::BindingPipe(pipe) => match scope.pipe_instance(pipe) {
+            Some(instance) => {
+                if scope.check_pipes() {
+                    out.write(&instance);
+                    out.write(".transform(");
+                } else {
+                    out.write("(");
+                    out.write(&instance);
+                    out.write(".transform as any)(");
+                }
+                translate_expression(&pipe.exp, scope, out);
+                for arg in &pipe.args {
+                    out.write(", ");
+                    translate_expression(arg, scope, out);
+                }
+                out.write(")");
+            }
+            None => out.write("(null as any)"),
+        },
+        AST::LiteralPrimitive(literal) => match literal {
+            LiteralPrimitive::String { value, .. } => out.write(&quote_string(value)),
+            LiteralPrimitive::Number { value, .. } => out.write(&format_number(*value)),
+            LiteralPrimitive::Boolean { value, .. } => out.write(&value.to_string()),
+            LiteralPrimitive::Null { .. } => out.write("null"),
+            LiteralPrimitive::Undefined { .. } => out.write("undefined"),
+        },
+        AST::LiteralArray(array) => {
+            out.write("[");
+            for (i, expr) in array.expressions.iter().enumerate() {
+                if i > 0 {
+                    out.write(", ");
+                }
+                translate_expression(expr, scope, out);
+            }
+            out.write("]");
+        }
+        AST::LiteralMap(map) => {
+            out.write("{ ");
+            for (i, (key, value)) in map.keys.iter().zip(&map.values).enumerate() {
+                if i > 0 {
+                    out.write(", ");
+                }
+                out.write(&quote_string(&key.key));
+                out.write(": ");
+                translate_expression(value, scope, out);
+            }
+            out.write(" }");
+        }
+        AST::Interpolation(interpolation) => {
+            out.write("\"\"");
+            for expr in &interpolation.expressions {
+                out.write(" + ");
+                translate_operand(expr, scope, out);
+            }
+        }
+        AST::Binary(binary) => {
+            translate_operand(&binary.left, scope, out);
+            out.write(" ");
+            out.write(&binary.operation);
+            out.write(" ");
+            translate_operand(&binary.right, scope, out);
+        }
+        AST::PrefixNot(not) => {
+            out.write("!");
+            translate_operand(&not.expression, scope, out);
+        }
+        AST::Unary(unary) => {
+            out.write(&unary.operator);
+            translate_operand(&unary.expr, scope, out);
+        }
+        AST::TypeofExpression(expr) => {
+            out.write("typeof ");
+            translate_operand(&expr.expression, scope, out);
+        }
+        AST::VoidExpression(expr) => {
+            out.write("void ");
+            translate_operand(&expr.expression, scope, out);
+        }
+        AST::NonNullAssert(assert) => {
+            translate_receiver(&assert.expression, scope, out);
+            out.write("!");
+        }
+        AST::Call(call) => {
+            translate_receiver(&call.receiver, scope, out);
+            translate_arguments(&call.args, scope, out);
+        }
+        AST::SafeCall(call) => {
+            translate_receiver(&call.receiver, scope, out);
+            out.write("?.");
+            translate_arguments(&call.args, scope, out);
+        }
+        AST::TemplateLiteral(template) => translate_template_literal(template, scope, out),
+        AST::TaggedTemplateLiteral(tagged) => {
+            translate_receiver(&tagged.tag, scope, out);
+            translate_template_literal(&tagged.template, scope, out);
+        }
+        AST::ParenthesizedExpression(paren) => {
+            out.write("(");
+            translate_expression(&paren.expression, scope, out);
+            out.write(")");
+        }
+        AST::RegularExpressionLiteral(regex) => {
+            out.write("/");
+            out.write(&regex.body);
+            out.write("/");
+            out.write(regex.flags.as_deref().unwrap_or(""));
+        }
+    }
+    let span = ast.source_span();
+    out.map(start, span.start, span.end);
+}
+
+/// Translate an expression which is used as an operand, parenthesizing it when needed.
+fn translate_operand(ast: &AST, scope: &mut dyn TcbExpressionScope, out: &mut TcbWriter) {
+    if is_primary(ast) {
+        translate_expression(ast, scope, out);
+    } else {
+        out.write("(");
+        translate_expression(ast, scope, out);
+        out.write(")");
+    }
+}
+
+/// Translate an expression which is the receiver of a member access or call.
+fn translate_receiver(ast: &AST, scope: &mut dyn TcbExpressionScope, out: &mut TcbWriter) {
+    if is_primary(ast) && !matches!(ast, AST::LiteralMap(_) | AST::LiteralPrimitive(_)) {
+        translate_expression(ast, scope, out);
+    } else {
+        out.write("(");
+        translate_expression(ast, scope, out);
+        out.write(")");
+    }
+}
+
+fn translate_arguments(args: &[Box<AST>], scope: &mut dyn TcbExpressionScope, out: &mut TcbWriter) {
+    out.write("(");
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            out.write(", ");
+        }
+        translate_expression(arg, scope, out);
+    }
+    out.write(")");
+}
+
+fn translate_template_literal(
+    template: &TemplateLiteral,
+    scope: &mut dyn TcbExpressionScope,
+    out: &mut TcbWriter,
+) {
+    out.write("`");
+    for (i, element) in template.elements.iter().enumerate() {
+        out.write(
+            &element
+                .text
+                .replace('\\', "\\\\")
+                .replace('`', "\\`")
+                .replace("${", "\\${"),
+        );
+        if let Some(expr) = template.expressions.get(i) {
+            out.write("${");
+            translate_expression(expr, scope, out);
+            out.write("}");
+        }
+    }
+    out.write("`");
+}
+
+/// Write a member name, mapped to its span in the template so that errors about
+/// missing members point at the name rather than at the whole expression.
+fn write_name(name: &str, span: AbsoluteSourceSpan, out: &mut TcbWriter) {
+    let start = out.offset();
+    out.write(name);
+    out.map(start, span.start, span.end);
+}
+
+/// Whether an expression can be used as an operand without parentheses.
+fn is_primary(ast: &AST) -> bool {
+    matches!(
+        ast,
+        AST::EmptyExpr(_)
+            | AST::ImplicitReceiver(_)
+            | AST::ThisReceiver(_)
+            | AST::PropertyRead(_)
+            | AST::SafePropertyRead(_)
+            | AST::KeyedRead(_)
+            | AST::SafeKeyedRead(_)
+            | AST::LiteralPrimitive(_)
+            | AST::LiteralArray(_)
+            | AST::LiteralMap(_)
+            | AST::Call(_)
+            | AST::SafeCall(_)
+            | AST::NonNullAssert(_)
+            | AST::ParenthesizedExpression(_)
+            | AST::TemplateLiteral(_)
+            | AST::Chain(_)
+    ) && !matches!(ast, AST::LiteralPrimitive(LiteralPrimitive::Number { value, .. }) if *value < 0.0)
+}
+
+/// Quote a string as a TypeScript string literal.
+pub fn quote_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{2028}' => quoted.push_str("\\u2028"),
+            '\u{2029}' => quoted.push_str("\\u2029"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn format_number(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
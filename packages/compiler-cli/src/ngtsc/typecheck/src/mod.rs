@@ -3,14 +3,24 @@
 pub mod checker;
 pub mod context;
 pub mod diagnostics;
+pub mod dom;
+pub mod expression;
+pub mod shim;
 pub mod type_check_block;
 
 // Re-exports
-pub use checker::TemplateTypeCheckerImpl;
+pub use checker::{find_tsc, TemplateTypeCheckerImpl};
 pub use context::{TypeCheckEnvironment, TypeCheckingContext};
 pub use diagnostics::{
     create_missing_pipe_diagnostic, create_missing_required_input_diagnostic,
     create_type_mismatch_diagnostic, create_unknown_element_diagnostic,
-    create_unknown_property_diagnostic, TemplateDiagnosticCode,
+    create_unknown_property_diagnostic, parse_tsc_global_errors, parse_tsc_output,
+    translate_file_diagnostic, translate_tsc_diagnostic,
+    TemplateDiagnosticCode, TscDiagnostic,
+};
+pub use dom::{schemas_from_names, RegistryDomSchemaChecker};
+pub use expression::{translate_expression, TcbSourceMapping, TcbWriter};
+pub use shim::{is_type_check_shim, type_check_shim_path, ShimBlock, TypeCheckShim};
+pub use type_check_block::{
+    bind_template, OutOfBandDiagnosticRecorder, TypeCheckBlock, TypeCheckBlockGenerator,
 };
-pub use type_check_block::{OutOfBandDiagnosticRecorder, TypeCheckBlockGenerator};
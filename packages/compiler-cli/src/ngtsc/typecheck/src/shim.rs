@@ -0,0 +1,89 @@
+// TypeCheck Shims
+//
+// Type-check blocks are written to `.ngtypecheck.ts` shim files named after the
+// component's source file. The shims import relative to the source file, and are
+// checked with `rootDirs` spanning the source directory, so that they resolve imports
+// exactly like the component.
+
+use super::super::api::TypeCheckError;
+use super::expression::TcbSourceMapping;
+use std::path::Path;
+
+/// Extension of type-check shim files, replacing `.ts`.
+pub const TYPE_CHECK_SHIM_EXTENSION: &str = ".ngtypecheck.ts";
+
+/// Path of the type-check shim of a source file: `foo.ts` becomes `foo.ngtypecheck.ts`.
+pub fn type_check_shim_path(source_file: &str) -> String {
+    let stem = source_file.strip_suffix(".ts").unwrap_or(source_file);
+    format!("{}{}", stem, TYPE_CHECK_SHIM_EXTENSION)
+}
+
+/// Whether a file is a type-check shim.
+pub fn is_type_check_shim(file: &str) -> bool {
+    file.ends_with(TYPE_CHECK_SHIM_EXTENSION)
+}
+
+/// Module specifier under which a shim imports its source file.
+pub fn shim_module_specifier(source_file: &str) -> String {
+    let file_name = Path::new(source_file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("./{}", file_name.strip_suffix(".ts").unwrap_or(&file_name))
+}
+
+/// A type-check block placed in a shim file.
+#[derive(Debug, Clone)]
+pub struct ShimBlock {
+    /// Name of the component the block checks.
+    pub component_name: String,
+    /// Start offset of the block in the shim.
+    pub start: usize,
+    /// End offset of the block in the shim.
+    pub end: usize,
+    /// Mappings from ranges of the shim to template spans.
+    pub mappings: Vec<TcbSourceMapping>,
+    /// File diagnostics within the block are reported against.
+    pub template_file: String,
+    /// Offset of the template within `template_file`.
+    pub template_offset: usize,
+}
+
+impl ShimBlock {
+    /// The template offset which a position in the shim corresponds to, found through the
+    /// innermost mapping containing the position.
+    pub fn template_position(&self, shim_offset: usize) -> Option<(usize, usize)> {
+        if shim_offset < self.start || shim_offset >= self.end {
+            return None;
+        }
+        self.mappings
+            .iter()
+            .filter(|m| m.tcb_start <= shim_offset && shim_offset < m.tcb_end)
+            .min_by_key(|m| m.tcb_end - m.tcb_start)
+            .map(|m| (m.template_start, m.template_end))
+    }
+}
+
+/// A generated `.ngtypecheck.ts` file.
+#[derive(Debug, Clone)]
+pub struct TypeCheckShim {
+    /// Path of the shim file.
+    pub path: String,
+    /// Path of the source file the shim checks.
+    pub source_file: String,
+    /// Contents of the shim.
+    pub code: String,
+    /// The type-check blocks within the shim.
+    pub blocks: Vec<ShimBlock>,
+    /// Diagnostics found while generating the blocks.
+    pub diagnostics: Vec<TypeCheckError>,
+}
+
+impl TypeCheckShim {
+    /// The block containing a position in the shim.
+    pub fn block_at(&self, shim_offset: usize) -> Option<&ShimBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.start <= shim_offset && shim_offset < block.end)
+    }
+}
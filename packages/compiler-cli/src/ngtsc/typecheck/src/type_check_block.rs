@@ -1,84 +1,1049 @@
 // TypeCheck Block Generation
 //
-// Generates type-check blocks for templates.
+// Generates type-check blocks (TCBs) for component templates. A TCB is a TypeScript
+// function whose body mirrors the structure of the template: elements and directives
+// become variables, bindings become assignments to those variables and embedded views
+// become nested blocks guarded by the conditions under which they are rendered.
+// Type errors TypeScript reports within the TCB are mapped back to the template.
 
-use super::super::api::{TypeCheckError, TypeCheckingConfig};
-use std::fmt::Write;
+use super::super::api::{
+    TemplateGuardKind, TypeCheckBlockMetadata, TypeCheckError, TypeCheckableDirectiveMeta,
+    TypeCheckingConfig,
+};
+use super::diagnostics::{
+    create_missing_pipe_diagnostic, create_missing_required_input_diagnostic,
+    create_unknown_element_diagnostic, create_unknown_property_diagnostic,
+};
+use super::dom::{schemas_from_names, RegistryDomSchemaChecker};
+use super::expression::{
+    quote_string, translate_expression, TcbExpressionScope, TcbSourceMapping, TcbWriter,
+};
+use angular_compiler::core::SchemaMetadata;
+use angular_compiler::directive_matching::{CssSelector, SelectorMatcher};
+use angular_compiler::expression_parser::ast::{BindingPipe, BindingType, ParsedEventType, AST};
+use angular_compiler::parse_util::ParseSourceSpan;
+use angular_compiler::render3::r3_ast::{
+    BoundAttribute, BoundEvent, DeferredBlock, Element, ForLoopBlock, IfBlockBranch, R3Node,
+    Reference, SwitchBlock, Template, TemplateAttr,
+};
+use angular_compiler::render3::view::t2_api::{BoundTarget, DirectiveOwner, Target, TargetBinder};
+use angular_compiler::render3::view::t2_binder::{DirectiveMatcher, R3TargetBinder};
+use std::collections::HashMap;
+
+/// A generated type-check block.
+#[derive(Debug, Clone)]
+pub struct TypeCheckBlock {
+    /// TypeScript source of the block.
+    pub code: String,
+    /// Mappings from ranges of `code` to template spans.
+    pub mappings: Vec<TcbSourceMapping>,
+    /// Diagnostics found while generating the block, which TypeScript cannot report.
+    pub diagnostics: Vec<TypeCheckError>,
+}
 
 /// Generates a type-check block (TCB) for a component template.
 pub struct TypeCheckBlockGenerator {
     /// Configuration.
     config: TypeCheckingConfig,
-    /// Output buffer.
-    output: String,
-    /// Indentation level.
-    indent: usize,
+    /// Checks elements and properties no directive claims.
+    dom_schema_checker: RegistryDomSchemaChecker,
 }
 
 impl TypeCheckBlockGenerator {
     pub fn new(config: TypeCheckingConfig) -> Self {
         Self {
             config,
-            output: String::new(),
-            indent: 0,
+            dom_schema_checker: RegistryDomSchemaChecker::new(),
         }
     }
 
-    /// Generate TCB for a component.
+    /// Generate the TCB function `name` for a component, whose class is referenced from
+    /// the type-check file as `component_ref`.
     pub fn generate(
-        &mut self,
-        component_name: &str,
-        template: &str,
-    ) -> Result<String, TypeCheckError> {
-        self.output.clear();
+        &self,
+        name: &str,
+        component_ref: &str,
+        meta: &TypeCheckBlockMetadata,
+    ) -> TypeCheckBlock {
+        let bound = bind_template(meta);
+
+        let mut tcb = Tcb {
+            config: &self.config,
+            bound: bound.as_ref(),
+            out: TcbWriter::new(),
+            env: TcbEnvironment {
+                scopes: vec![HashMap::new()],
+                pipes: &meta.pipes,
+                pipe_instances: HashMap::new(),
+                pipe_declarations: Vec::new(),
+                check_pipes: self.config.check_type_of_pipes,
+                oob: OutOfBandDiagnosticRecorder::new(),
+                check_scope: !meta.is_poisoned,
+                template_file: &meta.template_file,
+                template_offset: meta.template_offset,
+            },
+            dom_schema_checker: &self.dom_schema_checker,
+            schemas: schemas_from_names(&meta.schemas),
+            next_id: 1,
+            indent: 1,
+            guards: Vec::new(),
+            elements: HashMap::new(),
+            directives: HashMap::new(),
+        };
+        tcb.view(&meta.nodes);
 
-        // Generate function signature
-        self.write_line(&format!(
-            "function _tcb_{}(ctx: {}) {{",
-            component_name, component_name
+        let header = format!("function {}(this: {}) {{\n", name, component_ref);
+        let mut prefix = header;
+        for declaration in &tcb.env.pipe_declarations {
+            prefix.push_str("  ");
+            prefix.push_str(declaration);
+            prefix.push('\n');
+        }
+        let shift = prefix.len();
+        let mut code = prefix;
+        code.push_str(&tcb.out.code);
+        code.push_str("}\n");
+
+        TypeCheckBlock {
+            code,
+            mappings: tcb
+                .out
+                .mappings
+                .into_iter()
+                .map(|m| TcbSourceMapping {
+                    tcb_start: m.tcb_start + shift,
+                    tcb_end: m.tcb_end + shift,
+                    ..m
+                })
+                .collect(),
+            diagnostics: tcb.env.oob.diagnostics().to_vec(),
+        }
+    }
+}
+
+/// Bind a component template against the directives in its scope.
+pub fn bind_template(
+    meta: &TypeCheckBlockMetadata,
+) -> Box<dyn BoundTarget<TypeCheckableDirectiveMeta>> {
+    let mut matcher = SelectorMatcher::<Vec<TypeCheckableDirectiveMeta>>::new();
+    for directive in &meta.directives {
+        let Some(selector) = &directive.selector else {
+            continue;
+        };
+        for css_selector in CssSelector::parse(selector).unwrap_or_default() {
+            matcher.add_selectable(css_selector, vec![directive.clone()]);
+        }
+    }
+    R3TargetBinder::new(Some(DirectiveMatcher::Selector(matcher))).bind(Target {
+        template: Some(meta.nodes.clone()),
+        host: None,
+    })
+}
+
+/// The names visible to template expressions, and the pipes they can use.
+struct TcbEnvironment<'a> {
+    /// Template variables, references and `@let` declarations, innermost view last.
+    scopes: Vec<HashMap<String, String>>,
+    /// Pipes in scope of the template.
+    pipes: &'a HashMap<String, String>,
+    /// Variables holding pipe instances, by pipe name.
+    pipe_instances: HashMap<String, String>,
+    /// Declarations of the pipe instances, hoisted to the top of the block.
+    pipe_declarations: Vec<String>,
+    check_pipes: bool,
+    oob: OutOfBandDiagnosticRecorder,
+    /// Whether all directives and pipes in scope are known, so that names missing from
+    /// the scope can be reported.
+    check_scope: bool,
+    template_file: &'a str,
+    template_offset: usize,
+}
+
+impl TcbExpressionScope for TcbEnvironment<'_> {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn pipe_instance(&mut self, pipe: &BindingPipe) -> Option<String> {
+        if let Some(instance) = self.pipe_instances.get(&pipe.name) {
+            return Some(instance.clone());
+        }
+        let Some(reference) = self.pipes.get(&pipe.name) else {
+            if self.check_scope {
+                self.oob.missing_pipe(
+                    self.template_file,
+                    &pipe.name,
+                    self.template_offset + pipe.name_span.start,
+                    pipe.name_span.end.saturating_sub(pipe.name_span.start),
+                );
+            }
+            return None;
+        };
+        let instance = format!("_pipe{}", self.pipe_instances.len() + 1);
+        self.pipe_declarations.push(format!(
+            "var {}: InstanceType<typeof {}> = null!;",
+            instance, reference
         ));
+        self.pipe_instances
+            .insert(pipe.name.clone(), instance.clone());
+        Some(instance)
+    }
+
+    fn check_pipes(&self) -> bool {
+        self.check_pipes
+    }
+}
+
+/// Whether a node owning directives is an element or a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum OwnerKind {
+    Element,
+    Template,
+}
+
+/// State of the generation of a single TCB.
+struct Tcb<'a> {
+    config: &'a TypeCheckingConfig,
+    bound: &'a dyn BoundTarget<TypeCheckableDirectiveMeta>,
+    out: TcbWriter,
+    env: TcbEnvironment<'a>,
+    dom_schema_checker: &'a RegistryDomSchemaChecker,
+    schemas: Vec<SchemaMetadata>,
+    next_id: usize,
+    indent: usize,
+    /// Conditions under which the current view is rendered, replicated into event
+    /// listeners since TypeScript does not carry narrowing into callbacks.
+    guards: Vec<String>,
+    /// Variables holding elements, by the offset of the element.
+    elements: HashMap<usize, String>,
+    /// Variables holding the directives matched on a node, by the offset of the node.
+    directives: HashMap<(OwnerKind, usize), Vec<(TypeCheckableDirectiveMeta, String)>>,
+}
+
+impl Tcb<'_> {
+    fn allocate_id(&mut self) -> String {
+        let id = format!("_t{}", self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn line(&mut self, code: &str) {
+        self.start_line();
+        self.out.write(code);
+        self.out.write("\n");
+    }
+
+    fn start_line(&mut self) {
+        self.out.write(&"  ".repeat(self.indent));
+    }
+
+    fn open_block(&mut self, head: &str) {
+        self.line(&format!("{}{{", head));
         self.indent += 1;
+    }
+
+    fn close_block(&mut self) {
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    /// Generate the code of a view, in a scope of its own.
+    fn view(&mut self, nodes: &[R3Node]) {
+        self.env.scopes.push(HashMap::new());
+        self.view_in_current_scope(nodes);
+        self.env.scopes.pop();
+    }
 
-        // For now, generate placeholder
-        self.write_line("// Template type-check block");
-        self.write_line(&format!("// Template: {}", template.replace('\n', " ")));
+    /// Declarations come first, so that references can be used anywhere in their view.
+    fn view_in_current_scope(&mut self, nodes: &[R3Node]) {
+        self.declare_nodes(nodes);
+        self.check_nodes(nodes);
+    }
+
+    fn scope(&mut self) -> &mut HashMap<String, String> {
+        self.env
+            .scopes
+            .last_mut()
+            .expect("TCB scope stack is never empty")
+    }
+
+    /// Declare the elements, directives, references and `@let` declarations of a view.
+    fn declare_nodes(&mut self, nodes: &[R3Node]) {
+        for node in nodes {
+            match node {
+                R3Node::Element(element) => {
+                    let id = self.allocate_id();
+                    self.line(&format!(
+                        "var {} = document.createElement({});",
+                        id,
+                        quote_string(&element.name)
+                    ));
+                    let offset = element.source_span.start.offset;
+                    self.elements.insert(offset, id.clone());
+                    let directives = self
+                        .bound
+                        .get_directives_of_node(&DirectiveOwner::Element((**element).clone()))
+                        .unwrap_or_default();
+                    self.declare_directives(OwnerKind::Element, offset, directives);
+                    self.declare_references(
+                        &element.references,
+                        OwnerKind::Element,
+                        offset,
+                        Some(id),
+                    );
+                    self.declare_nodes(&element.children);
+                }
+                R3Node::Component(component) => {
+                    let id = self.allocate_id();
+                    let tag = component.tag_name.as_deref().unwrap_or("ng-component");
+                    self.line(&format!(
+                        "var {} = document.createElement({});",
+                        id,
+                        quote_string(tag)
+                    ));
+                    let offset = component.source_span.start.offset;
+                    self.elements.insert(offset, id.clone());
+                    let directives = self
+                        .bound
+                        .get_directives_of_node(&DirectiveOwner::Component((**component).clone()))
+                        .unwrap_or_default();
+                    self.declare_directives(OwnerKind::Element, offset, directives);
+                    self.declare_references(
+                        &component.references,
+                        OwnerKind::Element,
+                        offset,
+                        Some(id),
+                    );
+                    self.declare_nodes(&component.children);
+                }
+                R3Node::Template(template) => {
+                    let offset = template.source_span.start.offset;
+                    let directives = self
+                        .bound
+                        .get_directives_of_node(&DirectiveOwner::Template((**template).clone()))
+                        .unwrap_or_default();
+                    self.declare_directives(OwnerKind::Template, offset, directives);
+                    self.declare_references(
+                        &template.references,
+                        OwnerKind::Template,
+                        offset,
+                        None,
+                    );
+                }
+                R3Node::Content(content) => self.declare_nodes(&content.children),
+                R3Node::LetDeclaration(decl) => {
+                    let id = self.allocate_id();
+                    self.scope().insert(decl.name.to_string(), id);
+                }
+                _ => {}
+            }
+        }
+    }
 
-        // Close function
+    fn declare_directives(
+        &mut self,
+        kind: OwnerKind,
+        offset: usize,
+        directives: Vec<TypeCheckableDirectiveMeta>,
+    ) {
+        let mut vars = Vec::with_capacity(directives.len());
+        for directive in directives {
+            let id = self.allocate_id();
+            self.line(&format!(
+                "var {}: InstanceType<typeof {}> = null!;",
+                id, directive.reference
+            ));
+            vars.push((directive, id));
+        }
+        self.directives.insert((kind, offset), vars);
+    }
+
+    fn declare_references(
+        &mut self,
+        references: &[Reference],
+        kind: OwnerKind,
+        offset: usize,
+        element: Option<String>,
+    ) {
+        for reference in references {
+            let directives = self.directives.get(&(kind, offset));
+            let target = if reference.value.is_empty() {
+                directives
+                    .and_then(|dirs| dirs.iter().find(|(dir, _)| dir.is_component))
+                    .map(|(_, id)| id.clone())
+            } else {
+                directives
+                    .and_then(|dirs| {
+                        dirs.iter().find(|(dir, _)| {
+                            dir.export_as
+                                .as_ref()
+                                .is_some_and(|names| names.iter().any(|n| **n == *reference.value))
+                        })
+                    })
+                    .map(|(_, id)| id.clone())
+            };
+            let value = match (target, &element) {
+                (Some(directive), _) => directive,
+                (None, _) if !reference.value.is_empty() => {
+                    let span = reference
+                        .value_span
+                        .as_ref()
+                        .unwrap_or(&reference.source_span);
+                    if self.env.check_scope {
+                        self.env.oob.missing_reference_target(
+                            self.env.template_file,
+                            &reference.value,
+                            self.env.template_offset + span.start.offset,
+                            span.end.offset.saturating_sub(span.start.offset),
+                        );
+                    }
+                    let id = self.allocate_id();
+                    self.line(&format!("var {}: any = null!;", id));
+                    id
+                }
+                (None, Some(element)) if self.config.check_type_of_dom_references => {
+                    element.clone()
+                }
+                (None, Some(_)) => {
+                    let id = self.allocate_id();
+                    self.line(&format!("var {}: any = null!;", id));
+                    id
+                }
+                (None, None) => {
+                    let id = self.allocate_id();
+                    self.line(&format!("var {} = null! as i0.TemplateRef<any>;", id));
+                    id
+                }
+            };
+            self.scope().insert(reference.name.to_string(), value);
+        }
+    }
+
+    /// Check the bindings and nested views of a view.
+    fn check_nodes(&mut self, nodes: &[R3Node]) {
+        for node in nodes {
+            match node {
+                R3Node::Element(element) => {
+                    let offset = element.source_span.start.offset;
+                    self.check_dom_schema(element);
+                    let bound = element
+                        .inputs
+                        .iter()
+                        .filter(|input| is_property_binding(input))
+                        .map(|input| input.name.to_string())
+                        .chain(element.attributes.iter().map(|attr| attr.name.to_string()))
+                        .collect();
+                    self.check_required_inputs(
+                        OwnerKind::Element,
+                        offset,
+                        &element.start_source_span,
+                        bound,
+                    );
+                    self.check_bindings(
+                        OwnerKind::Element,
+                        offset,
+                        &element.inputs,
+                        &element.outputs,
+                    );
+                    self.check_nodes(&element.children);
+                }
+                R3Node::Component(component) => {
+                    let offset = component.source_span.start.offset;
+                    self.check_bindings(
+                        OwnerKind::Element,
+                        offset,
+                        &component.inputs,
+                        &component.outputs,
+                    );
+                    self.check_nodes(&component.children);
+                }
+                R3Node::Template(template) => self.check_template(template),
+                R3Node::Content(content) => self.check_nodes(&content.children),
+                R3Node::BoundText(text) => self.expression_statement(&text.value),
+                R3Node::Icu(icu) => {
+                    for var in icu.vars.values() {
+                        self.expression_statement(&var.value);
+                    }
+                    for placeholder in icu.placeholders.values() {
+                        if let angular_compiler::render3::r3_ast::IcuPlaceholder::BoundText(text) =
+                            placeholder
+                        {
+                            self.expression_statement(&text.value);
+                        }
+                    }
+                }
+                R3Node::IfBlock(block) => self.check_if_branches(&block.branches),
+                R3Node::SwitchBlock(block) => self.check_switch(block),
+                R3Node::ForLoopBlock(block) => self.check_for_loop(block),
+                R3Node::DeferredBlock(block) => self.check_defer(block),
+                R3Node::LetDeclaration(decl) => {
+                    let id = self
+                        .env
+                        .resolve(&decl.name)
+                        .expect("@let declarations are declared with their view");
+                    self.start_line();
+                    self.out.write(&format!("const {} = ", id));
+                    self.translate(&decl.value);
+                    self.out.write(";\n");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn translate(&mut self, ast: &AST) {
+        self.out.write("(");
+        translate_expression(ast, &mut self.env, &mut self.out);
+        self.out.write(")");
+    }
+
+    /// Translate an expression without recording mappings, for code that replicates an
+    /// expression which is already checked elsewhere.
+    fn translate_unmapped(&mut self, ast: &AST) -> String {
+        let mut out = TcbWriter::new();
+        out.record_mappings = false;
+        out.write("(");
+        translate_expression(ast, &mut self.env, &mut out);
+        out.write(")");
+        out.code
+    }
+
+    fn expression_statement(&mut self, ast: &AST) {
+        self.start_line();
+        self.translate(ast);
+        self.out.write(";\n");
+    }
+
+    fn check_bindings(
+        &mut self,
+        kind: OwnerKind,
+        offset: usize,
+        inputs: &[BoundAttribute],
+        outputs: &[BoundEvent],
+    ) {
+        let directives = self
+            .directives
+            .get(&(kind, offset))
+            .cloned()
+            .unwrap_or_default();
+        for input in inputs {
+            self.check_input(kind, offset, input, &directives);
+        }
+        for output in outputs {
+            self.check_output(kind, offset, output, &directives);
+        }
+    }
+
+    /// Check an element and the properties bound on it against the DOM schema. Elements
+    /// matched by a component and properties claimed by a directive are not checked.
+    fn check_dom_schema(&mut self, element: &Element) {
+        if !self.env.check_scope {
+            return;
+        }
+        let directives = self
+            .directives
+            .get(&(OwnerKind::Element, element.source_span.start.offset))
+            .cloned()
+            .unwrap_or_default();
+        let file = self.env.template_file;
+        let base = self.env.template_offset;
+
+        if !directives.iter().any(|(dir, _)| dir.is_component) {
+            let span = &element.start_source_span;
+            self.dom_schema_checker.check_element(
+                &mut self.env.oob,
+                file,
+                &element.name,
+                &self.schemas,
+                base + span.start.offset,
+                span_length(span),
+            );
+        }
+        for input in &element.inputs {
+            if !is_property_binding(input) || &*input.name == "class" || &*input.name == "style" {
+                continue;
+            }
+            let claimed = directives.iter().any(|(dir, _)| {
+                dir.inputs
+                    .get_by_binding_property_name(&input.name)
+                    .is_some()
+            });
+            if !claimed {
+                self.dom_schema_checker.check_property(
+                    &mut self.env.oob,
+                    file,
+                    &element.name,
+                    &input.name,
+                    &self.schemas,
+                    base + input.source_span.start.offset,
+                    span_length(&input.source_span),
+                );
+            }
+        }
+    }
+
+    /// Check that the required inputs of the directives matched on a node are bound,
+    /// either through a property binding or a static attribute.
+    fn check_required_inputs(
+        &mut self,
+        kind: OwnerKind,
+        offset: usize,
+        span: &ParseSourceSpan,
+        bound: Vec<String>,
+    ) {
+        let directives = self
+            .directives
+            .get(&(kind, offset))
+            .cloned()
+            .unwrap_or_default();
+        for (dir, _) in &directives {
+            for input in &dir.inputs.0 {
+                if input.required && !bound.contains(&input.binding_property_name) {
+                    self.env.oob.missing_required_input(
+                        self.env.template_file,
+                        &dir.name,
+                        &input.binding_property_name,
+                        self.env.template_offset + span.start.offset,
+                        span_length(span),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_input(
+        &mut self,
+        kind: OwnerKind,
+        offset: usize,
+        input: &BoundAttribute,
+        directives: &[(TypeCheckableDirectiveMeta, String)],
+    ) {
+        let is_property = matches!(input.type_, BindingType::Property | BindingType::TwoWay);
+        let claims: Vec<_> = if is_property {
+            directives
+                .iter()
+                .filter_map(|(dir, id)| {
+                    dir.inputs
+                        .get_by_binding_property_name(&input.name)
+                        .map(|meta| (dir, id, meta))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        if claims.is_empty() {
+            self.start_line();
+            let element = self.elements.get(&offset).cloned();
+            match element {
+                Some(element)
+                    if is_property
+                        && kind == OwnerKind::Element
+                        && self.config.check_type_of_dom_bindings =>
+                {
+                    let start = self.out.offset();
+                    self.out
+                        .write(&format!("{}[{}]", element, quote_string(&input.name)));
+                    self.map_span(start, &input.key_span);
+                    self.out.write(" = ");
+                }
+                _ => {}
+            }
+            self.translate(&input.value);
+            self.out.write(";\n");
+            return;
+        }
+
+        for (dir, id, meta) in claims {
+            self.start_line();
+            let assignable = self.config.check_type_of_inputs
+                && (input.type_ != BindingType::TwoWay
+                    || self.config.check_type_of_two_way_bindings);
+            match &meta.class_property_name {
+                Some(property) if assignable && !dir.coerced_input_fields.contains(property) => {
+                    let start = self.out.offset();
+                    self.out
+                        .write(&format!("{}[{}]", id, quote_string(property)));
+                    if meta.is_signal {
+                        self.out.write("[i0.ɵINPUT_SIGNAL_BRAND_WRITE_TYPE]");
+                    }
+                    self.map_span(start, &input.key_span);
+                    self.out.write(" = ");
+                    if input.type_ == BindingType::TwoWay {
+                        self.out.write("i0.ɵunwrapWritableSignal");
+                    }
+                }
+                _ => {}
+            }
+            self.translate(&input.value);
+            self.out.write(";\n");
+        }
+    }
+
+    fn check_output(
+        &mut self,
+        kind: OwnerKind,
+        offset: usize,
+        output: &BoundEvent,
+        directives: &[(TypeCheckableDirectiveMeta, String)],
+    ) {
+        let claims: Vec<_> = if output.type_ == ParsedEventType::Regular {
+            directives
+                .iter()
+                .filter_map(|(dir, id)| {
+                    dir.outputs
+                        .get_by_binding_property_name(&output.name)
+                        .map(|meta| (id.clone(), meta.class_property_name.clone()))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        if claims.is_empty() {
+            let element = self.elements.get(&offset).cloned();
+            match element {
+                Some(element)
+                    if kind == OwnerKind::Element
+                        && output.type_ == ParsedEventType::Regular
+                        && output.target.is_none()
+                        && self.config.check_type_of_outputs =>
+                {
+                    self.start_line();
+                    let start = self.out.offset();
+                    self.out.write(&format!(
+                        "{}.addEventListener({}",
+                        element,
+                        quote_string(&output.name)
+                    ));
+                    self.map_span(start, &output.key_span);
+                    self.out.write(", ");
+                    self.event_handler(output, false);
+                    self.out.write(");\n");
+                }
+                _ => {
+                    self.start_line();
+                    self.event_handler(output, true);
+                    self.out.write(";\n");
+                }
+            }
+            return;
+        }
+
+        for (id, property) in claims {
+            self.start_line();
+            match property {
+                Some(property) if self.config.check_type_of_outputs => {
+                    let start = self.out.offset();
+                    self.out
+                        .write(&format!("{}[{}]", id, quote_string(&property)));
+                    self.map_span(start, &output.key_span);
+                    self.out.write(".subscribe(");
+                    self.event_handler(output, false);
+                    self.out.write(");\n");
+                }
+                _ => {
+                    self.event_handler(output, true);
+                    self.out.write(";\n");
+                }
+            }
+        }
+    }
+
+    /// Emit an arrow function evaluating the handler of an event binding.
+    fn event_handler(&mut self, output: &BoundEvent, untyped: bool) {
+        self.out.write(if untyped {
+            "($event: any): any => {\n"
+        } else {
+            "($event): any => {\n"
+        });
+        self.indent += 1;
+        let mut guarded = false;
+        if !self.guards.is_empty() {
+            let guards = self.guards.join(" && ");
+            self.open_block(&format!("if ({}) ", guards));
+            guarded = true;
+        }
+        let mut scope = HashMap::new();
+        scope.insert("$event".to_string(), "$event".to_string());
+        self.env.scopes.push(scope);
+        let statements: Vec<&AST> = match &output.handler {
+            AST::Chain(chain) => chain.expressions.iter().map(|e| &**e).collect(),
+            handler => vec![handler],
+        };
+        for statement in statements {
+            self.expression_statement(statement);
+        }
+        self.env.scopes.pop();
+        if guarded {
+            self.close_block();
+        }
         self.indent -= 1;
-        self.write_line("}");
+        self.start_line();
+        self.out.write("}");
+    }
+
+    fn check_template(&mut self, template: &Template) {
+        let offset = template.source_span.start.offset;
+        let directives = self
+            .directives
+            .get(&(OwnerKind::Template, offset))
+            .cloned()
+            .unwrap_or_default();
+
+        let bound_attrs: Vec<BoundAttribute> = template
+            .template_attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                TemplateAttr::Bound(bound) => Some(bound.clone()),
+                TemplateAttr::Text(_) => None,
+            })
+            .chain(template.inputs.iter().cloned())
+            .collect();
+        self.check_bindings(OwnerKind::Template, offset, &bound_attrs, &template.outputs);
+        let bound = bound_attrs
+            .iter()
+            .filter(|attr| is_property_binding(attr))
+            .map(|attr| attr.name.to_string())
+            .chain(
+                template
+                    .template_attrs
+                    .iter()
+                    .filter_map(|attr| match attr {
+                        TemplateAttr::Text(text) => Some(text.name.to_string()),
+                        TemplateAttr::Bound(_) => None,
+                    }),
+            )
+            .chain(template.attributes.iter().map(|attr| attr.name.to_string()))
+            .collect();
+        self.check_required_inputs(
+            OwnerKind::Template,
+            offset,
+            &template.start_source_span,
+            bound,
+        );
+
+        if !self.config.apply_full_template_type_check_mode {
+            return;
+        }
+
+        // Template guards narrow the types within the embedded view.
+        let mut guards = Vec::new();
+        for (dir, id) in &directives {
+            for guard in &dir.ng_template_guards {
+                let Some(attr) = bound_attrs.iter().find(|a| *a.name == guard.input_name) else {
+                    continue;
+                };
+                let expression = self.translate_unmapped(&attr.value);
+                guards.push(match guard.kind {
+                    TemplateGuardKind::Binding => expression,
+                    TemplateGuardKind::Invocation => format!(
+                        "{}.ngTemplateGuard_{}({}, {})",
+                        dir.reference, guard.input_name, id, expression
+                    ),
+                });
+            }
+        }
+
+        let guard_count = guards.len();
+        if guards.is_empty() {
+            self.open_block("");
+        } else {
+            self.open_block(&format!("if ({}) ", guards.join(" && ")));
+        }
+        self.guards.extend(guards);
+        self.env.scopes.push(HashMap::new());
 
-        Ok(self.output.clone())
+        let context = self.allocate_id();
+        self.line(&format!("var {}: any = null!;", context));
+        let mut context_guards = 0;
+        for (dir, id) in &directives {
+            if dir.has_ng_template_context_guard {
+                self.open_block(&format!(
+                    "if ({}.ngTemplateContextGuard({}, {})) ",
+                    dir.reference, id, context
+                ));
+                context_guards += 1;
+            }
+        }
+        for variable in &template.variables {
+            let id = self.allocate_id();
+            let property = if variable.value.is_empty() {
+                "$implicit"
+            } else {
+                &variable.value
+            };
+            self.line(&format!(
+                "var {} = {}[{}];",
+                id,
+                context,
+                quote_string(property)
+            ));
+            self.scope().insert(variable.name.to_string(), id);
+        }
+        self.view_in_current_scope(&template.children);
+        for _ in 0..context_guards {
+            self.close_block();
+        }
+
+        self.env.scopes.pop();
+        self.guards.truncate(self.guards.len() - guard_count);
+        self.close_block();
+    }
+
+    fn check_if_branches(&mut self, branches: &[IfBlockBranch]) {
+        let Some((branch, rest)) = branches.split_first() else {
+            return;
+        };
+        let Some(expression) = &branch.expression else {
+            // `@else`
+            self.open_block("");
+            self.view(&branch.children);
+            self.close_block();
+            return;
+        };
+
+        self.env.scopes.push(HashMap::new());
+        let guard = match &branch.expression_alias {
+            Some(alias) => {
+                let id = self.allocate_id();
+                self.start_line();
+                self.out.write(&format!("var {} = ", id));
+                self.translate(expression);
+                self.out.write(";\n");
+                self.open_block(&format!("if ({}) ", id));
+                self.scope().insert(alias.name.to_string(), id.clone());
+                id
+            }
+            None => {
+                self.start_line();
+                self.out.write("if ");
+                self.translate(expression);
+                self.out.write(" {\n");
+                self.indent += 1;
+                self.translate_unmapped(expression)
+            }
+        };
+        self.guards.push(guard);
+        self.view(&branch.children);
+        self.guards.pop();
+        self.env.scopes.pop();
+
+        if rest.is_empty() {
+            self.close_block();
+        } else {
+            self.indent -= 1;
+            self.line("} else {");
+            self.indent += 1;
+            self.check_if_branches(rest);
+            self.close_block();
+        }
     }
 
-    /// Generate element type-check.
-    pub fn generate_element(&mut self, tag: &str, attrs: &[(String, String)]) {
-        self.write_line(&format!("// Element: <{}>", tag));
-        for (name, value) in attrs {
-            self.write_line(&format!("// Attr: {}=\"{}\"", name, value));
+    fn check_switch(&mut self, block: &SwitchBlock) {
+        self.start_line();
+        self.out.write("switch ");
+        self.translate(&block.expression);
+        self.out.write(" {\n");
+        self.indent += 1;
+        let subject = self.translate_unmapped(&block.expression);
+        for case in &block.cases {
+            match &case.expression {
+                Some(expression) => {
+                    self.start_line();
+                    self.out.write("case ");
+                    self.translate(expression);
+                    self.out.write(": {\n");
+                    self.indent += 1;
+                    let value = self.translate_unmapped(expression);
+                    self.guards.push(format!("{} === {}", subject, value));
+                    self.view(&case.children);
+                    self.guards.pop();
+                }
+                None => {
+                    self.open_block("default: ");
+                    self.view(&case.children);
+                }
+            }
+            self.line("break;");
+            self.close_block();
         }
+        self.close_block();
     }
 
-    /// Generate directive type-check.
-    pub fn generate_directive(&mut self, directive_name: &str, inputs: &[(String, String)]) {
-        self.write_line(&format!("const _dir = new {}();", directive_name));
-        for (input, value) in inputs {
-            self.write_line(&format!("_dir.{} = {};", input, value));
+    fn check_for_loop(&mut self, block: &ForLoopBlock) {
+        self.env.scopes.push(HashMap::new());
+        let item = self.allocate_id();
+        self.start_line();
+        self.out.write(&format!("for (const {} of ", item));
+        self.translate(&block.expression.ast);
+        self.out.write("!) {\n");
+        self.indent += 1;
+        self.scope().insert(block.item.name.to_string(), item);
+        for variable in &block.context_variables {
+            let id = self.allocate_id();
+            let type_ = match &*variable.value {
+                "$index" | "$count" => "number",
+                _ => "boolean",
+            };
+            self.line(&format!("var {}: {} = null!;", id, type_));
+            self.scope().insert(variable.name.to_string(), id);
+        }
+        self.expression_statement(&block.track_by.ast);
+        self.view(&block.children);
+        self.close_block();
+        self.env.scopes.pop();
+
+        if let Some(empty) = &block.empty {
+            self.open_block("");
+            self.view(&empty.children);
+            self.close_block();
         }
     }
 
-    /// Generate pipe type-check.
-    pub fn generate_pipe(&mut self, pipe_name: &str, args: &[String]) {
-        let args_str = args.join(", ");
-        self.write_line(&format!("const _pipe = new {}();", pipe_name));
-        self.write_line(&format!("_pipe.transform({});", args_str));
+    fn check_defer(&mut self, block: &DeferredBlock) {
+        for triggers in [
+            &block.triggers,
+            &block.prefetch_triggers,
+            &block.hydrate_triggers,
+        ] {
+            if let Some(when) = &triggers.when {
+                self.expression_statement(&when.value);
+            }
+        }
+        self.open_block("");
+        self.view(&block.children);
+        self.close_block();
+        let sub_blocks = [
+            block.placeholder.as_ref().map(|b| &b.children),
+            block.loading.as_ref().map(|b| &b.children),
+            block.error.as_ref().map(|b| &b.children),
+        ];
+        for children in sub_blocks.into_iter().flatten() {
+            self.open_block("");
+            self.view(children);
+            self.close_block();
+        }
     }
 
-    fn write_line(&mut self, line: &str) {
-        let indent = "  ".repeat(self.indent);
-        writeln!(self.output, "{}{}", indent, line).ok();
+    fn map_span(&mut self, start: usize, span: &angular_compiler::parse_util::ParseSourceSpan) {
+        self.out.map(start, span.start.offset, span.end.offset);
     }
 }
 
+/// Whether a binding sets a property, as opposed to an attribute, class, style or
+/// animation.
+fn is_property_binding(input: &BoundAttribute) -> bool {
+    matches!(input.type_, BindingType::Property | BindingType::TwoWay)
+}
+
+fn span_length(span: &ParseSourceSpan) -> usize {
+    span.end.offset.saturating_sub(span.start.offset)
+}
+
 /// Out-of-band checker for template errors.
 pub struct OutOfBandDiagnosticRecorder {
     /// Collected diagnostics.
@@ -92,17 +1057,65 @@ impl OutOfBandDiagnosticRecorder {
         }
     }
 
-    /// Record a missing pipe error.
-    pub fn missing_pipe(&mut self, component: &str, pipe_name: &str) {
+    /// Record a diagnostic at a span of the template file.
+    fn record(&mut self, diagnostic: TypeCheckError, start: usize, length: usize) {
         self.diagnostics.push(TypeCheckError {
-            message: format!("The pipe '{}' could not be found", pipe_name),
-            code: "NG8004".to_string(),
-            file: Some(component.to_string()),
-            start: None,
-            length: None,
+            start: Some(start),
+            length: Some(length),
+            ..diagnostic
         });
     }
 
+    /// Record a missing pipe error.
+    pub fn missing_pipe(&mut self, file: &str, pipe_name: &str, start: usize, length: usize) {
+        self.record(
+            create_missing_pipe_diagnostic(file, pipe_name),
+            start,
+            length,
+        );
+    }
+
+    /// Record an element unknown to the DOM schema which no component matches.
+    pub fn unknown_element(&mut self, file: &str, element: &str, start: usize, length: usize) {
+        self.record(
+            create_unknown_element_diagnostic(file, element),
+            start,
+            length,
+        );
+    }
+
+    /// Record a binding to a property unknown to the DOM schema which no directive claims.
+    pub fn unknown_property(
+        &mut self,
+        file: &str,
+        element: &str,
+        property: &str,
+        start: usize,
+        length: usize,
+    ) {
+        self.record(
+            create_unknown_property_diagnostic(file, element, property),
+            start,
+            length,
+        );
+    }
+
+    /// Record a required input of a directive which is not bound.
+    pub fn missing_required_input(
+        &mut self,
+        file: &str,
+        directive: &str,
+        input: &str,
+        start: usize,
+        length: usize,
+    ) {
+        self.record(
+            create_missing_required_input_diagnostic(file, directive, input),
+            start,
+            length,
+        );
+    }
+
     /// Record a missing directive error.
     pub fn missing_directive(&mut self, component: &str, selector: &str) {
         self.diagnostics.push(TypeCheckError {
@@ -114,6 +1127,23 @@ impl OutOfBandDiagnosticRecorder {
         });
     }
 
+    /// Record a reference to an `exportAs` name no directive on the element declares.
+    pub fn missing_reference_target(
+        &mut self,
+        file: &str,
+        export_as: &str,
+        start: usize,
+        length: usize,
+    ) {
+        self.diagnostics.push(TypeCheckError {
+            message: format!("No directive found with exportAs '{}'.", export_as),
+            code: "NG8003".to_string(),
+            file: Some(file.to_string()),
+            start: Some(start),
+            length: Some(length),
+        });
+    }
+
     /// Get all diagnostics.
     pub fn diagnostics(&self) -> &[TypeCheckError] {
         &self.diagnostics
@@ -125,3 +1155,296 @@ impl Default for OutOfBandDiagnosticRecorder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::api::{
+        TemplateGuard, TypeCheckInput, TypeCheckInputs, TypeCheckOutput, TypeCheckOutputs,
+    };
+    use super::*;
+    use angular_compiler::render3::view::template::{parse_template, ParseTemplateOptions};
+
+    fn metadata(
+        template: &str,
+        directives: Vec<TypeCheckableDirectiveMeta>,
+        pipes: &[(&str, &str)],
+    ) -> TypeCheckBlockMetadata {
+        let parsed = parse_template(template, "app.html", ParseTemplateOptions::default());
+        TypeCheckBlockMetadata {
+            component_name: "AppComponent".to_string(),
+            nodes: parsed.nodes,
+            directives,
+            pipes: pipes
+                .iter()
+                .map(|(name, reference)| (name.to_string(), reference.to_string()))
+                .collect(),
+            template_file: "app.html".to_string(),
+            template_offset: 0,
+            schemas: Vec::new(),
+            is_poisoned: false,
+            component_file: "app.ts".to_string(),
+            imports: Vec::new(),
+        }
+    }
+
+    fn generate_for(meta: &TypeCheckBlockMetadata) -> TypeCheckBlock {
+        TypeCheckBlockGenerator::new(TypeCheckingConfig::strict()).generate(
+            "_tcb1",
+            "i1.AppComponent",
+            meta,
+        )
+    }
+
+    fn generate(
+        template: &str,
+        directives: Vec<TypeCheckableDirectiveMeta>,
+        pipes: &[(&str, &str)],
+    ) -> TypeCheckBlock {
+        generate_for(&metadata(template, directives, pipes))
+    }
+
+    /// The code and template text of each diagnostic of a block.
+    fn reported<'t>(tcb: &TypeCheckBlock, template: &'t str) -> Vec<(String, &'t str)> {
+        tcb.diagnostics
+            .iter()
+            .map(|d| {
+                let start = d.start.unwrap();
+                (d.code.clone(), &template[start..start + d.length.unwrap()])
+            })
+            .collect()
+    }
+
+    fn input(binding: &str, property: &str, is_signal: bool) -> TypeCheckInput {
+        TypeCheckInput {
+            binding_property_name: binding.to_string(),
+            class_property_name: Some(property.to_string()),
+            is_signal,
+            required: false,
+        }
+    }
+
+    fn ng_if() -> TypeCheckableDirectiveMeta {
+        TypeCheckableDirectiveMeta {
+            name: "NgIf".to_string(),
+            reference: "NgIf".to_string(),
+            selector: Some("[ngIf]".to_string()),
+            is_structural: true,
+            inputs: TypeCheckInputs(vec![input("ngIf", "ngIf", false)]),
+            ng_template_guards: vec![TemplateGuard {
+                input_name: "ngIf".to_string(),
+                kind: TemplateGuardKind::Binding,
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// The template text a piece of generated code is mapped to.
+    fn mapped<'t>(tcb: &TypeCheckBlock, template: &'t str, code: &str) -> Option<&'t str> {
+        tcb.mappings
+            .iter()
+            .find(|m| &tcb.code[m.tcb_start..m.tcb_end] == code)
+            .map(|m| &template[m.template_start..m.template_end])
+    }
+
+    #[test]
+    fn test_reads_component_members_and_listens_to_dom_events() {
+        let tcb = generate(
+            "<button (click)=\"save($event); saved = true\">{{ user?.name }}</button>",
+            vec![],
+            &[],
+        );
+        assert!(tcb
+            .code
+            .starts_with("function _tcb1(this: i1.AppComponent) {"));
+        assert!(tcb
+            .code
+            .contains("var _t1 = document.createElement(\"button\");"));
+        assert!(tcb
+            .code
+            .contains("_t1.addEventListener(\"click\", ($event): any => {"));
+        assert!(tcb.code.contains("(this.save($event));"));
+        assert!(tcb.code.contains("(this.saved = true);"));
+        assert!(tcb.code.contains("(\"\" + this.user?.name);"));
+    }
+
+    #[test]
+    fn test_assigns_directive_inputs_and_subscribes_to_outputs() {
+        let directive = TypeCheckableDirectiveMeta {
+            name: "Dir".to_string(),
+            reference: "Dir".to_string(),
+            selector: Some("[dir]".to_string()),
+            inputs: TypeCheckInputs(vec![
+                input("value", "val", false),
+                input("size", "size", true),
+            ]),
+            outputs: TypeCheckOutputs(vec![TypeCheckOutput {
+                binding_property_name: "changed".to_string(),
+                class_property_name: Some("changed".to_string()),
+            }]),
+            ..Default::default()
+        };
+        let tcb = generate(
+            "<div dir [value]=\"a\" [size]=\"b\" [title]=\"c\" (changed)=\"d($event)\"></div>",
+            vec![directive],
+            &[],
+        );
+        assert!(tcb
+            .code
+            .contains("var _t2: InstanceType<typeof Dir> = null!;"));
+        assert!(tcb.code.contains("_t2[\"val\"] = (this.a);"));
+        assert!(tcb
+            .code
+            .contains("_t2[\"size\"][i0.ɵINPUT_SIGNAL_BRAND_WRITE_TYPE] = (this.b);"));
+        assert!(tcb.code.contains("  (this.c);"));
+        assert!(tcb
+            .code
+            .contains("_t2[\"changed\"].subscribe(($event): any => {"));
+    }
+
+    #[test]
+    fn test_narrows_embedded_views_with_template_guards() {
+        let tcb = generate(
+            "<div *ngIf=\"user\"><span (click)=\"edit(user.id)\">{{ user.name }}</span></div>",
+            vec![ng_if()],
+            &[],
+        );
+        assert!(tcb.code.contains("_t1[\"ngIf\"] = (this.user);"));
+        assert!(tcb.code.contains("if ((this.user)) {"));
+        assert!(tcb.code.contains("(\"\" + this.user.name);"));
+        // Narrowing does not carry into callbacks, so the guard is repeated.
+        assert!(tcb.code.contains(
+            "($event): any => {\n      if ((this.user)) {\n        (this.edit(this.user.id));"
+        ));
+    }
+
+    #[test]
+    fn test_checks_control_flow_blocks() {
+        let tcb = generate(
+            "@if (user$ | async; as user) { {{ user.name }} } @else { none }\n\
+             @for (item of items; track item.id; let i = $index) { {{ i }}: {{ item.label }} }\n\
+             @switch (mode) { @case ('a') { a } @default { other } }\n\
+             @let total = price * count; {{ total }}",
+            vec![],
+            &[("async", "AsyncPipe")],
+        );
+        assert!(tcb
+            .code
+            .contains("var _pipe1: InstanceType<typeof AsyncPipe> = null!;"));
+        assert!(tcb.code.contains("= (_pipe1.transform(this.user$));"));
+        assert!(tcb.code.contains("for (const _t"));
+        assert!(tcb.code.contains(" of (this.items)!) {"));
+        assert!(tcb.code.contains("switch (this.mode) {"));
+        assert!(tcb.code.contains("case (\"a\"): {"));
+        assert!(tcb.code.contains(" = (this.price * this.count);"));
+        assert!(tcb.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_resolves_references() {
+        let form = TypeCheckableDirectiveMeta {
+            name: "NgForm".to_string(),
+            reference: "NgForm".to_string(),
+            selector: Some("form".to_string()),
+            export_as: Some(vec!["ngForm".to_string()]),
+            ..Default::default()
+        };
+        let tcb = generate(
+            "<form #f=\"ngForm\" #el><input #box></form>{{ f.valid }} {{ box.value }} {{ el }}<div #bad=\"missing\"></div>",
+            vec![form],
+            &[],
+        );
+        assert!(tcb.code.contains("(\"\" + _t2.valid + _t3.value + _t1);"));
+        assert_eq!(tcb.diagnostics.len(), 1);
+        assert_eq!(tcb.diagnostics[0].code, "NG8003");
+    }
+
+    #[test]
+    fn test_reports_unknown_pipes() {
+        let template = "{{ value | unknown }}";
+        let tcb = generate(template, vec![], &[]);
+        assert_eq!(tcb.diagnostics.len(), 1);
+        let diagnostic = &tcb.diagnostics[0];
+        assert_eq!(diagnostic.code, "NG8004");
+        let start = diagnostic.start.unwrap();
+        assert_eq!(
+            &template[start..start + diagnostic.length.unwrap()],
+            "unknown"
+        );
+    }
+
+    #[test]
+    fn test_reports_unknown_elements_and_properties() {
+        let template = "<app-user [user]=\"u\"></app-user><div [foo]=\"x\" [title]=\"t\" [class]=\"c\" [attr.role]=\"r\"></div>";
+        let tcb = generate(template, vec![], &[]);
+        assert_eq!(
+            reported(&tcb, template),
+            vec![
+                ("NG8001".to_string(), "<app-user [user]=\"u\">"),
+                ("NG8002".to_string(), "[user]=\"u\""),
+                ("NG8002".to_string(), "[foo]=\"x\""),
+            ]
+        );
+        assert!(tcb.diagnostics[0]
+            .message
+            .starts_with("'app-user' is not a known element:"));
+        assert!(tcb.diagnostics[2]
+            .message
+            .starts_with("Can't bind to 'foo' since it isn't a known property of 'div'."));
+    }
+
+    #[test]
+    fn test_honors_component_schemas() {
+        let template = "<app-user [user]=\"u\"></app-user><div [foo]=\"x\"></div>";
+        let mut meta = metadata(template, vec![], &[]);
+        meta.schemas = vec!["CUSTOM_ELEMENTS_SCHEMA".to_string()];
+        assert_eq!(
+            reported(&generate_for(&meta), template),
+            vec![("NG8002".to_string(), "[foo]=\"x\"")]
+        );
+
+        meta.schemas = vec!["NO_ERRORS_SCHEMA".to_string()];
+        assert!(generate_for(&meta).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_does_not_report_names_missing_from_a_poisoned_scope() {
+        let template = "<app-user #user=\"user\"></app-user>{{ value | unknown }}";
+        let mut meta = metadata(template, vec![], &[]);
+        meta.is_poisoned = true;
+        assert!(generate_for(&meta).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_reports_missing_required_inputs() {
+        let user = TypeCheckableDirectiveMeta {
+            name: "UserComponent".to_string(),
+            reference: "UserComponent".to_string(),
+            selector: Some("app-user".to_string()),
+            is_component: true,
+            inputs: TypeCheckInputs(vec![TypeCheckInput {
+                required: true,
+                ..input("user", "user", true)
+            }]),
+            ..Default::default()
+        };
+        let template = "<app-user></app-user><app-user user=\"a\"></app-user><app-user [user]=\"u\"></app-user>";
+        let tcb = generate(template, vec![user], &[]);
+        assert_eq!(
+            reported(&tcb, template),
+            vec![("NG8008".to_string(), "<app-user>")]
+        );
+        assert_eq!(
+            tcb.diagnostics[0].message,
+            "Required input 'user' from directive UserComponent must be specified."
+        );
+    }
+
+    #[test]
+    fn test_maps_generated_code_back_to_the_template() {
+        let template = "<p>{{ user.nmae }}</p><div [title]=\"title\"></div>";
+        let tcb = generate(template, vec![], &[]);
+        assert_eq!(mapped(&tcb, template, "nmae"), Some("nmae"));
+        assert_eq!(mapped(&tcb, template, "this.user.nmae"), Some("user.nmae"));
+        assert_eq!(mapped(&tcb, template, "this.title"), Some("title"));
+    }
+}
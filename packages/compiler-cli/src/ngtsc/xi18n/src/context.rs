@@ -2,8 +2,35 @@
 //
 // Message extraction context and utilities.
 
+use angular_compiler::i18n::i18n_ast::Message;
+use angular_compiler::i18n::message_bundle::MessageBundle;
+use angular_compiler::parse_util::ParseError;
 use std::collections::HashMap;
 
+/// Captures template information intended for extraction of i18n messages from a template.
+///
+/// This interface is compatible with the `MessageBundle` in the compiler, which is the
+/// implementation used by `ng-xi18n`.
+pub trait Xi18nContext {
+    /// Capture the messages of the template `html`, found at `url`. Returns the errors
+    /// encountered while parsing the template or extracting its messages.
+    fn update_from_template(&mut self, html: &str, url: &str) -> Vec<ParseError>;
+
+    /// Capture messages extracted outside of templates, e.g. from `$localize` tagged
+    /// templates in TypeScript sources.
+    fn add_messages(&mut self, messages: Vec<Message>);
+}
+
+impl Xi18nContext for MessageBundle {
+    fn update_from_template(&mut self, html: &str, url: &str) -> Vec<ParseError> {
+        MessageBundle::update_from_template(self, html, url)
+    }
+
+    fn add_messages(&mut self, messages: Vec<Message>) {
+        MessageBundle::add_messages(self, messages)
+    }
+}
+
 /// I18n message.
 #[derive(Debug, Clone)]
 pub struct I18nMessage {
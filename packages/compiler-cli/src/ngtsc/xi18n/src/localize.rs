@@ -0,0 +1,291 @@
+// $localize Messages
+//
+// Extraction and translation of the messages of `$localize` tagged templates in TypeScript
+// sources.
+// Corresponds to packages/localize/src/utils/src/messages.ts
+
+use super::translate::translate_localize_message;
+use angular_compiler::i18n::digest::compute_msg_id;
+use angular_compiler::i18n::i18n_ast::{Message, MessageSpan, Node, Placeholder, Text};
+use angular_compiler::i18n::TranslationBundle;
+use angular_compiler::parse_util::{ParseError, ParseLocation, ParseSourceFile, ParseSourceSpan};
+use indexmap::IndexMap;
+use oxc_ast::ast::{Expression, TaggedTemplateExpression, TemplateElement};
+use oxc_ast::AstKind;
+use oxc_span::{GetSpan, Span};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The marker that opens and closes a metadata block, e.g. `:meaning|description@@id:`.
+const BLOCK_MARKER: char = ':';
+/// The separator between the meaning and the description in a metadata block.
+const MEANING_SEPARATOR: char = '|';
+/// The separator before the custom id in a metadata block.
+const ID_SEPARATOR: &str = "@@";
+/// The separator before each legacy id in a metadata block.
+const LEGACY_ID_INDICATOR: char = '\u{241F}';
+
+/// The metadata of a `$localize` message, parsed from the block at the start of its first
+/// message part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageMetadata {
+    /// The text of the message part, without the metadata block.
+    pub text: String,
+    pub meaning: Option<String>,
+    pub description: Option<String>,
+    pub custom_id: Option<String>,
+    pub legacy_ids: Vec<String>,
+}
+
+/// Parse the metadata block at the start of the first part of a `$localize` message:
+///
+/// ```text
+/// $localize`:meaning|description@@custom-id␟legacy-id:Hello`
+/// ```
+///
+/// `cooked` and `raw` are the cooked and raw strings of the part: a marker escaped in the
+/// raw string (`\:`) does not open or close a block.
+pub fn parse_metadata(cooked: &str, raw: &str) -> Result<MessageMetadata, String> {
+    let (text, block) = split_block(cooked, raw)?;
+    let Some(block) = block else {
+        return Ok(MessageMetadata {
+            text,
+            ..MessageMetadata::default()
+        });
+    };
+
+    let mut ids = block.split(LEGACY_ID_INDICATOR);
+    let meaning_desc_and_id = ids.next().unwrap_or_default();
+    let legacy_ids = ids.map(str::to_string).collect();
+    let mut parts = meaning_desc_and_id.split(ID_SEPARATOR);
+    let meaning_and_desc = parts.next().unwrap_or_default();
+    let custom_id = parts.next().filter(|id| !id.is_empty());
+    let mut parts = meaning_and_desc.split(MEANING_SEPARATOR);
+    let first = parts.next().unwrap_or_default();
+    let (meaning, description) = match parts.next() {
+        Some(description) => (Some(first), description),
+        None => (None, first),
+    };
+
+    Ok(MessageMetadata {
+        text,
+        meaning: meaning.map(str::to_string),
+        description: Some(description.to_string()).filter(|d| !d.is_empty()),
+        custom_id: custom_id.map(str::to_string),
+        legacy_ids,
+    })
+}
+
+/// Parse the placeholder name block at the start of a message part that follows a
+/// substitution, e.g. `:name:` in `` $localize`Hello ${name}:name:!` ``. Returns the text of
+/// the part and the name of the placeholder, if any.
+///
+/// The id of an associated message (`:name@@id:`) is ignored.
+pub fn parse_placeholder(cooked: &str, raw: &str) -> Result<(String, Option<String>), String> {
+    let (text, block) = split_block(cooked, raw)?;
+    let name = block.map(|block| {
+        block
+            .split(ID_SEPARATOR)
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    });
+    Ok((text, name))
+}
+
+/// The name of the `index`th placeholder of a message, when it is not named explicitly.
+pub fn compute_placeholder_name(index: usize) -> String {
+    if index == 1 {
+        "PH".to_string()
+    } else {
+        format!("PH_{}", index - 1)
+    }
+}
+
+/// Split a message part into its text and the block at its start, if any.
+fn split_block(cooked: &str, raw: &str) -> Result<(String, Option<String>), String> {
+    if !raw.starts_with(BLOCK_MARKER) {
+        return Ok((cooked.to_string(), None));
+    }
+    let cooked: Vec<char> = cooked.chars().collect();
+    let end = find_end_of_block(&cooked, raw)?;
+    Ok((
+        cooked[end + 1..].iter().collect(),
+        Some(cooked[1..end].iter().collect()),
+    ))
+}
+
+/// The index of the marker that closes the block at the start of `cooked`. Markers escaped
+/// in the raw string are skipped.
+fn find_end_of_block(cooked: &[char], raw: &str) -> Result<usize, String> {
+    let raw_chars: Vec<char> = raw.chars().collect();
+    let mut raw_index = 1;
+    for (cooked_index, &ch) in cooked.iter().enumerate().skip(1) {
+        if raw_chars.get(raw_index) == Some(&'\\') {
+            raw_index += 1;
+        } else if ch == BLOCK_MARKER {
+            return Ok(cooked_index);
+        }
+        raw_index += 1;
+    }
+    Err(format!(
+        "Unterminated $localize metadata block in \"{}\".",
+        raw
+    ))
+}
+
+/// Extract the messages of the `$localize` tagged templates of a TypeScript file, with their
+/// ids computed as `$localize` does at runtime. Files which fail to parse have no messages.
+pub fn extract_localize_messages(file_path: &str, source: &str) -> (Vec<Message>, Vec<ParseError>) {
+    let source_type = oxc_span::SourceType::from_path(file_path).unwrap_or_default();
+    let mut messages = Vec::new();
+    let mut errors = Vec::new();
+    let (_, tagged_messages) = localize_messages(file_path, source, source_type);
+    for (_, message) in tagged_messages {
+        match message {
+            Ok(message) => messages.push(message),
+            Err(error) => errors.push(error),
+        }
+    }
+    (messages, errors)
+}
+
+/// Translate the `$localize` tagged templates of `code`, a JavaScript file emitted for
+/// `file_path`, see `translate_localize_message()`. Returns the byte range of each tagged
+/// template along with its replacement, and the errors found while translating them.
+pub fn translate_localize_messages(
+    file_path: &str,
+    code: &str,
+    translations: &mut TranslationBundle,
+) -> (Vec<(Range<usize>, String)>, Vec<ParseError>) {
+    let mut replacements = Vec::new();
+    let mut errors = Vec::new();
+    let (file, tagged_messages) = localize_messages(file_path, code, oxc_span::SourceType::mjs());
+    for (span, message) in tagged_messages {
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        let (replacement, error) =
+            translate_localize_message(&message, &source_span(&file, span), translations);
+        if let Some(replacement) = replacement {
+            replacements.push((span.start as usize..span.end as usize, replacement));
+        }
+        errors.extend(error);
+    }
+    (replacements, errors)
+}
+
+/// The messages of the `$localize` tagged templates of `source`, along with the span of
+/// each tagged template. Sources which fail to parse have no messages.
+fn localize_messages(
+    file_path: &str,
+    source: &str,
+    source_type: oxc_span::SourceType,
+) -> (
+    Arc<ParseSourceFile>,
+    Vec<(Span, Result<Message, ParseError>)>,
+) {
+    let file = Arc::new(ParseSourceFile::new(
+        source.to_string(),
+        file_path.to_string(),
+    ));
+    let allocator = oxc_allocator::Allocator::default();
+    let ret = oxc_parser::Parser::new(&allocator, source, source_type).parse();
+    if !ret.errors.is_empty() {
+        return (file, Vec::new());
+    }
+    let semantic = oxc_semantic::SemanticBuilder::new().build(&ret.program);
+
+    let mut messages = Vec::new();
+    for node in semantic.semantic.nodes().iter() {
+        let AstKind::TaggedTemplateExpression(tagged) = node.kind() else {
+            continue;
+        };
+        if !matches!(&tagged.tag, Expression::Identifier(id) if id.name.as_str() == "$localize") {
+            continue;
+        }
+        messages.push((tagged.span, localize_message(&file, tagged)));
+    }
+    (file, messages)
+}
+
+/// The message of a `$localize` tagged template.
+fn localize_message(
+    file: &Arc<ParseSourceFile>,
+    tagged: &TaggedTemplateExpression,
+) -> Result<Message, ParseError> {
+    let quasis = &tagged.quasi.quasis;
+    let first = &quasis[0];
+    let metadata = parse_metadata(cooked(first), first.value.raw.as_str())
+        .map_err(|msg| ParseError::new(source_span(file, first.span), msg))?;
+
+    let mut nodes = Vec::new();
+    if !metadata.text.is_empty() {
+        nodes.push(Node::Text(Text::new(
+            metadata.text,
+            source_span(file, first.span),
+        )));
+    }
+    for (i, (part, expression)) in quasis
+        .iter()
+        .skip(1)
+        .zip(tagged.quasi.expressions.iter())
+        .enumerate()
+    {
+        let (text, name) = parse_placeholder(cooked(part), part.value.raw.as_str())
+            .map_err(|msg| ParseError::new(source_span(file, part.span), msg))?;
+        let span = expression.span();
+        nodes.push(Node::Placeholder(Placeholder::new(
+            file.content[span.start as usize..span.end as usize].to_string(),
+            name.unwrap_or_else(|| compute_placeholder_name(i + 1)),
+            source_span(file, span),
+        )));
+        if !text.is_empty() {
+            nodes.push(Node::Text(Text::new(text, source_span(file, part.span))));
+        }
+    }
+
+    let mut message = Message::new(
+        nodes,
+        IndexMap::new(),
+        HashMap::new(),
+        metadata.meaning.unwrap_or_default(),
+        metadata.description.unwrap_or_default(),
+        metadata.custom_id.unwrap_or_default(),
+    );
+    if message.id.is_empty() {
+        message.id = compute_msg_id(&message.message_string, &message.meaning);
+    }
+    message.legacy_ids = metadata.legacy_ids;
+
+    let span = source_span(file, tagged.span);
+    message.sources = vec![MessageSpan {
+        file_path: file.url.clone(),
+        start_line: span.start.line + 1,
+        start_col: span.start.col + 1,
+        end_line: span.end.line + 1,
+        end_col: span.end.col + 1,
+    }];
+    Ok(message)
+}
+
+/// The cooked string of a message part, or its raw string if it has an invalid escape.
+fn cooked<'a>(part: &'a TemplateElement) -> &'a str {
+    part.value
+        .cooked
+        .as_ref()
+        .map_or(part.value.raw.as_str(), |cooked| cooked.as_str())
+}
+
+fn source_span(file: &Arc<ParseSourceFile>, span: Span) -> ParseSourceSpan {
+    let start = ParseLocation::new(file.clone(), 0, 0, 0);
+    ParseSourceSpan::new(
+        start.move_by(span.start as i32),
+        start.move_by(span.end as i32),
+    )
+}
@@ -1,5 +1,9 @@
 //! Xi18n Source
 
 pub mod context;
+pub mod localize;
+pub mod translate;
 
 pub use context::*;
+pub use localize::*;
+pub use translate::*;
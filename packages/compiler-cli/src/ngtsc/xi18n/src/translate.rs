@@ -0,0 +1,233 @@
+// Translations
+//
+// Compile-time translation of i18n messages, from a translation file loaded once per locale.
+// Corresponds to packages/localize/tools/src/translate
+
+use angular_compiler::core::MissingTranslationStrategy;
+use angular_compiler::i18n::i18n_ast::{Message, Node};
+use angular_compiler::i18n::serializers::{Arb, Json, Serializer, Xliff, Xliff2, Xtb};
+use angular_compiler::i18n::TranslationBundle;
+use angular_compiler::output::abstract_emitter::escape_identifier;
+use angular_compiler::parse_util::{ParseError, ParseErrorLevel, ParseSourceSpan};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The messages of a translation file for one locale.
+///
+/// The messages are loaded once and shared by the threads emitting the translated files,
+/// each of which translates through its own `TranslationBundle`, see `bundle()`.
+#[derive(Clone)]
+pub struct Translations {
+    i18n_nodes_by_msg_id: Arc<HashMap<String, Vec<Node>>>,
+    locale: Option<String>,
+    serializer: Arc<dyn Serializer + Send + Sync>,
+    missing_translation: MissingTranslationStrategy,
+}
+
+impl Translations {
+    /// Load the translation file `content`, found at `url`, written in `format` (`xlf`,
+    /// `xlf2`, `xtb`, `json` or `arb`).
+    pub fn load(
+        content: &str,
+        url: &str,
+        format: &str,
+        missing_translation: MissingTranslationStrategy,
+    ) -> Result<Self, String> {
+        let serializer: Arc<dyn Serializer + Send + Sync> = match format.to_lowercase().as_str() {
+            "xlf" | "xlif" | "xliff" => Arc::new(Xliff::new()),
+            "xlf2" | "xliff2" => Arc::new(Xliff2::new()),
+            "xmb" | "xtb" => Arc::new(Xtb::new()),
+            "json" => Arc::new(Json::new()),
+            "arb" => Arc::new(Arb::new()),
+            _ => return Err(format!("Unsupported translation format \"{}\"", format)),
+        };
+        let result = serializer.load(content, url)?;
+        Ok(Self {
+            i18n_nodes_by_msg_id: Arc::new(result.i18n_nodes_by_msg_id),
+            locale: result.locale,
+            serializer,
+            missing_translation,
+        })
+    }
+
+    /// Use `locale` instead of the locale declared by the translation file.
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// The locale of the translations, if the translation file or the options declare one.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// A bundle translating messages with these translations.
+    pub fn bundle(&self) -> TranslationBundle {
+        TranslationBundle::with_serializer(
+            self.i18n_nodes_by_msg_id.clone(),
+            self.locale.clone(),
+            self.serializer.clone(),
+            self.missing_translation,
+        )
+    }
+}
+
+/// Parse the value of the `missingTranslation` option.
+pub fn parse_missing_translation_strategy(
+    value: &str,
+) -> Result<MissingTranslationStrategy, String> {
+    match value {
+        "error" => Ok(MissingTranslationStrategy::Error),
+        "warning" => Ok(MissingTranslationStrategy::Warning),
+        "ignore" => Ok(MissingTranslationStrategy::Ignore),
+        _ => Err(format!(
+            "Invalid missingTranslation \"{}\", expected \"error\", \"warning\" or \"ignore\"",
+            value
+        )),
+    }
+}
+
+/// Translate a `$localize` message, found at `span`, into a JavaScript expression that
+/// concatenates the text of its translation with the expressions of its placeholders, e.g.
+/// `'Bonjour ' + name + ' !'`.
+///
+/// Returns the expression together with the error reporting a missing translation, if any:
+/// a message without translation is replaced by its source text, and the
+/// `missingTranslation` strategy of `translations` decides whether an error, a warning or
+/// nothing is reported. No expression is returned when the translation refers to a
+/// placeholder the message does not have.
+pub fn translate_localize_message(
+    message: &Message,
+    span: &ParseSourceSpan,
+    translations: &mut TranslationBundle,
+) -> (Option<String>, Option<ParseError>) {
+    let Some(nodes) = translations.get_i18n_nodes(message) else {
+        let error = missing_translation_error(message, span, translations);
+        return (
+            build_localize_replacement(message, &message.nodes).ok(),
+            error,
+        );
+    };
+    match build_localize_replacement(message, &nodes) {
+        Ok(replacement) => (Some(replacement), None),
+        Err(name) => (
+            None,
+            Some(ParseError::new(
+                span.clone(),
+                format!(
+                    "There is a placeholder name mismatch with the translation provided for the message \"{}\" (\"{}\").\nThe translation contains a placeholder with name {}, which does not exist in the message.",
+                    translations.digest(message),
+                    message.message_string,
+                    name
+                ),
+            )),
+        ),
+    }
+}
+
+fn missing_translation_error(
+    message: &Message,
+    span: &ParseSourceSpan,
+    translations: &TranslationBundle,
+) -> Option<ParseError> {
+    let level = match translations.missing_translation_strategy() {
+        MissingTranslationStrategy::Error => ParseErrorLevel::Error,
+        MissingTranslationStrategy::Warning => ParseErrorLevel::Warning,
+        MissingTranslationStrategy::Ignore => return None,
+    };
+    let mut error = ParseError::new(
+        span.clone(),
+        format!(
+            "No translation found for \"{}\" (\"{}\").",
+            translations.digest(message),
+            message.message_string
+        ),
+    );
+    error.level = level;
+    Some(error)
+}
+
+/// The `'text' + expression + 'text'` concatenation of `nodes`, where placeholders are
+/// replaced by the expression they name in `message`. Fails with the name of a placeholder
+/// that `message` does not have.
+fn build_localize_replacement(message: &Message, nodes: &[Node]) -> Result<String, String> {
+    let mut parts = vec![String::new()];
+    let mut substitutions = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Placeholder(ph) => {
+                let expression = message
+                    .nodes
+                    .iter()
+                    .find_map(|node| match node {
+                        Node::Placeholder(source) if source.name == ph.name => {
+                            Some(source.value.as_str())
+                        }
+                        _ => None,
+                    })
+                    .ok_or_else(|| ph.name.clone())?;
+                substitutions.push(expression);
+                parts.push(String::new());
+            }
+            node => message_text(node, parts.last_mut().unwrap()),
+        }
+    }
+
+    let mut replacement = escape_identifier(&parts[0], false, true);
+    for (expression, part) in substitutions.iter().zip(&parts[1..]) {
+        replacement.push_str(" + ");
+        if expression
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+        {
+            replacement.push_str(expression);
+        } else {
+            replacement.push_str(&format!("({})", expression));
+        }
+        if !part.is_empty() {
+            replacement.push_str(" + ");
+            replacement.push_str(&escape_identifier(part, false, true));
+        }
+    }
+    Ok(replacement)
+}
+
+/// Append the text of `node`, as it appears in a `$localize` message, to `text`. Nested
+/// placeholders are kept as `{$NAME}`, they are not substitutions of the message.
+fn message_text(node: &Node, text: &mut String) {
+    match node {
+        Node::Text(t) => text.push_str(&t.value),
+        Node::Container(container) => {
+            for child in &container.children {
+                message_text(child, text);
+            }
+        }
+        Node::Icu(icu) => {
+            text.push_str(&format!("{{{}, {},", icu.expression, icu.type_));
+            for (value, case) in &icu.cases {
+                text.push_str(&format!(" {} {{", value));
+                message_text(case, text);
+                text.push('}');
+            }
+            text.push('}');
+        }
+        Node::TagPlaceholder(ph) => {
+            text.push_str(&format!("{{${}}}", ph.start_name));
+            for child in &ph.children {
+                message_text(child, text);
+            }
+            if !ph.is_void {
+                text.push_str(&format!("{{${}}}", ph.close_name));
+            }
+        }
+        Node::BlockPlaceholder(ph) => {
+            text.push_str(&format!("{{${}}}", ph.start_name));
+            for child in &ph.children {
+                message_text(child, text);
+            }
+            text.push_str(&format!("{{${}}}", ph.close_name));
+        }
+        Node::Placeholder(ph) => text.push_str(&format!("{{${}}}", ph.name)),
+        Node::IcuPlaceholder(ph) => text.push_str(&format!("{{${}}}", ph.name)),
+    }
+}
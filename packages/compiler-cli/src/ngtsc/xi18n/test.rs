@@ -103,4 +103,169 @@ mod tests {
             assert!(xmb.contains("Goodbye"));
         }
     }
+
+    mod localize_tests {
+        use super::*;
+        use angular_compiler::core::MissingTranslationStrategy;
+        use angular_compiler::i18n::digest::compute_msg_id;
+        use angular_compiler::parse_util::ParseErrorLevel;
+
+        #[test]
+        fn should_parse_metadata_block() {
+            let metadata = parse_metadata(":m|d@@custom:Hello", ":m|d@@custom:Hello").unwrap();
+            assert_eq!(metadata.text, "Hello");
+            assert_eq!(metadata.meaning.as_deref(), Some("m"));
+            assert_eq!(metadata.description.as_deref(), Some("d"));
+            assert_eq!(metadata.custom_id.as_deref(), Some("custom"));
+        }
+
+        #[test]
+        fn should_parse_description_only_metadata() {
+            let metadata = parse_metadata(":desc:Hi", ":desc:Hi").unwrap();
+            assert_eq!(metadata.meaning, None);
+            assert_eq!(metadata.description.as_deref(), Some("desc"));
+            assert_eq!(metadata.custom_id, None);
+        }
+
+        #[test]
+        fn should_parse_legacy_ids() {
+            let block = ":@@id\u{241F}legacy1\u{241F}legacy2:Hi";
+            let metadata = parse_metadata(block, block).unwrap();
+            assert_eq!(metadata.custom_id.as_deref(), Some("id"));
+            assert_eq!(metadata.legacy_ids, vec!["legacy1", "legacy2"]);
+        }
+
+        #[test]
+        fn should_ignore_escaped_block_markers() {
+            let metadata = parse_metadata(":a:b", "\\:a:b").unwrap();
+            assert_eq!(metadata.text, ":a:b");
+            assert_eq!(metadata.description, None);
+
+            let metadata = parse_metadata(":a:b:c", ":a\\:b:c").unwrap();
+            assert_eq!(metadata.text, "c");
+            assert_eq!(metadata.description.as_deref(), Some("a:b"));
+        }
+
+        #[test]
+        fn should_report_unterminated_metadata_block() {
+            let error = parse_metadata(":desc", ":desc").unwrap_err();
+            assert_eq!(error, "Unterminated $localize metadata block in \":desc\".");
+        }
+
+        #[test]
+        fn should_parse_placeholder_names() {
+            assert_eq!(
+                parse_placeholder(":name: world", ":name: world").unwrap(),
+                (" world".to_string(), Some("name".to_string()))
+            );
+            assert_eq!(
+                parse_placeholder(":name@@other: world", ":name@@other: world").unwrap(),
+                (" world".to_string(), Some("name".to_string()))
+            );
+            assert_eq!(
+                parse_placeholder(" world", " world").unwrap(),
+                (" world".to_string(), None)
+            );
+            assert_eq!(compute_placeholder_name(1), "PH");
+            assert_eq!(compute_placeholder_name(3), "PH_2");
+        }
+
+        #[test]
+        fn should_extract_localize_tagged_templates() {
+            let source = r#"
+const name = 'World';
+export const greeting = $localize`:@@greeting:Hello ${name}:name:!`;
+export function farewell() {
+  return $localize`:leaving|Says goodbye:Bye ${name} and ${name.length}`;
+}
+export const other = html`<p>Ignored</p>`;
+"#;
+            let (messages, errors) = extract_localize_messages("/app/greeting.ts", source);
+            assert!(errors.is_empty());
+            assert_eq!(messages.len(), 2);
+
+            assert_eq!(messages[0].id, "greeting");
+            assert_eq!(messages[0].message_string, "Hello {$name}!");
+            assert_eq!(messages[0].sources[0].file_path, "/app/greeting.ts");
+            assert_eq!(messages[0].sources[0].start_line, 3);
+
+            assert_eq!(messages[1].meaning, "leaving");
+            assert_eq!(messages[1].description, "Says goodbye");
+            assert_eq!(messages[1].message_string, "Bye {$PH} and {$PH_1}");
+            assert_eq!(
+                messages[1].id,
+                compute_msg_id("Bye {$PH} and {$PH_1}", "leaving")
+            );
+            assert_eq!(messages[1].sources[0].start_line, 5);
+        }
+
+        #[test]
+        fn should_report_invalid_localize_metadata() {
+            let source = "export const broken = $localize`:unterminated`;";
+            let (messages, errors) = extract_localize_messages("/app/broken.ts", source);
+            assert!(messages.is_empty());
+            assert_eq!(errors.len(), 1);
+            assert_eq!(
+                errors[0].msg,
+                "Unterminated $localize metadata block in \":unterminated\"."
+            );
+        }
+
+        #[test]
+        fn should_translate_localize_tagged_templates() {
+            let source = r#"
+const name = 'World';
+export const greeting = $localize`:@@greeting:Hello ${name}:name:!`;
+export const farewell = $localize`Bye ${name} and ${name.length}`;
+"#;
+            let translations = Translations::load(
+                r#"{ "locale": "fr", "translations": { "greeting": "Bonjour {$name} !" } }"#,
+                "messages.fr.json",
+                "json",
+                MissingTranslationStrategy::Warning,
+            )
+            .unwrap();
+            let (replacements, errors) =
+                translate_localize_messages("/app/greeting.js", source, &mut translations.bundle());
+
+            assert_eq!(replacements.len(), 2);
+            assert_eq!(
+                &source[replacements[0].0.clone()],
+                "$localize`:@@greeting:Hello ${name}:name:!`"
+            );
+            assert_eq!(replacements[0].1, "'Bonjour ' + name + ' !'");
+            // Messages without translation keep their source text.
+            assert_eq!(replacements[1].1, "'Bye ' + name + ' and ' + name.length");
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].level, ParseErrorLevel::Warning);
+            assert_eq!(
+                errors[0].msg,
+                format!(
+                    "No translation found for \"{}\" (\"Bye {{$PH}} and {{$PH_1}}\").",
+                    compute_msg_id("Bye {$PH} and {$PH_1}", "")
+                )
+            );
+        }
+
+        #[test]
+        fn should_report_placeholder_mismatch() {
+            let source = "export const greeting = $localize`:@@greeting:Hello ${name}:name:!`;";
+            let translations = Translations::load(
+                r#"{ "translations": { "greeting": "Bonjour {$user} !" } }"#,
+                "messages.fr.json",
+                "json",
+                MissingTranslationStrategy::Error,
+            )
+            .unwrap();
+            let (replacements, errors) =
+                translate_localize_messages("/app/greeting.js", source, &mut translations.bundle());
+
+            assert!(replacements.is_empty());
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0]
+                .msg
+                .contains("The translation contains a placeholder with name user"));
+        }
+    }
 }
@@ -3,12 +3,15 @@
 //! Corresponds to packages/compiler-cli/src/perform_compile.ts
 //! Config parsing and compilation entry point.
 
-use crate::ngtsc::core::NgCompilerOptions;
+use crate::config::tsconfig::{load_tsconfig, overlay_options, ConfigFileSpecs};
+use crate::ngtsc::core::{AnalysisCache, NgCompilerOptions};
+use crate::ngtsc::diagnostics::ErrorCode;
 use crate::ngtsc::file_system::NodeJSFileSystem;
 use crate::ngtsc::program::NgtscProgram;
-use crate::transformers::api::{CompilerOptions, Diagnostic, DiagnosticCategory};
+use crate::ngtsc::transform::src::api::CompilationMode as NgCompilationMode;
+use crate::transformers::api::{CompilationMode, CompilerOptions, Diagnostic, DiagnosticCategory};
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Emit flags for controlling output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -51,6 +54,8 @@ pub struct CompilationResult {
 pub struct Program {
     /// Source files in the program.
     pub source_files: Vec<String>,
+    /// Analysis of the source files, reused by the next compilation of the program.
+    pub analysis: AnalysisCache,
 }
 
 /// Old result structure for backward compatibility.
@@ -69,6 +74,7 @@ pub fn read_configuration(
     let project_path = Path::new(project);
     let mut root_names = Vec::new();
     let mut errors = Vec::new();
+    let mut options = CompilerOptions::default();
 
     // Determine the tsconfig file path and base directory
     let (tsconfig_path, base_dir) = if project_path.is_dir() {
@@ -101,86 +107,41 @@ pub fn read_configuration(
         };
     };
 
-    // Parse tsconfig.json
+    // Parse tsconfig.json and every config it extends
     if tsconfig_path.exists() {
-        match std::fs::read_to_string(&tsconfig_path) {
-            Ok(content) => {
-                // Strip comments for JSON5 compatibility (simplified)
-                let content = strip_json_comments(&content);
-                match serde_json::from_str::<serde_json::Value>(&content) {
-                    Ok(config) => {
-                        // Get include patterns (default to ["**/*.ts"])
-                        let include_patterns: Vec<String> = config
-                            .get("include")
-                            .and_then(|v| v.as_array())
-                            .map(|arr| {
-                                arr.iter()
-                                    .filter_map(|v| v.as_str().map(String::from))
-                                    .collect()
-                            })
-                            .unwrap_or_else(|| vec!["**/*.ts".to_string()]);
-
-                        // Get exclude patterns (default to ["node_modules"])
-                        let exclude_patterns: Vec<String> = config
-                            .get("exclude")
-                            .and_then(|v| v.as_array())
-                            .map(|arr| {
-                                arr.iter()
-                                    .filter_map(|v| v.as_str().map(String::from))
-                                    .collect()
-                            })
-                            .unwrap_or_else(|| vec!["**/node_modules/**".to_string()]);
-
-                        // Get explicit files list if specified
-                        let files: Vec<String> = config
-                            .get("files")
-                            .and_then(|v| v.as_array())
-                            .map(|arr| {
-                                arr.iter()
-                                    .filter_map(|v| v.as_str().map(String::from))
-                                    .collect()
-                            })
-                            .unwrap_or_default();
-
-                        // If files is specified, use it; otherwise glob from include patterns
-                        if !files.is_empty() {
-                            for file in files {
-                                let file_path = base_dir.join(&file);
-                                if file_path.exists() {
-                                    root_names.push(file_path.to_string_lossy().to_string());
-                                }
-                            }
-                        } else {
-                            // Use glob to find files matching include patterns
-                            let discovered =
-                                discover_files(&base_dir, &include_patterns, &exclude_patterns);
-                            root_names = discovered;
-                        }
-
-                        println!("Discovered {} TypeScript files", root_names.len());
-                    }
-                    Err(e) => {
-                        errors.push(Diagnostic {
-                            category: DiagnosticCategory::Error,
-                            code: -1,
-                            message: format!("Failed to parse tsconfig.json: {}", e),
-                            file: Some(tsconfig_path.to_string_lossy().to_string()),
-                            start: None,
-                            length: None,
-                        });
+        if let Some(resolved) = load_tsconfig(&tsconfig_path, &mut errors) {
+            options = resolved.options;
+            options.project = Some(tsconfig_path.to_string_lossy().to_string());
+            options.base_path = Some(base_dir.to_string_lossy().to_string());
+
+            if let Some(files) = resolved.files.filter(|f| !f.specs.is_empty()) {
+                // If files is specified, use it; otherwise glob from include patterns
+                for file in &files.specs {
+                    let file_path = files.base_dir.join(file);
+                    if file_path.exists() {
+                        root_names.push(file_path.to_string_lossy().to_string());
                     }
                 }
-            }
-            Err(e) => {
-                errors.push(Diagnostic {
-                    category: DiagnosticCategory::Error,
-                    code: -1,
-                    message: format!("Failed to read tsconfig.json: {}", e),
-                    file: Some(tsconfig_path.to_string_lossy().to_string()),
-                    start: None,
-                    length: None,
+            } else {
+                // Include defaults to all TypeScript files next to the root tsconfig
+                let include = resolved.include.unwrap_or_else(|| ConfigFileSpecs {
+                    base_dir: base_dir.clone(),
+                    specs: vec!["**/*.ts".to_string()],
                 });
+                // Exclude defaults to node_modules
+                let exclude = resolved.exclude.unwrap_or_else(|| ConfigFileSpecs {
+                    base_dir: base_dir.clone(),
+                    specs: vec!["**/node_modules/**".to_string()],
+                });
+                root_names = discover_files(
+                    &include.base_dir,
+                    &include.specs,
+                    &exclude.base_dir,
+                    &exclude.specs,
+                );
             }
+
+            println!("Discovered {} TypeScript files", root_names.len());
         }
     } else {
         errors.push(Diagnostic {
@@ -193,42 +154,114 @@ pub fn read_configuration(
         });
     }
 
+    // Command-line options take precedence over the tsconfig.
+    if let Some(cmd_options) = &cmd_options {
+        overlay_options(&mut options, cmd_options);
+    }
+
+    if options.extended_diagnostics.is_some() && options.strict_templates != Some(true) {
+        errors.push(Diagnostic {
+            category: DiagnosticCategory::Error,
+            code: ErrorCode::ConfigExtendedDiagnosticsImpliesStrictTemplates as i32,
+            message: "Angular compiler option \"extendedDiagnostics\" is configured, however \"strictTemplates\" is disabled. Using \"extendedDiagnostics\" requires that \"strictTemplates\" is also enabled.".to_string(),
+            file: options.project.clone(),
+            start: None,
+            length: None,
+        });
+    }
+
     ParsedConfiguration {
         project: project.to_string(),
         root_names,
-        options: cmd_options.unwrap_or_default(),
+        options,
         errors,
         emit_flags: EmitFlags::DEFAULT,
     }
 }
 
-/// Strip JSON comments (simple implementation for single-line comments)
-fn strip_json_comments(input: &str) -> String {
-    let mut result = String::new();
-    // let in_string = false;
-    // let prev_char = '\0';
-
-    for line in input.lines() {
-        let trimmed = line.trim();
-        // Skip lines that start with // or /*
-        if !trimmed.starts_with("//") && !trimmed.starts_with("/*") {
-            result.push_str(line);
-            result.push('\n');
-        }
+/// The category of a compiler diagnostic as reported to the caller.
+fn diagnostic_category(category: ts::DiagnosticCategory) -> DiagnosticCategory {
+    match category {
+        ts::DiagnosticCategory::Warning => DiagnosticCategory::Warning,
+        ts::DiagnosticCategory::Error => DiagnosticCategory::Error,
+        ts::DiagnosticCategory::Suggestion => DiagnosticCategory::Suggestion,
+        ts::DiagnosticCategory::Message => DiagnosticCategory::Message,
+    }
+}
+
+/// A diagnostic of `NgCompiler` as reported to the caller.
+pub(crate) fn compiler_diagnostic(d: crate::ngtsc::core::Diagnostic) -> Diagnostic {
+    Diagnostic {
+        category: diagnostic_category(d.category),
+        code: d.code as i32,
+        message: d.message,
+        file: d.file.map(|p| p.to_string_lossy().to_string()),
+        start: d.start,
+        length: d.length,
+    }
+}
+
+/// Map the parsed compiler options onto the options understood by `NgCompiler`.
+pub(crate) fn to_ng_compiler_options(options: &CompilerOptions) -> NgCompilerOptions {
+    let project = options.project.clone().unwrap_or_default();
+
+    // Default to rust-output next to the tsconfig if no outDir is specified
+    let out_dir = options.out_dir.clone().or_else(|| {
+        options.project.as_ref().map(|p| {
+            let base_dir = Path::new(p).parent().unwrap_or(Path::new("."));
+            base_dir.join("rust-output").to_string_lossy().to_string()
+        })
+    });
+
+    NgCompilerOptions {
+        project,
+        strict_templates: options.strict_templates.unwrap_or_default(),
+        extended_diagnostics: options.extended_diagnostics.clone(),
+        strict_injection_parameters: options.strict_injection_parameters.unwrap_or_default(),
+        skip_template_codegen: options.skip_template_codegen.unwrap_or_default(),
+        flat_module_out_file: options.flat_module_out_file.clone(),
+        out_dir,
+        root_dir: options.root_dir.clone(),
+        source_map: options.source_map.unwrap_or_default(),
+        inline_source_map: options.inline_source_map.unwrap_or_default(),
+        inline_sources: options.inline_sources.unwrap_or_default(),
+        declaration: options.declaration.unwrap_or_default(),
+        compilation_mode: match options.compilation_mode.unwrap_or_default() {
+            CompilationMode::Full => NgCompilationMode::Full,
+            CompilationMode::Partial => NgCompilationMode::Partial,
+            CompilationMode::Local => NgCompilationMode::Local,
+        },
+        i18n_in_file: options.i18n_in_file.clone(),
+        i18n_in_files: options.i18n_in_files.clone().unwrap_or_default(),
+        i18n_in_format: options.i18n_in_format.clone(),
+        i18n_in_locale: options.i18n_in_locale.clone(),
+        i18n_in_missing_translations: options.i18n_in_missing_translations.clone(),
+        enable_template_source_locations: options
+            .enable_template_source_locations
+            .unwrap_or_default(),
     }
-    result
 }
 
 /// Discover files matching include patterns and excluding exclude patterns
 fn discover_files(
     base_dir: &std::path::Path,
     include: &[String],
+    exclude_base_dir: &std::path::Path,
     exclude: &[String],
 ) -> Vec<String> {
     let mut files = Vec::new();
 
     for pattern in include {
-        let full_pattern = base_dir.join(pattern);
+        // A pattern without a file extension matches TypeScript files only
+        let pattern = if Path::new(pattern).extension().is_some() {
+            pattern.clone()
+        } else {
+            match pattern.trim_end_matches(|c| c == '/' || c == '*') {
+                "" => "**/*.ts".to_string(),
+                dir => format!("{}/**/*.ts", dir),
+            }
+        };
+        let full_pattern = base_dir.join(&pattern);
         let pattern_str = full_pattern.to_string_lossy();
 
         match glob::glob(&pattern_str) {
@@ -247,7 +280,7 @@ fn discover_files(
                                     return true;
                                 }
 
-                                let excl_pattern = base_dir.join(excl);
+                                let excl_pattern = exclude_base_dir.join(excl);
                                 let excl_str = excl_pattern.to_string_lossy();
                                 match glob::Pattern::new(&excl_str) {
                                     Ok(p) => p.matches(&path_str),
@@ -255,7 +288,7 @@ fn discover_files(
                                 }
                             });
 
-                            if !should_exclude && path.is_file() {
+                            if !should_exclude && path.is_file() && !files.contains(&path_str) {
                                 files.push(path_str);
                             }
                         }
@@ -273,13 +306,17 @@ fn discover_files(
 }
 
 /// Perform compilation with full options.
+///
+/// With an `old_program`, files that did not change reuse its analysis. With
+/// `affected_files` as well, only those files are analyzed again and emitted.
 pub fn perform_compilation(
     root_names: Vec<String>,
-    _options: CompilerOptions,
+    options: CompilerOptions,
     _emit_flags: EmitFlags,
-    _old_program: Option<Program>,
+    old_program: Option<Program>,
     _custom_transformers: Option<crate::transformers::api::CustomTransformers>,
-    _modified_resource_files: Option<HashSet<String>>,
+    modified_resource_files: Option<HashSet<String>>,
+    affected_files: Option<HashSet<String>>,
 ) -> CompilationResult {
     println!(
         "Performing compilation with {} root files...",
@@ -287,8 +324,18 @@ pub fn perform_compilation(
     );
 
     let fs = NodeJSFileSystem::new();
-    let ng_options = NgCompilerOptions::default();
-    let mut program = NgtscProgram::new(root_names.clone(), ng_options, &fs);
+    let ng_options = to_ng_compiler_options(&options);
+    let mut program = match old_program {
+        Some(old_program) => NgtscProgram::incremental(
+            root_names.clone(),
+            ng_options,
+            &fs,
+            old_program.analysis,
+            modified_resource_files.unwrap_or_default(),
+            affected_files.map(|files| files.into_iter().map(PathBuf::from).collect()),
+        ),
+        None => NgtscProgram::new(root_names.clone(), ng_options, &fs),
+    };
 
     let mut diagnostics = Vec::new();
 
@@ -310,27 +357,13 @@ pub fn perform_compilation(
 
     // Aggregate diagnostics from analysis
     for d in program.get_diagnostics() {
-        diagnostics.push(Diagnostic {
-            category: DiagnosticCategory::Error,
-            code: d.code as i32,
-            message: d.message,
-            file: d.file.map(|p| p.to_string_lossy().to_string()),
-            start: d.start,
-            length: d.length,
-        });
+        diagnostics.push(compiler_diagnostic(d));
     }
 
     match program.emit() {
         Ok(emit_diagnostics) => {
             for d in emit_diagnostics {
-                diagnostics.push(Diagnostic {
-                    category: DiagnosticCategory::Warning, // Default for now
-                    code: d.code as i32,
-                    message: d.message,
-                    file: d.file.map(|p| p.to_string_lossy().to_string()),
-                    start: d.start,
-                    length: d.length,
-                });
+                diagnostics.push(compiler_diagnostic(d));
             }
         }
         Err(e) => {
@@ -353,6 +386,7 @@ pub fn perform_compilation(
         diagnostics,
         program: Some(Program {
             source_files: root_names,
+            analysis: program.take_analysis(),
         }),
     }
 }
@@ -378,33 +412,7 @@ pub fn perform_compilation_simple(
             }
         }
 
-        // Get outDir from tsconfig if available
-        let mut opts = NgCompilerOptions::default();
-
-        // Parse tsconfig again to get compilerOptions
-        let tsconfig_path = Path::new(p);
-        if let Ok(content) = std::fs::read_to_string(tsconfig_path) {
-            let content = strip_json_comments(&content);
-            if let Ok(config) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(compiler_opts) = config.get("compilerOptions") {
-                    if let Some(out_dir) = compiler_opts.get("outDir").and_then(|v| v.as_str()) {
-                        // Resolve outDir relative to tsconfig location
-                        let base_dir = tsconfig_path.parent().unwrap_or(Path::new("."));
-                        let resolved_out_dir = base_dir.join(out_dir);
-                        opts.out_dir = Some(resolved_out_dir.to_string_lossy().to_string());
-                    }
-                }
-            }
-        }
-
-        // Default to rust-output if no outDir is specified
-        if opts.out_dir.is_none() {
-            let base_dir = Path::new(p).parent().unwrap_or(Path::new("."));
-            let resolved = base_dir.join("rust-output");
-            opts.out_dir = Some(resolved.to_string_lossy().to_string());
-        }
-
-        (parsed.root_names, opts)
+        (parsed.root_names, to_ng_compiler_options(&parsed.options))
     } else {
         (vec![], NgCompilerOptions::default())
     };
@@ -424,27 +432,13 @@ pub fn perform_compilation_simple(
 
     // Collect diagnostics from analysis
     for d in program.get_diagnostics() {
-        diagnostics.push(Diagnostic {
-            category: DiagnosticCategory::Error,
-            code: d.code as i32,
-            message: d.message,
-            file: d.file.map(|p| p.to_string_lossy().to_string()),
-            start: d.start,
-            length: d.length,
-        });
+        diagnostics.push(compiler_diagnostic(d));
     }
 
     match program.emit() {
         Ok(emit_diagnostics) => {
             for d in emit_diagnostics {
-                diagnostics.push(Diagnostic {
-                    category: DiagnosticCategory::Warning,
-                    code: d.code as i32,
-                    message: d.message,
-                    file: d.file.map(|p| p.to_string_lossy().to_string()),
-                    start: d.start,
-                    length: d.length,
-                });
+                diagnostics.push(compiler_diagnostic(d));
             }
         }
         Err(e) => {
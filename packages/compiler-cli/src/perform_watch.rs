@@ -3,9 +3,20 @@
 //! Corresponds to packages/compiler-cli/src/perform_watch.ts
 //! Watch mode compilation with incremental rebuilds.
 
+use crate::ngtsc::incremental::{DependencyTracker, FileDependencyGraph};
+use crate::ngtsc::metadata::{DecoratorMetadata, MetadataReader, OxcMetadataReader};
+use crate::perform_compile::{perform_compilation, read_configuration, EmitFlags, Program};
 use crate::transformers::api::{CompilerOptions, Diagnostic};
-use std::collections::HashSet;
-use std::time::Duration;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::Statement;
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Extensions of files whose changes trigger a rebuild.
+const WATCHED_EXTENSIONS: &[&str] = &["ts", "html", "css", "scss", "sass", "less"];
 
 /// Watch mode configuration.
 #[derive(Debug, Clone)]
@@ -14,6 +25,9 @@ pub struct WatchOptions {
     pub project: String,
     /// Poll interval for file changes (if using polling).
     pub poll_interval: Duration,
+    /// Quiet period after the last change before a rebuild starts, so that a burst of
+    /// saves results in a single rebuild.
+    pub debounce: Duration,
     /// Files to watch.
     pub watched_files: HashSet<String>,
 }
@@ -23,6 +37,7 @@ impl Default for WatchOptions {
         Self {
             project: "tsconfig.json".to_string(),
             poll_interval: Duration::from_millis(250),
+            debounce: Duration::from_millis(250),
             watched_files: HashSet::new(),
         }
     }
@@ -42,7 +57,7 @@ pub struct WatchResult {
 }
 
 /// File change event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FileChangeEvent {
     /// File was created.
     Created(String),
@@ -52,6 +67,17 @@ pub enum FileChangeEvent {
     Deleted(String),
 }
 
+impl FileChangeEvent {
+    /// Path of the changed file.
+    pub fn path(&self) -> &str {
+        match self {
+            FileChangeEvent::Created(f)
+            | FileChangeEvent::Modified(f)
+            | FileChangeEvent::Deleted(f) => f,
+        }
+    }
+}
+
 /// Perform watch host for watch mode.
 pub struct PerformWatchHost<F>
 where
@@ -61,7 +87,7 @@ where
     pub project: String,
     /// Report diagnostics callback.
     pub report_diagnostics: F,
-    /// Compiler options.
+    /// Compiler options given on the command line, layered over the tsconfig.
     pub options: CompilerOptions,
 }
 
@@ -82,24 +108,32 @@ where
 }
 
 /// Perform watch compilation.
+///
+/// Runs an initial build, then blocks watching the project for changes. Each burst of
+/// changes triggers a rebuild of the affected files, and the diagnostics of every cycle
+/// are passed to the host's `report_diagnostics` callback.
 pub fn perform_watch_compilation<F>(host: PerformWatchHost<F>) -> WatchResult
 where
     F: Fn(&[Diagnostic]),
 {
     println!("Starting watch mode for project: {}", host.project);
 
-    // In a real implementation, this would:
-    // 1. Do initial compilation
-    // 2. Set up file watchers
-    // 3. Run in a loop detecting changes and recompiling
+    let options = WatchOptions {
+        project: host.project.clone(),
+        ..Default::default()
+    };
+    let mut compiler = WatchCompiler::with_compiler_options(options, host.options.clone());
 
-    let first_compile_result = vec![];
+    let first_compile_result = compiler.start();
+    (host.report_diagnostics)(&first_compile_result);
+    println!("Compilation complete. Watching for file changes.");
 
-    WatchResult {
-        success: true,
-        diagnostics: Vec::new(),
-        changed_files: Vec::new(),
-        first_compile_result,
+    loop {
+        let changes = compiler.wait_for_changes();
+        println!("File change detected. Starting incremental compilation...");
+        let result = compiler.on_file_change(&changes);
+        (host.report_diagnostics)(&result.first_compile_result);
+        println!("Compilation complete. Watching for file changes.");
     }
 }
 
@@ -107,31 +141,79 @@ where
 pub struct WatchCompiler {
     /// Watch options.
     options: WatchOptions,
+    /// Compiler options given on the command line, layered over the tsconfig.
+    overrides: CompilerOptions,
+    /// Compiler options from the last read of the tsconfig.
+    compiler_options: CompilerOptions,
+    /// Root files of the program, from the last read of the tsconfig.
+    root_names: Vec<String>,
+    /// The tsconfig the root files were read from.
+    config_file: Option<String>,
     /// Currently watched files.
     watched: HashSet<String>,
     /// File modification times.
-    file_times: std::collections::HashMap<String, std::time::SystemTime>,
+    file_times: HashMap<String, SystemTime>,
+    /// Directories holding root files; their modification time changes when files are
+    /// added or removed.
+    dir_times: HashMap<String, SystemTime>,
+    /// Imports and component resources of every root file.
+    dependency_graph: FileDependencyGraph,
+    /// The program of the last compilation, whose analysis rebuilds reuse.
+    program: Option<Program>,
 }
 
 impl WatchCompiler {
     pub fn new(options: WatchOptions) -> Self {
-        Self {
+        Self::with_compiler_options(options, CompilerOptions::default())
+    }
+
+    /// A watch compiler that layers `overrides` over the tsconfig every time it is read.
+    pub fn with_compiler_options(options: WatchOptions, overrides: CompilerOptions) -> Self {
+        let mut compiler = Self {
             options,
+            compiler_options: overrides.clone(),
+            overrides,
+            root_names: Vec::new(),
+            config_file: None,
             watched: HashSet::new(),
-            file_times: std::collections::HashMap::new(),
+            file_times: HashMap::new(),
+            dir_times: HashMap::new(),
+            dependency_graph: FileDependencyGraph::new(),
+            program: None,
+        };
+        for file in compiler.options.watched_files.clone() {
+            compiler.add_file(file);
         }
+        compiler
     }
 
-    /// Start watching.
-    pub fn start(&mut self) {
+    /// Start watching. Performs the initial compilation and returns its diagnostics.
+    pub fn start(&mut self) -> Vec<Diagnostic> {
         println!("Starting watch mode...");
-        self.initial_compile();
+        self.initial_compile()
     }
 
     /// Perform initial compilation.
-    fn initial_compile(&mut self) {
+    fn initial_compile(&mut self) -> Vec<Diagnostic> {
         println!("Performing initial compilation...");
-        // Use perform_compile::perform_compilation
+        let mut diagnostics = self.reload_configuration();
+        if diagnostics.is_empty() {
+            for file in self.root_names.clone() {
+                self.scan_dependencies(&file);
+            }
+            diagnostics.extend(self.compile(None, None));
+        }
+        diagnostics
+    }
+
+    /// The files the watched program consists of.
+    pub fn root_names(&self) -> &[String] {
+        &self.root_names
+    }
+
+    /// Imports and component resources recorded for the watched files.
+    pub fn dependency_graph(&self) -> &FileDependencyGraph {
+        &self.dependency_graph
     }
 
     /// Check for file changes.
@@ -158,50 +240,520 @@ impl WatchCompiler {
             }
         }
 
+        // Files being added or removed show up as a change of their directory.
+        let mut dirs_changed = false;
+        for (dir, prev_time) in self.dir_times.iter_mut() {
+            match std::fs::metadata(dir).and_then(|m| m.modified()) {
+                Ok(modified) if modified > *prev_time => {
+                    *prev_time = modified;
+                    dirs_changed = true;
+                }
+                Ok(_) => {}
+                Err(_) => dirs_changed = true,
+            }
+        }
+        if let Some(config_file) = self.config_file.clone().filter(|_| dirs_changed) {
+            let previous: HashSet<String> = self.root_names.iter().cloned().collect();
+            if self.reload_configuration().is_empty() {
+                for file in &self.root_names {
+                    if !previous.contains(file) {
+                        changes.push(FileChangeEvent::Created(file.clone()));
+                    }
+                }
+                for file in previous {
+                    if !self.root_names.contains(&file) && !changes.iter().any(|c| c.path() == file)
+                    {
+                        changes.push(FileChangeEvent::Deleted(file));
+                    }
+                }
+            } else if !changes.iter().any(|c| c.path() == config_file) {
+                // Handling a change of the tsconfig reads it again and reports its errors.
+                changes.push(FileChangeEvent::Modified(config_file));
+            }
+        }
+
         changes
     }
 
+    /// Block until files change, then wait for the burst of changes to settle.
+    pub fn wait_for_changes(&mut self) -> Vec<FileChangeEvent> {
+        let mut changes = Vec::new();
+        let mut last_change = Instant::now();
+
+        loop {
+            std::thread::sleep(self.options.poll_interval.min(self.options.debounce));
+            let new_changes = self.check_for_changes();
+            if !new_changes.is_empty() {
+                last_change = Instant::now();
+                for change in new_changes {
+                    changes.retain(|c: &FileChangeEvent| c.path() != change.path());
+                    changes.push(change);
+                }
+            } else if !changes.is_empty() && last_change.elapsed() >= self.options.debounce {
+                return changes;
+            }
+        }
+    }
+
+    /// Root files that must be rebuilt for the given changes.
+    ///
+    /// A changed file affects itself and every file that transitively imports it; a
+    /// changed template or stylesheet affects the components using it and their
+    /// importers. A change to the tsconfig affects every file.
+    pub fn affected_files(&self, changes: &[FileChangeEvent]) -> Vec<String> {
+        if changes
+            .iter()
+            .any(|c| Some(c.path()) == self.config_file.as_deref())
+        {
+            return self.root_names.clone();
+        }
+
+        let mut affected = HashSet::new();
+        for change in changes {
+            affected.insert(change.path().to_string());
+            affected.extend(
+                self.dependency_graph
+                    .get_transitive_dependents(change.path()),
+            );
+        }
+        self.root_names
+            .iter()
+            .filter(|f| affected.contains(*f))
+            .cloned()
+            .collect()
+    }
+
     /// Handle file changes (incremental recompile).
     pub fn on_file_change(&mut self, changes: &[FileChangeEvent]) -> WatchResult {
         println!("Files changed, recompiling...");
 
-        let changed_files: Vec<String> = changes
+        let changed_files: Vec<String> = changes.iter().map(|c| c.path().to_string()).collect();
+
+        let mut diagnostics = Vec::new();
+        if changes
             .iter()
-            .map(|c| match c {
-                FileChangeEvent::Created(f)
-                | FileChangeEvent::Modified(f)
-                | FileChangeEvent::Deleted(f) => f.clone(),
-            })
+            .any(|c| Some(c.path()) == self.config_file.as_deref())
+        {
+            diagnostics.extend(self.reload_configuration());
+            // The analysis was made with the previous options.
+            self.program = None;
+        }
+
+        // Re-scan changed sources before computing what they affect, so that new
+        // imports and resources are taken into account.
+        for change in changes {
+            match change {
+                FileChangeEvent::Deleted(file) => {
+                    self.dependency_graph.clear_dependencies_of(file);
+                    self.watched.remove(file);
+                }
+                FileChangeEvent::Created(file) | FileChangeEvent::Modified(file) => {
+                    if self.root_names.contains(file) {
+                        self.scan_dependencies(file);
+                    }
+                }
+            }
+        }
+
+        let affected = self.affected_files(changes);
+        let modified_resources: HashSet<String> = changed_files
+            .iter()
+            .filter(|f| !f.ends_with(".ts"))
+            .cloned()
             .collect();
 
-        // Would trigger incremental compilation here
+        if diagnostics.is_empty() && !affected.is_empty() {
+            println!("Rebuilding {} affected file(s)", affected.len());
+            diagnostics.extend(self.compile(
+                Some(modified_resources),
+                Some(affected.into_iter().collect()),
+            ));
+        }
 
         WatchResult {
-            success: true,
+            success: !diagnostics
+                .iter()
+                .any(|d| d.category == crate::transformers::api::DiagnosticCategory::Error),
             diagnostics: Vec::new(),
             changed_files,
-            first_compile_result: Vec::new(),
+            first_compile_result: diagnostics,
         }
     }
 
     /// Add a file to watch list.
     pub fn add_file(&mut self, file: impl Into<String>) {
-        self.watched.insert(file.into());
+        let file = file.into();
+        if let Ok(modified) = std::fs::metadata(&file).and_then(|m| m.modified()) {
+            self.file_times.entry(file.clone()).or_insert(modified);
+        }
+        self.watched.insert(file);
+    }
+
+    /// Re-read the tsconfig, updating the root files and the watched directories.
+    fn reload_configuration(&mut self) -> Vec<Diagnostic> {
+        let config = read_configuration(&self.options.project, Some(self.overrides.clone()));
+        if !config.errors.is_empty() {
+            return config.errors;
+        }
+
+        self.config_file = config.options.project.clone();
+        if let Some(config_file) = self.config_file.clone() {
+            self.add_file(config_file);
+        }
+        self.compiler_options = config.options;
+        self.root_names = config.root_names;
+
+        for file in self.root_names.clone() {
+            if let Some(dir) = Path::new(&file).parent() {
+                let dir = dir.to_string_lossy().to_string();
+                if let Ok(modified) = std::fs::metadata(&dir).and_then(|m| m.modified()) {
+                    self.dir_times.entry(dir).or_insert(modified);
+                }
+            }
+            if !self.watched.contains(&file) {
+                self.add_file(file);
+            }
+        }
+        Vec::new()
+    }
+
+    /// Record the imports and component resources of a file, and watch them.
+    fn scan_dependencies(&mut self, file: &str) {
+        self.dependency_graph.clear_dependencies_of(file);
+        for dep in collect_file_dependencies(Path::new(file)) {
+            let dep = dep.to_string_lossy().to_string();
+            self.dependency_graph.add_dependency(file, &dep);
+            let watched_ext = Path::new(&dep)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| WATCHED_EXTENSIONS.contains(&e));
+            if watched_ext && !self.watched.contains(&dep) {
+                self.add_file(dep);
+            }
+        }
+    }
+
+    /// Compile the program. With `affected_files`, only those files are analyzed again
+    /// and emitted, and the others reuse the analysis of the last compilation.
+    fn compile(
+        &mut self,
+        modified_resource_files: Option<HashSet<String>>,
+        affected_files: Option<HashSet<String>>,
+    ) -> Vec<Diagnostic> {
+        let result = perform_compilation(
+            self.root_names.clone(),
+            self.compiler_options.clone(),
+            EmitFlags::DEFAULT,
+            self.program.take(),
+            None,
+            modified_resource_files,
+            affected_files,
+        );
+        self.program = result.program;
+        result.diagnostics
     }
 }
 
-/// Main entry point for watch mode (simple version).
-pub fn perform_watch_compilation_simple(project: &str) -> i32 {
-    let options = WatchOptions {
-        project: project.to_string(),
-        ..Default::default()
+/// Collect the relative imports and the `templateUrl`/`styleUrls` resources of a file.
+fn collect_file_dependencies(file: &Path) -> Vec<PathBuf> {
+    let Ok(content) = std::fs::read_to_string(file) else {
+        return Vec::new();
     };
+    let dir = file.parent().unwrap_or(Path::new("."));
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(file).unwrap_or_default();
+    let ret = Parser::new(&allocator, &content, source_type).parse();
 
-    let mut compiler = WatchCompiler::new(options);
-    compiler.start();
+    let mut dependencies = Vec::new();
+    for statement in &ret.program.body {
+        let source = match statement {
+            Statement::ImportDeclaration(decl) => Some(decl.source.value.as_str()),
+            Statement::ExportNamedDeclaration(decl) => {
+                decl.source.as_ref().map(|s| s.value.as_str())
+            }
+            Statement::ExportAllDeclaration(decl) => Some(decl.source.value.as_str()),
+            _ => None,
+        };
+        if let Some(resolved) = source
+            .filter(|s| s.starts_with('.'))
+            .and_then(|s| resolve_relative_import(dir, s))
+        {
+            dependencies.push(resolved);
+        }
+    }
 
-    // In a real implementation, this would run a watch loop
-    println!("Watch mode would run here...");
+    for metadata in OxcMetadataReader.get_directive_metadata(&ret.program, file) {
+        if let DecoratorMetadata::Directive(dir_meta) = metadata {
+            if let Some(component) = dir_meta.component {
+                dependencies.extend(component.template_url.iter().map(|url| dir.join(url)));
+                dependencies.extend(
+                    component
+                        .style_urls
+                        .iter()
+                        .flatten()
+                        .chain(component.style_url.iter())
+                        .map(|url| dir.join(url)),
+                );
+            }
+        }
+    }
+    dependencies
+}
+
+/// Resolve a relative module specifier to a TypeScript file.
+fn resolve_relative_import(dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let base = dir.join(specifier);
+    let mut with_ts = base.clone().into_os_string();
+    with_ts.push(".ts");
+    [PathBuf::from(with_ts), base.join("index.ts"), base]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
 
+/// Main entry point for watch mode (simple version).
+pub fn perform_watch_compilation_simple(project: &str) -> i32 {
+    let host = create_perform_watch_host(
+        project,
+        |diagnostics| {
+            for diag in diagnostics {
+                eprintln!("Error: {}", diag.message);
+            }
+        },
+        CompilerOptions::default(),
+    );
+    perform_watch_compilation(host);
     0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ngc_watch_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn collects_imports_and_component_resources() {
+        let dir = temp_project("deps");
+        std::fs::write(dir.join("util.ts"), "export const x = 1;").unwrap();
+        std::fs::write(dir.join("app.component.html"), "<p></p>").unwrap();
+        std::fs::write(
+            dir.join("app.component.ts"),
+            r#"
+                import { Component } from '@angular/core';
+                import { x } from './util';
+
+                @Component({
+                    selector: 'app-root',
+                    templateUrl: './app.component.html',
+                    styleUrls: ['./app.component.css'],
+                })
+                export class AppComponent {}
+            "#,
+        )
+        .unwrap();
+
+        let deps = collect_file_dependencies(&dir.join("app.component.ts"));
+        assert!(deps.contains(&dir.join("util.ts")));
+        assert!(deps.contains(&dir.join("./app.component.html")));
+        assert!(deps.contains(&dir.join("./app.component.css")));
+        assert!(!deps
+            .iter()
+            .any(|d| d.to_string_lossy().contains("@angular")));
+    }
+
+    #[test]
+    fn template_change_affects_component_and_importers() {
+        let mut compiler = WatchCompiler::new(WatchOptions::default());
+        compiler.root_names = vec![
+            "/p/app.component.ts".to_string(),
+            "/p/main.ts".to_string(),
+            "/p/other.ts".to_string(),
+        ];
+        compiler
+            .dependency_graph
+            .add_dependency("/p/app.component.ts", "/p/app.component.html");
+        compiler
+            .dependency_graph
+            .add_dependency("/p/main.ts", "/p/app.component.ts");
+
+        let mut affected = compiler.affected_files(&[FileChangeEvent::Modified(
+            "/p/app.component.html".to_string(),
+        )]);
+        affected.sort();
+        assert_eq!(affected, vec!["/p/app.component.ts", "/p/main.ts"]);
+    }
+
+    #[test]
+    fn rebuilds_compile_every_root_file() {
+        let dir = temp_project("rebuild");
+        std::fs::write(
+            dir.join("tsconfig.json"),
+            r#"{ "compilerOptions": { "outDir": "./dist" }, "files": ["a.ts", "b.ts"] }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+        std::fs::write(dir.join("b.ts"), "export const b = 2;").unwrap();
+
+        let mut compiler = WatchCompiler::new(WatchOptions {
+            project: dir.join("tsconfig.json").to_string_lossy().to_string(),
+            ..Default::default()
+        });
+        assert!(compiler.start().is_empty());
+
+        let a = dir.join("a.ts");
+        std::fs::write(&a, "export const a = 3;").unwrap();
+        let result =
+            compiler.on_file_change(&[FileChangeEvent::Modified(a.to_string_lossy().to_string())]);
+        assert!(result.success, "{:?}", result.first_compile_result);
+
+        let program = compiler.program.as_ref().unwrap();
+        assert_eq!(program.source_files, compiler.root_names);
+        assert!(program.analysis.contains(&dir.join("b.ts")));
+    }
+
+    fn touch_later(path: &Path) {
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::open(path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+    }
+
+    #[test]
+    fn rebuilds_emit_only_affected_files() {
+        let dir = temp_project("affected");
+        std::fs::write(
+            dir.join("tsconfig.json"),
+            r#"{ "compilerOptions": { "outDir": "./dist" }, "files": ["a.ts", "b.ts"] }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+        std::fs::write(dir.join("b.ts"), "export const b = 2;").unwrap();
+
+        let mut compiler = WatchCompiler::new(WatchOptions {
+            project: dir.join("tsconfig.json").to_string_lossy().to_string(),
+            ..Default::default()
+        });
+        assert!(compiler.start().is_empty());
+        std::fs::remove_file(dir.join("dist/b.js")).unwrap();
+
+        let a = dir.join("a.ts");
+        std::fs::write(&a, "export const a = 3;").unwrap();
+        let result =
+            compiler.on_file_change(&[FileChangeEvent::Modified(a.to_string_lossy().to_string())]);
+        assert!(result.success, "{:?}", result.first_compile_result);
+
+        let a_js = std::fs::read_to_string(dir.join("dist/a.js")).unwrap();
+        assert!(a_js.contains("a = 3"), "{}", a_js);
+        assert!(!dir.join("dist/b.js").exists());
+    }
+
+    #[test]
+    fn tsconfig_edits_apply_between_cycles() {
+        let dir = temp_project("config_edit");
+        let tsconfig = dir.join("tsconfig.json");
+        std::fs::write(
+            &tsconfig,
+            r#"{ "compilerOptions": { "outDir": "./dist", "declaration": true }, "files": ["a.ts"] }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+
+        let out_dir = dir.join("out").to_string_lossy().to_string();
+        let mut compiler = WatchCompiler::with_compiler_options(
+            WatchOptions {
+                project: tsconfig.to_string_lossy().to_string(),
+                ..Default::default()
+            },
+            CompilerOptions {
+                out_dir: Some(out_dir.clone()),
+                ..CompilerOptions::default()
+            },
+        );
+        assert!(compiler.start().is_empty());
+        assert_eq!(compiler.compiler_options.declaration, Some(true));
+
+        std::fs::write(
+            &tsconfig,
+            r#"{ "compilerOptions": { "outDir": "./dist", "declaration": false }, "files": ["a.ts"] }"#,
+        )
+        .unwrap();
+        let result = compiler.on_file_change(&[FileChangeEvent::Modified(
+            tsconfig.to_string_lossy().to_string(),
+        )]);
+        assert!(result.success, "{:?}", result.first_compile_result);
+        assert_eq!(compiler.compiler_options.declaration, Some(false));
+        assert_eq!(compiler.compiler_options.out_dir, Some(out_dir));
+    }
+
+    #[test]
+    fn reports_errors_of_a_tsconfig_read_for_added_files() {
+        let dir = temp_project("config_error");
+        let tsconfig = dir.join("tsconfig.json");
+        std::fs::write(
+            &tsconfig,
+            r#"{ "compilerOptions": { "outDir": "./dist" } }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+
+        let mut compiler = WatchCompiler::new(WatchOptions {
+            project: tsconfig.to_string_lossy().to_string(),
+            ..Default::default()
+        });
+        assert!(compiler.start().is_empty());
+        let config_file = compiler.config_file.clone().unwrap();
+        assert!(compiler.check_for_changes().is_empty());
+
+        // Break the tsconfig without it being seen as modified, then add a file.
+        let modified = std::fs::metadata(&tsconfig).unwrap().modified().unwrap();
+        std::fs::write(&tsconfig, r#"{ "compilerOptions": { "outDir": 1 } }"#).unwrap();
+        std::fs::File::open(&tsconfig)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        std::fs::write(dir.join("b.ts"), "export const b = 2;").unwrap();
+        touch_later(&dir);
+
+        let changes = compiler.check_for_changes();
+        assert_eq!(changes, vec![FileChangeEvent::Modified(config_file)]);
+        let result = compiler.on_file_change(&changes);
+        assert!(!result.success);
+        assert!(!result.first_compile_result.is_empty());
+    }
+
+    #[test]
+    fn detects_modified_and_deleted_files() {
+        let dir = temp_project("changes");
+        let file = dir.join("a.ts");
+        std::fs::write(&file, "export const a = 1;").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let mut compiler = WatchCompiler::new(WatchOptions::default());
+        compiler.add_file(file.clone());
+        assert!(compiler.check_for_changes().is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(
+            compiler.check_for_changes(),
+            vec![FileChangeEvent::Modified(file.clone())]
+        );
+
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(
+            compiler.check_for_changes(),
+            vec![FileChangeEvent::Deleted(file)]
+        );
+    }
+}
@@ -2,9 +2,13 @@
 //
 // Public API for Angular transformers.
 
+use crate::ngtsc::typecheck::extended::ExtendedDiagnosticsOptions;
+
 /// Compiler options for Angular compilation.
 #[derive(Debug, Clone, Default)]
 pub struct CompilerOptions {
+    /// Path to the tsconfig.json the options were read from.
+    pub project: Option<String>,
     /// Base URL for resolving module specifiers.
     pub base_url: Option<String>,
     /// Base path for the project.
@@ -14,23 +18,55 @@ pub struct CompilerOptions {
     /// Output directory for compiled files.
     pub out_dir: Option<String>,
     /// Enable source maps.
-    pub source_map: bool,
+    pub source_map: Option<bool>,
+    /// Emit source maps inline as data URLs.
+    pub inline_source_map: Option<bool>,
+    /// Embed the original sources in the source maps.
+    pub inline_sources: Option<bool>,
     /// Enable declaration file generation.
-    pub declaration: bool,
-    /// Strict null checks.
-    pub strict_null_checks: bool,
+    pub declaration: Option<bool>,
+    /// Enable all strict type-checking options.
+    pub strict: Option<bool>,
+    /// Strict null checks. Defaults to `strict`.
+    pub strict_null_checks: Option<bool>,
     /// Full template type check.
-    pub full_template_type_check: bool,
+    pub full_template_type_check: Option<bool>,
     /// Strict templates.
-    pub strict_templates: bool,
+    pub strict_templates: Option<bool>,
+    /// Categories of the extended template diagnostics. Requires strict templates.
+    pub extended_diagnostics: Option<ExtendedDiagnosticsOptions>,
+    /// Report an error for injection parameters that cannot be resolved.
+    pub strict_injection_parameters: Option<bool>,
+    /// Check access modifiers of inputs bound from templates.
+    pub strict_input_access_modifiers: Option<bool>,
+    /// Skip generating template code.
+    pub skip_template_codegen: Option<bool>,
+    /// Output file for a flat module index.
+    pub flat_module_out_file: Option<String>,
     /// Enable Ivy.
-    pub enable_ivy: bool,
-    /// Compilation mode.
-    pub compilation_mode: CompilationMode,
+    pub enable_ivy: Option<bool>,
+    /// Compilation mode. Defaults to full compilation.
+    pub compilation_mode: Option<CompilationMode>,
     /// i18n options.
     pub i18n: I18nOptions,
+    /// i18n output file for extracted messages.
+    pub i18n_out_file: Option<String>,
+    /// i18n output format for extracted messages.
+    pub i18n_out_format: Option<String>,
+    /// i18n source locale for extracted messages.
+    pub i18n_out_locale: Option<String>,
+    /// Use external ids for extracted messages.
+    pub i18n_use_external_ids: Option<bool>,
+    /// Render legacy message ids in `$localize` strings.
+    pub enable_i18n_legacy_message_id_format: Option<bool>,
+    /// Normalize line endings in ICU expressions.
+    pub i18n_normalize_line_endings_in_icus: Option<bool>,
+    /// Attach the template location of each element to it in development mode.
+    pub enable_template_source_locations: Option<bool>,
     /// i18n input file.
     pub i18n_in_file: Option<String>,
+    /// i18n input files, one per locale to emit.
+    pub i18n_in_files: Option<Vec<String>>,
     /// i18n input format.
     pub i18n_in_format: Option<String>,
     /// i18n input locale.
@@ -43,6 +79,13 @@ pub struct CompilerOptions {
     pub new_line: Option<NewLineKind>,
 }
 
+impl CompilerOptions {
+    /// Whether null checks are strict, either explicitly or through `strict`.
+    pub fn uses_strict_null_checks(&self) -> bool {
+        self.strict_null_checks.or(self.strict).unwrap_or(false)
+    }
+}
+
 /// New line kind.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NewLineKind {
@@ -80,6 +123,7 @@ pub enum I18nFormat {
     Xlf2,
     Xmb,
     Json,
+    Arb,
 }
 
 /// Diagnostic message.
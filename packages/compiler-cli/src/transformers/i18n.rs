@@ -2,109 +2,79 @@
 //
 // Internationalization support for transformers.
 
-use super::api::I18nFormat;
+use super::api::CompilerOptions;
+use angular_compiler::i18n::message_bundle::MessageBundle;
+use angular_compiler::i18n::serializers::{Arb, Json, Serializer, Xliff, Xliff2, Xmb};
+use std::path::{Path, PathBuf};
 
-/// i18n message bundle loader.
-pub struct MessageLoader {
-    /// Loaded messages by ID.
-    messages: std::collections::HashMap<String, String>,
-    /// Locale.
-    locale: String,
-}
-
-impl MessageLoader {
-    pub fn new(locale: impl Into<String>) -> Self {
-        Self {
-            messages: std::collections::HashMap::new(),
-            locale: locale.into(),
-        }
-    }
-
-    /// Load messages from a file.
-    pub fn load(&mut self, content: &str, format: I18nFormat) -> Result<(), String> {
-        match format {
-            I18nFormat::Xlf | I18nFormat::Xlf2 => self.load_xliff(content),
-            I18nFormat::Xmb => self.load_xmb(content),
-            I18nFormat::Json => self.load_json(content),
-        }
-    }
-
-    /// Get a translated message.
-    pub fn get(&self, id: &str) -> Option<&str> {
-        self.messages.get(id).map(|s| s.as_str())
-    }
-
-    /// Get locale.
-    pub fn locale(&self) -> &str {
-        &self.locale
-    }
-
-    fn load_xliff(&mut self, _content: &str) -> Result<(), String> {
-        // Simplified XLIFF parsing
-        Ok(())
-    }
-
-    fn load_xmb(&mut self, _content: &str) -> Result<(), String> {
-        // Simplified XMB parsing
-        Ok(())
-    }
-
-    fn load_json(&mut self, content: &str) -> Result<(), String> {
-        // Simple JSON parsing
-        for line in content.lines() {
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim().trim_matches('"');
-                let value = value.trim().trim_matches(',').trim_matches('"');
-                self.messages.insert(key.to_string(), value.to_string());
-            }
-        }
-        Ok(())
+/// The file extension of the messages written in the given format.
+pub fn i18n_get_extension(format_name: &str) -> Result<&'static str, String> {
+    match format_name.to_lowercase().as_str() {
+        "xmb" => Ok("xmb"),
+        "json" => Ok("json"),
+        "arb" => Ok("arb"),
+        "xlf" | "xlif" | "xliff" | "xlf2" | "xliff2" => Ok("xlf"),
+        _ => Err(format!("Unsupported format \"{}\"", format_name)),
     }
 }
 
-/// i18n inlining transformer.
-pub struct I18nInlineTransformer {
-    /// Message loader.
-    loader: MessageLoader,
-    /// Missing translation strategy.
-    missing_strategy: MissingTranslationStrategy,
+/// The serializer for the given format, XLIFF 1.2 by default.
+pub fn i18n_get_serializer(format_name: &str) -> Box<dyn Serializer> {
+    match format_name.to_lowercase().as_str() {
+        "xmb" => Box::new(Xmb::new()),
+        "json" => Box::new(Json::new()),
+        "arb" => Box::new(Arb::new()),
+        "xliff2" | "xlf2" => Box::new(Xliff2::new()),
+        _ => Box::new(Xliff::new()),
+    }
 }
 
-/// Strategy for missing translations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum MissingTranslationStrategy {
-    /// Throw an error.
-    Error,
-    /// Log a warning.
-    #[default]
-    Warning,
-    /// Ignore missing translations.
-    Ignore,
+/// Serialize the messages of `bundle`, with source files relative to the project's base
+/// path.
+pub fn i18n_serialize(
+    bundle: &MessageBundle,
+    format_name: &str,
+    options: &CompilerOptions,
+) -> Result<String, String> {
+    let format = format_name.to_lowercase();
+    i18n_get_extension(&format)?;
+    let serializer = i18n_get_serializer(&format);
+    let base_path = options.base_path.clone().unwrap_or_default();
+    let relative_source = |source_path: &str| {
+        let path = Path::new(source_path);
+        path.strip_prefix(&base_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    Ok(bundle.write(serializer.as_ref(), Some(&relative_source)))
 }
 
-impl I18nInlineTransformer {
-    pub fn new(loader: MessageLoader, missing_strategy: MissingTranslationStrategy) -> Self {
-        Self {
-            loader,
-            missing_strategy,
-        }
-    }
-
-    /// Translate a message.
-    pub fn translate(&self, id: &str, fallback: &str) -> String {
-        if let Some(msg) = self.loader.get(id) {
-            msg.to_string()
-        } else {
-            match self.missing_strategy {
-                MissingTranslationStrategy::Error => {
-                    panic!("Missing translation for: {}", id);
-                }
-                MissingTranslationStrategy::Warning => {
-                    eprintln!("Warning: Missing translation for: {}", id);
-                    fallback.to_string()
-                }
-                MissingTranslationStrategy::Ignore => fallback.to_string(),
-            }
-        }
+/// Write the messages of `bundle` to `out_file`, `messages.<ext>` by default, relative to
+/// the output directory or the project's base path. Returns the written path and content.
+pub fn i18n_extract(
+    format_name: Option<&str>,
+    out_file: Option<&str>,
+    options: &CompilerOptions,
+    bundle: &MessageBundle,
+) -> Result<(PathBuf, String), String> {
+    let format_name = format_name.unwrap_or("xlf");
+    let ext = i18n_get_extension(format_name)?;
+    let content = i18n_serialize(bundle, format_name, options)?;
+    let dst_file = out_file.map_or_else(|| format!("messages.{}", ext), str::to_string);
+    let dst_path = Path::new(
+        options
+            .out_dir
+            .as_deref()
+            .or(options.base_path.as_deref())
+            .unwrap_or("."),
+    )
+    .join(dst_file);
+    if let Some(parent) = dst_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
     }
+    std::fs::write(&dst_path, &content)
+        .map_err(|e| format!("Cannot write {}: {}", dst_path.display(), e))?;
+    Ok((dst_path, content))
 }
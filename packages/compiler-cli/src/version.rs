@@ -6,6 +6,10 @@
 /// Angular compiler version.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Version of the Angular runtime that generated code targets. Partial declarations
+/// record it in their `version` field.
+pub const ANGULAR_VERSION: &str = "21.0.0";
+
 /// Angular compiler version string.
 pub fn version_string() -> String {
     format!("Angular Compiler CLI v{}", VERSION)
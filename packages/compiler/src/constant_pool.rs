@@ -263,9 +263,16 @@ impl ConstantPool {
         }
     }
 
-    pub fn unique_name(&mut self, preferred_name: String, _always_include_suffix: bool) -> String {
-        // Rust specific: TS has `alwaysIncludeSuffix` (boolean).
-        // I'll ignore it for now or assume false implies check existing.
+    pub fn unique_name(&mut self, preferred_name: String, always_include_suffix: bool) -> String {
+        if always_include_suffix {
+            let count = self
+                .claimed_names
+                .get(&preferred_name)
+                .copied()
+                .unwrap_or(0);
+            self.claimed_names.insert(preferred_name.clone(), count + 1);
+            return format!("{}{}", preferred_name, count);
+        }
 
         if !self.claimed_names.contains_key(&preferred_name) {
             self.claimed_names.insert(preferred_name.clone(), 0);
@@ -293,6 +293,24 @@ impl Parser {
         })
     }
 
+    /// Parse a single expression as an interpolation with empty prefix and suffix strings
+    /// (e.g. the value of an ICU expression placeholder).
+    pub fn parse_interpolation_expression(
+        &self,
+        input: &str,
+        absolute_offset: usize,
+    ) -> Result<Interpolation> {
+        let ast = self.parse_binding(input, absolute_offset)?;
+        let span = ParseSpan::new(0, input.len());
+        Ok(Interpolation {
+            source_span: span.to_absolute(absolute_offset),
+            span,
+            // The prefix and suffix strings are both empty
+            strings: vec![String::new(), String::new()],
+            expressions: vec![Box::new(ast)],
+        })
+    }
+
     /// Split interpolation string into strings and expressions
     pub fn split_interpolation(
         &self,
@@ -301,11 +301,11 @@ pub fn sha1(s: &str) -> String {
     let mut e = 0xc3d2e1f0u32;
 
     let mut words32_mut = words32;
-    words32_mut[len >> 5] |= 0x80 << (24 - (len % 32));
     let idx = (((len + 64) >> 9) << 4) + 15;
     if idx >= words32_mut.len() {
         words32_mut.resize(idx + 1, 0);
     }
+    words32_mut[len >> 5] |= 0x80 << (24 - (len % 32));
     words32_mut[idx] = len as u32;
 
     for i in (0..words32_mut.len()).step_by(16) {
@@ -398,8 +398,7 @@ fn hash32(bytes: &[u8], length: usize, mut c: u32) -> u32 {
     let mut b = 0x9e3779b9u32;
     let mut index = 0;
 
-    let end = length.saturating_sub(12);
-    while index <= end {
+    while index + 12 <= length {
         a = a.wrapping_add(read_u32_le(bytes, index));
         b = b.wrapping_add(read_u32_le(bytes, index + 4));
         c = c.wrapping_add(read_u32_le(bytes, index + 8));
@@ -7,10 +7,9 @@ use crate::i18n::i18n_ast::{Message, Node as I18nNode};
 use crate::i18n::i18n_parser::{create_i18n_message_factory, I18nMessageFactory};
 use crate::i18n::translation_bundle::TranslationBundle;
 use crate::ml_parser::ast as html;
-use crate::ml_parser::ast::Visitor as HtmlVisitor;
 use crate::ml_parser::defaults::DEFAULT_CONTAINER_BLOCKS;
 use crate::ml_parser::parser::ParseTreeResult;
-use crate::parse_util::{ParseError, ParseLocation, ParseSourceFile, ParseSourceSpan};
+use crate::parse_util::{ParseError, ParseSourceFile};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
@@ -169,26 +168,7 @@ impl Visitor {
         self.init(VisitorMode::Merge);
         self.translations = Some(translations as *mut TranslationBundle);
 
-        // Construct a single fake root element
-        let start_loc = ParseLocation::from_source(String::new(), String::new(), 0, 0, 0);
-        let end_loc = ParseLocation::from_source(String::new(), String::new(), 0, 0, 0);
-        let source_span = ParseSourceSpan::new(start_loc.clone(), end_loc.clone());
-
-        let wrapper = html::Element {
-            name: "wrapper".into(),
-            attrs: Vec::new(),
-            directives: Vec::new(),
-            children: nodes.to_vec(),
-            is_self_closing: false,
-            source_span: source_span.clone(),
-            start_source_span: ParseSourceSpan::new(start_loc.clone(), start_loc),
-            end_source_span: Some(ParseSourceSpan::new(end_loc.clone(), end_loc)),
-            is_void: false,
-            i18n: None,
-        };
-
-        let mut context: *mut () = std::ptr::null_mut();
-        let translated_result = HtmlVisitor::visit_element(self, &wrapper, &mut context);
+        let root_nodes = self.merge_nodes(nodes);
 
         if self.in_i18n_block {
             if let Some(last_node) = nodes.last() {
@@ -196,17 +176,37 @@ impl Visitor {
             }
         }
 
-        let translated_element = translated_result
-            .and_then(|r| r.downcast::<html::Element>().ok())
-            .map(|e| *e)
-            .unwrap_or(wrapper);
-
         ParseTreeResult {
-            root_nodes: translated_element.children,
+            root_nodes,
             errors: self.errors.clone(),
         }
     }
 
+    /// Visit `nodes` in merge mode, returning the translated nodes.
+    fn merge_nodes(&mut self, nodes: &[html::Node]) -> Vec<html::Node> {
+        let mut merged = Vec::new();
+        let mut context: *mut () = std::ptr::null_mut();
+        for node in nodes {
+            let visited = html::visit_all(self, std::slice::from_ref(node), &mut context);
+            // Do not add the children from translatable sections (= i18n blocks here)
+            // They will be added when the block closes (i.e. on `<!-- /i18n -->`)
+            if self.is_in_translatable_section() {
+                continue;
+            }
+            for result in visited {
+                match result.downcast::<html::Node>() {
+                    Ok(node) => merged.push(*node),
+                    Err(result) => {
+                        if let Ok(nodes) = result.downcast::<Vec<html::Node>>() {
+                            merged.extend(*nodes);
+                        }
+                    }
+                }
+            }
+        }
+        merged
+    }
+
     fn is_in_translatable_section(&self) -> bool {
         self.msg_count_at_section_start.is_some()
     }
@@ -250,7 +250,12 @@ impl Visitor {
             return None;
         }
 
-        let meta = parse_message_meta(msg_meta);
+        let message = self.create_message(ast, &parse_message_meta(msg_meta))?;
+        self.messages.push(message.clone());
+        Some(message)
+    }
+
+    fn create_message(&mut self, ast: &[html::Node], meta: &MessageMeta) -> Option<Message> {
         let factory = self.create_i18n_message.as_mut()?;
         let message = factory.create_message(
             ast,
@@ -271,21 +276,96 @@ impl Visitor {
             },
             None,
         );
-        self.messages.push(message.clone());
         Some(message)
     }
 
-    fn translate_message(&mut self, _el: &html::Node, message: &Message) -> Vec<html::Node> {
-        if self.mode == VisitorMode::Merge {
-            if let Some(translations_ptr) = self.translations {
-                unsafe {
-                    if let Ok(nodes) = (*translations_ptr).get(message) {
-                        return nodes;
-                    }
+    /// The translation of `message`, or `None` when it can not be translated, in which case
+    /// the errors are reported.
+    fn translate_message(&mut self, message: &Message) -> Option<Vec<html::Node>> {
+        if self.mode != VisitorMode::Merge {
+            return None;
+        }
+        let translations = unsafe { &mut *self.translations? };
+        let result = translations.get(message);
+        self.errors.extend(translations.take_warnings());
+        match result {
+            Ok(nodes) => Some(nodes),
+            Err(errors) => {
+                self.errors.extend(errors);
+                None
+            }
+        }
+    }
+
+    /// Strip the i18n specific attributes and translate the attributes marked with
+    /// `i18n-<name>`.
+    fn translate_attributes(
+        &mut self,
+        node: &html::Node,
+        attrs: &[html::Attribute],
+    ) -> Vec<html::Attribute> {
+        let i18n_parsed_message_meta: HashMap<&str, MessageMeta> = attrs
+            .iter()
+            .filter_map(|attr| {
+                attr.name
+                    .strip_prefix(I18N_ATTR_PREFIX)
+                    .map(|name| (name, parse_message_meta(Some(&attr.value))))
+            })
+            .collect();
+
+        let mut translated_attributes = Vec::new();
+        for attr in attrs {
+            if attr.name.as_ref() == I18N_ATTR || attr.name.starts_with(I18N_ATTR_PREFIX) {
+                // strip i18n specific attributes
+                continue;
+            }
+
+            let meta = match i18n_parsed_message_meta.get(attr.name.as_ref()) {
+                Some(meta) if !attr.value.is_empty() => meta,
+                _ => {
+                    translated_attributes.push(attr.clone());
+                    continue;
                 }
+            };
+            let Some(message) = self.create_message(&[html::Node::Attribute(attr.clone())], meta)
+            else {
+                translated_attributes.push(attr.clone());
+                continue;
+            };
+            let id = if meta.id.is_empty() {
+                self.translations
+                    .map(|translations| unsafe { (*translations).digest(&message) })
+                    .unwrap_or_default()
+            } else {
+                meta.id.clone()
+            };
+            match self.translate_message(&message) {
+                Some(nodes) => match nodes.first() {
+                    None => translated_attributes.push(html::Attribute {
+                        value: "".into(),
+                        value_tokens: None,
+                        ..attr.clone()
+                    }),
+                    Some(html::Node::Text(text)) => translated_attributes.push(html::Attribute {
+                        value: text.value.clone(),
+                        value_tokens: None,
+                        ..attr.clone()
+                    }),
+                    Some(_) => {
+                        self.report_error(
+                            node,
+                            &format!(
+                                "Unexpected translation for attribute \"{}\" (id=\"{}\")",
+                                attr.name, id
+                            ),
+                        );
+                        translated_attributes.push(attr.clone());
+                    }
+                },
+                None => translated_attributes.push(attr.clone()),
             }
         }
-        Vec::new()
+        translated_attributes
     }
 
     fn may_be_add_block_children(&mut self, node: &html::Node) {
@@ -312,6 +392,28 @@ impl Visitor {
             .push(ParseError::new(span.clone(), msg.to_string()));
     }
 
+    /// Add a message for each attribute marked with `i18n-<name>` or listed as implicitly
+    /// translatable for `name`.
+    fn visit_attributes_of(&mut self, name: &str, attrs: &[html::Attribute]) {
+        let explicit_attr_name_to_value: HashMap<&str, String> = attrs
+            .iter()
+            .filter_map(|attr| {
+                attr.name
+                    .strip_prefix(I18N_ATTR_PREFIX)
+                    .map(|attr_name| (attr_name, attr.value.to_string()))
+            })
+            .collect();
+        let implicit_attr_names = self.implicit_attrs.get(name).cloned().unwrap_or_default();
+
+        for attr in attrs {
+            if let Some(meta) = explicit_attr_name_to_value.get(attr.name.as_ref()) {
+                self.add_message(&[html::Node::Attribute(attr.clone())], Some(meta));
+            } else if implicit_attr_names.iter().any(|n| n == attr.name.as_ref()) {
+                self.add_message(&[html::Node::Attribute(attr.clone())], None);
+            }
+        }
+    }
+
     // Helper to check if element has i18n attribute
     fn has_i18n_attr(el: &html::Element) -> bool {
         el.attrs.iter().any(|attr| attr.name.as_ref() == I18N_ATTR)
@@ -367,9 +469,7 @@ impl html::Visitor for Visitor {
             if has_i18n_attr || is_top_level_implicit {
                 self.in_i18n_node = true;
                 if let Some(message) = self.add_message(&element.children, Some(&i18n_meta)) {
-                    translated_child_nodes = Some(
-                        self.translate_message(&html::Node::Element(element.clone()), &message),
-                    );
+                    translated_child_nodes = self.translate_message(&message);
                 }
             }
 
@@ -403,32 +503,11 @@ impl html::Visitor for Visitor {
 
         if self.mode == VisitorMode::Merge {
             let visit_nodes = translated_child_nodes.as_ref().unwrap_or(&element.children);
-            let mut context: *mut () = std::ptr::null_mut();
-            for child in visit_nodes {
-                let visit_results = html::visit_all(self, &[child.clone()], &mut context);
-                if let Some(visited) = visit_results.into_iter().next() {
-                    if !self.is_in_translatable_section() {
-                        if let Some(node_ref) = visited.downcast_ref::<html::Node>() {
-                            child_nodes.push(node_ref.clone());
-                        }
-                    }
-                }
-            }
+            child_nodes = self.merge_nodes(visit_nodes);
         }
 
-        // Visit attributes (simplified - just mark messages for extraction)
         if self.mode == VisitorMode::Extract {
-            let implicit_attr_names = self
-                .implicit_attrs
-                .get(element.name.as_ref())
-                .cloned()
-                .unwrap_or_default();
-
-            for attr in &element.attrs {
-                if implicit_attr_names.iter().any(|n| n == attr.name.as_ref()) {
-                    self.add_message(&[html::Node::Attribute(attr.clone())], None);
-                }
-            }
+            self.visit_attributes_of(element.name.as_ref(), &element.attrs);
         }
 
         self.depth -= 1;
@@ -436,9 +515,11 @@ impl html::Visitor for Visitor {
         self.in_implicit_node = was_in_implicit_node;
 
         if self.mode == VisitorMode::Merge {
-            Some(Box::new(html::Element {
+            let attrs =
+                self.translate_attributes(&html::Node::Element(element.clone()), &element.attrs);
+            Some(Box::new(html::Node::Element(html::Element {
                 name: element.name.clone(),
-                attrs: element.attrs.clone(), // Simplified - should translate attributes
+                attrs,
                 directives: element.directives.clone(),
                 children: child_nodes,
                 is_self_closing: element.is_self_closing,
@@ -447,7 +528,7 @@ impl html::Visitor for Visitor {
                 end_source_span: element.end_source_span.clone(),
                 is_void: element.is_void,
                 i18n: None,
-            }))
+            })))
         } else {
             None
         }
@@ -478,9 +559,7 @@ impl html::Visitor for Visitor {
             if has_i18n_attr || is_top_level_implicit {
                 self.in_i18n_node = true;
                 if let Some(message) = self.add_message(&component.children, Some(&i18n_meta)) {
-                    translated_child_nodes = Some(
-                        self.translate_message(&html::Node::Component(component.clone()), &message),
-                    );
+                    translated_child_nodes = self.translate_message(&message);
                 }
             }
 
@@ -516,32 +595,14 @@ impl html::Visitor for Visitor {
             let visit_nodes = translated_child_nodes
                 .as_ref()
                 .unwrap_or(&component.children);
-            let mut context: *mut () = std::ptr::null_mut();
-            for child in visit_nodes {
-                let visit_results = html::visit_all(self, &[child.clone()], &mut context);
-                if let Some(visited) = visit_results.into_iter().next() {
-                    if !self.is_in_translatable_section() {
-                        if let Some(node_ref) = visited.downcast_ref::<html::Node>() {
-                            child_nodes.push(node_ref.clone());
-                        }
-                    }
-                }
-            }
+            child_nodes = self.merge_nodes(visit_nodes);
         }
 
-        // Visit attributes
         if self.mode == VisitorMode::Extract {
-            let implicit_attr_names = self
-                .implicit_attrs
-                .get(component.tag_name.as_deref().unwrap_or(""))
-                .cloned()
-                .unwrap_or_default();
-
-            for attr in &component.attrs {
-                if implicit_attr_names.iter().any(|n| n == attr.name.as_ref()) {
-                    self.add_message(&[html::Node::Attribute(attr.clone())], None);
-                }
-            }
+            self.visit_attributes_of(
+                component.tag_name.as_deref().unwrap_or(""),
+                &component.attrs,
+            );
         }
 
         self.depth -= 1;
@@ -549,11 +610,15 @@ impl html::Visitor for Visitor {
         self.in_implicit_node = was_in_implicit_node;
 
         if self.mode == VisitorMode::Merge {
-            Some(Box::new(html::Component {
+            let attrs = self.translate_attributes(
+                &html::Node::Component(component.clone()),
+                &component.attrs,
+            );
+            Some(Box::new(html::Node::Component(html::Component {
                 component_name: component.component_name.clone(),
                 tag_name: component.tag_name.clone(),
                 full_name: component.full_name.clone(),
-                attrs: component.attrs.clone(), // Simplified
+                attrs,
                 directives: component.directives.clone(),
                 children: child_nodes,
                 is_self_closing: component.is_self_closing,
@@ -561,7 +626,7 @@ impl html::Visitor for Visitor {
                 start_source_span: component.start_source_span.clone(),
                 end_source_span: component.end_source_span.clone(),
                 i18n: None,
-            }))
+            })))
         } else {
             None
         }
@@ -583,7 +648,7 @@ impl html::Visitor for Visitor {
         if self.is_in_translatable_section() {
             self.may_be_add_block_children(&html::Node::Text(text.clone()));
         }
-        Some(Box::new(text.clone()))
+        Some(Box::new(html::Node::Text(text.clone())))
     }
 
     fn visit_comment(
@@ -619,7 +684,7 @@ impl html::Visitor for Visitor {
                     self.block_children = Vec::new();
                     if let Some(ref value) = comment.value {
                         self.block_meaning_and_desc = I18N_COMMENT_PREFIX_REGEXP
-                            .replace(value, "")
+                            .replace(value.trim(), "")
                             .trim()
                             .to_string();
                     }
@@ -639,14 +704,9 @@ impl html::Visitor for Visitor {
                             self.add_message(&block_children, Some(&block_meaning_and_desc))
                         {
                             let nodes = self
-                                .translate_message(&html::Node::Comment(comment.clone()), &message);
-                            let mut context: *mut () = std::ptr::null_mut();
-                            let results: Vec<html::Node> =
-                                html::visit_all(self, &nodes, &mut context)
-                                    .into_iter()
-                                    .filter_map(|r| r.downcast::<html::Node>().ok().map(|n| *n))
-                                    .collect();
-                            return Some(Box::new(results));
+                                .translate_message(&message)
+                                .unwrap_or(block_children);
+                            return Some(Box::new(self.merge_nodes(&nodes)));
                         }
                     } else {
                         self.report_error(
@@ -688,20 +748,23 @@ impl html::Visitor for Visitor {
             &mut context,
         )
         .into_iter()
-        .filter_map(|r| r.downcast::<html::ExpansionCase>().ok().map(|c| *c))
+        .filter_map(|r| match r.downcast::<html::Node>().ok().map(|n| *n) {
+            Some(html::Node::ExpansionCase(case)) => Some(case),
+            _ => None,
+        })
         .collect();
 
         self.in_icu = was_in_icu;
 
         if self.mode == VisitorMode::Merge {
-            Some(Box::new(html::Expansion {
+            Some(Box::new(html::Node::Expansion(html::Expansion {
                 switch_value: expansion.switch_value.clone(),
                 expansion_type: expansion.expansion_type.clone(),
                 cases,
                 source_span: expansion.source_span.clone(),
                 switch_value_source_span: expansion.switch_value_source_span.clone(),
                 i18n: None,
-            }))
+            })))
         } else {
             None
         }
@@ -712,20 +775,16 @@ impl html::Visitor for Visitor {
         icu_case: &html::ExpansionCase,
         _context: &mut dyn std::any::Any,
     ) -> Option<Box<dyn std::any::Any>> {
-        let mut context: *mut () = std::ptr::null_mut();
-        let expression: Vec<html::Node> = html::visit_all(self, &icu_case.expression, &mut context)
-            .into_iter()
-            .filter_map(|r| r.downcast::<html::Node>().ok().map(|n| *n))
-            .collect();
+        let expression = self.merge_nodes(&icu_case.expression);
 
         if self.mode == VisitorMode::Merge {
-            Some(Box::new(html::ExpansionCase {
+            Some(Box::new(html::Node::ExpansionCase(html::ExpansionCase {
                 value: icu_case.value.clone(),
                 expression,
                 source_span: icu_case.source_span.clone(),
                 value_source_span: icu_case.value_source_span.clone(),
                 exp_source_span: icu_case.exp_source_span.clone(),
-            }))
+            })))
         } else {
             None
         }
@@ -736,9 +795,17 @@ impl html::Visitor for Visitor {
         block: &html::Block,
         _context: &mut dyn std::any::Any,
     ) -> Option<Box<dyn std::any::Any>> {
-        let mut context: *mut () = std::ptr::null_mut();
-        html::visit_all(self, &block.children, &mut context);
-        None
+        let children = self.merge_nodes(&block.children);
+
+        if self.mode == VisitorMode::Merge {
+            Some(Box::new(html::Node::Block(html::Block {
+                children,
+                i18n: None,
+                ..block.clone()
+            })))
+        } else {
+            None
+        }
     }
 
     fn visit_block_parameter(
@@ -751,10 +818,14 @@ impl html::Visitor for Visitor {
 
     fn visit_let_declaration(
         &mut self,
-        _decl: &html::LetDeclaration,
+        decl: &html::LetDeclaration,
         _context: &mut dyn std::any::Any,
     ) -> Option<Box<dyn std::any::Any>> {
-        None
+        if self.mode == VisitorMode::Merge {
+            Some(Box::new(html::Node::LetDeclaration(decl.clone())))
+        } else {
+            None
+        }
     }
 
     fn visit_directive(
@@ -769,14 +840,14 @@ impl html::Visitor for Visitor {
 fn is_opening_comment(n: &html::Comment) -> bool {
     n.value
         .as_ref()
-        .map(|v| v.starts_with("i18n"))
+        .map(|v| v.trim().starts_with("i18n"))
         .unwrap_or(false)
 }
 
 fn is_closing_comment(n: &html::Comment) -> bool {
     n.value
         .as_ref()
-        .map(|v| v.as_ref() == "/i18n")
+        .map(|v| v.trim() == "/i18n")
         .unwrap_or(false)
 }
 
@@ -4,6 +4,7 @@
 //! Defines the AST nodes for internationalization messages
 
 use crate::parse_util::ParseSourceSpan;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 /// Describes the text contents of a placeholder as it appears in an ICU expression,
@@ -30,7 +31,7 @@ pub struct MessageSpan {
 #[derive(Debug, Clone)]
 pub struct Message {
     pub nodes: Vec<Node>,
-    pub placeholders: HashMap<String, MessagePlaceholder>,
+    pub placeholders: IndexMap<String, MessagePlaceholder>,
     pub placeholder_to_message: HashMap<String, Box<Message>>,
     pub meaning: String,
     pub description: String,
@@ -45,7 +46,7 @@ pub struct Message {
 impl Message {
     pub fn new(
         nodes: Vec<Node>,
-        placeholders: HashMap<String, MessagePlaceholder>,
+        placeholders: IndexMap<String, MessagePlaceholder>,
         placeholder_to_message: HashMap<String, Box<Message>>,
         meaning: String,
         description: String,
@@ -164,16 +165,18 @@ impl Container {
 pub struct Icu {
     pub expression: String,
     pub type_: String,
-    pub cases: HashMap<String, Node>,
+    pub cases: IndexMap<String, Node>,
     pub source_span: ParseSourceSpan,
     pub expression_placeholder: Option<String>,
+    /// Name of the ICU placeholder this ICU was extracted from (set by the i18n meta visitor)
+    pub name: Option<String>,
 }
 
 impl Icu {
     pub fn new(
         expression: String,
         type_: String,
-        cases: HashMap<String, Node>,
+        cases: IndexMap<String, Node>,
         source_span: ParseSourceSpan,
         expression_placeholder: Option<String>,
     ) -> Self {
@@ -183,6 +186,7 @@ impl Icu {
             cases,
             source_span,
             expression_placeholder,
+            name: None,
         }
     }
 }
@@ -391,7 +395,7 @@ impl Visitor for CloneVisitor {
         icu: &Icu,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut cases = HashMap::new();
+        let mut cases = IndexMap::new();
         for (key, node) in &icu.cases {
             let result = node.visit(self, None); // TODO: Fix context passing
             cases.insert(key.clone(), *result.downcast::<Node>().unwrap());
@@ -6,6 +6,8 @@
 use crate::core::MissingTranslationStrategy;
 use crate::i18n::digest::digest;
 use crate::i18n::extractor_merger::merge_translations;
+use crate::i18n::serializers::arb::Arb;
+use crate::i18n::serializers::json::Json;
 use crate::i18n::serializers::xliff::Xliff;
 use crate::i18n::serializers::xliff2::Xliff2;
 use crate::i18n::serializers::xmb::Xmb;
@@ -38,6 +40,8 @@ impl I18NHtmlParser {
             let result = match format.as_str() {
                 "xmb" => TranslationBundle::load(&trans, "i18n", Xmb::new(), missing_translation),
                 "xtb" => TranslationBundle::load(&trans, "i18n", Xtb::new(), missing_translation),
+                "json" => TranslationBundle::load(&trans, "i18n", Json::new(), missing_translation),
+                "arb" => TranslationBundle::load(&trans, "i18n", Arb::new(), missing_translation),
                 "xliff2" | "xlf2" => {
                     TranslationBundle::load(&trans, "i18n", Xliff2::new(), missing_translation)
                 }
@@ -70,6 +74,17 @@ impl I18NHtmlParser {
         }
     }
 
+    /// Create a parser merging the translations of an already loaded bundle.
+    pub fn with_translation_bundle(
+        html_parser: HtmlParser,
+        translation_bundle: TranslationBundle,
+    ) -> Self {
+        I18NHtmlParser {
+            html_parser,
+            translation_bundle,
+        }
+    }
+
     pub fn parse(&mut self, source: &str, url: &str, options: TokenizeOptions) -> ParseTreeResult {
         let parse_result = self.html_parser.parse(source, url, Some(options));
 
@@ -15,6 +15,7 @@ use crate::ml_parser::html_tags::get_html_tag_definition;
 use crate::ml_parser::tags::TagDefinition;
 use crate::ml_parser::tokens::{InterpolationToken, Token};
 use crate::parse_util::{ParseLocation, ParseSourceFile, ParseSourceSpan};
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
@@ -58,7 +59,7 @@ struct I18nMessageVisitorContext {
     is_icu: bool,
     icu_depth: usize,
     placeholder_registry: PlaceholderRegistry,
-    placeholder_to_content: HashMap<String, i18n::MessagePlaceholder>,
+    placeholder_to_content: IndexMap<String, i18n::MessagePlaceholder>,
     placeholder_to_message: HashMap<String, Box<Message>>,
     visit_node_fn: Option<VisitNodeFn>,
 }
@@ -102,7 +103,7 @@ impl I18nVisitor {
             is_icu,
             icu_depth: 0,
             placeholder_registry: PlaceholderRegistry::new(),
-            placeholder_to_content: HashMap::new(),
+            placeholder_to_content: IndexMap::new(),
             placeholder_to_message: HashMap::new(),
             visit_node_fn,
         };
@@ -492,9 +493,9 @@ impl html::Visitor for I18nVisitor {
         context: &mut dyn std::any::Any,
     ) -> Option<Box<dyn std::any::Any>> {
         let ctx = context.downcast_mut::<I18nMessageVisitorContext>().unwrap();
-        let node = if attribute.value_tokens.is_none()
-            || attribute.value_tokens.as_ref().unwrap().len() == 1
-        {
+        let node = if attribute.value_tokens.as_ref().is_none_or(|tokens| {
+            tokens.len() == 1 && !matches!(tokens[0], Token::AttrValueInterpolation(_))
+        }) {
             i18n::Node::Text(I18nText::new(
                 attribute.value.to_string(),
                 attribute
@@ -531,7 +532,8 @@ impl html::Visitor for I18nVisitor {
         context: &mut dyn std::any::Any,
     ) -> Option<Box<dyn std::any::Any>> {
         let ctx = context.downcast_mut::<I18nMessageVisitorContext>().unwrap();
-        let node = if text.tokens.len() == 1 {
+        // The lexer drops empty text tokens, so a lone interpolation is also a single token
+        let node = if text.tokens.len() == 1 && !matches!(text.tokens[0], Token::Interpolation(_)) {
             i18n::Node::Text(I18nText::new(
                 text.value.to_string(),
                 text.source_span.clone(),
@@ -575,7 +577,7 @@ impl html::Visitor for I18nVisitor {
         }
 
         // Visit all cases (this needs mutable access to context)
-        let mut i18n_icu_cases: HashMap<String, i18n::Node> = HashMap::new();
+        let mut i18n_icu_cases: IndexMap<String, i18n::Node> = IndexMap::new();
         for case in &expansion.cases {
             let case_nodes: Vec<i18n::Node> = html::visit_all(self, &case.expression, context)
                 .into_iter()
@@ -600,6 +602,7 @@ impl html::Visitor for I18nVisitor {
             cases: i18n_icu_cases.clone(),
             source_span: expansion.source_span.clone(),
             expression_placeholder: None,
+            name: None,
         };
 
         if ctx.is_icu || ctx.icu_depth > 0 {
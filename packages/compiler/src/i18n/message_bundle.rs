@@ -8,8 +8,10 @@ use crate::i18n::i18n_ast::{Message, Node, Visitor};
 use crate::i18n::serializers::serializer::{PlaceholderMapper, Serializer};
 use crate::ml_parser::ast::Node as HtmlNode;
 use crate::ml_parser::html_parser::HtmlParser;
+use crate::ml_parser::html_whitespaces::remove_whitespaces;
 use crate::ml_parser::lexer::TokenizeOptions;
 use crate::parse_util::ParseError;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 /// A container for message extracted from the templates
@@ -51,14 +53,10 @@ impl MessageBundle {
             return html_parser_result.errors;
         }
 
-        // Note: WhitespaceVisitor is not yet implemented, so we skip whitespace trimming
-        // for now. This can be added later when WhitespaceVisitor is implemented.
         let root_nodes: Vec<HtmlNode> = if self.preserve_whitespace {
             html_parser_result.root_nodes
         } else {
-            // TODO: Apply WhitespaceVisitor when implemented
-            // For now, just use the nodes as-is
-            html_parser_result.root_nodes
+            remove_whitespaces(html_parser_result, false).root_nodes
         };
 
         // Extract i18n messages
@@ -78,28 +76,33 @@ impl MessageBundle {
         vec![]
     }
 
+    /// Add messages extracted outside of templates, e.g. from `$localize` tagged templates.
+    pub fn add_messages(&mut self, messages: impl IntoIterator<Item = Message>) {
+        self.messages.extend(messages);
+    }
+
     pub fn get_messages(&self) -> &[Message] {
         &self.messages
     }
 
+    /// Serialize the messages, de-duplicated by their id in order of first appearance.
+    /// `filter_sources` rewrites the file paths of the message sources.
     pub fn write(
         &self,
         serializer: &dyn Serializer,
-        filter_sources: Option<fn(&str) -> String>,
+        filter_sources: Option<&dyn Fn(&str) -> String>,
     ) -> String {
-        let mut messages: HashMap<String, Message> = HashMap::new();
+        let mut messages: IndexMap<String, Message> = IndexMap::new();
         let mapper_visitor = MapPlaceholderNames;
 
         // Deduplicate messages based on their ID
         for message in &self.messages {
             let id = serializer.digest(message);
-            if !messages.contains_key(&id) {
-                messages.insert(id.clone(), message.clone());
-            } else {
+            if let Some(msg) = messages.get_mut(&id) {
                 // Merge sources
-                if let Some(msg) = messages.get_mut(&id) {
-                    msg.sources.extend(message.sources.clone());
-                }
+                msg.sources.extend(message.sources.clone());
+            } else {
+                messages.insert(id, message.clone());
             }
         }
 
@@ -116,7 +119,7 @@ impl MessageBundle {
 
                 let mut transformed_message = Message::new(
                     nodes,
-                    HashMap::new(),
+                    IndexMap::new(),
                     HashMap::new(),
                     src.meaning.clone(),
                     src.description.clone(),
@@ -193,7 +196,7 @@ impl Visitor for MapPlaceholderNamesVisitor<'_> {
         icu: &crate::i18n::i18n_ast::Icu,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let cases: HashMap<String, crate::i18n::i18n_ast::Node> = icu
+        let cases: indexmap::IndexMap<String, crate::i18n::i18n_ast::Node> = icu
             .cases
             .iter()
             .map(|(k, v)| {
@@ -207,6 +210,7 @@ impl Visitor for MapPlaceholderNamesVisitor<'_> {
             cases,
             source_span: icu.source_span.clone(),
             expression_placeholder: icu.expression_placeholder.clone(),
+            name: icu.name.clone(),
         }))
     }
 
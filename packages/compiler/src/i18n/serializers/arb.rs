@@ -0,0 +1,184 @@
+//! ARB Serializer Module
+//!
+//! Corresponds to the ARB translation format of @angular/localize
+//! (packages/localize/tools/src/extract/translation_files/arb_translation_serializer.ts and
+//! packages/localize/tools/src/translate/translation_files/translation_parsers/arb_translation_parser.ts)
+//!
+//! ```json
+//! {
+//!   "@@locale": "en-US",
+//!   "message-id": "Target message string",
+//!   "@message-id": {
+//!     "description": "Some description",
+//!     "x-meaning": "Some meaning",
+//!     "x-locations": [
+//!       {
+//!         "file": "path/to/file.ts",
+//!         "start": { "line": 21, "column": 10 },
+//!         "end": { "line": 21, "column": 30 }
+//!       }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! See https://github.com/google/app-resource-bundle/wiki/ApplicationResourceBundleSpecification
+
+use crate::i18n::digest::decimal_digest;
+use crate::i18n::i18n_ast::{Message, MessageSpan};
+use crate::i18n::serializers::json::{message_text, parse_json_object, parse_translation};
+use crate::i18n::serializers::serializer::{PlaceholderMapper, Serializer};
+use crate::i18n::translation_bundle::LoadResult;
+use crate::parse_util::ParseSourceFile;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const DEFAULT_SOURCE_LANG: &str = "en";
+const LOCALE_KEY: &str = "@@locale";
+
+/// Application Resource Bundle serializer, the messages are written as `$localize` message
+/// strings keyed by their `$localize` id, with their metadata under `@<id>`.
+pub struct Arb {
+    // Implementation fields
+}
+
+impl Arb {
+    pub fn new() -> Self {
+        Arb {}
+    }
+}
+
+impl Default for Arb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer for Arb {
+    fn write(&self, messages: &[Message], locale: Option<&str>) -> String {
+        let mut output = format!(
+            "{{\n  {}: {}",
+            json_string(LOCALE_KEY),
+            json_string(locale.unwrap_or(DEFAULT_SOURCE_LANG))
+        );
+        for message in messages {
+            output += &format!(
+                ",\n  {}: {}",
+                json_string(&message.id),
+                json_string(&message_text(&message.nodes))
+            );
+            output += &serialize_meta(message);
+        }
+        output += "\n}";
+        output
+    }
+
+    fn load(&self, content: &str, url: &str) -> Result<LoadResult, String> {
+        let file = Arc::new(ParseSourceFile::new(content.to_string(), url.to_string()));
+        let mut errors = Vec::new();
+        let mut i18n_nodes_by_msg_id = HashMap::new();
+        let mut locale = None;
+
+        match parse_json_object(&file) {
+            Ok(json) => {
+                locale = json
+                    .get(LOCALE_KEY)
+                    .and_then(|locale| locale.as_str())
+                    .map(str::to_string);
+                // Skip the locale and the metadata of the messages
+                for (msg_id, text) in json.iter().filter(|(key, _)| !key.starts_with('@')) {
+                    match parse_translation(&file, msg_id, text) {
+                        Ok(nodes) => {
+                            i18n_nodes_by_msg_id.insert(msg_id.clone(), nodes);
+                        }
+                        Err(error) => errors.push(error),
+                    }
+                }
+            }
+            Err(error) => errors.push(error),
+        }
+
+        if !errors.is_empty() {
+            return Err(format!(
+                "arb parse errors:\n{}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+
+        Ok(LoadResult {
+            locale,
+            i18n_nodes_by_msg_id,
+        })
+    }
+
+    fn digest(&self, message: &Message) -> String {
+        decimal_digest(message)
+    }
+
+    fn create_name_mapper(&self, _message: &Message) -> Option<Box<dyn PlaceholderMapper>> {
+        None
+    }
+}
+
+/// The `"@<id>"` metadata of a message, empty when it has none.
+fn serialize_meta(message: &Message) -> String {
+    let mut meta = Vec::new();
+    if !message.description.is_empty() {
+        meta.push(format!(
+            "\n    \"description\": {}",
+            json_string(&message.description)
+        ));
+    }
+    if !message.meaning.is_empty() {
+        meta.push(format!(
+            "\n    \"x-meaning\": {}",
+            json_string(&message.meaning)
+        ));
+    }
+    if !message.sources.is_empty() {
+        let locations = message
+            .sources
+            .iter()
+            .map(serialize_location)
+            .collect::<Vec<_>>()
+            .join(",\n");
+        meta.push(format!("\n    \"x-locations\": [\n{}\n    ]", locations));
+    }
+
+    if meta.is_empty() {
+        return String::new();
+    }
+    format!(
+        ",\n  {}: {{{}\n  }}",
+        json_string(&format!("@{}", message.id)),
+        meta.join(",")
+    )
+}
+
+/// A location of a message, with 0-based lines and columns.
+fn serialize_location(source: &MessageSpan) -> String {
+    [
+        "      {".to_string(),
+        format!("        \"file\": {},", json_string(&source.file_path)),
+        format!(
+            "        \"start\": {{ \"line\": {}, \"column\": {} }},",
+            source.start_line.saturating_sub(1),
+            source.start_col.saturating_sub(1)
+        ),
+        format!(
+            "        \"end\": {{ \"line\": {}, \"column\": {} }}",
+            source.end_line.saturating_sub(1),
+            source.end_col.saturating_sub(1)
+        ),
+        "      }".to_string(),
+    ]
+    .join("\n")
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
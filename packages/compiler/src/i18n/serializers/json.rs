@@ -0,0 +1,432 @@
+//! JSON Serializer Module
+//!
+//! Corresponds to the simple JSON translation format of @angular/localize
+//! (packages/localize/tools/src/extract/translation_files/json_translation_serializer.ts and
+//! packages/localize/tools/src/translate/translation_files/translation_parsers/simple_json_translation_parser.ts)
+//!
+//! ```json
+//! {
+//!   "locale": "fr",
+//!   "translations": {
+//!     "6015092383917405034": "Bonjour {$INTERPOLATION} !"
+//!   }
+//! }
+//! ```
+
+use crate::i18n::digest::decimal_digest;
+use crate::i18n::i18n_ast::{self as i18n, Message, Node, Visitor};
+use crate::i18n::serializers::serializer::{PlaceholderMapper, Serializer};
+use crate::i18n::translation_bundle::LoadResult;
+use crate::parse_util::{ParseError, ParseLocation, ParseSourceFile, ParseSourceSpan};
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const DEFAULT_SOURCE_LANG: &str = "en";
+
+/// Simple JSON serializer, the messages are written as `$localize` message strings keyed by
+/// their `$localize` id.
+pub struct Json {
+    // Implementation fields
+}
+
+impl Json {
+    pub fn new() -> Self {
+        Json {}
+    }
+}
+
+impl Default for Json {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer for Json {
+    fn write(&self, messages: &[Message], locale: Option<&str>) -> String {
+        let mut translations = serde_json::Map::new();
+        for message in messages {
+            translations.insert(
+                message.id.clone(),
+                serde_json::Value::String(message_text(&message.nodes)),
+            );
+        }
+        let mut file = serde_json::Map::new();
+        file.insert(
+            "locale".to_string(),
+            serde_json::Value::String(locale.unwrap_or(DEFAULT_SOURCE_LANG).to_string()),
+        );
+        file.insert(
+            "translations".to_string(),
+            serde_json::Value::Object(translations),
+        );
+        serde_json::to_string_pretty(&file).unwrap_or_default()
+    }
+
+    fn load(&self, content: &str, url: &str) -> Result<LoadResult, String> {
+        let file = Arc::new(ParseSourceFile::new(content.to_string(), url.to_string()));
+        let mut errors = Vec::new();
+        let mut i18n_nodes_by_msg_id = HashMap::new();
+        let mut locale = None;
+
+        match parse_json_object(&file) {
+            Ok(json) => {
+                locale = json
+                    .get("locale")
+                    .and_then(|locale| locale.as_str())
+                    .map(str::to_string);
+                match json.get("translations").and_then(|t| t.as_object()) {
+                    Some(translations) => {
+                        for (msg_id, text) in translations {
+                            match parse_translation(&file, msg_id, text) {
+                                Ok(nodes) => {
+                                    i18n_nodes_by_msg_id.insert(msg_id.clone(), nodes);
+                                }
+                                Err(error) => errors.push(error),
+                            }
+                        }
+                    }
+                    None => errors.push(ParseError::new(
+                        file_span(&file),
+                        "Missing \"translations\" object".to_string(),
+                    )),
+                }
+            }
+            Err(error) => errors.push(error),
+        }
+
+        if !errors.is_empty() {
+            return Err(format!(
+                "json parse errors:\n{}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+
+        Ok(LoadResult {
+            locale,
+            i18n_nodes_by_msg_id,
+        })
+    }
+
+    fn digest(&self, message: &Message) -> String {
+        decimal_digest(message)
+    }
+
+    fn create_name_mapper(&self, _message: &Message) -> Option<Box<dyn PlaceholderMapper>> {
+        None
+    }
+}
+
+/// Parse a translation file whose root is a JSON object.
+pub(crate) fn parse_json_object(
+    file: &Arc<ParseSourceFile>,
+) -> Result<serde_json::Map<String, serde_json::Value>, ParseError> {
+    match serde_json::from_str::<serde_json::Value>(&file.content) {
+        Ok(serde_json::Value::Object(json)) => Ok(json),
+        Ok(_) => Err(ParseError::new(
+            file_span(file),
+            "The translation file must contain a JSON object".to_string(),
+        )),
+        Err(e) => {
+            let offset: usize = file
+                .content
+                .split_inclusive('\n')
+                .take(e.line().saturating_sub(1))
+                .map(str::len)
+                .sum::<usize>()
+                + e.column().saturating_sub(1);
+            let start = ParseLocation::new(file.clone(), 0, 0, 0).move_by(offset as i32);
+            Err(ParseError::new(
+                ParseSourceSpan::new(start.clone(), start),
+                format!("Invalid JSON: {}", e),
+            ))
+        }
+    }
+}
+
+/// Convert the text of a translated message to i18n nodes.
+pub(crate) fn parse_translation(
+    file: &Arc<ParseSourceFile>,
+    msg_id: &str,
+    text: &serde_json::Value,
+) -> Result<Vec<Node>, ParseError> {
+    // Point at the message in the file, the nodes have no finer location.
+    let key = serde_json::to_string(msg_id).unwrap_or_default();
+    let start = ParseLocation::new(file.clone(), 0, 0, 0);
+    let start = match file.content.find(&key) {
+        Some(offset) => start.move_by(offset as i32),
+        None => start,
+    };
+    let span = ParseSourceSpan::new(start.clone(), start.move_by(key.len() as i32));
+
+    let Some(text) = text.as_str() else {
+        return Err(ParseError::new(
+            span,
+            format!("The translation of msg {} must be a string", msg_id),
+        ));
+    };
+    let mut parser = MessageTextParser {
+        chars: text.chars().collect(),
+        index: 0,
+        msg_id,
+        span,
+    };
+    parser.parse_nodes(false)
+}
+
+fn file_span(file: &Arc<ParseSourceFile>) -> ParseSourceSpan {
+    let start = ParseLocation::new(file.clone(), 0, 0, 0);
+    ParseSourceSpan::new(start.clone(), start)
+}
+
+/// Serialize i18n nodes to the text of a `$localize` message, where placeholders are written as
+/// `{$NAME}`.
+pub(crate) fn message_text(nodes: &[Node]) -> String {
+    let mut visitor = MessageTextVisitor;
+    nodes.iter().map(|node| visitor.visit_node(node)).collect()
+}
+
+struct MessageTextVisitor;
+
+impl MessageTextVisitor {
+    fn visit_node(&mut self, node: &Node) -> String {
+        *node.visit(self, None).downcast::<String>().unwrap()
+    }
+
+    fn visit_all(&mut self, nodes: &[Node]) -> String {
+        nodes.iter().map(|node| self.visit_node(node)).collect()
+    }
+}
+
+impl Visitor for MessageTextVisitor {
+    fn visit_text(
+        &mut self,
+        text: &i18n::Text,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        Box::new(text.value.clone())
+    }
+
+    fn visit_container(
+        &mut self,
+        container: &i18n::Container,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        Box::new(self.visit_all(&container.children))
+    }
+
+    fn visit_icu(
+        &mut self,
+        icu: &i18n::Icu,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        let cases = icu
+            .cases
+            .iter()
+            .map(|(k, case)| format!("{} {{{}}}", k, self.visit_node(case)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let expression = icu
+            .expression_placeholder
+            .as_ref()
+            .unwrap_or(&icu.expression);
+        Box::new(format!("{{{}, {}, {}}}", expression, icu.type_, cases))
+    }
+
+    fn visit_tag_placeholder(
+        &mut self,
+        ph: &i18n::TagPlaceholder,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        if ph.is_void {
+            return Box::new(format!("{{${}}}", ph.start_name));
+        }
+        Box::new(format!(
+            "{{${}}}{}{{${}}}",
+            ph.start_name,
+            self.visit_all(&ph.children),
+            ph.close_name
+        ))
+    }
+
+    fn visit_placeholder(
+        &mut self,
+        ph: &i18n::Placeholder,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        Box::new(format!("{{${}}}", ph.name))
+    }
+
+    fn visit_block_placeholder(
+        &mut self,
+        ph: &i18n::BlockPlaceholder,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        Box::new(format!(
+            "{{${}}}{}{{${}}}",
+            ph.start_name,
+            self.visit_all(&ph.children),
+            ph.close_name
+        ))
+    }
+
+    fn visit_icu_placeholder(
+        &mut self,
+        ph: &i18n::IcuPlaceholder,
+        _context: Option<&mut dyn std::any::Any>,
+    ) -> Box<dyn std::any::Any> {
+        Box::new(format!("{{${}}}", ph.name))
+    }
+}
+
+/// Parse the text of a `$localize` message back to i18n nodes: `{$NAME}` is a placeholder and
+/// `{EXPR, type, case {...} ...}` an ICU message. Within ICU cases, `{NAME}` is a placeholder
+/// as well, as written by compiled templates.
+struct MessageTextParser<'a> {
+    chars: Vec<char>,
+    index: usize,
+    msg_id: &'a str,
+    span: ParseSourceSpan,
+}
+
+impl MessageTextParser<'_> {
+    /// Parse nodes up to the end of the text, or the `}` closing the ICU case being parsed.
+    fn parse_nodes(&mut self, in_icu_case: bool) -> Result<Vec<Node>, ParseError> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        while let Some(&ch) = self.chars.get(self.index) {
+            if ch == '}' && in_icu_case {
+                break;
+            }
+            let node = if ch == '{' {
+                self.parse_placeholder(in_icu_case)
+                    .or_else(|| self.parse_icu().transpose())
+                    .transpose()?
+            } else {
+                None
+            };
+            match node {
+                Some(node) => {
+                    if !text.is_empty() {
+                        nodes.push(self.text(std::mem::take(&mut text)));
+                    }
+                    nodes.push(node);
+                }
+                None => {
+                    text.push(ch);
+                    self.index += 1;
+                }
+            }
+        }
+        if !text.is_empty() {
+            nodes.push(self.text(text));
+        }
+        Ok(nodes)
+    }
+
+    /// `{$NAME}`, or `{NAME}` in an ICU case.
+    fn parse_placeholder(&mut self, in_icu_case: bool) -> Option<Result<Node, ParseError>> {
+        let rest = &self.chars[self.index + 1..];
+        let (skip, rest) = match rest.first() {
+            Some('$') => (2, &rest[1..]),
+            _ if in_icu_case => (1, rest),
+            _ => return None,
+        };
+        let end = rest.iter().position(|&c| c == '}')?;
+        let name: String = rest[..end].iter().collect();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        self.index += skip + end + 1;
+        Some(Ok(Node::Placeholder(i18n::Placeholder::new(
+            String::new(),
+            name,
+            self.span.clone(),
+        ))))
+    }
+
+    /// `{EXPR, type, case {...} ...}`. The text is not an ICU message when it does not start with
+    /// `{EXPR, type,`.
+    fn parse_icu(&mut self) -> Result<Option<Node>, ParseError> {
+        let start = self.index;
+        self.index += 1;
+        let (Some(expression), Some(type_)) = (self.read_until_comma(), self.read_until_comma())
+        else {
+            self.index = start;
+            return Ok(None);
+        };
+
+        let mut cases = IndexMap::new();
+        loop {
+            self.skip_whitespace();
+            match self.chars.get(self.index) {
+                Some('}') => {
+                    self.index += 1;
+                    break;
+                }
+                None => return Err(self.error("Unterminated ICU message")),
+                _ => {}
+            }
+            let value: String = self.read_while(|c| !c.is_whitespace() && c != '{' && c != '}');
+            self.skip_whitespace();
+            if self.chars.get(self.index) != Some(&'{') {
+                return Err(self.error("Invalid ICU message, missing '{'"));
+            }
+            self.index += 1;
+            let nodes = self.parse_nodes(true)?;
+            if self.chars.get(self.index) != Some(&'}') {
+                return Err(self.error("Unterminated ICU message case"));
+            }
+            self.index += 1;
+            cases.insert(
+                value,
+                Node::Container(i18n::Container::new(nodes, self.span.clone())),
+            );
+        }
+
+        Ok(Some(Node::Icu(i18n::Icu::new(
+            expression.trim().to_string(),
+            type_.trim().to_string(),
+            cases,
+            self.span.clone(),
+            None,
+        ))))
+    }
+
+    /// Read up to the next `,` and skip it. Returns `None` when a brace comes first.
+    fn read_until_comma(&mut self) -> Option<String> {
+        let value = self.read_while(|c| c != ',' && c != '{' && c != '}');
+        if self.chars.get(self.index) != Some(&',') {
+            return None;
+        }
+        self.index += 1;
+        Some(value)
+    }
+
+    fn read_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.index;
+        while self.chars.get(self.index).is_some_and(|&c| predicate(c)) {
+            self.index += 1;
+        }
+        self.chars[start..self.index].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.read_while(char::is_whitespace);
+    }
+
+    fn text(&self, value: String) -> Node {
+        Node::Text(i18n::Text::new(value, self.span.clone()))
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(
+            self.span.clone(),
+            format!("{} in the translation of msg {}", message, self.msg_id),
+        )
+    }
+}
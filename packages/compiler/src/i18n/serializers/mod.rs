@@ -3,6 +3,8 @@
 //! Corresponds to packages/compiler/src/i18n/serializers/
 //! Contains various i18n serialization formats
 
+pub mod arb;
+pub mod json;
 pub mod placeholder;
 pub mod serializer;
 pub mod xliff;
@@ -12,6 +14,8 @@ pub mod xml_helper;
 pub mod xtb;
 
 // Re-export commonly used items
+pub use arb::Arb;
+pub use json::Json;
 pub use placeholder::PlaceholderRegistry;
 pub use serializer::{PlaceholderMapper, Serializer, SimplePlaceholderMapper};
 pub use xliff::Xliff;
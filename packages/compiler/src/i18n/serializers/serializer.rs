@@ -5,6 +5,10 @@
 
 use crate::i18n::i18n_ast::{self as i18n, Message, Visitor};
 use crate::i18n::translation_bundle::LoadResult;
+use crate::ml_parser::ast as html;
+use crate::ml_parser::lexer::{LexerRange, TokenizeOptions};
+use crate::ml_parser::parser::ParseTreeResult;
+use crate::ml_parser::xml_parser::XmlParser;
 use std::collections::HashMap;
 
 /// Base trait for i18n serializers
@@ -16,7 +20,9 @@ pub trait Serializer {
     fn write(&self, messages: &[Message], locale: Option<&str>) -> String;
 
     /// Load messages from serialized content
-    fn load(&self, content: &str, url: &str) -> LoadResult;
+    ///
+    /// Returns the parse errors of the content, one per line, when it can not be loaded.
+    fn load(&self, content: &str, url: &str) -> Result<LoadResult, String>;
 
     /// Compute digest for a message
     fn digest(&self, message: &Message) -> String;
@@ -28,6 +34,32 @@ pub trait Serializer {
     }
 }
 
+/// Parse the content of an element of the XML document `content` with ICU messages enabled,
+/// e.g. the `<target>` of a translation unit. The nodes and errors keep their location in the
+/// document.
+pub(crate) fn parse_element_content(
+    content: &str,
+    url: &str,
+    element: &html::Element,
+) -> ParseTreeResult {
+    let start = &element.start_source_span.end;
+    let end_pos = match &element.end_source_span {
+        Some(span) if !element.is_self_closing => span.start.offset,
+        _ => start.offset,
+    };
+    let options = TokenizeOptions {
+        tokenize_expansion_forms: true,
+        range: Some(LexerRange {
+            start_pos: start.offset,
+            start_line: start.line,
+            start_col: start.col,
+            end_pos,
+        }),
+        ..TokenizeOptions::default()
+    };
+    XmlParser::new().parse(content, url, Some(options))
+}
+
 /// A `PlaceholderMapper` converts placeholder names from internal to serialized representation
 /// and back.
 ///
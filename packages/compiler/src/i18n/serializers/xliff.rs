@@ -6,9 +6,13 @@
 
 use crate::i18n::digest::digest;
 use crate::i18n::i18n_ast::{self as i18n, Message, Node, Visitor};
-use crate::i18n::serializers::serializer::{PlaceholderMapper, Serializer};
+use crate::i18n::serializers::serializer::{parse_element_content, PlaceholderMapper, Serializer};
 use crate::i18n::serializers::xml_helper as xml;
 use crate::i18n::translation_bundle::LoadResult;
+use crate::ml_parser::ast as html;
+use crate::ml_parser::xml_parser::XmlParser;
+use crate::parse_util::ParseError;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 const VERSION: &str = "1.2";
@@ -47,84 +51,139 @@ impl Default for Xliff {
 impl Serializer for Xliff {
     fn write(&self, messages: &[Message], locale: Option<&str>) -> String {
         let mut visitor = XliffVisitor;
-        let source_lang = locale.unwrap_or(DEFAULT_SOURCE_LANG);
-
-        let mut xliff_attrs = HashMap::new();
-        xliff_attrs.insert("version".to_string(), VERSION.to_string());
-        xliff_attrs.insert("xmlns".to_string(), XMLNS.to_string());
-        let mut xliff_tag = xml::Tag::new("xliff".to_string(), xliff_attrs, Vec::new());
-
-        let mut file_attrs = HashMap::new();
-        file_attrs.insert("source-language".to_string(), source_lang.to_string());
-        file_attrs.insert("datatype".to_string(), "html".to_string());
-        let mut file_tag = xml::Tag::new(FILE_TAG.to_string(), file_attrs, Vec::new());
-
-        let mut body_tag = xml::Tag::new("body".to_string(), HashMap::new(), Vec::new());
+        let mut trans_units: Vec<Box<dyn xml::Node>> = Vec::new();
 
         for message in messages {
             let mut unit_attrs = HashMap::new();
             unit_attrs.insert("id".to_string(), message.id.clone());
+            unit_attrs.insert("datatype".to_string(), "html".to_string());
             let mut unit_tag = xml::Tag::new(UNIT_TAG.to_string(), unit_attrs, Vec::new());
 
-            // Add context-group with context for meaning and description
-            if !message.meaning.is_empty() || !message.description.is_empty() {
-                let mut context_group =
-                    xml::Tag::new(CONTEXT_GROUP_TAG.to_string(), HashMap::new(), Vec::new());
-
-                if !message.meaning.is_empty() {
-                    let mut context_attrs = HashMap::new();
-                    context_attrs.insert("context-type".to_string(), "x-meaning".to_string());
-                    let mut context_tag =
-                        xml::Tag::new(CONTEXT_TAG.to_string(), context_attrs, Vec::new());
-                    context_tag
-                        .children
-                        .push(Box::new(xml::Text::new(message.meaning.clone())));
-                    context_group.children.push(Box::new(context_tag));
-                }
-
-                if !message.description.is_empty() {
-                    let mut context_attrs = HashMap::new();
-                    context_attrs.insert("context-type".to_string(), "x-description".to_string());
-                    let mut context_tag =
-                        xml::Tag::new(CONTEXT_TAG.to_string(), context_attrs, Vec::new());
-                    context_tag
-                        .children
-                        .push(Box::new(xml::Text::new(message.description.clone())));
-                    context_group.children.push(Box::new(context_tag));
-                }
+            let source_tag = xml::Tag::new(
+                SOURCE_TAG.to_string(),
+                HashMap::new(),
+                visitor.serialize(&message.nodes),
+            );
+            unit_tag.children.push(Box::new(xml::CR::new(8)));
+            unit_tag.children.push(Box::new(source_tag));
 
+            for source in &message.sources {
+                let mut group_attrs = HashMap::new();
+                group_attrs.insert("purpose".to_string(), "location".to_string());
+                let context_group = xml::Tag::new(
+                    CONTEXT_GROUP_TAG.to_string(),
+                    group_attrs,
+                    vec![
+                        Box::new(xml::CR::new(10)),
+                        Box::new(context_tag("sourcefile", &source.file_path)),
+                        Box::new(xml::CR::new(10)),
+                        Box::new(context_tag("linenumber", &source.start_line.to_string())),
+                        Box::new(xml::CR::new(8)),
+                    ],
+                );
+                unit_tag.children.push(Box::new(xml::CR::new(8)));
                 unit_tag.children.push(Box::new(context_group));
             }
 
-            // Add source
-            let source_nodes = visitor.serialize(&message.nodes);
-            let mut source_tag = xml::Tag::new(SOURCE_TAG.to_string(), HashMap::new(), Vec::new());
-            source_tag.children.extend(source_nodes);
-            unit_tag.children.push(Box::new(source_tag));
+            for (from, note) in [
+                ("description", &message.description),
+                ("meaning", &message.meaning),
+            ] {
+                if !note.is_empty() {
+                    let mut note_attrs = HashMap::new();
+                    note_attrs.insert("priority".to_string(), "1".to_string());
+                    note_attrs.insert("from".to_string(), from.to_string());
+                    let note_tag = xml::Tag::new(
+                        "note".to_string(),
+                        note_attrs,
+                        vec![Box::new(xml::Text::new(note.clone()))],
+                    );
+                    unit_tag.children.push(Box::new(xml::CR::new(8)));
+                    unit_tag.children.push(Box::new(note_tag));
+                }
+            }
 
-            body_tag.children.push(Box::new(unit_tag));
+            unit_tag.children.push(Box::new(xml::CR::new(6)));
+            trans_units.push(Box::new(xml::CR::new(6)));
+            trans_units.push(Box::new(unit_tag));
         }
 
-        file_tag.children.push(Box::new(body_tag));
-        xliff_tag.children.push(Box::new(file_tag));
+        trans_units.push(Box::new(xml::CR::new(4)));
+        let body_tag = xml::Tag::new("body".to_string(), HashMap::new(), trans_units);
+
+        let mut file_attrs = HashMap::new();
+        file_attrs.insert(
+            "source-language".to_string(),
+            locale.unwrap_or(DEFAULT_SOURCE_LANG).to_string(),
+        );
+        file_attrs.insert("datatype".to_string(), "plaintext".to_string());
+        file_attrs.insert("original".to_string(), "ng2.template".to_string());
+        let file_tag = xml::Tag::new(
+            FILE_TAG.to_string(),
+            file_attrs,
+            vec![
+                Box::new(xml::CR::new(4)),
+                Box::new(body_tag),
+                Box::new(xml::CR::new(2)),
+            ],
+        );
+
+        let mut xliff_attrs = HashMap::new();
+        xliff_attrs.insert("version".to_string(), VERSION.to_string());
+        xliff_attrs.insert("xmlns".to_string(), XMLNS.to_string());
+        let xliff_tag = xml::Tag::new(
+            "xliff".to_string(),
+            xliff_attrs,
+            vec![
+                Box::new(xml::CR::new(2)),
+                Box::new(file_tag),
+                Box::new(xml::CR::new(0)),
+            ],
+        );
 
         let mut decl_attrs = HashMap::new();
         decl_attrs.insert("version".to_string(), "1.0".to_string());
         decl_attrs.insert("encoding".to_string(), "UTF-8".to_string());
 
-        let mut nodes: Vec<Box<dyn xml::Node>> = Vec::new();
-        nodes.push(Box::new(xml::Declaration::new(decl_attrs)));
-        nodes.push(Box::new(xliff_tag));
+        let nodes: Vec<Box<dyn xml::Node>> = vec![
+            Box::new(xml::Declaration::new(decl_attrs)),
+            Box::new(xml::CR::new(0)),
+            Box::new(xliff_tag),
+            Box::new(xml::CR::new(0)),
+        ];
 
         xml::serialize(&nodes)
     }
 
-    fn load(&self, _content: &str, _url: &str) -> LoadResult {
-        // TODO: Implement XLIFF 1.2 load
-        LoadResult {
-            locale: None,
-            i18n_nodes_by_msg_id: HashMap::new(),
+    fn load(&self, content: &str, url: &str) -> Result<LoadResult, String> {
+        // xliff to xml nodes
+        let mut xliff_parser = XliffParser::default();
+        let (targets, mut errors) = xliff_parser.parse(content, url);
+
+        // xml nodes to i18n nodes
+        let mut i18n_nodes_by_msg_id = HashMap::new();
+        let mut converter = XmlToI18n::default();
+        for (msg_id, target) in targets {
+            let (i18n_nodes, e) = converter.convert(content, url, &target);
+            errors.extend(e);
+            i18n_nodes_by_msg_id.insert(msg_id, i18n_nodes);
         }
+
+        if !errors.is_empty() {
+            return Err(format!(
+                "xliff parse errors:\n{}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+
+        Ok(LoadResult {
+            locale: xliff_parser.locale,
+            i18n_nodes_by_msg_id,
+        })
     }
 
     fn digest(&self, message: &Message) -> String {
@@ -136,6 +195,17 @@ impl Serializer for Xliff {
     }
 }
 
+/// `<context context-type="...">` of a location context group.
+fn context_tag(context_type: &str, value: &str) -> xml::Tag {
+    let mut attrs = HashMap::new();
+    attrs.insert("context-type".to_string(), context_type.to_string());
+    xml::Tag::new(
+        CONTEXT_TAG.to_string(),
+        attrs,
+        vec![Box::new(xml::Text::new(value.to_string()))],
+    )
+}
+
 /// Visitor that converts i18n AST nodes to XLIFF XML nodes
 struct XliffVisitor;
 
@@ -168,14 +238,7 @@ impl Visitor for XliffVisitor {
         container: &i18n::Container,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut result: Vec<Box<dyn xml::Node>> = Vec::new();
-        for child in &container.children {
-            let child_nodes = child.visit(self, None);
-            if let Ok(nodes_vec) = child_nodes.downcast::<Vec<Box<dyn xml::Node>>>() {
-                result.extend(*nodes_vec);
-            }
-        }
-        Box::new(result)
+        Box::new(self.serialize(&container.children))
     }
 
     fn visit_icu(
@@ -183,26 +246,20 @@ impl Visitor for XliffVisitor {
         icu: &i18n::Icu,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        // ICU messages are converted to text representation
-        let mut result: Vec<Box<dyn xml::Node>> = Vec::new();
-        let mut case_texts = Vec::new();
-        for (k, v) in &icu.cases {
-            let case_nodes = v.visit(self, None);
-            let case_text = if let Ok(nodes_vec) = case_nodes.downcast::<Vec<Box<dyn xml::Node>>>()
-            {
-                xml::serialize(&nodes_vec)
-            } else {
-                String::new()
-            };
-            case_texts.push(format!("{} {{{}}}", k, case_text));
+        let expression = icu
+            .expression_placeholder
+            .as_ref()
+            .unwrap_or(&icu.expression);
+        let mut result: Vec<Box<dyn xml::Node>> = vec![Box::new(xml::Text::new(format!(
+            "{{{}, {}, ",
+            expression, icu.type_
+        )))];
+        for (c, case) in &icu.cases {
+            result.push(Box::new(xml::Text::new(format!("{} {{", c))));
+            result.extend(self.serialize(std::slice::from_ref(case)));
+            result.push(Box::new(xml::Text::new("} ".to_string())));
         }
-        let icu_text = format!(
-            "{{{{{}}}, {}, {}}}",
-            icu.expression,
-            icu.type_,
-            case_texts.join(" ")
-        );
-        result.push(Box::new(xml::Text::new(icu_text)));
+        result.push(Box::new(xml::Text::new("}".to_string())));
         Box::new(result)
     }
 
@@ -211,18 +268,19 @@ impl Visitor for XliffVisitor {
         ph: &i18n::TagPlaceholder,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.start_name.clone());
-        let mut ph_tag = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
-
-        for child in &ph.children {
-            let child_nodes = child.visit(self, None);
-            if let Ok(nodes_vec) = child_nodes.downcast::<Vec<Box<dyn xml::Node>>>() {
-                ph_tag.children.extend(*nodes_vec);
-            }
+        let ctype = get_ctype_for_tag(&ph.tag);
+        if ph.is_void {
+            // void tags have no children nor closing tags
+            let tag_ph = placeholder_tag(&ph.start_name, Some(&ctype), &format!("<{}/>", ph.tag));
+            return Box::new(vec![Box::new(tag_ph) as Box<dyn xml::Node>]);
         }
 
-        Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
+        let start_tag_ph = placeholder_tag(&ph.start_name, Some(&ctype), &format!("<{}>", ph.tag));
+        let close_tag_ph = placeholder_tag(&ph.close_name, Some(&ctype), &format!("</{}>", ph.tag));
+        let mut result: Vec<Box<dyn xml::Node>> = vec![Box::new(start_tag_ph)];
+        result.extend(self.serialize(&ph.children));
+        result.push(Box::new(close_tag_ph));
+        Box::new(result)
     }
 
     fn visit_placeholder(
@@ -230,9 +288,7 @@ impl Visitor for XliffVisitor {
         ph: &i18n::Placeholder,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.name.clone());
-        let ph_tag = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
+        let ph_tag = placeholder_tag(&ph.name, None, &format!("{{{{{}}}}}", ph.value));
         Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
     }
 
@@ -241,18 +297,21 @@ impl Visitor for XliffVisitor {
         ph: &i18n::BlockPlaceholder,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.start_name.clone());
-        let mut ph_tag = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
-
-        for child in &ph.children {
-            let child_nodes = child.visit(self, None);
-            if let Ok(nodes_vec) = child_nodes.downcast::<Vec<Box<dyn xml::Node>>>() {
-                ph_tag.children.extend(*nodes_vec);
-            }
-        }
-
-        Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
+        let ctype = format!(
+            "x-{}",
+            ph.name
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect::<String>()
+        );
+        let start_block_ph =
+            placeholder_tag(&ph.start_name, Some(&ctype), &format!("@{}", ph.name));
+        let close_block_ph = placeholder_tag(&ph.close_name, Some(&ctype), "}");
+        let mut result: Vec<Box<dyn xml::Node>> = vec![Box::new(start_block_ph)];
+        result.extend(self.serialize(&ph.children));
+        result.push(Box::new(close_block_ph));
+        Box::new(result)
     }
 
     fn visit_icu_placeholder(
@@ -260,9 +319,201 @@ impl Visitor for XliffVisitor {
         ph: &i18n::IcuPlaceholder,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.name.clone());
-        let ph_tag = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
+        let cases = ph
+            .value
+            .cases
+            .keys()
+            .map(|value| format!("{} {{...}}", value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let equiv_text = format!("{{{}, {}, {}}}", ph.value.expression, ph.value.type_, cases);
+        let ph_tag = placeholder_tag(&ph.name, None, &equiv_text);
         Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
     }
 }
+
+/// `<x id="..." ctype="..." equiv-text="..."/>`
+fn placeholder_tag(id: &str, ctype: Option<&str>, equiv_text: &str) -> xml::Tag {
+    let mut attrs = HashMap::new();
+    attrs.insert("id".to_string(), id.to_string());
+    if let Some(ctype) = ctype {
+        attrs.insert("ctype".to_string(), ctype.to_string());
+    }
+    attrs.insert("equiv-text".to_string(), equiv_text.to_string());
+    xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new())
+}
+
+/// The `ctype` of the placeholders of an element.
+fn get_ctype_for_tag(tag: &str) -> String {
+    match tag.to_lowercase().as_str() {
+        "br" => "lb".to_string(),
+        "img" => "image".to_string(),
+        _ => format!("x-{}", tag),
+    }
+}
+
+/// Extract the `<target>` elements of the translation units of an XLIFF file, by message id.
+#[derive(Default)]
+struct XliffParser {
+    /// The `<target>` of the current unit.
+    unit_target: Option<html::Element>,
+    targets: IndexMap<String, html::Element>,
+    errors: Vec<ParseError>,
+    locale: Option<String>,
+}
+
+impl XliffParser {
+    fn parse(
+        &mut self,
+        xliff: &str,
+        url: &str,
+    ) -> (IndexMap<String, html::Element>, Vec<ParseError>) {
+        let xml = XmlParser::new().parse(xliff, url, None);
+        self.errors = xml.errors;
+        self.visit_all(&xml.root_nodes);
+        (
+            std::mem::take(&mut self.targets),
+            std::mem::take(&mut self.errors),
+        )
+    }
+
+    fn visit_all(&mut self, nodes: &[html::Node]) {
+        for node in nodes {
+            if let html::Node::Element(element) = node {
+                self.visit_element(element);
+            }
+        }
+    }
+
+    fn visit_element(&mut self, element: &html::Element) {
+        match &*element.name {
+            UNIT_TAG => {
+                self.unit_target = None;
+                let Some(id) = get_attr(element, "id") else {
+                    self.add_error(
+                        element,
+                        format!("<{}> misses the \"id\" attribute", UNIT_TAG),
+                    );
+                    return;
+                };
+                if self.targets.contains_key(id) {
+                    self.add_error(element, format!("Duplicated translations for msg {}", id));
+                    return;
+                }
+                self.visit_all(&element.children);
+                match self.unit_target.take() {
+                    Some(target) => {
+                        self.targets.insert(id.to_string(), target);
+                    }
+                    None => self.add_error(element, format!("Message {} misses a translation", id)),
+                }
+            }
+            // ignore those tags
+            SOURCE_TAG | SEGMENT_SOURCE_TAG | ALT_TRANS_TAG => {}
+            TARGET_TAG => self.unit_target = Some(element.clone()),
+            FILE_TAG => {
+                if let Some(locale) = get_attr(element, "target-language") {
+                    self.locale = Some(locale.to_string());
+                }
+                self.visit_all(&element.children);
+            }
+            // `<xliff>`, `<body>`, `<group>` and any unknown tag
+            _ => self.visit_all(&element.children),
+        }
+    }
+
+    fn add_error(&mut self, element: &html::Element, message: String) {
+        self.errors
+            .push(ParseError::new(element.source_span.clone(), message));
+    }
+}
+
+/// Convert the content of a `<target>` element to i18n nodes.
+#[derive(Default)]
+struct XmlToI18n {
+    errors: Vec<ParseError>,
+}
+
+impl XmlToI18n {
+    fn convert(
+        &mut self,
+        content: &str,
+        url: &str,
+        target: &html::Element,
+    ) -> (Vec<Node>, Vec<ParseError>) {
+        let xml_icu = parse_element_content(content, url, target);
+        self.errors = xml_icu.errors;
+        let i18n_nodes = if self.errors.is_empty() {
+            self.visit_all(&xml_icu.root_nodes)
+        } else {
+            Vec::new()
+        };
+        (i18n_nodes, std::mem::take(&mut self.errors))
+    }
+
+    fn visit_all(&mut self, nodes: &[html::Node]) -> Vec<Node> {
+        nodes.iter().flat_map(|node| self.visit(node)).collect()
+    }
+
+    fn visit(&mut self, node: &html::Node) -> Vec<Node> {
+        match node {
+            html::Node::Text(text) => vec![Node::Text(i18n::Text::new(
+                text.value.to_string(),
+                text.source_span.clone(),
+            ))],
+            html::Node::Element(el) if &*el.name == PLACEHOLDER_TAG => match get_attr(el, "id") {
+                Some(name) => vec![Node::Placeholder(i18n::Placeholder::new(
+                    String::new(),
+                    name.to_string(),
+                    el.source_span.clone(),
+                ))],
+                None => {
+                    self.add_error(
+                        el,
+                        format!("<{}> misses the \"id\" attribute", PLACEHOLDER_TAG),
+                    );
+                    Vec::new()
+                }
+            },
+            html::Node::Element(el) if &*el.name == MARKER_TAG => self.visit_all(&el.children),
+            html::Node::Element(el) => {
+                self.add_error(el, "Unexpected tag".to_string());
+                Vec::new()
+            }
+            html::Node::Expansion(icu) => {
+                let cases = icu
+                    .cases
+                    .iter()
+                    .map(|c| {
+                        let nodes = self.visit_all(&c.expression);
+                        (
+                            c.value.to_string(),
+                            Node::Container(i18n::Container::new(nodes, icu.source_span.clone())),
+                        )
+                    })
+                    .collect();
+                vec![Node::Icu(i18n::Icu::new(
+                    icu.switch_value.to_string(),
+                    icu.expansion_type.to_string(),
+                    cases,
+                    icu.source_span.clone(),
+                    None,
+                ))]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn add_error(&mut self, element: &html::Element, message: String) {
+        self.errors
+            .push(ParseError::new(element.source_span.clone(), message));
+    }
+}
+
+fn get_attr<'a>(element: &'a html::Element, name: &str) -> Option<&'a str> {
+    element
+        .attrs
+        .iter()
+        .find(|attr| &*attr.name == name)
+        .map(|attr| &*attr.value)
+}
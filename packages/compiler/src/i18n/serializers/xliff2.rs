@@ -6,9 +6,13 @@
 
 use crate::i18n::digest::decimal_digest;
 use crate::i18n::i18n_ast::{self as i18n, Message, Node, Visitor};
-use crate::i18n::serializers::serializer::{PlaceholderMapper, Serializer};
+use crate::i18n::serializers::serializer::{parse_element_content, PlaceholderMapper, Serializer};
 use crate::i18n::serializers::xml_helper as xml;
 use crate::i18n::translation_bundle::LoadResult;
+use crate::ml_parser::ast as html;
+use crate::ml_parser::xml_parser::XmlParser;
+use crate::parse_util::ParseError;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 const VERSION: &str = "2.0";
@@ -42,75 +46,130 @@ impl Default for Xliff2 {
 
 impl Serializer for Xliff2 {
     fn write(&self, messages: &[Message], locale: Option<&str>) -> String {
-        let mut visitor = Xliff2Visitor;
-        let source_lang = locale.unwrap_or(DEFAULT_SOURCE_LANG);
-
-        let mut xliff_attrs = HashMap::new();
-        xliff_attrs.insert("version".to_string(), VERSION.to_string());
-        xliff_attrs.insert("xmlns".to_string(), XMLNS.to_string());
-        xliff_attrs.insert("srcLang".to_string(), source_lang.to_string());
-        let mut xliff_tag = xml::Tag::new(XLIFF_TAG.to_string(), xliff_attrs, Vec::new());
-
-        let mut file_attrs = HashMap::new();
-        file_attrs.insert("id".to_string(), "f1".to_string());
-        let mut file_tag = xml::Tag::new("file".to_string(), file_attrs, Vec::new());
+        let mut visitor = Xliff2Visitor::default();
+        let mut units: Vec<Box<dyn xml::Node>> = Vec::new();
 
         for message in messages {
             let mut unit_attrs = HashMap::new();
             unit_attrs.insert("id".to_string(), message.id.clone());
             let mut unit_tag = xml::Tag::new(UNIT_TAG.to_string(), unit_attrs, Vec::new());
 
-            // Add notes for meaning and description
-            if !message.meaning.is_empty() {
-                let mut note_attrs = HashMap::new();
-                note_attrs.insert("category".to_string(), "x-meaning".to_string());
-                let mut note_tag = xml::Tag::new("note".to_string(), note_attrs, Vec::new());
-                note_tag
-                    .children
-                    .push(Box::new(xml::Text::new(message.meaning.clone())));
-                unit_tag.children.push(Box::new(note_tag));
+            let mut notes_tag = xml::Tag::new("notes".to_string(), HashMap::new(), Vec::new());
+            for (category, note) in [
+                ("description", &message.description),
+                ("meaning", &message.meaning),
+            ] {
+                if !note.is_empty() {
+                    notes_tag.children.push(Box::new(xml::CR::new(8)));
+                    notes_tag.children.push(Box::new(note_tag(category, note)));
+                }
             }
-
-            if !message.description.is_empty() {
-                let mut note_attrs = HashMap::new();
-                note_attrs.insert("category".to_string(), "x-description".to_string());
-                let mut note_tag = xml::Tag::new("note".to_string(), note_attrs, Vec::new());
-                note_tag
+            for source in &message.sources {
+                let location = if source.end_line != source.start_line {
+                    format!(
+                        "{}:{},{}",
+                        source.file_path, source.start_line, source.end_line
+                    )
+                } else {
+                    format!("{}:{}", source.file_path, source.start_line)
+                };
+                notes_tag.children.push(Box::new(xml::CR::new(8)));
+                notes_tag
                     .children
-                    .push(Box::new(xml::Text::new(message.description.clone())));
-                unit_tag.children.push(Box::new(note_tag));
+                    .push(Box::new(note_tag("location", &location)));
             }
-
-            // Add segment with source
-            let source_nodes = visitor.serialize(&message.nodes);
-            let mut segment_tag = xml::Tag::new("segment".to_string(), HashMap::new(), Vec::new());
-            let mut source_tag = xml::Tag::new(SOURCE_TAG.to_string(), HashMap::new(), Vec::new());
-            source_tag.children.extend(source_nodes);
-            segment_tag.children.push(Box::new(source_tag));
+            notes_tag.children.push(Box::new(xml::CR::new(6)));
+            unit_tag.children.push(Box::new(xml::CR::new(6)));
+            unit_tag.children.push(Box::new(notes_tag));
+
+            let source_tag = xml::Tag::new(
+                SOURCE_TAG.to_string(),
+                HashMap::new(),
+                visitor.serialize(&message.nodes),
+            );
+            let segment_tag = xml::Tag::new(
+                "segment".to_string(),
+                HashMap::new(),
+                vec![
+                    Box::new(xml::CR::new(8)),
+                    Box::new(source_tag),
+                    Box::new(xml::CR::new(6)),
+                ],
+            );
+            unit_tag.children.push(Box::new(xml::CR::new(6)));
             unit_tag.children.push(Box::new(segment_tag));
+            unit_tag.children.push(Box::new(xml::CR::new(4)));
 
-            file_tag.children.push(Box::new(unit_tag));
+            units.push(Box::new(xml::CR::new(4)));
+            units.push(Box::new(unit_tag));
         }
 
-        xliff_tag.children.push(Box::new(file_tag));
+        units.push(Box::new(xml::CR::new(2)));
+        let mut file_attrs = HashMap::new();
+        file_attrs.insert("original".to_string(), "ng.template".to_string());
+        file_attrs.insert("id".to_string(), "ngi18n".to_string());
+        let file_tag = xml::Tag::new("file".to_string(), file_attrs, units);
+
+        let mut xliff_attrs = HashMap::new();
+        xliff_attrs.insert("version".to_string(), VERSION.to_string());
+        xliff_attrs.insert("xmlns".to_string(), XMLNS.to_string());
+        xliff_attrs.insert(
+            "srcLang".to_string(),
+            locale.unwrap_or(DEFAULT_SOURCE_LANG).to_string(),
+        );
+        let xliff_tag = xml::Tag::new(
+            XLIFF_TAG.to_string(),
+            xliff_attrs,
+            vec![
+                Box::new(xml::CR::new(2)),
+                Box::new(file_tag),
+                Box::new(xml::CR::new(0)),
+            ],
+        );
 
         let mut decl_attrs = HashMap::new();
         decl_attrs.insert("version".to_string(), "1.0".to_string());
         decl_attrs.insert("encoding".to_string(), "UTF-8".to_string());
 
-        let mut nodes: Vec<Box<dyn xml::Node>> = Vec::new();
-        nodes.push(Box::new(xml::Declaration::new(decl_attrs)));
-        nodes.push(Box::new(xliff_tag));
+        let nodes: Vec<Box<dyn xml::Node>> = vec![
+            Box::new(xml::Declaration::new(decl_attrs)),
+            Box::new(xml::CR::new(0)),
+            Box::new(xliff_tag),
+            Box::new(xml::CR::new(0)),
+        ];
 
         xml::serialize(&nodes)
     }
 
-    fn load(&self, _content: &str, _url: &str) -> LoadResult {
-        // TODO: Implement XLIFF 2.0 load
-        LoadResult {
-            locale: None,
-            i18n_nodes_by_msg_id: HashMap::new(),
+    fn load(&self, content: &str, url: &str) -> Result<LoadResult, String> {
+        // xliff to xml nodes
+        let mut xliff2_parser = Xliff2Parser::default();
+        let (targets, mut errors) = xliff2_parser.parse(content, url);
+
+        // xml nodes to i18n nodes
+        let mut i18n_nodes_by_msg_id = HashMap::new();
+        let mut converter = XmlToI18n::default();
+        for (msg_id, target) in targets {
+            let (i18n_nodes, e) = converter.convert(content, url, &target);
+            errors.extend(e);
+            i18n_nodes_by_msg_id.insert(msg_id, i18n_nodes);
+        }
+
+        if !errors.is_empty() {
+            return Err(format!(
+                "xliff2 parse errors:\n{}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
         }
+
+        Ok(LoadResult {
+            locale: xliff2_parser.locale,
+            i18n_nodes_by_msg_id,
+        })
     }
 
     fn digest(&self, message: &Message) -> String {
@@ -122,11 +181,30 @@ impl Serializer for Xliff2 {
     }
 }
 
+/// `<note category="...">` of a unit.
+fn note_tag(category: &str, value: &str) -> xml::Tag {
+    let mut attrs = HashMap::new();
+    attrs.insert("category".to_string(), category.to_string());
+    xml::Tag::new(
+        "note".to_string(),
+        attrs,
+        vec![Box::new(xml::Text::new(value.to_string()))],
+    )
+}
+
 /// Visitor that converts i18n AST nodes to XLIFF2 XML nodes
-struct Xliff2Visitor;
+#[derive(Default)]
+struct Xliff2Visitor {
+    next_placeholder_id: usize,
+}
 
 impl Xliff2Visitor {
     fn serialize(&mut self, nodes: &[Node]) -> Vec<Box<dyn xml::Node>> {
+        self.next_placeholder_id = 0;
+        self.visit_all(nodes)
+    }
+
+    fn visit_all(&mut self, nodes: &[Node]) -> Vec<Box<dyn xml::Node>> {
         let mut result: Vec<Box<dyn xml::Node>> = Vec::new();
         for node in nodes {
             let xml_nodes = node.visit(self, None);
@@ -136,6 +214,22 @@ impl Xliff2Visitor {
         }
         result
     }
+
+    fn next_id(&mut self) -> String {
+        let id = self.next_placeholder_id;
+        self.next_placeholder_id += 1;
+        id.to_string()
+    }
+
+    /// `<pc>` spanning the serialized `children` of a tag or block placeholder.
+    fn spanning_tag(&mut self, mut attrs: HashMap<String, String>, children: &[Node]) -> xml::Tag {
+        attrs.insert("id".to_string(), self.next_id());
+        let mut nodes = self.visit_all(children);
+        if nodes.is_empty() {
+            nodes.push(Box::new(xml::Text::new(String::new())));
+        }
+        xml::Tag::new(PLACEHOLDER_SPANNING_TAG.to_string(), attrs, nodes)
+    }
 }
 
 impl Visitor for Xliff2Visitor {
@@ -154,14 +248,7 @@ impl Visitor for Xliff2Visitor {
         container: &i18n::Container,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut result: Vec<Box<dyn xml::Node>> = Vec::new();
-        for child in &container.children {
-            let child_nodes = child.visit(self, None);
-            if let Ok(nodes_vec) = child_nodes.downcast::<Vec<Box<dyn xml::Node>>>() {
-                result.extend(*nodes_vec);
-            }
-        }
-        Box::new(result)
+        Box::new(self.visit_all(&container.children))
     }
 
     fn visit_icu(
@@ -169,26 +256,20 @@ impl Visitor for Xliff2Visitor {
         icu: &i18n::Icu,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        // ICU messages are converted to text representation
-        let mut result: Vec<Box<dyn xml::Node>> = Vec::new();
-        let mut case_texts = Vec::new();
-        for (k, v) in &icu.cases {
-            let case_nodes = v.visit(self, None);
-            let case_text = if let Ok(nodes_vec) = case_nodes.downcast::<Vec<Box<dyn xml::Node>>>()
-            {
-                xml::serialize(&nodes_vec)
-            } else {
-                String::new()
-            };
-            case_texts.push(format!("{} {{{}}}", k, case_text));
+        let expression = icu
+            .expression_placeholder
+            .as_ref()
+            .unwrap_or(&icu.expression);
+        let mut result: Vec<Box<dyn xml::Node>> = vec![Box::new(xml::Text::new(format!(
+            "{{{}, {}, ",
+            expression, icu.type_
+        )))];
+        for (c, case) in &icu.cases {
+            result.push(Box::new(xml::Text::new(format!("{} {{", c))));
+            result.extend(self.visit_all(std::slice::from_ref(case)));
+            result.push(Box::new(xml::Text::new("} ".to_string())));
         }
-        let icu_text = format!(
-            "{{{{{}}}, {}, {}}}",
-            icu.expression,
-            icu.type_,
-            case_texts.join(" ")
-        );
-        result.push(Box::new(xml::Text::new(icu_text)));
+        result.push(Box::new(xml::Text::new("}".to_string())));
         Box::new(result)
     }
 
@@ -197,18 +278,26 @@ impl Visitor for Xliff2Visitor {
         ph: &i18n::TagPlaceholder,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
-        let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.start_name.clone());
-        let mut ph_tag = xml::Tag::new(PLACEHOLDER_SPANNING_TAG.to_string(), attrs, Vec::new());
-
-        for child in &ph.children {
-            let child_nodes = child.visit(self, None);
-            if let Ok(nodes_vec) = child_nodes.downcast::<Vec<Box<dyn xml::Node>>>() {
-                ph_tag.children.extend(*nodes_vec);
-            }
+        let type_ = get_type_for_tag(&ph.tag);
+
+        if ph.is_void {
+            let mut attrs = HashMap::new();
+            attrs.insert("id".to_string(), self.next_id());
+            attrs.insert("equiv".to_string(), ph.start_name.clone());
+            attrs.insert("type".to_string(), type_.to_string());
+            attrs.insert("disp".to_string(), format!("<{}/>", ph.tag));
+            let tag_ph = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
+            return Box::new(vec![Box::new(tag_ph) as Box<dyn xml::Node>]);
         }
 
-        Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
+        let mut attrs = HashMap::new();
+        attrs.insert("equivStart".to_string(), ph.start_name.clone());
+        attrs.insert("equivEnd".to_string(), ph.close_name.clone());
+        attrs.insert("type".to_string(), type_.to_string());
+        attrs.insert("dispStart".to_string(), format!("<{}>", ph.tag));
+        attrs.insert("dispEnd".to_string(), format!("</{}>", ph.tag));
+        let tag_pc = self.spanning_tag(attrs, &ph.children);
+        Box::new(vec![Box::new(tag_pc) as Box<dyn xml::Node>])
     }
 
     fn visit_placeholder(
@@ -217,7 +306,9 @@ impl Visitor for Xliff2Visitor {
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
         let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.name.clone());
+        attrs.insert("id".to_string(), self.next_id());
+        attrs.insert("equiv".to_string(), ph.name.clone());
+        attrs.insert("disp".to_string(), format!("{{{{{}}}}}", ph.value));
         let ph_tag = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
         Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
     }
@@ -228,17 +319,13 @@ impl Visitor for Xliff2Visitor {
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
         let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.start_name.clone());
-        let mut ph_tag = xml::Tag::new(PLACEHOLDER_SPANNING_TAG.to_string(), attrs, Vec::new());
-
-        for child in &ph.children {
-            let child_nodes = child.visit(self, None);
-            if let Ok(nodes_vec) = child_nodes.downcast::<Vec<Box<dyn xml::Node>>>() {
-                ph_tag.children.extend(*nodes_vec);
-            }
-        }
-
-        Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
+        attrs.insert("equivStart".to_string(), ph.start_name.clone());
+        attrs.insert("equivEnd".to_string(), ph.close_name.clone());
+        attrs.insert("type".to_string(), "other".to_string());
+        attrs.insert("dispStart".to_string(), format!("@{}", ph.name));
+        attrs.insert("dispEnd".to_string(), "}".to_string());
+        let block_pc = self.spanning_tag(attrs, &ph.children);
+        Box::new(vec![Box::new(block_pc) as Box<dyn xml::Node>])
     }
 
     fn visit_icu_placeholder(
@@ -246,9 +333,242 @@ impl Visitor for Xliff2Visitor {
         ph: &i18n::IcuPlaceholder,
         _context: Option<&mut dyn std::any::Any>,
     ) -> Box<dyn std::any::Any> {
+        let cases = ph
+            .value
+            .cases
+            .keys()
+            .map(|value| format!("{} {{...}}", value))
+            .collect::<Vec<_>>()
+            .join(" ");
         let mut attrs = HashMap::new();
-        attrs.insert("id".to_string(), ph.name.clone());
+        attrs.insert("id".to_string(), self.next_id());
+        attrs.insert("equiv".to_string(), ph.name.clone());
+        attrs.insert(
+            "disp".to_string(),
+            format!("{{{}, {}, {}}}", ph.value.expression, ph.value.type_, cases),
+        );
         let ph_tag = xml::Tag::new(PLACEHOLDER_TAG.to_string(), attrs, Vec::new());
         Box::new(vec![Box::new(ph_tag) as Box<dyn xml::Node>])
     }
 }
+
+/// The `type` of the placeholders of an element.
+fn get_type_for_tag(tag: &str) -> &'static str {
+    match tag.to_lowercase().as_str() {
+        "br" | "b" | "i" | "u" => "fmt",
+        "img" => "image",
+        "a" => "link",
+        _ => "other",
+    }
+}
+
+/// Extract the `<target>` elements of the units of an XLIFF 2.0 file, by message id.
+#[derive(Default)]
+struct Xliff2Parser {
+    /// The `<target>` of the current unit.
+    unit_target: Option<html::Element>,
+    targets: IndexMap<String, html::Element>,
+    errors: Vec<ParseError>,
+    locale: Option<String>,
+}
+
+impl Xliff2Parser {
+    fn parse(
+        &mut self,
+        xliff: &str,
+        url: &str,
+    ) -> (IndexMap<String, html::Element>, Vec<ParseError>) {
+        let xml = XmlParser::new().parse(xliff, url, None);
+        self.errors = xml.errors;
+        self.visit_all(&xml.root_nodes);
+        (
+            std::mem::take(&mut self.targets),
+            std::mem::take(&mut self.errors),
+        )
+    }
+
+    fn visit_all(&mut self, nodes: &[html::Node]) {
+        for node in nodes {
+            if let html::Node::Element(element) = node {
+                self.visit_element(element);
+            }
+        }
+    }
+
+    fn visit_element(&mut self, element: &html::Element) {
+        match &*element.name {
+            UNIT_TAG => {
+                self.unit_target = None;
+                let Some(id) = get_attr(element, "id") else {
+                    self.add_error(
+                        element,
+                        format!("<{}> misses the \"id\" attribute", UNIT_TAG),
+                    );
+                    return;
+                };
+                if self.targets.contains_key(id) {
+                    self.add_error(element, format!("Duplicated translations for msg {}", id));
+                    return;
+                }
+                self.visit_all(&element.children);
+                match self.unit_target.take() {
+                    Some(target) => {
+                        self.targets.insert(id.to_string(), target);
+                    }
+                    None => self.add_error(element, format!("Message {} misses a translation", id)),
+                }
+            }
+            SOURCE_TAG => {}
+            TARGET_TAG => self.unit_target = Some(element.clone()),
+            XLIFF_TAG => {
+                if let Some(locale) = get_attr(element, "trgLang") {
+                    self.locale = Some(locale.to_string());
+                }
+                if let Some(version) = get_attr(element, "version") {
+                    if version != VERSION {
+                        self.add_error(
+                            element,
+                            format!(
+                                "The XLIFF file version {} is not compatible with XLIFF 2.0 serializer",
+                                version
+                            ),
+                        );
+                    } else {
+                        self.visit_all(&element.children);
+                    }
+                }
+            }
+            // `<file>`, `<segment>`, `<group>` and any unknown tag
+            _ => self.visit_all(&element.children),
+        }
+    }
+
+    fn add_error(&mut self, element: &html::Element, message: String) {
+        self.errors
+            .push(ParseError::new(element.source_span.clone(), message));
+    }
+}
+
+/// Convert the content of a `<target>` element to i18n nodes.
+#[derive(Default)]
+struct XmlToI18n {
+    errors: Vec<ParseError>,
+}
+
+impl XmlToI18n {
+    fn convert(
+        &mut self,
+        content: &str,
+        url: &str,
+        target: &html::Element,
+    ) -> (Vec<Node>, Vec<ParseError>) {
+        let xml_icu = parse_element_content(content, url, target);
+        self.errors = xml_icu.errors;
+        let i18n_nodes = if self.errors.is_empty() {
+            self.visit_all(&xml_icu.root_nodes)
+        } else {
+            Vec::new()
+        };
+        (i18n_nodes, std::mem::take(&mut self.errors))
+    }
+
+    fn visit_all(&mut self, nodes: &[html::Node]) -> Vec<Node> {
+        nodes.iter().flat_map(|node| self.visit(node)).collect()
+    }
+
+    fn visit(&mut self, node: &html::Node) -> Vec<Node> {
+        match node {
+            html::Node::Text(text) => vec![Node::Text(i18n::Text::new(
+                text.value.to_string(),
+                text.source_span.clone(),
+            ))],
+            html::Node::Element(el) => self.visit_element(el),
+            html::Node::Expansion(icu) => {
+                let cases = icu
+                    .cases
+                    .iter()
+                    .map(|c| {
+                        let nodes = self.visit_all(&c.expression);
+                        (
+                            c.value.to_string(),
+                            Node::Container(i18n::Container::new(nodes, icu.source_span.clone())),
+                        )
+                    })
+                    .collect();
+                vec![Node::Icu(i18n::Icu::new(
+                    icu.switch_value.to_string(),
+                    icu.expansion_type.to_string(),
+                    cases,
+                    icu.source_span.clone(),
+                    None,
+                ))]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn visit_element(&mut self, el: &html::Element) -> Vec<Node> {
+        let placeholder = |name: &str| {
+            Node::Placeholder(i18n::Placeholder::new(
+                String::new(),
+                name.to_string(),
+                el.source_span.clone(),
+            ))
+        };
+        match &*el.name {
+            PLACEHOLDER_TAG => match get_attr(el, "equiv") {
+                Some(name) => vec![placeholder(name)],
+                None => {
+                    self.add_error(
+                        el,
+                        format!("<{}> misses the \"equiv\" attribute", PLACEHOLDER_TAG),
+                    );
+                    Vec::new()
+                }
+            },
+            PLACEHOLDER_SPANNING_TAG => {
+                match (get_attr(el, "equivStart"), get_attr(el, "equivEnd")) {
+                    (Some(start_id), Some(end_id)) => {
+                        let mut nodes = vec![placeholder(start_id)];
+                        nodes.extend(self.visit_all(&el.children));
+                        nodes.push(placeholder(end_id));
+                        nodes
+                    }
+                    (start, _) => {
+                        let attr = if start.is_none() {
+                            "equivStart"
+                        } else {
+                            "equivEnd"
+                        };
+                        self.add_error(
+                            el,
+                            format!(
+                                "<{}> misses the \"{}\" attribute",
+                                PLACEHOLDER_SPANNING_TAG, attr
+                            ),
+                        );
+                        Vec::new()
+                    }
+                }
+            }
+            MARKER_TAG => self.visit_all(&el.children),
+            _ => {
+                self.add_error(el, "Unexpected tag".to_string());
+                Vec::new()
+            }
+        }
+    }
+
+    fn add_error(&mut self, element: &html::Element, message: String) {
+        self.errors
+            .push(ParseError::new(element.source_span.clone(), message));
+    }
+}
+
+fn get_attr<'a>(element: &'a html::Element, name: &str) -> Option<&'a str> {
+    element
+        .attrs
+        .iter()
+        .find(|attr| &*attr.name == name)
+        .map(|attr| &*attr.value)
+}
@@ -101,27 +101,33 @@ impl Serializer for Xmb {
             let mut msg_tag = xml::Tag::new(MESSAGE_TAG.to_string(), attrs, Vec::new());
             msg_tag.children.extend(source_tags);
             msg_tag.children.extend(msg_nodes);
+            root.children.push(Box::new(xml::CR::new(2)));
             root.children.push(Box::new(msg_tag));
         }
+        root.children.push(Box::new(xml::CR::new(0)));
 
         let mut decl_attrs = HashMap::new();
         decl_attrs.insert("version".to_string(), "1.0".to_string());
         decl_attrs.insert("encoding".to_string(), "UTF-8".to_string());
 
-        let mut nodes: Vec<Box<dyn xml::Node>> = Vec::new();
-        nodes.push(Box::new(xml::Declaration::new(decl_attrs)));
-        nodes.push(Box::new(xml::Doctype::new(
-            MESSAGES_TAG.to_string(),
-            DOCTYPE.to_string(),
-        )));
-        nodes.push(Box::new(root));
+        let nodes: Vec<Box<dyn xml::Node>> = vec![
+            Box::new(xml::Declaration::new(decl_attrs)),
+            Box::new(xml::CR::new(0)),
+            Box::new(xml::Doctype::new(
+                MESSAGES_TAG.to_string(),
+                DOCTYPE.to_string(),
+            )),
+            Box::new(xml::CR::new(0)),
+            Box::new(root),
+            Box::new(xml::CR::new(0)),
+        ];
 
         xml::serialize(&nodes)
     }
 
-    fn load(&self, _content: &str, _url: &str) -> LoadResult {
+    fn load(&self, _content: &str, _url: &str) -> Result<LoadResult, String> {
         // XMB is write-only format, use XTB for loading
-        panic!("Unsupported: XMB is a write-only format. Use XTB to load translations.");
+        Err("Unsupported: XMB is a write-only format. Use XTB to load translations.".to_string())
     }
 
     fn digest(&self, message: &Message) -> String {
@@ -39,7 +39,18 @@ impl IVisitor for Visitor {
     }
 
     fn visit_declaration(&mut self, decl: &Declaration) -> String {
-        let str_attrs = self.serialize_attributes(&decl.attrs);
+        // The declaration's pseudo-attributes must appear in this order.
+        let mut names: Vec<&String> = decl.attrs.keys().collect();
+        names.sort_by_key(|name| {
+            let position = ["version", "encoding", "standalone"]
+                .iter()
+                .position(|n| n == name);
+            (position.unwrap_or(usize::MAX), name.as_str())
+        });
+        let str_attrs: String = names
+            .iter()
+            .map(|name| format!(" {}=\"{}\"", name, decl.attrs[*name]))
+            .collect();
         format!("<?xml{} ?>", str_attrs)
     }
 
@@ -2,14 +2,17 @@
 //!
 //! Corresponds to packages/compiler/src/i18n/serializers/xtb.ts
 //! XTB (XML Translation Bundle) format loader
-#![allow(dead_code)]
 
-use crate::i18n::i18n_ast::Message;
+use crate::i18n::i18n_ast::{self as i18n, Message, Node};
 use crate::i18n::serializers::serializer::{
-    PlaceholderMapper, Serializer, SimplePlaceholderMapper,
+    parse_element_content, PlaceholderMapper, Serializer, SimplePlaceholderMapper,
 };
 use crate::i18n::serializers::xmb::{to_public_name, Xmb};
 use crate::i18n::translation_bundle::LoadResult;
+use crate::ml_parser::ast as html;
+use crate::ml_parser::xml_parser::XmlParser;
+use crate::parse_util::ParseError;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 const TRANSLATIONS_TAG: &str = "translationbundle";
@@ -39,12 +42,35 @@ impl Serializer for Xtb {
         panic!("Unsupported: XTB is a read-only format. Use XMB to write messages.");
     }
 
-    fn load(&self, _content: &str, _url: &str) -> LoadResult {
-        // TODO: Implement XTB load
-        LoadResult {
-            locale: None,
-            i18n_nodes_by_msg_id: HashMap::new(),
+    fn load(&self, content: &str, url: &str) -> Result<LoadResult, String> {
+        // xtb to xml nodes
+        let mut xtb_parser = XtbParser::default();
+        let (translations, mut errors) = xtb_parser.parse(content, url);
+
+        // xml nodes to i18n nodes
+        let mut i18n_nodes_by_msg_id = HashMap::new();
+        let mut converter = XmlToI18n::default();
+        for (msg_id, translation) in translations {
+            let (i18n_nodes, e) = converter.convert(content, url, &translation);
+            errors.extend(e);
+            i18n_nodes_by_msg_id.insert(msg_id, i18n_nodes);
+        }
+
+        if !errors.is_empty() {
+            return Err(format!(
+                "xtb parse errors:\n{}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
         }
+
+        Ok(LoadResult {
+            locale: xtb_parser.locale,
+            i18n_nodes_by_msg_id,
+        })
     }
 
     fn digest(&self, message: &Message) -> String {
@@ -59,7 +85,166 @@ impl Serializer for Xtb {
     }
 }
 
-// TODO: Implement XtbParser for parsing XTB XML
-// This parser extracts translations from XTB format
+/// Extract the `<translation>` elements of an XTB file, by message id.
+#[derive(Default)]
+struct XtbParser {
+    bundle_depth: usize,
+    translations: IndexMap<String, html::Element>,
+    errors: Vec<ParseError>,
+    locale: Option<String>,
+}
+
+impl XtbParser {
+    fn parse(
+        &mut self,
+        xtb: &str,
+        url: &str,
+    ) -> (IndexMap<String, html::Element>, Vec<ParseError>) {
+        self.bundle_depth = 0;
+        // The ICU messages are parsed with each translation, as the messages that do not
+        // originate from Angular might not be lexed as such.
+        let xml = XmlParser::new().parse(xtb, url, None);
+        self.errors = xml.errors;
+        self.visit_all(&xml.root_nodes);
+        (
+            std::mem::take(&mut self.translations),
+            std::mem::take(&mut self.errors),
+        )
+    }
 
-// TODO: Implement XmlToI18n for converting XTB XML to i18n nodes
+    fn visit_all(&mut self, nodes: &[html::Node]) {
+        for node in nodes {
+            if let html::Node::Element(element) = node {
+                self.visit_element(element);
+            }
+        }
+    }
+
+    fn visit_element(&mut self, element: &html::Element) {
+        match &*element.name {
+            TRANSLATIONS_TAG => {
+                self.bundle_depth += 1;
+                if self.bundle_depth > 1 {
+                    self.add_error(
+                        element,
+                        format!("<{}> elements can not be nested", TRANSLATIONS_TAG),
+                    );
+                }
+                if let Some(locale) = get_attr(element, "lang") {
+                    self.locale = Some(locale.to_string());
+                }
+                self.visit_all(&element.children);
+                self.bundle_depth -= 1;
+            }
+            TRANSLATION_TAG => {
+                let Some(id) = get_attr(element, "id") else {
+                    self.add_error(
+                        element,
+                        format!("<{}> misses the \"id\" attribute", TRANSLATION_TAG),
+                    );
+                    return;
+                };
+                if self.translations.contains_key(id) {
+                    self.add_error(element, format!("Duplicated translations for msg {}", id));
+                } else {
+                    self.translations.insert(id.to_string(), element.clone());
+                }
+            }
+            _ => self.add_error(element, "Unexpected tag".to_string()),
+        }
+    }
+
+    fn add_error(&mut self, element: &html::Element, message: String) {
+        self.errors
+            .push(ParseError::new(element.source_span.clone(), message));
+    }
+}
+
+/// Convert the content of a `<translation>` element to i18n nodes.
+#[derive(Default)]
+struct XmlToI18n {
+    errors: Vec<ParseError>,
+}
+
+impl XmlToI18n {
+    fn convert(
+        &mut self,
+        content: &str,
+        url: &str,
+        translation: &html::Element,
+    ) -> (Vec<Node>, Vec<ParseError>) {
+        let xml_icu = parse_element_content(content, url, translation);
+        self.errors = xml_icu.errors;
+        let i18n_nodes = if self.errors.is_empty() {
+            self.visit_all(&xml_icu.root_nodes)
+        } else {
+            Vec::new()
+        };
+        (i18n_nodes, std::mem::take(&mut self.errors))
+    }
+
+    fn visit_all(&mut self, nodes: &[html::Node]) -> Vec<Node> {
+        nodes.iter().filter_map(|node| self.visit(node)).collect()
+    }
+
+    fn visit(&mut self, node: &html::Node) -> Option<Node> {
+        match node {
+            html::Node::Text(text) => Some(Node::Text(i18n::Text::new(
+                text.value.to_string(),
+                text.source_span.clone(),
+            ))),
+            html::Node::Element(el) if &*el.name == PLACEHOLDER_TAG => match get_attr(el, "name") {
+                Some(name) => Some(Node::Placeholder(i18n::Placeholder::new(
+                    String::new(),
+                    name.to_string(),
+                    el.source_span.clone(),
+                ))),
+                None => {
+                    self.add_error(
+                        el,
+                        format!("<{}> misses the \"name\" attribute", PLACEHOLDER_TAG),
+                    );
+                    None
+                }
+            },
+            html::Node::Element(el) => {
+                self.add_error(el, "Unexpected tag".to_string());
+                None
+            }
+            html::Node::Expansion(icu) => {
+                let cases = icu
+                    .cases
+                    .iter()
+                    .map(|c| {
+                        let nodes = self.visit_all(&c.expression);
+                        (
+                            c.value.to_string(),
+                            Node::Container(i18n::Container::new(nodes, icu.source_span.clone())),
+                        )
+                    })
+                    .collect();
+                Some(Node::Icu(i18n::Icu::new(
+                    icu.switch_value.to_string(),
+                    icu.expansion_type.to_string(),
+                    cases,
+                    icu.source_span.clone(),
+                    None,
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    fn add_error(&mut self, element: &html::Element, message: String) {
+        self.errors
+            .push(ParseError::new(element.source_span.clone(), message));
+    }
+}
+
+fn get_attr<'a>(element: &'a html::Element, name: &str) -> Option<&'a str> {
+    element
+        .attrs
+        .iter()
+        .find(|attr| &*attr.name == name)
+        .map(|attr| &*attr.value)
+}
@@ -13,21 +13,23 @@ use crate::i18n::serializers::xml_helper::escape_xml;
 use crate::ml_parser::ast as html;
 use crate::ml_parser::html_parser::HtmlParser;
 use crate::ml_parser::lexer::TokenizeOptions;
-use crate::parse_util::ParseError;
+use crate::parse_util::{ParseError, ParseErrorLevel};
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
 /// A container for translated messages
 pub struct TranslationBundle {
-    i18n_nodes_by_msg_id: HashMap<String, Vec<Node>>,
+    i18n_nodes_by_msg_id: Arc<HashMap<String, Vec<Node>>>,
     locale: Option<String>,
     digest_fn: Rc<dyn Fn(&Message) -> String>,
     mapper_factory: Option<Rc<dyn Fn(&Message) -> Box<dyn PlaceholderMapper>>>,
     missing_translation_strategy: MissingTranslationStrategy,
     i18n_to_html: I18nToHtmlVisitor,
     // Store serializer for load method - wrapped in Arc for sharing
-    serializer: Option<Arc<dyn Serializer>>,
+    serializer: Option<Arc<dyn Serializer + Send + Sync>>,
+    /// Missing translations reported with the `Warning` strategy, see `take_warnings()`.
+    warnings: Vec<ParseError>,
 }
 
 impl TranslationBundle {
@@ -37,6 +39,22 @@ impl TranslationBundle {
         digest_fn: Rc<dyn Fn(&Message) -> String>,
         mapper_factory: Option<Rc<dyn Fn(&Message) -> Box<dyn PlaceholderMapper>>>,
         missing_translation_strategy: MissingTranslationStrategy,
+    ) -> Self {
+        Self::from_shared(
+            Arc::new(i18n_nodes_by_msg_id),
+            locale,
+            digest_fn,
+            mapper_factory,
+            missing_translation_strategy,
+        )
+    }
+
+    fn from_shared(
+        i18n_nodes_by_msg_id: Arc<HashMap<String, Vec<Node>>>,
+        locale: Option<String>,
+        digest_fn: Rc<dyn Fn(&Message) -> String>,
+        mapper_factory: Option<Rc<dyn Fn(&Message) -> Box<dyn PlaceholderMapper>>>,
+        missing_translation_strategy: MissingTranslationStrategy,
     ) -> Self {
         let i18n_to_html = I18nToHtmlVisitor::new(
             i18n_nodes_by_msg_id.clone(),
@@ -54,6 +72,7 @@ impl TranslationBundle {
             missing_translation_strategy,
             i18n_to_html,
             serializer: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -65,17 +84,31 @@ impl TranslationBundle {
         serializer: S,
         missing_translation_strategy: MissingTranslationStrategy,
     ) -> Result<Self, String> {
-        let load_result = serializer.load(content, url);
-
-        // Wrap serializer in Arc for sharing
-        let serializer_arc = Arc::new(serializer);
+        let load_result = serializer.load(content, url)?;
+        Ok(Self::with_serializer(
+            Arc::new(load_result.i18n_nodes_by_msg_id),
+            load_result.locale,
+            Arc::new(serializer),
+            missing_translation_strategy,
+        ))
+    }
 
+    /// Creates a `TranslationBundle` for messages loaded by `serializer`.
+    ///
+    /// The messages are shared rather than copied, so that a bundle can be created cheaply
+    /// for each thread translating templates.
+    pub fn with_serializer(
+        i18n_nodes_by_msg_id: Arc<HashMap<String, Vec<Node>>>,
+        locale: Option<String>,
+        serializer: Arc<dyn Serializer + Send + Sync>,
+        missing_translation_strategy: MissingTranslationStrategy,
+    ) -> Self {
         // Create closures that capture the Arc
-        let serializer_clone = serializer_arc.clone();
+        let serializer_clone = serializer.clone();
         let digest_fn: Rc<dyn Fn(&Message) -> String> =
             Rc::new(move |m: &Message| serializer_clone.digest(m));
 
-        let serializer_clone2 = serializer_arc.clone();
+        let serializer_clone2 = serializer.clone();
         let mapper_factory: Option<Rc<dyn Fn(&Message) -> Box<dyn PlaceholderMapper>>> =
             Some(Rc::new(move |m: &Message| {
                 serializer_clone2
@@ -83,37 +116,116 @@ impl TranslationBundle {
                     .unwrap_or_else(|| Box::new(NoOpPlaceholderMapper))
             }));
 
-        let mut bundle = TranslationBundle::new(
-            load_result.i18n_nodes_by_msg_id,
-            load_result.locale,
+        let mut bundle = TranslationBundle::from_shared(
+            i18n_nodes_by_msg_id,
+            locale,
             digest_fn,
             mapper_factory,
             missing_translation_strategy,
         );
-        bundle.serializer = Some(serializer_arc);
-        Ok(bundle)
+        bundle.serializer = Some(serializer);
+        bundle
     }
 
     /// Returns the translation as HTML nodes from the given source message.
-    pub fn get(&mut self, src_msg: &Message) -> Result<Vec<html::Node>, String> {
+    ///
+    /// Missing translations are errors with the `Error` strategy. With the `Warning` strategy
+    /// they are collected as warnings, see `take_warnings()`, and the source message is used.
+    pub fn get(&mut self, src_msg: &Message) -> Result<Vec<html::Node>, Vec<ParseError>> {
         let result = self.i18n_to_html.convert(src_msg);
 
-        if !result.errors.is_empty() {
-            return Err(result
-                .errors
-                .iter()
-                .map(|e| e.to_string())
-                .collect::<Vec<_>>()
-                .join("\n"));
+        let (warnings, errors): (Vec<_>, Vec<_>) = result
+            .errors
+            .into_iter()
+            .partition(|e| e.level == ParseErrorLevel::Warning);
+        self.warnings.extend(warnings);
+        if !errors.is_empty() {
+            return Err(errors);
         }
 
         Ok(result.nodes)
     }
 
+    /// Returns the translation of the given source message as i18n nodes, with their
+    /// placeholders named as in the source message, or `None` when it is not translated.
+    pub fn get_i18n_nodes(&self, src_msg: &Message) -> Option<Vec<Node>> {
+        let id = (self.digest_fn)(src_msg);
+        let nodes = self.i18n_nodes_by_msg_id.get(&id)?;
+        let mapper = self.mapper_factory.as_ref().map(|factory| factory(src_msg));
+        Some(
+            nodes
+                .iter()
+                .map(|node| rename_placeholders(node, mapper.as_deref()))
+                .collect(),
+        )
+    }
+
     pub fn has(&self, src_msg: &Message) -> bool {
         let id = (self.digest_fn)(src_msg);
         self.i18n_nodes_by_msg_id.contains_key(&id)
     }
+
+    /// The id of the given source message in the translations.
+    pub fn digest(&self, src_msg: &Message) -> String {
+        (self.digest_fn)(src_msg)
+    }
+
+    /// The locale of the translations, when the translation file declares it.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    pub fn missing_translation_strategy(&self) -> MissingTranslationStrategy {
+        self.missing_translation_strategy
+    }
+
+    /// Returns and clears the missing translations reported since the last call.
+    pub fn take_warnings(&mut self) -> Vec<ParseError> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+/// Rename the placeholders of translated nodes from their serialized names to the internal
+/// names of the source message.
+fn rename_placeholders(node: &Node, mapper: Option<&dyn PlaceholderMapper>) -> Node {
+    let rename = |name: &str| {
+        mapper
+            .and_then(|mapper| mapper.to_internal_name(name))
+            .unwrap_or_else(|| name.to_string())
+    };
+    let rename_all = |nodes: &[Node]| {
+        nodes
+            .iter()
+            .map(|node| rename_placeholders(node, mapper))
+            .collect()
+    };
+    match node {
+        Node::Placeholder(ph) => Node::Placeholder(Placeholder {
+            name: rename(&ph.name),
+            ..ph.clone()
+        }),
+        Node::Container(container) => Node::Container(Container {
+            children: rename_all(&container.children),
+            ..container.clone()
+        }),
+        Node::Icu(icu) => Node::Icu(Icu {
+            cases: icu
+                .cases
+                .iter()
+                .map(|(key, case)| (key.clone(), rename_placeholders(case, mapper)))
+                .collect(),
+            ..icu.clone()
+        }),
+        Node::TagPlaceholder(ph) => Node::TagPlaceholder(TagPlaceholder {
+            children: rename_all(&ph.children),
+            ..ph.clone()
+        }),
+        Node::BlockPlaceholder(ph) => Node::BlockPlaceholder(BlockPlaceholder {
+            children: rename_all(&ph.children),
+            ..ph.clone()
+        }),
+        Node::Text(_) | Node::IcuPlaceholder(_) => node.clone(),
+    }
 }
 
 struct ConvertResult {
@@ -122,7 +234,7 @@ struct ConvertResult {
 }
 
 struct I18nToHtmlVisitor {
-    i18n_nodes_by_msg_id: HashMap<String, Vec<Node>>,
+    i18n_nodes_by_msg_id: Arc<HashMap<String, Vec<Node>>>,
     locale: Option<String>,
     digest_fn: Rc<dyn Fn(&Message) -> String>,
     mapper_factory: Option<Rc<dyn Fn(&Message) -> Box<dyn PlaceholderMapper>>>,
@@ -159,7 +271,7 @@ impl PlaceholderMapper for NoOpPlaceholderMapper {
 
 impl I18nToHtmlVisitor {
     fn new(
-        i18n_nodes_by_msg_id: HashMap<String, Vec<Node>>,
+        i18n_nodes_by_msg_id: Arc<HashMap<String, Vec<Node>>>,
         locale: Option<String>,
         digest_fn: Rc<dyn Fn(&Message) -> String>,
         mapper_factory: Option<Rc<dyn Fn(&Message) -> Box<dyn PlaceholderMapper>>>,
@@ -222,7 +334,7 @@ impl I18nToHtmlVisitor {
             msg: current_msg.unwrap_or_else(|| {
                 Message::new(
                     vec![],
-                    HashMap::new(),
+                    indexmap::IndexMap::new(),
                     HashMap::new(),
                     String::new(),
                     String::new(),
@@ -255,20 +367,25 @@ impl I18nToHtmlVisitor {
             // - report an error / a warning / nothing,
             // - use the nodes from the original message
             // - placeholders are already internal and need no mapper
-            if self.missing_translation_strategy == MissingTranslationStrategy::Error {
+            let level = match self.missing_translation_strategy {
+                MissingTranslationStrategy::Error => Some(ParseErrorLevel::Error),
+                MissingTranslationStrategy::Warning => Some(ParseErrorLevel::Warning),
+                MissingTranslationStrategy::Ignore => None,
+            };
+            if let (Some(level), Some(node)) = (level, src_msg.nodes.first()) {
                 let ctx = if let Some(ref locale) = self.locale {
                     format!(" for locale \"{}\"", locale)
                 } else {
                     String::new()
                 };
-                if !src_msg.nodes.is_empty() {
-                    self.add_error(
-                        &src_msg.nodes[0],
-                        &format!("Missing translation for message \"{}\"{}", id, ctx),
-                    );
-                }
+                self.errors.push(ParseError {
+                    level,
+                    ..ParseError::new(
+                        node.source_span().clone(),
+                        format!("Missing translation for message \"{}\"{}", id, ctx),
+                    )
+                });
             }
-            // Note: Warning strategy would require a Console trait, which we don't have yet
             nodes = src_msg.nodes.clone();
             mapper_fn = Box::new(|name: &str| name.to_string());
         }
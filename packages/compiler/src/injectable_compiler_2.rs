@@ -5,40 +5,21 @@
 //! Compiles @Injectable decorators to generate factory functions
 //! for dependency injection.
 
-use serde::{Deserialize, Serialize};
-
-/// Expression reference (simplified)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Expression {
-    pub value: serde_json::Value,
-}
-
-/// R3 Reference (to a type)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct R3Reference {
-    pub value: Expression,
-    #[serde(rename = "type")]
-    pub type_ref: Expression,
-}
-
-/// Maybe forward ref expression
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MaybeForwardRefExpression {
-    pub expression: Expression,
-    pub forward_ref: bool,
-}
-
-/// Dependency metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct R3DependencyMetadata {
-    pub token: Expression,
-    pub attribute: Option<String>,
-    pub host: bool,
-    pub optional: bool,
-    #[serde(rename = "self")]
-    pub self_dep: bool,
-    pub skip_self: bool,
-}
+use crate::output::output_ast::{
+    dynamic_type, ArrowFunctionBody, ArrowFunctionExpr, Expression, ExpressionType, ExternalExpr,
+    FnParam, LiteralExpr, LiteralValue, ReadPropExpr, ReadVarExpr, Type, TypeModifier,
+};
+use crate::render3::r3_factory::{
+    compile_factory_function, DepsOrInvalid, FactoryTarget, R3ConstructorFactoryMetadata,
+    R3DelegatedFnOrClassMetadata, R3DependencyMetadata, R3ExpressionFactoryMetadata,
+    R3FactoryDelegateType, R3FactoryMetadata,
+};
+use crate::render3::r3_identifiers::Identifiers as R3;
+use crate::render3::util::{
+    convert_from_maybe_forward_ref_expression, type_with_parameters, MaybeForwardRefExpression,
+    R3CompiledExpression, R3Reference,
+};
+use crate::render3::view::util::DefinitionMap;
 
 /// Injectable metadata for compilation
 ///
@@ -56,64 +37,38 @@ pub struct R3DependencyMetadata {
 ///   deps?: R3DependencyMetadata[];
 /// }
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct R3InjectableMetadata {
     /// Injectable class name
     pub name: String,
 
     /// Type reference
-    #[serde(rename = "type")]
-    pub type_ref: R3Reference,
+    pub type_: R3Reference,
 
     /// Number of type arguments (for generics)
-    pub type_argument_count: u32,
+    pub type_argument_count: usize,
 
-    /// Where this injectable is provided (root, platform, any, or a specific module)
+    /// Where this injectable is provided (root, platform, any, or a specific module).
+    /// A `null` literal means the injectable is not provided automatically.
     pub provided_in: MaybeForwardRefExpression,
 
     /// Alternative class to use
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_class: Option<MaybeForwardRefExpression>,
 
     /// Factory function to use
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_factory: Option<Expression>,
 
     /// Existing injectable to reference
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_existing: Option<MaybeForwardRefExpression>,
 
     /// Direct value to use
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_value: Option<MaybeForwardRefExpression>,
 
-    /// Dependencies for factory
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Dependencies passed to `useClass` or `useFactory`
     pub deps: Option<Vec<R3DependencyMetadata>>,
 }
 
-/// Compiled expression result
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct R3CompiledExpression {
-    /// Compiled expression
-    pub expression: Expression,
-
-    /// Generated type
-    #[serde(rename = "type")]
-    pub type_expr: Expression,
-
-    /// Additional statements
-    pub statements: Vec<Statement>,
-}
-
-/// Statement (simplified)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Statement {
-    pub kind: String,
-    pub content: String,
-}
-
-/// Compile injectable to factory definition
+/// Compile injectable to an `ɵɵdefineInjectable` call.
 ///
 /// TypeScript equivalent:
 /// ```typescript
@@ -123,133 +78,112 @@ pub struct Statement {
 /// ): R3CompiledExpression
 /// ```
 pub fn compile_injectable(
-    meta: R3InjectableMetadata,
+    meta: &R3InjectableMetadata,
     resolve_forward_refs: bool,
-) -> Result<R3CompiledExpression, String> {
+) -> R3CompiledExpression {
+    let factory_meta = R3ConstructorFactoryMetadata {
+        name: meta.name.clone(),
+        type_: meta.type_.clone(),
+        type_argument_count: meta.type_argument_count,
+        deps: Some(DepsOrInvalid::Valid(vec![])),
+        target: FactoryTarget::Injectable,
+    };
+
     // Determine which factory strategy to use
-    let result = if let Some(use_class) = meta.use_class.clone() {
-        compile_injectable_use_class(meta, use_class, resolve_forward_refs)?
-    } else if let Some(use_factory) = meta.use_factory.clone() {
-        compile_injectable_use_factory(meta, use_factory)?
-    } else if let Some(use_value) = meta.use_value.clone() {
-        compile_injectable_use_value(meta, use_value)?
-    } else if let Some(use_existing) = meta.use_existing.clone() {
-        compile_injectable_use_existing(meta, use_existing)?
+    let result = if let Some(use_class) = &meta.use_class {
+        // meta.useClass has two modes of operation. Either deps are specified, in which case
+        // `new` is used to create the instance, or deps are not specified and the factory of
+        // the useClass type is delegated to.
+        let use_class_on_self = use_class.expression.is_equivalent(&meta.type_.value);
+        if let Some(deps) = &meta.deps {
+            compile_factory_function(&R3FactoryMetadata::Delegated(
+                R3DelegatedFnOrClassMetadata {
+                    base: factory_meta,
+                    delegate: use_class.expression.clone(),
+                    delegate_type: R3FactoryDelegateType::Class,
+                    delegate_deps: deps.clone(),
+                },
+            ))
+        } else if use_class_on_self {
+            compile_factory_function(&R3FactoryMetadata::Constructor(factory_meta))
+        } else {
+            R3CompiledExpression::new(
+                delegate_to_factory(
+                    &meta.type_.value,
+                    &use_class.expression,
+                    resolve_forward_refs,
+                ),
+                dynamic_type(),
+                vec![],
+            )
+        }
+    } else if let Some(use_factory) = &meta.use_factory {
+        if let Some(deps) = &meta.deps {
+            compile_factory_function(&R3FactoryMetadata::Delegated(
+                R3DelegatedFnOrClassMetadata {
+                    base: factory_meta,
+                    delegate: use_factory.clone(),
+                    delegate_type: R3FactoryDelegateType::Function,
+                    delegate_deps: deps.clone(),
+                },
+            ))
+        } else {
+            // `() => useFactory()`
+            R3CompiledExpression::new(
+                arrow_fn(vec![], *use_factory.call_fn(vec![], None, None)),
+                dynamic_type(),
+                vec![],
+            )
+        }
+    } else if let Some(use_value) = &meta.use_value {
+        // Note: it's safe to use `meta.useValue` instead of the `useValue` of the factory
+        // metadata, since the value is an expression that can be evaluated multiple times.
+        compile_factory_function(&R3FactoryMetadata::Expression(
+            R3ExpressionFactoryMetadata {
+                base: factory_meta,
+                expression: use_value.expression.clone(),
+            },
+        ))
+    } else if let Some(use_existing) = &meta.use_existing {
+        // useExisting is an `inject` call on the existing token.
+        compile_factory_function(&R3FactoryMetadata::Expression(
+            R3ExpressionFactoryMetadata {
+                base: factory_meta,
+                expression: *external(R3::inject()).call_fn(
+                    vec![use_existing.expression.clone()],
+                    None,
+                    None,
+                ),
+            },
+        ))
     } else {
         // Default: use the type's own factory
-        compile_injectable_default(meta)?
+        R3CompiledExpression::new(
+            delegate_to_factory(&meta.type_.value, &meta.type_.value, resolve_forward_refs),
+            dynamic_type(),
+            vec![],
+        )
     };
 
-    Ok(result)
-}
+    let mut injectable_props = DefinitionMap::new();
+    injectable_props.set("token", Some(meta.type_.value.clone()));
+    injectable_props.set("factory", Some(result.expression));
 
-/// Compile injectable with useClass
-fn compile_injectable_use_class(
-    meta: R3InjectableMetadata,
-    use_class: MaybeForwardRefExpression,
-    _resolve_forward_refs: bool,
-) -> Result<R3CompiledExpression, String> {
-    // Check if useClass is the same as the type itself
-    let use_class_on_self = is_expression_equivalent(&use_class.expression, &meta.type_ref.value);
-
-    if use_class_on_self && meta.deps.is_none() {
-        // useClass: Type where Type is self - just use default factory
-        return compile_injectable_default(meta);
+    // Only generate providedIn property if it has a non-null value
+    if !is_null_literal(&meta.provided_in.expression) {
+        injectable_props.set(
+            "providedIn",
+            Some(convert_from_maybe_forward_ref_expression(&meta.provided_in)),
+        );
     }
 
-    // Generate factory that instantiates useClass
-    let expression = Expression {
-        value: serde_json::json!({
-            "kind": "factory",
-            "target": "Injectable",
-            "useClass": use_class.expression.value,
-            "deps": meta.deps
-        }),
-    };
+    let expression = *external(R3::define_injectable()).call_fn(
+        vec![Expression::LiteralMap(injectable_props.to_literal_map())],
+        None,
+        Some(true),
+    );
 
-    Ok(R3CompiledExpression {
-        expression,
-        type_expr: create_injectable_type(&meta),
-        statements: vec![],
-    })
-}
-
-/// Compile injectable with useFactory
-fn compile_injectable_use_factory(
-    meta: R3InjectableMetadata,
-    use_factory: Expression,
-) -> Result<R3CompiledExpression, String> {
-    let expression = Expression {
-        value: serde_json::json!({
-            "kind": "factory",
-            "target": "Injectable",
-            "useFactory": use_factory.value,
-            "deps": meta.deps
-        }),
-    };
-
-    Ok(R3CompiledExpression {
-        expression,
-        type_expr: create_injectable_type(&meta),
-        statements: vec![],
-    })
-}
-
-/// Compile injectable with useValue
-fn compile_injectable_use_value(
-    meta: R3InjectableMetadata,
-    use_value: MaybeForwardRefExpression,
-) -> Result<R3CompiledExpression, String> {
-    let expression = Expression {
-        value: serde_json::json!({
-            "kind": "factory",
-            "target": "Injectable",
-            "useValue": use_value.expression.value
-        }),
-    };
-
-    Ok(R3CompiledExpression {
-        expression,
-        type_expr: create_injectable_type(&meta),
-        statements: vec![],
-    })
-}
-
-/// Compile injectable with useExisting
-fn compile_injectable_use_existing(
-    meta: R3InjectableMetadata,
-    use_existing: MaybeForwardRefExpression,
-) -> Result<R3CompiledExpression, String> {
-    // useExisting is an inject() call on the existing token
-    let expression = Expression {
-        value: serde_json::json!({
-            "kind": "inject",
-            "token": use_existing.expression.value
-        }),
-    };
-
-    Ok(R3CompiledExpression {
-        expression,
-        type_expr: create_injectable_type(&meta),
-        statements: vec![],
-    })
-}
-
-/// Compile injectable with default factory
-fn compile_injectable_default(meta: R3InjectableMetadata) -> Result<R3CompiledExpression, String> {
-    let expression = Expression {
-        value: serde_json::json!({
-            "kind": "factory",
-            "target": "Injectable",
-            "type": meta.type_ref.value.value
-        }),
-    };
-
-    Ok(R3CompiledExpression {
-        expression,
-        type_expr: create_injectable_type(&meta),
-        statements: vec![],
-    })
+    R3CompiledExpression::new(expression, create_injectable_type(meta), result.statements)
 }
 
 /// Create injectable type expression
@@ -264,160 +198,223 @@ fn compile_injectable_default(meta: R3InjectableMetadata) -> Result<R3CompiledEx
 ///   );
 /// }
 /// ```
-pub fn create_injectable_type(meta: &R3InjectableMetadata) -> Expression {
-    Expression {
-        value: serde_json::json!({
-            "kind": "InjectableDeclaration",
-            "type": meta.type_ref.type_ref.value,
-            "typeArguments": meta.type_argument_count
-        }),
+pub fn create_injectable_type(meta: &R3InjectableMetadata) -> Type {
+    Type::Expression(ExpressionType {
+        value: Box::new(*external(R3::injectable_declaration())),
+        modifiers: TypeModifier::None,
+        type_params: Some(vec![type_with_parameters(
+            meta.type_.type_expr.clone(),
+            meta.type_argument_count,
+        )]),
+    })
+}
+
+/// Delegate to the factory of `use_type`. When `use_type` is the injectable itself its
+/// factory is referenced directly.
+fn delegate_to_factory(
+    type_: &Expression,
+    use_type: &Expression,
+    unwrap_forward_refs: bool,
+) -> Expression {
+    if type_.is_equivalent(use_type) {
+        // `Type.ɵfac`
+        return fac_of(use_type);
     }
+
+    if !unwrap_forward_refs {
+        // `(__ngFactoryType__) => UseType.ɵfac(__ngFactoryType__)`
+        return create_factory_function(use_type.clone());
+    }
+
+    // `(__ngFactoryType__) => resolveForwardRef(UseType).ɵfac(__ngFactoryType__)`
+    let unwrapped_type =
+        *external(R3::resolve_forward_ref()).call_fn(vec![use_type.clone()], None, None);
+    create_factory_function(unwrapped_type)
 }
 
-/// Check if two expressions are equivalent
-fn is_expression_equivalent(expr1: &Expression, expr2: &Expression) -> bool {
-    // Simplified comparison - compare JSON values
-    expr1.value == expr2.value
+fn create_factory_function(type_: Expression) -> Expression {
+    let param = "__ngFactoryType__";
+    arrow_fn(
+        vec![FnParam {
+            name: param.to_string(),
+            type_: Some(dynamic_type()),
+        }],
+        *fac_of(&type_).call_fn(
+            vec![Expression::ReadVar(ReadVarExpr {
+                name: param.to_string(),
+                type_: None,
+                source_span: None,
+            })],
+            None,
+            None,
+        ),
+    )
 }
 
-/// Helper to create factory function expression
-fn _create_factory_function(use_type: &Expression) -> Expression {
-    Expression {
-        value: serde_json::json!({
-            "kind": "arrowFunction",
-            "params": ["__ngFactoryType__"],
-            "body": {
-                "kind": "callExpression",
-                "callee": {
-                    "kind": "propertyAccess",
-                    "object": use_type.value,
-                    "property": "ɵfac"
-                },
-                "arguments": ["__ngFactoryType__"]
-            }
-        }),
-    }
+fn fac_of(type_: &Expression) -> Expression {
+    Expression::ReadProp(ReadPropExpr {
+        receiver: Box::new(type_.clone()),
+        name: "ɵfac".to_string(),
+        type_: None,
+        source_span: None,
+    })
+}
+
+fn arrow_fn(params: Vec<FnParam>, body: Expression) -> Expression {
+    Expression::ArrowFn(ArrowFunctionExpr {
+        params,
+        body: ArrowFunctionBody::Expression(Box::new(body)),
+        type_: None,
+        source_span: None,
+    })
+}
+
+fn external(reference: crate::output::output_ast::ExternalReference) -> Box<Expression> {
+    Box::new(Expression::External(ExternalExpr {
+        value: reference,
+        type_: None,
+        source_span: None,
+    }))
+}
+
+fn is_null_literal(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Literal(LiteralExpr {
+            value: LiteralValue::Null,
+            ..
+        })
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::abstract_emitter::EmitterVisitorContext;
+    use crate::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+    use crate::output::output_ast::ExpressionTrait;
+    use crate::render3::util::ForwardRefHandling;
+
+    fn var(name: &str) -> Expression {
+        Expression::ReadVar(ReadVarExpr {
+            name: name.to_string(),
+            type_: None,
+            source_span: None,
+        })
+    }
 
-    #[test]
-    fn test_compile_injectable_default() {
-        let meta = R3InjectableMetadata {
-            name: "MyService".to_string(),
-            type_ref: R3Reference {
-                value: Expression {
-                    value: serde_json::json!("MyService"),
-                },
-                type_ref: Expression {
-                    value: serde_json::json!("MyService"),
-                },
+    fn maybe_forward_ref(expression: Expression) -> MaybeForwardRefExpression {
+        MaybeForwardRefExpression {
+            expression,
+            forward_ref: ForwardRefHandling::None,
+        }
+    }
+
+    fn metadata(name: &str) -> R3InjectableMetadata {
+        R3InjectableMetadata {
+            name: name.to_string(),
+            type_: R3Reference {
+                value: var(name),
+                type_expr: var(name),
             },
             type_argument_count: 0,
-            provided_in: MaybeForwardRefExpression {
-                expression: Expression {
-                    value: serde_json::json!("root"),
-                },
-                forward_ref: false,
-            },
+            provided_in: maybe_forward_ref(Expression::Literal(LiteralExpr {
+                value: LiteralValue::String("root".to_string()),
+                type_: None,
+                source_span: None,
+            })),
             use_class: None,
             use_factory: None,
             use_existing: None,
             use_value: None,
             deps: None,
-        };
+        }
+    }
 
-        let result = compile_injectable(meta, false);
-        assert!(result.is_ok());
+    fn emit(meta: &R3InjectableMetadata) -> String {
+        let compiled = compile_injectable(meta, false);
+        let mut ctx = EmitterVisitorContext::create_root();
+        compiled
+            .expression
+            .visit_expression(&mut AbstractJsEmitterVisitor::new(), &mut ctx);
+        ctx.to_source()
+    }
 
-        let compiled = result.unwrap();
-        assert_eq!(compiled.statements.len(), 0);
+    #[test]
+    fn test_compile_injectable_default() {
+        let code = emit(&metadata("MyService"));
+        assert!(code.contains("i0.ɵɵdefineInjectable({"), "{}", code);
+        assert!(code.contains("token: MyService"), "{}", code);
+        assert!(code.contains("factory: MyService.ɵfac"), "{}", code);
+        assert!(code.contains("providedIn: \"root\""), "{}", code);
     }
 
     #[test]
     fn test_compile_injectable_use_value() {
-        let meta = R3InjectableMetadata {
-            name: "MY_TOKEN".to_string(),
-            type_ref: R3Reference {
-                value: Expression {
-                    value: serde_json::json!("MY_TOKEN"),
-                },
-                type_ref: Expression {
-                    value: serde_json::json!("any"),
-                },
-            },
-            type_argument_count: 0,
-            provided_in: MaybeForwardRefExpression {
-                expression: Expression {
-                    value: serde_json::json!("root"),
-                },
-                forward_ref: false,
-            },
-            use_class: None,
-            use_factory: None,
-            use_existing: None,
-            use_value: Some(MaybeForwardRefExpression {
-                expression: Expression {
-                    value: serde_json::json!(42),
-                },
-                forward_ref: false,
-            }),
-            deps: None,
-        };
-
-        let result = compile_injectable(meta, false);
-        assert!(result.is_ok());
+        let mut meta = metadata("MY_TOKEN");
+        meta.use_value = Some(maybe_forward_ref(Expression::Literal(LiteralExpr {
+            value: LiteralValue::Number(42.0),
+            type_: None,
+            source_span: None,
+        })));
+        let code = emit(&meta);
+        assert!(code.contains("__ngConditionalFactory__ = 42"), "{}", code);
     }
 
     #[test]
-    fn test_create_injectable_type() {
-        let meta = R3InjectableMetadata {
-            name: "TestService".to_string(),
-            type_ref: R3Reference {
-                value: Expression {
-                    value: serde_json::json!("TestService"),
-                },
-                type_ref: Expression {
-                    value: serde_json::json!("TestService"),
-                },
-            },
-            type_argument_count: 2,
-            provided_in: MaybeForwardRefExpression {
-                expression: Expression {
-                    value: serde_json::json!("root"),
-                },
-                forward_ref: false,
-            },
-            use_class: None,
-            use_factory: None,
-            use_existing: None,
-            use_value: None,
-            deps: None,
-        };
+    fn test_compile_injectable_use_existing() {
+        let mut meta = metadata("Alias");
+        meta.use_existing = Some(maybe_forward_ref(var("Real")));
+        let code = emit(&meta);
+        assert!(code.contains("i0.ɵɵinject(Real)"), "{}", code);
+    }
 
-        let type_expr = create_injectable_type(&meta);
+    #[test]
+    fn test_compile_injectable_use_class_delegates_to_its_factory() {
+        let mut meta = metadata("Base");
+        meta.use_class = Some(maybe_forward_ref(var("Impl")));
+        let code = emit(&meta);
+        assert!(
+            code.contains("(__ngFactoryType__) => Impl.ɵfac(__ngFactoryType__)"),
+            "{}",
+            code
+        );
+    }
 
-        // Check that type contains InjectableDeclaration
-        let json = type_expr.value.to_string();
-        assert!(json.contains("InjectableDeclaration"));
-        assert!(json.contains("2")); // typeArguments
+    #[test]
+    fn test_compile_injectable_use_factory_with_deps() {
+        let mut meta = metadata("Api");
+        meta.provided_in = maybe_forward_ref(Expression::Literal(LiteralExpr {
+            value: LiteralValue::Null,
+            type_: None,
+            source_span: None,
+        }));
+        meta.use_factory = Some(var("createApi"));
+        meta.deps = Some(vec![R3DependencyMetadata {
+            token: Some(var("Http")),
+            attribute_name_type: None,
+            host: false,
+            optional: true,
+            self_: false,
+            skip_self: false,
+        }]);
+        let code = emit(&meta);
+        assert!(code.contains("createApi(i0.ɵɵinject(Http, 8))"), "{}", code);
+        assert!(!code.contains("providedIn"), "{}", code);
     }
 
     #[test]
-    fn test_expression_equivalence() {
-        let expr1 = Expression {
-            value: serde_json::json!("MyClass"),
-        };
-        let expr2 = Expression {
-            value: serde_json::json!("MyClass"),
-        };
-        let expr3 = Expression {
-            value: serde_json::json!("OtherClass"),
+    fn test_create_injectable_type() {
+        let mut meta = metadata("TestService");
+        meta.type_argument_count = 2;
+        let Type::Expression(type_expr) = create_injectable_type(&meta) else {
+            panic!("expected an expression type");
         };
-
-        assert!(is_expression_equivalent(&expr1, &expr2));
-        assert!(!is_expression_equivalent(&expr1, &expr3));
+        assert!(matches!(
+            &*type_expr.value,
+            Expression::External(ExternalExpr { value, .. })
+                if value.name.as_deref() == Some("ɵɵInjectableDeclaration")
+        ));
+        assert_eq!(type_expr.type_params.map(|params| params.len()), Some(1));
     }
 }
@@ -16,12 +16,13 @@ use crate::injectable_compiler_2::{
     compile_injectable, R3InjectableMetadata as InjectableMetadata,
 };
 use crate::output::output_ast::{
-    DeclareVarStmt, Expression, Statement, StmtModifier, WrappedNodeExpr,
+    DeclareVarStmt, Expression, LiteralExpr, LiteralValue, Statement, StmtModifier, WrappedNodeExpr,
 };
 use crate::output::output_jit::{ExternalReferenceResolver, JitEvaluator};
 use crate::parse_util::{ParseLocation, ParseSourceFile, ParseSourceSpan};
 use crate::render3::r3_factory::{
-    compile_factory_function, FactoryTarget, R3ConstructorFactoryMetadata, R3FactoryMetadata,
+    compile_factory_function, FactoryTarget, R3ConstructorFactoryMetadata, R3DependencyMetadata,
+    R3FactoryMetadata,
 };
 use crate::render3::r3_injector_compiler::{compile_injector, R3InjectorMetadata};
 use crate::render3::r3_jit::{
@@ -34,7 +35,7 @@ use crate::render3::r3_module_compiler::{
 use crate::render3::r3_pipe_compiler::{compile_pipe_from_metadata, R3PipeMetadata};
 use crate::render3::util::{
     create_may_be_forward_ref_expression, get_safe_property_access_string, wrap_reference,
-    R3Reference,
+    ForwardRefHandling, MaybeForwardRefExpression, R3Reference,
 };
 use crate::render3::view::api::{
     ChangeDetectionOrExpression, R3ComponentMetadata, R3ComponentTemplate, R3DirectiveMetadata,
@@ -44,6 +45,7 @@ use crate::render3::view::compiler::{
     compile_component_from_metadata, compile_directive_from_metadata, parse_host_bindings,
     verify_host_bindings, ParsedHostBindings,
 };
+use crate::render3::view::config::get_template_source_locations_enabled;
 use crate::render3::view::template::{make_binding_parser, parse_template, ParseTemplateOptions};
 
 pub struct CompilerFacadeImpl {
@@ -361,22 +363,27 @@ impl CompilerFacade for CompilerFacadeImpl {
     ) -> Result<serde_json::Value, String> {
         let meta = InjectableMetadata {
             name: facade.name.clone(),
-            type_ref: create_injectable_reference(facade.type_ref),
-            type_argument_count: facade.type_argument_count,
+            type_: wrap_reference(facade.type_ref),
+            type_argument_count: facade.type_argument_count as usize,
             provided_in: compute_provided_in(facade.provided_in),
-            use_class: convert_to_maybe_forward_ref_expression(facade.use_class),
-            use_factory: convert_to_provider_expression(facade.use_factory),
-            use_existing: convert_to_maybe_forward_ref_expression(facade.use_existing),
-            use_value: convert_to_maybe_forward_ref_expression(facade.use_value),
-            deps: convert_r3_dependency_metadata_array(facade.deps),
+            use_class: convert_to_provider_expression(facade.use_class),
+            use_factory: facade.use_factory.map(new_wrapped_node_expr),
+            use_existing: convert_to_provider_expression(facade.use_existing),
+            use_value: convert_to_provider_expression(facade.use_value),
+            deps: facade.deps.map(|deps| {
+                deps.into_iter()
+                    .map(convert_r3_dependency_metadata)
+                    .collect()
+            }),
         };
 
-        if let Ok(res) = compile_injectable(meta, false) {
-            let expr = convert_injectable_expression_to_expression(res.expression);
-            self.jit_expression(expr, angular_core_env, source_map_url, vec![])
-        } else {
-            Err("Compile injectable failed".to_string())
-        }
+        let res = compile_injectable(&meta, true);
+        self.jit_expression(
+            res.expression,
+            angular_core_env,
+            source_map_url,
+            res.statements,
+        )
     }
 
     fn compile_injectable_declaration(
@@ -387,22 +394,27 @@ impl CompilerFacade for CompilerFacadeImpl {
     ) -> Result<serde_json::Value, String> {
         let meta = InjectableMetadata {
             name: facade.type_ref.clone(),
-            type_ref: create_injectable_reference(facade.type_ref.clone()),
+            type_: wrap_reference(facade.type_ref),
             type_argument_count: 0,
             provided_in: compute_provided_in(facade.provided_in),
-            use_class: convert_to_maybe_forward_ref_expression(facade.use_class),
-            use_factory: convert_to_provider_expression(facade.use_factory),
-            use_existing: convert_to_maybe_forward_ref_expression(facade.use_existing),
-            use_value: convert_to_maybe_forward_ref_expression(facade.use_value),
-            deps: convert_declare_dependency_metadata_array(facade.deps),
+            use_class: convert_to_provider_expression(facade.use_class),
+            use_factory: facade.use_factory.map(new_wrapped_node_expr),
+            use_existing: convert_to_provider_expression(facade.use_existing),
+            use_value: convert_to_provider_expression(facade.use_value),
+            deps: facade.deps.map(|deps| {
+                deps.into_iter()
+                    .map(convert_r3_declare_dependency_metadata)
+                    .collect()
+            }),
         };
 
-        if let Ok(res) = compile_injectable(meta, false) {
-            let expr = convert_injectable_expression_to_expression(res.expression);
-            self.jit_expression(expr, angular_core_env, source_map_url, vec![])
-        } else {
-            Err("Compile injectable declaration failed".to_string())
-        }
+        let res = compile_injectable(&meta, true);
+        self.jit_expression(
+            res.expression,
+            angular_core_env,
+            source_map_url,
+            res.statements,
+        )
     }
 
     fn create_parse_source_span(
@@ -821,6 +833,7 @@ fn convert_component_facade_to_metadata(
             .change_detection
             .map(|_cd| ChangeDetectionOrExpression::Strategy(ChangeDetectionStrategy::Default)),
         relative_template_path: None,
+        enable_template_source_locations: get_template_source_locations_enabled(),
         has_directive_dependencies: false,
         raw_imports: None,
     }
@@ -870,6 +883,7 @@ fn convert_declare_component_facade_to_metadata(
             .change_detection
             .map(|_cd| ChangeDetectionOrExpression::Strategy(ChangeDetectionStrategy::Default)),
         relative_template_path: None,
+        enable_template_source_locations: get_template_source_locations_enabled(),
         has_directive_dependencies: false,
         raw_imports: None,
     }
@@ -898,27 +912,6 @@ fn create_dummy_source_span() -> ParseSourceSpan {
     )
 }
 
-// Helper for Injectable conversion
-fn create_injectable_reference(type_ref: String) -> crate::injectable_compiler_2::R3Reference {
-    crate::injectable_compiler_2::R3Reference {
-        value: create_injectable_expr_json(type_ref.clone()),
-        type_ref: create_injectable_expr_json(type_ref),
-    }
-}
-
-fn create_injectable_expr_json(val: String) -> crate::injectable_compiler_2::Expression {
-    crate::injectable_compiler_2::Expression {
-        value: serde_json::Value::String(val),
-    }
-}
-
-#[allow(dead_code)]
-fn create_injectable_expr_from_value(
-    val: serde_json::Value,
-) -> crate::injectable_compiler_2::Expression {
-    crate::injectable_compiler_2::Expression { value: val }
-}
-
 fn convert_host_declaration_to_metadata(host: Option<HashMap<String, String>>) -> R3HostMetadata {
     let host = host.unwrap_or_default();
 
@@ -1029,92 +1022,83 @@ fn parse_legacy_input_partial_output_array(value: &[serde_json::Value]) -> R3Inp
     }
 }
 
-fn compute_provided_in(
-    provided_in: Option<crate::compiler_facade_interface::ProvidedIn>,
-) -> crate::injectable_compiler_2::MaybeForwardRefExpression {
-    let value = match provided_in {
-        Some(crate::compiler_facade_interface::ProvidedIn::Type(type_ref)) => {
-            serde_json::Value::String(type_ref)
-        }
-        Some(crate::compiler_facade_interface::ProvidedIn::Scope(scope)) => {
-            serde_json::Value::String(scope)
-        }
-        None => serde_json::Value::Null,
+fn compute_provided_in(provided_in: Option<facade::ProvidedIn>) -> MaybeForwardRefExpression {
+    let expression = match provided_in {
+        Some(facade::ProvidedIn::Type(type_ref)) => new_wrapped_node_expr(type_ref.into()),
+        Some(facade::ProvidedIn::Scope(scope)) => Expression::Literal(LiteralExpr {
+            value: LiteralValue::String(scope),
+            type_: None,
+            source_span: None,
+        }),
+        None => Expression::Literal(LiteralExpr {
+            value: LiteralValue::Null,
+            type_: None,
+            source_span: None,
+        }),
     };
-
-    crate::injectable_compiler_2::MaybeForwardRefExpression {
-        expression: crate::injectable_compiler_2::Expression { value },
-        forward_ref: false,
-    }
-}
-
-fn convert_to_maybe_forward_ref_expression(
-    val: Option<serde_json::Value>,
-) -> Option<crate::injectable_compiler_2::MaybeForwardRefExpression> {
-    val.map(
-        |v| crate::injectable_compiler_2::MaybeForwardRefExpression {
-            expression: crate::injectable_compiler_2::Expression { value: v },
-            forward_ref: false,
-        },
-    )
+    create_may_be_forward_ref_expression(expression, ForwardRefHandling::None)
 }
 
 fn convert_to_provider_expression(
-    val: Option<serde_json::Value>,
-) -> Option<crate::injectable_compiler_2::Expression> {
-    val.map(|v| crate::injectable_compiler_2::Expression { value: v })
-}
-
-fn convert_r3_dependency_metadata_array(
-    deps: Option<Vec<R3DependencyMetadataFacade>>,
-) -> Option<Vec<crate::injectable_compiler_2::R3DependencyMetadata>> {
-    deps.map(|d| d.into_iter().map(convert_r3_dependency_metadata).collect())
-}
-
-fn convert_declare_dependency_metadata_array(
-    deps: Option<Vec<R3DeclareDependencyMetadataFacade>>,
-) -> Option<Vec<crate::injectable_compiler_2::R3DependencyMetadata>> {
-    deps.map(|d| {
-        d.into_iter()
-            .map(convert_r3_declare_dependency_metadata)
-            .collect()
+    value: Option<serde_json::Value>,
+) -> Option<MaybeForwardRefExpression> {
+    // The JIT facade has already resolved any `forwardRef()` wrapper by the time it gets here.
+    value.map(|v| {
+        create_may_be_forward_ref_expression(new_wrapped_node_expr(v), ForwardRefHandling::None)
     })
 }
 
-fn convert_r3_dependency_metadata(
-    dep: R3DependencyMetadataFacade,
-) -> crate::injectable_compiler_2::R3DependencyMetadata {
-    crate::injectable_compiler_2::R3DependencyMetadata {
-        token: crate::injectable_compiler_2::Expression { value: dep.token },
-        attribute: dep.attribute,
-        host: dep.host,
-        optional: dep.optional,
-        self_dep: dep.self_dep,
-        skip_self: dep.skip_self,
-    }
+fn convert_r3_dependency_metadata(dep: R3DependencyMetadataFacade) -> R3DependencyMetadata {
+    create_r3_dependency_metadata(
+        dep.token,
+        dep.attribute.is_some(),
+        dep.host,
+        dep.optional,
+        dep.self_dep,
+        dep.skip_self,
+    )
 }
 
 fn convert_r3_declare_dependency_metadata(
     dep: R3DeclareDependencyMetadataFacade,
-) -> crate::injectable_compiler_2::R3DependencyMetadata {
-    crate::injectable_compiler_2::R3DependencyMetadata {
-        token: crate::injectable_compiler_2::Expression { value: dep.token },
-        attribute: dep
-            .attribute
-            .and_then(|t| if t { Some("unknown".to_string()) } else { None }), // TODO: Attribute logic in TS handles unknown?
-        host: dep.host.unwrap_or(false),
-        optional: dep.optional.unwrap_or(false),
-        self_dep: dep.self_dep.unwrap_or(false),
-        skip_self: dep.skip_self.unwrap_or(false),
-    }
+) -> R3DependencyMetadata {
+    create_r3_dependency_metadata(
+        dep.token,
+        dep.attribute.unwrap_or(false),
+        dep.host.unwrap_or(false),
+        dep.optional.unwrap_or(false),
+        dep.self_dep.unwrap_or(false),
+        dep.skip_self.unwrap_or(false),
+    )
 }
 
-// Helpers
-
-fn convert_injectable_expression_to_expression(
-    expr: crate::injectable_compiler_2::Expression,
-) -> Expression {
-    new_wrapped_node_expr(expr.value)
+fn create_r3_dependency_metadata(
+    token: serde_json::Value,
+    is_attribute_dep: bool,
+    host: bool,
+    optional: bool,
+    self_: bool,
+    skip_self: bool,
+) -> R3DependencyMetadata {
+    // If the dep is an `@Attribute()` the `attribute_name_type` ought to be the `unknown` type.
+    // But types are not available at runtime so we just use a literal `"unknown"` string.
+    let attribute_name_type = if is_attribute_dep {
+        Some(Expression::Literal(LiteralExpr {
+            value: LiteralValue::String("unknown".to_string()),
+            type_: None,
+            source_span: None,
+        }))
+    } else {
+        None
+    };
+    R3DependencyMetadata {
+        token: Some(new_wrapped_node_expr(token)),
+        attribute_name_type,
+        host,
+        optional,
+        self_,
+        skip_self,
+    }
 }
 
 fn convert_special_attributes(
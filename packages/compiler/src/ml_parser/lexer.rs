@@ -164,7 +164,7 @@ impl PlainCharacterCursor {
     }
 
     fn update_peek(&mut self) {
-        if self.state.offset < self.file.content.len() {
+        if self.state.offset < self.range.end_pos {
             // Use string slicing to get correct unicode char
             // Note: This relies on offset being at char boundary, which should be guaranteed by logic
             if let Some(c) = self.file.content[self.state.offset..].chars().next() {
@@ -297,7 +297,7 @@ impl EscapedCharacterCursor {
     }
 
     fn update_peek(&mut self) {
-        if self.internal_state.source_index < self.file.content.len() {
+        if self.internal_state.source_index < self.range.end_pos {
             let ch = self.file.content[self.internal_state.source_index..]
                 .chars()
                 .next()
@@ -621,9 +621,15 @@ impl Tokenizer {
                 } else if self.attempt_char_code('/') {
                     // </tag>
                     self.consume_tag_close(start);
-                } else {
+                } else if chars::is_ascii_letter(self.cursor.peek()) {
                     // <tag>
                     self.consume_tag_open(start);
+                } else {
+                    // When the start tag is invalid (e.g. `<?xml ...?>`), assume we want a "<"
+                    // as text
+                    self.current_token_type = Some(TokenType::Text);
+                    self.current_token_start = Some(start);
+                    self.end_token(vec!["<".to_string()]);
                 }
             } else if self.tokenize_let
                 && self.cursor.peek() == '@'
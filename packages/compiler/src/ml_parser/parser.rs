@@ -464,16 +464,17 @@ impl TreeBuilder {
             }
 
             // Expect }
-            if !matches!(self.peek, Some(Token::ExpansionFormEnd(_))) {
+            let Some(Token::ExpansionFormEnd(end_token)) = &self.peek else {
                 self.add_error(
                     "Invalid ICU message. Missing '}'.".to_string(),
                     exp_token.source_span.clone(),
                 );
                 return;
-            }
-
-            let _end_span = get_token_source_span(self.peek.as_ref().unwrap());
-            let source_span = exp_token.source_span.clone(); // TODO: Merge with _end_span
+            };
+            let source_span = ParseSourceSpan::new(
+                exp_token.source_span.start.clone(),
+                end_token.source_span.end.clone(),
+            );
 
             let expansion = Expansion {
                 switch_value: switch_value.into(),
@@ -1309,11 +1310,12 @@ impl TreeBuilder {
             let name = let_token.parts.get(0).cloned().unwrap_or_default();
 
             // Consume LET_VALUE
-            let value = if let Some(Token::LetValue(val)) = self.advance_if(TokenType::LetValue) {
-                val.parts.get(0).cloned().unwrap_or_default()
-            } else {
-                Arc::from("")
-            };
+            let (value, value_span) =
+                if let Some(Token::LetValue(val)) = self.advance_if(TokenType::LetValue) {
+                    (val.parts.get(0).cloned().unwrap_or_default(), Some(val.source_span))
+                } else {
+                    (Arc::from(""), None)
+                };
 
             // Consume LET_END
             let end_span = if let Some(Token::LetEnd(end)) = self.advance_if(TokenType::LetEnd) {
@@ -1327,7 +1329,7 @@ impl TreeBuilder {
                 value,
                 source_span: let_token.source_span.clone(),
                 name_span: let_token.source_span.clone(),
-                value_span: end_span,
+                value_span: value_span.unwrap_or(end_span),
             };
 
             self.add_to_parent(Node::LetDeclaration(decl));
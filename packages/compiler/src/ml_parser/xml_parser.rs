@@ -2,35 +2,49 @@
 //!
 //! Corresponds to packages/compiler/src/ml_parser/xml_parser.ts (27 lines)
 
-// NOTE: This depends on parser.rs which is not yet implemented
-// Will be completed after parser.rs is done
-
-/*
 use super::lexer::TokenizeOptions;
-use super::parser::{Parser, ParseTreeResult};
+use super::parser::{ParseTreeResult, Parser};
+use super::tags::TagDefinition;
 use super::xml_tags::get_xml_tag_definition;
 
 /// XML parser (extends generic Parser with XML tag definitions)
-pub struct XmlParser {
-    parser: Parser,
-}
+///
+/// TypeScript equivalent:
+/// ```typescript
+/// export class XmlParser extends Parser {
+///   constructor() {
+///     super(getXmlTagDefinition);
+///   }
+/// }
+/// ```
+pub struct XmlParser {}
 
 impl XmlParser {
+    /// Create new XML parser, treating all tags uniformly
     pub fn new() -> Self {
-        XmlParser {
-            parser: Parser::new(get_xml_tag_definition),
-        }
+        XmlParser {}
     }
 
-    pub fn parse(&self, source: &str, url: &str, options: Option<TokenizeOptions>) -> ParseTreeResult {
+    /// Parse XML source
+    pub fn parse(
+        &self,
+        source: &str,
+        url: &str,
+        options: Option<TokenizeOptions>,
+    ) -> ParseTreeResult {
+        fn tag_def(name: &str) -> &'static dyn TagDefinition {
+            get_xml_tag_definition(name)
+        }
+
         // Blocks and let declarations aren't supported in an XML context
-        let xml_options = TokenizeOptions {
+        let options = TokenizeOptions {
             tokenize_blocks: false,
             tokenize_let: false,
             selectorless_enabled: false,
             ..options.unwrap_or_default()
         };
-        self.parser.parse(source, url, Some(xml_options))
+        let parser = Parser::new(tag_def);
+        parser.parse(source, url, Some(options))
     }
 }
 
@@ -39,7 +53,3 @@ impl Default for XmlParser {
         Self::new()
     }
 }
-*/
-
-// Placeholder until parser.rs is implemented
-pub struct XmlParser;
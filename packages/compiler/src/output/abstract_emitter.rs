@@ -110,9 +110,11 @@ impl EmitterVisitorContext {
         if !part.is_empty() {
             let current = self.current_line_mut();
             current.content.push_str(part);
-            current
-                .src_spans
-                .push((part.len(), from.and_then(|f| f.source_span()).cloned()));
+            // Source map columns are measured in UTF-16 code units, not bytes.
+            current.src_spans.push((
+                part.encode_utf16().count(),
+                from.and_then(|f| f.source_span()).cloned(),
+            ));
         }
         if new_line {
             self.lines.push(EmittedLine::new(self.indent));
@@ -142,11 +142,17 @@ impl AbstractJsEmitterVisitor {
         let ref_expr = &expr.value;
         if let Some(module_name) = &ref_expr.module_name {
             self.used_imports.insert(module_name.clone());
-            if module_name == "@angular/core" {
-                ctx.print(Some(expr), "i0.", false);
-            } else if let Some(alias) = self.imports.get(module_name) {
+            let alias = if module_name == "@angular/core" {
+                Some("i0")
+            } else {
+                self.imports.get(module_name).map(String::as_str)
+            };
+            if let Some(alias) = alias {
                 ctx.print(Some(expr), alias, false);
-                ctx.print(Some(expr), ".", false);
+                // A reference without a name is to the module namespace itself.
+                if ref_expr.name.is_some() {
+                    ctx.print(Some(expr), ".", false);
+                }
             }
         }
         if let Some(name) = &ref_expr.name {
@@ -531,10 +537,18 @@ impl AbstractJsEmitterVisitor {
 
     fn emit_localized_string(
         &mut self,
-        _expr: &o::LocalizedString,
-        _ctx: &mut EmitterVisitorContext,
+        expr: &o::LocalizedString,
+        ctx: &mut EmitterVisitorContext,
     ) {
-        // TODO: Implement localized string
+        let head = expr.serialize_i18n_head();
+        ctx.print(Some(expr), &format!("$localize `{}", head.raw), false);
+        for i in 1..expr.message_parts.len() {
+            ctx.print(Some(expr), "${", false);
+            self.emit_expression(&expr.expressions[i - 1], ctx);
+            let part = expr.serialize_i18n_template_part(i);
+            ctx.print(Some(expr), &format!("}}{}", part.raw), false);
+        }
+        ctx.print(Some(expr), "`", false);
     }
 }
 
@@ -743,7 +757,9 @@ impl o::ExpressionVisitor for AbstractJsEmitterVisitor {
         expr: &o::LocalizedString,
         context: &mut dyn Any,
     ) -> Box<dyn Any> {
-        self.base.visit_localized_string(expr, context)
+        let ctx = context.downcast_mut::<EmitterVisitorContext>().unwrap();
+        self.emit_localized_string(expr, ctx);
+        Box::new(())
     }
 
     fn visit_external_expr(
@@ -757,11 +773,17 @@ impl o::ExpressionVisitor for AbstractJsEmitterVisitor {
         // Handle common Angular imports aliasing and configured imports
         if let Some(module_name) = &ref_expr.module_name {
             self.used_imports.insert(module_name.clone());
-            if module_name == "@angular/core" {
-                ctx.print(Some(expr), "i0.", false);
-            } else if let Some(alias) = self.imports.get(module_name) {
+            let alias = if module_name == "@angular/core" {
+                Some("i0")
+            } else {
+                self.imports.get(module_name).map(String::as_str)
+            };
+            if let Some(alias) = alias {
                 ctx.print(Some(expr), alias, false);
-                ctx.print(Some(expr), ".", false);
+                // A reference without a name is to the module namespace itself.
+                if ref_expr.name.is_some() {
+                    ctx.print(Some(expr), ".", false);
+                }
             }
             // If module_name is present but not in our imports map,
             // we assume the symbol is already available in scope via a local import
@@ -420,10 +420,99 @@ pub struct LiteralPiece {
     pub source_span: ParseSourceSpan,
 }
 
+impl LocalizedString {
+    /// Serialize the given `meta_block` and the first message part into a cooked and raw
+    /// string that can be used as the head of a `$localize` tagged string.
+    ///
+    /// The format of the metadata is the same as that parsed by `parseI18nMeta()`:
+    /// `:meaning|description@@custom-id:`.
+    pub fn serialize_i18n_head(&self) -> CookedRawString {
+        create_cooked_raw_string(
+            &self.meta_block,
+            self.message_parts
+                .first()
+                .map_or("", |part| part.text.as_str()),
+        )
+    }
+
+    /// Serialize the placeholder name and message part at `part_index` into a cooked and raw
+    /// string that can be used as a template part of a `$localize` tagged string.
+    pub fn serialize_i18n_template_part(&self, part_index: usize) -> CookedRawString {
+        let placeholder = &self.placeholder_names[part_index - 1];
+        let mut meta_block = placeholder.text.clone();
+        if let Some(id) = &placeholder.associated_message_id {
+            meta_block.push_str(I18N_ID_SEPARATOR);
+            meta_block.push_str(id);
+        }
+        create_cooked_raw_string(&meta_block, &self.message_parts[part_index].text)
+    }
+}
+
+/// Separator between the meaning and the description in a `$localize` meta block.
+pub const I18N_MEANING_SEPARATOR: &str = "|";
+/// Separator between the description and the custom id in a `$localize` meta block.
+pub const I18N_ID_SEPARATOR: &str = "@@";
+/// Prefix of each legacy message id in a `$localize` meta block.
+pub const I18N_LEGACY_ID_INDICATOR: &str = "\u{241F}";
+
+/// A template string part, both in its cooked form and in the raw form that is printed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookedRawString {
+    pub cooked: String,
+    pub raw: String,
+}
+
+/// Creates a `{cooked, raw}` pair from a meta block and a message part.
+///
+/// The `raw` text must have various character sequences escaped:
+/// * "\" would otherwise indicate that the next character is a control character.
+/// * "`" and "${" are template string control sequences that would otherwise prematurely
+///   indicate the end of the template literal element.
+/// * ":" at the start of a message part with no metadata would be read as a metadata block.
+fn create_cooked_raw_string(meta_block: &str, message_part: &str) -> CookedRawString {
+    if meta_block.is_empty() {
+        CookedRawString {
+            cooked: message_part.to_string(),
+            raw: escape_for_template_literal(&escape_starting_colon(&escape_slashes(message_part))),
+        }
+    } else {
+        CookedRawString {
+            cooked: format!(":{}:{}", meta_block, message_part),
+            raw: escape_for_template_literal(&format!(
+                ":{}:{}",
+                escape_colons(meta_block),
+                escape_slashes(message_part)
+            )),
+        }
+    }
+}
+
+fn escape_starting_colon(s: &str) -> String {
+    match s.strip_prefix(':') {
+        Some(rest) => format!("\\:{}", rest),
+        None => s.to_string(),
+    }
+}
+
+fn escape_colons(s: &str) -> String {
+    s.replace(':', "\\:")
+}
+
+fn escape_slashes(s: &str) -> String {
+    s.replace('\\', "\\\\")
+}
+
+fn escape_for_template_literal(s: &str) -> String {
+    s.replace('`', "\\`").replace("${", "$\\{")
+}
+
 #[derive(Debug, Clone)]
 pub struct PlaceholderPiece {
     pub text: String,
     pub source_span: ParseSourceSpan,
+    /// Id of the message this placeholder refers to (e.g. an ICU sub-message), appended to the
+    /// placeholder's meta block when the message has no legacy ids.
+    pub associated_message_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -1582,6 +1671,12 @@ impl HasSourceSpan for DynamicImportExpr {
     }
 }
 
+impl HasSourceSpan for LocalizedString {
+    fn source_span(&self) -> Option<&ParseSourceSpan> {
+        self.source_span.as_ref()
+    }
+}
+
 impl HasSourceSpan for TemplateLiteralExpr {
     fn source_span(&self) -> Option<&ParseSourceSpan> {
         // TemplateLiteralExpr doesn't have source_span, return None
@@ -206,7 +206,10 @@ pub fn create_component_definition_map(
             let mut resolvers: Vec<Expression> = vec![];
             let mut has_resolvers = false;
 
-            for deps in blocks.values() {
+            // Blocks are keyed by source offset, so sorting restores template order.
+            let mut blocks: Vec<_> = blocks.iter().collect();
+            blocks.sort_by_key(|(offset, _)| **offset);
+            for (_, deps) in blocks {
                 if let Some(d) = deps {
                     resolvers.push(d.clone());
                     has_resolvers = true;
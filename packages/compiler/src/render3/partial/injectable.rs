@@ -3,6 +3,7 @@
 //! Corresponds to packages/compiler/src/render3/partial/injectable.ts
 //! Contains injectable declaration compilation for partial/linking mode
 
+use crate::injectable_compiler_2::create_injectable_type;
 use crate::output::output_ast::{
     Expression, ExternalExpr, InvokeFunctionExpr, LiteralArrayExpr, LiteralExpr, LiteralValue,
 };
@@ -12,6 +13,8 @@ use crate::render3::view::util::DefinitionMap;
 
 use super::util::compile_dependency;
 
+pub use crate::injectable_compiler_2::R3InjectableMetadata;
+
 /// Minimum version for partial linker
 const MINIMUM_PARTIAL_LINKER_VERSION: &str = "12.0.0";
 
@@ -33,19 +36,6 @@ fn external_expr(reference: crate::output::output_ast::ExternalReference) -> Exp
     })
 }
 
-/// Metadata for injectable compilation
-#[derive(Debug, Clone)]
-pub struct R3InjectableMetadata {
-    pub name: String,
-    pub type_: crate::render3::util::R3Reference,
-    pub provided_in: Option<crate::render3::util::MaybeForwardRefExpression>,
-    pub use_class: Option<crate::render3::util::MaybeForwardRefExpression>,
-    pub use_factory: Option<Expression>,
-    pub use_existing: Option<crate::render3::util::MaybeForwardRefExpression>,
-    pub use_value: Option<crate::render3::util::MaybeForwardRefExpression>,
-    pub deps: Option<Vec<crate::render3::r3_factory::R3DependencyMetadata>>,
-}
-
 /// Compile an Injectable declaration defined by the `R3InjectableMetadata`.
 pub fn compile_declare_injectable_from_metadata(
     meta: &R3InjectableMetadata,
@@ -63,8 +53,7 @@ pub fn compile_declare_injectable_from_metadata(
         pure: false,
     });
 
-    // TODO: implement create_injectable_type
-    let type_ = crate::output::output_ast::dynamic_type();
+    let type_ = create_injectable_type(meta);
 
     R3CompiledExpression::new(expression, type_, vec![])
 }
@@ -94,13 +83,12 @@ pub fn create_injectable_definition_map(meta: &R3InjectableMetadata) -> Definiti
     definition_map.set("type", Some(meta.type_.value.clone()));
 
     // Only generate providedIn property if it has a non-null value
-    if let Some(ref provided_in) = meta.provided_in {
-        let converted = convert_from_maybe_forward_ref_expression(provided_in);
-        // Check if it's not null literal
-        if !matches!(&converted, Expression::Literal(lit) if matches!(lit.value, LiteralValue::Null))
-        {
-            definition_map.set("providedIn", Some(converted));
-        }
+    if !matches!(&meta.provided_in.expression, Expression::Literal(lit) if matches!(lit.value, LiteralValue::Null))
+    {
+        definition_map.set(
+            "providedIn",
+            Some(convert_from_maybe_forward_ref_expression(&meta.provided_in)),
+        );
     }
 
     if let Some(ref use_class) = meta.use_class {
@@ -10,7 +10,7 @@ use crate::expression_parser::ast::{
 };
 use crate::i18n::i18n_ast::I18nMeta;
 use crate::parse_util::ParseSourceSpan;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::sync::Arc;
 
 /// Base trait for all R3 AST nodes
@@ -851,8 +851,8 @@ impl Node for Reference {
 /// ICU node
 #[derive(Debug, Clone)]
 pub struct Icu {
-    pub vars: HashMap<Arc<str>, BoundText>,
-    pub placeholders: HashMap<Arc<str>, IcuPlaceholder>,
+    pub vars: IndexMap<Arc<str>, BoundText>,
+    pub placeholders: IndexMap<Arc<str>, IcuPlaceholder>,
     pub source_span: ParseSourceSpan,
     pub i18n: Option<I18nMeta>,
 }
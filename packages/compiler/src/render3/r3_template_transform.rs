@@ -3,9 +3,10 @@
 //! Corresponds to packages/compiler/src/render3/r3_template_transform.ts
 //! Contains HTML AST to Ivy AST transformation
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -27,6 +28,7 @@ use super::r3_control_flow::{
     preprocess_if_block,
 };
 use super::r3_deferred_blocks::{create_deferred_block, is_connected_defer_loop_block};
+use super::view::i18n::util::I18N_ICU_VAR_PREFIX;
 
 lazy_static! {
     /// Regex to match binding prefixes
@@ -488,56 +490,59 @@ impl<'a, 'b> HtmlAstToIvyAst<'a, 'b> {
     }
 
     fn visit_expansion(&mut self, expansion: &html::Expansion) -> Option<t::R3Node> {
-        let mut vars = HashMap::new();
-        let mut placeholders = HashMap::new();
-
-        // 1. Process switch value (as a variable)
-        let switch_value = &expansion.switch_value;
-        let switch_span = &expansion.switch_value_source_span;
-
-        let switch_expr = self.binding_parser.parse_binding(
-            switch_value,
-            false,
-            switch_span.clone(),
-            switch_span.start.offset,
-        );
-
-        let bound_text = t::BoundText::new(
-            (*switch_expr.ast).clone(),
-            switch_span.clone(),
-            None, // switch value itself doesn't have i18n meta usually
-        );
-        vars.insert(switch_value.clone(), bound_text);
-
-        // 2. Process cases recursively
-        for case in &expansion.cases {
-            for node in &case.expression {
-                let r3_node = match node {
-                    html::Node::Element(e) => self.visit_element(e),
-                    html::Node::Text(txt) => self.visit_text(txt),
-                    // html::Node::BoundText does not exist in ml_parser
-                    html::Node::Expansion(e) => self.visit_expansion(e),
-                    _ => None,
-                };
+        // do not generate Icu in case it was created
+        // outside of i18n block in a template
+        let message = match &expansion.i18n {
+            None => return None,
+            Some(i18n::I18nMeta::Message(message)) => message,
+            Some(_) => {
+                self.report_error(
+                    &format!(
+                        "Invalid type for \"i18n\" property of {}. Expected a \"Message\"",
+                        expansion.source_span.to_string()
+                    ),
+                    &expansion.source_span,
+                );
+                return None;
+            }
+        };
 
-                if let Some(node) = r3_node {
-                    match node {
-                        t::R3Node::BoundText(bt) => {
-                            let key: Arc<str> = bt.source_span.to_string().into();
-                            placeholders.insert(key, t::IcuPlaceholder::BoundText(*bt));
-                        }
-                        t::R3Node::Text(txt) => {
-                            let key: Arc<str> = txt.source_span.to_string().into();
-                            placeholders.insert(key, t::IcuPlaceholder::Text(*txt));
-                        }
-                        t::R3Node::Icu(nested_icu) => {
-                            // Merge variables and placeholders from nested ICU
-                            vars.extend(nested_icu.vars);
-                            placeholders.extend(nested_icu.placeholders);
-                        }
-                        _ => {}
+        let mut vars = IndexMap::new();
+        let mut placeholders = IndexMap::new();
+
+        // extract VARs from ICUs - we process them separately while
+        // assembling resulting message via goog.getMsg function, since
+        // we need to pass them to top-level goog.getMsg call
+        for (key, value) in &message.placeholders {
+            if key.starts_with(I18N_ICU_VAR_PREFIX) {
+                // Currently when the `plural` or `select` keywords in an ICU contain trailing spaces
+                // (e.g. `{count, select , ...}`), these spaces are also included into the key names in
+                // ICU vars (e.g. "VAR_SELECT "). These trailing spaces are not desirable, since they will
+                // later be converted into `_` symbols while normalizing placeholder names, which might
+                // lead to mismatches at runtime (i.e. placeholder will not be replaced with the correct
+                // value).
+                let formatted_key: Arc<str> = key.trim().into();
+                let ast = self
+                    .binding_parser
+                    .parse_interpolation_expression(&value.text, &value.source_span);
+                vars.insert(
+                    formatted_key,
+                    t::BoundText::new((*ast.ast).clone(), value.source_span.clone(), None),
+                );
+            } else {
+                let placeholder = match self.visit_text_with_interpolation(
+                    &value.text,
+                    &value.source_span,
+                    &None,
+                    None,
+                ) {
+                    Some(t::R3Node::BoundText(bound_text)) => {
+                        t::IcuPlaceholder::BoundText(*bound_text)
                     }
-                }
+                    Some(t::R3Node::Text(text)) => t::IcuPlaceholder::Text(*text),
+                    _ => continue,
+                };
+                placeholders.insert(key.as_str().into(), placeholder);
             }
         }
 
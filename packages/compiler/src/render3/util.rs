@@ -8,8 +8,8 @@ use crate::output::abstract_emitter::escape_identifier;
 use crate::output::output_ast::{
     ArrowFunctionBody, ArrowFunctionExpr, BinaryOperator, BinaryOperatorExpr, BuiltinType,
     BuiltinTypeName, Expression, ExpressionType, ExternalExpr, ExternalReference,
-    InvokeFunctionExpr, LiteralArrayExpr, LiteralExpr, LiteralValue, Statement, Type, TypeModifier,
-    TypeofExpr, WrappedNodeExpr,
+    InvokeFunctionExpr, LiteralArrayExpr, LiteralExpr, LiteralValue, ParenthesizedExpr, Statement,
+    Type, TypeModifier, TypeofExpr, WrappedNodeExpr,
 };
 
 /// Creates an expression type with the given number of type parameters
@@ -140,6 +140,13 @@ pub fn guarded_expression(guard: &str, expr: Expression) -> Expression {
         source_span: None,
     });
 
+    // `&&` binds tighter than `||`, so the guard needs explicit parentheses.
+    let guard_undefined_or_true = Expression::Parens(ParenthesizedExpr {
+        expr: Box::new(guard_undefined_or_true),
+        type_: None,
+        source_span: None,
+    });
+
     Expression::BinaryOp(BinaryOperatorExpr {
         operator: BinaryOperator::And,
         lhs: Box::new(guard_undefined_or_true),
@@ -128,6 +128,8 @@ pub struct R3ComponentMetadata {
     pub change_detection: Option<ChangeDetectionOrExpression>,
     /// Relative path to the component's template.
     pub relative_template_path: Option<String>,
+    /// Whether to produce instructions that attach the source location to each DOM node.
+    pub enable_template_source_locations: bool,
     /// Whether any of the component's dependencies are directives.
     pub has_directive_dependencies: bool,
     /// The imports expression for standalone components.
@@ -156,6 +158,7 @@ pub struct R3ComponentTemplate {
 #[derive(Debug, Clone)]
 pub enum R3ComponentDeferMetadata {
     PerBlock {
+        /// Dependency functions keyed by the start offset of their `@defer` block's source span.
         blocks: HashMap<usize, Option<Expression>>,
     },
     PerComponent {
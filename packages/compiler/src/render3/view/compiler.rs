@@ -24,8 +24,8 @@ use crate::template::pipeline::src::phases::run_host;
 use crate::template_parser::binding_parser::{BindingParser, ParsedEvent, ParsedProperty};
 
 use super::api::{
-    DeclarationListEmitMode, R3ComponentMetadata, R3DeferResolverFunctionMetadata,
-    R3DirectiveMetadata, R3TemplateDependencyMetadata,
+    DeclarationListEmitMode, R3ComponentDeferMetadata, R3ComponentMetadata,
+    R3DeferResolverFunctionMetadata, R3DirectiveMetadata, R3TemplateDependencyMetadata,
 };
 use super::query_generation::{create_content_queries_function, create_view_queries_function};
 use super::template::make_binding_parser;
@@ -92,6 +92,18 @@ pub fn compile_component_from_metadata(
         meta.directive.queries.len()
     );
     // eprintln!("DEBUG: compile_component_from_metadata called for {}, inputs len: {}", meta.directive.name, meta.directive.inputs.len());
+    // In per-component mode, all defer blocks share a single dependency function declared
+    // alongside the component.
+    let all_deferrable_deps_fn = match &meta.defer {
+        R3ComponentDeferMetadata::PerComponent {
+            dependencies_fn: Some(dependencies_fn),
+        } => Some((
+            format!("{}_DeferFn", meta.directive.name),
+            dependencies_fn.clone(),
+        )),
+        _ => None,
+    };
+
     // 1. Ingest
     let mut job = crate::template::pipeline::src::ingest::ingest_component(
         meta.directive.name.clone(),
@@ -101,9 +113,11 @@ pub fn compile_component_from_metadata(
         meta.relative_context_file_path.clone(),
         meta.i18n_use_external_ids,
         meta.defer.clone(),
-        None, // all_deferrable_deps_fn
+        all_deferrable_deps_fn
+            .as_ref()
+            .map(|(fn_name, _)| *crate::output::output_ast::variable(fn_name.clone())),
         meta.relative_template_path.clone(),
-        false, // enable_debug_locations
+        meta.enable_template_source_locations,
         meta.change_detection.as_ref().and_then(|cd| match cd {
             super::api::ChangeDetectionOrExpression::Strategy(s) => Some(*s),
             _ => None,
@@ -111,6 +125,19 @@ pub fn compile_component_from_metadata(
         meta.declarations.clone(),
     );
 
+    // The job's pool is emitted with the template, so the shared function is declared there.
+    if let Some((fn_name, dependencies_fn)) = all_deferrable_deps_fn {
+        job.pool.statements.push(Statement::DeclareVar(
+            crate::output::output_ast::DeclareVarStmt {
+                name: fn_name,
+                value: Some(Box::new(dependencies_fn)),
+                type_: None,
+                modifiers: crate::output::output_ast::StmtModifier::Final,
+                source_span: None,
+            },
+        ));
+    }
+
     // 2. Run phases
     crate::template::pipeline::src::phases::run(&mut job);
     // NOTE: We do NOT copy job.pool back to constant_pool here because
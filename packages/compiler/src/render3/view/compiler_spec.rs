@@ -114,6 +114,7 @@ fn should_emit_inherit_definition_feature_and_host_attrs() {
             ChangeDetectionStrategy::OnPush,
         )),
         relative_template_path: None,
+        enable_template_source_locations: false,
         has_directive_dependencies: false,
         raw_imports: None,
     };
@@ -477,6 +478,7 @@ fn should_compile_host_bindings_for_mat_divider() {
         relative_context_file_path: "test.ts".to_string(),
         i18n_use_external_ids: false,
         relative_template_path: None,
+        enable_template_source_locations: false,
         has_directive_dependencies: false,
         raw_imports: None,
     };
@@ -540,3 +542,94 @@ fn should_compile_host_bindings_for_mat_divider() {
         panic!("Expected InvokeFn");
     }
 }
+
+#[test]
+fn should_attach_source_locations_when_enabled() {
+    let parsed = crate::render3::view::template::parse_template(
+        "<div>\n  <span></span>\n</div>",
+        "app.html",
+        crate::render3::view::template::ParseTemplateOptions::default(),
+    );
+
+    let mut component_metadata = R3ComponentMetadata {
+        directive: R3DirectiveMetadata {
+            name: "AppComponent".to_string(),
+            type_: create_mock_reference("AppComponent"),
+            type_argument_count: 0,
+            type_source_span: create_dummy_span(),
+            deps: None,
+            selector: Some("app-root".to_string()),
+            queries: vec![],
+            view_queries: vec![],
+            host: R3HostMetadata::default(),
+            lifecycle: R3LifecycleMetadata::default(),
+            inputs: IndexMap::new(),
+            outputs: IndexMap::new(),
+            uses_inheritance: false,
+            export_as: None,
+            providers: None,
+            is_standalone: true,
+            is_signal: false,
+            host_directives: None,
+        },
+        template: R3ComponentTemplate {
+            nodes: parsed.nodes,
+            ng_content_selectors: vec![],
+            preserve_whitespaces: false,
+        },
+        declarations: vec![],
+        defer: R3ComponentDeferMetadata::PerComponent {
+            dependencies_fn: None,
+        },
+        declaration_list_emit_mode: DeclarationListEmitMode::Closure,
+        styles: vec![],
+        external_styles: None,
+        encapsulation: ViewEncapsulation::None,
+        animations: None,
+        view_providers: None,
+        relative_context_file_path: "app.ts".to_string(),
+        i18n_use_external_ids: false,
+        change_detection: None,
+        relative_template_path: Some("app.html".to_string()),
+        enable_template_source_locations: false,
+        has_directive_dependencies: false,
+        raw_imports: None,
+    };
+
+    let compile = |component_metadata: &R3ComponentMetadata| {
+        let expression_parser = Parser::new();
+        let schema_registry = DomElementSchemaRegistry::new();
+        let mut binding_parser = crate::template_parser::binding_parser::BindingParser::new(
+            &expression_parser,
+            &schema_registry,
+            vec![],
+        );
+        let result = compile_component_from_metadata(
+            component_metadata,
+            &mut ConstantPool::new(false),
+            &mut binding_parser,
+        );
+        let mut ctx = crate::output::abstract_emitter::EmitterVisitorContext::create_root();
+        crate::output::output_ast::ExpressionTrait::visit_expression(
+            &result.expression,
+            &mut crate::output::abstract_js_emitter::AbstractJsEmitterVisitor::new(),
+            &mut ctx,
+        );
+        ctx.to_source()
+    };
+
+    let disabled = compile(&component_metadata);
+    component_metadata.enable_template_source_locations = true;
+    let enabled = compile(&component_metadata);
+
+    assert!(
+        enabled.contains("ɵɵattachSourceLocations(\"app.html\""),
+        "{}",
+        enabled
+    );
+    assert!(
+        !disabled.contains("ɵɵattachSourceLocations"),
+        "{}",
+        disabled
+    );
+}
@@ -5,7 +5,10 @@
 
 use std::sync::atomic::{AtomicBool, Ordering};
 
-/// Whether to produce instructions that will attach the source location to each DOM node.
+/// Whether to produce instructions that will attach the source location to each DOM node,
+/// for components compiled at runtime through the JIT compiler facade. Ahead-of-time
+/// compilations pass the option through `R3ComponentMetadata` instead, so that concurrent
+/// compilations cannot observe each other's setting.
 static ENABLE_TEMPLATE_SOURCE_LOCATIONS: AtomicBool = AtomicBool::new(false);
 
 /// Utility function to enable source locations. Intended to be used **only** inside unit tests.
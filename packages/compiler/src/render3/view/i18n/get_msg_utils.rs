@@ -8,12 +8,13 @@ use std::collections::HashMap;
 use crate::i18n::i18n_ast as i18n;
 use crate::output::output_ast::{
     DeclareVarStmt, Expression, ExpressionStatement, InvokeFunctionExpr, LiteralExpr,
-    LiteralMapEntry, LiteralMapExpr, LiteralValue, ReadVarExpr, Statement, StmtModifier,
+    LiteralMapEntry, LiteralMapExpr, LiteralValue, ReadPropExpr, ReadVarExpr, Statement,
+    StmtModifier,
 };
 
 use super::icu_serializer::serialize_icu_node;
 use super::meta::i18n_meta_to_jsdoc;
-use super::util::{format_i18n_placeholder_name, format_i18n_placeholder_names_in_map};
+use super::util::format_i18n_placeholder_name;
 
 /// Closure uses `goog.getMsg(message)` to lookup translations
 const GOOG_GET_MSG: &str = "goog.getMsg";
@@ -53,13 +54,16 @@ pub fn create_google_get_msg_statements(
     let mut args: Vec<Expression> = vec![literal_string(message_string)];
 
     if !placeholder_values.is_empty() {
+        // Params are emitted in placeholder name order so that the output is deterministic.
+        let mut sorted_params: Vec<&String> = placeholder_values.keys().collect();
+        sorted_params.sort();
+
         // Message template parameters containing the magic strings
-        let formatted_params = format_i18n_placeholder_names_in_map(placeholder_values, true);
-        let params_entries: Vec<LiteralMapEntry> = formatted_params
-            .into_iter()
-            .map(|(key, value)| LiteralMapEntry {
-                key,
-                value: Box::new(value),
+        let params_entries: Vec<LiteralMapEntry> = sorted_params
+            .iter()
+            .map(|param| LiteralMapEntry {
+                key: format_i18n_placeholder_name(param, true),
+                value: Box::new(placeholder_values[*param].clone()),
                 quoted: true,
             })
             .collect();
@@ -70,13 +74,13 @@ pub fn create_google_get_msg_statements(
         }));
 
         // Message options object with original source code for placeholders
-        let original_code_entries: Vec<LiteralMapEntry> = placeholder_values
-            .keys()
+        let original_code_entries: Vec<LiteralMapEntry> = sorted_params
+            .iter()
             .map(|param| {
                 let formatted_name = format_i18n_placeholder_name(param, true);
-                let value = if let Some(placeholder) = message.placeholders.get(param) {
+                let value = if let Some(placeholder) = message.placeholders.get(*param) {
                     literal_string(placeholder.source_span.to_string())
-                } else if let Some(msg) = message.placeholder_to_message.get(param) {
+                } else if let Some(msg) = message.placeholder_to_message.get(*param) {
                     let source: String = msg
                         .nodes
                         .iter()
@@ -114,8 +118,15 @@ pub fn create_google_get_msg_statements(
 
     // const MSG_... = goog.getMsg(..);
     // In TypeScript: o.variable(GOOG_GET_MSG).callFn(args)
-    let goog_get_msg_var = Expression::ReadVar(ReadVarExpr {
-        name: GOOG_GET_MSG.to_string(),
+    // The name is split into a property read so that the emitter does not quote it.
+    let (goog_namespace, get_msg_name) = GOOG_GET_MSG.split_once('.').unwrap();
+    let goog_get_msg_var = Expression::ReadProp(ReadPropExpr {
+        receiver: Box::new(Expression::ReadVar(ReadVarExpr {
+            name: goog_namespace.to_string(),
+            type_: None,
+            source_span: None,
+        })),
+        name: get_msg_name.to_string(),
         type_: None,
         source_span: None,
     });
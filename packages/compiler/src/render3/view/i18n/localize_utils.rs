@@ -5,8 +5,9 @@
 
 use std::collections::HashMap;
 
+use crate::i18n::digest::compute_msg_id;
 use crate::i18n::i18n_ast as i18n;
-use crate::output::output_ast::{Expression, ReadVarExpr, Statement};
+use crate::output::output_ast::{self as o, Expression, ReadVarExpr, Statement};
 use crate::parse_util::{ParseLocation, ParseSourceSpan};
 
 use super::icu_serializer::serialize_icu_node;
@@ -84,37 +85,76 @@ impl LocalizedString {
 
 impl From<LocalizedString> for Expression {
     fn from(val: LocalizedString) -> Self {
-        // For now, convert to a literal string as placeholder
-        // TODO: Implement proper TaggedTemplateExpr
-        Expression::Literal(crate::output::output_ast::LiteralExpr {
-            value: crate::output::output_ast::LiteralValue::String(
-                val.message_parts
-                    .iter()
-                    .map(|p| p.text.clone())
-                    .collect::<Vec<_>>()
-                    .join(""),
-            ),
-            type_: None,
-            source_span: None,
+        let meta_block = serialize_i18n_meta_block(&val.message);
+        Expression::Localized(o::LocalizedString {
+            meta_block,
+            message_parts: val
+                .message_parts
+                .into_iter()
+                .map(|part| o::LiteralPiece {
+                    text: part.text,
+                    source_span: part.source_span,
+                })
+                .collect(),
+            placeholder_names: val
+                .placeholders
+                .into_iter()
+                .map(|ph| o::PlaceholderPiece {
+                    associated_message_id: ph
+                        .associated_message
+                        .filter(|msg| msg.legacy_ids.is_empty())
+                        .map(|msg| compute_msg_id(&msg.message_string, &msg.meaning)),
+                    text: ph.text,
+                    source_span: ph.source_span,
+                })
+                .collect(),
+            expressions: val.expressions,
+            source_span: Some(val.source_span),
         })
     }
 }
 
+/// Serialize the metadata of a message into the block that prefixes a `$localize` string,
+/// i.e. `meaning|description@@custom-id␟legacy-id`.
+fn serialize_i18n_meta_block(message: &i18n::Message) -> String {
+    let mut meta_block = message.description.clone();
+    if !message.meaning.is_empty() {
+        meta_block = format!(
+            "{}{}{}",
+            message.meaning,
+            o::I18N_MEANING_SEPARATOR,
+            meta_block
+        );
+    }
+    if !message.custom_id.is_empty() {
+        meta_block = format!(
+            "{}{}{}",
+            meta_block,
+            o::I18N_ID_SEPARATOR,
+            message.custom_id
+        );
+    }
+    for legacy_id in &message.legacy_ids {
+        meta_block = format!("{}{}{}", meta_block, o::I18N_LEGACY_ID_INDICATOR, legacy_id);
+    }
+    meta_block
+}
+
 /// Create $localize statements
 pub fn create_localize_statements(
     variable: &ReadVarExpr,
     message: &i18n::Message,
     params: &HashMap<String, Expression>,
 ) -> Vec<Statement> {
-    let (_message_parts, placeholders) = serialize_i18n_message_for_localize(message);
-    let _source_span = get_source_span(message);
+    let (message_parts, placeholders) = serialize_i18n_message_for_localize(message);
+    let source_span = get_source_span(message);
 
-    let _expressions: Vec<Expression> = placeholders
+    let expressions: Vec<Expression> = placeholders
         .iter()
         .map(|ph| {
             params.get(&ph.text).cloned().unwrap_or_else(|| {
-                Expression::Literal(crate::output::output_ast::LiteralExpr {
-                    value: crate::output::output_ast::LiteralValue::String(ph.text.clone()),
+                Expression::Literal(o::LiteralExpr {
+                    value: o::LiteralValue::String(ph.text.clone()),
                     type_: None,
                     source_span: None,
                 })
@@ -122,25 +162,23 @@ pub fn create_localize_statements(
         })
         .collect();
 
-    // TODO: Implement LocalizedString in output_ast
-    // For now, just create a placeholder statement using WriteVarExpr
-    let empty_string_expr = Expression::Literal(crate::output::output_ast::LiteralExpr {
-        value: crate::output::output_ast::LiteralValue::String(String::new()),
-        type_: None,
-        source_span: None,
-    });
-    let write_expr = Expression::WriteVar(crate::output::output_ast::WriteVarExpr {
+    let localized_string = LocalizedString::new(
+        message.clone(),
+        message_parts,
+        placeholders,
+        expressions,
+        source_span,
+    );
+    let variable_initialization = Expression::WriteVar(o::WriteVarExpr {
         name: variable.name.clone(),
-        value: Box::new(empty_string_expr),
+        value: Box::new(localized_string.into()),
         type_: None,
         source_span: None,
     });
-    vec![Statement::Expression(
-        crate::output::output_ast::ExpressionStatement {
-            expr: Box::new(write_expr),
-            source_span: None,
-        },
-    )]
+    vec![Statement::Expression(o::ExpressionStatement {
+        expr: Box::new(variable_initialization),
+        source_span: None,
+    })]
 }
 
 /// Localize serializer visitor
@@ -187,7 +225,7 @@ impl LocalizeSerializerVisitor {
         self.pieces
             .push(MessagePiece::Placeholder(self.create_placeholder_piece(
                 &ph.start_name,
-                &ph.source_span,
+                ph.start_source_span.as_ref().unwrap_or(&ph.source_span),
                 None,
             )));
 
@@ -198,7 +236,7 @@ impl LocalizeSerializerVisitor {
             self.pieces
                 .push(MessagePiece::Placeholder(self.create_placeholder_piece(
                     &ph.close_name,
-                    &ph.source_span,
+                    ph.end_source_span.as_ref().unwrap_or(&ph.source_span),
                     None,
                 )));
         }
@@ -217,7 +255,7 @@ impl LocalizeSerializerVisitor {
         self.pieces
             .push(MessagePiece::Placeholder(self.create_placeholder_piece(
                 &ph.start_name,
-                &ph.source_span,
+                ph.start_source_span.as_ref().unwrap_or(&ph.source_span),
                 None,
             )));
 
@@ -228,7 +266,7 @@ impl LocalizeSerializerVisitor {
         self.pieces
             .push(MessagePiece::Placeholder(self.create_placeholder_piece(
                 &ph.close_name,
-                &ph.source_span,
+                ph.end_source_span.as_ref().unwrap_or(&ph.source_span),
                 None,
             )));
     }
@@ -3,12 +3,17 @@
 //! Corresponds to packages/compiler/src/render3/view/i18n/meta.ts
 //! Contains i18n meta processing for templates
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 
 use crate::i18n::digest::{compute_decimal_digest, compute_digest, decimal_digest};
 use crate::i18n::i18n_ast as i18n;
+use crate::i18n::i18n_parser::{create_i18n_message_factory, VisitNodeFn};
 use crate::ml_parser::ast as html;
+use crate::ml_parser::defaults::DEFAULT_CONTAINER_BLOCKS;
+use crate::ml_parser::html_whitespaces::{visit_all_with_siblings_nodes, WhitespaceVisitor};
 use crate::ml_parser::parser::ParseTreeResult;
+use crate::schema::trusted_types_sinks::is_trusted_types_sink;
 /// JSDoc tag name
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JSDocTagName {
@@ -37,7 +42,7 @@ impl JSDocComment {
 }
 use crate::parse_util::ParseError;
 
-use super::util::{has_i18n_attrs, I18N_ATTR, I18N_ATTR_PREFIX};
+use super::util::{has_i18n_attrs, is_i18n_attribute, I18N_ATTR, I18N_ATTR_PREFIX};
 
 /// i18n metadata
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -61,10 +66,28 @@ pub struct I18nMetaVisitor {
     errors: Vec<ParseError>,
     keep_i18n_attrs: bool,
     enable_i18n_legacy_message_id_format: bool,
+    container_blocks: HashSet<String>,
     preserve_significant_whitespace: bool,
     retain_empty_tokens: bool,
 }
 
+/// Source of the metadata used to generate an i18n message: either the raw
+/// value of an `i18n`/`i18n-*` attribute or metadata from a previous pass.
+enum MetaSource<'a> {
+    Value(&'a str),
+    Message(&'a i18n::Message),
+    Meta(Option<&'a i18n::I18nMeta>),
+}
+
+/// Key identifying an html node by its kind and source span offsets.
+type NodeKey = (&'static str, usize, usize);
+
+thread_local! {
+    /// i18n nodes produced for html nodes while generating the current message
+    /// (see `set_i18n_refs`).
+    static I18N_REFS: RefCell<HashMap<NodeKey, i18n::Node>> = RefCell::new(HashMap::new());
+}
+
 impl I18nMetaVisitor {
     pub fn new(
         keep_i18n_attrs: bool,
@@ -77,141 +100,332 @@ impl I18nMetaVisitor {
             errors: vec![],
             keep_i18n_attrs,
             enable_i18n_legacy_message_id_format,
+            container_blocks: DEFAULT_CONTAINER_BLOCKS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
             preserve_significant_whitespace,
             retain_empty_tokens,
         }
     }
 
-    pub fn visit_all_with_errors(&mut self, nodes: Vec<html::Node>) -> ParseTreeResult {
-        let result: Vec<html::Node> = nodes
-            .into_iter()
-            .map(|node| self.visit_node(node))
-            .collect();
-        ParseTreeResult::new(result, self.errors.clone())
+    pub fn visit_all_with_errors(&mut self, mut nodes: Vec<html::Node>) -> ParseTreeResult {
+        for node in nodes.iter_mut() {
+            self.visit_node(node, None);
+        }
+        ParseTreeResult::new(nodes, self.errors.clone())
     }
 
-    fn visit_node(&mut self, node: html::Node) -> html::Node {
+    fn visit_node(&mut self, node: &mut html::Node, current_message: Option<&mut i18n::Message>) {
         match node {
-            html::Node::Element(el) => self.visit_element(el),
-            html::Node::Text(text) => html::Node::Text(self.visit_text(text)),
-            html::Node::Comment(comment) => html::Node::Comment(self.visit_comment(comment)),
-            html::Node::Expansion(expansion) => self.visit_expansion(expansion, None),
-            html::Node::ExpansionCase(case) => {
-                html::Node::ExpansionCase(self.visit_expansion_case(case))
-            }
-            html::Node::Block(block) => self.visit_block(block),
-            html::Node::BlockParameter(param) => {
-                html::Node::BlockParameter(self.visit_block_parameter(param))
-            }
-            html::Node::LetDeclaration(decl) => {
-                html::Node::LetDeclaration(self.visit_let_declaration(decl))
+            html::Node::Element(element) => self.visit_element_like(element),
+            html::Node::Expansion(expansion) => self.visit_expansion(expansion, current_message),
+            html::Node::Block(block) => self.visit_block(block, current_message),
+            html::Node::Component(component) => {
+                for child in component.children.iter_mut() {
+                    self.visit_node(child, None);
+                }
             }
-            _ => node,
+            _ => {}
         }
     }
 
-    fn visit_element(&mut self, mut element: html::Element) -> html::Node {
-        self.visit_element_like(&mut element);
-        html::Node::Element(element)
-    }
-
     fn visit_element_like(&mut self, node: &mut html::Element) {
+        let mut has_message = false;
         if has_i18n_attrs(node) {
             self.has_i18n_meta = true;
             let mut attrs: Vec<html::Attribute> = vec![];
             let mut attrs_meta: HashMap<String, String> = HashMap::new();
 
-            for attr in &node.attrs {
+            for attr in node.attrs.clone() {
                 if attr.name.as_ref() == I18N_ATTR {
                     // Root 'i18n' node attribute
-                    // TODO: Generate i18n message
-                } else if attr.name.starts_with(I18N_ATTR_PREFIX) {
+                    let previous = node.i18n.take();
+                    let meta = match &previous {
+                        Some(meta) => MetaSource::Meta(Some(meta)),
+                        None => MetaSource::Value(&attr.value),
+                    };
+                    let trimmed_nodes = if self.preserve_significant_whitespace {
+                        node.children.clone()
+                    } else {
+                        visit_all_with_siblings_nodes(
+                            &mut WhitespaceVisitor::new(false, None, true),
+                            &node.children,
+                        )
+                    };
+                    let message =
+                        self.generate_i18n_message(&trimmed_nodes, meta, Some(set_i18n_refs));
+                    apply_i18n_refs(&mut node.children);
+                    if !message.nodes.is_empty() {
+                        node.i18n = Some(i18n::I18nMeta::Message(message));
+                        has_message = true;
+                    }
+                } else if let Some(name) = attr.name.strip_prefix(I18N_ATTR_PREFIX) {
                     // 'i18n-*' attributes
-                    let name = attr.name[I18N_ATTR_PREFIX.len()..].to_string();
-                    attrs_meta.insert(name, attr.value.to_string());
+                    if is_trusted_types_sink(&node.name, name) {
+                        self.report_error(
+                            &html::Node::Attribute(attr.clone()),
+                            &format!(
+                                "Translating attribute '{}' is disallowed for security reasons.",
+                                name
+                            ),
+                        );
+                    } else {
+                        attrs_meta.insert(name.to_string(), attr.value.to_string());
+                    }
                 } else {
                     // non-i18n attributes
-                    attrs.push(attr.clone());
+                    attrs.push(attr);
+                }
+            }
+
+            // Set i18n meta for attributes
+            if !attrs_meta.is_empty() {
+                for attr in node
+                    .attrs
+                    .iter_mut()
+                    .chain(attrs.iter_mut())
+                    .filter(|attr| !is_i18n_attribute(&attr.name))
+                {
+                    let Some(meta) = attrs_meta.get(attr.name.as_ref()) else {
+                        continue;
+                    };
+                    if attr.value.is_empty() {
+                        continue;
+                    }
+                    let previous = attr.i18n.take();
+                    let source = match &previous {
+                        Some(previous) => MetaSource::Meta(Some(previous)),
+                        None => MetaSource::Value(meta),
+                    };
+                    let message = self.generate_i18n_message(
+                        &[html::Node::Attribute(attr.clone())],
+                        source,
+                        None,
+                    );
+                    attr.i18n = Some(i18n::I18nMeta::Message(message));
                 }
             }
 
             if !self.keep_i18n_attrs {
+                // update element's attributes,
+                // keeping only non-i18n related ones
                 node.attrs = attrs;
             }
         }
 
-        // Visit children
-        let children: Vec<html::Node> = node
-            .children
-            .drain(..)
-            .map(|child| self.visit_node(child))
-            .collect();
-        node.children = children;
-    }
-
-    fn visit_text(&self, text: html::Text) -> html::Text {
-        text
-    }
-
-    fn visit_comment(&self, comment: html::Comment) -> html::Comment {
-        comment
+        // Visit children, passing the element's message so nested ICUs can register
+        // their own messages with it
+        let html::Element { children, i18n, .. } = node;
+        let mut current_message = match i18n {
+            Some(i18n::I18nMeta::Message(message)) if has_message => Some(message),
+            _ => None,
+        };
+        for child in children.iter_mut() {
+            self.visit_node(child, current_message.as_deref_mut());
+        }
     }
 
     fn visit_expansion(
         &mut self,
-        expansion: html::Expansion,
-        _current_message: Option<&i18n::Message>,
-    ) -> html::Node {
+        expansion: &mut html::Expansion,
+        current_message: Option<&mut i18n::Message>,
+    ) {
         self.has_i18n_meta = true;
-        // TODO: Generate i18n message for expansion
-        html::Node::Expansion(expansion)
-    }
-
-    fn visit_expansion_case(&self, case: html::ExpansionCase) -> html::ExpansionCase {
-        case
-    }
-
-    fn visit_block(&mut self, mut block: html::Block) -> html::Node {
-        let children: Vec<html::Node> = block
-            .children
-            .drain(..)
-            .map(|child| self.visit_node(child))
-            .collect();
-        block.children = children;
-        html::Node::Block(block)
+        let meta = expansion.i18n.take();
+        let message = match &meta {
+            Some(i18n::I18nMeta::Node(i18n::Node::IcuPlaceholder(placeholder))) => {
+                // Set ICU placeholder name (e.g. "ICU_1"), generated while processing
+                // root element contents, so we can reference it when we output translation
+                let name = placeholder.name.clone();
+                let mut message = self.generate_i18n_message(
+                    &[html::Node::Expansion(expansion.clone())],
+                    MetaSource::Meta(meta.as_ref()),
+                    None,
+                );
+                if let Some(i18n::Node::Icu(icu)) = message.nodes.first_mut() {
+                    icu.name = Some(name.clone());
+                }
+                if let Some(current_message) = current_message {
+                    // Also update the placeholderToMessage map with this new message
+                    current_message
+                        .placeholder_to_message
+                        .insert(name, Box::new(message.clone()));
+                }
+                message
+            }
+            _ => {
+                // ICU is a top level message, try to use metadata from container element if
+                // provided via `context` argument. Note: context may not be available for
+                // standalone ICUs (without wrapping element), so fallback to ICU metadata in
+                // this case.
+                let source = match &current_message {
+                    Some(current_message) => MetaSource::Message(current_message),
+                    None => MetaSource::Meta(meta.as_ref()),
+                };
+                self.generate_i18n_message(
+                    &[html::Node::Expansion(expansion.clone())],
+                    source,
+                    None,
+                )
+            }
+        };
+        expansion.i18n = Some(i18n::I18nMeta::Message(message));
     }
 
-    fn visit_block_parameter(&self, param: html::BlockParameter) -> html::BlockParameter {
-        param
+    fn visit_block(
+        &mut self,
+        block: &mut html::Block,
+        mut current_message: Option<&mut i18n::Message>,
+    ) {
+        for child in block.children.iter_mut() {
+            self.visit_node(child, current_message.as_deref_mut());
+        }
     }
 
-    fn visit_let_declaration(&self, decl: html::LetDeclaration) -> html::LetDeclaration {
-        decl
+    /// Generate (or update) an i18n message for the given nodes using the provided metadata.
+    fn generate_i18n_message(
+        &self,
+        nodes: &[html::Node],
+        meta: MetaSource,
+        visit_node_fn: Option<VisitNodeFn>,
+    ) -> i18n::Message {
+        let parsed = self.parse_metadata(&meta);
+        let mut factory = create_i18n_message_factory(
+            self.container_blocks.clone(),
+            self.retain_empty_tokens,
+            self.preserve_significant_whitespace,
+        );
+        if visit_node_fn.is_some() {
+            I18N_REFS.with(|refs| refs.borrow_mut().clear());
+        }
+        let mut message = factory.create_message(
+            nodes,
+            parsed.meaning.as_deref(),
+            parsed.description.as_deref(),
+            parsed.custom_id.as_deref(),
+            visit_node_fn,
+        );
+        self.set_message_id(&mut message, &parsed);
+        self.set_legacy_ids(&mut message, &meta);
+        message
     }
 
-    fn _parse_metadata(&self, meta: &str) -> I18nMeta {
-        parse_i18n_meta(meta)
+    fn parse_metadata(&self, meta: &MetaSource) -> I18nMeta {
+        match *meta {
+            MetaSource::Value(value) => parse_i18n_meta(value),
+            MetaSource::Meta(Some(i18n::I18nMeta::Message(message)))
+            | MetaSource::Message(message) => {
+                let non_empty = |value: &str| Some(value.to_string()).filter(|v| !v.is_empty());
+                I18nMeta {
+                    id: non_empty(&message.id),
+                    custom_id: non_empty(&message.custom_id),
+                    legacy_ids: Some(message.legacy_ids.clone()),
+                    description: non_empty(&message.description),
+                    meaning: non_empty(&message.meaning),
+                }
+            }
+            MetaSource::Meta(_) => I18nMeta::default(),
+        }
     }
 
-    fn _set_message_id(&self, message: &mut i18n::Message, meta: &I18nMeta) {
+    /// Generate the message id if it has not been provided or computed previously.
+    fn set_message_id(&self, message: &mut i18n::Message, meta: &I18nMeta) {
         if message.id.is_empty() {
             message.id = meta.id.clone().unwrap_or_else(|| decimal_digest(message));
         }
     }
 
-    fn _set_legacy_ids(&self, message: &mut i18n::Message, _meta: &I18nMeta) {
+    /// Update the `message` with a `legacyId` if necessary.
+    fn set_legacy_ids(&self, message: &mut i18n::Message, meta: &MetaSource) {
         if self.enable_i18n_legacy_message_id_format {
             message.legacy_ids = vec![compute_digest(message), compute_decimal_digest(message)];
+        } else {
+            let previous_message = match *meta {
+                MetaSource::Value(_) => return,
+                MetaSource::Message(message) => Some(message),
+                MetaSource::Meta(Some(i18n::I18nMeta::Message(message))) => Some(message),
+                MetaSource::Meta(Some(i18n::I18nMeta::Node(i18n::Node::IcuPlaceholder(
+                    placeholder,
+                )))) => placeholder.previous_message.as_deref(),
+                MetaSource::Meta(_) => None,
+            };
+            message.legacy_ids = previous_message
+                .map(|previous| previous.legacy_ids.clone())
+                .unwrap_or_default();
         }
     }
 
-    fn _report_error(&mut self, node: &html::Node, msg: &str) {
+    fn report_error(&mut self, node: &html::Node, msg: &str) {
         let source_span = get_node_source_span(node);
         self.errors
             .push(ParseError::new(source_span, msg.to_string()));
     }
 }
 
+/// Records the i18n node generated for an html node, so that it can later be
+/// attached to the original html node (see `apply_i18n_refs`). Trimmed nodes
+/// keep the source spans of the nodes they were created from.
+fn set_i18n_refs(html_node: &html::Node, i18n_node: &i18n::Node) -> i18n::Node {
+    let mut i18n_node = i18n_node.clone();
+    if let Some(key) = node_key(html_node) {
+        if let (
+            i18n::Node::IcuPlaceholder(placeholder),
+            html::Node::Expansion(html::Expansion {
+                i18n: Some(i18n::I18nMeta::Message(previous)),
+                ..
+            }),
+        ) = (&mut i18n_node, html_node)
+        {
+            placeholder.previous_message = Some(Box::new(previous.clone()));
+        }
+        I18N_REFS.with(|refs| refs.borrow_mut().insert(key, i18n_node.clone()));
+    }
+    i18n_node
+}
+
+/// Attaches the i18n nodes recorded by `set_i18n_refs` to the html nodes they were created for.
+fn apply_i18n_refs(nodes: &mut [html::Node]) {
+    for node in nodes.iter_mut() {
+        if let Some(key) = node_key(node) {
+            if let Some(i18n_node) = I18N_REFS.with(|refs| refs.borrow_mut().remove(&key)) {
+                let meta = Some(i18n::I18nMeta::Node(i18n_node));
+                match node {
+                    html::Node::Element(element) => element.i18n = meta,
+                    html::Node::Text(text) => text.i18n = meta,
+                    html::Node::Expansion(expansion) => expansion.i18n = meta,
+                    html::Node::Block(block) => block.i18n = meta,
+                    html::Node::Component(component) => component.i18n = meta,
+                    _ => {}
+                }
+            }
+        }
+        match node {
+            html::Node::Element(element) => apply_i18n_refs(&mut element.children),
+            html::Node::Component(component) => apply_i18n_refs(&mut component.children),
+            html::Node::Block(block) => apply_i18n_refs(&mut block.children),
+            html::Node::Expansion(expansion) => {
+                for case in expansion.cases.iter_mut() {
+                    apply_i18n_refs(&mut case.expression);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn node_key(node: &html::Node) -> Option<NodeKey> {
+    let (kind, span) = match node {
+        html::Node::Element(n) => ("element", &n.source_span),
+        html::Node::Text(n) => ("text", &n.source_span),
+        html::Node::Expansion(n) => ("expansion", &n.source_span),
+        html::Node::Block(n) => ("block", &n.source_span),
+        html::Node::Component(n) => ("component", &n.source_span),
+        _ => return None,
+    };
+    Some((kind, span.start.offset, span.end.offset))
+}
+
 /// Helper to get source_span from an html::Node enum
 fn get_node_source_span(node: &html::Node) -> crate::parse_util::ParseSourceSpan {
     match node {
@@ -107,36 +107,10 @@ pub fn find_matching_directives_and_pipes(
     directive_selectors: &[String],
 ) -> MatchingDirectivesAndPipes {
     use super::template::parse_template;
-    use crate::directive_matching::CssSelector;
 
-    // Create a SelectorMatcher and add fake directives for each selector
-    let mut matcher = SelectorMatcher::<Vec<FakeDirectiveMeta>>::new();
-    for selector in directive_selectors {
-        // Create a fake directive for matching
-        let fake_directive = FakeDirectiveMeta {
-            selector_str: selector.clone(),
-        };
-
-        // Parse the selector and add to matcher
-        if let Ok(css_selectors) = CssSelector::parse(selector) {
-            for css_selector in css_selectors {
-                matcher.add_selectable(css_selector, vec![fake_directive.clone()]);
-            }
-        }
-    }
-
-    // Parse the template
+    // Parse the template and bind it
     let parsed_template = parse_template(template, "", Default::default());
-
-    // Create binder with matcher
-    let binder = R3TargetBinder::new(Some(DirectiveMatcher::Selector(matcher)));
-    let target = Target {
-        template: Some(parsed_template.nodes),
-        host: None,
-    };
-
-    // Bind the template
-    let bound = binder.bind(target);
+    let bound = bind_with_selectors(parsed_template.nodes, directive_selectors);
 
     // Extract directives and pipes
     let eager_directives = bound.get_eagerly_used_directives();
@@ -166,6 +140,57 @@ pub fn find_matching_directives_and_pipes(
     }
 }
 
+/// Directives, by selector, and pipes used within a list of template nodes.
+#[derive(Debug, Clone)]
+pub struct UsedDirectivesAndPipes {
+    pub directive_selectors: Vec<String>,
+    pub pipes: Vec<String>,
+}
+
+/// Find which of `directive_selectors` match within `nodes`, e.g. the children of a
+/// `@defer` block, and which pipes they use.
+pub fn find_used_directives_and_pipes(
+    nodes: &[t::R3Node],
+    directive_selectors: &[String],
+) -> UsedDirectivesAndPipes {
+    let bound = bind_with_selectors(nodes.to_vec(), directive_selectors);
+    UsedDirectivesAndPipes {
+        directive_selectors: bound
+            .get_used_directives()
+            .iter()
+            .filter_map(|d| d.selector().map(String::from))
+            .collect(),
+        pipes: bound.get_used_pipes(),
+    }
+}
+
+/// Bind `nodes` against fake directives with the given selectors.
+fn bind_with_selectors(
+    nodes: Vec<t::R3Node>,
+    directive_selectors: &[String],
+) -> Box<dyn BoundTarget<FakeDirectiveMeta>> {
+    use crate::directive_matching::CssSelector;
+
+    // Create a SelectorMatcher and add fake directives for each selector
+    let mut matcher = SelectorMatcher::<Vec<FakeDirectiveMeta>>::new();
+    for selector in directive_selectors {
+        let fake_directive = FakeDirectiveMeta {
+            selector_str: selector.clone(),
+        };
+        if let Ok(css_selectors) = CssSelector::parse(selector) {
+            for css_selector in css_selectors {
+                matcher.add_selectable(css_selector, vec![fake_directive.clone()]);
+            }
+        }
+    }
+
+    let binder = R3TargetBinder::new(Some(DirectiveMatcher::Selector(matcher)));
+    binder.bind(Target {
+        template: Some(nodes),
+        host: None,
+    })
+}
+
 /// Processes `Target`s with a given set of directives and performs binding.
 pub struct R3TargetBinder<DirectiveT: DirectiveMeta + Clone> {
     directive_matcher: Option<DirectiveMatcher<DirectiveT>>,
@@ -10,6 +10,7 @@ use crate::ml_parser::lexer::{LexerRange, TokenizeOptions};
 use crate::parse_util::ParseError;
 use crate::render3::r3_ast as t;
 use crate::render3::r3_template_transform::{html_ast_to_render3_ast, Render3ParseOptions};
+use crate::render3::view::i18n::meta::I18nMetaVisitor;
 use crate::schema::dom_element_schema_registry::DomElementSchemaRegistry;
 use crate::template_parser::binding_parser::BindingParser;
 
@@ -108,17 +109,38 @@ pub fn parse_template(
 
     let parse_result = html_parser.parse(template, template_url, Some(tokenize_options));
 
-    // Process i18n metadata (simplified for now)
-    let mut html_nodes = parse_result.root_nodes;
+    // Process i18n metadata
+    let preserve_whitespaces = options.preserve_whitespaces.unwrap_or(false);
+    let retain_empty_tokens = !options.preserve_significant_whitespace.unwrap_or(true);
+    let mut i18n_meta_visitor = I18nMetaVisitor::new(
+        !preserve_whitespaces,
+        options
+            .enable_i18n_legacy_message_id_format
+            .unwrap_or(false),
+        options.preserve_significant_whitespace.unwrap_or(true),
+        retain_empty_tokens,
+    );
+    let i18n_meta_result = i18n_meta_visitor.visit_all_with_errors(parse_result.root_nodes);
+    let mut html_nodes = i18n_meta_result.root_nodes;
 
     // Handle whitespace preservation
-    if options.preserve_whitespaces != Some(true) {
+    if !preserve_whitespaces {
         let mut visitor = WhitespaceVisitor::new(
             options.preserve_significant_whitespace.unwrap_or(true), // Default to true based on view_util
             None,
             false,
         );
         html_nodes = visit_all_with_siblings_nodes(&mut visitor, &html_nodes);
+
+        // run i18n meta visitor again in case whitespaces are removed (because that might affect
+        // generated i18n message content) and first pass indicated that i18n content is present in
+        // a template. During this pass i18n IDs generated at the first pass will be preserved, so
+        // we can mimic existing extraction process (ng extract-i18n)
+        if i18n_meta_visitor.has_i18n_meta {
+            html_nodes = I18nMetaVisitor::new(false, false, true, retain_empty_tokens)
+                .visit_all_with_errors(html_nodes)
+                .root_nodes;
+        }
     }
 
     // Create binding parser
@@ -134,6 +156,7 @@ pub fn parse_template(
     let r3_result = html_ast_to_render3_ast(&html_nodes, &mut binding_parser, &r3_options);
 
     let mut errors = parse_result.errors;
+    errors.extend(i18n_meta_result.errors);
     errors.extend(r3_result.errors);
 
     ParsedTemplate {
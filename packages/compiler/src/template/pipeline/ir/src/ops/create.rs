@@ -8,8 +8,8 @@ use crate::i18n::i18n_ast::TagPlaceholder;
 use crate::output::output_ast::Expression as OutputExpression;
 use crate::parse_util::ParseSourceSpan;
 use crate::template::pipeline::ir::enums::{
-    AnimationKind, BindingKind, DeferOpModifierKind, I18nContextKind, I18nParamValueFlags,
-    Namespace, OpKind, TemplateKind,
+    AnimationKind, BindingKind, DeferOpModifierKind, DeferTriggerKind, I18nContextKind,
+    I18nParamValueFlags, Namespace, OpKind, TemplateKind,
 };
 use crate::template::pipeline::ir::handle::{ConstIndex, SlotHandle, XrefId};
 use crate::template::pipeline::ir::operations::{CreateOp, Op, OpList as IrOpList, UpdateOp};
@@ -2102,25 +2102,39 @@ pub enum DeferTrigger {
         target_xref: Option<XrefId>,
         target_slot: Option<SlotHandle>,
         target_view: Option<XrefId>,
-        target_slot_view_steps: Option<usize>,
+        target_slot_view_steps: Option<isize>,
     },
     Interaction {
         target_name: Option<String>,
         target_xref: Option<XrefId>,
         target_slot: Option<SlotHandle>,
         target_view: Option<XrefId>,
-        target_slot_view_steps: Option<usize>,
+        target_slot_view_steps: Option<isize>,
     },
     Viewport {
         target_name: Option<String>,
         target_xref: Option<XrefId>,
         target_slot: Option<SlotHandle>,
         target_view: Option<XrefId>,
-        target_slot_view_steps: Option<usize>,
+        target_slot_view_steps: Option<isize>,
         options: Option<OutputExpression>,
     },
 }
 
+impl DeferTrigger {
+    pub fn kind(&self) -> DeferTriggerKind {
+        match self {
+            DeferTrigger::Idle => DeferTriggerKind::Idle,
+            DeferTrigger::Immediate => DeferTriggerKind::Immediate,
+            DeferTrigger::Never => DeferTriggerKind::Never,
+            DeferTrigger::Timer { .. } => DeferTriggerKind::Timer,
+            DeferTrigger::Hover { .. } => DeferTriggerKind::Hover,
+            DeferTrigger::Interaction { .. } => DeferTriggerKind::Interaction,
+            DeferTrigger::Viewport { .. } => DeferTriggerKind::Viewport,
+        }
+    }
+}
+
 /// ExtractedAttributeOp - Represents an attribute that has been extracted for inclusion in the consts array
 #[derive(Debug, Clone)]
 pub struct ExtractedAttributeOp {
@@ -6,7 +6,7 @@
 use crate::constant_pool::ConstantPool;
 use crate::core::ChangeDetectionStrategy;
 use crate::directive_matching::{CssSelector, SelectorMatcher};
-use crate::output::output_ast::Expression;
+use crate::output::output_ast::{Expression, Statement};
 use crate::parse_util::ParseError;
 use crate::render3::view::api::{R3ComponentDeferMetadata, R3TemplateDependencyMetadata};
 use crate::schema::dom_element_schema_registry::DomElementSchemaRegistry;
@@ -100,7 +100,7 @@ pub struct ComponentCompilationJob {
     pub views: indexmap::IndexMap<ir::XrefId, ViewCompilationUnit>,
     pub content_selectors: Option<Expression>,
     pub consts: Vec<Expression>,
-    pub consts_initializers: Vec<Expression>,
+    pub consts_initializers: Vec<Statement>,
 
     pub available_dependencies: Vec<R3TemplateDependencyMetadata>,
     pub used_dependencies: HashSet<usize>,
@@ -210,7 +210,7 @@ impl ComponentCompilationJob {
     pub fn add_const(
         &mut self,
         new_const: Expression,
-        initializers: Option<Vec<Expression>>,
+        initializers: Option<Vec<Statement>>,
     ) -> ir::ConstIndex {
         // Check for equivalent constants
         for (idx, existing) in self.consts.iter().enumerate() {
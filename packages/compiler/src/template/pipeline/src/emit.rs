@@ -412,14 +412,26 @@ pub fn emit_component(
         });
     }
 
-    // consts - collected element attributes from const_collection phase
+    // consts - collected element attributes from const_collection phase. When some consts need
+    // initializing statements (e.g. i18n messages), they are wrapped in a function.
+    let consts_expr = o::Expression::LiteralArray(o::LiteralArrayExpr {
+        entries: job.consts.iter().cloned().collect(),
+        type_: None,
+        source_span: None,
+    });
+    let consts_value = if job.consts_initializers.is_empty() {
+        consts_expr
+    } else {
+        let mut body = job.consts_initializers.clone();
+        body.push(o::Statement::Return(o::ReturnStatement {
+            value: Box::new(consts_expr),
+            source_span: None,
+        }));
+        *o::arrow_fn(vec![], o::ArrowFunctionBody::Statements(body), None)
+    };
     definition_entries.push(o::LiteralMapEntry {
         key: "consts".into(),
-        value: Box::new(o::Expression::LiteralArray(o::LiteralArrayExpr {
-            entries: job.consts.iter().cloned().collect(),
-            type_: None,
-            source_span: None,
-        })),
+        value: Box::new(consts_value),
         quoted: false,
     });
 
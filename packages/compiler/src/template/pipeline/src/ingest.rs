@@ -511,7 +511,7 @@ fn ingest_element(
         let i18n_start_op = ir::ops::create::create_i18n_start_op(
             i18n_block_id.unwrap(),
             msg.clone(),
-            Some(id),
+            None,
             Some(element.start_source_span.clone()),
         );
         view.create.push(i18n_start_op);
@@ -1283,82 +1283,118 @@ fn ingest_switch_block(
 }
 
 /// Ingest a deferred block
+/// Ingests one of the views of a `@defer` block as a block template, returning the xref of the
+/// view and the slot handle of its template.
+fn ingest_defer_view(
+    view: &mut ViewCompilationUnit,
+    job: &mut ComponentCompilationJob,
+    suffix: &str,
+    children: Vec<t::R3Node>,
+    source_span: &ParseSourceSpan,
+) -> (ir::XrefId, ir::handle::SlotHandle) {
+    let secondary_view_xref = job.allocate_view(Some(view.xref));
+    ingest_children_into_view(job, secondary_view_xref, children);
+
+    let template_op = ir::ops::create::TemplateOp::new(
+        secondary_view_xref,
+        ir::TemplateKind::Block,
+        None,
+        format!("Defer{}", suffix),
+        ir::Namespace::HTML,
+        None,
+        source_span.clone(),
+        source_span.clone(),
+    );
+    let handle = template_op.base.base.handle.clone();
+    view.create.push(Box::new(template_op));
+    (secondary_view_xref, handle)
+}
+
 fn ingest_defer_block(
     view: &mut ViewCompilationUnit,
     deferred_block: t::DeferredBlock,
     job: &mut ComponentCompilationJob,
 ) {
-    let defer_xref = job.allocate_xref_id();
-
-    // 1. Ingest main block
-    let main_view_xref = job.allocate_view(Some(view.xref));
-
-    // Extract children for main view
-    let main_children = deferred_block.children.clone();
-
-    // Ingest children into main view
-    ingest_children_into_view(job, main_view_xref, main_children);
-
-    // Resolver (not yet supported in Rust AST?)
-    let own_resolver_fn = None;
-
-    // 2. Ingest @loading block if present
-    let (loading_view, loading_minimum_time, loading_after_time) =
-        if let Some(loading) = &deferred_block.loading {
-            let view_xref = job.allocate_view(Some(view.xref));
-            ingest_children_into_view(job, view_xref, loading.children.clone());
-
-            let min = loading.minimum_time.map(|t| t as f64);
-            let after = loading.after_time.map(|t| t as f64);
-            (Some(view_xref), min, after)
-        } else {
-            (None, None, None)
-        };
-
-    // 3. Ingest @placeholder block if present
-    let (placeholder_view, placeholder_minimum_time) =
-        if let Some(placeholder) = &deferred_block.placeholder {
-            let view_xref = job.allocate_view(Some(view.xref));
-            ingest_children_into_view(job, view_xref, placeholder.children.clone());
-
-            let min = placeholder.minimum_time.map(|t| t as f64);
-            (Some(view_xref), min)
-        } else {
-            (None, None)
-        };
-
-    // 4. Ingest @error block if present
-    let error_view = if let Some(error) = &deferred_block.error {
-        let view_xref = job.allocate_view(Some(view.xref));
-        ingest_children_into_view(job, view_xref, error.children.clone());
-
-        Some(view_xref)
-    } else {
-        None
+    // In per-block mode, the resolver functions are keyed by the offset of their defer block.
+    let own_resolver_fn = match &job.defer_meta {
+        R3ComponentDeferMetadata::PerBlock { blocks } => blocks
+            .get(&deferred_block.block.source_span.start.offset)
+            .unwrap_or_else(|| {
+                panic!(
+                    "AssertionError: unable to find a dependency function for this deferred block"
+                )
+            })
+            .clone(),
+        R3ComponentDeferMetadata::PerComponent { .. } => None,
     };
 
-    // Create DeferOp
+    // Generate the defer main view and all secondary views.
+    let (main_view, main_slot) = ingest_defer_view(
+        view,
+        job,
+        "",
+        deferred_block.children.clone(),
+        &deferred_block.block.source_span,
+    );
+    let loading = deferred_block.loading.as_ref().map(|loading| {
+        ingest_defer_view(
+            view,
+            job,
+            "Loading",
+            loading.children.clone(),
+            &loading.block.source_span,
+        )
+    });
+    let placeholder = deferred_block.placeholder.as_ref().map(|placeholder| {
+        ingest_defer_view(
+            view,
+            job,
+            "Placeholder",
+            placeholder.children.clone(),
+            &placeholder.block.source_span,
+        )
+    });
+    let error = deferred_block.error.as_ref().map(|error| {
+        ingest_defer_view(
+            view,
+            job,
+            "Error",
+            error.children.clone(),
+            &error.block.source_span,
+        )
+    });
+
+    // Create the main defer op, and ops for all secondary views.
+    let defer_xref = job.allocate_xref_id();
     let mut defer_op = ir::ops::create::DeferOp::new(
         defer_xref,
-        main_view_xref,
-        ir::handle::SlotHandle::with_slot(0), // main_slot - TODO: extract from view
+        main_view,
+        main_slot,
         own_resolver_fn,
-        job.all_deferrable_deps_fn.clone(), // resolver_fn
+        job.all_deferrable_deps_fn.clone(),
         deferred_block.block.source_span.clone(),
     );
-
-    // Set secondary views and their slots
-    defer_op.placeholder_view = placeholder_view;
-    defer_op.placeholder_slot = placeholder_view.map(|_| ir::handle::SlotHandle::with_slot(0)); // TODO: extract
-    defer_op.loading_view = loading_view;
-    defer_op.loading_slot = loading_view.map(|_| ir::handle::SlotHandle::with_slot(0)); // TODO: extract
-    defer_op.error_view = error_view;
-    defer_op.error_slot = error_view.map(|_| ir::handle::SlotHandle::with_slot(0)); // TODO: extract
-
-    // Set minimum times
-    defer_op.placeholder_minimum_time = placeholder_minimum_time;
-    defer_op.loading_minimum_time = loading_minimum_time;
-    defer_op.loading_after_time = loading_after_time;
+    defer_op.placeholder_view = placeholder.as_ref().map(|(xref, _)| *xref);
+    defer_op.placeholder_slot = placeholder.map(|(_, handle)| handle);
+    defer_op.loading_view = loading.as_ref().map(|(xref, _)| *xref);
+    defer_op.loading_slot = loading.map(|(_, handle)| handle);
+    defer_op.error_view = error.as_ref().map(|(xref, _)| *xref);
+    defer_op.error_slot = error.map(|(_, handle)| handle);
+    defer_op.placeholder_minimum_time = deferred_block
+        .placeholder
+        .as_ref()
+        .and_then(|placeholder| placeholder.minimum_time)
+        .map(|t| t as f64);
+    defer_op.loading_minimum_time = deferred_block
+        .loading
+        .as_ref()
+        .and_then(|loading| loading.minimum_time)
+        .map(|t| t as f64);
+    defer_op.loading_after_time = deferred_block
+        .loading
+        .as_ref()
+        .and_then(|loading| loading.after_time)
+        .map(|t| t as f64);
 
     // Calculate flags
     if !deferred_block.hydrate_triggers.when.is_none()
@@ -1586,11 +1622,11 @@ fn ingest_defer_triggers(
 fn icu_from_i18n_message(message: &crate::i18n::i18n_ast::Message) -> Option<String> {
     use crate::i18n::i18n_ast::Node as I18nNode;
 
-    // Get first node which should be IcuPlaceholder for single ICU
-    if let Some(I18nNode::IcuPlaceholder(icu_ph)) = message.nodes.first() {
-        Some(icu_ph.name.clone())
-    } else {
-        None
+    // The first node is the ICU itself; nested ICUs are named by the meta visitor
+    match message.nodes.first() {
+        Some(I18nNode::Icu(icu)) => icu.name.clone(),
+        Some(I18nNode::IcuPlaceholder(icu_ph)) => Some(icu_ph.name.clone()),
+        _ => None,
     }
 }
 
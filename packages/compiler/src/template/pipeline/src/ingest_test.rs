@@ -206,6 +206,7 @@ mod tests {
             encapsulation: crate::core::ViewEncapsulation::Emulated,
             relative_context_file_path: "test.ts".to_string(),
             relative_template_path: Some("test.html".to_string()),
+            enable_template_source_locations: false,
             i18n_use_external_ids: false,
             defer: crate::render3::view::api::R3ComponentDeferMetadata::PerComponent {
                 dependencies_fn: None,
@@ -5,6 +5,7 @@
 use crate::output::output_ast as o;
 use crate::parse_util::ParseSourceSpan;
 use crate::render3::r3_identifiers::Identifiers;
+use crate::template::pipeline::ir::enums::{DeferOpModifierKind, DeferTriggerKind};
 use o::ExternalReference;
 
 pub fn call(
@@ -160,6 +161,59 @@ pub fn text<S: AsRef<str>>(
     call(Identifiers::text(), args, source_span)
 }
 
+pub fn i18n_start(
+    slot: i32,
+    const_index: i32,
+    sub_template_index: Option<i32>,
+    source_span: Option<ParseSourceSpan>,
+) -> o::Statement {
+    let mut args = vec![*o::literal(slot as f64), *o::literal(const_index as f64)];
+    if let Some(sub_template_index) = sub_template_index {
+        args.push(*o::literal(sub_template_index as f64));
+    }
+    call(Identifiers::i18n_start(), args, source_span)
+}
+
+pub fn i18n(
+    slot: i32,
+    const_index: i32,
+    sub_template_index: Option<i32>,
+    source_span: Option<ParseSourceSpan>,
+) -> o::Statement {
+    let mut args = vec![*o::literal(slot as f64), *o::literal(const_index as f64)];
+    if let Some(sub_template_index) = sub_template_index {
+        args.push(*o::literal(sub_template_index as f64));
+    }
+    call(Identifiers::i18n(), args, source_span)
+}
+
+pub fn i18n_end(end_source_span: Option<ParseSourceSpan>) -> o::Statement {
+    call(Identifiers::i18n_end(), vec![], end_source_span)
+}
+
+pub fn i18n_attributes(slot: i32, i18n_attributes_config: i32) -> o::Statement {
+    call(
+        Identifiers::i18n_attributes(),
+        vec![
+            *o::literal(slot as f64),
+            *o::literal(i18n_attributes_config as f64),
+        ],
+        None,
+    )
+}
+
+pub fn i18n_exp(expr: o::Expression, source_span: Option<ParseSourceSpan>) -> o::Statement {
+    call(Identifiers::i18n_exp(), vec![expr], source_span)
+}
+
+pub fn i18n_apply(slot: i32, source_span: Option<ParseSourceSpan>) -> o::Statement {
+    call(
+        Identifiers::i18n_apply(),
+        vec![*o::literal(slot as f64)],
+        source_span,
+    )
+}
+
 pub fn pipe<S: AsRef<str>>(slot: i32, name: S) -> o::Statement {
     call(
         Identifiers::pipe(),
@@ -168,6 +222,24 @@ pub fn pipe<S: AsRef<str>>(slot: i32, name: S) -> o::Statement {
     )
 }
 
+pub fn attach_source_locations<S: AsRef<str>>(
+    template_path: S,
+    locations: Vec<o::Expression>,
+) -> o::Statement {
+    call(
+        Identifiers::attach_source_locations(),
+        vec![
+            *o::literal(template_path.as_ref()),
+            o::Expression::LiteralArray(o::LiteralArrayExpr {
+                entries: locations,
+                type_: None,
+                source_span: None,
+            }),
+        ],
+        None,
+    )
+}
+
 pub fn advance(delta: i32, source_span: ParseSourceSpan) -> o::Statement {
     let args = if delta > 1 {
         vec![*o::literal(delta as f64)]
@@ -388,7 +460,7 @@ pub fn dom_template(
     template_fn: o::Expression,
     decls: usize,
     vars: usize,
-    tag: String,
+    tag: Option<String>,
     const_index: Option<i32>,
     local_ref_index: Option<i32>,
     source_span: ParseSourceSpan,
@@ -398,9 +470,15 @@ pub fn dom_template(
         template_fn,
         *o::literal(decls as f64),
         *o::literal(vars as f64),
-        *o::literal(tag),
     ];
 
+    // Tag argument (or null if we need to emit later args)
+    if let Some(t) = tag {
+        args.push(*o::literal(t));
+    } else if const_index.is_some() || local_ref_index.is_some() {
+        args.push(*o::literal(o::LiteralValue::Null));
+    }
+
     // Const index argument
     if let Some(c) = const_index {
         args.push(*o::literal(c as f64));
@@ -411,11 +489,108 @@ pub fn dom_template(
     // Local ref index
     if let Some(lri) = local_ref_index {
         args.push(*o::literal(lri as f64));
+        args.push(*o::import_ref(Identifiers::template_ref_extractor()));
     }
 
     call(Identifiers::dom_template(), args, Some(source_span))
 }
 
+/// Generates a ɵɵdefer(selfSlot, primarySlot, ...) statement. Trailing null arguments are omitted.
+#[allow(clippy::too_many_arguments)]
+pub fn defer(
+    self_slot: i32,
+    primary_slot: i32,
+    dependency_resolver_fn: Option<o::Expression>,
+    loading_slot: Option<i32>,
+    placeholder_slot: Option<i32>,
+    error_slot: Option<i32>,
+    loading_config: Option<o::Expression>,
+    placeholder_config: Option<o::Expression>,
+    enable_timer_scheduling: bool,
+    source_span: ParseSourceSpan,
+    flags: Option<u8>,
+) -> o::Statement {
+    let null = || *o::literal(o::LiteralValue::Null);
+    let slot = |slot: Option<i32>| slot.map_or_else(null, |s| *o::literal(s as f64));
+    let mut args = vec![
+        *o::literal(self_slot as f64),
+        *o::literal(primary_slot as f64),
+        dependency_resolver_fn.unwrap_or_else(null),
+        slot(loading_slot),
+        slot(placeholder_slot),
+        slot(error_slot),
+        loading_config.unwrap_or_else(null),
+        placeholder_config.unwrap_or_else(null),
+        if enable_timer_scheduling {
+            *o::import_ref(Identifiers::defer_enable_timer_scheduling())
+        } else {
+            null()
+        },
+        flags.map_or_else(null, |f| *o::literal(f as f64)),
+    ];
+
+    while matches!(
+        args.last(),
+        Some(o::Expression::Literal(o::LiteralExpr {
+            value: o::LiteralValue::Null,
+            ..
+        }))
+    ) {
+        args.pop();
+    }
+
+    call(Identifiers::defer(), args, Some(source_span))
+}
+
+/// Generates the `ɵɵdefer*On*` instruction for a trigger kind and modifier.
+pub fn defer_on(
+    trigger: DeferTriggerKind,
+    args: Vec<o::Expression>,
+    modifier: DeferOpModifierKind,
+    source_span: ParseSourceSpan,
+) -> o::Statement {
+    use DeferOpModifierKind as M;
+    use DeferTriggerKind as T;
+
+    let instruction = match (trigger, modifier) {
+        (T::Idle, M::None) => Identifiers::defer_on_idle(),
+        (T::Idle, M::Prefetch) => Identifiers::defer_prefetch_on_idle(),
+        (T::Idle, M::Hydrate) => Identifiers::defer_hydrate_on_idle(),
+        (T::Immediate, M::None) => Identifiers::defer_on_immediate(),
+        (T::Immediate, M::Prefetch) => Identifiers::defer_prefetch_on_immediate(),
+        (T::Immediate, M::Hydrate) => Identifiers::defer_hydrate_on_immediate(),
+        (T::Timer, M::None) => Identifiers::defer_on_timer(),
+        (T::Timer, M::Prefetch) => Identifiers::defer_prefetch_on_timer(),
+        (T::Timer, M::Hydrate) => Identifiers::defer_hydrate_on_timer(),
+        (T::Hover, M::None) => Identifiers::defer_on_hover(),
+        (T::Hover, M::Prefetch) => Identifiers::defer_prefetch_on_hover(),
+        (T::Hover, M::Hydrate) => Identifiers::defer_hydrate_on_hover(),
+        (T::Interaction, M::None) => Identifiers::defer_on_interaction(),
+        (T::Interaction, M::Prefetch) => Identifiers::defer_prefetch_on_interaction(),
+        (T::Interaction, M::Hydrate) => Identifiers::defer_hydrate_on_interaction(),
+        (T::Viewport, M::None) => Identifiers::defer_on_viewport(),
+        (T::Viewport, M::Prefetch) => Identifiers::defer_prefetch_on_viewport(),
+        (T::Viewport, M::Hydrate) => Identifiers::defer_hydrate_on_viewport(),
+        (T::Never, _) => Identifiers::defer_hydrate_never(),
+    };
+
+    call(instruction, args, Some(source_span))
+}
+
+/// Generates a `ɵɵdeferWhen` (or prefetch/hydrate variant) statement.
+pub fn defer_when(
+    modifier: DeferOpModifierKind,
+    expr: o::Expression,
+    source_span: ParseSourceSpan,
+) -> o::Statement {
+    let instruction = match modifier {
+        DeferOpModifierKind::None => Identifiers::defer_when(),
+        DeferOpModifierKind::Prefetch => Identifiers::defer_prefetch_when(),
+        DeferOpModifierKind::Hydrate => Identifiers::defer_hydrate_when(),
+    };
+    call_update(instruction, vec![expr], Some(source_span))
+}
+
 pub fn conditional(
     slot: i32,
     condition: o::Expression,
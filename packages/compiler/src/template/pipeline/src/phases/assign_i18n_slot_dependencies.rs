@@ -99,7 +99,8 @@ fn process_unit(unit: &mut crate::template::pipeline::src::compilation::ViewComp
                                 index: update_index,
                             });
                             i18n_expressions_in_progress.push(expr_op.clone());
-                            // Don't increment update_index - next op is now at this index
+                            // Removals are deferred, so indices stay in terms of the original list
+                            update_index += 1;
                             continue;
                         }
                     }
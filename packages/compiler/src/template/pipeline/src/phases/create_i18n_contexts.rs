@@ -296,7 +296,7 @@ fn process_blocks_for_unit(
 
         // Extract context_op fields before pushing
         let context_op_clone = unsafe {
-            let op_ptr = &context_op as *const Box<dyn ir::CreateOp + Send + Sync>;
+            let op_ptr = context_op.as_ref() as *const dyn ir::CreateOp;
             let ctx_ptr = op_ptr as *const I18nContextOp;
             (*ctx_ptr).clone()
         };
@@ -51,294 +51,146 @@ pub fn extract_i18n_messages(job: &mut dyn CompilationJob) {
         &mut *component_job_ptr
     };
 
-    // Create an i18n message for each context.
+    // Create an i18n message for each context. The message ops are only pushed onto their units
+    // once the ICU sub-messages and placeholders below have been associated with them.
     let mut i18n_messages_by_context: std::collections::HashMap<ir::XrefId, I18nMessageOp> =
         std::collections::HashMap::new();
     let mut i18n_blocks: std::collections::HashMap<ir::XrefId, I18nStartOp> =
         std::collections::HashMap::new();
     let mut i18n_contexts: std::collections::HashMap<ir::XrefId, I18nContextOp> =
         std::collections::HashMap::new();
+    let mut contexts_by_unit: Vec<Vec<ir::XrefId>> = Vec::new();
 
-    // Collect I18nContext and I18nStart ops
-    // Process root unit - collect first
-    let mut root_message_ops: Vec<(I18nMessageOp, I18nContextOp)> = Vec::new();
-    for op in component_job.root.create.iter() {
-        match op.kind() {
-            OpKind::I18nContext => unsafe {
-                let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                let ctx_ptr = op_ptr as *const I18nContextOp;
-                let ctx = &*ctx_ptr;
-
-                let i18n_message_op = create_i18n_message(job, ctx.clone(), None);
-                root_message_ops.push((i18n_message_op.clone(), ctx.clone()));
-                i18n_messages_by_context.insert(ctx.xref, i18n_message_op);
-                i18n_contexts.insert(ctx.xref, ctx.clone());
-            },
-            OpKind::I18nStart => unsafe {
-                let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                let i18n_ptr = op_ptr as *const I18nStartOp;
-                let i18n = &*i18n_ptr;
-                i18n_blocks.insert(i18n.base.xref, i18n.clone());
-            },
-            _ => {}
-        }
-    }
-
-    // Push message ops to root unit
-    for (message_op, _) in root_message_ops {
-        component_job.root.create.push(Box::new(message_op));
-    }
-
-    // Process all view units
-    for (_, unit) in component_job.views.iter() {
+    let units = std::iter::once(&component_job.root).chain(component_job.views.values());
+    for unit in units {
+        let mut unit_contexts = Vec::new();
         for op in unit.create.iter() {
             match op.kind() {
-                OpKind::I18nContext => {
-                    unsafe {
-                        let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                        let ctx_ptr = op_ptr as *const I18nContextOp;
-                        let ctx = &*ctx_ptr;
-
-                        let i18n_message_op = create_i18n_message(job, ctx.clone(), None);
-                        // We'll add this to the unit later
-                        i18n_messages_by_context.insert(ctx.xref, i18n_message_op);
-                        i18n_contexts.insert(ctx.xref, ctx.clone());
-                    }
-                }
+                OpKind::I18nContext => unsafe {
+                    let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
+                    let ctx = &*(op_ptr as *const I18nContextOp);
+                    let i18n_message_op = create_i18n_message(job, ctx.clone(), None);
+                    i18n_messages_by_context.insert(ctx.xref, i18n_message_op);
+                    i18n_contexts.insert(ctx.xref, ctx.clone());
+                    unit_contexts.push(ctx.xref);
+                },
                 OpKind::I18nStart => unsafe {
                     let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                    let i18n_ptr = op_ptr as *const I18nStartOp;
-                    let i18n = &*i18n_ptr;
+                    let i18n = &*(op_ptr as *const I18nStartOp);
                     i18n_blocks.insert(i18n.base.xref, i18n.clone());
                 },
                 _ => {}
             }
         }
-    }
-
-    // Add I18nMessageOps to units - collect first
-    let mut view_message_ops: Vec<(ir::XrefId, I18nMessageOp)> = Vec::new();
-    for (_, unit) in component_job.views.iter() {
-        for op in unit.create.iter() {
-            if op.kind() == OpKind::I18nContext {
-                unsafe {
-                    let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                    let ctx_ptr = op_ptr as *const I18nContextOp;
-                    let ctx = &*ctx_ptr;
-
-                    if let Some(message_op) = i18n_messages_by_context.get(&ctx.xref) {
-                        view_message_ops.push((ctx.xref, message_op.clone()));
-                    }
-                }
-            }
-        }
-    }
-
-    // Push message ops to view units - collect context xrefs first
-    let mut view_context_xrefs: Vec<ir::XrefId> = Vec::new();
-    for (_, unit) in component_job.views.iter() {
-        for op in unit.create.iter() {
-            if op.kind() == OpKind::I18nContext {
-                unsafe {
-                    let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                    let ctx_ptr = op_ptr as *const I18nContextOp;
-                    let ctx = &*ctx_ptr;
-                    view_context_xrefs.push(ctx.xref);
-                }
-            }
-        }
-    }
-
-    // Push message ops
-    for (_, unit) in component_job.views.iter_mut() {
-        for ctx_xref in &view_context_xrefs {
-            if let Some((_, message_op)) =
-                view_message_ops.iter().find(|(xref, _)| *xref == *ctx_xref)
-            {
-                unit.create.push(Box::new(message_op.clone()));
-            }
-        }
+        contexts_by_unit.push(unit_contexts);
     }
 
     // Associate sub-messages for ICUs with their root message. At this point we can also remove the
     // ICU start/end ops, as they are no longer needed.
-    let mut current_icu: Option<usize> = None;
-    let mut icu_indices_to_remove = Vec::new();
-    let mut icu_placeholder_indices_to_remove = Vec::new();
-
-    // Process root unit
-    process_icus_for_unit(
-        &mut component_job.root,
-        &i18n_blocks,
-        &i18n_contexts,
-        &mut i18n_messages_by_context,
-        &mut current_icu,
-        &mut icu_indices_to_remove,
-        &mut icu_placeholder_indices_to_remove,
-    );
-
-    // Process all view units
-    for (_, unit) in component_job.views.iter_mut() {
-        process_icus_for_unit(
-            unit,
-            &i18n_blocks,
-            &i18n_contexts,
-            &mut i18n_messages_by_context,
-            &mut current_icu,
-            &mut icu_indices_to_remove,
-            &mut icu_placeholder_indices_to_remove,
-        );
-    }
-
-    // Remove ICU ops
-    // Process root unit
-    icu_indices_to_remove.sort();
-    icu_indices_to_remove.reverse();
-    for idx in &icu_indices_to_remove {
-        component_job.root.create.remove_at(*idx);
-    }
-    icu_placeholder_indices_to_remove.sort();
-    icu_placeholder_indices_to_remove.reverse();
-    for idx in &icu_placeholder_indices_to_remove {
-        component_job.root.create.remove_at(*idx);
-    }
-
-    // Process all view units
-    for (_, unit) in component_job.views.iter_mut() {
-        // Note: We need to track indices per unit, but for simplicity, we'll process each unit separately
-        let mut unit_icu_indices = Vec::new();
-        let mut unit_icu_placeholder_indices = Vec::new();
-
+    let mut current_icu: Option<IcuStartOp> = None;
+    let units = std::iter::once(&mut component_job.root).chain(component_job.views.values_mut());
+    for (unit, unit_contexts) in units.zip(contexts_by_unit) {
+        let mut indices_to_remove = Vec::new();
         for (idx, op) in unit.create.iter().enumerate() {
             match op.kind() {
-                OpKind::IcuStart | OpKind::IcuEnd => {
-                    unit_icu_indices.push(idx);
+                OpKind::IcuStart => {
+                    indices_to_remove.push(idx);
+                    let icu = unsafe {
+                        let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
+                        (*(op_ptr as *const IcuStartOp)).clone()
+                    };
+                    associate_icu_sub_message(
+                        &icu,
+                        &i18n_blocks,
+                        &i18n_contexts,
+                        &mut i18n_messages_by_context,
+                    );
+                    current_icu = Some(icu);
+                }
+                OpKind::IcuEnd => {
+                    indices_to_remove.push(idx);
+                    current_icu = None;
                 }
                 OpKind::IcuPlaceholder => {
-                    unit_icu_placeholder_indices.push(idx);
+                    // Add ICU placeholders to the message, then remove the ICU placeholder ops.
+                    let icu_context = current_icu.as_ref().and_then(|icu| icu.context);
+                    let Some(icu_context) = icu_context else {
+                        panic!(
+                            "AssertionError: Unexpected ICU placeholder outside of i18n context"
+                        );
+                    };
+                    let icu_ph = unsafe {
+                        let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
+                        &*(op_ptr as *const IcuPlaceholderOp)
+                    };
+                    if let Some(msg) = i18n_messages_by_context.get_mut(&icu_context) {
+                        msg.postprocessing_params.insert(
+                            icu_ph.name.to_string(),
+                            OutputExpression::Literal(LiteralExpr {
+                                value: LiteralValue::String(format_icu_placeholder(icu_ph)),
+                                type_: None,
+                                source_span: None,
+                            }),
+                        );
+                    }
+                    indices_to_remove.push(idx);
                 }
                 _ => {}
             }
         }
 
-        unit_icu_indices.sort();
-        unit_icu_indices.reverse();
-        for idx in unit_icu_indices {
+        for idx in indices_to_remove.into_iter().rev() {
             unit.create.remove_at(idx);
         }
-
-        unit_icu_placeholder_indices.sort();
-        unit_icu_placeholder_indices.reverse();
-        for idx in unit_icu_placeholder_indices {
-            unit.create.remove_at(idx);
+        for context_xref in unit_contexts {
+            if let Some(message_op) = i18n_messages_by_context.get(&context_xref) {
+                unit.create.push(Box::new(message_op.clone()));
+            }
         }
     }
 }
 
-fn process_icus_for_unit(
-    unit: &mut crate::template::pipeline::src::compilation::ViewCompilationUnit,
+/// Records the message of an ICU with its own context as a sub-message of its root message.
+fn associate_icu_sub_message(
+    icu: &IcuStartOp,
     i18n_blocks: &std::collections::HashMap<ir::XrefId, I18nStartOp>,
     i18n_contexts: &std::collections::HashMap<ir::XrefId, I18nContextOp>,
     i18n_messages_by_context: &mut std::collections::HashMap<ir::XrefId, I18nMessageOp>,
-    current_icu: &mut Option<usize>,
-    icu_indices_to_remove: &mut Vec<usize>,
-    icu_placeholder_indices_to_remove: &mut Vec<usize>,
 ) {
-    for (idx, op) in unit.create.iter().enumerate() {
-        match op.kind() {
-            OpKind::IcuStart => {
-                *current_icu = Some(idx);
-                icu_indices_to_remove.push(idx);
+    // Skip any contexts not associated with an ICU.
+    let Some(icu_context) = icu.context.and_then(|context| i18n_contexts.get(&context)) else {
+        return;
+    };
+    if icu_context.context_kind != ir::enums::I18nContextKind::Icu {
+        return;
+    }
 
-                unsafe {
-                    let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                    let icu_ptr = op_ptr as *const IcuStartOp;
-                    let icu = &*icu_ptr;
-
-                    // Skip any contexts not associated with an ICU.
-                    if let Some(context_id) = icu.context {
-                        if let Some(icu_context) = i18n_contexts.get(&context_id) {
-                            if icu_context.context_kind != ir::enums::I18nContextKind::Icu {
-                                continue;
-                            }
-
-                            // Skip ICUs that share a context with their i18n message. These represent root-level
-                            // ICUs, not sub-messages.
-                            if let Some(i18n_block_id) = icu_context.i18n_block {
-                                if let Some(i18n_block) = i18n_blocks.get(&i18n_block_id) {
-                                    if i18n_block.base.context == Some(context_id) {
-                                        continue;
-                                    }
-
-                                    // Find the root message and push this ICUs message as a sub-message.
-                                    if let Some(root_i18n_block) =
-                                        i18n_blocks.get(&i18n_block.base.root)
-                                    {
-                                        if let Some(root_context_id) = root_i18n_block.base.context
-                                        {
-                                            // Collect sub_message xref first to avoid double mutable borrow
-                                            let sub_message_xref = i18n_messages_by_context
-                                                .get(&context_id)
-                                                .map(|m| m.xref);
-
-                                            if let Some(root_message) =
-                                                i18n_messages_by_context.get_mut(&root_context_id)
-                                            {
-                                                if let Some(sub_xref) = sub_message_xref {
-                                                    // Set message placeholder if available
-                                                    // Note: IcuStartOp doesn't have message_placeholder field directly
-                                                    // We'll need to find it from the message
-                                                    root_message.sub_messages.push(sub_xref);
-                                                }
-                                            } else {
-                                                panic!("AssertionError: ICU sub-message should belong to a root message.");
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            OpKind::IcuEnd => {
-                *current_icu = None;
-                icu_indices_to_remove.push(idx);
-            }
-            OpKind::IcuPlaceholder => {
-                // Add ICU placeholders to the message, then remove the ICU placeholder ops.
-                if let Some(icu_idx) = *current_icu {
-                    unsafe {
-                        let icu_op_ptr =
-                            unit.create.get(icu_idx).unwrap().as_ref() as *const dyn ir::CreateOp;
-                        let icu_ptr = icu_op_ptr as *const IcuStartOp;
-                        let icu = &*icu_ptr;
-
-                        if icu.context.is_none() {
-                            panic!("AssertionError: Unexpected ICU placeholder outside of i18n context");
-                        }
+    // Skip ICUs that share a context with their i18n message. These represent root-level
+    // ICUs, not sub-messages.
+    let Some(i18n_block) = icu_context
+        .i18n_block
+        .and_then(|block| i18n_blocks.get(&block))
+    else {
+        return;
+    };
+    if i18n_block.base.context == Some(icu_context.xref) {
+        return;
+    }
 
-                        let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
-                        let icu_ph_ptr = op_ptr as *const IcuPlaceholderOp;
-                        let icu_ph = &*icu_ph_ptr;
-
-                        if let Some(msg) = i18n_messages_by_context.get_mut(&icu.context.unwrap()) {
-                            let formatted = format_icu_placeholder(icu_ph);
-                            msg.postprocessing_params.insert(
-                                icu_ph.name.to_string(),
-                                OutputExpression::Literal(LiteralExpr {
-                                    value: LiteralValue::String(formatted),
-                                    type_: None,
-                                    source_span: None,
-                                }),
-                            );
-                        }
-                    }
-                }
-                icu_placeholder_indices_to_remove.push(idx);
-            }
-            _ => {}
+    // Find the root message and push this ICUs message as a sub-message.
+    let root_context = i18n_blocks
+        .get(&i18n_block.base.root)
+        .and_then(|root| root.base.context);
+    let sub_message_xref = match i18n_messages_by_context.get_mut(&icu_context.xref) {
+        Some(sub_message) => {
+            sub_message.message_placeholder = Some(icu.message_placeholder.clone());
+            sub_message.xref
         }
+        None => return,
+    };
+    match root_context.and_then(|context| i18n_messages_by_context.get_mut(&context)) {
+        Some(root_message) => root_message.sub_messages.push(sub_message_xref),
+        None => panic!("AssertionError: ICU sub-message should belong to a root message."),
     }
 }
 
@@ -500,19 +352,19 @@ fn format_value(value: &ir::ops::create::I18nParamValue) -> String {
 
     // Encode the remaining flags as part of the value.
     let tag_marker = if value.flags.contains(I18nParamValueFlags::ELEMENT_TAG) {
-        ELEMENT_MARKER
+        Some(ELEMENT_MARKER)
     } else if value.flags.contains(I18nParamValueFlags::TEMPLATE_TAG) {
-        TEMPLATE_MARKER
+        Some(TEMPLATE_MARKER)
     } else {
-        '\0'
+        None
     };
 
-    let close_marker = if tag_marker != '\0' && value.flags.contains(I18nParamValueFlags::CLOSE_TAG)
-    {
-        TAG_CLOSE_MARKER
-    } else {
-        '\0'
-    };
+    let close_marker =
+        if tag_marker.is_some() && value.flags.contains(I18nParamValueFlags::CLOSE_TAG) {
+            Some(TAG_CLOSE_MARKER)
+        } else {
+            None
+        };
 
     let context = if let Some(sub_template_index) = value.sub_template_index {
         format!("{}{}", CONTEXT_MARKER, sub_template_index)
@@ -528,6 +380,11 @@ fn format_value(value: &ir::ops::create::I18nParamValue) -> String {
 
     format!(
         "{}{}{}{}{}{}",
-        ESCAPE, close_marker, tag_marker, value_str, context, ESCAPE
+        ESCAPE,
+        close_marker.map(String::from).unwrap_or_default(),
+        tag_marker.map(String::from).unwrap_or_default(),
+        value_str,
+        context,
+        ESCAPE
     )
 }
@@ -153,6 +153,8 @@ fn has_depends_on_slot_context_trait_by_kind(kind: OpKind) -> bool {
             | OpKind::InterpolateText
             | OpKind::Repeater
             | OpKind::Conditional
+            | OpKind::I18nExpression
+            | OpKind::DeferWhen
     )
 }
 
@@ -322,6 +324,12 @@ fn get_target_from_update_op(
                 let map = &*map_ptr;
                 Some((map.target, map.source_span.clone()))
             }
+            OpKind::I18nExpression => {
+                use crate::template::pipeline::ir::ops::update::I18nExpressionOp;
+                let expr_ptr = op_ptr as *const I18nExpressionOp;
+                let expr = &*expr_ptr;
+                Some((expr.target, expr.source_span.clone()))
+            }
             OpKind::DomProperty => {
                 use crate::template::pipeline::ir::ops::update::PropertyOp as DomPropertyOp;
                 let dom_ptr = op_ptr as *const DomPropertyOp;
@@ -352,6 +360,12 @@ fn get_target_from_update_op(
                 let cond = &*cond_ptr;
                 Some((cond.target, cond.source_span.clone()))
             }
+            OpKind::DeferWhen => {
+                use crate::template::pipeline::ir::ops::update::DeferWhenOp;
+                let when_ptr = op_ptr as *const DeferWhenOp;
+                let when = &*when_ptr;
+                Some((when.target, when.source_span.clone()))
+            }
             OpKind::InterpolateText => {
                 use crate::template::pipeline::ir::ops::update::InterpolateTextOp;
                 let text_ptr = op_ptr as *const InterpolateTextOp;
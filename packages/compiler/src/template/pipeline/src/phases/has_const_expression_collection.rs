@@ -43,6 +43,10 @@ fn transform_expressions_in_create_op(
                     ));
                 }
             }
+            OpKind::Defer => {
+                // Defer configs are wrapped in `ConstCollectedExpr` by the defer configs phase
+                transform_expressions_in_op(op.as_mut(), transform, flags);
+            }
             _ => {
                 // Other create ops don't have expressions or are handled elsewhere
             }
@@ -14,7 +14,9 @@ use crate::parse_util::sanitize_identifier;
 use crate::render3::r3_identifiers::Identifiers;
 use crate::render3::view::i18n::get_msg_utils::create_google_get_msg_statements;
 use crate::render3::view::i18n::localize_utils::create_localize_statements;
-use crate::render3::view::i18n::util::format_i18n_placeholder_names_in_map;
+use crate::render3::view::i18n::util::{
+    format_i18n_placeholder_name, format_i18n_placeholder_names_in_map,
+};
 use crate::template::pipeline::ir;
 use crate::template::pipeline::ir::enums::{I18nExpressionFor, OpKind};
 use crate::template::pipeline::ir::ops::create::{
@@ -61,7 +63,7 @@ pub fn declare_i18n_variable(variable: &ReadVarExpr) -> Statement {
         name: variable.name.clone(),
         value: None,
         type_: None,
-        modifiers: crate::output::output_ast::StmtModifier::Final,
+        modifiers: crate::output::output_ast::StmtModifier::None,
         source_span: variable.source_span.clone(),
     })
 }
@@ -235,16 +237,14 @@ pub fn collect_i18n_consts(job: &mut ComponentCompilationJob) {
             if msg_op.i18n_block.is_some() {
                 // This is a regular i18n message with a corresponding i18n block. Collect it into the
                 // const array.
-                let i18n_const =
-                    job.add_const(OutputExpression::ReadVar(result.main_var.clone()), None);
+                let i18n_const = job.add_const(
+                    OutputExpression::ReadVar(result.main_var.clone()),
+                    Some(result.statements),
+                );
                 message_const_indices.insert(msg_op.i18n_block.unwrap(), i18n_const);
             } else {
                 // This is an i18n attribute. Extract the initializers into the const pool.
-                // Note: In TypeScript, statements are pushed to constsInitializers
-                // In Rust, we need to convert statements to expressions
-                // For now, we'll just store the main variable
-                job.consts_initializers
-                    .push(OutputExpression::ReadVar(result.main_var.clone()));
+                job.consts_initializers.extend(result.statements);
 
                 // Save the i18n variable value for later.
                 i18n_values_by_context.insert(
@@ -494,22 +494,12 @@ fn collect_message(
         }
     }
 
-    // Note: add_sub_message_params is currently a no-op as we can't mutate message_op
-    // This would need to be refactored to return updated params
-    // add_sub_message_params(message_op, &mut sub_message_placeholders);
-
-    // Sort the params for consistency with TemplateDefinitionBuilder output.
-    let mut sorted_params: Vec<(String, OutputExpression)> = message_op
-        .params
-        .iter()
-        .map(|(k, v)| (k.clone(), v.clone()))
-        .collect();
-    sorted_params.sort_by(|a, b| a.0.cmp(&b.0));
-    // Note: We can't directly mutate message_op.params here, so we'll work with a copy
+    let mut message_op = message_op.clone();
+    add_sub_message_params(&mut message_op, sub_message_placeholders);
 
     let main_var_expr = crate::output::output_ast::variable(
         job.pool
-            .unique_name(TRANSLATION_VAR_PREFIX.to_string(), false),
+            .unique_name(TRANSLATION_VAR_PREFIX.to_string(), true),
     );
     let main_var = match *main_var_expr {
         OutputExpression::ReadVar(ref expr) => expr.clone(),
@@ -532,48 +522,39 @@ fn collect_message(
     // set in post-processing.
     if message_op.needs_postprocessing || !message_op.postprocessing_params.is_empty() {
         // Sort the post-processing params for consistency with TemplateDefinitionBuilder output.
-        let mut sorted_postprocessing_params: Vec<(String, OutputExpression)> = message_op
-            .postprocessing_params
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-        sorted_postprocessing_params.sort_by(|a, b| a.0.cmp(&b.0));
-        let postprocessing_params_map: HashMap<String, OutputExpression> =
-            sorted_postprocessing_params.into_iter().collect();
-        let formatted_postprocessing_params =
-            format_i18n_placeholder_names_in_map(&postprocessing_params_map, false);
-
-        if !message_op.postprocessing_params.is_empty() {
-            let extra_transform_fn_params: Vec<OutputExpression> =
-                vec![OutputExpression::LiteralMap(LiteralMapExpr {
-                    entries: formatted_postprocessing_params
-                        .iter()
-                        .map(|(k, v)| LiteralMapEntry {
-                            key: k.clone(),
-                            value: Box::new(v.clone()),
-                            quoted: true,
-                        })
-                        .collect(),
-                    type_: None,
-                    source_span: None,
-                })];
-
-            transform_fn = Some(Box::new(move |expr: &ReadVarExpr| {
-                OutputExpression::InvokeFn(crate::output::output_ast::InvokeFunctionExpr {
-                    fn_: Box::new(OutputExpression::ExternalRef(
-                        Identifiers::i18n_postprocess(),
-                    )),
-                    args: {
-                        let mut args = vec![OutputExpression::ReadVar(expr.clone())];
-                        args.extend(extra_transform_fn_params.iter().cloned());
-                        args
-                    },
-                    type_: None,
-                    source_span: None,
-                    pure: false,
-                })
+        let mut sorted_postprocessing_params: Vec<(&String, &OutputExpression)> =
+            message_op.postprocessing_params.iter().collect();
+        sorted_postprocessing_params.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut extra_transform_fn_params: Vec<OutputExpression> = Vec::new();
+        if !sorted_postprocessing_params.is_empty() {
+            extra_transform_fn_params.push(OutputExpression::LiteralMap(LiteralMapExpr {
+                entries: sorted_postprocessing_params
+                    .into_iter()
+                    .map(|(key, value)| LiteralMapEntry {
+                        key: format_i18n_placeholder_name(key, false),
+                        value: Box::new(value.clone()),
+                        quoted: true,
+                    })
+                    .collect(),
+                type_: None,
+                source_span: None,
             }));
         }
+
+        transform_fn = Some(Box::new(move |expr: &ReadVarExpr| {
+            OutputExpression::InvokeFn(crate::output::output_ast::InvokeFunctionExpr {
+                fn_: crate::output::output_ast::import_ref(Identifiers::i18n_postprocess()),
+                args: {
+                    let mut args = vec![OutputExpression::ReadVar(expr.clone())];
+                    args.extend(extra_transform_fn_params.iter().cloned());
+                    args
+                },
+                type_: None,
+                source_span: None,
+                pure: false,
+            })
+        }));
     }
 
     // Add the message's statements
@@ -603,23 +584,35 @@ struct CollectMessageResult {
 /// as the param value. However, if the placeholder corresponds to multiple sub-message
 /// variables, we need to add a special placeholder value that is handled by the post-processing
 /// step. We then add the array of variables as a post-processing param.
-#[allow(dead_code)]
 fn add_sub_message_params(
-    _message_op: &I18nMessageOp,
-    _sub_message_placeholders: &mut HashMap<String, Vec<OutputExpression>>,
+    message_op: &mut I18nMessageOp,
+    sub_message_placeholders: HashMap<String, Vec<OutputExpression>>,
 ) {
-    // Note: We can't directly mutate message_op here, so this function would need to return
-    // the updated params and postprocessing_params
-    // For now, we'll work with the existing structure
-    // This is a limitation - we may need to refactor to use mutable references
-    for (_placeholder, _sub_messages) in _sub_message_placeholders.iter() {
-        if _sub_messages.len() == 1 {
-            // Would set message_op.params.set(placeholder, sub_messages[0])
-            // But we can't mutate here
+    for (placeholder, mut sub_messages) in sub_message_placeholders {
+        if sub_messages.len() == 1 {
+            message_op
+                .params
+                .insert(placeholder, sub_messages.remove(0));
         } else {
-            // Would set message_op.params.set(placeholder, o.literal(`${ESCAPE}${I18N_ICU_MAPPING_PREFIX}${placeholder}${ESCAPE}`))
-            // And message_op.postprocessing_params.set(placeholder, o.literalArr(subMessages))
-            // But we can't mutate here
+            message_op.params.insert(
+                placeholder.clone(),
+                OutputExpression::Literal(LiteralExpr {
+                    value: LiteralValue::String(format!(
+                        "{}{}{}{}",
+                        ESCAPE, I18N_ICU_MAPPING_PREFIX, placeholder, ESCAPE
+                    )),
+                    type_: None,
+                    source_span: None,
+                }),
+            );
+            message_op.postprocessing_params.insert(
+                placeholder,
+                OutputExpression::LiteralArray(LiteralArrayExpr {
+                    entries: sub_messages,
+                    type_: None,
+                    source_span: None,
+                }),
+            );
         }
     }
 }
@@ -731,7 +724,7 @@ fn i18n_generate_closure_var(
 ) -> ReadVarExpr {
     let name = if use_external_ids {
         let prefix = get_translation_const_prefix("EXTERNAL_");
-        let unique_suffix = pool.unique_name(file_based_i18n_suffix.to_string(), false);
+        let unique_suffix = pool.unique_name(file_based_i18n_suffix.to_string(), true);
         format!(
             "{}{}$${}",
             prefix,
@@ -740,7 +733,7 @@ fn i18n_generate_closure_var(
         )
     } else {
         let prefix = get_translation_const_prefix(file_based_i18n_suffix);
-        pool.unique_name(prefix, false)
+        pool.unique_name(prefix, true)
     };
     match *crate::output::output_ast::variable(name) {
         OutputExpression::ReadVar(expr) => expr,
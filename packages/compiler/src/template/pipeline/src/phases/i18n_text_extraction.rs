@@ -47,11 +47,11 @@ fn process_unit(
 ) {
     // Remove all text nodes within i18n blocks, their content is already captured in the i18n
     // message.
-    let mut current_i18n: Option<usize> = None;
-    let mut current_icu: Option<usize> = None;
-    let mut text_node_i18n_blocks: std::collections::HashMap<ir::XrefId, usize> =
+    let mut current_i18n: Option<I18nStartOp> = None;
+    let mut current_icu: Option<IcuStartOp> = None;
+    let mut text_node_i18n_blocks: std::collections::HashMap<ir::XrefId, I18nStartOp> =
         std::collections::HashMap::new();
-    let mut text_node_icus: std::collections::HashMap<ir::XrefId, Option<usize>> =
+    let mut text_node_icus: std::collections::HashMap<ir::XrefId, Option<IcuStartOp>> =
         std::collections::HashMap::new();
     let mut icu_placeholder_by_text: std::collections::HashMap<ir::XrefId, ir::XrefId> =
         std::collections::HashMap::new();
@@ -69,7 +69,7 @@ fn process_unit(
                 if i18n.base.context.is_none() {
                     panic!("I18n op should have its context set.");
                 }
-                current_i18n = Some(idx);
+                current_i18n = Some(i18n.clone());
             },
             OpKind::I18nEnd => {
                 current_i18n = None;
@@ -82,20 +82,20 @@ fn process_unit(
                 if icu.context.is_none() {
                     panic!("Icu op should have its context set.");
                 }
-                current_icu = Some(idx);
+                current_icu = Some(icu.clone());
             },
             OpKind::IcuEnd => {
                 current_icu = None;
             }
             OpKind::Text => {
-                if let Some(i18n_idx) = current_i18n {
+                if let Some(i18n) = &current_i18n {
                     unsafe {
                         let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
                         let text_ptr = op_ptr as *const TextOp;
                         let text = &*text_ptr;
 
-                        text_node_i18n_blocks.insert(text.xref, i18n_idx);
-                        text_node_icus.insert(text.xref, current_icu);
+                        text_node_i18n_blocks.insert(text.xref, i18n.clone());
+                        text_node_icus.insert(text.xref, current_icu.clone());
 
                         if let Some(ref icu_placeholder) = text.icu_placeholder {
                             // Create an op to represent the ICU placeholder. Initially set its static text to the
@@ -147,21 +147,10 @@ fn process_unit(
                 let interpolate_ptr = op_ptr as *const InterpolateTextOp;
                 let interpolate = &*interpolate_ptr;
 
-                if let Some(&i18n_idx) = text_node_i18n_blocks.get(&interpolate.target) {
-                    let i18n_op_ptr =
-                        unit.create.get(i18n_idx).unwrap().as_ref() as *const dyn ir::CreateOp;
-                    let i18n_ptr = i18n_op_ptr as *const I18nStartOp;
-                    let i18n_op = unsafe { &*i18n_ptr };
-
-                    let icu_idx = text_node_icus.get(&interpolate.target).copied().flatten();
-                    let icu_op = if let Some(icu_idx) = icu_idx {
-                        let icu_op_ptr =
-                            unit.create.get(icu_idx).unwrap().as_ref() as *const dyn ir::CreateOp;
-                        let icu_ptr = icu_op_ptr as *const IcuStartOp;
-                        Some(unsafe { &*icu_ptr })
-                    } else {
-                        None
-                    };
+                if let Some(i18n_op) = text_node_i18n_blocks.get(&interpolate.target) {
+                    let icu_op = text_node_icus
+                        .get(&interpolate.target)
+                        .and_then(Option::as_ref);
 
                     let context_id = if let Some(icu) = icu_op {
                         icu.context
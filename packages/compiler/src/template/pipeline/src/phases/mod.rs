@@ -76,11 +76,14 @@ use crate::template::pipeline::src::compilation::{CompilationJob, ComponentCompi
 
 pub fn run(job: &mut ComponentCompilationJob) {
     // Simplified phase order for vars debugging
+    propagate_i18n_blocks::propagate_i18n_blocks(job);
+    wrap_icus::wrap_i18n_icus(job);
     pure_literal_structures::phase(job);
     generate_variables::phase(job); // Generate context variables including $implicit
 
     save_restore_view::save_and_restore_view(job); // Save/restore view for listeners - MUST run AFTER generate_variables so RestoreView is prepended last (appears first)
     resolve_names::phase(job);
+    defer_resolve_targets::resolve_defer_target_names(job); // Needs local refs before they are lifted to consts
     transform_two_way_binding_set::transform_two_way_binding_set(job); // Transform TwoWayBindingSetExpr BEFORE resolve_contexts so ContextExpr in target is resolved
     resolve_contexts::phase(job);
 
@@ -97,12 +100,10 @@ pub fn run(job: &mut ComponentCompilationJob) {
     style_binding_specialization::specialize_style_bindings(job); // Specialize [style] and [class] bindings
     binding_specialization::specialize_bindings(job); // Converts BindingOp -> AttributeOp, PropertyOp, etc.
     attribute_extraction::extract_attributes(job);
+    create_i18n_contexts::create_i18n_contexts(job);
     local_refs::lift_local_refs(job); // Lift local refs (#templateName) to consts for templateRefExtractor
     namespace::emit_namespace_changes(job);
 
-    empty_elements::collapse_empty_instructions(job); // Merge ElementStart+ElementEnd -> Element for empty elements
-    const_collection::collect_element_consts(job);
-
     // Resolve sanitizers for security-sensitive properties/attributes (e.g. href, src)
     resolve_sanitizers::resolve_sanitizers(job);
 
@@ -111,12 +112,33 @@ pub fn run(job: &mut ComponentCompilationJob) {
 
     // Create pipe operations before slot allocation
     pipe_creation::create_pipes(job);
+    defer_configs::configure_defer_instructions(job);
 
     // Generate projection definitions (must run before slot allocation to reserve slots)
     generate_projection_def::generate_projection_defs(job);
     remove_content_selectors::remove_content_selectors(job);
 
+    // Convert i18n text and bindings into i18n expressions (must run after names and contexts are resolved)
+    i18n_text_extraction::convert_i18n_text(job);
+    convert_i18n_bindings::convert_i18n_bindings(job);
+    remove_unused_i18n_attrs::remove_unused_i18n_attributes_ops(job);
+    assign_i18n_slot_dependencies::assign_i18n_slot_dependencies(job);
+    apply_i18n_expressions::apply_i18n_expressions(job);
+
     slot_allocation::phase(job);
+
+    // Build i18n messages and collect their consts ahead of the element consts, as upstream does
+    resolve_i18n_element_placeholders::resolve_i18n_element_placeholders(job);
+    resolve_i18n_expression_placeholders::resolve_i18n_expression_placeholders(job);
+    extract_i18n_messages::extract_i18n_messages(job);
+    i18n_const_collection::collect_i18n_consts(job);
+    has_const_expression_collection::collect_const_expressions(job);
+    const_collection::collect_element_consts(job);
+    remove_i18n_contexts::remove_i18n_contexts(job);
+
+    empty_elements::collapse_empty_instructions(job); // Merge ElementStart+ElementEnd -> Element for empty elements (and I18nStart+I18nEnd -> I18n)
+    attach_source_locations::attach_source_locations(job); // Expose element locations (debug builds only)
+
     pure_function_extraction::phase(job); // Extract pure functions to constants like _c0, _c1
     track_fn_optimization::optimize_track_fns(job); // Generate track functions for @for loops
     var_counting::phase(job);
@@ -125,6 +147,7 @@ pub fn run(job: &mut ComponentCompilationJob) {
     next_context_merging::merge_next_context_expressions(job); // Merge sequential nextContext() calls
 
     naming::name_functions_and_variables(job);
+    resolve_defer_deps_fns::resolve_defer_deps_fns(job);
     generate_advance::phase(job);
     conditionals::generate_conditional_expressions(job); // Collapse conditional expressions to single ternary
     temporary_variables::generate_temporary_variables(job); // Name and declare temporary variables (must run after conditionals and expand_safe_reads)
@@ -225,6 +225,18 @@ fn reify_create_operations(
                             local_ref_index,
                             template_op.base.base.start_source_span.clone(),
                         )
+                    } else if template_op.template_kind == ir::TemplateKind::Block {
+                        // Block templates can't have directives so we can always generate them as DOM-only.
+                        ng::dom_template(
+                            slot,
+                            *o::variable(fn_name),
+                            decls,
+                            vars,
+                            template_op.base.tag.clone(),
+                            const_index,
+                            local_ref_index,
+                            template_op.base.base.start_source_span.clone(),
+                        )
                     } else {
                         let tag = template_op.base.tag.clone();
                         ng::template(
@@ -374,6 +386,59 @@ fn reify_create_operations(
                     None
                 }
             }
+            ir::OpKind::I18nStart => {
+                let i18n_op = op
+                    .as_any()
+                    .downcast_ref::<ir::ops::create::I18nStartOp>()
+                    .expect("I18nStart op should be an I18nStartOp");
+                let stmt = ng::i18n_start(
+                    i18n_slot(&i18n_op.base),
+                    i18n_message_index(&i18n_op.base),
+                    i18n_op.base.sub_template_index.map(|idx| idx as i32),
+                    i18n_op.source_span.clone(),
+                );
+                Some(Box::new(ir::ops::shared::create_statement_op::<
+                    Box<dyn CreateOp + Send + Sync>,
+                >(Box::new(stmt))))
+            }
+            ir::OpKind::I18n => {
+                let i18n_op = op
+                    .as_any()
+                    .downcast_ref::<ir::ops::create::I18nOp>()
+                    .expect("I18n op should be an I18nOp");
+                let stmt = ng::i18n(
+                    i18n_slot(&i18n_op.base),
+                    i18n_message_index(&i18n_op.base),
+                    i18n_op.base.sub_template_index.map(|idx| idx as i32),
+                    i18n_op.source_span.clone(),
+                );
+                Some(Box::new(ir::ops::shared::create_statement_op::<
+                    Box<dyn CreateOp + Send + Sync>,
+                >(Box::new(stmt))))
+            }
+            ir::OpKind::I18nEnd => {
+                let stmt = ng::i18n_end(op.source_span().cloned());
+                Some(Box::new(ir::ops::shared::create_statement_op::<
+                    Box<dyn CreateOp + Send + Sync>,
+                >(Box::new(stmt))))
+            }
+            ir::OpKind::I18nAttributes => {
+                let attrs_op = op
+                    .as_any()
+                    .downcast_ref::<ir::ops::create::I18nAttributesOp>()
+                    .expect("I18nAttributes op should be an I18nAttributesOp");
+                let config = attrs_op
+                    .i18n_attributes_config
+                    .expect("AssertionError: i18nAttributesConfig was not set");
+                let slot = attrs_op
+                    .handle
+                    .get_slot()
+                    .expect("AssertionError: expected i18nAttributes slot to be allocated");
+                let stmt = ng::i18n_attributes(slot as i32, config.as_usize() as i32);
+                Some(Box::new(ir::ops::shared::create_statement_op::<
+                    Box<dyn CreateOp + Send + Sync>,
+                >(Box::new(stmt))))
+            }
             ir::OpKind::Text => {
                 if let Some(text_op) = op.as_any().downcast_ref::<ir::ops::create::TextOp>() {
                     if let Some(slot) = text_op.handle.get_slot() {
@@ -509,6 +574,119 @@ fn reify_create_operations(
                 >(Box::new(stmt))))
             }
 
+            ir::OpKind::Defer => {
+                if let Some(defer_op) = op.as_any().downcast_ref::<ir::ops::create::DeferOp>() {
+                    let slot_of = |handle: &ir::handle::SlotHandle| {
+                        handle.get_slot().expect("Expected a slot") as i32
+                    };
+                    let timer_scheduling = defer_op.loading_minimum_time.is_some_and(|t| t != 0.0)
+                        || defer_op.loading_after_time.is_some_and(|t| t != 0.0)
+                        || defer_op.placeholder_minimum_time.is_some_and(|t| t != 0.0);
+                    let stmt = ng::defer(
+                        slot_of(&defer_op.handle),
+                        slot_of(&defer_op.main_slot),
+                        defer_op.resolver_fn.clone(),
+                        defer_op.loading_slot.as_ref().map(slot_of),
+                        defer_op.placeholder_slot.as_ref().map(slot_of),
+                        defer_op.error_slot.as_ref().map(slot_of),
+                        defer_op.loading_config.clone(),
+                        defer_op.placeholder_config.clone(),
+                        timer_scheduling,
+                        defer_op.source_span.clone(),
+                        defer_op.flags,
+                    );
+                    Some(Box::new(ir::ops::shared::create_statement_op::<
+                        Box<dyn CreateOp + Send + Sync>,
+                    >(Box::new(stmt))))
+                } else {
+                    None
+                }
+            }
+            ir::OpKind::DeferOn => {
+                if let Some(defer_on) = op.as_any().downcast_ref::<ir::ops::create::DeferOnOp>() {
+                    use ir::ops::create::DeferTrigger;
+
+                    let mut args: Vec<o::Expression> = Vec::new();
+                    match &defer_on.trigger {
+                        DeferTrigger::Idle | DeferTrigger::Immediate | DeferTrigger::Never => {}
+                        DeferTrigger::Timer { delay } => args.push(*o::literal(*delay)),
+                        DeferTrigger::Hover {
+                            target_slot,
+                            target_slot_view_steps,
+                            ..
+                        }
+                        | DeferTrigger::Interaction {
+                            target_slot,
+                            target_slot_view_steps,
+                            ..
+                        }
+                        | DeferTrigger::Viewport {
+                            target_slot,
+                            target_slot_view_steps,
+                            ..
+                        } => {
+                            // `hydrate` triggers don't support targets.
+                            if defer_on.modifier != ir::enums::DeferOpModifierKind::Hydrate {
+                                // Missing targets are reported during type checking, so pass null here.
+                                args.push(
+                                    match target_slot.as_ref().and_then(|slot| slot.get_slot()) {
+                                        Some(slot) => *o::literal(slot as f64),
+                                        None => *o::literal(o::LiteralValue::Null),
+                                    },
+                                );
+                                if let Some(steps) = target_slot_view_steps.filter(|s| *s != 0) {
+                                    args.push(*o::literal(steps as f64));
+                                }
+                            }
+                        }
+                    }
+                    let stmt = ng::defer_on(
+                        defer_on.trigger.kind(),
+                        args,
+                        defer_on.modifier,
+                        defer_on.source_span.clone(),
+                    );
+                    Some(Box::new(ir::ops::shared::create_statement_op::<
+                        Box<dyn CreateOp + Send + Sync>,
+                    >(Box::new(stmt))))
+                } else {
+                    None
+                }
+            }
+            ir::OpKind::SourceLocation => {
+                if let Some(loc_op) = op
+                    .as_any()
+                    .downcast_ref::<ir::ops::create::SourceLocationOp>()
+                {
+                    let locations = loc_op
+                        .locations
+                        .iter()
+                        .map(|loc| {
+                            let slot = loc
+                                .target_slot
+                                .get_slot()
+                                .expect("No slot was assigned to source location")
+                                as f64;
+                            o::Expression::LiteralArray(o::LiteralArrayExpr {
+                                entries: vec![
+                                    *o::literal(slot),
+                                    *o::literal(loc.offset as f64),
+                                    *o::literal(loc.line as f64),
+                                    *o::literal(loc.column as f64),
+                                ],
+                                type_: None,
+                                source_span: None,
+                            })
+                        })
+                        .collect();
+                    let stmt = ng::attach_source_locations(&loc_op.template_path, locations);
+                    Some(Box::new(ir::ops::shared::create_statement_op::<
+                        Box<dyn CreateOp + Send + Sync>,
+                    >(Box::new(stmt))))
+                } else {
+                    None
+                }
+            }
             ir::OpKind::DisableBindings => {
                 let stmt = ng::disable_bindings();
                 Some(Box::new(ir::ops::shared::create_statement_op::<
@@ -784,6 +962,18 @@ fn reify_create_operations(
     }
 }
 
+fn i18n_slot(base: &ir::ops::create::I18nOpBase) -> i32 {
+    base.handle
+        .get_slot()
+        .expect("AssertionError: expected i18n block slot to be allocated") as i32
+}
+
+fn i18n_message_index(base: &ir::ops::create::I18nOpBase) -> i32 {
+    base.message_index
+        .expect("AssertionError: expected i18n message index to be set")
+        .as_usize() as i32
+}
+
 fn reify_update_operations(unit: &mut dyn CompilationUnit) {
     for op in unit.update_mut().iter_mut() {
         ir::transform_expressions_in_op(
@@ -803,6 +993,33 @@ fn reify_update_operations(unit: &mut dyn CompilationUnit) {
                     None
                 }
             }
+            ir::OpKind::I18nExpression => {
+                let i18n_expr = op
+                    .as_any()
+                    .downcast_ref::<ir::ops::update::I18nExpressionOp>()
+                    .expect("I18nExpression op should be an I18nExpressionOp");
+                let stmt = ng::i18n_exp(
+                    i18n_expr.expression.clone(),
+                    Some(i18n_expr.source_span.clone()),
+                );
+                Some(Box::new(ir::ops::shared::create_statement_op::<
+                    Box<dyn UpdateOp + Send + Sync>,
+                >(Box::new(stmt))))
+            }
+            ir::OpKind::I18nApply => {
+                let apply = op
+                    .as_any()
+                    .downcast_ref::<ir::ops::update::I18nApplyOp>()
+                    .expect("I18nApply op should be an I18nApplyOp");
+                let slot = apply
+                    .handle
+                    .get_slot()
+                    .expect("AssertionError: expected i18nApply slot to be allocated");
+                let stmt = ng::i18n_apply(slot as i32, Some(apply.source_span.clone()));
+                Some(Box::new(ir::ops::shared::create_statement_op::<
+                    Box<dyn UpdateOp + Send + Sync>,
+                >(Box::new(stmt))))
+            }
             ir::OpKind::Property => {
                 if let Some(prop) = op.as_any().downcast_ref::<ir::ops::update::PropertyOp>() {
                     if let ir::ops::update::BindingExpression::Expression(expr) = &prop.expression {
@@ -930,6 +1147,21 @@ fn reify_update_operations(unit: &mut dyn CompilationUnit) {
                     None
                 }
             }
+            ir::OpKind::DeferWhen => {
+                if let Some(defer_when) = op.as_any().downcast_ref::<ir::ops::update::DeferWhenOp>()
+                {
+                    let stmt = ng::defer_when(
+                        defer_when.modifier,
+                        defer_when.expr.clone(),
+                        defer_when.source_span.clone(),
+                    );
+                    Some(Box::new(ir::ops::shared::create_statement_op::<
+                        Box<dyn UpdateOp + Send + Sync>,
+                    >(Box::new(stmt))))
+                } else {
+                    None
+                }
+            }
             ir::OpKind::Attribute => {
                 if let Some(attr) = op.as_any().downcast_ref::<ir::ops::update::AttributeOp>() {
                     let expression = match &attr.expression {
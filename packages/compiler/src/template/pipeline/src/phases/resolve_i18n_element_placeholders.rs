@@ -82,6 +82,31 @@ pub fn resolve_i18n_element_placeholders(job: &mut dyn CompilationJob) {
         &elements,
         None,
     );
+
+    store_i18n_contexts(component_job, &i18n_contexts);
+}
+
+/// Copy the resolved params of the recorded contexts back onto the context ops in the job.
+fn store_i18n_contexts(
+    job: &mut ComponentCompilationJob,
+    i18n_contexts: &std::collections::HashMap<ir::XrefId, I18nContextOp>,
+) {
+    let units = std::iter::once(&mut job.root).chain(job.views.values_mut());
+    for unit in units {
+        for op in unit.create.iter_mut() {
+            if op.kind() != OpKind::I18nContext {
+                continue;
+            }
+            unsafe {
+                let op_ptr = op.as_mut() as *mut dyn ir::CreateOp;
+                let ctx = &mut *(op_ptr as *mut I18nContextOp);
+                if let Some(resolved) = i18n_contexts.get(&ctx.xref) {
+                    ctx.params = resolved.params.clone();
+                    ctx.postprocessing_params = resolved.postprocessing_params.clone();
+                }
+            }
+        }
+    }
 }
 
 /// Helper function to handle template kind ops (ConditionalCreate, ConditionalBranchCreate, Template)
@@ -91,11 +116,10 @@ fn handle_template_kind_op(
     op: &Box<dyn ir::CreateOp + Send + Sync>,
     op_kind: OpKind,
     op_xref: ir::XrefId,
-    idx: usize,
     current_ops: &mut Option<(usize, usize)>,
     i18n_contexts: &mut std::collections::HashMap<ir::XrefId, I18nContextOp>,
     elements: &std::collections::HashMap<ir::XrefId, ElementStartOp>,
-    pending_structural_directive: Option<usize>,
+    pending_structural_directive: Option<*const dyn std::any::Any>,
 ) {
     unsafe {
         let op_ptr = op.as_ref() as *const dyn ir::CreateOp;
@@ -172,7 +196,7 @@ fn handle_template_kind_op(
                             view,
                             i18n_contexts,
                             elements,
-                            Some(idx),
+                            Some(op.as_any() as *const dyn std::any::Any),
                         );
                     } else {
                         // If this is some other kind of template, we can record its start, recurse into its
@@ -246,11 +270,7 @@ fn handle_template_kind_op(
                                     &tag_ph,
                                     i18n_context_mut,
                                     i18n_block_typed,
-                                    pending_structural_directive.and_then(|pd_idx| {
-                                        unit.create
-                                            .get(pd_idx)
-                                            .map(|pd_op| pd_op.as_any() as *const dyn std::any::Any)
-                                    }),
+                                    pending_structural_directive,
                                 );
                             }
 
@@ -267,11 +287,7 @@ fn handle_template_kind_op(
                                     &tag_ph,
                                     i18n_context_mut,
                                     i18n_block_typed,
-                                    pending_structural_directive.and_then(|pd_idx| {
-                                        unit.create
-                                            .get(pd_idx)
-                                            .map(|pd_op| pd_op.as_any() as *const dyn std::any::Any)
-                                    }),
+                                    pending_structural_directive,
                                 );
                             }
                         }
@@ -290,12 +306,14 @@ fn resolve_placeholders_for_view(
     unit: &mut ViewCompilationUnit,
     i18n_contexts: &mut std::collections::HashMap<ir::XrefId, I18nContextOp>,
     elements: &std::collections::HashMap<ir::XrefId, ElementStartOp>,
-    pending_structural_directive: Option<usize>, // Index of pending structural directive op
+    pending_structural_directive: Option<*const dyn std::any::Any>,
 ) {
     // Track the current i18n op and corresponding i18n context op as we step through the creation IR.
     let mut current_ops: Option<(usize, usize)> = None; // (i18n_block_idx, i18n_context_idx)
-    let mut pending_structural_directive_closes: std::collections::HashMap<ir::XrefId, usize> =
-        std::collections::HashMap::new();
+    let mut pending_structural_directive_closes: std::collections::HashMap<
+        ir::XrefId,
+        *const dyn std::any::Any,
+    > = std::collections::HashMap::new();
 
     // Collect ops to process to avoid borrow conflicts
     let mut ops_to_process: Vec<(usize, OpKind, ir::XrefId)> = Vec::new();
@@ -354,11 +372,7 @@ fn resolve_placeholders_for_view(
                                     elem,
                                     i18n_context_mut,
                                     i18n_block_typed,
-                                    pending_structural_directive.and_then(|pd_idx| {
-                                        unit.create
-                                            .get(pd_idx)
-                                            .map(|pd_op| pd_op.as_any() as *const dyn std::any::Any)
-                                    }),
+                                    pending_structural_directive,
                                 );
 
                                 // If there is a separate close tag placeholder for this element, save the pending
@@ -399,11 +413,7 @@ fn resolve_placeholders_for_view(
 
                                     let pending_close = pending_structural_directive_closes
                                         .get(&elem_end_xref)
-                                        .and_then(|pd_idx| {
-                                            unit.create.get(*pd_idx).map(|pd_op| {
-                                                pd_op.as_any() as *const dyn std::any::Any
-                                            })
-                                        });
+                                        .copied();
 
                                     record_element_close(
                                         start_op,
@@ -442,22 +452,14 @@ fn resolve_placeholders_for_view(
                                     proj,
                                     i18n_context_mut,
                                     i18n_block_typed,
-                                    pending_structural_directive.and_then(|pd_idx| {
-                                        unit.create
-                                            .get(pd_idx)
-                                            .map(|pd_op| pd_op.as_any() as *const dyn std::any::Any)
-                                    }),
+                                    pending_structural_directive,
                                 );
 
                                 record_element_close(
                                     proj,
                                     i18n_context_mut,
                                     i18n_block_typed,
-                                    pending_structural_directive.and_then(|pd_idx| {
-                                        unit.create
-                                            .get(pd_idx)
-                                            .map(|pd_op| pd_op.as_any() as *const dyn std::any::Any)
-                                    }),
+                                    pending_structural_directive,
                                 );
                             }
                         } else {
@@ -503,11 +505,7 @@ fn resolve_placeholders_for_view(
                                                 ph,
                                                 i18n_context_mut,
                                                 i18n_block_typed,
-                                                pending_structural_directive.and_then(|pd_idx| {
-                                                    unit.create.get(pd_idx).map(|pd_op| {
-                                                        pd_op.as_any() as *const dyn std::any::Any
-                                                    })
-                                                }),
+                                                pending_structural_directive,
                                             );
                                         }
 
@@ -530,11 +528,7 @@ fn resolve_placeholders_for_view(
                                                 ph,
                                                 i18n_context_mut,
                                                 i18n_block_typed,
-                                                pending_structural_directive.and_then(|pd_idx| {
-                                                    unit.create.get(pd_idx).map(|pd_op| {
-                                                        pd_op.as_any() as *const dyn std::any::Any
-                                                    })
-                                                }),
+                                                pending_structural_directive,
                                             );
                                         }
                                     } else {
@@ -548,17 +542,16 @@ fn resolve_placeholders_for_view(
             }
             OpKind::ConditionalCreate | OpKind::ConditionalBranchCreate | OpKind::Template => {
                 // Get op reference and convert to raw pointer to avoid borrow conflicts
-                let op_ref = unit.create.get(idx).unwrap();
-                let op_ptr = op_ref.as_ref() as *const dyn ir::CreateOp;
+                let op_ptr =
+                    unit.create.get(idx).unwrap() as *const Box<dyn ir::CreateOp + Send + Sync>;
                 // Now we can borrow unit mutably
-                let op = unsafe { &*(op_ptr as *const Box<dyn ir::CreateOp + Send + Sync>) };
+                let op = unsafe { &*op_ptr };
                 handle_template_kind_op(
                     job,
                     unit,
                     op,
                     op_kind,
                     op_xref,
-                    idx,
                     &mut current_ops,
                     i18n_contexts,
                     elements,
@@ -159,6 +159,31 @@ pub fn resolve_i18n_expression_placeholders(job: &mut dyn CompilationJob) {
             update_placeholder(&expr_op, value, &mut i18n_contexts, unit);
         }
     }
+
+    store_i18n_contexts(component_job, &i18n_contexts);
+}
+
+/// Copy the resolved params of the recorded contexts back onto the context ops in the job.
+fn store_i18n_contexts(
+    job: &mut ComponentCompilationJob,
+    i18n_contexts: &std::collections::HashMap<ir::XrefId, I18nContextOp>,
+) {
+    let units = std::iter::once(&mut job.root).chain(job.views.values_mut());
+    for unit in units {
+        for op in unit.create.iter_mut() {
+            if op.kind() != OpKind::I18nContext {
+                continue;
+            }
+            unsafe {
+                let op_ptr = op.as_mut() as *mut dyn ir::CreateOp;
+                let ctx = &mut *(op_ptr as *mut I18nContextOp);
+                if let Some(resolved) = i18n_contexts.get(&ctx.xref) {
+                    ctx.params = resolved.params.clone();
+                    ctx.postprocessing_params = resolved.postprocessing_params.clone();
+                }
+            }
+        }
+    }
 }
 
 fn update_placeholder(
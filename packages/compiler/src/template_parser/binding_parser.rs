@@ -424,9 +424,12 @@ impl<'a> BindingParser<'a> {
         source_span: &ParseSourceSpan,
     ) -> ASTWithSource {
         let absolute_offset = source_span.start.offset;
-        match self.expr_parser.parse_binding(expression, absolute_offset) {
+        match self
+            .expr_parser
+            .parse_interpolation_expression(expression, absolute_offset)
+        {
             Ok(ast) => ASTWithSource::new(
-                Box::new(ast),
+                Box::new(ExprAST::Interpolation(ast)),
                 Some(expression.to_string()),
                 source_span.start.to_string(),
                 absolute_offset,
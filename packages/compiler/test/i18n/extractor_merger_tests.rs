@@ -0,0 +1,158 @@
+//! Extractor Merger Tests
+//!
+//! Mirrors the merge tests of angular/packages/compiler/test/i18n/extractor_merger_spec.ts
+
+#[path = "../ml_parser/util/mod.rs"]
+mod utils;
+
+#[cfg(test)]
+mod tests {
+    use super::utils::serializer::serialize_nodes as serialize_html_nodes;
+    use angular_compiler::core::MissingTranslationStrategy;
+    use angular_compiler::i18n::digest::{digest, serialize_nodes};
+    use angular_compiler::i18n::i18n_ast::{Node, Text};
+    use angular_compiler::i18n::serializers::Json;
+    use angular_compiler::i18n::{
+        extract_messages, merge_translations, I18NHtmlParser, TranslationBundle,
+    };
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use angular_compiler::ml_parser::lexer::TokenizeOptions;
+    use angular_compiler::parse_util::ParseErrorLevel;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    fn tokenize_options() -> TokenizeOptions {
+        TokenizeOptions {
+            tokenize_expansion_forms: true,
+            ..TokenizeOptions::default()
+        }
+    }
+
+    /// Translate each message of `content` to its serialized nodes surrounded by `**`.
+    fn fake_translate(content: &str) -> String {
+        let html_nodes = HtmlParser::new()
+            .parse(content, "url", Some(tokenize_options()))
+            .root_nodes;
+        let messages = extract_messages(&html_nodes, &[], &HashMap::new(), true).messages;
+        let i18n_msg_map = messages
+            .iter()
+            .map(|message| {
+                let text = serialize_nodes(&message.nodes).join("").replace('<', "[");
+                let span = message.nodes[0].source_span().clone();
+                (
+                    digest(message),
+                    vec![Node::Text(Text::new(format!("**{}**", text), span))],
+                )
+            })
+            .collect();
+        let mut translation_bundle = TranslationBundle::new(
+            i18n_msg_map,
+            None,
+            Rc::new(digest),
+            None,
+            MissingTranslationStrategy::Warning,
+        );
+        let output = merge_translations(&html_nodes, &mut translation_bundle, &[], &HashMap::new());
+        assert!(output.errors.is_empty(), "{:?}", output.errors);
+        serialize_html_nodes(&output.root_nodes).join("")
+    }
+
+    fn translate(content: &str, translations: &str, strategy: MissingTranslationStrategy) -> (String, Vec<String>) {
+        let bundle = TranslationBundle::load(translations, "messages.fr.json", Json::new(), strategy)
+            .expect("the translations should load");
+        let mut parser = I18NHtmlParser::with_translation_bundle(HtmlParser::new(), bundle);
+        let output = parser.parse(content, "url", tokenize_options());
+        let errors = output
+            .errors
+            .iter()
+            .map(|e| {
+                let level = match e.level {
+                    ParseErrorLevel::Warning => "warning",
+                    ParseErrorLevel::Error => "error",
+                };
+                format!("{}: {}", level, e.msg)
+            })
+            .collect();
+        (serialize_html_nodes(&output.root_nodes).join(""), errors)
+    }
+
+    #[test]
+    fn should_merge_elements() {
+        assert_eq!(fake_translate(r#"<p i18n="m|d">foo</p>"#), "<p>**foo**</p>");
+    }
+
+    #[test]
+    fn should_merge_nested_elements() {
+        assert_eq!(
+            fake_translate(r#"<div>before<p i18n="m|d">foo</p><!-- comment --></div>"#),
+            "<div>before<p>**foo**</p></div>"
+        );
+    }
+
+    #[test]
+    fn should_merge_blocks() {
+        assert_eq!(
+            fake_translate("before<!-- i18n: m|d -->foo<!-- /i18n -->after"),
+            "before**foo**after"
+        );
+        assert_eq!(
+            fake_translate("before<!-- i18n: m|d --><p>foo</p><!-- /i18n -->after"),
+            "before**[ph tag name=\"START_PARAGRAPH\">foo[/ph name=\"CLOSE_PARAGRAPH\">**after"
+        );
+    }
+
+    #[test]
+    fn should_merge_attributes() {
+        assert_eq!(
+            fake_translate(r#"<p i18n-title="m|d" title="foo"></p>"#),
+            r#"<p title="**foo**"></p>"#
+        );
+        assert_eq!(
+            fake_translate(r#"<p i18n="m|d" i18n-title="m|d" title="foo">bar</p>"#),
+            r#"<p title="**foo**">**bar**</p>"#
+        );
+    }
+
+    #[test]
+    fn should_keep_control_flow_blocks_and_let_declarations() {
+        assert_eq!(
+            fake_translate(r#"@if (cond) {<p i18n>foo</p>}@let x = 1;<b>bar</b>"#),
+            "@if (cond) {<p>**foo**</p>}@let x = 1;<b>bar</b>"
+        );
+    }
+
+    #[test]
+    fn should_reorder_placeholders() {
+        let (html, errors) = translate(
+            "<p i18n=\"@@greeting\">Hello <b>{{ name }}</b>!</p>",
+            r#"{"locale": "fr", "translations": {"greeting": "{$START_BOLD_TEXT}{$INTERPOLATION}{$CLOSE_BOLD_TEXT}, bonjour !"}}"#,
+            MissingTranslationStrategy::Error,
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(html, "<p><b>{{ name }}</b>, bonjour !</p>");
+    }
+
+    #[test]
+    fn should_handle_missing_translations() {
+        let translations = r#"{"locale": "fr", "translations": {}}"#;
+        let content = "<p i18n=\"@@greeting\">Hello</p><p>World</p>";
+
+        let (html, errors) = translate(content, translations, MissingTranslationStrategy::Error);
+        assert_eq!(
+            errors,
+            vec![r#"error: Missing translation for message "greeting" for locale "fr""#]
+        );
+        assert_eq!(html, "<p>Hello</p><p>World</p>");
+
+        let (html, errors) = translate(content, translations, MissingTranslationStrategy::Warning);
+        assert_eq!(
+            errors,
+            vec![r#"warning: Missing translation for message "greeting" for locale "fr""#]
+        );
+        assert_eq!(html, "<p>Hello</p><p>World</p>");
+
+        let (html, errors) = translate(content, translations, MissingTranslationStrategy::Ignore);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(html, "<p>Hello</p><p>World</p>");
+    }
+}
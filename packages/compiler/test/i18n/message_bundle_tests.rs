@@ -0,0 +1,104 @@
+//! Message Bundle Tests
+//!
+//! Mirrors angular/packages/compiler/test/i18n/message_bundle_spec.ts
+
+#[cfg(test)]
+mod tests {
+    use angular_compiler::i18n::message_bundle::MessageBundle;
+    use angular_compiler::i18n::serializers::{Xliff, Xliff2, Xmb};
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use std::collections::HashMap;
+
+    fn bundle(templates: &[(&str, &str)]) -> MessageBundle {
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        for (html, url) in templates {
+            let errors = bundle.update_from_template(html, url);
+            assert!(errors.is_empty(), "unexpected errors in {}", url);
+        }
+        bundle
+    }
+
+    #[test]
+    fn should_extract_the_message_to_the_catalog() {
+        let bundle = bundle(&[(
+            r#"<p i18n="m|d">Translate Me</p><div i18n-title="t|desc@@titleId" title="Title"></div>"#,
+            "url",
+        )]);
+        let messages: Vec<_> = bundle
+            .get_messages()
+            .iter()
+            .map(|m| {
+                (
+                    m.message_string.as_str(),
+                    m.meaning.as_str(),
+                    m.description.as_str(),
+                    m.custom_id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("Translate Me", "m", "d", ""),
+                ("Title", "t", "desc", "titleId"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_the_errors_of_a_template() {
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        let errors = bundle.update_from_template("<div i18n><p i18n>Nested</p></div>", "url");
+        assert!(!errors.is_empty());
+        assert!(bundle.get_messages().is_empty());
+    }
+
+    #[test]
+    fn should_extract_and_dedup_messages() {
+        let bundle = bundle(&[
+            (
+                r#"<p i18n="m|d@@1">Translate Me</p><p i18n="@@2">Translate Me</p><p i18n="@@2">Translate Me</p>"#,
+                "url",
+            ),
+            (r#"<p i18n="@@1">Translate Me</p>"#, "other"),
+        ]);
+        assert_eq!(bundle.get_messages().len(), 4);
+
+        let xliff = bundle.write(&Xliff::new(), None);
+        assert_eq!(xliff.matches("<trans-unit ").count(), 2);
+        assert_eq!(
+            xliff
+                .matches(r#"<context-group purpose="location">"#)
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn should_merge_added_messages() {
+        let other = bundle(&[("<p i18n>Hello</p>", "other.html")]);
+        let mut bundle = bundle(&[("<p i18n>Hello</p>", "url")]);
+        bundle.add_messages(other.get_messages().to_vec());
+        assert_eq!(bundle.get_messages().len(), 2);
+
+        let xliff = bundle.write(&Xliff::new(), None);
+        assert_eq!(xliff.matches("<trans-unit ").count(), 1);
+        assert!(xliff.contains(r#"<context context-type="sourcefile">other.html</context>"#));
+    }
+
+    #[test]
+    fn should_write_sources_through_the_filter() {
+        let bundle = bundle(&[("<p i18n>Hello</p>\n<p i18n>World</p>", "/src/app.html")]);
+        let filter = |path: &str| path.trim_start_matches("/src/").to_string();
+        let xliff = bundle.write(&Xliff::new(), Some(&filter));
+        assert!(xliff.starts_with(r#"<?xml version="1.0" encoding="UTF-8" ?>"#));
+        assert!(xliff.contains(r#"<context context-type="sourcefile">app.html</context>"#));
+        assert!(xliff.contains(r#"<context context-type="linenumber">2</context>"#));
+
+        let xliff2 = bundle.write(&Xliff2::new(), Some(&filter));
+        assert!(xliff2.contains(r#"<note category="location">app.html:2</note>"#));
+
+        let xmb = bundle.write(&Xmb::new(), Some(&filter));
+        assert!(xmb.contains("<source>app.html:2</source>"));
+    }
+}
@@ -0,0 +1,126 @@
+//! ARB Serializer Tests
+//!
+//! Mirrors angular/packages/localize/tools/test/extract/translation_files/arb_translation_serializer_spec.ts
+//! and angular/packages/localize/tools/test/translate/translation_files/translation_parsers/arb_translation_parser_spec.ts
+
+#[cfg(test)]
+mod tests {
+    use angular_compiler::i18n::digest::serialize_nodes;
+    use angular_compiler::i18n::message_bundle::MessageBundle;
+    use angular_compiler::i18n::serializers::{Arb, Serializer};
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn write(html: &str, locale: Option<String>) -> String {
+        let mut bundle =
+            MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), locale, true);
+        let errors = bundle.update_from_template(html, "src/app.html");
+        assert!(errors.is_empty());
+        bundle.write(&Arb::new(), None)
+    }
+
+    fn load_as_map(arb: &str) -> (Option<String>, BTreeMap<String, String>) {
+        let result = Arb::new().load(arb, "url").unwrap();
+        let messages = result
+            .i18n_nodes_by_msg_id
+            .iter()
+            .map(|(id, nodes)| (id.clone(), serialize_nodes(nodes).join("")))
+            .collect();
+        (result.locale, messages)
+    }
+
+    #[test]
+    fn should_write_the_messages_with_their_metadata() {
+        assert_eq!(
+            write(
+                "<p i18n=\"meaning|some description@@hello\">Hello {{ name }}</p>\n<p i18n>Bye</p>",
+                Some("en-US".to_string())
+            ),
+            r#"{
+  "@@locale": "en-US",
+  "hello": "Hello {$INTERPOLATION}",
+  "@hello": {
+    "description": "some description",
+    "x-meaning": "meaning",
+    "x-locations": [
+      {
+        "file": "src/app.html",
+        "start": { "line": 0, "column": 42 },
+        "end": { "line": 0, "column": 42 }
+      }
+    ]
+  },
+  "6753586455780720856": "Bye",
+  "@6753586455780720856": {
+    "x-locations": [
+      {
+        "file": "src/app.html",
+        "start": { "line": 1, "column": 8 },
+        "end": { "line": 1, "column": 8 }
+      }
+    ]
+  }
+}"#
+        );
+    }
+
+    #[test]
+    fn should_write_an_empty_file() {
+        assert_eq!(write("", None), "{\n  \"@@locale\": \"en\"\n}");
+    }
+
+    #[test]
+    fn should_load_arb_files_and_skip_the_metadata() {
+        let (locale, messages) = load_as_map(
+            r#"{
+  "@@locale": "fr",
+  "hello": "Bonjour {$INTERPOLATION}",
+  "@hello": {
+    "description": "some description",
+    "x-locations": [{ "file": "src/app.html", "start": { "line": 0, "column": 42 }, "end": { "line": 0, "column": 42 } }]
+  },
+  "6753586455780720856": "Au revoir"
+}"#,
+        );
+        assert_eq!(locale.as_deref(), Some("fr"));
+        let expected: BTreeMap<String, String> = [
+            ("hello", r#"Bonjour <ph name="INTERPOLATION"/>"#),
+            ("6753586455780720856", "Au revoir"),
+        ]
+        .into_iter()
+        .map(|(id, message)| (id.to_string(), message.to_string()))
+        .collect();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn should_load_the_messages_it_writes() {
+        let arb = write(
+            "<p i18n=\"@@icu\">{ count, plural, =0 {<b>none</b>} other {many}}</p>",
+            None,
+        );
+        let (_, messages) = load_as_map(&arb);
+        assert_eq!(
+            messages["icu"],
+            r#"{VAR_PLURAL, plural, =0 {[<ph name="START_BOLD_TEXT"/>, none, <ph name="CLOSE_BOLD_TEXT"/>]}, other {[many]}}"#
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_translations() {
+        let error = match Arb::new().load(
+            "{\n  \"@@locale\": \"fr\",\n  \"hello\": [\"Bonjour\"]\n}",
+            "url",
+        ) {
+            Ok(_) => panic!("expected the ARB file to fail to load"),
+            Err(e) => e,
+        };
+        assert!(error.starts_with("arb parse errors:\n"), "{}", error);
+        assert!(
+            error.contains("The translation of msg hello must be a string"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@2:2"), "{}", error);
+    }
+}
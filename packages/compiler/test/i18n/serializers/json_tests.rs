@@ -0,0 +1,151 @@
+//! JSON Serializer Tests
+//!
+//! Mirrors angular/packages/localize/tools/test/extract/translation_files/json_translation_serializer_spec.ts
+//! and angular/packages/localize/tools/test/translate/translation_files/translation_parsers/simple_json_translation_parser_spec.ts
+
+#[cfg(test)]
+mod tests {
+    use angular_compiler::i18n::digest::serialize_nodes;
+    use angular_compiler::i18n::message_bundle::MessageBundle;
+    use angular_compiler::i18n::serializers::{Json, Serializer};
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use std::collections::{BTreeMap, HashMap};
+
+    const HTML: &str = r#"
+<p i18n-title title="translatable attribute">not translatable</p>
+<p i18n>translatable element <b>with placeholders</b> {{ interpolation}}</p>
+<p i18n="m|d@@i">foo "quoted"</p>
+<p i18n="@@icu">{ count, plural, =0 {<p>test</p>} other {{{ count }} items}}</p>
+<p i18n="@@br">Line<br/>break</p>
+"#;
+
+    fn write(html: &str, locale: Option<String>) -> String {
+        let mut bundle =
+            MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), locale, true);
+        let errors = bundle.update_from_template(html, "file.ts");
+        assert!(errors.is_empty());
+        bundle.write(&Json::new(), None)
+    }
+
+    fn load_as_map(json: &str) -> (Option<String>, BTreeMap<String, String>) {
+        let result = Json::new().load(json, "url").unwrap();
+        let messages = result
+            .i18n_nodes_by_msg_id
+            .iter()
+            .map(|(id, nodes)| (id.clone(), serialize_nodes(nodes).join("")))
+            .collect();
+        (result.locale, messages)
+    }
+
+    fn load_error(json: &str) -> String {
+        match Json::new().load(json, "url") {
+            Ok(_) => panic!("expected the JSON file to fail to load"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn should_write_the_messages_by_their_localize_id() {
+        assert_eq!(
+            write(HTML, Some("fr".to_string())),
+            r#"{
+  "locale": "fr",
+  "translations": {
+    "1933478729560469763": "translatable attribute",
+    "7056919470098446707": "translatable element {$START_BOLD_TEXT}with placeholders{$CLOSE_BOLD_TEXT} {$INTERPOLATION}",
+    "i": "foo \"quoted\"",
+    "icu": "{VAR_PLURAL, plural, =0 {{$START_PARAGRAPH}test{$CLOSE_PARAGRAPH}} other {{$INTERPOLATION} items}}",
+    "br": "Line{$LINE_BREAK}break"
+  }
+}"#
+        );
+    }
+
+    #[test]
+    fn should_write_an_empty_file() {
+        assert_eq!(
+            write("", None),
+            "{\n  \"locale\": \"en\",\n  \"translations\": {}\n}"
+        );
+    }
+
+    #[test]
+    fn should_load_json_files() {
+        let (locale, messages) = load_as_map(
+            r#"{
+  "locale": "fr",
+  "translations": {
+    "1933478729560469763": "etubirtta elbatalsnart",
+    "7056919470098446707": "{$INTERPOLATION} {$START_BOLD_TEXT}sredlohecalp htiw{$CLOSE_BOLD_TEXT} tnemele elbatalsnart",
+    "icu": "{VAR_PLURAL, plural, =0 {{$START_PARAGRAPH}TEST{$CLOSE_PARAGRAPH}} other {{INTERPOLATION} stuff}}",
+    "braces": "{ not a placeholder } {$}"
+  }
+}"#,
+        );
+        assert_eq!(locale.as_deref(), Some("fr"));
+        let expected: BTreeMap<String, String> = [
+            ("1933478729560469763", "etubirtta elbatalsnart"),
+            ("7056919470098446707", r#"<ph name="INTERPOLATION"/> <ph name="START_BOLD_TEXT"/>sredlohecalp htiw<ph name="CLOSE_BOLD_TEXT"/> tnemele elbatalsnart"#),
+            ("icu", r#"{VAR_PLURAL, plural, =0 {[<ph name="START_PARAGRAPH"/>, TEST, <ph name="CLOSE_PARAGRAPH"/>]}, other {[<ph name="INTERPOLATION"/>,  stuff]}}"#),
+            ("braces", "{ not a placeholder } {$}"),
+        ]
+        .into_iter()
+        .map(|(id, message)| (id.to_string(), message.to_string()))
+        .collect();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn should_load_the_messages_it_writes() {
+        let (locale, messages) = load_as_map(&write(HTML, None));
+        assert_eq!(locale.as_deref(), Some("en"));
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages["br"], r#"Line<ph name="LINE_BREAK"/>break"#);
+        assert_eq!(messages["i"], r#"foo "quoted""#);
+    }
+
+    #[test]
+    fn should_report_invalid_json_with_its_line() {
+        let error = load_error(
+            "{\n  \"locale\": \"fr\",\n  \"translations\": {\n    \"a\": \"b\",\n  }\n}",
+        );
+        assert!(error.starts_with("json parse errors:\n"), "{}", error);
+        assert!(error.contains("Invalid JSON"), "{}", error);
+        assert!(error.contains("url@4:2"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_missing_translations() {
+        let error = load_error(r#"{"locale": "fr"}"#);
+        assert!(
+            error.contains(r#"Missing "translations" object"#),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_translations() {
+        let error = load_error(
+            r#"{
+  "locale": "fr",
+  "translations": {
+    "number": 1,
+    "icu": "{VAR_PLURAL, plural, =0 {zero}"
+  }
+}"#,
+        );
+        assert!(
+            error.contains("The translation of msg number must be a string"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@3:4"), "{}", error);
+        assert!(
+            error.contains("Unterminated ICU message in the translation of msg icu"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@4:4"), "{}", error);
+    }
+}
@@ -0,0 +1,237 @@
+//! XLIFF 2.0 Serializer Tests
+//!
+//! Mirrors angular/packages/compiler/test/i18n/serializers/xliff2_spec.ts
+
+#[cfg(test)]
+mod tests {
+    use angular_compiler::i18n::digest::serialize_nodes;
+    use angular_compiler::i18n::message_bundle::MessageBundle;
+    use angular_compiler::i18n::serializers::{Serializer, Xliff2};
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use std::collections::{BTreeMap, HashMap};
+
+    const HTML: &str = r#"
+<p i18n-title title="translatable attribute">not translatable</p>
+<p i18n>translatable element <b>with placeholders</b> {{ interpolation}}</p>
+<p i18n="m|d@@i">foo</p>
+<p i18n="@@icu">{ count, plural, =0 {<p>test</p>}}</p>
+<p i18n="@@br">Line<br/>break</p>
+"#;
+
+    const LOAD_XLIFF: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en" trgLang="fr">
+  <file original="ng.template" id="ngi18n">
+    <unit id="attr">
+      <segment>
+        <source>translatable attribute</source>
+        <target>etubirtta elbatalsnart</target>
+      </segment>
+    </unit>
+    <unit id="element">
+      <segment>
+        <source>translatable element <pc id="0" equivStart="START_BOLD_TEXT" equivEnd="CLOSE_BOLD_TEXT" type="fmt" dispStart="&lt;b&gt;" dispEnd="&lt;/b&gt;">with placeholders</pc> <ph id="1" equiv="INTERPOLATION" disp="{{ interpolation}}"/></source>
+        <target><ph id="1" equiv="INTERPOLATION" disp="{{ interpolation}}"/> <pc id="0" equivStart="START_BOLD_TEXT" equivEnd="CLOSE_BOLD_TEXT" type="fmt" dispStart="&lt;b&gt;" dispEnd="&lt;/b&gt;">sredlohecalp htiw</pc> tnemele elbatalsnart</target>
+      </segment>
+    </unit>
+    <unit id="icu">
+      <segment>
+        <source>{VAR_PLURAL, plural, =0 {<pc id="0" equivStart="START_PARAGRAPH" equivEnd="CLOSE_PARAGRAPH" type="other" dispStart="&lt;p&gt;" dispEnd="&lt;/p&gt;">test</pc>} }</source>
+        <target>{VAR_PLURAL, plural, =0 {<pc id="0" equivStart="START_PARAGRAPH" equivEnd="CLOSE_PARAGRAPH" type="other" dispStart="&lt;p&gt;" dispEnd="&lt;/p&gt;">TEST</pc>} }</target>
+      </segment>
+    </unit>
+    <unit id="br">
+      <segment>
+        <source>Line<ph id="0" equiv="LINE_BREAK" type="fmt" disp="&lt;br/&gt;"/>break</source>
+        <target>Ligne<ph id="0" equiv="LINE_BREAK" type="fmt" disp="&lt;br/&gt;"/><mrk id="1" translate="no">coupée</mrk></target>
+      </segment>
+    </unit>
+  </file>
+</xliff>
+"#;
+
+    fn write(html: &str) -> String {
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        let errors = bundle.update_from_template(html, "file.ts");
+        assert!(errors.is_empty());
+        bundle.write(&Xliff2::new(), None)
+    }
+
+    fn load_as_map(xliff: &str) -> (Option<String>, BTreeMap<String, String>) {
+        let result = Xliff2::new().load(xliff, "url").unwrap();
+        let messages = result
+            .i18n_nodes_by_msg_id
+            .iter()
+            .map(|(id, nodes)| (id.clone(), serialize_nodes(nodes).join("")))
+            .collect();
+        (result.locale, messages)
+    }
+
+    fn load_error(xliff: &str) -> String {
+        match Xliff2::new().load(xliff, "url") {
+            Ok(_) => panic!("expected the XLIFF file to fail to load"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn should_write_placeholders_with_their_original_text() {
+        let xliff = write(HTML);
+        assert!(xliff.contains(
+            r#"<source>translatable element <pc dispEnd="&lt;/b&gt;" dispStart="&lt;b&gt;" equivEnd="CLOSE_BOLD_TEXT" equivStart="START_BOLD_TEXT" id="0" type="fmt">with placeholders</pc> <ph disp="{{ interpolation}}" equiv="INTERPOLATION" id="1"/></source>"#
+        ), "{}", xliff);
+        assert!(xliff.contains(
+            r#"<source>Line<ph disp="&lt;br/&gt;" equiv="LINE_BREAK" id="0" type="fmt"/>break</source>"#
+        ), "{}", xliff);
+        assert!(xliff.contains(
+            r#"<source>{VAR_PLURAL, plural, =0 {<pc dispEnd="&lt;/p&gt;" dispStart="&lt;p&gt;" equivEnd="CLOSE_PARAGRAPH" equivStart="START_PARAGRAPH" id="0" type="other">test</pc>} }</source>"#
+        ), "{}", xliff);
+    }
+
+    #[test]
+    fn should_load_xliff_files() {
+        let (locale, messages) = load_as_map(LOAD_XLIFF);
+        assert_eq!(locale.as_deref(), Some("fr"));
+        let expected: BTreeMap<String, String> = [
+            ("attr", "etubirtta elbatalsnart"),
+            ("element", r#"<ph name="INTERPOLATION"/> <ph name="START_BOLD_TEXT"/>sredlohecalp htiw<ph name="CLOSE_BOLD_TEXT"/> tnemele elbatalsnart"#),
+            ("icu", r#"{VAR_PLURAL, plural, =0 {[<ph name="START_PARAGRAPH"/>, TEST, <ph name="CLOSE_PARAGRAPH"/>]}}"#),
+            ("br", r#"Ligne<ph name="LINE_BREAK"/>coupée"#),
+        ]
+        .into_iter()
+        .map(|(id, message)| (id.to_string(), message.to_string()))
+        .collect();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn should_load_the_messages_it_writes() {
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        bundle.update_from_template(HTML, "file.ts");
+        let serializer = Xliff2::new();
+        let mut ids: Vec<_> = bundle
+            .get_messages()
+            .iter()
+            .map(|m| serializer.digest(m))
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        // Translate each message to its source
+        let xliff = bundle
+            .write(&serializer, None)
+            .split("</source>")
+            .map(|part| match part.rfind("<source>") {
+                Some(i) => format!("{}</source><target>{}</target>", part, &part[i + 8..]),
+                None => part.to_string(),
+            })
+            .collect::<String>();
+
+        let (_, messages) = load_as_map(&xliff);
+        assert_eq!(messages.keys().cloned().collect::<Vec<_>>(), ids);
+        assert_eq!(messages["br"], r#"Line<ph name="LINE_BREAK"/>break"#);
+    }
+
+    #[test]
+    fn should_report_incompatible_versions() {
+        let error = load_error(
+            r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+</xliff>
+"#,
+        );
+        assert!(error.starts_with("xliff2 parse errors:\n"), "{}", error);
+        assert!(
+            error
+                .contains("The XLIFF file version 1.2 is not compatible with XLIFF 2.0 serializer"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@0:0"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_missing_and_duplicated_translations() {
+        let error = load_error(
+            r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en" trgLang="fr">
+  <file original="ng.template" id="ngi18n">
+    <unit id="missing">
+      <segment>
+        <source>foo</source>
+      </segment>
+    </unit>
+    <unit id="twice">
+      <segment>
+        <source/>
+        <target/>
+      </segment>
+    </unit>
+    <unit id="twice">
+      <segment>
+        <source/>
+        <target/>
+      </segment>
+    </unit>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(
+            error.contains("Message missing misses a translation"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@2:4"), "{}", error);
+        assert!(
+            error.contains("Duplicated translations for msg twice"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@13:4"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_placeholders_without_equiv_attributes() {
+        let error = load_error(
+            r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en" trgLang="fr">
+  <file original="ng.template" id="ngi18n">
+    <unit id="deadbeef">
+      <segment>
+        <source/>
+        <target><ph id="0"/><pc id="1" equivStart="START_BOLD_TEXT">text</pc></target>
+      </segment>
+    </unit>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(
+            error.contains(r#"<ph> misses the "equiv" attribute"#),
+            "{}",
+            error
+        );
+        assert!(
+            error.contains(r#"<pc> misses the "equivEnd" attribute"#),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@5:16"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_unexpected_tags() {
+        let error = load_error(
+            r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en" trgLang="fr">
+  <file original="ng.template" id="ngi18n">
+    <unit id="deadbeef">
+      <segment>
+        <source/>
+        <target><b>msg</b></target>
+      </segment>
+    </unit>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(error.contains("Unexpected tag"), "{}", error);
+        assert!(error.contains("url@5:16"), "{}", error);
+    }
+}
@@ -0,0 +1,277 @@
+//! XLIFF 1.2 Serializer Tests
+//!
+//! Mirrors angular/packages/compiler/test/i18n/serializers/xliff_spec.ts
+
+#[cfg(test)]
+mod tests {
+    use angular_compiler::i18n::digest::serialize_nodes;
+    use angular_compiler::i18n::message_bundle::MessageBundle;
+    use angular_compiler::i18n::serializers::{Serializer, Xliff};
+    use angular_compiler::ml_parser::html_parser::HtmlParser;
+    use std::collections::{BTreeMap, HashMap};
+
+    const HTML: &str = r#"
+<p i18n-title title="translatable attribute">not translatable</p>
+<p i18n>translatable element <b>with placeholders</b> {{ interpolation}}</p>
+<p i18n="m|d@@i">foo</p>
+<p i18n>{ count, plural, =0 {<p>test</p>}}</p>
+<p i18n>Test: { count, plural, =0 { { sex, select, other {<p>deeply nested</p>}} } =other {a lot}}</p>
+<p i18n="@@br">Line<br/>break</p>
+"#;
+
+    const LOAD_XLIFF: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="983775b9a51ce14b036be72d4cfd65d68d64e231" datatype="html">
+        <source>translatable attribute</source>
+        <target>etubirtta elbatalsnart</target>
+      </trans-unit>
+      <trans-unit id="ec1d033f2436133c14ab038286c4f5df4697484a" datatype="html">
+        <source>translatable element <x id="START_BOLD_TEXT" ctype="x-b" equiv-text="&lt;b&gt;"/>with placeholders<x id="CLOSE_BOLD_TEXT" ctype="x-b" equiv-text="&lt;/b&gt;"/> <x id="INTERPOLATION" equiv-text="{{ interpolation}}"/></source>
+        <target><x id="INTERPOLATION"/> <x id="START_BOLD_TEXT"/>sredlohecalp htiw<x id="CLOSE_BOLD_TEXT"/> tnemele elbatalsnart</target>
+      </trans-unit>
+      <trans-unit id="i" datatype="html">
+        <source>foo</source>
+        <target>oof</target>
+      </trans-unit>
+      <trans-unit id="e2ccf3d131b15f54aa1fcf1314b1ca77c14bfcc2" datatype="html">
+        <source>{VAR_PLURAL, plural, =0 {<x id="START_PARAGRAPH" ctype="x-p" equiv-text="&lt;p&gt;"/>test<x id="CLOSE_PARAGRAPH" ctype="x-p" equiv-text="&lt;/p&gt;"/>} }</source>
+        <target>{VAR_PLURAL, plural, =0 {<x id="START_PARAGRAPH"/>TEST<x id="CLOSE_PARAGRAPH"/>} }</target>
+      </trans-unit>
+      <trans-unit id="br" datatype="html">
+        <source>Line<x id="LINE_BREAK" ctype="lb" equiv-text="&lt;br/&gt;"/>break</source>
+        <target>Ligne<x id="LINE_BREAK"/><mrk mtype="seg">coupée</mrk></target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#;
+
+    fn write(html: &str) -> String {
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        let errors = bundle.update_from_template(html, "file.ts");
+        assert!(errors.is_empty());
+        bundle.write(&Xliff::new(), None)
+    }
+
+    fn load_as_map(xliff: &str) -> (Option<String>, BTreeMap<String, String>) {
+        let result = Xliff::new().load(xliff, "url").unwrap();
+        let messages = result
+            .i18n_nodes_by_msg_id
+            .iter()
+            .map(|(id, nodes)| (id.clone(), serialize_nodes(nodes).join("")))
+            .collect();
+        (result.locale, messages)
+    }
+
+    fn load_error(xliff: &str) -> String {
+        match Xliff::new().load(xliff, "url") {
+            Ok(_) => panic!("expected the XLIFF file to fail to load"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn should_write_placeholders_with_their_original_text() {
+        let xliff = write(HTML);
+        assert!(xliff.contains(
+            r#"<source>translatable element <x ctype="x-b" equiv-text="&lt;b&gt;" id="START_BOLD_TEXT"/>with placeholders<x ctype="x-b" equiv-text="&lt;/b&gt;" id="CLOSE_BOLD_TEXT"/> <x equiv-text="{{ interpolation}}" id="INTERPOLATION"/></source>"#
+        ), "{}", xliff);
+        assert!(xliff.contains(
+            r#"<source>Line<x ctype="lb" equiv-text="&lt;br/&gt;" id="LINE_BREAK"/>break</source>"#
+        ), "{}", xliff);
+        assert!(xliff.contains(
+            r#"<source>{VAR_PLURAL, plural, =0 {<x ctype="x-p" equiv-text="&lt;p&gt;" id="START_PARAGRAPH"/>test<x ctype="x-p" equiv-text="&lt;/p&gt;" id="CLOSE_PARAGRAPH"/>} }</source>"#
+        ), "{}", xliff);
+    }
+
+    #[test]
+    fn should_load_xliff_files() {
+        let (locale, messages) = load_as_map(LOAD_XLIFF);
+        assert_eq!(locale.as_deref(), Some("fr"));
+        let expected: BTreeMap<String, String> = [
+            ("983775b9a51ce14b036be72d4cfd65d68d64e231", "etubirtta elbatalsnart"),
+            ("ec1d033f2436133c14ab038286c4f5df4697484a", r#"<ph name="INTERPOLATION"/> <ph name="START_BOLD_TEXT"/>sredlohecalp htiw<ph name="CLOSE_BOLD_TEXT"/> tnemele elbatalsnart"#),
+            ("i", "oof"),
+            ("e2ccf3d131b15f54aa1fcf1314b1ca77c14bfcc2", r#"{VAR_PLURAL, plural, =0 {[<ph name="START_PARAGRAPH"/>, TEST, <ph name="CLOSE_PARAGRAPH"/>]}}"#),
+            ("br", r#"Ligne<ph name="LINE_BREAK"/>coupée"#),
+        ]
+        .into_iter()
+        .map(|(id, message)| (id.to_string(), message.to_string()))
+        .collect();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn should_load_nested_icu_messages() {
+        let xliff = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="nested" datatype="html">
+        <source>{VAR_PLURAL, plural, =0 {{VAR_SELECT, select, other {deeply nested} }} =other {a lot} }</source>
+        <target>{VAR_PLURAL, plural, =0 {{VAR_SELECT, select, other {<x id="START_PARAGRAPH"/>profondément imbriqué<x id="CLOSE_PARAGRAPH"/>} }} =other {beaucoup} }</target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#;
+        let (_, messages) = load_as_map(xliff);
+        assert_eq!(
+            messages["nested"],
+            r#"{VAR_PLURAL, plural, =0 {[{VAR_SELECT, select, other {[<ph name="START_PARAGRAPH"/>, profondément imbriqué, <ph name="CLOSE_PARAGRAPH"/>]}}]}, =other {[beaucoup]}}"#
+        );
+    }
+
+    #[test]
+    fn should_load_the_messages_it_writes() {
+        let mut bundle = MessageBundle::new(HtmlParser::new(), vec![], HashMap::new(), None, true);
+        bundle.update_from_template(HTML, "file.ts");
+        let serializer = Xliff::new();
+        let mut ids: Vec<_> = bundle
+            .get_messages()
+            .iter()
+            .map(|m| serializer.digest(m))
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        // Translate each message to its source
+        let xliff = bundle
+            .write(&serializer, None)
+            .split("</source>")
+            .map(|part| match part.rfind("<source>") {
+                Some(i) => format!("{}</source><target>{}</target>", part, &part[i + 8..]),
+                None => part.to_string(),
+            })
+            .collect::<String>();
+
+        let (_, messages) = load_as_map(&xliff);
+        assert_eq!(messages.keys().cloned().collect::<Vec<_>>(), ids);
+        assert_eq!(messages["br"], r#"Line<ph name="LINE_BREAK"/>break"#);
+    }
+
+    #[test]
+    fn should_report_missing_translations() {
+        let error = load_error(
+            r#"<?xml version="1.0" encoding="UTF-8" ?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="missing" datatype="html">
+        <source>foo</source>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(error.starts_with("xliff parse errors:\n"), "{}", error);
+        assert!(
+            error.contains("Message missing misses a translation"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@4:6"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_duplicated_translations_and_missing_ids() {
+        let error = load_error(
+            r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="deadbeef" datatype="html">
+        <source/>
+        <target/>
+      </trans-unit>
+      <trans-unit id="deadbeef" datatype="html">
+        <source/>
+        <target/>
+      </trans-unit>
+      <trans-unit datatype="html">
+        <source/>
+        <target/>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(
+            error.contains("Duplicated translations for msg deadbeef"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@7:6"), "{}", error);
+        assert!(
+            error.contains(r#"<trans-unit> misses the "id" attribute"#),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@11:6"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_unexpected_tags_with_their_line_in_the_file() {
+        let error = load_error(
+            r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="deadbeef" datatype="html">
+        <source>foo</source>
+        <target>
+          <b>msg</b>
+        </target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(error.contains("Unexpected tag"), "{}", error);
+        assert!(error.contains("url@6:10"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_placeholders_without_an_id() {
+        let error = load_error(
+            r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="deadbeef" datatype="html">
+        <source/>
+        <target><x/></target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(
+            error.contains(r#"<x> misses the "id" attribute"#),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn should_report_icu_syntax_errors() {
+        let error = load_error(
+            r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="fr" datatype="plaintext" original="ng2.template">
+    <body>
+      <trans-unit id="deadbeef" datatype="html">
+        <source/>
+        <target>{VAR_PLURAL, plural, =0 {text}</target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#,
+        );
+        assert!(error.starts_with("xliff parse errors:\n"), "{}", error);
+        assert!(error.contains("url@5:"), "{}", error);
+    }
+}
@@ -0,0 +1,174 @@
+//! XTB Serializer Tests
+//!
+//! Mirrors angular/packages/compiler/test/i18n/serializers/xtb_spec.ts
+
+#[cfg(test)]
+mod tests {
+    use angular_compiler::i18n::digest::serialize_nodes;
+    use angular_compiler::i18n::serializers::{Serializer, Xtb};
+    use std::collections::BTreeMap;
+
+    fn load_as_map(xtb: &str) -> (Option<String>, BTreeMap<String, String>) {
+        let result = Xtb::new().load(xtb, "url").unwrap();
+        let messages = result
+            .i18n_nodes_by_msg_id
+            .iter()
+            .map(|(id, nodes)| (id.clone(), serialize_nodes(nodes).join("")))
+            .collect();
+        (result.locale, messages)
+    }
+
+    fn load_error(xtb: &str) -> String {
+        match Xtb::new().load(xtb, "url") {
+            Ok(_) => panic!("expected the XTB file to fail to load"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn should_load_xtb_files_with_a_doctype() {
+        let xtb = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE translationbundle [<!ELEMENT translationbundle (translation)*>
+<!ATTLIST translationbundle lang CDATA #REQUIRED>
+
+<!ELEMENT translation (#PCDATA|ph)*>
+<!ATTLIST translation id CDATA #REQUIRED>
+
+<!ELEMENT ph EMPTY>
+<!ATTLIST ph name CDATA #REQUIRED>
+]>
+<translationbundle>
+  <translation id="8841459487341224498">rab</translation>
+</translationbundle>"#;
+        let (_, messages) = load_as_map(xtb);
+        assert_eq!(messages["8841459487341224498"], "rab");
+    }
+
+    #[test]
+    fn should_load_xtb_files_without_placeholders() {
+        let xtb = r#"<?xml version="1.0" encoding="UTF-8"?>
+<translationbundle>
+  <translation id="8841459487341224498">rab</translation>
+</translationbundle>"#;
+        let (_, messages) = load_as_map(xtb);
+        assert_eq!(messages["8841459487341224498"], "rab");
+    }
+
+    #[test]
+    fn should_return_the_target_locale() {
+        let xtb = r#"<?xml version="1.0" encoding="UTF-8"?>
+<translationbundle lang='fr'>
+  <translation id="8841459487341224498">rab</translation>
+</translationbundle>"#;
+        let (locale, _) = load_as_map(xtb);
+        assert_eq!(locale.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn should_load_xtb_files_with_placeholders() {
+        let xtb = r#"<?xml version="1.0" encoding="UTF-8"?>
+<translationbundle>
+  <translation id="8877975308926375834"><ph name="START_PARAGRAPH"/>rab<ph name="CLOSE_PARAGRAPH"/></translation>
+</translationbundle>"#;
+        let (_, messages) = load_as_map(xtb);
+        assert_eq!(
+            messages["8877975308926375834"],
+            r#"<ph name="START_PARAGRAPH"/>rab<ph name="CLOSE_PARAGRAPH"/>"#
+        );
+    }
+
+    #[test]
+    fn should_load_nested_icu_messages() {
+        let xtb = r#"<?xml version="1.0" encoding="UTF-8"?>
+<translationbundle>
+  <translation id="7717087045075616176">*<ph name="ICU"/>*</translation>
+  <translation id="5115002811911870583">{VAR_PLURAL, plural, =1 {<ph name="START_PARAGRAPH"/>rab<ph name="CLOSE_PARAGRAPH"/>} =other {{VAR_SELECT, select, m {masc} f {fem}}}}</translation>
+</translationbundle>"#;
+        let (_, messages) = load_as_map(xtb);
+        assert_eq!(messages["7717087045075616176"], r#"*<ph name="ICU"/>*"#);
+        assert_eq!(
+            messages["5115002811911870583"],
+            r#"{VAR_PLURAL, plural, =1 {[<ph name="START_PARAGRAPH"/>, rab, <ph name="CLOSE_PARAGRAPH"/>]}, =other {[{VAR_SELECT, select, m {[masc]}, f {[fem]}}]}}"#
+        );
+    }
+
+    #[test]
+    fn should_report_nested_translation_bundles() {
+        let error = load_error(
+            r#"<translationbundle>
+  <translationbundle></translationbundle>
+</translationbundle>"#,
+        );
+        assert!(error.starts_with("xtb parse errors:\n"), "{}", error);
+        assert!(
+            error.contains("<translationbundle> elements can not be nested"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@1:2"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_translations_without_an_id() {
+        let error = load_error(
+            r#"<translationbundle>
+  <translation></translation>
+</translationbundle>"#,
+        );
+        assert!(
+            error.contains(r#"<translation> misses the "id" attribute"#),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@1:2"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_duplicated_translations() {
+        let error = load_error(
+            r#"<translationbundle>
+  <translation id="1186013544048295927">rab</translation>
+  <translation id="1186013544048295927">rab</translation>
+</translationbundle>"#,
+        );
+        assert!(
+            error.contains("Duplicated translations for msg 1186013544048295927"),
+            "{}",
+            error
+        );
+        assert!(error.contains("url@2:2"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_unexpected_tags() {
+        let error = load_error(
+            r#"<translationbundle>
+  <translation id="1186013544048295927"><b>msg</b></translation>
+  <unexpected></unexpected>
+</translationbundle>"#,
+        );
+        assert!(error.contains("Unexpected tag"), "{}", error);
+        assert!(error.contains("url@1:40"), "{}", error);
+        assert!(error.contains("url@2:2"), "{}", error);
+    }
+
+    #[test]
+    fn should_report_placeholders_without_a_name() {
+        let error = load_error(
+            r#"<translationbundle>
+  <translation id="1186013544048295927"><ph/></translation>
+</translationbundle>"#,
+        );
+        assert!(
+            error.contains(r#"<ph> misses the "name" attribute"#),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported")]
+    fn should_not_write_xtb_files() {
+        Xtb::new().write(&[], None);
+    }
+}
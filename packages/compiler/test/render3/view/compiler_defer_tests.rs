@@ -0,0 +1,197 @@
+use angular_compiler::constant_pool::ConstantPool;
+use angular_compiler::core::ViewEncapsulation;
+use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
+use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+use angular_compiler::output::output_ast as o;
+use angular_compiler::output::output_ast::ExpressionTrait;
+use angular_compiler::parse_util::{ParseLocation, ParseSourceFile, ParseSourceSpan};
+use angular_compiler::render3::util::R3Reference;
+use angular_compiler::render3::view::api::{
+    DeclarationListEmitMode, R3ComponentDeferMetadata, R3ComponentMetadata, R3ComponentTemplate,
+    R3DeferPerComponentDependency, R3DeferResolverFunctionMetadata, R3DirectiveMetadata,
+    R3HostMetadata, R3LifecycleMetadata,
+};
+use angular_compiler::render3::view::compiler::{
+    compile_component_from_metadata, compile_defer_resolver_function,
+};
+use angular_compiler::render3::view::template::{
+    make_binding_parser, parse_template, ParseTemplateOptions,
+};
+use indexmap::IndexMap;
+use std::sync::Arc;
+
+/// Compiles the template into a component definition and returns the emitted JS.
+fn compile_template(template: &str, defer: R3ComponentDeferMetadata) -> String {
+    let parsed = parse_template(
+        template,
+        "test.html",
+        ParseTemplateOptions {
+            preserve_whitespaces: Some(false),
+            ..Default::default()
+        },
+    );
+    assert!(
+        parsed
+            .errors
+            .as_ref()
+            .map_or(true, |errors| errors.is_empty()),
+        "Unexpected parse errors for template: {}",
+        template
+    );
+
+    let source_file = Arc::new(ParseSourceFile::new("".to_string(), "test.ts".to_string()));
+    let start = ParseLocation::new(Arc::clone(&source_file), 0, 0, 0);
+    let end = ParseLocation::new(source_file, 0, 0, 0);
+    let type_span = ParseSourceSpan::new(start, end);
+
+    let directive_meta = R3DirectiveMetadata {
+        name: "TestComponent".to_string(),
+        type_: R3Reference {
+            value: *o::variable("TestComponent"),
+            type_expr: *o::variable("TestComponent"),
+        },
+        type_argument_count: 0,
+        type_source_span: type_span,
+        deps: None,
+        selector: Some("test-comp".to_string()),
+        queries: vec![],
+        view_queries: vec![],
+        host: R3HostMetadata::default(),
+        lifecycle: R3LifecycleMetadata::default(),
+        inputs: IndexMap::new(),
+        outputs: IndexMap::new(),
+        uses_inheritance: false,
+        export_as: None,
+        providers: None,
+        is_standalone: true,
+        is_signal: false,
+        host_directives: None,
+    };
+
+    let component_meta = R3ComponentMetadata {
+        directive: directive_meta,
+        template: R3ComponentTemplate {
+            nodes: parsed.nodes,
+            ng_content_selectors: vec![],
+            preserve_whitespaces: false,
+        },
+        declarations: vec![],
+        defer,
+        declaration_list_emit_mode: DeclarationListEmitMode::Direct,
+        styles: vec![],
+        external_styles: None,
+        encapsulation: ViewEncapsulation::Emulated,
+        animations: None,
+        view_providers: None,
+        relative_context_file_path: "test.ts".to_string(),
+        i18n_use_external_ids: false,
+        change_detection: None,
+        relative_template_path: None,
+        enable_template_source_locations: false,
+        has_directive_dependencies: false,
+        raw_imports: None,
+    };
+
+    let mut constant_pool = ConstantPool::new(false);
+    let mut binding_parser = make_binding_parser(false);
+    let compiled =
+        compile_component_from_metadata(&component_meta, &mut constant_pool, &mut binding_parser);
+
+    let mut emitter = AbstractJsEmitterVisitor::new();
+    let mut ctx = EmitterVisitorContext::create_root();
+    emitter.visit_all_statements(&compiled.statements, &mut ctx);
+    compiled.expression.visit_expression(&mut emitter, &mut ctx);
+    ctx.to_source()
+}
+
+fn assert_contains(output: &str, expected: &str) {
+    assert!(
+        output.contains(expected),
+        "Expected output to contain `{}`. Output:\n{}",
+        expected,
+        output
+    );
+}
+
+fn no_dependencies() -> R3ComponentDeferMetadata {
+    R3ComponentDeferMetadata::PerComponent {
+        dependencies_fn: None,
+    }
+}
+
+#[test]
+fn should_allocate_slots_for_defer_sub_blocks() {
+    let output = compile_template(
+        "@defer (on timer(100ms)) {<b>main</b>} @placeholder (minimum 500ms) {<i>p</i>} \
+         @loading (after 100ms; minimum 1s) {load} @error {err}",
+        no_dependencies(),
+    );
+
+    assert_contains(
+        &output,
+        "i0.ɵɵdomTemplate(0, TestComponent_Defer_0_Template, 2, 0)\
+         (1, TestComponent_DeferLoading_1_Template, 1, 0)\
+         (2, TestComponent_DeferPlaceholder_2_Template, 2, 0)\
+         (3, TestComponent_DeferError_3_Template, 1, 0);",
+    );
+    assert_contains(
+        &output,
+        "i0.ɵɵdefer(4, 0, null, 1, 2, 3, 0, 1, i0.ɵɵdeferEnableTimerScheduling);",
+    );
+    assert_contains(&output, "i0.ɵɵdeferOnTimer(100);");
+    assert_contains(&output, "consts: [[1000, 100], [500]]");
+}
+
+#[test]
+fn should_resolve_trigger_targets() {
+    let output = compile_template(
+        "<button #trigger></button>@defer (on hover(trigger); prefetch on interaction) {main} \
+         @placeholder {<span>p</span>}",
+        no_dependencies(),
+    );
+
+    assert_contains(&output, "i0.ɵɵdefer(4, 2, null, null, 3);");
+    assert_contains(&output, "i0.ɵɵdeferOnHover(0);");
+    assert_contains(&output, "i0.ɵɵdeferPrefetchOnInteraction(0, -1);");
+}
+
+#[test]
+fn should_resolve_trigger_targets_in_parent_views() {
+    let output = compile_template(
+        "<div #trigger></div>@if (show) {@defer (on viewport(trigger)) {main}}",
+        no_dependencies(),
+    );
+
+    assert_contains(&output, "i0.ɵɵdeferOnViewport(0, 1);");
+}
+
+#[test]
+fn should_emit_when_conditions() {
+    let output = compile_template("@defer (when ready) {main}", no_dependencies());
+
+    assert_contains(&output, "i0.ɵɵadvance();\n    i0.ɵɵdeferWhen(ctx.ready);");
+}
+
+#[test]
+fn should_share_the_component_dependency_function() {
+    let output = compile_template(
+        "@defer {<child-cmp></child-cmp>}",
+        R3ComponentDeferMetadata::PerComponent {
+            dependencies_fn: Some(compile_defer_resolver_function(
+                &R3DeferResolverFunctionMetadata::PerComponent {
+                    dependencies: vec![R3DeferPerComponentDependency {
+                        symbol_name: "ChildCmp".to_string(),
+                        import_path: "./child".to_string(),
+                        is_default_import: false,
+                    }],
+                },
+            )),
+        },
+    );
+
+    assert_contains(
+        &output,
+        "const TestComponent_DeferFn = () => [import('./child').then((m) => m.ChildCmp)];",
+    );
+    assert_contains(&output, "i0.ɵɵdefer(1, 0, TestComponent_DeferFn);");
+}
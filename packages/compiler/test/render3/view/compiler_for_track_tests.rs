@@ -77,6 +77,7 @@ fn compile_template(template: &str) -> (Vec<o::Statement>, ConstantPool) {
         i18n_use_external_ids: false,
         change_detection: None,
         relative_template_path: None,
+        enable_template_source_locations: false,
         has_directive_dependencies: false,
         raw_imports: None,
     };
@@ -219,6 +220,7 @@ fn should_handle_ngfor_nested_svg_attributes() {
         i18n_use_external_ids: false,
         change_detection: None,
         relative_template_path: None,
+        enable_template_source_locations: false,
         has_directive_dependencies: false,
         raw_imports: None,
     };
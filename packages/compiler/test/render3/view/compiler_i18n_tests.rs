@@ -0,0 +1,183 @@
+use angular_compiler::constant_pool::ConstantPool;
+use angular_compiler::core::ViewEncapsulation;
+use angular_compiler::output::abstract_emitter::EmitterVisitorContext;
+use angular_compiler::output::abstract_js_emitter::AbstractJsEmitterVisitor;
+use angular_compiler::output::output_ast as o;
+use angular_compiler::output::output_ast::ExpressionTrait;
+use angular_compiler::parse_util::{ParseLocation, ParseSourceFile, ParseSourceSpan};
+use angular_compiler::render3::util::R3Reference;
+use angular_compiler::render3::view::api::{
+    DeclarationListEmitMode, R3ComponentDeferMetadata, R3ComponentMetadata, R3ComponentTemplate,
+    R3DirectiveMetadata, R3HostMetadata, R3LifecycleMetadata,
+};
+use angular_compiler::render3::view::compiler::compile_component_from_metadata;
+use angular_compiler::render3::view::template::{
+    make_binding_parser, parse_template, ParseTemplateOptions,
+};
+use indexmap::IndexMap;
+use std::sync::Arc;
+
+/// Compiles the template into a component definition and returns the emitted JS.
+fn compile_template(template: &str) -> String {
+    let parsed = parse_template(
+        template,
+        "test.html",
+        ParseTemplateOptions {
+            preserve_whitespaces: Some(false),
+            ..Default::default()
+        },
+    );
+    assert!(
+        parsed
+            .errors
+            .as_ref()
+            .map_or(true, |errors| errors.is_empty()),
+        "Unexpected parse errors for template: {}",
+        template
+    );
+
+    let source_file = Arc::new(ParseSourceFile::new("".to_string(), "test.ts".to_string()));
+    let start = ParseLocation::new(Arc::clone(&source_file), 0, 0, 0);
+    let end = ParseLocation::new(source_file, 0, 0, 0);
+    let type_span = ParseSourceSpan::new(start, end);
+
+    let directive_meta = R3DirectiveMetadata {
+        name: "TestComponent".to_string(),
+        type_: R3Reference {
+            value: *o::variable("TestComponent"),
+            type_expr: *o::variable("TestComponent"),
+        },
+        type_argument_count: 0,
+        type_source_span: type_span,
+        deps: None,
+        selector: Some("test-comp".to_string()),
+        queries: vec![],
+        view_queries: vec![],
+        host: R3HostMetadata::default(),
+        lifecycle: R3LifecycleMetadata::default(),
+        inputs: IndexMap::new(),
+        outputs: IndexMap::new(),
+        uses_inheritance: false,
+        export_as: None,
+        providers: None,
+        is_standalone: true,
+        is_signal: false,
+        host_directives: None,
+    };
+
+    let component_meta = R3ComponentMetadata {
+        directive: directive_meta,
+        template: R3ComponentTemplate {
+            nodes: parsed.nodes,
+            ng_content_selectors: vec![],
+            preserve_whitespaces: false,
+        },
+        declarations: vec![],
+        defer: R3ComponentDeferMetadata::PerComponent {
+            dependencies_fn: None,
+        },
+        declaration_list_emit_mode: DeclarationListEmitMode::Direct,
+        styles: vec![],
+        external_styles: None,
+        encapsulation: ViewEncapsulation::Emulated,
+        animations: None,
+        view_providers: None,
+        relative_context_file_path: "test.ts".to_string(),
+        i18n_use_external_ids: false,
+        change_detection: None,
+        relative_template_path: None,
+        enable_template_source_locations: false,
+        has_directive_dependencies: false,
+        raw_imports: None,
+    };
+
+    let mut constant_pool = ConstantPool::new(false);
+    let mut binding_parser = make_binding_parser(false);
+    let compiled =
+        compile_component_from_metadata(&component_meta, &mut constant_pool, &mut binding_parser);
+
+    let mut emitter = AbstractJsEmitterVisitor::new();
+    let mut ctx = EmitterVisitorContext::create_root();
+    emitter.visit_all_statements(&compiled.statements, &mut ctx);
+    compiled.expression.visit_expression(&mut emitter, &mut ctx);
+    ctx.to_source()
+}
+
+fn assert_contains(output: &str, expected: &str) {
+    assert!(
+        output.contains(expected),
+        "Expected output to contain `{}`. Output:\n{}",
+        expected,
+        output
+    );
+}
+
+#[test]
+fn should_generate_i18n_instructions_for_text_with_interpolation() {
+    let output = compile_template("<div i18n>Hello {{name}}</div>");
+
+    assert_contains(&output, "i0.ɵɵi18n(1, 0);");
+    assert_contains(&output, "i0.ɵɵi18nExp(ctx.name);");
+    assert_contains(&output, "i0.ɵɵi18nApply(1);");
+    assert_contains(&output, "var i18n_0;");
+    assert_contains(
+        &output,
+        "goog.getMsg(\"Hello {$interpolation}\", {'interpolation': \"\u{FFFD}0\u{FFFD}\"}",
+    );
+    assert_contains(
+        &output,
+        "i18n_0 = $localize `Hello ${\"\u{FFFD}0\u{FFFD}\"}:INTERPOLATION:`;",
+    );
+}
+
+#[test]
+fn should_generate_element_placeholders() {
+    let output = compile_template("<div i18n>a<span>x</span></div>");
+
+    assert_contains(&output, "i0.ɵɵi18nStart(1, 0);");
+    assert_contains(&output, "i0.ɵɵi18nEnd();");
+    assert_contains(
+        &output,
+        "$localize `a${\"\u{FFFD}#2\u{FFFD}\"}:START_TAG_SPAN:x${\"\u{FFFD}/#2\u{FFFD}\"}:CLOSE_TAG_SPAN:`",
+    );
+}
+
+#[test]
+fn should_postprocess_icu_messages() {
+    let output = compile_template("<div i18n>{count, plural, =0 {none} other {many}}</div>");
+
+    assert_contains(&output, "i0.ɵɵi18nExp(ctx.count);");
+    assert_contains(
+        &output,
+        "$localize `{VAR_PLURAL, plural, =0 {none} other {many}}`",
+    );
+    assert_contains(
+        &output,
+        "i18n_0 = i0.ɵɵi18nPostprocess(i18n_0, {'VAR_PLURAL': \"\u{FFFD}0\u{FFFD}\"});",
+    );
+}
+
+#[test]
+fn should_handle_i18n_blocks_inside_templates() {
+    let output = compile_template("<ng-template i18n>{{a}}</ng-template>");
+
+    assert_contains(&output, "i0.ɵɵi18n(0, 0);");
+    assert_contains(&output, "i0.ɵɵi18nExp(ctx_r0.a);");
+    assert_contains(&output, "i0.ɵɵi18nApply(0);");
+}
+
+#[test]
+fn should_combine_element_and_template_slots_for_structural_directives() {
+    let output = compile_template("<div i18n><span *ngIf=\"b\">{{b}}</span></div>");
+
+    assert_contains(&output, "i0.ɵɵi18nStart(0, 0, 1);");
+    assert_contains(
+        &output,
+        "'startTagSpan': \"\u{FFFD}*2:1\u{FFFD}\u{FFFD}#1:1\u{FFFD}\"",
+    );
+    assert_contains(
+        &output,
+        "'closeTagSpan': \"\u{FFFD}/#1:1\u{FFFD}\u{FFFD}/*2:1\u{FFFD}\"",
+    );
+    assert_contains(&output, "'interpolation': \"\u{FFFD}0:1\u{FFFD}\"");
+}
@@ -12,6 +12,7 @@ use angular_compiler::ml_parser::lexer::TokenizeOptions;
 use angular_compiler::render3::r3_template_transform::{
     html_ast_to_render3_ast, Render3ParseOptions, Render3ParseResult,
 };
+use angular_compiler::render3::view::i18n::meta::I18nMetaVisitor;
 use angular_compiler::render3::view::template::LEADING_TRIVIA_CHARS;
 use angular_compiler::render3::view::template::{
     make_binding_parser, make_binding_parser_with_parser,
@@ -65,8 +66,19 @@ pub fn parse_r3(input: &str, options: ParseR3Options) -> Render3ParseResult {
         panic!("Parse errors: {}", msg);
     }
 
-    // Process i18n metadata (simplified - would need I18nMetaVisitor in real implementation)
-    let mut html_nodes = parse_result.root_nodes;
+    let html_nodes = parse_result.root_nodes;
+    let i18n_meta_result =
+        I18nMetaVisitor::new(false, false, true, false).visit_all_with_errors(html_nodes);
+    if !i18n_meta_result.errors.is_empty() && options.ignore_error != Some(true) {
+        let msg = i18n_meta_result
+            .errors
+            .iter()
+            .map(|e| format!("{:?}", e))
+            .collect::<Vec<_>>()
+            .join("\n");
+        panic!("Parse errors: {}", msg);
+    }
+    let mut html_nodes = i18n_meta_result.root_nodes;
 
     // Handle whitespace preservation
     if options.preserve_whitespaces != Some(true) {
@@ -12,8 +12,15 @@ export interface Diagnostic {
 }
 export interface CompileResult {
   code: string;
+  /** Source map (JSON) for `code`, mapping back to the original `.ts` file. */
+  map?: string;
   diagnostics: Array<Diagnostic>;
 }
+export interface LinkResult {
+  code: string;
+  /** Source map (JSON) for `code`, composed with the source map of the input file. */
+  map?: string;
+}
 export interface FileEntry {
   filename: string;
   content: string;
@@ -26,6 +33,6 @@ export interface BatchEntryResult {
 export declare class Compiler {
   constructor();
   compile(filename: string, content: string): CompileResult;
-  linkFile(filename: string, sourceCode: string): string;
+  linkFile(filename: string, sourceCode: string): LinkResult;
   compileBatch(files: Array<FileEntry>): Array<BatchEntryResult>;
 }
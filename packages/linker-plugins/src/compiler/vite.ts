@@ -169,12 +169,12 @@ export function angularCompilerVitePlugin(options?: CompilerOptions): Plugin {
           const cleanId = id.split('?')[0];
           if (cleanId.endsWith('.mjs') || cleanId.endsWith('.js')) {
             try {
-              const result = compiler.linkFile(id, code);
-              if (result.startsWith('/* Linker Error')) {
-                 if (debug) console.error(`[Linker Error] ${id}: ${result}`);
+              const { code: linkedCode, map } = compiler.linkFile(id, code);
+              if (linkedCode.startsWith('/* Linker Error')) {
+                 if (debug) console.error(`[Linker Error] ${id}: ${linkedCode}`);
                  return null;
               }
-              return { code: result, map: null };
+              return { code: linkedCode, map: map ?? null };
             } catch (e) {
                if (debug) console.error(`Linker failed for ${id}:`, e);
               return null;
@@ -198,7 +198,7 @@ export function angularCompilerVitePlugin(options?: CompilerOptions): Plugin {
         const result = compiler.compile(id, code);
 
         // Handle structured result
-        const { code: compiledCode, diagnostics } = result;
+        const { code: compiledCode, map, diagnostics } = result;
 
         if (compiledCode.startsWith('/* Error')) {
           console.error(`[Angular Compiler Error] ${id}:\n${compiledCode}`);
@@ -215,7 +215,7 @@ export function angularCompilerVitePlugin(options?: CompilerOptions): Plugin {
           console.log(`[Angular Compiler] Successfully compiled: ${id}`);
         }
 
-        return { code: compiledCode, map: null };
+        return { code: compiledCode, map: map ?? null };
       } catch (e) {
         console.error(`[Angular Compiler Failed] ${id}:`, e);
         throw e;
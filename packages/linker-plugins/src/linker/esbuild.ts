@@ -116,11 +116,11 @@ export function angularLinkerEsbuildPlugin(options?: LinkerOptions): Plugin {
         }
 
         try {
-          const result = compiler.linkFile(args.path, code);
+          const { code: linkedCode, map } = compiler.linkFile(args.path, code);
 
-          if (result.startsWith("/* Linker Error")) {
+          if (linkedCode.startsWith("/* Linker Error")) {
             if (debug) {
-              console.error(`[Angular Linker Error] ${args.path}:\n${result}`);
+              console.error(`[Angular Linker Error] ${args.path}:\n${linkedCode}`);
             }
             return { contents: code, loader: "js" };
           }
@@ -129,7 +129,11 @@ export function angularLinkerEsbuildPlugin(options?: LinkerOptions): Plugin {
             console.log(`[Angular Linker] Successfully linked: ${args.path}`);
           }
 
-          return { contents: result, loader: "js" };
+          // esbuild only picks up source maps of loaded files when they are inlined.
+          const contents = map
+            ? `${linkedCode}\n//# sourceMappingURL=data:application/json;base64,${Buffer.from(map).toString("base64")}`
+            : linkedCode;
+          return { contents, loader: "js" };
         } catch (e) {
           if (debug) {
             console.error(`[Angular Linker Failed] ${args.path}:`, e);
@@ -129,10 +129,10 @@ export function angularLinkerRolldownPlugin(
       }
 
       try {
-        const result = compiler.linkFile(cleanId, code);
+        const { code: linkedCode, map } = compiler.linkFile(cleanId, code);
 
-        if (result.startsWith("/* Linker Error")) {
-          console.error(`[Rolldown Linker Error] ${id}:\n${result}`);
+        if (linkedCode.startsWith("/* Linker Error")) {
+          console.error(`[Rolldown Linker Error] ${id}:\n${linkedCode}`);
           return null;
         }
 
@@ -140,7 +140,7 @@ export function angularLinkerRolldownPlugin(
           console.log(`[Angular Linker] Successfully linked: ${cleanId}`);
         }
 
-        return { code: result, map: null };
+        return { code: linkedCode, map: map ?? null };
       } catch (e) {
         console.error(`[Rolldown Linker Failed] ${id}:`, e);
         return null;
@@ -18,11 +18,11 @@ export interface LinkerOptions {
 
 export interface LinkerResult {
   code: string;
-  map: null | undefined;
+  map: string | null | undefined;
 }
 
 export interface CompilerBinding {
-  linkFile(filePath: string, code: string): string;
+  linkFile(filePath: string, code: string): { code: string; map?: string };
   compile?(filePath: string, code: string): string;
 }
 
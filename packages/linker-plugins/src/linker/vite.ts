@@ -163,10 +163,10 @@ export function angularLinkerVitePlugin(
       }
 
       try {
-        const result = compiler.linkFile(cleanId, code);
+        const { code: linkedCode, map } = compiler.linkFile(cleanId, code);
 
-        if (result.startsWith("/* Linker Error")) {
-          console.error(`[Angular Linker Error] ${id}:\n${result}`);
+        if (linkedCode.startsWith("/* Linker Error")) {
+          console.error(`[Angular Linker Error] ${id}:\n${linkedCode}`);
           return null;
         }
 
@@ -174,7 +174,7 @@ export function angularLinkerVitePlugin(
           console.log(`[Angular Linker] Successfully linked: ${cleanId}`);
         }
 
-        return { code: result, map: null };
+        return { code: linkedCode, map: map ?? null };
       } catch (e) {
         console.error(`[Angular Linker Failed] ${id}:`, e);
         return null;
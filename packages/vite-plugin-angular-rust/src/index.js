@@ -373,13 +373,13 @@ export default function angularRustPlugin(options = {}) {
                          console.log('[Vite Debug] Linking p-button:', id);
                     }
                     try {
-                        let result = compiler.linkFile(id, code);
-                        if (result.startsWith('/* Linker Error')) {
-                            console.error(`[Linker] Linker Error for ${id}:`, result);
+                        const { code: linkedCode, map } = compiler.linkFile(id, code);
+                        if (linkedCode.startsWith('/* Linker Error')) {
+                            console.error(`[Linker] Linker Error for ${id}:`, linkedCode);
                             return null;
                         }
-                        if (result !== code) {
-                            return `/* LINKED BY RUST LINKER */\n${result}`;
+                        if (linkedCode !== code) {
+                            return { code: linkedCode, map: map ?? null };
                         }
                     } catch (e) {
                          console.error(`[Linker] Exception for ${id}:`, e);